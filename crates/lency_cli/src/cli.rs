@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "lencyc")]
@@ -18,6 +18,36 @@ pub struct Cli {
     /// 安静模式 (只输出错误)
     #[arg(short, long, global = true)]
     pub quiet: bool,
+
+    /// 诊断输出格式
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Human)]
+    pub error_format: ErrorFormat,
+}
+
+/// 诊断输出格式
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// 带源码片段的文本
+    #[default]
+    Human,
+    /// 每条诊断一行 JSON 对象，供编辑器与 CI 解析
+    Json,
+}
+
+/// lint 级别，可重复；`warnings` 代表全部 lint。依次应用 allow、warn、deny，后者优先
+#[derive(Args, Debug, Clone, Default)]
+pub struct LintArgs {
+    /// 关闭 lint (如 `-A unused-variable`)
+    #[arg(short = 'A', long = "allow", value_name = "LINT")]
+    pub allow: Vec<String>,
+
+    /// 以警告报告 lint (默认级别)
+    #[arg(short = 'W', long = "warn", value_name = "LINT")]
+    pub warn: Vec<String>,
+
+    /// 以错误报告 lint，编译失败 (如 `-D warnings`)
+    #[arg(short = 'D', long = "deny", value_name = "LINT")]
+    pub deny: Vec<String>,
 }
 
 #[derive(Subcommand)]
@@ -34,12 +64,22 @@ pub enum Commands {
         /// 输出目录 (可选)。设置后，输出文件会写入该目录
         #[arg(long, value_name = "DIR")]
         out_dir: Option<String>,
+
+        /// 生成 DWARF 调试信息 (用于 gdb/lldb 源码级调试)
+        #[arg(short = 'g', long = "debug")]
+        debug: bool,
+
+        #[command(flatten)]
+        lints: LintArgs,
     },
 
     /// 编译并运行 Lency 程序
     Run {
-        /// 输入文件
-        input: String,
+        /// 输入文件 (省略时运行当前目录 lency.toml 声明的包)
+        input: Option<String>,
+
+        #[command(flatten)]
+        lints: LintArgs,
 
         /// 传递给程序的参数
         #[arg(last = true)]
@@ -48,18 +88,27 @@ pub enum Commands {
 
     /// 检查语法和语义错误
     Check {
-        /// 输入文件
-        input: String,
+        /// 输入文件 (省略时检查当前目录 lency.toml 声明的包)
+        input: Option<String>,
+
+        #[command(flatten)]
+        lints: LintArgs,
+    },
+
+    /// 自动应用编译器给出的修复建议（如拼写相近的名称），直接改写源文件
+    Fix {
+        /// 输入文件 (省略时修复当前目录 lency.toml 声明的包入口)
+        input: Option<String>,
     },
 
     /// 编译并生成可执行文件
     Build {
-        /// 输入文件
-        input: String,
+        /// 输入文件 (省略时构建当前目录 lency.toml 声明的包)
+        input: Option<String>,
 
-        /// 输出文件 (默认: lencyTemp.out)
-        #[arg(short, long, default_value = "lencyTemp.out")]
-        output: String,
+        /// 输出文件 (默认: lencyTemp.out；构建包时为 target/<包名>)
+        #[arg(short, long)]
+        output: Option<String>,
 
         /// 输出目录 (可选)。设置后，输出文件会写入该目录
         #[arg(long, value_name = "DIR")]
@@ -72,6 +121,32 @@ pub enum Commands {
         /// 仅做语法/语义检查，不产出可执行文件
         #[arg(long)]
         check_only: bool,
+
+        /// 禁用增量编译缓存 (target/lency-cache)
+        #[arg(long)]
+        no_cache: bool,
+
+        /// 生成 DWARF 调试信息 (用于 gdb/lldb 源码级调试)
+        #[arg(short = 'g', long = "debug")]
+        debug: bool,
+
+        #[command(flatten)]
+        lints: LintArgs,
+    },
+
+    /// 发现并运行测试函数 (`test_` 前缀)
+    Test {
+        /// 测试文件或目录 (目录下查找 *_test.lcy 与 test_*.lcy)
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// 只运行名称 (或 文件::名称) 包含该字符串的测试
+        #[arg(short, long)]
+        filter: Option<String>,
+
+        /// 额外输出 JUnit XML 报告到该文件
+        #[arg(long, value_name = "FILE")]
+        junit: Option<String>,
     },
 
     /// 交互式 REPL (实验性)
@@ -1,49 +1,81 @@
 use anyhow::{bail, Result};
+use lency_driver::session::DEFAULT_CACHE_DIR;
+use lency_driver::{
+    BuildCache, CompileOptions, Emitter, LintConfig, Pipeline, PipelineOutput, Session,
+};
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use crate::path_utils::resolve_output_path;
 
 use super::{
     check::cmd_check,
-    common::{compile_to_llvm_ir, find_runtime_library, require_tool, temp_artifact_path},
+    common::{
+        compile_to_llvm_ir, find_runtime_library, report_compile_error, require_tool,
+        temp_artifact_path,
+    },
+    package::Target,
 };
 
-/// 构建命令 - 生成可执行文件
+/// 单文件构建的默认输出
+const DEFAULT_OUTPUT: &str = "lencyTemp.out";
+
+/// 构建命令 - 生成可执行文件；未给出文件时构建当前目录的包
+#[allow(clippy::too_many_arguments)]
 pub fn cmd_build(
-    input: &str,
-    output: &str,
+    input: Option<&str>,
+    output: Option<&str>,
     out_dir: Option<&str>,
     release: bool,
     check_only: bool,
+    no_cache: bool,
+    debug: bool,
+    lints: &LintConfig,
+    emitter: &Emitter,
 ) -> Result<()> {
+    let target = Target::resolve(input)?;
+    let input = target.input.as_str();
     if check_only {
         println!("Building {} (check-only=true) ...", input);
-        return cmd_check(input);
+        return cmd_check(Some(input), lints, emitter);
     }
 
     println!("Building {} (release={}) ...", input, release);
 
-    let ir = compile_to_llvm_ir(input)?;
-    let temp_ll = temp_artifact_path("ll")?;
-    fs::write(&temp_ll, ir)?;
-
-    println!("  Generating object file...");
-    let temp_obj = temp_artifact_path("o")?;
-    let llc = require_tool(&["llc-15", "llc"], "LLVM static compiler (llc)")?;
-    let mut llc_cmd = std::process::Command::new(llc);
-    llc_cmd.args(["-filetype=obj"]);
-    if release {
-        llc_cmd.arg("-O2");
-    }
-    let llc_status = llc_cmd
-        .args([
-            temp_ll.to_string_lossy().as_ref(),
-            "-o",
-            temp_obj.to_string_lossy().as_ref(),
-        ])
-        .status()?;
-    if !llc_status.success() {
-        bail!("llc compilation failed");
+    // 增量构建时每个模块一个目标文件；未变化的模块直接使用缓存中的目标文件，跳过 llc
+    let mut objects = Vec::new();
+    let mut temp_objects = Vec::new();
+    if no_cache || input.ends_with(".lir") {
+        let obj = generate_object(
+            &compile_to_llvm_ir(input, &target.search_paths, debug, release, lints, emitter)?,
+            release,
+        )?;
+        objects.push(obj.clone());
+        temp_objects.push(obj);
+    } else {
+        let (cache, result) = compile_with_cache(&target, debug, release, lints, emitter)?;
+        let reused = result.units.len() - result.rebuilt_units().count();
+        if reused > 0 {
+            println!("  Using {} cached object file(s)", reused);
+        }
+        for unit in &result.units {
+            let Some(ir) = &unit.ir else {
+                objects.push(cache.object_path(unit.key));
+                continue;
+            };
+            let obj = generate_object(ir, release)?;
+            // 写缓存失败时仍使用临时目标文件链接
+            match cache.store_object(unit.key, &obj) {
+                Ok(path) => {
+                    let _ = fs::remove_file(&obj);
+                    objects.push(path);
+                }
+                Err(_) => {
+                    objects.push(obj.clone());
+                    temp_objects.push(obj);
+                }
+            }
+        }
     }
 
     let runtime_lib = find_runtime_library();
@@ -52,16 +84,18 @@ pub fn cmd_build(
     }
 
     println!("  Linking executable...");
-    let output_path = resolve_output_path(output, out_dir)?;
+    // 包构建默认输出到 target/<包名>
+    let default_output = match &target.package {
+        Some(name) => format!("target/{}", name),
+        None => DEFAULT_OUTPUT.to_string(),
+    };
+    let output_path = resolve_output_path(output.unwrap_or(&default_output), out_dir)?;
     let output_str = output_path.to_string_lossy().into_owned();
 
     let linker = require_tool(&["gcc", "clang"], "linker (gcc/clang)")?;
     let mut gcc_cmd = std::process::Command::new(linker);
-    gcc_cmd.args([
-        temp_obj.to_string_lossy().as_ref(),
-        "-o",
-        output_str.as_str(),
-    ]);
+    gcc_cmd.args(&objects);
+    gcc_cmd.args(["-o", output_str.as_str()]);
     if !cfg!(windows) {
         gcc_cmd.arg("-no-pie");
     }
@@ -77,14 +111,83 @@ pub fn cmd_build(
         }
     }
 
+    for lib in &target.links {
+        gcc_cmd.arg(format!("-l{}", lib));
+    }
+
     let gcc_status = gcc_cmd.status()?;
     if !gcc_status.success() {
         bail!("Linking failed - please ensure lency_runtime is built");
     }
 
-    let _ = fs::remove_file(&temp_ll);
-    let _ = fs::remove_file(&temp_obj);
+    for obj in &temp_objects {
+        let _ = fs::remove_file(obj);
+    }
 
     println!("Successfully built: {}", output_path.display());
     Ok(())
 }
+
+fn compile_with_cache(
+    target: &Target,
+    debug: bool,
+    release: bool,
+    lints: &LintConfig,
+    emitter: &Emitter,
+) -> Result<(BuildCache, PipelineOutput)> {
+    let root = std::env::current_dir()?;
+    let cache = BuildCache::new(root.join(DEFAULT_CACHE_DIR));
+    let session =
+        Session::with_cache(&root, cache.dir()).with_search_paths(target.search_paths.clone());
+    let options = CompileOptions {
+        debug_info: debug,
+        lints: lints.clone(),
+        release,
+        ..CompileOptions::default()
+    };
+    let source = fs::read_to_string(&target.input)?;
+    let result = Pipeline::new(&session)
+        .with_options(options)
+        .compile_file(Path::new(&target.input))
+        .map_err(|e| report_compile_error(&e, &target.input, &source, emitter))?;
+    // 命中缓存时同样重放上次的警告
+    result.emit_warnings(emitter, &source);
+    if result.cached {
+        println!("  Up to date (cached)");
+    } else {
+        println!(
+            "  {} module(s) changed, regenerating {} of {} module(s)",
+            result.changed_modules.len(),
+            result.rebuilt_units().count(),
+            result.units.len()
+        );
+    }
+    Ok((cache, result))
+}
+
+/// 调用 llc 把 IR 编译为目标文件
+fn generate_object(ir: &str, release: bool) -> Result<PathBuf> {
+    let temp_ll = temp_artifact_path("ll")?;
+    fs::write(&temp_ll, ir)?;
+
+    println!("  Generating object file...");
+    let temp_obj = temp_artifact_path("o")?;
+    let llc = require_tool(&["llc-15", "llc"], "LLVM static compiler (llc)")?;
+    let mut llc_cmd = std::process::Command::new(llc);
+    llc_cmd.args(["-filetype=obj"]);
+    if release {
+        llc_cmd.arg("-O2");
+    }
+    let llc_status = llc_cmd
+        .args([
+            temp_ll.to_string_lossy().as_ref(),
+            "-o",
+            temp_obj.to_string_lossy().as_ref(),
+        ])
+        .status()?;
+    let _ = fs::remove_file(&temp_ll);
+    if !llc_status.success() {
+        bail!("llc compilation failed");
+    }
+    Ok(temp_obj)
+}
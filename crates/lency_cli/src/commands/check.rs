@@ -1,26 +1,50 @@
 use crate::lir_backend;
 use anyhow::Result;
+use lency_driver::{compile_with_options, CompileOptions, Emitter, ErrorFormat, LintConfig};
 use std::fs;
+use std::path::PathBuf;
 
-/// 检查命令
-pub fn cmd_check(input: &str) -> Result<()> {
-    println!("Checking {} ...", input);
+use super::package::Target;
+
+/// 检查命令；未给出文件时检查当前目录的包
+///
+/// JSON 输出格式下标准输出只包含诊断
+pub fn cmd_check(input: Option<&str>, lints: &LintConfig, emitter: &Emitter) -> Result<()> {
+    let target = Target::resolve(input)?;
+    let input = target.input.as_str();
+    let human = emitter.format() == ErrorFormat::Human;
+    if human {
+        println!("Checking {} ...", input);
+    }
 
     if input.ends_with(".lir") {
         let source = fs::read_to_string(input)?;
         lir_backend::compile_lir_to_llvm_ir(&source)?;
-        println!("No errors found");
+        if human {
+            println!("No errors found");
+        }
         return Ok(());
     }
 
     let source = fs::read_to_string(input)?;
-    match lency_driver::compile(&source) {
-        Ok(_) => {
-            println!("No errors found");
+    let options = CompileOptions {
+        source_path: Some(PathBuf::from(input)),
+        search_paths: target.search_paths,
+        lints: lints.clone(),
+        ..CompileOptions::default()
+    };
+    match compile_with_options(&source, &options) {
+        Ok(output) => {
+            output.emit_warnings(emitter, &source);
+            match output.warnings.len() {
+                _ if !human => {}
+                0 => println!("No errors found"),
+                n => println!("No errors found ({} warning(s))", n),
+            }
             Ok(())
         }
         Err(e) => {
-            e.emit(Some(input), Some(&source));
+            e.emit_with(emitter, Some(input), Some(&source));
             std::process::exit(1);
         }
     }
@@ -1,15 +1,92 @@
+use super::shared_lib::find_shared_library;
+use crate::cli::ErrorFormat;
 use crate::lir_backend;
-use anyhow::{bail, Context, Result};
-use lency_driver::compile_file;
+use anyhow::{anyhow, bail, Context, Result};
+use lency_driver::{compile_with_options, CompileError, CompileOptions, Emitter, LintConfig};
+use std::process::Command;
 use std::time::{SystemTime, UNIX_EPOCH};
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-pub fn compile_to_llvm_ir(input: &str) -> Result<String> {
+impl ErrorFormat {
+    /// 按输出格式创建诊断输出器
+    pub fn emitter(self) -> Emitter {
+        Emitter::with_format(match self {
+            ErrorFormat::Human => lency_driver::ErrorFormat::Human,
+            ErrorFormat::Json => lency_driver::ErrorFormat::Json,
+        })
+    }
+}
+
+/// 用 `emitter` 输出编译错误的诊断，返回简短的错误信息
+pub fn report_compile_error(
+    err: &CompileError,
+    input: &str,
+    source: &str,
+    emitter: &Emitter,
+) -> anyhow::Error {
+    err.emit_with(emitter, Some(input), Some(source));
+    anyhow!("could not compile {}", input)
+}
+
+/// 编译为 LLVM IR 并输出 lint 警告；`debug` 为 true 时附带 DWARF 调试信息（LIR 输入不支持）
+pub fn compile_to_llvm_ir(
+    input: &str,
+    search_paths: &[PathBuf],
+    debug: bool,
+    release: bool,
+    lints: &LintConfig,
+    emitter: &Emitter,
+) -> Result<String> {
+    let source = fs::read_to_string(input)?;
     if input.ends_with(".lir") {
-        let source = fs::read_to_string(input)?;
         return lir_backend::compile_lir_to_llvm_ir(&source);
     }
-    Ok(compile_file(input)?.ir)
+    let options = CompileOptions {
+        debug_info: debug,
+        source_path: Some(PathBuf::from(input)),
+        search_paths: search_paths.to_vec(),
+        lints: lints.clone(),
+        release,
+        ..CompileOptions::default()
+    };
+    let output = compile_with_options(&source, &options)
+        .map_err(|e| report_compile_error(&e, input, &source, emitter))?;
+    output.emit_warnings(emitter, &source);
+    Ok(output.ir)
+}
+
+/// 用 lli 解释执行 IR 文件的命令，并加载 lency_runtime 与 `links` 中的 C 库
+pub fn lli_command(ir_path: &Path, links: &[String]) -> Result<Command> {
+    let lli = require_tool(&["lli-15", "lli"], "LLVM IR interpreter (lli)")?;
+    let mut cmd = Command::new(lli);
+    if let Some(runtime_dir) = find_runtime_dir() {
+        let libs = ["liblency_runtime.so", "liblency_runtime.dylib"];
+        if let Some(lib_path) = libs
+            .iter()
+            .map(|lib| runtime_dir.join(lib))
+            .find(|path| path.exists())
+        {
+            cmd.arg(format!("-load={}", lib_path.display()));
+        }
+    } else {
+        eprintln!("Warning: lency_runtime library not found. I/O operations may fail.");
+    }
+    for lib in links {
+        match find_shared_library(lib) {
+            Some(path) => {
+                cmd.arg(format!("-load={}", path.display()));
+            }
+            None => eprintln!(
+                "Warning: shared library for '{}' not found; its symbols may be unresolved.",
+                lib
+            ),
+        }
+    }
+    cmd.arg(ir_path);
+    Ok(cmd)
 }
 
 pub fn find_runtime_dir() -> Option<PathBuf> {
@@ -1,14 +1,24 @@
 use crate::path_utils::resolve_output_path;
 use anyhow::Result;
+use lency_driver::{Emitter, LintConfig};
 use std::fs;
 
 use super::common::compile_to_llvm_ir;
+use super::package::Target;
 
 /// 编译命令
-pub fn cmd_compile(input: &str, output: &str, out_dir: Option<&str>) -> Result<()> {
+pub fn cmd_compile(
+    input: &str,
+    output: &str,
+    out_dir: Option<&str>,
+    debug: bool,
+    lints: &LintConfig,
+    emitter: &Emitter,
+) -> Result<()> {
     println!("Compiling {} ...", input);
 
-    let result_ir = compile_to_llvm_ir(input)?;
+    let target = Target::resolve(Some(input))?;
+    let result_ir = compile_to_llvm_ir(input, &target.search_paths, debug, false, lints, emitter)?;
     let output_path = resolve_output_path(output, out_dir)?;
     fs::write(&output_path, result_ir)?;
     println!("Generated {}", output_path.display());
@@ -0,0 +1,47 @@
+use anyhow::{bail, Result};
+use lency_driver::{fix_source, CompileOptions, Emitter};
+use std::fs;
+use std::path::PathBuf;
+
+use super::package::Target;
+
+/// 修复命令：应用可自动修复的建议并写回源文件；未给出文件时修复当前目录的包
+pub fn cmd_fix(input: Option<&str>, emitter: &Emitter) -> Result<()> {
+    let target = Target::resolve(input)?;
+    let input = target.input.as_str();
+    if input.ends_with(".lir") {
+        bail!("lencyc fix does not support LIR input: {}", input);
+    }
+
+    let source = fs::read_to_string(input)?;
+    let options = CompileOptions {
+        source_path: Some(PathBuf::from(input)),
+        search_paths: target.search_paths,
+        ..CompileOptions::default()
+    };
+    let outcome = match fix_source(&source, &options) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            e.emit_with(emitter, Some(input), Some(&source));
+            std::process::exit(1);
+        }
+    };
+
+    if outcome.applied.is_empty() {
+        println!("No fixes to apply in {}", input);
+        return Ok(());
+    }
+    for fix in &outcome.applied {
+        let line = source[..fix.span.start].matches('\n').count() + 1;
+        println!(
+            "{}:{}: `{}` -> `{}`",
+            input,
+            line,
+            &source[fix.span.clone()],
+            fix.replacement
+        );
+    }
+    fs::write(input, &outcome.source)?;
+    println!("Applied {} fix(es) to {}", outcome.applied.len(), input);
+    Ok(())
+}
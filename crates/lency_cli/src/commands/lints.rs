@@ -0,0 +1,68 @@
+//! lint 级别参数 (`-A` / `-W` / `-D`)
+
+use crate::cli::LintArgs;
+use anyhow::{bail, Result};
+use lency_driver::{Lint, LintConfig, LintLevel, ALL_LINTS};
+
+impl LintArgs {
+    /// 转换为 lint 配置：依次应用 allow、warn、deny，同一 lint 以最后应用的为准
+    pub fn config(&self) -> Result<LintConfig> {
+        let mut config = LintConfig::new();
+        let groups = [
+            (&self.allow, LintLevel::Allow),
+            (&self.warn, LintLevel::Warn),
+            (&self.deny, LintLevel::Deny),
+        ];
+        for (names, level) in groups {
+            for name in names {
+                if !config.set_by_name(name, level) {
+                    let known: Vec<_> = Lint::ALL.iter().map(|lint| lint.name()).collect();
+                    bail!(
+                        "unknown lint '{}' (known lints: {}, or '{}' for all)",
+                        name,
+                        known.join(", "),
+                        ALL_LINTS
+                    );
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(allow: &[&str], warn: &[&str], deny: &[&str]) -> LintArgs {
+        let owned = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        LintArgs {
+            allow: owned(allow),
+            warn: owned(warn),
+            deny: owned(deny),
+        }
+    }
+
+    #[test]
+    fn test_deny_overrides_allow() -> Result<()> {
+        let config = args(&[ALL_LINTS], &[], &["unused-import"]).config()?;
+        assert_eq!(config.level(Lint::UnusedImport), LintLevel::Deny);
+        assert_eq!(config.level(Lint::Shadowing), LintLevel::Allow);
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_is_warn() -> Result<()> {
+        let config = LintArgs::default().config()?;
+        assert!(Lint::ALL
+            .iter()
+            .all(|&lint| config.level(lint) == LintLevel::Warn));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_lint_is_rejected() {
+        let result = args(&["unused-thing"], &[], &[]).config();
+        assert!(matches!(result, Err(e) if e.to_string().contains("unknown lint 'unused-thing'")));
+    }
+}
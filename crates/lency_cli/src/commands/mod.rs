@@ -2,11 +2,18 @@ mod build;
 mod check;
 mod common;
 mod compile;
+mod fix;
+mod lints;
+mod package;
 mod repl;
 mod run;
+mod shared_lib;
+mod test;
 
 pub use build::cmd_build;
 pub use check::cmd_check;
 pub use compile::cmd_compile;
+pub use fix::cmd_fix;
 pub use repl::cmd_repl;
 pub use run::cmd_run;
+pub use test::cmd_test;
@@ -0,0 +1,47 @@
+//! 构建目标：命令行给出的文件，或当前目录 `lency.toml` 声明的包入口
+//!
+//! 当前目录有清单时，即使显式给出文件，也使用清单中的搜索路径与链接库。
+
+use anyhow::{bail, Result};
+use lency_driver::{Manifest, MANIFEST_FILE};
+use std::path::{Path, PathBuf};
+
+/// 要编译的入口及其包配置
+pub struct Target {
+    pub input: String,
+    /// import 搜索路径（为空时为当前目录）
+    pub search_paths: Vec<PathBuf>,
+    /// 链接的 C 库
+    pub links: Vec<String>,
+    /// 包名（按清单构建时）
+    pub package: Option<String>,
+}
+
+impl Target {
+    pub fn resolve(input: Option<&str>) -> Result<Self> {
+        let manifest = Manifest::discover(Path::new(""))?;
+        let input = match (input, &manifest) {
+            (Some(input), _) => input.to_string(),
+            (None, Some(manifest)) => manifest.entry_path().to_string_lossy().into_owned(),
+            (None, None) => bail!(
+                "no input file given and no {} in the current directory",
+                MANIFEST_FILE
+            ),
+        };
+
+        Ok(match manifest {
+            Some(manifest) => Self {
+                input,
+                search_paths: manifest.search_paths(),
+                links: manifest.links.clone(),
+                package: Some(manifest.name),
+            },
+            None => Self {
+                input,
+                search_paths: Vec::new(),
+                links: Vec::new(),
+                package: None,
+            },
+        })
+    }
+}
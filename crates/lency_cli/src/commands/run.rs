@@ -1,33 +1,32 @@
 use anyhow::Result;
+use lency_driver::{compile_with_options, CompileOptions, Emitter, LintConfig};
 use std::fs;
+use std::path::PathBuf;
 
-use super::common::{find_runtime_dir, require_tool, temp_artifact_path};
+use super::common::{lli_command, report_compile_error, temp_artifact_path};
+use super::package::Target;
 
-/// 运行命令
-pub fn cmd_run(input: &str) -> Result<()> {
+/// 运行命令；未给出文件时运行当前目录的包
+pub fn cmd_run(input: Option<&str>, lints: &LintConfig, emitter: &Emitter) -> Result<()> {
+    let target = Target::resolve(input)?;
+    let input = target.input.as_str();
     println!("Running {} ...", input);
 
-    let result = lency_driver::compile_file(input)?;
+    let source = fs::read_to_string(input)?;
+    let options = CompileOptions {
+        source_path: Some(PathBuf::from(input)),
+        search_paths: target.search_paths.clone(),
+        lints: lints.clone(),
+        ..CompileOptions::default()
+    };
+    let result = compile_with_options(&source, &options)
+        .map_err(|e| report_compile_error(&e, input, &source, emitter))?;
+    result.emit_warnings(emitter, &source);
 
     let temp_ir = temp_artifact_path("ll")?;
     fs::write(&temp_ir, result.ir)?;
 
-    let lli = require_tool(&["lli-15", "lli"], "LLVM IR interpreter (lli)")?;
-    let mut cmd = std::process::Command::new(lli);
-    if let Some(runtime_dir) = find_runtime_dir() {
-        let libs = ["liblency_runtime.so", "liblency_runtime.dylib"];
-        for lib in libs {
-            let lib_path = runtime_dir.join(lib);
-            if lib_path.exists() {
-                cmd.arg(format!("-load={}", lib_path.display()));
-                break;
-            }
-        }
-    } else {
-        eprintln!("Warning: lency_runtime library not found. I/O operations may fail.");
-    }
-
-    let output = cmd.arg(&temp_ir).output()?;
+    let output = lli_command(&temp_ir, &target.links)?.output()?;
 
     print!("{}", String::from_utf8_lossy(&output.stdout));
     eprint!("{}", String::from_utf8_lossy(&output.stderr));
@@ -0,0 +1,100 @@
+//! 为 `lencyc run` / `lencyc test` 查找 `[build] links` 对应的动态库
+//!
+//! lli 不经过链接器，`-l<name>` 需要换成动态库的路径用 `-load=` 加载。
+//! glibc 的 `libm.so` 等是链接脚本而非真正的库，dlopen 无法加载，
+//! 因此只接受 ELF / Mach-O 文件，找不到时再尝试带版本号的 `lib<name>.so.N`。
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 查找 `-l<name>` 对应的动态库
+pub fn find_shared_library(name: &str) -> Option<PathBuf> {
+    find_in_dirs(name, &library_dirs())
+}
+
+/// 库搜索目录：`LIBRARY_PATH`、`LD_LIBRARY_PATH`、`DYLD_LIBRARY_PATH`，然后是系统库目录
+fn library_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = ["LIBRARY_PATH", "LD_LIBRARY_PATH", "DYLD_LIBRARY_PATH"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .flat_map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+        .collect();
+    let multiarch = format!("{}-linux-gnu", std::env::consts::ARCH);
+    for root in ["/usr/local/lib", "/usr/lib", "/lib", "/usr/lib64", "/lib64"] {
+        dirs.push(Path::new(root).join(&multiarch));
+        dirs.push(PathBuf::from(root));
+    }
+    dirs.push(PathBuf::from("/opt/homebrew/lib"));
+    dirs
+}
+
+fn find_in_dirs(name: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let exact = [format!("lib{}.so", name), format!("lib{}.dylib", name)];
+    let versioned = format!("lib{}.so.", name);
+    for dir in dirs {
+        if let Some(path) = exact
+            .iter()
+            .map(|file| dir.join(file))
+            .find(|path| is_shared_library(path))
+        {
+            return Some(path);
+        }
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut candidates: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&versioned))
+            .map(|entry| entry.path())
+            .filter(|path| is_shared_library(path))
+            .collect();
+        // 版本号最短的通常是 soname（libm.so.6 而不是 libm.so.6.0.1）
+        candidates.sort_by_key(|path| path.as_os_str().len());
+        if let Some(path) = candidates.into_iter().next() {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// 文件是否是可被 dlopen 加载的 ELF / Mach-O 文件
+fn is_shared_library(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    let read = fs::File::open(path).and_then(|mut file| file.read_exact(&mut magic));
+    if read.is_err() {
+        return false;
+    }
+    let word = u32::from_be_bytes(magic);
+    magic == *b"\x7fELF"
+        || matches!(
+            word,
+            0xfeedface | 0xfeedfacf | 0xcefaedfe | 0xcffaedfe | 0xcafebabe
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skips_linker_scripts() -> std::io::Result<()> {
+        let dir = std::env::temp_dir().join(format!("lency_shared_lib_{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            dir.join("libdemo.so"),
+            "/* GNU ld script */\nGROUP ( libdemo.so.2 )\n",
+        )?;
+        fs::write(dir.join("libdemo.so.2"), b"\x7fELF\x02\x01\x01")?;
+        fs::write(dir.join("libdemo.so.2.1.0"), b"\x7fELF\x02\x01\x01")?;
+
+        let found = find_in_dirs("demo", std::slice::from_ref(&dir));
+        let missing = find_in_dirs("absent", std::slice::from_ref(&dir));
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(found, Some(dir.join("libdemo.so.2")));
+        assert_eq!(missing, None);
+        Ok(())
+    }
+}
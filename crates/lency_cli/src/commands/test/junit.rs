@@ -0,0 +1,122 @@
+//! JUnit XML 报告
+//!
+//! 每个测试文件对应一个 `<testsuite>`，CI 系统（GitHub Actions、GitLab、Jenkins）均可直接读取。
+
+use super::SuiteResult;
+use std::fmt::Write;
+
+/// 生成 JUnit XML 报告
+pub(crate) fn render(suites: &[SuiteResult]) -> String {
+    let count = |suite: &SuiteResult| suite.cases.len();
+    let failures = |suite: &SuiteResult| {
+        suite
+            .cases
+            .iter()
+            .filter(|case| case.failure.is_some())
+            .count()
+    };
+    let seconds = |suite: &SuiteResult| {
+        suite
+            .cases
+            .iter()
+            .map(|case| case.duration.as_secs_f64())
+            .sum::<f64>()
+    };
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"lency\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">",
+        suites.iter().map(count).sum::<usize>(),
+        suites.iter().map(failures).sum::<usize>(),
+        suites.iter().map(seconds).sum::<f64>()
+    );
+    for suite in suites {
+        let file = escape(&suite.file);
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">",
+            file,
+            count(suite),
+            failures(suite),
+            seconds(suite)
+        );
+        for case in &suite.cases {
+            let _ = write!(
+                out,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.name),
+                file,
+                case.duration.as_secs_f64()
+            );
+            match &case.failure {
+                None => out.push_str("/>\n"),
+                Some(output) => {
+                    let message = output.lines().next().unwrap_or_default();
+                    let _ = writeln!(
+                        out,
+                        ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+                        escape(message),
+                        escape(output)
+                    );
+                }
+            }
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // XML 1.0 不允许大部分控制字符
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::CaseResult;
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_render_junit_report() {
+        let suites = vec![SuiteResult {
+            file: "tests/math_test.lcy".to_string(),
+            cases: vec![
+                CaseResult {
+                    name: "test_add".to_string(),
+                    duration: Duration::from_millis(12),
+                    failure: None,
+                },
+                CaseResult {
+                    name: "test_sub".to_string(),
+                    duration: Duration::from_millis(8),
+                    failure: Some("panic: 1 < 2\n  --> tests/math_test.lcy:7:9\n".to_string()),
+                },
+            ],
+        }];
+
+        let xml = render(&suites);
+        assert!(
+            xml.contains("<testsuites name=\"lency\" tests=\"2\" failures=\"1\" time=\"0.020\">")
+        );
+        assert!(xml.contains(
+            "<testcase name=\"test_add\" classname=\"tests/math_test.lcy\" time=\"0.012\"/>"
+        ));
+        assert!(xml.contains("<failure message=\"panic: 1 &lt; 2\">"));
+        assert!(xml.ends_with("  </testsuite>\n</testsuites>\n"));
+    }
+}
@@ -0,0 +1,277 @@
+//! `lencyc test`：发现并运行测试函数
+//!
+//! 测试文件为 `*_test.lcy` 或 `test_*.lcy`（直接指定的文件不受此限制），
+//! 测试函数的规则见 `lency_driver::testing`。每个文件的测试编译为一个程序，
+//! 每个测试在独立的 lli 进程中运行（由 `LENCY_TEST` 选择），以退出码判断结果，
+//! 因此一个测试 panic 不会中断其余测试。
+
+mod junit;
+
+use anyhow::{bail, Result};
+use lency_driver::{compile_tests, discover_tests, CompileOptions, TEST_SELECTOR_ENV};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::common::{lli_command, temp_artifact_path};
+use super::package::Target;
+
+/// 一个测试文件的运行结果
+pub(crate) struct SuiteResult {
+    pub file: String,
+    pub cases: Vec<CaseResult>,
+}
+
+/// 单个测试的运行结果
+pub(crate) struct CaseResult {
+    pub name: String,
+    pub duration: Duration,
+    /// 失败时的输出（编译错误或程序的 stderr/stdout）
+    pub failure: Option<String>,
+}
+
+/// 测试命令
+pub fn cmd_test(path: &str, filter: Option<&str>, junit_path: Option<&str>) -> Result<()> {
+    // 当前目录是包时沿用清单中的搜索路径与链接库
+    let Target {
+        search_paths,
+        links,
+        ..
+    } = Target::resolve(Some(path))?;
+    let started = Instant::now();
+    let mut suites = Vec::new();
+    let mut filtered_out = 0;
+
+    for file in collect_test_files(Path::new(path))? {
+        let display = file.display().to_string();
+        let source = fs::read_to_string(&file)?;
+        let cases = match discover_tests(&source) {
+            Ok(cases) => cases,
+            Err(err) => {
+                println!("error: failed to parse {}", display);
+                suites.push(SuiteResult {
+                    file: display,
+                    cases: vec![CaseResult {
+                        name: "<parse>".to_string(),
+                        duration: Duration::ZERO,
+                        failure: Some(err.to_string()),
+                    }],
+                });
+                continue;
+            }
+        };
+
+        let (selected, skipped): (Vec<_>, Vec<_>) = cases
+            .into_iter()
+            .partition(|case| matches_filter(&display, &case.name, filter));
+        filtered_out += skipped.len();
+        if selected.is_empty() {
+            continue;
+        }
+
+        println!("\nrunning {} test(s) in {}", selected.len(), display);
+        let options = CompileOptions {
+            source_path: Some(file.clone()),
+            search_paths: search_paths.clone(),
+            ..CompileOptions::default()
+        };
+        // 整个文件只编译一次，每个测试以不同的序号运行同一个程序
+        let names: Vec<String> = selected.iter().map(|case| case.name.clone()).collect();
+        let ir_path = temp_artifact_path("ll")?;
+        let compiled = compile_tests(&source, &names, &options)
+            .map_err(|err| format!("compilation failed: {}", err));
+        if let Ok(output) = &compiled {
+            fs::write(&ir_path, &output.ir)?;
+        }
+        let mut results = Vec::with_capacity(names.len());
+        for (index, name) in names.into_iter().enumerate() {
+            let result = match &compiled {
+                Ok(_) => run_test(&ir_path, index, name, &links),
+                Err(message) => Ok(CaseResult {
+                    name,
+                    duration: Duration::ZERO,
+                    failure: Some(message.clone()),
+                }),
+            };
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    let _ = fs::remove_file(&ir_path);
+                    return Err(err);
+                }
+            };
+            let status = if result.failure.is_some() {
+                "FAILED"
+            } else {
+                "ok"
+            };
+            println!(
+                "test {} ... {} ({}ms)",
+                result.name,
+                status,
+                result.duration.as_millis()
+            );
+            results.push(result);
+        }
+        let _ = fs::remove_file(&ir_path);
+        suites.push(SuiteResult {
+            file: display,
+            cases: results,
+        });
+    }
+
+    let (passed, failed) = report(&suites, filtered_out, started.elapsed());
+
+    if let Some(junit_path) = junit_path {
+        fs::write(junit_path, junit::render(&suites))?;
+        println!("JUnit report written to {}", junit_path);
+    }
+
+    if passed + failed == 0 && filtered_out == 0 {
+        println!("no tests found in {}", path);
+    }
+    if failed > 0 {
+        bail!("{} test(s) failed", failed);
+    }
+    Ok(())
+}
+
+/// 运行测试程序中的第 `index` 个测试
+fn run_test(ir_path: &Path, index: usize, name: String, links: &[String]) -> Result<CaseResult> {
+    let started = Instant::now();
+    let result = lli_command(ir_path, links)?
+        .env(TEST_SELECTOR_ENV, index.to_string())
+        .output()?;
+
+    let failure = if result.status.success() {
+        None
+    } else {
+        let mut captured = String::from_utf8_lossy(&result.stdout).into_owned();
+        captured.push_str(&String::from_utf8_lossy(&result.stderr));
+        if captured.trim().is_empty() {
+            captured = match result.status.code() {
+                Some(code) => format!("exited with code {}", code),
+                None => "terminated by signal".to_string(),
+            };
+        }
+        Some(captured)
+    };
+
+    Ok(CaseResult {
+        name,
+        duration: started.elapsed(),
+        failure,
+    })
+}
+
+/// 打印失败详情与汇总，返回 (通过数, 失败数)
+fn report(suites: &[SuiteResult], filtered_out: usize, elapsed: Duration) -> (usize, usize) {
+    let failures: Vec<(&str, &CaseResult)> = suites
+        .iter()
+        .flat_map(|suite| {
+            suite
+                .cases
+                .iter()
+                .map(move |case| (suite.file.as_str(), case))
+        })
+        .filter(|(_, case)| case.failure.is_some())
+        .collect();
+    let total: usize = suites.iter().map(|suite| suite.cases.len()).sum();
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (file, case) in &failures {
+            println!("\n---- {}::{} ----", file, case.name);
+            println!("{}", case.failure.as_deref().unwrap_or_default().trim_end());
+        }
+    }
+
+    let failed = failures.len();
+    let passed = total - failed;
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} filtered out; finished in {:.2}s",
+        if failed == 0 { "ok" } else { "FAILED" },
+        passed,
+        failed,
+        filtered_out,
+        elapsed.as_secs_f64()
+    );
+    (passed, failed)
+}
+
+/// 过滤条件匹配测试名或 `文件::测试名`
+fn matches_filter(file: &str, name: &str, filter: Option<&str>) -> bool {
+    match filter {
+        None => true,
+        Some(pattern) => format!("{}::{}", file, name).contains(pattern),
+    }
+}
+
+/// 收集测试文件：目录下递归查找（跳过隐藏目录与 target），结果排序
+fn collect_test_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if !path.is_dir() {
+        bail!("no such file or directory: {}", path.display());
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry_path = entry?.path();
+            let name = entry_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if entry_path.is_dir() {
+                if !name.starts_with('.') && name != "target" {
+                    pending.push(entry_path);
+                }
+            } else if is_test_file(&name) {
+                files.push(entry_path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn is_test_file(name: &str) -> bool {
+    name.strip_suffix(".lcy")
+        .is_some_and(|stem| stem.ends_with("_test") || stem.starts_with("test_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_test_file() {
+        assert!(is_test_file("math_test.lcy"));
+        assert!(is_test_file("test_math.lcy"));
+        assert!(!is_test_file("math.lcy"));
+        assert!(!is_test_file("math_test.rs"));
+    }
+
+    #[test]
+    fn test_filter_matches_qualified_name() {
+        assert!(matches_filter("tests/math_test.lcy", "test_add", None));
+        assert!(matches_filter(
+            "tests/math_test.lcy",
+            "test_add",
+            Some("add")
+        ));
+        assert!(matches_filter(
+            "tests/math_test.lcy",
+            "test_add",
+            Some("math_test")
+        ));
+        assert!(!matches_filter(
+            "tests/math_test.lcy",
+            "test_add",
+            Some("sub")
+        ));
+    }
+}
@@ -160,6 +160,10 @@ pub(super) fn resolve_builtin_call(
         "int_to_string" => Some(("lency_int_to_string", vec![ValueType::I64], ValueType::Ptr)),
         "file_exists" => Some(("lency_file_exists", vec![ValueType::Ptr], ValueType::I64)),
         "is_dir" => Some(("lency_file_is_dir", vec![ValueType::Ptr], ValueType::I64)),
+        "gc_collect" => Some(("lency_gc_collect", vec![], ValueType::I64)),
+        "gc_live_objects" => Some(("lency_gc_live_objects", vec![], ValueType::I64)),
+        "gc_live_bytes" => Some(("lency_gc_live_bytes", vec![], ValueType::I64)),
+        "gc_collections" => Some(("lency_gc_collections", vec![], ValueType::I64)),
         "lency_vec_new" => Some(("lency_vec_new", vec![ValueType::I64], ValueType::Ptr)),
         "lency_vec_push" => Some((
             "lency_vec_push",
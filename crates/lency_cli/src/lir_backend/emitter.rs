@@ -100,6 +100,11 @@ impl Emitter {
         out
     }
 
+    /// 字符串常量的全局变量类型，`array_len` 含结尾的 NUL
+    fn string_global_type(array_len: usize) -> String {
+        format!("{{ i64, i64, [{} x i8] }}", array_len)
+    }
+
     fn ensure_string_global(&mut self, literal: &str) -> Option<(String, usize)> {
         if let Some((name, len, _)) = self.string_globals.get(literal) {
             return Some((name.clone(), *len));
@@ -107,9 +112,13 @@ impl Emitter {
         let bytes = Self::parse_string_literal(literal)?;
         let global_name = format!("@.str.{}", self.string_globals.len());
         let global_len = bytes.len() + 1;
+        // 与运行时字符串相同的布局：{ len, cap, data + NUL }
         let decl = format!(
-            "{} = private unnamed_addr constant [{} x i8] c\"{}\"",
+            "{} = private unnamed_addr constant {} {{ i64 {}, i64 {}, [{} x i8] c\"{}\" }}",
             global_name,
+            Self::string_global_type(global_len),
+            bytes.len(),
+            bytes.len(),
             global_len,
             Self::llvm_c_string(&bytes)
         );
@@ -220,9 +229,10 @@ impl Emitter {
         }
         if let Some((global_name, global_len)) = self.ensure_string_global(op) {
             let gep = self.next_tmp("str");
+            let global_type = Self::string_global_type(global_len);
             self.push(format!(
-                "  {} = getelementptr inbounds [{} x i8], [{} x i8]* {}, i64 0, i64 0",
-                gep, global_len, global_len, global_name
+                "  {} = getelementptr inbounds {}, {}* {}, i64 0, i32 2, i64 0",
+                gep, global_type, global_type, global_name
             ));
             self.mark_temp(&gep, ValueType::Ptr);
             return Ok((gep, ValueType::Ptr));
@@ -340,6 +350,43 @@ impl Emitter {
                 self.push(format!("  {} = {} i1 {}, {}", dst, llvm_op, lhs_i1, rhs_i1));
                 self.mark_temp(dst, ValueType::I1);
             }
+            "bit_and" | "bit_or" | "bit_xor" => {
+                let llvm_op = match op {
+                    "bit_and" => "and",
+                    "bit_or" => "or",
+                    "bit_xor" => "xor",
+                    _ => unreachable!(),
+                };
+                // bool 操作数保持 i1，其余按 i64
+                if lhs_ty == ValueType::I1 && rhs_ty == ValueType::I1 {
+                    self.push(format!(
+                        "  {} = {} i1 {}, {}",
+                        dst, llvm_op, lhs_repr, rhs_repr
+                    ));
+                    self.mark_temp(dst, ValueType::I1);
+                } else {
+                    let (lhs_i64, _) = self.ensure_i64(lhs_repr, lhs_ty);
+                    let (rhs_i64, _) = self.ensure_i64(rhs_repr, rhs_ty);
+                    self.push(format!(
+                        "  {} = {} i64 {}, {}",
+                        dst, llvm_op, lhs_i64, rhs_i64
+                    ));
+                    self.mark_temp(dst, ValueType::I64);
+                }
+            }
+            "shl" | "shr" => {
+                let (lhs_i64, _) = self.ensure_i64(lhs_repr, lhs_ty);
+                let (rhs_i64, _) = self.ensure_i64(rhs_repr, rhs_ty);
+                // 移位量对 64 取模，与主编译器一致
+                let amount = self.next_tmp("shift_amount");
+                self.push(format!("  {} = and i64 {}, 63", amount, rhs_i64));
+                let llvm_op = if op == "shl" { "shl" } else { "ashr" };
+                self.push(format!(
+                    "  {} = {} i64 {}, {}",
+                    dst, llvm_op, lhs_i64, amount
+                ));
+                self.mark_temp(dst, ValueType::I64);
+            }
             _ => bail!("unsupported binary op: {}", op),
         }
         Ok(())
@@ -358,6 +405,11 @@ impl Emitter {
                 self.push(format!("  {} = xor i1 {}, true", dst, rhs_i1));
                 self.mark_temp(dst, ValueType::I1);
             }
+            "bit_not" => {
+                let (rhs_i64, _) = self.ensure_i64(rhs_repr, rhs_ty);
+                self.push(format!("  {} = xor i64 {}, -1", dst, rhs_i64));
+                self.mark_temp(dst, ValueType::I64);
+            }
             _ => bail!("unsupported unary op: {}", op),
         }
         Ok(())
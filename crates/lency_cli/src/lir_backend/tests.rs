@@ -27,6 +27,30 @@ else_1:
     assert!(ir.contains("ret i32"));
 }
 
+#[test]
+fn test_compile_lir_bitwise_ops() {
+    let src = r#"
+; lencyc-lir v0
+func main {
+entry:
+  var %x = 12
+  %t0 = bit_and %x, 10
+  %t1 = shr %t0, 1
+  %t2 = bit_not %t1
+  %t3 = cmp_lt %x, 0
+  %t4 = bit_xor %t3, %t3
+  ret %t2
+}
+"#;
+    let result = compile_lir_to_llvm_ir(src);
+    assert!(result.is_ok(), "lir compile failed: {:?}", result.err());
+    let ir = result.unwrap_or_default();
+    assert!(ir.contains("and i64"));
+    assert!(ir.contains("ashr i64"));
+    assert!(ir.contains("xor i64"));
+    assert!(ir.contains("xor i1"));
+}
+
 #[test]
 fn test_compile_lir_call_external_function() {
     let src = r#"
@@ -247,7 +271,11 @@ entry:
     let result = compile_lir_to_llvm_ir(src);
     assert!(result.is_ok(), "lir compile failed: {:?}", result.err());
     let ir = result.unwrap_or_default();
-    assert!(ir.contains("@.str.0 = private unnamed_addr constant [3 x i8] c\"42\\00\""));
+    assert!(ir.contains(
+        "@.str.0 = private unnamed_addr constant { i64, i64, [3 x i8] } { i64 2, i64 2, [3 x i8] c\"42\\00\" }"
+    ));
+    // 字符串指针指向长度头之后的数据
+    assert!(ir.contains("getelementptr inbounds { i64, i64, [3 x i8] }, { i64, i64, [3 x i8] }* @.str.0, i64 0, i32 2, i64 0"));
     assert!(ir.contains("declare i64 @lency_string_eq(i8*, i8*)"));
     assert!(ir.contains("call i64 @lency_string_eq(i8*"));
 }
@@ -7,7 +7,7 @@ mod lir_backend;
 mod path_utils;
 
 use cli::{Cli, Commands};
-use commands::{cmd_build, cmd_check, cmd_compile, cmd_repl, cmd_run};
+use commands::{cmd_build, cmd_check, cmd_compile, cmd_fix, cmd_repl, cmd_run, cmd_test};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -18,21 +18,56 @@ fn main() -> Result<()> {
         println!("Verbose mode enabled");
     }
 
+    let emitter = cli.error_format.emitter();
     match cli.command {
         Commands::Compile {
             input,
             output,
             out_dir,
-        } => cmd_compile(&input, &output, out_dir.as_deref())?,
-        Commands::Run { input, args: _ } => cmd_run(&input)?,
-        Commands::Check { input } => cmd_check(&input)?,
+            debug,
+            lints,
+        } => cmd_compile(
+            &input,
+            &output,
+            out_dir.as_deref(),
+            debug,
+            &lints.config()?,
+            &emitter,
+        )?,
+        Commands::Run {
+            input,
+            lints,
+            args: _,
+        } => cmd_run(input.as_deref(), &lints.config()?, &emitter)?,
+        Commands::Check { input, lints } => {
+            cmd_check(input.as_deref(), &lints.config()?, &emitter)?
+        }
+        Commands::Fix { input } => cmd_fix(input.as_deref(), &emitter)?,
         Commands::Build {
             input,
             output,
             out_dir,
             release,
             check_only,
-        } => cmd_build(&input, &output, out_dir.as_deref(), release, check_only)?,
+            no_cache,
+            debug,
+            lints,
+        } => cmd_build(
+            input.as_deref(),
+            output.as_deref(),
+            out_dir.as_deref(),
+            release,
+            check_only,
+            no_cache,
+            debug,
+            &lints.config()?,
+            &emitter,
+        )?,
+        Commands::Test {
+            path,
+            filter,
+            junit,
+        } => cmd_test(&path, filter.as_deref(), junit.as_deref())?,
         Commands::Repl => cmd_repl()?,
     }
 
@@ -2,10 +2,18 @@
 //!
 //! 封装 LLVM 的 Context、Module、Builder，简化代码生成过程
 
+use crate::debug_info::DebugInfo;
 use crate::error::CodegenError;
+use crate::partition::Partition;
+use crate::source_map::{SourceLocation, SourceMap};
 use inkwell::builder::Builder;
 use inkwell::context::Context;
-use inkwell::module::Module;
+use inkwell::module::{Linkage, Module};
+use inkwell::values::PointerValue;
+use inkwell::AddressSpace;
+use lency_syntax::ast::Span;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 
 /// LLVM 代码生成上下文
 ///
@@ -34,13 +42,40 @@ pub struct CodegenContext<'ctx> {
     pub enum_variants:
         std::collections::HashMap<String, Vec<(String, Vec<lency_syntax::ast::Type>)>>,
 
+    /// Map TraitName -> Ordered Methods [(Name, Param Types, Return Type)]
+    /// 方法顺序即 vtable 槽位顺序
+    pub trait_methods: std::collections::HashMap<
+        String,
+        Vec<(
+            String,
+            Vec<lency_syntax::ast::Type>,
+            lency_syntax::ast::Type,
+        )>,
+    >,
+
     /// Global Variable Types
     pub global_var_types: std::collections::HashMap<String, lency_syntax::ast::Type>,
+    /// `extern` 声明的外部函数（返回的字符串需要从 C 字符串转换）
+    pub extern_functions: std::collections::HashSet<String>,
 
     /// Runtime Panic Function
     pub panic_func: Option<inkwell::values::FunctionValue<'ctx>>,
-    /// Line starts for source mapping
-    line_starts: Vec<usize>,
+    /// DWARF 调试信息（仅 `-g` 编译时存在）
+    pub debug: Option<DebugInfo<'ctx>>,
+    /// 整数 `+ - *` 与取负的溢出检查（release 构建关闭，按位宽回绕）
+    pub overflow_checks: bool,
+    /// 调用前后压入/弹出 panic 调用栈帧（release 构建关闭）
+    pub call_traces: bool,
+    /// 分模块编译时本 Module 负责定义的声明；为 None 时定义整个程序
+    pub partition: Option<Partition>,
+    /// 源码位置映射
+    pub sources: SourceMap,
+    /// 当前正在生成的函数所在的源文件（SourceMap 下标）
+    pub current_file: Cell<usize>,
+    /// 源码位置与函数名等常量字符串（按内容去重）
+    const_strings: RefCell<HashMap<String, PointerValue<'ctx>>>,
+    /// 字符串字面量（带长度头，按内容去重）
+    lency_strings: RefCell<HashMap<String, PointerValue<'ctx>>>,
 }
 
 impl<'ctx> CodegenContext<'ctx> {
@@ -49,16 +84,14 @@ impl<'ctx> CodegenContext<'ctx> {
     /// # Arguments
     /// * `context` - LLVM Context 引用
     /// * `module_name` - 模块名称
-    /// * `source` - 源代码 (可选, 用于调试信息)
+    /// * `source` - 源代码 (可选, 用于源码位置)
     pub fn new(context: &'ctx Context, module_name: &str, source: Option<&str>) -> Self {
-        let line_starts = if let Some(src) = source {
-            std::iter::once(0)
-                .chain(src.match_indices('\n').map(|(i, _)| i + 1))
-                .collect()
-        } else {
-            Vec::new()
-        };
+        let sources = SourceMap::new(format!("{}.lcy", module_name), source.unwrap_or(""));
+        Self::with_sources(context, module_name, sources)
+    }
 
+    /// 使用完整的源码映射创建上下文（入口文件与导入模块）
+    pub fn with_sources(context: &'ctx Context, module_name: &str, sources: SourceMap) -> Self {
         Self {
             context,
             module: context.create_module(module_name),
@@ -69,21 +102,101 @@ impl<'ctx> CodegenContext<'ctx> {
             struct_field_types: std::collections::HashMap::new(),
             enum_types: std::collections::HashSet::new(),
             enum_variants: std::collections::HashMap::new(),
+            trait_methods: std::collections::HashMap::new(),
             global_var_types: std::collections::HashMap::new(),
+            extern_functions: std::collections::HashSet::new(),
             panic_func: None,
-            line_starts,
+            debug: None,
+            overflow_checks: true,
+            call_traces: true,
+            partition: None,
+            sources,
+            current_file: Cell::new(0),
+            const_strings: RefCell::new(HashMap::new()),
+            lency_strings: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// 启用 DWARF 调试信息
+    pub fn enable_debug_info(&mut self) {
+        self.debug = Some(DebugInfo::new(self));
+    }
+
+    /// 当前函数所在文件中 `span` 起点的位置
+    pub fn location(&self, span: &Span) -> SourceLocation {
+        self.sources.location(self.current_file.get(), span)
+    }
+
+    /// 是否定义第 `index` 个声明（未分区时定义全部声明）
+    pub fn defines(&self, index: usize) -> bool {
+        self.partition.as_ref().is_none_or(|p| p.owns(index))
+    }
+
+    /// 是否生成 `main` 包装函数与全局变量初始化
+    pub fn defines_entry(&self) -> bool {
+        self.partition.as_ref().is_none_or(Partition::is_entry)
+    }
+
+    /// 各分区各自生成的辅助函数（枚举构造函数、闭包）的链接方式
+    pub fn helper_linkage(&self) -> Option<Linkage> {
+        self.partition.as_ref().map(|_| Linkage::Internal)
+    }
+
+    /// 常量 C 字符串（i8*），相同内容只生成一个全局变量
+    pub fn const_string(&self, value: &str) -> PointerValue<'ctx> {
+        if let Some(ptr) = self.const_strings.borrow().get(value) {
+            return *ptr;
         }
+        let bytes = self.context.const_string(value.as_bytes(), true);
+        let global = self.module.add_global(bytes.get_type(), None, ".str.loc");
+        global.set_initializer(&bytes);
+        global.set_constant(true);
+        global.set_linkage(Linkage::Private);
+        global.set_unnamed_addr(true);
+        let ptr = global
+            .as_pointer_value()
+            .const_cast(self.context.i8_type().ptr_type(AddressSpace::default()));
+        self.const_strings
+            .borrow_mut()
+            .insert(value.to_string(), ptr);
+        ptr
     }
 
-    /// 获取字节偏移对应的行号 (1-based)
-    pub fn get_line(&self, byte_offset: usize) -> u32 {
-        if self.line_starts.is_empty() {
-            return 0;
+    /// Lency 字符串常量（i8*），相同内容只生成一个全局变量
+    ///
+    /// 全局变量布局与运行时分配的字符串相同（见 `lency_runtime::lstring`）：
+    /// `{ i64 len, i64 cap, [len + 1 x i8] }`，返回的指针指向数据首字节。
+    pub fn lency_string(&self, value: &str) -> PointerValue<'ctx> {
+        if let Some(ptr) = self.lency_strings.borrow().get(value) {
+            return *ptr;
         }
-        match self.line_starts.binary_search(&byte_offset) {
-            Ok(line) => (line + 1) as u32,
-            Err(next_line_idx) => next_line_idx as u32,
+        let i64_type = self.context.i64_type();
+        let i32_type = self.context.i32_type();
+        let len = i64_type.const_int(value.len() as u64, false);
+        let bytes = self.context.const_string(value.as_bytes(), true);
+        let init = self
+            .context
+            .const_struct(&[len.into(), len.into(), bytes.into()], false);
+        let global = self.module.add_global(init.get_type(), None, ".str");
+        global.set_initializer(&init);
+        global.set_constant(true);
+        global.set_linkage(Linkage::Private);
+        global.set_unnamed_addr(true);
+        let ptr = unsafe {
+            global.as_pointer_value().const_in_bounds_gep(
+                init.get_type(),
+                &[
+                    i32_type.const_zero(),
+                    i32_type.const_int(2, false),
+                    i32_type.const_zero(),
+                ],
+            )
         }
+        .const_cast(self.context.i8_type().ptr_type(AddressSpace::default()));
+        self.lency_strings
+            .borrow_mut()
+            .insert(value.to_string(), ptr);
+        ptr
     }
 
     /// 获取模块的 LLVM IR 字符串表示
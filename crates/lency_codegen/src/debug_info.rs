@@ -0,0 +1,453 @@
+//! Debug Info Generation
+//!
+//! 为 `-g` 编译生成 DWARF 调试信息：
+//! - 每个源文件一个 DIFile，整个程序一个 DICompileUnit
+//! - 每个用户函数（包括方法与闭包）一个 DISubprogram
+//! - 语句级行号：根据 `Stmt` 的 Span 设置 builder 的当前调试位置
+//! - 参数与 `var` 局部变量通过 `llvm.dbg.declare` 关联到各自的 alloca
+//!
+//! 源文件与行列由 `CodegenContext::sources` 提供。
+
+use crate::context::CodegenContext;
+use crate::source_map::SourceLocation;
+use inkwell::debug_info::{
+    AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DILocation, DISubprogram, DIType,
+    DWARFEmissionKind, DWARFSourceLanguage, DebugInfoBuilder,
+};
+use inkwell::module::FlagBehavior;
+use inkwell::values::{FunctionValue, PointerValue};
+use inkwell::AddressSpace;
+use lency_syntax::ast::{Span, Stmt, Type};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// DWARF 基础类型编码 (DW_ATE_*)
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_SIGNED_CHAR: u32 = 0x06;
+const DW_ATE_UNSIGNED: u32 = 0x08;
+const DW_ATE_UTF: u32 = 0x10;
+
+/// 指针宽度（目前只支持 64 位目标）
+const POINTER_BITS: u64 = 64;
+
+/// 正在生成的函数
+struct Scope<'ctx> {
+    subprogram: DISubprogram<'ctx>,
+    /// 最近一次设置的位置（退出内层闭包后恢复）
+    location: DILocation<'ctx>,
+}
+
+/// 模块级调试信息状态
+pub struct DebugInfo<'ctx> {
+    builder: DebugInfoBuilder<'ctx>,
+    compile_unit: DICompileUnit<'ctx>,
+    /// 与 `SourceMap` 的文件一一对应
+    files: Vec<DIFile<'ctx>>,
+    scopes: RefCell<Vec<Scope<'ctx>>>,
+    types: RefCell<HashMap<String, DIType<'ctx>>>,
+}
+
+impl<'ctx> DebugInfo<'ctx> {
+    /// 为模块创建编译单元，并写入调试信息版本标记
+    pub fn new(ctx: &CodegenContext<'ctx>) -> Self {
+        let (file_name, directory) = split_path(ctx.sources.path(0));
+        let (builder, compile_unit) = ctx.module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &file_name,
+            &directory,
+            concat!("lencyc ", env!("CARGO_PKG_VERSION")),
+            false,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+
+        let i32_type = ctx.context.i32_type();
+        ctx.module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            i32_type.const_int(inkwell::debug_info::debug_metadata_version() as u64, false),
+        );
+        ctx.module.add_basic_value_flag(
+            "Dwarf Version",
+            FlagBehavior::Warning,
+            i32_type.const_int(4, false),
+        );
+
+        let mut files = vec![compile_unit.get_file()];
+        for source in &ctx.sources.files()[1..] {
+            let (file_name, directory) = split_path(&source.path);
+            files.push(builder.create_file(&file_name, &directory));
+        }
+
+        Self {
+            builder,
+            compile_unit,
+            files,
+            scopes: RefCell::new(Vec::new()),
+            types: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// 开始生成函数体：创建 DISubprogram 并把位置设为函数声明处
+    ///
+    /// 函数所在文件取自 `ctx.current_file`。
+    pub fn enter_function(
+        &self,
+        ctx: &CodegenContext<'ctx>,
+        function: FunctionValue<'ctx>,
+        name: &str,
+        span: &Span,
+        param_types: &[Type],
+        return_type: &Type,
+    ) {
+        let file = self.file(ctx);
+        let line = ctx.location(span).line;
+
+        let return_di = match return_type {
+            Type::Void => None,
+            ty => Some(self.di_type(ctx, ty)),
+        };
+        let params: Vec<DIType<'ctx>> = param_types.iter().map(|t| self.di_type(ctx, t)).collect();
+        let subroutine =
+            self.builder
+                .create_subroutine_type(file, return_di, &params, DIFlags::ZERO);
+
+        let llvm_name = function.get_name().to_string_lossy();
+        let linkage_name = (llvm_name != name).then_some(llvm_name.as_ref());
+        let subprogram = self.builder.create_function(
+            file.as_debug_info_scope(),
+            name,
+            linkage_name,
+            file,
+            line,
+            subroutine,
+            false,
+            true,
+            line,
+            DIFlags::PROTOTYPED,
+            false,
+        );
+        function.set_subprogram(subprogram);
+
+        let location = self.builder.create_debug_location(
+            ctx.context,
+            line,
+            1,
+            subprogram.as_debug_info_scope(),
+            None,
+        );
+        ctx.builder.set_current_debug_location(location);
+        self.scopes.borrow_mut().push(Scope {
+            subprogram,
+            location,
+        });
+    }
+
+    fn file(&self, ctx: &CodegenContext<'ctx>) -> DIFile<'ctx> {
+        self.files
+            .get(ctx.current_file.get())
+            .copied()
+            .unwrap_or_else(|| self.compile_unit.get_file())
+    }
+
+    /// 结束函数体：恢复外层函数的位置（闭包），否则清除位置
+    pub fn exit_function(&self, ctx: &CodegenContext<'ctx>) {
+        let mut scopes = self.scopes.borrow_mut();
+        scopes.pop();
+        match scopes.last() {
+            Some(outer) => ctx.builder.set_current_debug_location(outer.location),
+            None => ctx.builder.unset_current_debug_location(),
+        }
+    }
+
+    /// 将后续指令的位置设为 `span` 起点
+    pub fn set_location(&self, ctx: &CodegenContext<'ctx>, span: &Span) {
+        let mut scopes = self.scopes.borrow_mut();
+        let Some(scope) = scopes.last_mut() else {
+            return;
+        };
+        let SourceLocation { line, column, .. } = ctx.location(span);
+        let location = self.builder.create_debug_location(
+            ctx.context,
+            line,
+            column,
+            scope.subprogram.as_debug_info_scope(),
+            None,
+        );
+        ctx.builder.set_current_debug_location(location);
+        scope.location = location;
+    }
+
+    /// 为局部变量（`arg_no` 为 Some 时为参数）生成 `llvm.dbg.declare`
+    pub fn declare_variable(
+        &self,
+        ctx: &CodegenContext<'ctx>,
+        storage: PointerValue<'ctx>,
+        name: &str,
+        ty: &Type,
+        arg_no: Option<u32>,
+    ) {
+        let Some(block) = ctx.builder.get_insert_block() else {
+            return;
+        };
+        let scopes = self.scopes.borrow();
+        let Some(scope) = scopes.last() else {
+            return;
+        };
+        let file = self.file(ctx);
+        let line = scope.location.get_line();
+        let di_scope = scope.subprogram.as_debug_info_scope();
+        let di_type = self.di_type(ctx, ty);
+
+        let variable = match arg_no {
+            Some(arg_no) => self.builder.create_parameter_variable(
+                di_scope,
+                name,
+                arg_no,
+                file,
+                line,
+                di_type,
+                true,
+                DIFlags::ZERO,
+            ),
+            None => self.builder.create_auto_variable(
+                di_scope,
+                name,
+                file,
+                line,
+                di_type,
+                true,
+                DIFlags::ZERO,
+                0,
+            ),
+        };
+        self.builder
+            .insert_declare_at_end(storage, Some(variable), None, scope.location, block);
+    }
+
+    /// 解析所有前向引用，必须在验证模块之前调用
+    pub fn finalize(&self) {
+        self.builder.finalize();
+    }
+
+    /// Lency 类型 -> DWARF 类型（按类型名缓存）
+    fn di_type(&self, ctx: &CodegenContext<'ctx>, ty: &Type) -> DIType<'ctx> {
+        self.di_type_inner(ctx, ty, &mut HashSet::new())
+    }
+
+    fn di_type_inner(
+        &self,
+        ctx: &CodegenContext<'ctx>,
+        ty: &Type,
+        in_progress: &mut HashSet<String>,
+    ) -> DIType<'ctx> {
+        let key = ty.to_string();
+        if let Some(cached) = self.types.borrow().get(&key) {
+            return *cached;
+        }
+
+        let di_type = match ty {
+            Type::Int => self.basic_type("int", 64, DW_ATE_SIGNED),
+            Type::FixedInt(kind) => {
+                let encoding = if kind.is_signed() {
+                    DW_ATE_SIGNED
+                } else {
+                    DW_ATE_UNSIGNED
+                };
+                self.basic_type(kind.name(), kind.bits() as u64, encoding)
+            }
+            Type::Float => self.basic_type("float", 64, DW_ATE_FLOAT),
+            Type::Bool => self.basic_type("bool", 8, DW_ATE_BOOLEAN),
+            Type::Char => self.basic_type("char", 32, DW_ATE_UTF),
+            Type::String => {
+                let char_type = self.basic_type("char", 8, DW_ATE_SIGNED_CHAR);
+                self.pointer_type("string", char_type)
+            }
+            Type::Nullable(inner) => {
+                let inner = self.di_type_inner(ctx, inner, in_progress);
+                self.pointer_type(&key, inner)
+            }
+            Type::Array { element_type, size } => {
+                let element = self.di_type_inner(ctx, element_type, in_progress);
+                let size_in_bits = element.get_size_in_bits() * *size as u64;
+                let subscript = 0..*size as i64;
+                self.builder
+                    .create_array_type(element, size_in_bits, 0, std::slice::from_ref(&subscript))
+                    .as_type()
+            }
+            Type::Struct(name) if ctx.struct_field_types.contains_key(name) => {
+                let pointee = if in_progress.insert(name.clone()) {
+                    let di = self.struct_type(ctx, name, in_progress);
+                    in_progress.remove(name);
+                    di
+                } else {
+                    // 递归结构体：内层引用使用前向声明
+                    self.forward_struct(name)
+                };
+                self.pointer_type(&key, pointee)
+            }
+            // 区间按值保存，这里只给出名称
+            Type::Range => self.forward_struct("Range"),
+            // 其余类型（Vec、Result、闭包、trait 对象等）在运行时都是指针
+            _ => {
+                let byte = self.basic_type("byte", 8, DW_ATE_SIGNED_CHAR);
+                self.pointer_type(&key, byte)
+            }
+        };
+
+        self.types.borrow_mut().insert(key, di_type);
+        di_type
+    }
+
+    fn basic_type(&self, name: &str, size_in_bits: u64, encoding: u32) -> DIType<'ctx> {
+        match self
+            .builder
+            .create_basic_type(name, size_in_bits, encoding, DIFlags::ZERO)
+        {
+            Ok(ty) => ty.as_type(),
+            // 只有 size 为 0 时才会失败，这里的尺寸都是常量
+            Err(_) => self.forward_struct(name),
+        }
+    }
+
+    fn pointer_type(&self, name: &str, pointee: DIType<'ctx>) -> DIType<'ctx> {
+        self.builder
+            .create_pointer_type(
+                name,
+                pointee,
+                POINTER_BITS,
+                POINTER_BITS as u32,
+                AddressSpace::default(),
+            )
+            .as_type()
+    }
+
+    fn forward_struct(&self, name: &str) -> DIType<'ctx> {
+        self.builder
+            .create_struct_type(
+                self.compile_unit.as_debug_info_scope(),
+                name,
+                self.compile_unit.get_file(),
+                0,
+                0,
+                0,
+                DIFlags::FWD_DECL,
+                None,
+                &[],
+                0,
+                None,
+                name,
+            )
+            .as_type()
+    }
+
+    /// 结构体本体（按 C 布局计算字段偏移）
+    fn struct_type(
+        &self,
+        ctx: &CodegenContext<'ctx>,
+        name: &str,
+        in_progress: &mut HashSet<String>,
+    ) -> DIType<'ctx> {
+        let file = self
+            .files
+            .get(ctx.sources.file_of(name))
+            .copied()
+            .unwrap_or_else(|| self.compile_unit.get_file());
+        let scope = self.compile_unit.as_debug_info_scope();
+        let field_names = ctx.struct_fields.get(name).cloned().unwrap_or_default();
+        let field_types = ctx
+            .struct_field_types
+            .get(name)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut offset = 0u64;
+        let mut max_align = 8u64;
+        let mut members = Vec::with_capacity(field_types.len());
+        for (field_name, field_ty) in field_names.iter().zip(&field_types) {
+            let field_di = self.di_type_inner(ctx, field_ty, in_progress);
+            let size = field_di.get_size_in_bits();
+            let align = size.clamp(8, POINTER_BITS);
+            offset = offset.next_multiple_of(align);
+            max_align = max_align.max(align);
+            members.push(
+                self.builder
+                    .create_member_type(
+                        scope,
+                        field_name,
+                        file,
+                        0,
+                        size,
+                        align as u32,
+                        offset,
+                        DIFlags::PUBLIC,
+                        field_di,
+                    )
+                    .as_type(),
+            );
+            offset += size;
+        }
+
+        self.builder
+            .create_struct_type(
+                scope,
+                name,
+                file,
+                0,
+                offset.next_multiple_of(max_align),
+                max_align as u32,
+                DIFlags::PUBLIC,
+                None,
+                &members,
+                0,
+                None,
+                name,
+            )
+            .as_type()
+    }
+}
+
+/// 语句的源码范围（Block 没有自己的 Span）
+pub fn stmt_span(stmt: &Stmt) -> Option<&Span> {
+    match stmt {
+        Stmt::VarDecl { span, .. }
+        | Stmt::VarPattern { span, .. }
+        | Stmt::Assignment { span, .. }
+        | Stmt::If { span, .. }
+        | Stmt::IfLet { span, .. }
+        | Stmt::While { span, .. }
+        | Stmt::WhileLet { span, .. }
+        | Stmt::For { span, .. }
+        | Stmt::ForIn { span, .. }
+        | Stmt::Return { span, .. }
+        | Stmt::Break { span }
+        | Stmt::Continue { span } => Some(span),
+        Stmt::Expression(expr) => Some(&expr.span),
+        Stmt::Block(_) => None,
+    }
+}
+
+/// 路径 -> (文件名, 所在目录)；相对路径以当前目录为基准，便于调试器定位源码
+fn split_path(path: &Path) -> (String, String) {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "main.lcy".to_string());
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let directory = match std::env::current_dir() {
+        Ok(cwd) if parent.is_relative() => cwd.join(parent),
+        _ => parent.to_path_buf(),
+    };
+    (file_name, directory.to_string_lossy().into_owned())
+}
@@ -1,6 +1,7 @@
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
 use inkwell::types::BasicType;
 use lency_syntax::ast::{Expr, Type};
 use std::collections::HashMap;
@@ -86,12 +87,17 @@ pub fn gen_index_access<'ctx>(
     locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, lency_syntax::ast::Type)>,
     array_expr: &Expr,
     index_expr: &Expr,
-    line: u32,
+    loc: SourceLocation,
 ) -> CodegenResult<CodegenValue<'ctx>> {
     // 生成数组和索引
     let array_wrapper = generate_expr(ctx, locals, array_expr)?;
     let index_wrapper = generate_expr(ctx, locals, index_expr)?;
 
+    // 区间下标：切片
+    if index_wrapper.ty == Type::Range {
+        return super::numeric::range::gen_slice(ctx, array_wrapper, index_wrapper, loc);
+    }
+
     let array_val = array_wrapper.value;
     let index_val = index_wrapper.value;
 
@@ -102,6 +108,7 @@ pub fn gen_index_access<'ctx>(
         // Vec 索引访问
         // call lency_vec_get
         let vec_ptr = array_val.into_pointer_value(); // %LencyVec*
+        crate::expr::vec::gen_vec_bounds_check(ctx, vec_ptr, index_int, loc)?;
 
         let func = crate::expr::vec::get_or_declare_vec_get(ctx)?;
         let call = ctx
@@ -120,10 +127,11 @@ pub fn gen_index_access<'ctx>(
 
     if array_wrapper.ty == Type::String {
         // String Indexing: s[i] -> int (byte)
-        // Assume s is i8*
+        // s 是指向数据首字节的 i8*，长度在头部
         let str_ptr = array_val.into_pointer_value();
 
-        // TODO: Bounds check? Need strlen. Skip for now.
+        let len = super::string::string_ops::gen_string_len(ctx, str_ptr)?;
+        crate::runtime::gen_bounds_check(ctx, index_int, len, loc);
 
         // GEP i8* s, index
         let char_ptr = unsafe {
@@ -179,17 +187,8 @@ pub fn gen_index_access<'ctx>(
     };
 
     // === 边界检查 ===
-    if let Some(panic_func) = ctx.panic_func {
-        let len_val = ctx.context.i64_type().const_int(array_size, false);
-        crate::runtime::gen_bounds_check(
-            ctx.context,
-            &ctx.builder,
-            panic_func,
-            index_int,
-            len_val,
-            line,
-        );
-    }
+    let len_val = ctx.context.i64_type().const_int(array_size, false);
+    crate::runtime::gen_bounds_check(ctx, index_int, len_val, loc);
 
     // 需要先将数组存到栈上（因为 array_val 是值）
     let array_alloca = ctx
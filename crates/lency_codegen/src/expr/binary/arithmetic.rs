@@ -1,7 +1,9 @@
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
-use crate::expr::string_ops;
+use crate::expr::string::string_ops;
+use crate::types::is_unsigned_type;
 use inkwell::values::BasicValueEnum;
+use lency_syntax::ast::Type;
 
 pub fn gen_add<'ctx>(
     ctx: &CodegenContext<'ctx>,
@@ -135,8 +137,16 @@ pub fn gen_div<'ctx>(
     ctx: &CodegenContext<'ctx>,
     lhs: BasicValueEnum<'ctx>,
     rhs: BasicValueEnum<'ctx>,
+    lhs_ty: &Type,
 ) -> CodegenResult<BasicValueEnum<'ctx>> {
     match (lhs, rhs) {
+        // 无符号整数用 udiv，其余按有符号
+        (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) if is_unsigned_type(lhs_ty) => {
+            ctx.builder
+                .build_int_unsigned_div(l, r, "divtmp")
+                .map(Into::into)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+        }
         (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => ctx
             .builder
             .build_int_signed_div(l, r, "divtmp")
@@ -176,8 +186,15 @@ pub fn gen_mod<'ctx>(
     ctx: &CodegenContext<'ctx>,
     lhs: BasicValueEnum<'ctx>,
     rhs: BasicValueEnum<'ctx>,
+    lhs_ty: &Type,
 ) -> CodegenResult<BasicValueEnum<'ctx>> {
     match (lhs, rhs) {
+        (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) if is_unsigned_type(lhs_ty) => {
+            ctx.builder
+                .build_int_unsigned_rem(l, r, "modtmp")
+                .map(Into::into)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+        }
         (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => ctx
             .builder
             .build_int_signed_rem(l, r, "modtmp")
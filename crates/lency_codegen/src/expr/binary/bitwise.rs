@@ -0,0 +1,95 @@
+//! Bitwise Operation Code Generation
+//!
+//! 按位运算与移位。移位量按位宽取模（`x << 65` 即 `x << 1`，与 `wrapping_shl` 一致），
+//! 避免 LLVM 对超出位宽的移位产生 poison；`>>` 对有符号整数是算术右移，无符号是逻辑右移。
+
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::types::is_unsigned_type;
+use inkwell::values::{BasicValueEnum, IntValue};
+use lency_syntax::ast::Type;
+
+pub fn gen_bit_and<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    lhs: BasicValueEnum<'ctx>,
+    rhs: BasicValueEnum<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let (l, r) = int_operands(lhs, rhs)?;
+    ctx.builder
+        .build_and(l, r, "bitand")
+        .map(Into::into)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
+
+pub fn gen_bit_or<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    lhs: BasicValueEnum<'ctx>,
+    rhs: BasicValueEnum<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let (l, r) = int_operands(lhs, rhs)?;
+    ctx.builder
+        .build_or(l, r, "bitor")
+        .map(Into::into)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
+
+pub fn gen_bit_xor<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    lhs: BasicValueEnum<'ctx>,
+    rhs: BasicValueEnum<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let (l, r) = int_operands(lhs, rhs)?;
+    ctx.builder
+        .build_xor(l, r, "bitxor")
+        .map(Into::into)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
+
+pub fn gen_shl<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    lhs: BasicValueEnum<'ctx>,
+    rhs: BasicValueEnum<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let (l, r) = int_operands(lhs, rhs)?;
+    let amount = shift_amount(ctx, r)?;
+    ctx.builder
+        .build_left_shift(l, amount, "shl")
+        .map(Into::into)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
+
+pub fn gen_shr<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    lhs: BasicValueEnum<'ctx>,
+    rhs: BasicValueEnum<'ctx>,
+    lhs_ty: &Type,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let (l, r) = int_operands(lhs, rhs)?;
+    let amount = shift_amount(ctx, r)?;
+    ctx.builder
+        .build_right_shift(l, amount, !is_unsigned_type(lhs_ty), "shr")
+        .map(Into::into)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
+
+fn int_operands<'ctx>(
+    lhs: BasicValueEnum<'ctx>,
+    rhs: BasicValueEnum<'ctx>,
+) -> CodegenResult<(IntValue<'ctx>, IntValue<'ctx>)> {
+    match (lhs, rhs) {
+        (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => Ok((l, r)),
+        _ => Err(CodegenError::TypeMismatch),
+    }
+}
+
+/// 移位量对位宽取模（位宽均为 2 的幂，取低位即可）
+fn shift_amount<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    amount: IntValue<'ctx>,
+) -> CodegenResult<IntValue<'ctx>> {
+    let int_type = amount.get_type();
+    let mask = int_type.const_int(u64::from(int_type.get_bit_width() - 1), false);
+    ctx.builder
+        .build_and(amount, mask, "shift_amount")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
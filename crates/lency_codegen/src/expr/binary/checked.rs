@@ -0,0 +1,130 @@
+//! Checked Integer Arithmetic
+//!
+//! 整数 `+ - * / %` 的运行时检查，失败时带源码位置 panic：
+//!
+//! - 非 release 构建：`+ - *` 经 `llvm.{s,u}{op}.with.overflow` 检查溢出
+//! - release 构建：`+ - *` 按位宽回绕，不做检查
+//! - 所有构建：`/ %` 检查除以零与有符号 `MIN / -1`（在 LLVM 中二者都是未定义行为）
+
+use crate::context::CodegenContext;
+use crate::error::CodegenResult;
+use crate::expr::numeric::integer::{gen_checked_arith, gen_div_checks};
+use crate::expr::CodegenValue;
+use crate::source_map::SourceLocation;
+use crate::types::{is_int_type, is_unsigned_type};
+use inkwell::values::BasicValueEnum;
+use lency_syntax::ast::BinaryOp;
+
+/// 生成检查并返回结果；`None` 表示由调用方生成普通指令（`/ %` 在检查之后也走这里）
+pub fn gen_checked_int_op<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    op: &BinaryOp,
+    lhs: &CodegenValue<'ctx>,
+    rhs: &CodegenValue<'ctx>,
+    loc: SourceLocation,
+) -> CodegenResult<Option<CodegenValue<'ctx>>> {
+    let (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) = (lhs.value, rhs.value) else {
+        return Ok(None);
+    };
+    if !is_int_type(&lhs.ty) {
+        return Ok(None);
+    }
+
+    let name = match op {
+        BinaryOp::Add => "add",
+        BinaryOp::Sub => "sub",
+        BinaryOp::Mul => "mul",
+        BinaryOp::Div | BinaryOp::Mod => {
+            gen_div_checks(ctx, l, r, is_unsigned_type(&lhs.ty), loc)?;
+            return Ok(None);
+        }
+        _ => return Ok(None),
+    };
+    if !ctx.overflow_checks {
+        return Ok(None);
+    }
+    gen_checked_arith(ctx, name, l, r, lhs.ty.clone(), loc).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::PANIC_FN;
+    use crate::source_map::SourceMap;
+
+    const SOURCE: &str = "int mix(int a, int b) {
+    return -(a * b - a) + b
+}
+
+u8 bump(u8 x, u8 y) {
+    return x + y
+}
+";
+
+    /// 编译同一份源码；`overflow_checks` 为 false 即 `--release`
+    fn compile(overflow_checks: bool) -> String {
+        let program = lency_syntax::parser::parse_spanned(SOURCE).expect("parse failed");
+        let sources = SourceMap::new("checked.lcy", SOURCE);
+        crate::compile_to_ir_with_sources(&program, "checked", sources, false, overflow_checks)
+            .expect("codegen failed")
+    }
+
+    /// IR 中 `@name` 的函数定义
+    fn function_ir<'a>(ir: &'a str, name: &str) -> &'a str {
+        let signature = format!("@{}(", name);
+        let start = ir
+            .match_indices("define ")
+            .map(|(i, _)| i)
+            .find(|&i| {
+                ir[i..]
+                    .lines()
+                    .next()
+                    .is_some_and(|l| l.contains(&signature))
+            })
+            .unwrap_or_else(|| panic!("function {} not found in IR", name));
+        let body = &ir[start..];
+        &body[..body.find("\n}\n").map_or(body.len(), |end| end + 2)]
+    }
+
+    #[test]
+    fn test_overflow_checks_only_in_debug_builds() {
+        let debug = compile(true);
+        let release = compile(false);
+        let panic_call = format!("@{}(", PANIC_FN);
+
+        // 默认构建：+ - * 与取负都经溢出内建函数检查，溢出分支调用 panic
+        let mix = function_ir(&debug, "mix");
+        for intrinsic in [
+            "@llvm.smul.with.overflow.i64",
+            "@llvm.ssub.with.overflow.i64",
+            "@llvm.sadd.with.overflow.i64",
+        ] {
+            assert!(
+                mix.contains(intrinsic),
+                "missing {} in:\n{}",
+                intrinsic,
+                mix
+            );
+        }
+        assert!(mix.contains(&panic_call), "missing panic call in:\n{}", mix);
+        let bump = function_ir(&debug, "bump");
+        assert!(bump.contains("@llvm.uadd.with.overflow.i8"));
+        assert!(bump.contains(&panic_call));
+
+        // release：同一函数按位宽回绕，没有溢出检查也没有 panic
+        for name in ["mix", "bump"] {
+            let body = function_ir(&release, name);
+            assert!(
+                !body.contains("with.overflow"),
+                "unexpected check in:\n{}",
+                body
+            );
+            assert!(
+                !body.contains(&panic_call),
+                "unexpected panic in:\n{}",
+                body
+            );
+        }
+        assert!(function_ir(&release, "mix").contains("mul i64"));
+        assert!(function_ir(&release, "bump").contains("add i8"));
+    }
+}
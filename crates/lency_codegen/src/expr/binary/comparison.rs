@@ -1,5 +1,7 @@
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
+use crate::expr::string::string_ops;
+use crate::types::is_unsigned_type;
 use inkwell::values::BasicValueEnum;
 use inkwell::IntPredicate;
 use lency_syntax::ast::Type;
@@ -22,38 +24,9 @@ pub fn gen_eq<'ctx>(
             .map(Into::into)
             .map_err(|e| CodegenError::LLVMBuildError(e.to_string())),
         (BasicValueEnum::PointerValue(l), BasicValueEnum::PointerValue(r)) => {
-            // 检查是否为字符串类型，使用 strcmp 进行内容比较
+            // 字符串按内容比较（长度在头部，内容可含 NUL）
             if matches!(lhs_ty, Type::String) {
-                // 获取或声明 strcmp 函数
-                let strcmp_fn = ctx.module.get_function("strcmp").unwrap_or_else(|| {
-                    let i32_type = ctx.context.i32_type();
-                    let i8_ptr_type = ctx
-                        .context
-                        .i8_type()
-                        .ptr_type(inkwell::AddressSpace::default());
-                    let fn_type =
-                        i32_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false);
-                    ctx.module.add_function(
-                        "strcmp",
-                        fn_type,
-                        Some(inkwell::module::Linkage::External),
-                    )
-                });
-
-                // 调用 strcmp(lhs, rhs)
-                let call = ctx
-                    .builder
-                    .build_call(strcmp_fn, &[l.into(), r.into()], "strcmp_result")
-                    .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-                let strcmp_result = call.try_as_basic_value().left().unwrap().into_int_value();
-
-                // strcmp 返回 0 表示相等
-                let zero = ctx.context.i32_type().const_int(0, false);
-                ctx.builder
-                    .build_int_compare(IntPredicate::EQ, strcmp_result, zero, "streqtmp")
-                    .map(Into::into)
-                    .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+                string_ops::gen_string_eq(ctx, l, r).map(Into::into)
             } else {
                 // 非字符串指针：比较指针地址
                 let l_int = ctx
@@ -94,33 +67,9 @@ pub fn gen_neq<'ctx>(
         (BasicValueEnum::PointerValue(l), BasicValueEnum::PointerValue(r)) => {
             // 检查是否为字符串类型
             if matches!(lhs_ty, Type::String) {
-                // 获取或声明 strcmp 函数
-                let strcmp_fn = ctx.module.get_function("strcmp").unwrap_or_else(|| {
-                    let i32_type = ctx.context.i32_type();
-                    let i8_ptr_type = ctx
-                        .context
-                        .i8_type()
-                        .ptr_type(inkwell::AddressSpace::default());
-                    let fn_type =
-                        i32_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false);
-                    ctx.module.add_function(
-                        "strcmp",
-                        fn_type,
-                        Some(inkwell::module::Linkage::External),
-                    )
-                });
-
-                let call = ctx
-                    .builder
-                    .build_call(strcmp_fn, &[l.into(), r.into()], "strcmp_result")
-                    .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-                let strcmp_result = call.try_as_basic_value().left().unwrap().into_int_value();
-
-                // strcmp 返回非 0 表示不相等
-                let zero = ctx.context.i32_type().const_int(0, false);
+                let eq = string_ops::gen_string_eq(ctx, l, r)?;
                 ctx.builder
-                    .build_int_compare(IntPredicate::NE, strcmp_result, zero, "strnetmp")
+                    .build_not(eq, "strnetmp")
                     .map(Into::into)
                     .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
             } else {
@@ -142,15 +91,30 @@ pub fn gen_neq<'ctx>(
     }
 }
 
+/// 按操作数类型选择有符号或无符号的整数比较谓词
+fn int_predicate(ty: &Type, signed: IntPredicate, unsigned: IntPredicate) -> IntPredicate {
+    if is_unsigned_type(ty) {
+        unsigned
+    } else {
+        signed
+    }
+}
+
 pub fn gen_lt<'ctx>(
     ctx: &CodegenContext<'ctx>,
     lhs: BasicValueEnum<'ctx>,
     rhs: BasicValueEnum<'ctx>,
+    lhs_ty: &Type,
 ) -> CodegenResult<BasicValueEnum<'ctx>> {
     match (lhs, rhs) {
         (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => ctx
             .builder
-            .build_int_compare(IntPredicate::SLT, l, r, "lttmp")
+            .build_int_compare(
+                int_predicate(lhs_ty, IntPredicate::SLT, IntPredicate::ULT),
+                l,
+                r,
+                "lttmp",
+            )
             .map(Into::into)
             .map_err(|e| CodegenError::LLVMBuildError(e.to_string())),
         (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) => ctx
@@ -166,11 +130,17 @@ pub fn gen_gt<'ctx>(
     ctx: &CodegenContext<'ctx>,
     lhs: BasicValueEnum<'ctx>,
     rhs: BasicValueEnum<'ctx>,
+    lhs_ty: &Type,
 ) -> CodegenResult<BasicValueEnum<'ctx>> {
     match (lhs, rhs) {
         (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => ctx
             .builder
-            .build_int_compare(IntPredicate::SGT, l, r, "gttmp")
+            .build_int_compare(
+                int_predicate(lhs_ty, IntPredicate::SGT, IntPredicate::UGT),
+                l,
+                r,
+                "gttmp",
+            )
             .map(Into::into)
             .map_err(|e| CodegenError::LLVMBuildError(e.to_string())),
         (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) => ctx
@@ -186,11 +156,17 @@ pub fn gen_leq<'ctx>(
     ctx: &CodegenContext<'ctx>,
     lhs: BasicValueEnum<'ctx>,
     rhs: BasicValueEnum<'ctx>,
+    lhs_ty: &Type,
 ) -> CodegenResult<BasicValueEnum<'ctx>> {
     match (lhs, rhs) {
         (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => ctx
             .builder
-            .build_int_compare(IntPredicate::SLE, l, r, "letmp")
+            .build_int_compare(
+                int_predicate(lhs_ty, IntPredicate::SLE, IntPredicate::ULE),
+                l,
+                r,
+                "letmp",
+            )
             .map(Into::into)
             .map_err(|e| CodegenError::LLVMBuildError(e.to_string())),
         (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) => ctx
@@ -206,11 +182,17 @@ pub fn gen_geq<'ctx>(
     ctx: &CodegenContext<'ctx>,
     lhs: BasicValueEnum<'ctx>,
     rhs: BasicValueEnum<'ctx>,
+    lhs_ty: &Type,
 ) -> CodegenResult<BasicValueEnum<'ctx>> {
     match (lhs, rhs) {
         (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => ctx
             .builder
-            .build_int_compare(IntPredicate::SGE, l, r, "getmp")
+            .build_int_compare(
+                int_predicate(lhs_ty, IntPredicate::SGE, IntPredicate::UGE),
+                l,
+                r,
+                "getmp",
+            )
             .map(Into::into)
             .map_err(|e| CodegenError::LLVMBuildError(e.to_string())),
         (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) => ctx
@@ -7,17 +7,22 @@ use std::collections::HashMap;
 
 use crate::context::CodegenContext;
 use crate::error::CodegenResult;
+use crate::source_map::SourceLocation;
 
 use crate::expr::{generate_expr, CodegenValue};
 use lency_syntax::ast::Type;
 
 pub mod arithmetic;
+pub mod bitwise;
+pub mod checked;
 pub mod comparison;
 pub mod logical;
 
 pub mod elvis;
 
 use arithmetic::{gen_add, gen_div, gen_mod, gen_mul, gen_sub};
+use bitwise::{gen_bit_and, gen_bit_or, gen_bit_xor, gen_shl, gen_shr};
+use checked::gen_checked_int_op;
 use comparison::{gen_eq, gen_geq, gen_gt, gen_leq, gen_lt, gen_neq};
 use elvis::gen_elvis;
 use logical::{gen_and, gen_or};
@@ -29,6 +34,7 @@ pub(super) fn gen_binary<'ctx>(
     left: &Expr,
     op: &BinaryOp,
     right: &Expr,
+    loc: SourceLocation,
 ) -> CodegenResult<CodegenValue<'ctx>> {
     // Short-circuiting operators
     if matches!(op, BinaryOp::Elvis) {
@@ -38,6 +44,11 @@ pub(super) fn gen_binary<'ctx>(
     let lhs_wrapper = generate_expr(ctx, locals, left)?;
     let rhs_wrapper = generate_expr(ctx, locals, right)?;
 
+    // 整数溢出与除零检查
+    if let Some(value) = gen_checked_int_op(ctx, op, &lhs_wrapper, &rhs_wrapper, loc)? {
+        return Ok(value);
+    }
+
     let lhs_val = lhs_wrapper.value;
     let rhs_val = rhs_wrapper.value;
 
@@ -45,23 +56,35 @@ pub(super) fn gen_binary<'ctx>(
         BinaryOp::Add => gen_add(ctx, lhs_val, rhs_val),
         BinaryOp::Sub => gen_sub(ctx, lhs_val, rhs_val),
         BinaryOp::Mul => gen_mul(ctx, lhs_val, rhs_val),
-        BinaryOp::Div => gen_div(ctx, lhs_val, rhs_val),
-        BinaryOp::Mod => gen_mod(ctx, lhs_val, rhs_val),
+        BinaryOp::Div => gen_div(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
+        BinaryOp::Mod => gen_mod(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
         BinaryOp::Eq => gen_eq(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
         BinaryOp::Neq => gen_neq(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
-        BinaryOp::Lt => gen_lt(ctx, lhs_val, rhs_val),
-        BinaryOp::Gt => gen_gt(ctx, lhs_val, rhs_val),
-        BinaryOp::Leq => gen_leq(ctx, lhs_val, rhs_val),
-        BinaryOp::Geq => gen_geq(ctx, lhs_val, rhs_val),
+        BinaryOp::Lt => gen_lt(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
+        BinaryOp::Gt => gen_gt(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
+        BinaryOp::Leq => gen_leq(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
+        BinaryOp::Geq => gen_geq(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
         BinaryOp::And => gen_and(ctx, lhs_val, rhs_val),
         BinaryOp::Or => gen_or(ctx, lhs_val, rhs_val),
+        BinaryOp::BitAnd => gen_bit_and(ctx, lhs_val, rhs_val),
+        BinaryOp::BitOr => gen_bit_or(ctx, lhs_val, rhs_val),
+        BinaryOp::BitXor => gen_bit_xor(ctx, lhs_val, rhs_val),
+        BinaryOp::Shl => gen_shl(ctx, lhs_val, rhs_val),
+        BinaryOp::Shr => gen_shr(ctx, lhs_val, rhs_val, &lhs_wrapper.ty),
         BinaryOp::Elvis => unreachable!("Elvis operator handled by short-circuit logic"),
     }?;
 
     let result_ty = match op {
-        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
-            lhs_wrapper.ty
-        }
+        BinaryOp::Add
+        | BinaryOp::Sub
+        | BinaryOp::Mul
+        | BinaryOp::Div
+        | BinaryOp::Mod
+        | BinaryOp::BitAnd
+        | BinaryOp::BitOr
+        | BinaryOp::BitXor
+        | BinaryOp::Shl
+        | BinaryOp::Shr => lhs_wrapper.ty,
         _ => Type::Bool,
     };
 
@@ -10,7 +10,6 @@ use crate::error::{CodegenError, CodegenResult};
 
 use crate::expr::{generate_expr, CodegenValue};
 use crate::types::ToLLVMType;
-use inkwell::types::BasicType;
 use lency_syntax::ast::Type;
 
 /// 生成函数调用代码
@@ -20,10 +19,39 @@ pub(super) fn gen_call<'ctx>(
     callee: &Expr,
     args: &[Expr],
 ) -> CodegenResult<CodegenValue<'ctx>> {
+    // 调用点，用于运行时调用栈
+    let loc = ctx.location(&callee.span);
+
     // 获取函数名
     let func_name = match &callee.kind {
         ExprKind::Variable(name) => name,
-        _ => return Err(CodegenError::UnsupportedExpression),
+        _ => {
+            // 复杂被调用者 (如 callbacks.get(0)(x))：必须求值为闭包
+            let callee_val = generate_expr(ctx, locals, callee)?;
+            let Type::Function {
+                param_types,
+                return_type,
+            } = &callee_val.ty
+            else {
+                return Err(CodegenError::UnsupportedExpression);
+            };
+
+            let mut arg_values = Vec::new();
+            for arg in args {
+                arg_values.push(generate_expr(ctx, locals, arg)?.value.into());
+            }
+
+            crate::runtime::gen_trace_push(ctx, "<closure>", loc);
+            let result = super::closure::gen_closure_call(
+                ctx,
+                callee_val.value.into_pointer_value(),
+                param_types,
+                return_type,
+                &arg_values,
+            );
+            crate::runtime::gen_trace_pop(ctx);
+            return result;
+        }
     };
 
     // 检查是否为 hashmap extern 函数
@@ -36,6 +64,18 @@ pub(super) fn gen_call<'ctx>(
         return super::conversion::gen_type_conversion_call(ctx, locals, func_name, args);
     }
 
+    // 检查是否为垃圾回收器内置函数
+    if super::gc::is_gc_fn(func_name) {
+        return super::gc::gen_gc_call(ctx, func_name, args);
+    }
+
+    // 检查是否为 StringBuilder 内置函数
+    if super::string::string_builder::is_string_builder_fn(func_name) {
+        return super::string::string_builder::gen_string_builder_call(
+            ctx, locals, func_name, args,
+        );
+    }
+
     // 生成参数
     let mut arg_values = Vec::new();
     for arg in args {
@@ -43,57 +83,30 @@ pub(super) fn gen_call<'ctx>(
         arg_values.push(val_wrapper.value.into());
     }
 
-    // 检查是否为函数指针变量 (闭包)
+    // 检查是否为闭包变量
     if let Some((ptr, var_ty)) = locals.get(func_name) {
         if let Type::Function {
             param_types,
             return_type,
         } = var_ty
         {
-            // 获取函数指针的 LLVM 类型
-            let fn_ptr_type = var_ty.to_llvm_type(ctx)?;
-
-            // 加载函数指针
-            let fn_ptr = ctx
+            // 加载闭包对象指针
+            let closure_ptr = ctx
                 .builder
-                .build_load(fn_ptr_type, *ptr, "fn_ptr_load")
+                .build_load(var_ty.to_llvm_type(ctx)?, *ptr, "closure_load")
                 .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
                 .into_pointer_value();
 
-            // 构建函数类型
-            let param_llvm_types: Result<Vec<_>, _> = param_types
-                .iter()
-                .map(|t| t.to_llvm_type(ctx).map(|ty| ty.into()))
-                .collect();
-            let param_llvm_types = param_llvm_types?;
-
-            let fn_type = if matches!(**return_type, Type::Void) {
-                ctx.context.void_type().fn_type(&param_llvm_types, false)
-            } else {
-                let ret = return_type.to_llvm_type(ctx)?;
-                ret.fn_type(&param_llvm_types, false)
-            };
-
-            // 间接调用
-            let call_site = ctx
-                .builder
-                .build_indirect_call(fn_type, fn_ptr, &arg_values, "closure_call")
-                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-            let val = call_site.try_as_basic_value().left();
-
-            if let Some(v) = val {
-                return Ok(CodegenValue {
-                    value: v,
-                    ty: *return_type.clone(),
-                });
-            } else {
-                let dummy = ctx.context.bool_type().const_int(0, false).into();
-                return Ok(CodegenValue {
-                    value: dummy,
-                    ty: Type::Void,
-                });
-            }
+            crate::runtime::gen_trace_push(ctx, func_name, loc);
+            let result = super::closure::gen_closure_call(
+                ctx,
+                closure_ptr,
+                param_types,
+                return_type,
+                &arg_values,
+            );
+            crate::runtime::gen_trace_pop(ctx);
+            return result;
         }
     }
 
@@ -111,12 +124,32 @@ pub(super) fn gen_call<'ctx>(
         .ok_or_else(|| CodegenError::FunctionNotFound(func_name.clone()))?;
 
     // 调用函数
+    crate::runtime::gen_trace_push(ctx, func_name, loc);
     let call_site = ctx
         .builder
         .build_call(function, &arg_values, "calltmp")
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    crate::runtime::gen_trace_pop(ctx);
 
-    let val = call_site.try_as_basic_value().left();
+    let mut val = call_site.try_as_basic_value().left();
+
+    // FFI 边界：外部 C 函数返回 NUL 结尾的字符串，复制为带长度头的 Lency 字符串
+    // （lency_ 前缀的运行时函数返回的已经是 Lency 字符串）
+    let returns_string = match &return_type {
+        Type::Nullable(inner) => **inner == Type::String,
+        ty => *ty == Type::String,
+    };
+    if returns_string
+        && ctx.extern_functions.contains(func_name)
+        && !func_name.starts_with("lency_")
+    {
+        if let Some(v) = val {
+            val = Some(super::string::string_ops::gen_from_cstr(
+                ctx,
+                v.into_pointer_value(),
+            )?);
+        }
+    }
 
     if let Some(v) = val {
         Ok(CodegenValue {
@@ -1,11 +1,16 @@
 //! Closure Code Generation
 //!
-//! 闭包代码生成 - 将闭包提升为匿名顶层函数
+//! 闭包代码生成：
+//! - 闭包体提升为匿名顶层函数 `__closure_N(i8* env, params...)`
+//! - 按值捕获的变量在创建闭包时复制进堆上的环境结构体
+//! - 闭包值是指向 `{ i8* fn, i8* env }` 闭包对象的指针，可以像其他指针一样存入 Vec
 
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::CodegenValue;
-use crate::types::ToLLVMType;
+use crate::types::{closure_struct_type, ToLLVMType};
+use inkwell::types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType};
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, PointerValue};
 use inkwell::AddressSpace;
 use lency_syntax::ast::{Expr, Param, Type};
 use std::collections::HashMap;
@@ -14,59 +19,122 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 /// 全局闭包计数器，用于生成唯一函数名
 static CLOSURE_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
+/// 构造闭包提升函数的 LLVM 类型：第一个参数固定为环境指针
+pub(crate) fn closure_fn_type<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    param_types: &[Type],
+    return_type: &Type,
+) -> CodegenResult<FunctionType<'ctx>> {
+    let env_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+    let mut llvm_params: Vec<BasicMetadataTypeEnum> = vec![env_ptr_type.into()];
+    for ty in param_types {
+        llvm_params.push(ty.to_llvm_type(ctx)?.into());
+    }
+
+    if matches!(return_type, Type::Void) {
+        Ok(ctx.context.void_type().fn_type(&llvm_params, false))
+    } else {
+        Ok(return_type.to_llvm_type(ctx)?.fn_type(&llvm_params, false))
+    }
+}
+
 /// 生成闭包表达式
-/// 策略：将闭包提升为匿名顶层函数，返回函数指针
+/// 策略：闭包体提升为顶层函数，捕获变量打包进环境，返回闭包对象指针
 pub fn gen_closure<'ctx>(
     ctx: &CodegenContext<'ctx>,
-    _locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, Type)>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
     params: &[Param],
     body: &Expr,
+    captures: &[Param],
+    return_type: Option<&Type>,
 ) -> CodegenResult<CodegenValue<'ctx>> {
-    // 1. 生成唯一函数名
+    // 返回类型由 sema 推导并写回 AST
+    let return_type = return_type.cloned().ok_or_else(|| {
+        CodegenError::UnsupportedFeature("closure return type was not inferred".to_string())
+    })?;
+    let param_types: Vec<Type> = params.iter().map(|p| p.ty.clone()).collect();
+
+    // 1. 在外层函数中求值捕获变量（按值捕获）
+    let mut captured_values: Vec<BasicValueEnum<'ctx>> = Vec::with_capacity(captures.len());
+    for capture in captures {
+        let value = super::variable::gen_variable(ctx, locals, &capture.name)?;
+        captured_values.push(value.value);
+    }
+    let env_field_types: Vec<BasicTypeEnum<'ctx>> =
+        captured_values.iter().map(|v| v.get_type()).collect();
+    let env_type = ctx.context.struct_type(&env_field_types, false);
+
+    // 2. 生成唯一函数名并创建 LLVM 函数
     let closure_id = CLOSURE_COUNTER.fetch_add(1, Ordering::SeqCst);
     let fn_name = format!("__closure_{}", closure_id);
+    let fn_type = closure_fn_type(ctx, &param_types, &return_type)?;
+    let function = ctx
+        .module
+        .add_function(&fn_name, fn_type, ctx.helper_linkage());
 
-    // 2. 构建参数类型
-    let param_types: Result<Vec<_>, _> = params
-        .iter()
-        .map(|p| p.ty.to_llvm_type(ctx).map(|ty| ty.into()))
-        .collect();
-    let param_types = param_types?;
-
-    // 3. 推导返回类型 (从 body 表达式推导)
-    //    注意：此时 body 应该已经被类型检查过
-    //    这里我们需要从 body 生成代码来获取返回值类型
-    //    简化处理：先创建函数，再生成函数体
-
-    // 4. 创建 LLVM 函数 (先假设返回 i64，后续会被 phi 节点覆盖)
-    //    更好的方式是第一遍推导返回类型，但这里简化为 void 或 int
-    let ret_type = ctx.context.i64_type(); // 简化：假设闭包返回 int
-    let fn_type = ret_type.fn_type(&param_types, false);
-    let function = ctx.module.add_function(&fn_name, fn_type, None);
-
-    // 5. 创建函数入口块
+    // 3. 创建函数入口块（保存当前 builder 位置）
     let entry = ctx.context.append_basic_block(function, "entry");
-
-    // 保存当前 builder 位置
     let current_block = ctx.builder.get_insert_block();
-
     ctx.builder.position_at_end(entry);
+    if let Some(debug) = &ctx.debug {
+        debug.enter_function(
+            ctx,
+            function,
+            &fn_name,
+            &body.span,
+            &param_types,
+            &return_type,
+        );
+    }
 
-    // 6. 设置局部变量 (参数)
-    let mut closure_locals: HashMap<String, (inkwell::values::PointerValue<'ctx>, Type)> =
-        HashMap::new();
+    let mut closure_locals: HashMap<String, (PointerValue<'ctx>, Type)> = HashMap::new();
 
     // 注入返回类型
-    let null_ptr = ctx
-        .context
-        .i8_type()
-        .ptr_type(AddressSpace::default())
-        .const_null();
-    closure_locals.insert("__return_type".to_string(), (null_ptr, Type::Int));
+    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+    closure_locals.insert(
+        "__return_type".to_string(),
+        (i8_ptr_type.const_null(), return_type.clone()),
+    );
+
+    // 4. 从环境中恢复捕获变量
+    let env_param = function
+        .get_nth_param(0)
+        .ok_or_else(|| CodegenError::LLVMBuildError("missing closure env".to_string()))?
+        .into_pointer_value();
+    env_param.set_name("env");
+
+    if !captures.is_empty() {
+        let env_ptr = ctx
+            .builder
+            .build_pointer_cast(env_param, env_type.ptr_type(AddressSpace::default()), "env")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
+        for (i, (capture, field_ty)) in captures.iter().zip(&env_field_types).enumerate() {
+            let field_ptr = ctx
+                .builder
+                .build_struct_gep(env_type, env_ptr, i as u32, "capture_ptr")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            let value = ctx
+                .builder
+                .build_load(*field_ty, field_ptr, &capture.name)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+            let alloca = ctx
+                .builder
+                .build_alloca(*field_ty, &capture.name)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            ctx.builder
+                .build_store(alloca, value)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+            closure_locals.insert(capture.name.clone(), (alloca, capture.ty.clone()));
+        }
+    }
+
+    // 5. 设置参数（跳过环境指针）
     for (i, param) in params.iter().enumerate() {
         let param_value = function
-            .get_nth_param(i as u32)
+            .get_nth_param(i as u32 + 1)
             .ok_or_else(|| CodegenError::LLVMBuildError(format!("missing param {}", i)))?;
         param_value.set_name(&param.name);
 
@@ -83,27 +151,187 @@ pub fn gen_closure<'ctx>(
         closure_locals.insert(param.name.clone(), (alloca, param.ty.clone()));
     }
 
-    // 7. 生成闭包体 (作为单个表达式)
+    // 6. 生成闭包体 (作为单个表达式) 并返回
     let body_value = crate::expr::generate_expr(ctx, &closure_locals, body)?;
+    if matches!(return_type, Type::Void) {
+        ctx.builder
+            .build_return(None)
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    } else {
+        ctx.builder
+            .build_return(Some(&body_value.value))
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    }
 
-    // 8. 生成 return
-    ctx.builder
-        .build_return(Some(&body_value.value))
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-    // 9. 恢复 builder 位置
+    // 7. 恢复 builder 位置（以及外层函数的调试位置）
+    if let Some(debug) = &ctx.debug {
+        debug.exit_function(ctx);
+    }
     if let Some(block) = current_block {
         ctx.builder.position_at_end(block);
     }
 
-    // 10. 返回函数指针
-    let fn_ptr = function.as_global_value().as_pointer_value();
+    // 8. 在堆上构造环境（无捕获时为 null）
+    let env_raw = if captures.is_empty() {
+        i8_ptr_type.const_null()
+    } else {
+        let env_raw = build_malloc(ctx, env_type.size_of(), "closure_env")?;
+        let env_ptr = ctx
+            .builder
+            .build_pointer_cast(env_raw, env_type.ptr_type(AddressSpace::default()), "env")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        for (i, value) in captured_values.iter().enumerate() {
+            let field_ptr = ctx
+                .builder
+                .build_struct_gep(env_type, env_ptr, i as u32, "capture_ptr")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            ctx.builder
+                .build_store(field_ptr, *value)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        }
+        env_raw
+    };
+
+    // 9. 构造闭包对象 { fn, env }
+    let closure_type = closure_struct_type(ctx);
+    let closure_raw = build_malloc(ctx, closure_type.size_of(), "closure_obj")?;
+    let closure_ptr = ctx
+        .builder
+        .build_pointer_cast(
+            closure_raw,
+            closure_type.ptr_type(AddressSpace::default()),
+            "closure",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    let fn_ptr = ctx
+        .builder
+        .build_pointer_cast(
+            function.as_global_value().as_pointer_value(),
+            i8_ptr_type,
+            "closure_fn",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let fn_field = ctx
+        .builder
+        .build_struct_gep(closure_type, closure_ptr, 0, "closure_fn_ptr")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    ctx.builder
+        .build_store(fn_field, fn_ptr)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let env_field = ctx
+        .builder
+        .build_struct_gep(closure_type, closure_ptr, 1, "closure_env_ptr")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    ctx.builder
+        .build_store(env_field, env_raw)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
     Ok(CodegenValue {
-        value: fn_ptr.into(),
+        value: closure_ptr.into(),
         ty: Type::Function {
-            param_types: params.iter().map(|p| p.ty.clone()).collect(),
-            return_type: Box::new(body_value.ty),
+            param_types,
+            return_type: Box::new(return_type),
         },
     })
 }
+
+/// 调用闭包值：从闭包对象取出函数指针与环境，以环境作为首个参数间接调用
+pub(crate) fn gen_closure_call<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    closure: PointerValue<'ctx>,
+    param_types: &[Type],
+    return_type: &Type,
+    args: &[BasicMetadataValueEnum<'ctx>],
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let closure_type = closure_struct_type(ctx);
+    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+
+    // 从 Vec 等容器取出的闭包是 i8*，统一转换为闭包对象指针
+    let closure_ptr = ctx
+        .builder
+        .build_pointer_cast(
+            closure,
+            closure_type.ptr_type(AddressSpace::default()),
+            "closure",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    let fn_field = ctx
+        .builder
+        .build_struct_gep(closure_type, closure_ptr, 0, "closure_fn_ptr")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let fn_raw = ctx
+        .builder
+        .build_load(i8_ptr_type, fn_field, "closure_fn")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_pointer_value();
+    let env_field = ctx
+        .builder
+        .build_struct_gep(closure_type, closure_ptr, 1, "closure_env_ptr")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let env = ctx
+        .builder
+        .build_load(i8_ptr_type, env_field, "closure_env")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    let fn_type = closure_fn_type(ctx, param_types, return_type)?;
+    let fn_ptr = ctx
+        .builder
+        .build_pointer_cast(
+            fn_raw,
+            fn_type.ptr_type(AddressSpace::default()),
+            "closure_fn_typed",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    let mut call_args: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(args.len() + 1);
+    call_args.push(env.into());
+    call_args.extend_from_slice(args);
+
+    // 间接调用
+    let call_site = ctx
+        .builder
+        .build_indirect_call(fn_type, fn_ptr, &call_args, "closure_call")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    if let Some(v) = call_site.try_as_basic_value().left() {
+        Ok(CodegenValue {
+            value: v,
+            ty: return_type.clone(),
+        })
+    } else {
+        let dummy = ctx.context.bool_type().const_int(0, false).into();
+        Ok(CodegenValue {
+            value: dummy,
+            ty: Type::Void,
+        })
+    }
+}
+
+/// 调用 lency_gc_alloc 分配指定大小的堆内存（由垃圾回收器管理）
+pub(crate) fn build_malloc<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    size: Option<inkwell::values::IntValue<'ctx>>,
+    name: &str,
+) -> CodegenResult<PointerValue<'ctx>> {
+    let size = size
+        .ok_or_else(|| CodegenError::LLVMBuildError(format!("{} has no size (opaque?)", name)))?;
+    let malloc = ctx
+        .module
+        .get_function(crate::runtime::ALLOC_FN)
+        .ok_or_else(|| CodegenError::LLVMBuildError("malloc function not found".to_string()))?;
+
+    let malloc_call = ctx
+        .builder
+        .build_call(malloc, &[size.into()], name)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    Ok(malloc_call
+        .try_as_basic_value()
+        .left()
+        .ok_or(CodegenError::LLVMBuildError(
+            "malloc returned void".to_string(),
+        ))?
+        .into_pointer_value())
+}
@@ -21,12 +21,9 @@ pub fn get_or_declare_read_all<'ctx>(
         return func;
     }
 
+    // char* lency_file_read_all(LencyFile* handle)
     let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
-    let i64_type = ctx.context.i64_type();
-    let fn_type = i64_type.fn_type(
-        &[i8_ptr_type.into(), i8_ptr_type.into(), i64_type.into()],
-        false,
-    );
+    let fn_type = i8_ptr_type.fn_type(&[i8_ptr_type.into()], false);
     ctx.module
         .add_function("lency_file_read_all", fn_type, None)
 }
@@ -62,5 +59,5 @@ pub fn get_or_declare_close<'ctx>(
 pub fn get_or_declare_malloc<'ctx>(
     ctx: &CodegenContext<'ctx>,
 ) -> Option<inkwell::values::FunctionValue<'ctx>> {
-    ctx.module.get_function("malloc")
+    ctx.module.get_function(crate::runtime::ALLOC_FN)
 }
@@ -68,32 +68,20 @@ pub fn gen_read_file<'ctx>(
         .build_conditional_branch(is_null, error_block, success_block)
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
-    // 5. Success path: 分配缓冲区，读取文件
+    // 5. Success path: 读取全部内容为字符串（按字节保存，不在 NUL 处截断）
     ctx.builder.position_at_end(success_block);
 
-    let buffer_size = i64_type.const_int(8192, false);
-    let malloc_fn = ffi::get_or_declare_malloc(ctx)
-        .ok_or(CodegenError::LLVMBuildError("malloc not found".to_string()))?;
-    let buffer = ctx
+    let contents = ctx
         .builder
-        .build_call(malloc_fn, &[buffer_size.into()], "read_buffer")
+        .build_call(file_read_fn, &[file_handle.into()], "file_contents")
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
         .try_as_basic_value()
         .left()
         .ok_or(CodegenError::LLVMBuildError(
-            "malloc returned void".to_string(),
+            "lency_file_read_all returned void".to_string(),
         ))?
         .into_pointer_value();
 
-    let _bytes_read = ctx
-        .builder
-        .build_call(
-            file_read_fn,
-            &[file_handle.into(), buffer.into(), buffer_size.into()],
-            "bytes_read",
-        )
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
     ctx.builder
         .build_call(file_close_fn, &[file_handle.into()], "")
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
@@ -111,6 +99,8 @@ pub fn gen_read_file<'ctx>(
         .get(&mangled_name)
         .ok_or_else(|| CodegenError::UndefinedStructType(mangled_name.clone()))?;
 
+    let malloc_fn = ffi::get_or_declare_malloc(ctx)
+        .ok_or(CodegenError::LLVMBuildError("malloc not found".to_string()))?;
     let result_size = struct_type.size_of().ok_or(CodegenError::LLVMBuildError(
         "Failed to get Result size".to_string(),
     ))?;
@@ -144,7 +134,7 @@ pub fn gen_read_file<'ctx>(
         .build_struct_gep(struct_type, ok_result_ptr, 1, "str_ptr")
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
     ctx.builder
-        .build_store(str_ptr, buffer)
+        .build_store(str_ptr, contents)
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
     ctx.builder
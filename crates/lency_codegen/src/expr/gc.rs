@@ -0,0 +1,60 @@
+//! GC Builtins Code Generation
+//!
+//! 垃圾回收器内置函数：`gc_collect()` 立即回收，`gc_live_objects()` 等查询运行时统计，
+//! 均映射到 lency_runtime 中无参、返回 i64 的同名 `lency_*` 函数
+
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::CodegenValue;
+use lency_syntax::ast::{Expr, Type};
+
+/// Lency 内置函数名 -> 运行时函数名
+fn runtime_name(name: &str) -> Option<&'static str> {
+    match name {
+        "gc_collect" => Some("lency_gc_collect"),
+        "gc_live_objects" => Some("lency_gc_live_objects"),
+        "gc_live_bytes" => Some("lency_gc_live_bytes"),
+        "gc_collections" => Some("lency_gc_collections"),
+        _ => None,
+    }
+}
+
+/// Check if function name is a GC builtin
+pub fn is_gc_fn(name: &str) -> bool {
+    runtime_name(name).is_some()
+}
+
+/// Generate code for GC builtin calls
+pub fn gen_gc_call<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    func_name: &str,
+    args: &[Expr],
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let runtime_fn = runtime_name(func_name)
+        .ok_or_else(|| CodegenError::FunctionNotFound(func_name.to_string()))?;
+    if !args.is_empty() {
+        return Err(CodegenError::UnsupportedFeature(format!(
+            "{} expects 0 argument",
+            func_name
+        )));
+    }
+
+    let func = ctx.module.get_function(runtime_fn).unwrap_or_else(|| {
+        let fn_type = ctx.context.i64_type().fn_type(&[], false);
+        ctx.module.add_function(runtime_fn, fn_type, None)
+    });
+
+    let call = ctx
+        .builder
+        .build_call(func, &[], func_name)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let result = call
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError(format!("{} returned void", runtime_fn)))?;
+
+    Ok(CodegenValue {
+        value: result,
+        ty: Type::Int,
+    })
+}
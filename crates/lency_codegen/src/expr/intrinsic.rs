@@ -7,6 +7,7 @@ use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::vec::{cast_from_i64, get_or_declare_vec_get, get_or_declare_vec_len};
 use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
 use inkwell::AddressSpace;
 use lency_syntax::ast::{Expr, Type};
 use std::collections::HashMap;
@@ -64,6 +65,35 @@ fn gen_print_value_impl<'ctx>(
                 )
                 .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
         }
+        // 定宽整数扩展为 64 位后打印（无符号零扩展）
+        Type::FixedInt(kind) => {
+            let printf_fn = get_printf();
+            let (format, widened) = if kind.is_signed() {
+                let widened = ctx
+                    .builder
+                    .build_int_s_extend_or_bit_cast(value.into_int_value(), i64_type, "widen")
+                    .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+                ("%lld", widened)
+            } else {
+                let widened = ctx
+                    .builder
+                    .build_int_z_extend_or_bit_cast(value.into_int_value(), i64_type, "widen")
+                    .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+                ("%llu", widened)
+            };
+            let format_str = ctx
+                .builder
+                .build_global_string_ptr(format, "fixed_int_fmt")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+            ctx.builder
+                .build_call(
+                    printf_fn,
+                    &[format_str.as_pointer_value().into(), widened.into()],
+                    "print_fixed_int",
+                )
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        }
         Type::Float => {
             let printf_fn = get_printf();
             let format_str = ctx
@@ -114,21 +144,13 @@ fn gen_print_value_impl<'ctx>(
                 )
                 .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
         }
-        Type::String => {
-            let printf_fn = get_printf();
-            let format_str = ctx
-                .builder
-                .build_global_string_ptr("%s", "str_fmt")
-                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-            ctx.builder
-                .build_call(
-                    printf_fn,
-                    &[format_str.as_pointer_value().into(), value.into()],
-                    "print_str",
-                )
-                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        // char 先编码为 UTF-8 字符串再打印
+        Type::Char => {
+            let str_val =
+                super::string::chars::gen_char_to_string_value(ctx, value.into_int_value())?;
+            gen_print_string(ctx, str_val.into_pointer_value())?;
         }
+        Type::String => gen_print_string(ctx, value.into_pointer_value())?,
         Type::Vec(inner_type) => {
             // Print "["
             gen_print_str_literal(ctx, "[")?;
@@ -241,6 +263,28 @@ fn gen_print_value_impl<'ctx>(
     Ok(())
 }
 
+/// 按头部长度输出 Lency 字符串（内容可含 NUL，不能用 printf 的 %s）
+fn gen_print_string<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    string: inkwell::values::PointerValue<'ctx>,
+) -> CodegenResult<()> {
+    let print_fn = ctx
+        .module
+        .get_function("lency_print_string")
+        .unwrap_or_else(|| {
+            let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+            let fn_type = ctx
+                .context
+                .void_type()
+                .fn_type(&[i8_ptr_type.into()], false);
+            ctx.module.add_function("lency_print_string", fn_type, None)
+        });
+    ctx.builder
+        .build_call(print_fn, &[string.into()], "")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    Ok(())
+}
+
 fn gen_print_str_literal<'ctx>(ctx: &CodegenContext<'ctx>, s: &str) -> CodegenResult<()> {
     let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
     let i64_type = ctx.context.i64_type();
@@ -279,29 +323,12 @@ pub fn gen_panic<'ctx>(
     ctx: &CodegenContext<'ctx>,
     locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, lency_syntax::ast::Type)>,
     arg: &Expr,
+    loc: SourceLocation,
 ) -> CodegenResult<CodegenValue<'ctx>> {
     let arg_val = generate_expr(ctx, locals, arg)?;
-    let msg_ptr = arg_val.value.into_pointer_value();
 
-    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
-
-    // 声明 C runtime 函数: void lency_panic(char* message)
-    let panic_fn = ctx.module.get_function("lency_panic").unwrap_or_else(|| {
-        let fn_type = ctx
-            .context
-            .void_type()
-            .fn_type(&[i8_ptr_type.into()], false);
-        ctx.module.add_function("lency_panic", fn_type, None)
-    });
-
-    ctx.builder
-        .build_call(panic_fn, &[msg_ptr.into()], "panic_call")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-    // panic 永远不返回，添加 unreachable 指令
-    ctx.builder
-        .build_unreachable()
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    // lency_panic_at(msg, file, line, column)：运行时打印位置与调用栈，永不返回
+    crate::runtime::gen_panic_val(ctx, arg_val.value, loc);
 
     // 返回 void（实际上不会执行到）
     Ok(CodegenValue {
@@ -6,7 +6,7 @@ use inkwell::AddressSpace;
 use lency_syntax::ast::{Literal, Type};
 
 use crate::context::CodegenContext;
-use crate::error::{CodegenError, CodegenResult};
+use crate::error::CodegenResult;
 use crate::expr::CodegenValue;
 
 /// 生成字面量代码
@@ -36,16 +36,14 @@ pub(super) fn gen_literal<'ctx>(
                 ty: Type::Bool,
             })
         }
-        Literal::String(s) => {
-            let str_val = ctx
-                .builder
-                .build_global_string_ptr(s, "str")
-                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-            Ok(CodegenValue {
-                value: str_val.as_pointer_value().into(),
-                ty: Type::String,
-            })
-        }
+        Literal::Char(c) => Ok(CodegenValue {
+            value: ctx.context.i32_type().const_int(*c as u64, false).into(),
+            ty: Type::Char,
+        }),
+        Literal::String(s) => Ok(CodegenValue {
+            value: ctx.lency_string(s).into(),
+            ty: Type::String,
+        }),
         Literal::Null => {
             let ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
             Ok(CodegenValue {
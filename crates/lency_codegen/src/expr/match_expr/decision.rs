@@ -0,0 +1,514 @@
+//! Decision Tree Lowering
+//!
+//! 把 match 的各个 case 编译成决策树：每个 case 是矩阵的一行，每一列对应被匹配值的一个部分
+//! （整体、枚举字段、结构体字段……，统称 occurrence，以槽的形式存放）。
+//!
+//! - 第一行全是通配符：匹配成功，保存绑定、检查守卫；守卫失败时继续匹配剩余的行
+//! - 否则取第一行中第一个需要检查的列：
+//!   - 枚举变体：按 tag 生成一个 `switch`，每个出现过的变体一个分支，其余走通配符行
+//!   - 结构体：只有一个构造器，直接把该列展开为各字段列
+//!   - 字面量 / 区间：生成一次比较，成功与失败两侧各自保留仍可能匹配的行
+//!
+//! 或模式展开成多行，`name @ p` 与变量模式只记录绑定。同一个 case 的所有入口跳到同一个分支块，
+//! 绑定的值先复制到该分支专用的 alloca 中，分支体因此只生成一次。
+
+use std::collections::HashMap;
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::{FunctionValue, PointerValue};
+use lency_syntax::ast::{Expr, Literal, MatchPattern, Type};
+
+use super::pattern::{
+    entry_alloca, enum_layout, gen_literal_test, gen_range_test, load_slot, load_tag,
+    struct_field_slots, variant_field_slots,
+};
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::{generate_expr, CodegenValue};
+
+type Locals<'ctx> = HashMap<String, (PointerValue<'ctx>, Type)>;
+
+/// 一个待匹配的分支
+pub(crate) struct Arm<'a> {
+    pub pattern: &'a MatchPattern,
+    pub guard: Option<&'a Expr>,
+}
+
+/// 分支入口：匹配成功后跳到 `block`，`bindings` 为模式绑定的变量（alloca）
+pub(crate) struct ArmEntry<'ctx> {
+    pub block: BasicBlock<'ctx>,
+    pub bindings: Locals<'ctx>,
+}
+
+/// 被匹配值的一部分：存放它的槽与类型
+#[derive(Clone)]
+struct Occurrence<'ctx> {
+    slot: PointerValue<'ctx>,
+    ty: Type,
+}
+
+#[derive(Clone)]
+struct Row<'p, 'ctx> {
+    /// 与 occurrence 一一对应
+    columns: Vec<&'p MatchPattern>,
+    bindings: Vec<(&'p str, Occurrence<'ctx>)>,
+    arm: usize,
+}
+
+const WILDCARD: &MatchPattern = &MatchPattern::Wildcard;
+
+/// 生成匹配 `subject` 的决策树。没有任何分支匹配时跳到 `fail_bb`。
+/// 返回每个分支的入口；永远不会被选中的分支为 `None`。
+pub(crate) fn lower_arms<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    locals: &Locals<'ctx>,
+    subject: CodegenValue<'ctx>,
+    arms: &[Arm],
+    fail_bb: BasicBlock<'ctx>,
+) -> CodegenResult<Vec<Option<ArmEntry<'ctx>>>> {
+    let function = ctx
+        .builder
+        .get_insert_block()
+        .and_then(|bb| bb.get_parent())
+        .ok_or_else(|| CodegenError::LLVMBuildError("match outside function".to_string()))?;
+
+    // 被匹配的值放进槽里，与字段、变体载荷的处理方式一致
+    let slot = entry_alloca(ctx, &subject.ty, "match_subject_tmp")?;
+    ctx.builder
+        .build_store(slot, subject.value)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let subject = Occurrence {
+        slot,
+        ty: subject.ty,
+    };
+
+    let rows = arms
+        .iter()
+        .enumerate()
+        .map(|(arm, a)| Row {
+            columns: vec![a.pattern],
+            bindings: Vec::new(),
+            arm,
+        })
+        .collect();
+
+    let mut lowering = Lowering {
+        ctx,
+        locals,
+        arms,
+        function,
+        fail_bb,
+        entries: arms.iter().map(|_| None).collect(),
+    };
+    lowering.compile(rows, vec![subject])?;
+    Ok(lowering.entries)
+}
+
+struct Lowering<'a, 'ctx> {
+    ctx: &'a CodegenContext<'ctx>,
+    locals: &'a Locals<'ctx>,
+    arms: &'a [Arm<'a>],
+    function: FunctionValue<'ctx>,
+    fail_bb: BasicBlock<'ctx>,
+    entries: Vec<Option<ArmEntry<'ctx>>>,
+}
+
+impl<'a, 'ctx> Lowering<'a, 'ctx> {
+    /// 在当前块生成匹配 `rows` 的代码
+    fn compile<'p>(
+        &mut self,
+        rows: Vec<Row<'p, 'ctx>>,
+        occurrences: Vec<Occurrence<'ctx>>,
+    ) -> CodegenResult<()> {
+        let rows = normalize(rows, &occurrences);
+        let Some(first) = rows.first() else {
+            return self.branch(self.fail_bb);
+        };
+
+        let Some(column) = first
+            .columns
+            .iter()
+            .position(|p| !matches!(p, MatchPattern::Wildcard))
+        else {
+            return self.leaf(rows, occurrences);
+        };
+
+        match first.columns[column] {
+            MatchPattern::Variant { .. } => self.switch_variant(rows, occurrences, column),
+            MatchPattern::Struct { .. } => self.expand_struct(rows, occurrences, column),
+            pattern @ (MatchPattern::Literal(_) | MatchPattern::Range { .. }) => {
+                self.test_value(rows, occurrences, column, pattern)
+            }
+            // normalize 之后不再出现
+            MatchPattern::Wildcard
+            | MatchPattern::Variable(_)
+            | MatchPattern::Binding { .. }
+            | MatchPattern::Or(_) => Err(CodegenError::LLVMBuildError(
+                "unexpected pattern in decision tree".to_string(),
+            )),
+        }
+    }
+
+    /// 第一行已经匹配：保存绑定，检查守卫
+    fn leaf<'p>(
+        &mut self,
+        mut rows: Vec<Row<'p, 'ctx>>,
+        occurrences: Vec<Occurrence<'ctx>>,
+    ) -> CodegenResult<()> {
+        let row = rows.remove(0);
+        let arm_block = self.arm_block(row.arm);
+        for (name, occurrence) in &row.bindings {
+            let ptr = self.binding_slot(row.arm, name, &occurrence.ty)?;
+            let value = load_slot(self.ctx, occurrence.slot, &occurrence.ty, name)?;
+            self.ctx
+                .builder
+                .build_store(ptr, value)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        }
+
+        let Some(guard) = self.arms[row.arm].guard else {
+            return self.branch(arm_block);
+        };
+
+        let mut guard_locals = self.locals.clone();
+        if let Some(entry) = &self.entries[row.arm] {
+            guard_locals.extend(entry.bindings.clone());
+        }
+        let cond = generate_expr(self.ctx, &guard_locals, guard)?
+            .value
+            .into_int_value();
+        let next_bb = self
+            .ctx
+            .context
+            .append_basic_block(self.function, "guard_fail");
+        self.ctx
+            .builder
+            .build_conditional_branch(cond, arm_block, next_bb)
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        self.ctx.builder.position_at_end(next_bb);
+        self.compile(rows, occurrences)
+    }
+
+    /// 按 tag 分派到各变体
+    fn switch_variant<'p>(
+        &mut self,
+        rows: Vec<Row<'p, 'ctx>>,
+        occurrences: Vec<Occurrence<'ctx>>,
+        column: usize,
+    ) -> CodegenResult<()> {
+        let occurrence = occurrences[column].clone();
+        let layout = enum_layout(self.ctx, &occurrence.ty)?;
+        let enum_ptr =
+            load_slot(self.ctx, occurrence.slot, &occurrence.ty, "enum_ptr")?.into_pointer_value();
+        let tag = load_tag(self.ctx, &layout, enum_ptr)?;
+
+        // 按出现顺序收集该列检查的变体
+        let mut variants: Vec<&str> = Vec::new();
+        for row in &rows {
+            if let MatchPattern::Variant { name, .. } = row.columns[column] {
+                if !variants.contains(&name.as_str()) {
+                    variants.push(name);
+                }
+            }
+        }
+
+        let default_bb = self
+            .ctx
+            .context
+            .append_basic_block(self.function, "variant_default");
+        let mut cases = Vec::with_capacity(variants.len());
+        for name in &variants {
+            let index = layout
+                .variants
+                .iter()
+                .position(|(v, _)| v == name)
+                .ok_or(CodegenError::TypeMismatch)?;
+            let block = self
+                .ctx
+                .context
+                .append_basic_block(self.function, &format!("variant_{}", name));
+            cases.push((index, block));
+        }
+        let tag_type = self.ctx.context.i64_type();
+        let switch_cases: Vec<_> = cases
+            .iter()
+            .map(|(index, block)| (tag_type.const_int(*index as u64, false), *block))
+            .collect();
+        self.ctx
+            .builder
+            .build_switch(tag, default_bb, &switch_cases)
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+        for (index, block) in cases {
+            self.ctx.builder.position_at_end(block);
+            let (name, field_types) = &layout.variants[index];
+            let slots = variant_field_slots(self.ctx, &layout, enum_ptr, field_types)?;
+            let fields: Vec<Occurrence> = slots
+                .into_iter()
+                .zip(field_types)
+                .map(|(slot, ty)| Occurrence {
+                    slot,
+                    ty: ty.clone(),
+                })
+                .collect();
+
+            let specialized = rows
+                .iter()
+                .filter_map(|row| {
+                    let subs: Vec<&MatchPattern> = match row.columns[column] {
+                        MatchPattern::Variant {
+                            name: v,
+                            sub_patterns,
+                        } if v == name => sub_patterns.iter().collect(),
+                        MatchPattern::Variant { .. } => return None,
+                        _ => vec![WILDCARD; fields.len()],
+                    };
+                    Some(replace_column(row, column, subs))
+                })
+                .collect();
+            self.compile(specialized, splice(&occurrences, column, fields))?;
+        }
+
+        // 其余变体只可能匹配该列为通配符的行
+        self.ctx.builder.position_at_end(default_bb);
+        let default = rows
+            .iter()
+            .filter(|row| !matches!(row.columns[column], MatchPattern::Variant { .. }))
+            .map(|row| replace_column(row, column, Vec::new()))
+            .collect();
+        self.compile(default, splice(&occurrences, column, Vec::new()))
+    }
+
+    /// 结构体只有一个构造器：把该列展开为各字段
+    fn expand_struct<'p>(
+        &mut self,
+        rows: Vec<Row<'p, 'ctx>>,
+        occurrences: Vec<Occurrence<'ctx>>,
+        column: usize,
+    ) -> CodegenResult<()> {
+        let occurrence = &occurrences[column];
+        let struct_ptr = load_slot(self.ctx, occurrence.slot, &occurrence.ty, "struct_ptr")?
+            .into_pointer_value();
+        let slots = struct_field_slots(self.ctx, struct_ptr, &occurrence.ty)?;
+
+        let expanded = rows
+            .iter()
+            .map(|row| {
+                let subs = match row.columns[column] {
+                    // 未列出的字段视为通配符
+                    MatchPattern::Struct { fields, .. } => slots
+                        .iter()
+                        .map(|(field, _, _)| {
+                            fields
+                                .iter()
+                                .find(|(f, _)| f == field)
+                                .map_or(WILDCARD, |(_, p)| p)
+                        })
+                        .collect(),
+                    _ => vec![WILDCARD; slots.len()],
+                };
+                replace_column(row, column, subs)
+            })
+            .collect();
+        let fields = slots
+            .into_iter()
+            .map(|(_, slot, ty)| Occurrence { slot, ty })
+            .collect();
+        self.compile(expanded, splice(&occurrences, column, fields))
+    }
+
+    /// 字面量 / 区间：比较一次，两侧分别继续
+    fn test_value<'p>(
+        &mut self,
+        rows: Vec<Row<'p, 'ctx>>,
+        occurrences: Vec<Occurrence<'ctx>>,
+        column: usize,
+        pattern: &MatchPattern,
+    ) -> CodegenResult<()> {
+        let occurrence = &occurrences[column];
+        let cond = match pattern {
+            MatchPattern::Literal(lit) => {
+                gen_literal_test(self.ctx, occurrence.slot, &occurrence.ty, lit)?
+            }
+            MatchPattern::Range {
+                start,
+                end,
+                inclusive,
+            } => gen_range_test(
+                self.ctx,
+                occurrence.slot,
+                &occurrence.ty,
+                start,
+                end,
+                *inclusive,
+            )?,
+            _ => return Err(CodegenError::TypeMismatch),
+        };
+
+        let matched_bb = self
+            .ctx
+            .context
+            .append_basic_block(self.function, "pattern_match");
+        let unmatched_bb = self
+            .ctx
+            .context
+            .append_basic_block(self.function, "pattern_mismatch");
+        self.ctx
+            .builder
+            .build_conditional_branch(cond, matched_bb, unmatched_bb)
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+        // 成功：相同的模式已满足；不同的字面量不可能再匹配；其余（如重叠的区间）保留原样
+        let matched = rows
+            .iter()
+            .filter_map(|row| {
+                let other = row.columns[column];
+                if other == pattern {
+                    Some(replace_column(row, column, vec![WILDCARD]))
+                } else if disjoint_literals(other, pattern) {
+                    None
+                } else {
+                    Some(row.clone())
+                }
+            })
+            .collect();
+        // 失败：相同的模式不可能匹配
+        let unmatched = rows
+            .iter()
+            .filter(|row| row.columns[column] != pattern)
+            .cloned()
+            .collect();
+
+        self.ctx.builder.position_at_end(matched_bb);
+        self.compile(matched, occurrences.clone())?;
+        self.ctx.builder.position_at_end(unmatched_bb);
+        self.compile(unmatched, occurrences)
+    }
+
+    fn branch(&self, target: BasicBlock<'ctx>) -> CodegenResult<()> {
+        self.ctx
+            .builder
+            .build_unconditional_branch(target)
+            .map(|_| ())
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+    }
+
+    /// 分支块（第一次到达时创建）
+    fn arm_block(&mut self, arm: usize) -> BasicBlock<'ctx> {
+        let entry = self.entries[arm].get_or_insert_with(|| ArmEntry {
+            block: self
+                .ctx
+                .context
+                .append_basic_block(self.function, &format!("case_{}_body", arm)),
+            bindings: HashMap::new(),
+        });
+        entry.block
+    }
+
+    /// 分支中绑定变量的 alloca，或模式的各个分支共用
+    fn binding_slot(
+        &mut self,
+        arm: usize,
+        name: &str,
+        ty: &Type,
+    ) -> CodegenResult<PointerValue<'ctx>> {
+        let ctx = self.ctx;
+        let Some(entry) = self.entries[arm].as_mut() else {
+            return Err(CodegenError::LLVMBuildError(format!(
+                "case {} has no entry block",
+                arm
+            )));
+        };
+        if let Some((ptr, _)) = entry.bindings.get(name) {
+            return Ok(*ptr);
+        }
+        let ptr = entry_alloca(ctx, ty, name)?;
+        entry.bindings.insert(name.to_string(), (ptr, ty.clone()));
+        Ok(ptr)
+    }
+}
+
+/// 展开或模式，并把变量 / `@` 绑定移出模式
+fn normalize<'p, 'ctx>(
+    rows: Vec<Row<'p, 'ctx>>,
+    occurrences: &[Occurrence<'ctx>],
+) -> Vec<Row<'p, 'ctx>> {
+    let mut result = Vec::with_capacity(rows.len());
+    let mut pending = rows;
+    pending.reverse();
+    while let Some(mut row) = pending.pop() {
+        let mut alternatives = None;
+        for (i, column) in row.columns.iter_mut().enumerate() {
+            loop {
+                let pattern: &'p MatchPattern = column;
+                match pattern {
+                    MatchPattern::Variable(name) => {
+                        row.bindings.push((name, occurrences[i].clone()));
+                        *column = WILDCARD;
+                    }
+                    MatchPattern::Binding { name, pattern } => {
+                        row.bindings.push((name, occurrences[i].clone()));
+                        *column = pattern;
+                        continue;
+                    }
+                    MatchPattern::Or(alts) if alternatives.is_none() => {
+                        alternatives = Some((i, alts));
+                    }
+                    _ => {}
+                }
+                break;
+            }
+        }
+        match alternatives {
+            // 每个分支一行，按原顺序放回待处理队列
+            Some((i, alts)) => {
+                for alt in alts.iter().rev() {
+                    let mut expanded = row.clone();
+                    expanded.columns[i] = alt;
+                    pending.push(expanded);
+                }
+            }
+            None => result.push(row),
+        }
+    }
+    result
+}
+
+/// 两个不同的字面量模式不会同时匹配
+fn disjoint_literals(a: &MatchPattern, b: &MatchPattern) -> bool {
+    match (a, b) {
+        (MatchPattern::Literal(x), MatchPattern::Literal(y)) => x != y && !is_nan(x) && !is_nan(y),
+        _ => false,
+    }
+}
+
+fn is_nan(lit: &Literal) -> bool {
+    matches!(lit, Literal::Float(f) if f.is_nan())
+}
+
+/// 用 `subs` 替换第 `column` 列
+fn replace_column<'p, 'ctx>(
+    row: &Row<'p, 'ctx>,
+    column: usize,
+    subs: Vec<&'p MatchPattern>,
+) -> Row<'p, 'ctx> {
+    let mut columns = row.columns[..column].to_vec();
+    columns.extend(subs);
+    columns.extend_from_slice(&row.columns[column + 1..]);
+    Row {
+        columns,
+        bindings: row.bindings.clone(),
+        arm: row.arm,
+    }
+}
+
+fn splice<'ctx>(
+    occurrences: &[Occurrence<'ctx>],
+    column: usize,
+    fields: Vec<Occurrence<'ctx>>,
+) -> Vec<Occurrence<'ctx>> {
+    let mut result = occurrences[..column].to_vec();
+    result.extend(fields);
+    result.extend_from_slice(&occurrences[column + 1..]);
+    result
+}
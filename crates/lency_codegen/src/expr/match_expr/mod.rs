@@ -1,4 +1,5 @@
+mod decision;
 mod gen;
-mod pattern;
+pub mod pattern;
 
 pub use gen::gen_match;
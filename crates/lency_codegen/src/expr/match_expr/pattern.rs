@@ -1,364 +1,322 @@
+//! Pattern Tests
+//!
+//! 决策树用到的基本操作：字面量 / 区间比较、读取枚举 tag、取变体与结构体字段的地址。
+//! 被匹配的值总是通过“槽”传递：槽是存放该值的内存地址（与 locals 中的 alloca 相同），
+//! 聚合类型的槽里存的是结构体 / 枚举指针。
+
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
+use crate::expr::CodegenValue;
 use crate::types::ToLLVMType;
-use inkwell::values::PointerValue;
-use lency_syntax::ast::{MatchPattern, Type};
+use inkwell::basic_block::BasicBlock;
+use inkwell::types::StructType;
+use inkwell::values::{IntValue, PointerValue};
+use inkwell::{AddressSpace, IntPredicate};
+use lency_syntax::ast::{Literal, MatchPattern, Type};
 use std::collections::HashMap;
 
-/// Recursively generate pattern checks.
-/// If any check fails, branch to `mismatch_bb`.
-/// If successful, populates `bindings`.
-/// Control flow falls through on success.
-#[allow(clippy::only_used_in_recursion)]
+use super::decision::{lower_arms, Arm};
+
+/// 单个模式的匹配：失败跳到 `mismatch_bb`，成功时 builder 停在匹配成功的块，
+/// 绑定的变量写入 `bindings`（名称、alloca、类型）。
 pub(crate) fn gen_pattern_check<'ctx>(
     ctx: &CodegenContext<'ctx>,
-    locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, lency_syntax::ast::Type)>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
     pattern: &MatchPattern,
-    subject_ptr: PointerValue<'ctx>,
-    subject_type: &Type,
+    subject: CodegenValue<'ctx>,
     bindings: &mut Vec<(String, PointerValue<'ctx>, Type)>,
-    mismatch_bb: inkwell::basic_block::BasicBlock<'ctx>,
+    mismatch_bb: BasicBlock<'ctx>,
 ) -> CodegenResult<()> {
-    match pattern {
-        MatchPattern::Wildcard => {
-            // Always matches.
-            Ok(())
-        }
-        MatchPattern::Variable(name) => {
-            // Always matches, binds variable.
-            // We just store the POINTER to the subject.
-            // When user uses variable, they load from this pointer.
-            bindings.push((name.clone(), subject_ptr, subject_type.clone()));
-            Ok(())
-        }
-        MatchPattern::Literal(lit) => {
-            // String Matching
-            if matches!(subject_type, Type::String) {
-                if let lency_syntax::ast::Literal::String(s) = lit {
-                    // 1. Load Subject (i8**) -> i8*
-                    let str_ptr = ctx
-                        .builder
-                        .build_load(
-                            ctx.context
-                                .i8_type()
-                                .ptr_type(inkwell::AddressSpace::default()),
-                            subject_ptr,
-                            "str_load",
-                        )
-                        .unwrap()
-                        .into_pointer_value();
-
-                    // 2. Global String Literal
-                    let lit_ptr = ctx
-                        .builder
-                        .build_global_string_ptr(s, "str_lit")
-                        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
-                        .as_pointer_value();
-
-                    // 3. Strcmp
-                    let strcmp_fn = ctx.module.get_function("strcmp").unwrap_or_else(|| {
-                        let i32_type = ctx.context.i32_type();
-                        let i8_ptr_type = ctx
-                            .context
-                            .i8_type()
-                            .ptr_type(inkwell::AddressSpace::default());
-                        let fn_type =
-                            i32_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false);
-                        ctx.module.add_function(
-                            "strcmp",
-                            fn_type,
-                            Some(inkwell::module::Linkage::External),
-                        )
-                    });
-
-                    let call = ctx
-                        .builder
-                        .build_call(
-                            strcmp_fn,
-                            &[str_ptr.into(), lit_ptr.into()],
-                            "strcmp_result",
-                        )
-                        .unwrap();
-
-                    let strcmp_result = call.try_as_basic_value().left().unwrap().into_int_value();
-                    let zero = ctx.context.i32_type().const_int(0, false);
-                    let cmp = ctx
-                        .builder
-                        .build_int_compare(
-                            inkwell::IntPredicate::EQ,
-                            strcmp_result,
-                            zero,
-                            "streqtmp",
-                        )
-                        .unwrap();
-
-                    let success_bb = ctx.context.append_basic_block(
-                        ctx.builder
-                            .get_insert_block()
-                            .unwrap()
-                            .get_parent()
-                            .unwrap(),
-                        "str_match_success",
-                    );
-
-                    ctx.builder
-                        .build_conditional_branch(cmp, success_bb, mismatch_bb)
-                        .unwrap();
-                    ctx.builder.position_at_end(success_bb);
-                    return Ok(());
-                } else {
-                    return Err(CodegenError::TypeMismatch);
-                }
-            }
-
-            // Primitive Matching (Int/Bool)
-            // Load value from pointer (unless subject is already loaded? No we standardized on ptr).
-            // For primitive types (Int/Float/Bool), we load and compare.
-            // String? -> Handled above.
-
-            // 1. Load Subject
-            // (Assuming int for now based on previous impl, but need to support others)
-
-            let load_val = match subject_type {
-                Type::Int | Type::Bool => ctx
-                    .builder
-                    .build_load(ctx.context.i64_type(), subject_ptr, "lit_chk_load")
-                    .unwrap()
-                    .into_int_value(),
-                Type::Float => {
-                    // Float equality is tricky?
-                    // Use ordered equal `oeq`
-                    let _fval = ctx
-                        .builder
-                        .build_load(ctx.context.f64_type(), subject_ptr, "lit_chk_fload")
-                        .unwrap()
-                        .into_float_value();
-                    // We need to compare with literal.
-                    // ...
-                    return Err(CodegenError::UnsupportedFeature("Float matching".into()));
-                }
-                Type::String => unreachable!("String handled above"),
-                _ => return Err(CodegenError::TypeMismatch),
-            };
-
-            let lit_val = match lit {
-                lency_syntax::ast::Literal::Int(v) => {
-                    ctx.context.i64_type().const_int(*v as u64, true)
-                }
-                lency_syntax::ast::Literal::Bool(b) => {
-                    ctx.context.bool_type().const_int(*b as u64, false)
-                }
-                _ => {
-                    return Err(CodegenError::UnsupportedFeature(
-                        "Unsupported literal in match".into(),
-                    ))
-                }
-            };
-
-            // Note: Bool load would result in i64 or i1?
-            // build_load type needs to match ptr type.
-            // If Int, i64. If Bool, i1.
-            // Adjust logic above.
+    let arm = Arm {
+        pattern,
+        guard: None,
+    };
+    let entry = lower_arms(ctx, locals, subject, &[arm], mismatch_bb)?
+        .pop()
+        .flatten()
+        .ok_or_else(|| CodegenError::LLVMBuildError("pattern can never match".to_string()))?;
+    ctx.builder.position_at_end(entry.block);
+    bindings.extend(
+        entry
+            .bindings
+            .into_iter()
+            .map(|(name, (ptr, ty))| (name, ptr, ty)),
+    );
+    Ok(())
+}
 
-            let cmp = if *subject_type == Type::Bool {
-                let bload = ctx
-                    .builder
-                    .build_load(ctx.context.bool_type(), subject_ptr, "b_load")
-                    .unwrap()
-                    .into_int_value();
-                ctx.builder
-                    .build_int_compare(inkwell::IntPredicate::EQ, bload, lit_val, "lit_eq")
-                    .unwrap()
-            } else {
-                // Int
-                ctx.builder
-                    .build_int_compare(inkwell::IntPredicate::EQ, load_val, lit_val, "lit_eq")
-                    .unwrap()
-            };
+/// 在函数入口块分配局部变量（循环中的 match 不会反复增长栈）
+pub(super) fn entry_alloca<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    ty: &Type,
+    name: &str,
+) -> CodegenResult<PointerValue<'ctx>> {
+    let llvm_type = ty.to_llvm_type(ctx)?;
+    let entry = ctx
+        .builder
+        .get_insert_block()
+        .and_then(|bb| bb.get_parent())
+        .and_then(|f| f.get_first_basic_block())
+        .ok_or_else(|| CodegenError::LLVMBuildError("match outside function".to_string()))?;
+    let builder = ctx.context.create_builder();
+    match entry.get_first_instruction() {
+        Some(first_instr) => builder.position_before(&first_instr),
+        None => builder.position_at_end(entry),
+    }
+    builder
+        .build_alloca(llvm_type, name)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
 
-            let success_bb = ctx.context.append_basic_block(
-                ctx.builder
-                    .get_insert_block()
-                    .unwrap()
-                    .get_parent()
-                    .unwrap(),
-                "lit_match_success",
-            );
+/// 读取槽中的值
+pub(super) fn load_slot<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    slot: PointerValue<'ctx>,
+    ty: &Type,
+    name: &str,
+) -> CodegenResult<inkwell::values::BasicValueEnum<'ctx>> {
+    ctx.builder
+        .build_load(ty.to_llvm_type(ctx)?, slot, name)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
 
+/// 字面量模式的比较结果（i1）
+pub(super) fn gen_literal_test<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    slot: PointerValue<'ctx>,
+    ty: &Type,
+    lit: &Literal,
+) -> CodegenResult<IntValue<'ctx>> {
+    let value = load_slot(ctx, slot, ty, "lit_chk_load")?;
+    match (ty, lit) {
+        (Type::String, Literal::String(s)) => {
+            // 字符串字面量（带长度头的常量），按内容比较
+            let lit_ptr = ctx.lency_string(s);
+            crate::expr::string::string_ops::gen_string_eq(ctx, value.into_pointer_value(), lit_ptr)
+        }
+        (Type::Float, Literal::Float(f)) => {
+            let lit_val = ctx.context.f64_type().const_float(*f);
             ctx.builder
-                .build_conditional_branch(cmp, success_bb, mismatch_bb)
-                .unwrap();
-            ctx.builder.position_at_end(success_bb);
-            Ok(())
+                .build_float_compare(
+                    inkwell::FloatPredicate::OEQ,
+                    value.into_float_value(),
+                    lit_val,
+                    "lit_eq",
+                )
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
         }
-        MatchPattern::Variant {
-            name: variant_name,
-            sub_patterns,
-        } => {
-            // Enum Matching.
-            // subject_ptr points to { i8, [size x i8] }
-
-            // 1. Check Tag
-            // We need to know the tag index for `variant_name`.
-            // The Enum Name is in `subject_type`.
-            let enum_name = match subject_type {
-                Type::Struct(n) => n,
-                Type::Generic(n, _) => n, // Generic Enum
-                // Sprint 15: Treat Result<T, E> as enum "Result"
-                Type::Result { .. } => "Result",
-                _ => return Err(CodegenError::TypeMismatch),
-            };
-
-            // Look up variants info to find index
-            // Sprint 15: Special handling for Result<T,E>
-            let variants_info = if enum_name == "Result" {
-                // Result.Ok and Result.Err are compiler built-ins
-                // Ok has index 0 with one field of type T (GenericParam)
-                // Err has index 1 with one field of type E (GenericParam)
-                // We need to provide the variant info dynamically
-                vec![
-                    ("Ok".to_string(), vec![Type::GenericParam("T".to_string())]),
-                    ("Err".to_string(), vec![Type::GenericParam("E".to_string())]),
-                ]
-            } else {
-                ctx.enum_variants
-                    .get(enum_name)
-                    .ok_or(CodegenError::UndefinedStructType(enum_name.to_string()))?
-                    .clone()
-            };
-
-            let (tag_idx, (_, field_types_ast)) = variants_info
-                .iter()
-                .enumerate()
-                .find(|(_, (n, _))| n == variant_name)
-                .ok_or(CodegenError::TypeMismatch)?; // Variant not found?
+        (Type::Int | Type::FixedInt(_) | Type::Char | Type::Bool, _) => {
+            let value = value.into_int_value();
+            // 与被匹配值同宽（定宽整数的字面量已由 sema 检查范围）
+            let lit_val = value.get_type().const_int(literal_bits(lit)?, true);
+            ctx.builder
+                .build_int_compare(IntPredicate::EQ, value, lit_val, "lit_eq")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+        }
+        _ => Err(CodegenError::UnsupportedFeature(
+            "Unsupported literal in match".into(),
+        )),
+    }
+}
 
-            // GEP Tag (element 0)
-            // Sprint 15: Result struct type special handling
-            let enum_struct_type = if enum_name == "Result" {
-                // Result enum type is { i64 (tag), [max_size x i8] (payload) }
-                // We need to ensure it exists in ctx.struct_types
-                if let Some(st) = ctx.struct_types.get(enum_name) {
-                    *st
-                } else {
-                    // Create Result struct type dynamically
-                    // tag: i64, payload: arbitrary size array (use i64 for simplicity)
-                    ctx.context.struct_type(
-                        &[
-                            ctx.context.i64_type().into(), // tag
-                            ctx.context.i64_type().into(), // payload (simplified)
-                        ],
-                        false,
-                    )
-                }
-            } else {
-                *ctx.struct_types.get(enum_name).unwrap()
-            };
-            let tag_ptr = ctx
-                .builder
-                .build_struct_gep(enum_struct_type, subject_ptr, 0, "tag_ptr")
-                .unwrap();
+/// 区间模式 `lo..hi` / `lo..=hi` 的比较结果（i1）
+pub(super) fn gen_range_test<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    slot: PointerValue<'ctx>,
+    ty: &Type,
+    start: &Literal,
+    end: &Literal,
+    inclusive: bool,
+) -> CodegenResult<IntValue<'ctx>> {
+    let value = load_slot(ctx, slot, ty, "range_chk_load")?.into_int_value();
+    let int_type = value.get_type();
+    let signed = match ty {
+        Type::Int => true,
+        Type::FixedInt(kind) => kind.is_signed(),
+        _ => false, // char
+    };
+    let (ge, le, lt) = if signed {
+        (IntPredicate::SGE, IntPredicate::SLE, IntPredicate::SLT)
+    } else {
+        (IntPredicate::UGE, IntPredicate::ULE, IntPredicate::ULT)
+    };
+    let lo = int_type.const_int(literal_bits(start)?, true);
+    let hi = int_type.const_int(literal_bits(end)?, true);
+    let above = ctx
+        .builder
+        .build_int_compare(ge, value, lo, "range_lo")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let below = ctx
+        .builder
+        .build_int_compare(if inclusive { le } else { lt }, value, hi, "range_hi")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    ctx.builder
+        .build_and(above, below, "in_range")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
 
-            let tag_val = ctx
-                .builder
-                .build_load(ctx.context.i64_type(), tag_ptr, "tag_val")
-                .unwrap()
-                .into_int_value();
-            let expected_tag = ctx.context.i64_type().const_int(tag_idx as u64, false);
+/// 整数类字面量的位模式
+fn literal_bits(lit: &Literal) -> CodegenResult<u64> {
+    match lit {
+        Literal::Int(v) => Ok(*v as u64),
+        Literal::Bool(b) => Ok(*b as u64),
+        Literal::Char(c) => Ok(*c as u64),
+        _ => Err(CodegenError::UnsupportedFeature(
+            "Unsupported literal in match".into(),
+        )),
+    }
+}
 
-            let tag_cmp = ctx
-                .builder
-                .build_int_compare(inkwell::IntPredicate::EQ, tag_val, expected_tag, "tag_eq")
-                .unwrap();
+/// 枚举的 LLVM 布局与各变体（名称、字段类型）
+pub(super) struct EnumLayout<'ctx> {
+    pub struct_type: StructType<'ctx>,
+    pub variants: Vec<(String, Vec<Type>)>,
+}
 
-            let tag_success_bb = ctx.context.append_basic_block(
-                ctx.builder
-                    .get_insert_block()
-                    .unwrap()
-                    .get_parent()
-                    .unwrap(),
-                "tag_match_success",
-            );
-            ctx.builder
-                .build_conditional_branch(tag_cmp, tag_success_bb, mismatch_bb)
-                .unwrap();
-            ctx.builder.position_at_end(tag_success_bb);
+pub(super) fn enum_layout<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    ty: &Type,
+) -> CodegenResult<EnumLayout<'ctx>> {
+    let enum_name = match ty {
+        Type::Struct(n) => n.as_str(),
+        Type::Generic(n, _) => n.as_str(), // Generic Enum
+        // Sprint 15: Treat Result<T, E> as enum "Result"
+        Type::Result { .. } => "Result",
+        _ => return Err(CodegenError::TypeMismatch),
+    };
 
-            // 2. Destructure Payload for Sub-patterns
-            if !sub_patterns.is_empty() {
-                // Bitcast payload (element 1) to variant struct layout
-                let payload_arr_ptr = ctx
-                    .builder
-                    .build_struct_gep(enum_struct_type, subject_ptr, 1, "payload_arr")
-                    .unwrap();
+    if let Type::Result { ok_type, err_type } = ty {
+        // Sprint 15: Result.Ok / Result.Err are compiler built-ins
+        // Ok has index 0 with one field of type T, Err has index 1 with one field of type E
+        let struct_type = ctx.struct_types.get(enum_name).copied().unwrap_or_else(|| {
+            // Result enum type is { i64 (tag), payload }
+            ctx.context.struct_type(
+                &[
+                    ctx.context.i64_type().into(), // tag
+                    ctx.context.i64_type().into(), // payload (simplified)
+                ],
+                false,
+            )
+        });
+        return Ok(EnumLayout {
+            struct_type,
+            variants: vec![
+                ("Ok".to_string(), vec![(**ok_type).clone()]),
+                ("Err".to_string(), vec![(**err_type).clone()]),
+            ],
+        });
+    }
 
-                // Construct Variant Body Type { field1, field2... }
-                // We need LLVM types for fields.
+    let variants = ctx
+        .enum_variants
+        .get(enum_name)
+        .ok_or(CodegenError::UndefinedStructType(enum_name.to_string()))?
+        .clone();
+    let struct_type = *ctx
+        .struct_types
+        .get(enum_name)
+        .ok_or(CodegenError::UndefinedStructType(enum_name.to_string()))?;
+    Ok(EnumLayout {
+        struct_type,
+        variants,
+    })
+}
 
-                // Sprint 15: For Result<T,E>, substitute GenericParam with concrete types
-                let field_types_concrete = if enum_name == "Result" && !field_types_ast.is_empty() {
-                    // Extract concrete types from subject_type (Result<ok_type, err_type>)
-                    match subject_type {
-                        Type::Result { ok_type, err_type } => {
-                            // Substitute T -> ok_type, E -> err_type
-                            field_types_ast
-                                .iter()
-                                .map(|ty| match ty {
-                                    Type::GenericParam(name) if name == "T" => (**ok_type).clone(),
-                                    Type::GenericParam(name) if name == "E" => (**err_type).clone(),
-                                    _ => ty.clone(),
-                                })
-                                .collect()
-                        }
-                        _ => field_types_ast.clone(),
-                    }
-                } else {
-                    field_types_ast.clone()
-                };
+/// 读取枚举的 tag（变体下标）
+pub(super) fn load_tag<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    layout: &EnumLayout<'ctx>,
+    enum_ptr: PointerValue<'ctx>,
+) -> CodegenResult<IntValue<'ctx>> {
+    let tag_ptr = ctx
+        .builder
+        .build_struct_gep(layout.struct_type, enum_ptr, 0, "tag_ptr")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    Ok(ctx
+        .builder
+        .build_load(ctx.context.i64_type(), tag_ptr, "tag_val")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_int_value())
+}
 
-                let mut variant_llvm_types = Vec::new();
-                for ty in &field_types_concrete {
-                    variant_llvm_types.push(ty.to_llvm_type(ctx)?);
-                }
-                let variant_struct_type = ctx.context.struct_type(&variant_llvm_types, false);
+/// 变体各字段的槽：payload（element 1）按变体字段布局 `{ field1, field2, ... }` 解释
+pub(super) fn variant_field_slots<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    layout: &EnumLayout<'ctx>,
+    enum_ptr: PointerValue<'ctx>,
+    field_types: &[Type],
+) -> CodegenResult<Vec<PointerValue<'ctx>>> {
+    if field_types.is_empty() {
+        return Ok(Vec::new());
+    }
+    let payload_arr_ptr = ctx
+        .builder
+        .build_struct_gep(layout.struct_type, enum_ptr, 1, "payload_arr")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
-                let payload_typed_ptr = ctx
-                    .builder
-                    .build_bitcast(
-                        payload_arr_ptr,
-                        variant_struct_type.ptr_type(inkwell::AddressSpace::default()),
-                        "payload_typed",
-                    )
-                    .unwrap()
-                    .into_pointer_value();
+    let mut variant_llvm_types = Vec::new();
+    for ty in field_types {
+        variant_llvm_types.push(ty.to_llvm_type(ctx)?);
+    }
+    let variant_struct_type = ctx.context.struct_type(&variant_llvm_types, false);
+    let payload_typed_ptr = ctx
+        .builder
+        .build_bitcast(
+            payload_arr_ptr,
+            variant_struct_type.ptr_type(AddressSpace::default()),
+            "payload_typed",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_pointer_value();
 
-                // Recurse for each field
-                for (i, sub_pat) in sub_patterns.iter().enumerate() {
-                    // GEP Field i
-                    let field_ptr = ctx
-                        .builder
-                        .build_struct_gep(
-                            variant_struct_type,
-                            payload_typed_ptr,
-                            i as u32,
-                            "field_ptr",
-                        )
-                        .unwrap();
-                    let field_type = &field_types_concrete[i]; // Use concrete types with GenericParam replaced
+    (0..field_types.len())
+        .map(|i| {
+            ctx.builder
+                .build_struct_gep(
+                    variant_struct_type,
+                    payload_typed_ptr,
+                    i as u32,
+                    "field_ptr",
+                )
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+        })
+        .collect()
+}
 
-                    gen_pattern_check(
-                        ctx,
-                        locals,
-                        sub_pat,
-                        field_ptr,
-                        field_type,
-                        bindings,
-                        mismatch_bb,
-                    )?;
-                }
-            }
+/// 结构体各字段的槽（名称、地址、类型），按声明顺序
+pub(super) fn struct_field_slots<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    struct_ptr: PointerValue<'ctx>,
+    ty: &Type,
+) -> CodegenResult<Vec<(String, PointerValue<'ctx>, Type)>> {
+    let Type::Struct(name) = ty else {
+        return Err(CodegenError::TypeMismatch);
+    };
+    let struct_type = *ctx
+        .struct_types
+        .get(name)
+        .ok_or_else(|| CodegenError::UndefinedStructType(name.clone()))?;
+    let names = ctx
+        .struct_fields
+        .get(name)
+        .ok_or_else(|| CodegenError::UndefinedStructType(name.clone()))?;
+    let types = ctx
+        .struct_field_types
+        .get(name)
+        .ok_or_else(|| CodegenError::UndefinedStructType(name.clone()))?;
 
-            Ok(())
-        }
+    let mut slots = Vec::with_capacity(names.len());
+    for (index, (field, field_ty)) in names.iter().zip(types).enumerate() {
+        let ptr = ctx
+            .builder
+            .build_struct_gep(
+                struct_type,
+                struct_ptr,
+                index as u32,
+                &format!("{}_ptr", field),
+            )
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        slots.push((field.clone(), ptr, field_ty.clone()));
     }
+    Ok(slots)
 }
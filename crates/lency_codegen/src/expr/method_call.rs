@@ -5,6 +5,7 @@
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
 use inkwell::values::PointerValue;
 use lency_syntax::ast::{Expr, Type};
 use std::collections::HashMap;
@@ -16,7 +17,7 @@ pub fn gen_method_call<'ctx>(
     object: &Expr,
     method_name: &str,
     args: &[Expr],
-    line: u32,
+    loc: SourceLocation,
 ) -> CodegenResult<CodegenValue<'ctx>> {
     // 0. Check for Enum Constructor Call (Enum.Variant(args))
     let enum_check = match &object.kind {
@@ -79,6 +80,22 @@ pub fn gen_method_call<'ctx>(
     // Clone type to avoid borrowing object_val, so we can move it
     let object_type = object_val.ty.clone();
 
+    // 整数的 wrapping_* / checked_* 内置方法
+    if crate::expr::numeric::integer::is_integer_method(&object_type, method_name) {
+        return crate::expr::numeric::integer::gen_integer_method(
+            ctx,
+            locals,
+            object_val,
+            method_name,
+            args,
+        );
+    }
+
+    // 字符串的 chars() / bytes() 视图
+    if crate::expr::string::chars::is_string_method(&object_type, method_name) {
+        return crate::expr::string::chars::gen_string_method(ctx, object_val, method_name);
+    }
+
     match object_type {
         Type::Vec(inner) => crate::expr::vec::gen_vec_method_call(
             ctx,
@@ -87,6 +104,7 @@ pub fn gen_method_call<'ctx>(
             method_name,
             args,
             &inner,
+            loc,
         ),
         // Primitive types: int, string, bool
         Type::Int | Type::String | Type::Bool => {
@@ -128,10 +146,12 @@ pub fn gen_method_call<'ctx>(
                 .ok_or_else(|| CodegenError::FunctionNotFound(mangled_name.clone()))?;
 
             // 生成调用
+            crate::runtime::gen_trace_push(ctx, &format!("{}.{}", type_name, method_name), loc);
             let call_site = ctx
                 .builder
                 .build_call(function, &compiled_args, "call_method")
                 .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            crate::runtime::gen_trace_pop(ctx);
 
             // 处理返回值
             let val = call_site.try_as_basic_value().left();
@@ -150,6 +170,16 @@ pub fn gen_method_call<'ctx>(
                 })
             }
         }
+        // dyn Trait: 经 vtable 动态分派
+        Type::TraitObject(trait_name) => crate::expr::trait_object::gen_dyn_method_call(
+            ctx,
+            locals,
+            object_val.value.into_pointer_value(),
+            &trait_name,
+            method_name,
+            args,
+            loc,
+        ),
         Type::Struct(name) => {
             // 获取 this 指针
             let this_ptr = if object_val.value.is_pointer_value() {
@@ -170,15 +200,7 @@ pub fn gen_method_call<'ctx>(
             let struct_name = name.clone();
 
             // 运行时 Null 检查
-            if let Some(panic_func) = ctx.panic_func {
-                crate::runtime::gen_null_check(
-                    ctx.context,
-                    &ctx.builder,
-                    panic_func,
-                    this_ptr,
-                    line,
-                );
-            }
+            crate::runtime::gen_null_check(ctx, this_ptr, loc);
 
             // Sprint 15: Option 内置方法支持
             if struct_name.starts_with("Option__") {
@@ -190,6 +212,7 @@ pub fn gen_method_call<'ctx>(
                     args,
                     &struct_name,
                     &[], // Generic args not preserved in Codegen Type::Struct, passing empty
+                    loc,
                 )? {
                     return Ok(res);
                 }
@@ -235,7 +258,7 @@ pub fn gen_method_call<'ctx>(
                     args,
                     &ok_type,
                     &err_type,
-                    line,
+                    loc,
                 )? {
                     return Ok(res);
                 }
@@ -270,10 +293,12 @@ pub fn gen_method_call<'ctx>(
                 .ok_or_else(|| CodegenError::FunctionNotFound(mangled_name.clone()))?;
 
             // 生成调用
+            crate::runtime::gen_trace_push(ctx, &format!("{}.{}", struct_name, method_name), loc);
             let call_site = ctx
                 .builder
                 .build_call(function, &compiled_args, "call_method")
                 .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            crate::runtime::gen_trace_pop(ctx);
 
             // 处理返回值
             let val = call_site.try_as_basic_value().left();
@@ -310,7 +335,7 @@ pub fn gen_method_call<'ctx>(
                 args,
                 &ok_type,
                 &err_type,
-                line,
+                loc,
             )? {
                 return Ok(result);
             }
@@ -349,10 +374,16 @@ pub fn gen_method_call<'ctx>(
                 .ok_or_else(|| CodegenError::FunctionNotFound(mangled_name.clone()))?;
 
             // 生成调用
+            crate::runtime::gen_trace_push(
+                ctx,
+                &format!("{}.{}", result_type_mangled, method_name),
+                loc,
+            );
             let call_site = ctx
                 .builder
                 .build_call(function, &compiled_args, "call_result_method")
                 .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            crate::runtime::gen_trace_pop(ctx);
 
             // 处理返回值
             let val = call_site.try_as_basic_value().left();
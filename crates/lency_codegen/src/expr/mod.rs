@@ -8,19 +8,22 @@ mod call;
 mod closure;
 pub mod conversion;
 mod file_io;
+mod gc;
 pub mod hashmap;
 mod intrinsic;
 mod literal;
-mod match_expr;
+pub mod match_expr;
 mod method_call;
+pub mod numeric;
 pub mod option;
 mod result;
-mod string_ops;
+pub mod string;
 mod struct_access;
 mod struct_init;
+mod trait_object;
 mod unary;
 mod variable;
-mod vec;
+pub mod vec;
 
 use inkwell::values::{BasicValueEnum, PointerValue};
 use lency_syntax::ast::{Expr, ExprKind};
@@ -28,6 +31,8 @@ use std::collections::HashMap;
 
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
+use numeric::{integer, range};
+use string::string_ops;
 
 pub struct CodegenValue<'ctx> {
     pub value: BasicValueEnum<'ctx>,
@@ -78,15 +83,21 @@ fn generate_expr<'ctx>(
             ty: lency_syntax::ast::Type::Void,
         }),
         ExprKind::Variable(name) => variable::gen_variable(ctx, locals, name),
-        ExprKind::Binary(left, op, right) => binary::gen_binary(ctx, locals, left, op, right),
-        ExprKind::Unary(op, operand) => unary::gen_unary(ctx, locals, op, operand),
+        ExprKind::Binary(left, op, right) => {
+            let loc = ctx.location(&expr.span);
+            binary::gen_binary(ctx, locals, left, op, right, loc)
+        }
+        ExprKind::Unary(op, operand) => {
+            let loc = ctx.location(&expr.span);
+            unary::gen_unary(ctx, locals, op, operand, loc)
+        }
         ExprKind::Call { callee, args } => {
             // 检查是否为方法调用 object.method(...)
             if let ExprKind::Get { object, name } = &callee.kind {
                 // 尝试作为方法调用处理
                 // gen_method_call 内部会验证 object 是否为 Struct
-                let line = ctx.get_line(callee.span.start);
-                method_call::gen_method_call(ctx, locals, object, name, args, line)
+                let loc = ctx.location(&callee.span);
+                method_call::gen_method_call(ctx, locals, object, name, args, loc)
             } else {
                 call::gen_call(ctx, locals, callee, args)
             }
@@ -97,18 +108,27 @@ fn generate_expr<'ctx>(
             default,
         } => match_expr::gen_match(ctx, locals, value, cases, default.as_deref()),
         ExprKind::Print(arg) => intrinsic::gen_print(ctx, locals, arg),
+        ExprKind::CoerceDyn { value, trait_name } => {
+            trait_object::gen_coerce_dyn(ctx, locals, value, trait_name)
+        }
+        ExprKind::Cast { value, target } => integer::gen_cast(ctx, locals, value, target),
         ExprKind::Array(elements) => array::gen_array_literal(ctx, locals, elements),
         ExprKind::Index { array, index } => {
-            let line = ctx.get_line(expr.span.start);
-            array::gen_index_access(ctx, locals, array, index, line)
+            let loc = ctx.location(&expr.span);
+            array::gen_index_access(ctx, locals, array, index, loc)
         }
+        ExprKind::Range {
+            start,
+            end,
+            inclusive,
+        } => range::gen_range(ctx, locals, start, end, *inclusive),
         ExprKind::Get { object, name } => {
-            let line = ctx.get_line(expr.span.start);
-            struct_access::gen_member_access(ctx, locals, object, name, line)
+            let loc = ctx.location(&expr.span);
+            struct_access::gen_member_access(ctx, locals, object, name, loc)
         }
         ExprKind::SafeGet { object, name } => {
-            let line = ctx.get_line(expr.span.start);
-            struct_access::gen_safe_member_access(ctx, locals, object, name, line)
+            let loc = ctx.location(&expr.span);
+            struct_access::gen_safe_member_access(ctx, locals, object, name, loc)
         }
         ExprKind::StructLiteral { type_, fields } => {
             let type_name = match type_ {
@@ -130,7 +150,12 @@ fn generate_expr<'ctx>(
         ExprKind::Ok(inner) => result::gen_ok(ctx, locals, inner),
         ExprKind::Err(inner) => result::gen_err(ctx, locals, inner),
         ExprKind::Try(inner) => result::gen_try(ctx, locals, inner),
-        ExprKind::Closure { params, body } => closure::gen_closure(ctx, locals, params, body),
+        ExprKind::Closure {
+            params,
+            body,
+            captures,
+            return_type,
+        } => closure::gen_closure(ctx, locals, params, body, captures, return_type.as_ref()),
         // File I/O intrinsics (Sprint 12)
         ExprKind::ReadFile(path) => intrinsic::gen_read_file(ctx, locals, path),
         ExprKind::WriteFile(path, content) => intrinsic::gen_write_file(ctx, locals, path, content),
@@ -144,7 +169,11 @@ fn generate_expr<'ctx>(
         }
         ExprKind::CharToString(arg) => string_ops::gen_char_to_string(ctx, locals, arg),
         ExprKind::Format(template, args) => string_ops::gen_format(ctx, locals, template, args),
-        ExprKind::Panic(arg) => intrinsic::gen_panic(ctx, locals, arg),
+        ExprKind::Panic(arg) => intrinsic::gen_panic(ctx, locals, arg, ctx.location(&expr.span)),
+        // 有语法错误的程序不会进入代码生成
+        ExprKind::Error => Err(CodegenError::UnsupportedFeature(
+            "syntax error placeholder".to_string(),
+        )),
     }
 }
 
@@ -159,14 +188,18 @@ fn generate_lvalue_addr<'ctx>(
             unreachable!("GenericInstantiation (turbo-fish) should be monomorphized before codegen")
         }
         ExprKind::Variable(name) => {
-            let (ptr, ty) = locals
-                .get(name)
-                .ok_or_else(|| CodegenError::UndefinedVariable(name.clone()))?;
-            Ok((*ptr, ty.clone()))
+            if let Some((ptr, ty)) = locals.get(name) {
+                return Ok((*ptr, ty.clone()));
+            }
+            // 全局变量
+            match (ctx.module.get_global(name), ctx.global_var_types.get(name)) {
+                (Some(global), Some(ty)) => Ok((global.as_pointer_value(), ty.clone())),
+                _ => Err(CodegenError::UndefinedVariable(name.clone())),
+            }
         }
         ExprKind::Get { object, name } => {
-            let line = ctx.get_line(expr.span.start);
-            let ptr = struct_access::gen_struct_member_ptr(ctx, locals, object, name, line)?;
+            let loc = ctx.location(&expr.span);
+            let ptr = struct_access::gen_struct_member_ptr(ctx, locals, object, name, loc)?;
             // Need to return type of field for verification?
             // Currently generate_lvalue_addr returns (ptr, type).
             // We need to look up field type.
@@ -0,0 +1,132 @@
+//! Checked Integer Methods
+//!
+//! `x.checked_add(y)` 等返回 `Option<T>`：溢出、除以零或有符号 `MIN / -1` 时为 `None`。
+//! `Option<T>` 是单态化后的普通枚举（如 `Option__u8`），
+//! 通过其构造函数 `Option__u8_Some` / `Option__u8_None` 创建。
+
+use super::integer::{gen_is_div_overflow, gen_is_div_zero, gen_overflow_arith};
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::CodegenValue;
+use crate::types::is_unsigned_type;
+use inkwell::values::{BasicValueEnum, FunctionValue, IntValue};
+use lency_syntax::ast::Type;
+
+/// 生成 `checked_{op}`，`op` 为 add / sub / mul / div
+pub fn gen_checked_method<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    op: &str,
+    lhs: IntValue<'ctx>,
+    rhs: IntValue<'ctx>,
+    ty: Type,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let unsigned = is_unsigned_type(&ty);
+    let option_name = lency_monomorph::mangling::mangle_type(&Type::Generic(
+        "Option".to_string(),
+        vec![ty.clone()],
+    ));
+    let some_fn = option_ctor(ctx, &option_name, "Some")?;
+    let none_fn = option_ctor(ctx, &option_name, "None")?;
+
+    // 加减乘由溢出内建函数同时给出结果与溢出标志；除法要先判断，避免执行会触发 UB 的指令
+    let (value, fails) = match op {
+        "add" | "sub" | "mul" => {
+            let (value, overflow) = gen_overflow_arith(ctx, op, lhs, rhs, &ty)?;
+            (Some(value), overflow)
+        }
+        "div" => {
+            let mut fails = gen_is_div_zero(ctx, rhs)?;
+            if !unsigned {
+                let overflow = gen_is_div_overflow(ctx, lhs, rhs)?;
+                fails = ctx
+                    .builder
+                    .build_or(fails, overflow, "checked_div_fails")
+                    .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            }
+            (None, fails)
+        }
+        _ => {
+            return Err(CodegenError::UnsupportedFeature(format!(
+                "integer method 'checked_{}'",
+                op
+            )))
+        }
+    };
+
+    let function = ctx
+        .builder
+        .get_insert_block()
+        .and_then(|bb| bb.get_parent())
+        .ok_or_else(|| CodegenError::LLVMBuildError("checked method outside function".into()))?;
+    let some_bb = ctx.context.append_basic_block(function, "checked_some");
+    let none_bb = ctx.context.append_basic_block(function, "checked_none");
+    let merge_bb = ctx.context.append_basic_block(function, "checked_merge");
+    ctx.builder
+        .build_conditional_branch(fails, none_bb, some_bb)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    ctx.builder.position_at_end(some_bb);
+    let value = match value {
+        Some(value) => value,
+        None if unsigned => ctx
+            .builder
+            .build_int_unsigned_div(lhs, rhs, "checked_div")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into(),
+        None => ctx
+            .builder
+            .build_int_signed_div(lhs, rhs, "checked_div")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into(),
+    };
+    let some = call_ctor(ctx, some_fn, &[value])?;
+    ctx.builder
+        .build_unconditional_branch(merge_bb)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    ctx.builder.position_at_end(none_bb);
+    let none = call_ctor(ctx, none_fn, &[])?;
+    ctx.builder
+        .build_unconditional_branch(merge_bb)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    ctx.builder.position_at_end(merge_bb);
+    let phi = ctx
+        .builder
+        .build_phi(some.get_type(), "checked_res")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    phi.add_incoming(&[(&some, some_bb), (&none, none_bb)]);
+
+    Ok(CodegenValue {
+        value: phi.as_basic_value(),
+        ty: Type::Struct(option_name),
+    })
+}
+
+/// 查找 `Option<T>` 变体的构造函数；程序中没有 `Option` 时单态化不会生成它
+fn option_ctor<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    option_name: &str,
+    variant: &str,
+) -> CodegenResult<FunctionValue<'ctx>> {
+    let ctor_name = format!("{}_{}", option_name, variant);
+    ctx.module
+        .get_function(&ctor_name)
+        .ok_or(CodegenError::FunctionNotFound(ctor_name))
+}
+
+fn call_ctor<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    function: FunctionValue<'ctx>,
+    args: &[BasicValueEnum<'ctx>],
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let args: Vec<_> = args.iter().map(|&arg| arg.into()).collect();
+    ctx.builder
+        .build_call(function, &args, "call_ctor")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or(CodegenError::LLVMBuildError(
+            "Constructor returned void".into(),
+        ))
+}
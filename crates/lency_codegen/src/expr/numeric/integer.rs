@@ -0,0 +1,227 @@
+//! Integer Conversion Code Generation
+//!
+//! `as` 数值转换与整数的 `wrapping_*` / `checked_*` 内置方法，以及算术的溢出检查。
+//! 有符号与无符号的区别只体现在类型上（LLVM 整数没有符号），
+//! 因此扩展、除法与浮点转换都要按 Lency 类型选择指令。
+
+use super::checked::gen_checked_method;
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
+use crate::types::{is_unsigned_type, ToLLVMType};
+use inkwell::intrinsics::Intrinsic;
+use inkwell::values::{BasicValueEnum, IntValue, PointerValue};
+use inkwell::IntPredicate;
+use lency_syntax::ast::{Expr, Type};
+use std::collections::HashMap;
+
+/// 生成 `value as target`
+pub fn gen_cast<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
+    value: &Expr,
+    target: &Type,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let source = generate_expr(ctx, locals, value)?;
+    // bool 与无符号整数零扩展 / 按无符号转浮点，其余按有符号
+    let source_signed = !is_unsigned_type(&source.ty) && source.ty != Type::Bool;
+    let target_llvm = target.to_llvm_type(ctx)?;
+
+    let result: BasicValueEnum<'ctx> = match (source.value, target) {
+        (BasicValueEnum::IntValue(v), Type::Int | Type::FixedInt(_) | Type::Char) => ctx
+            .builder
+            .build_int_cast_sign_flag(v, target_llvm.into_int_type(), source_signed, "cast")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into(),
+        (BasicValueEnum::IntValue(v), Type::Float) if source_signed => ctx
+            .builder
+            .build_signed_int_to_float(v, ctx.context.f64_type(), "itof")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into(),
+        (BasicValueEnum::IntValue(v), Type::Float) => ctx
+            .builder
+            .build_unsigned_int_to_float(v, ctx.context.f64_type(), "utof")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into(),
+        (BasicValueEnum::FloatValue(v), Type::Int | Type::FixedInt(_))
+            if is_unsigned_type(target) =>
+        {
+            ctx.builder
+                .build_float_to_unsigned_int(v, target_llvm.into_int_type(), "ftou")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+                .into()
+        }
+        (BasicValueEnum::FloatValue(v), Type::Int | Type::FixedInt(_)) => ctx
+            .builder
+            .build_float_to_signed_int(v, target_llvm.into_int_type(), "ftoi")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into(),
+        (BasicValueEnum::FloatValue(v), Type::Float) => v.into(),
+        _ => return Err(CodegenError::TypeMismatch),
+    };
+
+    Ok(CodegenValue {
+        value: result,
+        ty: target.clone(),
+    })
+}
+
+/// 是否为整数的内置算术方法（与 sema 的 `INTEGER_METHODS` 对应）
+pub fn is_integer_method(ty: &Type, name: &str) -> bool {
+    matches!(ty, Type::Int | Type::FixedInt(_))
+        && lency_sema::type_infer::INTEGER_METHODS.contains(&name)
+}
+
+/// 生成 `x.wrapping_add(y)`、`x.checked_div(y)` 等整数方法
+///
+/// `wrapping_*` 按位宽回绕；`checked_*` 返回 `Option<T>`（见 `numeric::checked`）。
+pub fn gen_integer_method<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
+    receiver: CodegenValue<'ctx>,
+    method_name: &str,
+    args: &[Expr],
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let [arg] = args else {
+        return Err(CodegenError::UnsupportedFeature(format!(
+            "{} expects one argument",
+            method_name
+        )));
+    };
+    let (BasicValueEnum::IntValue(lhs), BasicValueEnum::IntValue(rhs)) =
+        (receiver.value, generate_expr(ctx, locals, arg)?.value)
+    else {
+        return Err(CodegenError::TypeMismatch);
+    };
+    if let Some(op) = method_name.strip_prefix("checked_") {
+        return gen_checked_method(ctx, op, lhs, rhs, receiver.ty);
+    }
+
+    let value = match method_name {
+        "wrapping_add" => ctx.builder.build_int_add(lhs, rhs, "wrapping_add"),
+        "wrapping_sub" => ctx.builder.build_int_sub(lhs, rhs, "wrapping_sub"),
+        "wrapping_mul" => ctx.builder.build_int_mul(lhs, rhs, "wrapping_mul"),
+        _ => {
+            return Err(CodegenError::UnsupportedFeature(format!(
+                "integer method '{}'",
+                method_name
+            )))
+        }
+    }
+    .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    Ok(CodegenValue {
+        value: value.into(),
+        ty: receiver.ty,
+    })
+}
+
+/// 经 `llvm.{s,u}{op}.with.overflow` 计算，溢出时 panic
+///
+/// 也用于非 release 构建中的 `+`、`-`、`*` 与取负（见 `binary::checked`）。
+pub(crate) fn gen_checked_arith<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    op: &str,
+    lhs: IntValue<'ctx>,
+    rhs: IntValue<'ctx>,
+    ty: Type,
+    loc: SourceLocation,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let (value, overflow) = gen_overflow_arith(ctx, op, lhs, rhs, &ty)?;
+    crate::runtime::gen_panic_if(ctx, overflow, "Integer Overflow", "overflow", loc);
+    Ok(CodegenValue { value, ty })
+}
+
+/// 调用 `llvm.{s,u}{op}.with.overflow`，返回（按位宽回绕的）结果与溢出标志
+pub(crate) fn gen_overflow_arith<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    op: &str,
+    lhs: IntValue<'ctx>,
+    rhs: IntValue<'ctx>,
+    ty: &Type,
+) -> CodegenResult<(BasicValueEnum<'ctx>, IntValue<'ctx>)> {
+    let sign = if is_unsigned_type(ty) { "u" } else { "s" };
+    let name = format!("llvm.{}{}.with.overflow", sign, op);
+    let intrinsic = Intrinsic::find(&name)
+        .and_then(|i| i.get_declaration(&ctx.module, &[lhs.get_type().into()]))
+        .ok_or_else(|| CodegenError::FunctionNotFound(name.clone()))?;
+
+    let pair = ctx
+        .builder
+        .build_call(intrinsic, &[lhs.into(), rhs.into()], "checked")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError(format!("{} returned void", name)))?
+        .into_struct_value();
+    let value = ctx
+        .builder
+        .build_extract_value(pair, 0, "checked_value")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let overflow = ctx
+        .builder
+        .build_extract_value(pair, 1, "checked_overflow")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_int_value();
+    Ok((value, overflow))
+}
+
+/// 除数为零时 panic；有符号整数另检查 MIN / -1 的溢出
+pub(crate) fn gen_div_checks<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    lhs: IntValue<'ctx>,
+    rhs: IntValue<'ctx>,
+    unsigned: bool,
+    loc: SourceLocation,
+) -> CodegenResult<()> {
+    let is_zero = gen_is_div_zero(ctx, rhs)?;
+    crate::runtime::gen_panic_if(ctx, is_zero, "Division by Zero", "div_zero", loc);
+    if unsigned {
+        return Ok(());
+    }
+    let overflow = gen_is_div_overflow(ctx, lhs, rhs)?;
+    crate::runtime::gen_panic_if(ctx, overflow, "Integer Overflow", "overflow", loc);
+    Ok(())
+}
+
+/// 除数是否为零
+pub(crate) fn gen_is_div_zero<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    rhs: IntValue<'ctx>,
+) -> CodegenResult<IntValue<'ctx>> {
+    ctx.builder
+        .build_int_compare(
+            IntPredicate::EQ,
+            rhs,
+            rhs.get_type().const_zero(),
+            "is_div_zero",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
+
+/// 有符号除法 `MIN / -1` 是否溢出
+pub(crate) fn gen_is_div_overflow<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    lhs: IntValue<'ctx>,
+    rhs: IntValue<'ctx>,
+) -> CodegenResult<IntValue<'ctx>> {
+    let int_type = lhs.get_type();
+    let min = int_type.const_int(1u64 << (int_type.get_bit_width() - 1), false);
+    let is_min = ctx
+        .builder
+        .build_int_compare(IntPredicate::EQ, lhs, min, "is_min")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let is_neg_one = ctx
+        .builder
+        .build_int_compare(
+            IntPredicate::EQ,
+            rhs,
+            int_type.const_all_ones(),
+            "is_neg_one",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    ctx.builder
+        .build_and(is_min, is_neg_one, "div_overflow")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
@@ -0,0 +1,7 @@
+//! Numeric Code Generation
+//!
+//! 数值相关的代码生成：整数转换与内置方法、区间值
+
+pub mod checked;
+pub mod integer;
+pub mod range;
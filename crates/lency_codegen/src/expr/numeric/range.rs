@@ -0,0 +1,183 @@
+//! Range Code Generation
+//!
+//! 区间值按值传递，布局为 `{ i64 start, i64 end, i1 inclusive }`（见 `types::range_struct_type`）。
+//! for-in 直接使用两端计数，不分配内存；`v[a..b]` / `s[a..b]` 经运行时复制出新的 Vec / 字符串。
+
+use std::collections::HashMap;
+
+use inkwell::values::{FunctionValue, IntValue, PointerValue, StructValue};
+use inkwell::AddressSpace;
+use lency_syntax::ast::{Expr, Type};
+
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
+use crate::types::range_struct_type;
+
+/// 区间的两端与是否包含终点
+pub struct RangeBounds<'ctx> {
+    pub start: IntValue<'ctx>,
+    pub end: IntValue<'ctx>,
+    /// i1
+    pub inclusive: IntValue<'ctx>,
+}
+
+/// 生成 `start..end` / `start..=end`
+pub fn gen_range<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
+    start: &Expr,
+    end: &Expr,
+    inclusive: bool,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let bounds = gen_bounds(ctx, locals, start, end, inclusive)?;
+    let mut value = range_struct_type(ctx).get_undef();
+    for (index, field) in [bounds.start, bounds.end, bounds.inclusive]
+        .into_iter()
+        .enumerate()
+    {
+        value = ctx
+            .builder
+            .build_insert_value(value, field, index as u32, "range")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into_struct_value();
+    }
+    Ok(CodegenValue {
+        value: value.into(),
+        ty: Type::Range,
+    })
+}
+
+/// 区间字面量的两端（不构造区间值）
+pub fn gen_bounds<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
+    start: &Expr,
+    end: &Expr,
+    inclusive: bool,
+) -> CodegenResult<RangeBounds<'ctx>> {
+    let start = generate_expr(ctx, locals, start)?.value.into_int_value();
+    let end = generate_expr(ctx, locals, end)?.value.into_int_value();
+    Ok(RangeBounds {
+        start,
+        end,
+        inclusive: ctx.context.bool_type().const_int(inclusive as u64, false),
+    })
+}
+
+/// 拆开区间值
+pub fn range_bounds<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    range: StructValue<'ctx>,
+) -> CodegenResult<RangeBounds<'ctx>> {
+    let field = |index: u32, name: &str| -> CodegenResult<IntValue<'ctx>> {
+        Ok(ctx
+            .builder
+            .build_extract_value(range, index, name)
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into_int_value())
+    };
+    Ok(RangeBounds {
+        start: field(0, "range_start")?,
+        end: field(1, "range_end")?,
+        inclusive: field(2, "range_inclusive")?,
+    })
+}
+
+/// 生成切片 `target[range]`：target 为 Vec 或字符串
+pub fn gen_slice<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    target: CodegenValue<'ctx>,
+    range: CodegenValue<'ctx>,
+    loc: SourceLocation,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let bounds = range_bounds(ctx, range.value.into_struct_value())?;
+    let ptr = target.value.into_pointer_value();
+    let (runtime_fn, len) = match &target.ty {
+        Type::Vec(_) => {
+            let len = ctx
+                .builder
+                .build_call(
+                    crate::expr::vec::get_or_declare_vec_len(ctx)?,
+                    &[ptr.into()],
+                    "len",
+                )
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| CodegenError::LLVMBuildError("lency_vec_len returned void".into()))?
+                .into_int_value();
+            ("lency_vec_slice", len)
+        }
+        Type::String => (
+            "lency_string_slice",
+            crate::expr::string::string_ops::gen_string_len(ctx, ptr)?,
+        ),
+        _ => return Err(CodegenError::TypeMismatch),
+    };
+
+    // a..=b 即 a..b+1
+    let one = ctx.context.i64_type().const_int(1, false);
+    let end_plus_one = ctx
+        .builder
+        .build_int_add(bounds.end, one, "end_incl")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let end = ctx
+        .builder
+        .build_select(bounds.inclusive, end_plus_one, bounds.end, "slice_end")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_int_value();
+
+    // 0 <= start <= end <= len（按无符号比较，负数视为极大值）
+    let reversed = ctx
+        .builder
+        .build_int_compare(
+            inkwell::IntPredicate::UGT,
+            bounds.start,
+            end,
+            "slice_reversed",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let too_long = ctx
+        .builder
+        .build_int_compare(inkwell::IntPredicate::UGT, end, len, "slice_too_long")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let out_of_bounds = ctx
+        .builder
+        .build_or(reversed, too_long, "slice_oob")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    crate::runtime::gen_panic_if(ctx, out_of_bounds, "Slice Out of Bounds", "slice", loc);
+
+    let result = ctx
+        .builder
+        .build_call(
+            get_or_declare_slice(ctx, runtime_fn),
+            &[ptr.into(), bounds.start.into(), end.into()],
+            "slice",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError(format!("{} returned void", runtime_fn)))?;
+    Ok(CodegenValue {
+        value: result,
+        ty: target.ty,
+    })
+}
+
+/// 声明 `i8* runtime_fn(i8*, i64, i64)`
+fn get_or_declare_slice<'ctx>(ctx: &CodegenContext<'ctx>, name: &str) -> FunctionValue<'ctx> {
+    ctx.module.get_function(name).unwrap_or_else(|| {
+        let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+        let i64_type = ctx.context.i64_type();
+        ctx.module.add_function(
+            name,
+            i8_ptr_type.fn_type(
+                &[i8_ptr_type.into(), i64_type.into(), i64_type.into()],
+                false,
+            ),
+            None,
+        )
+    })
+}
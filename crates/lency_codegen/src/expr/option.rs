@@ -5,9 +5,10 @@
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
 use crate::types::ToLLVMType;
 use inkwell::values::PointerValue;
-use lency_syntax::ast::{Expr, Type};
+use lency_syntax::ast::{Expr, IntKind, Type};
 use std::collections::HashMap;
 
 /// Option 内置方法实现
@@ -16,6 +17,7 @@ use std::collections::HashMap;
 /// Option 内存布局 (通用 Enum):
 ///   index 0: tag (i64)  [0 = Some, 1 = None]
 ///   index 1: payload ([size x i8])
+#[allow(clippy::too_many_arguments)]
 pub fn gen_option_builtin_method<'ctx>(
     ctx: &CodegenContext<'ctx>,
     locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
@@ -24,6 +26,7 @@ pub fn gen_option_builtin_method<'ctx>(
     args: &[Expr],
     struct_name: &str,     // 例如 "Option_int"
     generic_args: &[Type], // T
+    loc: SourceLocation,
 ) -> CodegenResult<Option<CodegenValue<'ctx>>> {
     let struct_type = match ctx.struct_types.get(struct_name) {
         Some(st) => *st,
@@ -224,7 +227,10 @@ pub fn gen_option_builtin_method<'ctx>(
                         "bool" => Type::Bool,
                         "string" => Type::String,
                         "float" => Type::Float,
-                        _ => Type::Struct(type_name.to_string()),
+                        _ => match IntKind::ALL.into_iter().find(|k| k.name() == type_name) {
+                            Some(kind) => Type::FixedInt(kind),
+                            None => Type::Struct(type_name.to_string()),
+                        },
                     }
                 } else {
                     return Err(CodegenError::LLVMBuildError(
@@ -263,17 +269,7 @@ pub fn gen_option_builtin_method<'ctx>(
 
             // Emit None block (Panic!)
             ctx.builder.position_at_end(else_bb);
-            if let Some(panic_func) = ctx.panic_func {
-                crate::runtime::gen_panic(
-                    ctx.context,
-                    &ctx.builder,
-                    panic_func,
-                    "Option::unwrap called on None",
-                    0,
-                );
-            } else {
-                ctx.builder.build_unreachable().unwrap();
-            }
+            crate::runtime::gen_panic(ctx, "Option::unwrap called on None", loc);
 
             // Merge block
             ctx.builder.position_at_end(merge_bb);
@@ -39,7 +39,7 @@ pub fn gen_ok<'ctx>(
 
     let malloc = ctx
         .module
-        .get_function("malloc")
+        .get_function(crate::runtime::ALLOC_FN)
         .ok_or(CodegenError::LLVMBuildError("malloc not found".to_string()))?;
 
     let malloc_call = ctx
@@ -121,7 +121,7 @@ pub fn gen_err<'ctx>(
 
     let malloc = ctx
         .module
-        .get_function("malloc")
+        .get_function(crate::runtime::ALLOC_FN)
         .ok_or(CodegenError::LLVMBuildError("malloc not found".to_string()))?;
 
     let malloc_call = ctx
@@ -1,6 +1,7 @@
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
 
 use lency_syntax::ast::{Expr, Type};
 use std::collections::HashMap;
@@ -21,7 +22,7 @@ pub fn gen_result_builtin_method<'ctx>(
     args: &[Expr],
     ok_type: &Type,
     _err_type: &Type,
-    line: u32,
+    loc: SourceLocation,
 ) -> CodegenResult<Option<CodegenValue<'ctx>>> {
     // 获取 Result struct type
     let result_ty = Type::Result {
@@ -170,30 +171,14 @@ pub fn gen_result_builtin_method<'ctx>(
 
             // 4. Error 分支：Panic
             ctx.builder.position_at_end(error_bb);
-            if let Some(panic_func) = ctx.panic_func {
-                if method_name == "expect" {
-                    let msg_expr = &args[0];
-                    let msg_val = generate_expr(ctx, locals, msg_expr)?;
-
-                    crate::runtime::gen_panic_val(
-                        ctx.context,
-                        &ctx.builder,
-                        panic_func,
-                        msg_val.value,
-                        line,
-                    );
-                } else {
-                    let panic_msg = "Result::unwrap called on an Err value";
-                    crate::runtime::gen_panic(
-                        ctx.context,
-                        &ctx.builder,
-                        panic_func,
-                        panic_msg,
-                        line,
-                    );
-                };
+            if method_name == "expect" {
+                let msg_expr = &args[0];
+                let msg_val = generate_expr(ctx, locals, msg_expr)?;
+
+                crate::runtime::gen_panic_val(ctx, msg_val.value, loc);
             } else {
-                ctx.builder.build_unreachable().unwrap();
+                let panic_msg = "Result::unwrap called on an Err value";
+                crate::runtime::gen_panic(ctx, panic_msg, loc);
             }
 
             // 5. Merge 分支：Phi
@@ -106,7 +106,7 @@ pub fn gen_try<'ctx>(
             ))?;
         let malloc = ctx
             .module
-            .get_function("malloc")
+            .get_function(crate::runtime::ALLOC_FN)
             .ok_or(CodegenError::LLVMBuildError("malloc not found".to_string()))?;
         let malloc_call = ctx
             .builder
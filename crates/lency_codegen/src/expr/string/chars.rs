@@ -0,0 +1,136 @@
+//! Char and String View Code Generation
+//!
+//! `char` 以 i32 保存一个 Unicode 标量值。字符串是带长度头的 UTF-8 字节串，
+//! `s.chars()` / `s.bytes()` 与 `for c in s` 经运行时解码为 Vec 后再逐个访问。
+
+use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
+use inkwell::AddressSpace;
+use lency_syntax::ast::{IntKind, Type};
+
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::vec::{get_or_declare_vec_get, get_or_declare_vec_len};
+use crate::expr::CodegenValue;
+
+/// 是否为字符串的内置视图方法（与 sema 的 `STRING_METHODS` 对应）
+pub fn is_string_method(ty: &Type, name: &str) -> bool {
+    *ty == Type::String && lency_sema::type_infer::STRING_METHODS.contains(&name)
+}
+
+/// 生成 `s.chars()` -> Vec<char> 或 `s.bytes()` -> Vec<u8>
+pub fn gen_string_method<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    receiver: CodegenValue<'ctx>,
+    method_name: &str,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let (runtime_fn, element) = match method_name {
+        "chars" => ("lency_string_chars", Type::Char),
+        "bytes" => ("lency_string_bytes", Type::FixedInt(IntKind::U8)),
+        _ => {
+            return Err(CodegenError::UnsupportedFeature(format!(
+                "string method '{}'",
+                method_name
+            )))
+        }
+    };
+    let vec = call_string_to_vec(ctx, runtime_fn, receiver.value.into_pointer_value())?;
+    Ok(CodegenValue {
+        value: vec.into(),
+        ty: Type::Vec(Box::new(element)),
+    })
+}
+
+/// 将字符串解码为码点 Vec，返回 (Vec 指针, 字符数)，供 `for c in s` 使用
+pub fn gen_chars_source<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    string: PointerValue<'ctx>,
+) -> CodegenResult<(PointerValue<'ctx>, IntValue<'ctx>)> {
+    let vec = call_string_to_vec(ctx, "lency_string_chars", string)?;
+    let len = ctx
+        .builder
+        .build_call(get_or_declare_vec_len(ctx)?, &[vec.into()], "char_count")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError("lency_vec_len returned void".to_string()))?
+        .into_int_value();
+    Ok((vec, len))
+}
+
+/// 取码点 Vec 的第 `index` 个元素（调用者保证不越界）
+pub fn gen_char_at<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    vec: PointerValue<'ctx>,
+    index: IntValue<'ctx>,
+) -> CodegenResult<IntValue<'ctx>> {
+    let code = ctx
+        .builder
+        .build_call(
+            get_or_declare_vec_get(ctx)?,
+            &[vec.into(), index.into()],
+            "char_code",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError("lency_vec_get returned void".to_string()))?
+        .into_int_value();
+    ctx.builder
+        .build_int_truncate(code, ctx.context.i32_type(), "char")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
+}
+
+/// 将码点（char 或 int）编码为单字符的 UTF-8 字符串
+pub fn gen_char_to_string_value<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    code: IntValue<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let i64_type = ctx.context.i64_type();
+    // char 是无符号的码点，零扩展
+    let code = ctx
+        .builder
+        .build_int_z_extend_or_bit_cast(code, i64_type, "char_code")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    // 声明 C runtime 函数: char* lency_char_to_string(int64_t char_code)
+    let char_to_string_fn = get_or_declare(ctx, "lency_char_to_string", i64_type.into());
+    ctx.builder
+        .build_call(char_to_string_fn, &[code.into()], "char_str")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| {
+            CodegenError::LLVMBuildError("lency_char_to_string returned void".to_string())
+        })
+}
+
+/// 调用 `LencyVec* runtime_fn(const char*)`
+fn call_string_to_vec<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    runtime_fn: &str,
+    string: PointerValue<'ctx>,
+) -> CodegenResult<PointerValue<'ctx>> {
+    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+    let function = get_or_declare(ctx, runtime_fn, i8_ptr_type.into());
+    Ok(ctx
+        .builder
+        .build_call(function, &[string.into()], "string_view")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError(format!("{} returned void", runtime_fn)))?
+        .into_pointer_value())
+}
+
+/// 声明单参数、返回指针的运行时函数
+fn get_or_declare<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    name: &str,
+    param: inkwell::types::BasicMetadataTypeEnum<'ctx>,
+) -> FunctionValue<'ctx> {
+    ctx.module.get_function(name).unwrap_or_else(|| {
+        let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+        ctx.module
+            .add_function(name, i8_ptr_type.fn_type(&[param], false), None)
+    })
+}
@@ -0,0 +1,7 @@
+//! String Code Generation
+//!
+//! 字符串相关的代码生成：运行时字符串操作、char 视图与 StringBuilder 内置函数
+
+pub mod chars;
+pub mod string_builder;
+pub mod string_ops;
@@ -0,0 +1,161 @@
+//! StringBuilder Builtins Code Generation
+//!
+//! `string_builder_*` 内置函数映射到 lency_runtime 中同名的 `lency_string_builder_*`，
+//! 句柄与 HashMap 一样按 int (i64) 传递；char 参数零扩展为 i64 码点。
+//! 循环中 `s = s + x` 的改写（见 `stmt::string_append`）也通过这里生成调用。
+
+use inkwell::types::BasicMetadataTypeEnum;
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, IntValue, PointerValue};
+use inkwell::AddressSpace;
+use lency_syntax::ast::{Expr, Type};
+use std::collections::HashMap;
+
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::{generate_expr, CodegenValue};
+
+/// Lency 内置函数名 -> (运行时函数名, 参数类型, 返回类型)
+fn signature(name: &str) -> Option<(&'static str, Vec<Type>, Type)> {
+    let sig = match name {
+        "string_builder_new" => ("lency_string_builder_new", vec![], Type::Int),
+        "string_builder_append" => (
+            "lency_string_builder_append",
+            vec![Type::Int, Type::String],
+            Type::Void,
+        ),
+        "string_builder_append_int" => (
+            "lency_string_builder_append_int",
+            vec![Type::Int, Type::Int],
+            Type::Void,
+        ),
+        "string_builder_append_char" => (
+            "lency_string_builder_append_char",
+            vec![Type::Int, Type::Char],
+            Type::Void,
+        ),
+        "string_builder_len" => ("lency_string_builder_len", vec![Type::Int], Type::Int),
+        "string_builder_build" => ("lency_string_builder_build", vec![Type::Int], Type::String),
+        // 仅供循环拼接改写使用，不对 Lency 代码开放
+        "string_builder_from" => ("lency_string_builder_from", vec![Type::String], Type::Int),
+        _ => return None,
+    };
+    Some(sig)
+}
+
+/// Check if function name is a StringBuilder builtin
+pub fn is_string_builder_fn(name: &str) -> bool {
+    name != "string_builder_from" && signature(name).is_some()
+}
+
+/// Generate code for StringBuilder builtin calls
+pub fn gen_string_builder_call<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
+    func_name: &str,
+    args: &[Expr],
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let (_, params, _) = signature(func_name)
+        .ok_or_else(|| CodegenError::FunctionNotFound(func_name.to_string()))?;
+    if args.len() != params.len() {
+        return Err(CodegenError::UnsupportedFeature(format!(
+            "{} expects {} argument",
+            func_name,
+            params.len()
+        )));
+    }
+
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        values.push(generate_expr(ctx, locals, arg)?.value);
+    }
+    call(ctx, func_name, &values)
+}
+
+/// 以字符串 `s` 的内容创建 StringBuilder，返回 int 句柄
+pub fn gen_from<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    s: BasicValueEnum<'ctx>,
+) -> CodegenResult<IntValue<'ctx>> {
+    Ok(call(ctx, "string_builder_from", &[s])?
+        .value
+        .into_int_value())
+}
+
+/// 把字符串 `s` 追加到句柄 `sb`
+pub fn gen_append<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    sb: IntValue<'ctx>,
+    s: BasicValueEnum<'ctx>,
+) -> CodegenResult<()> {
+    call(ctx, "string_builder_append", &[sb.into(), s])?;
+    Ok(())
+}
+
+/// 取出句柄 `sb` 的内容，返回新字符串
+pub fn gen_build<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    sb: IntValue<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    Ok(call(ctx, "string_builder_build", &[sb.into()])?.value)
+}
+
+/// 调用（必要时先声明）内置函数对应的运行时函数
+fn call<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    func_name: &str,
+    values: &[BasicValueEnum<'ctx>],
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let (runtime_fn, params, return_type) = signature(func_name)
+        .ok_or_else(|| CodegenError::FunctionNotFound(func_name.to_string()))?;
+
+    let i64_type = ctx.context.i64_type();
+    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+    let function = ctx.module.get_function(runtime_fn).unwrap_or_else(|| {
+        let param_types: Vec<BasicMetadataTypeEnum> = params
+            .iter()
+            .map(|ty| -> BasicMetadataTypeEnum {
+                match ty {
+                    Type::String => i8_ptr_type.into(),
+                    _ => i64_type.into(),
+                }
+            })
+            .collect();
+        let fn_type = match return_type {
+            Type::Void => ctx.context.void_type().fn_type(&param_types, false),
+            Type::String => i8_ptr_type.fn_type(&param_types, false),
+            _ => i64_type.fn_type(&param_types, false),
+        };
+        ctx.module.add_function(runtime_fn, fn_type, None)
+    });
+
+    let mut call_args: Vec<BasicMetadataValueEnum> = Vec::with_capacity(values.len());
+    for (value, ty) in values.iter().zip(&params) {
+        let arg = match ty {
+            // char 是无符号的码点，零扩展
+            Type::Char => ctx
+                .builder
+                .build_int_z_extend_or_bit_cast(value.into_int_value(), i64_type, "char_code")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+                .into(),
+            _ => (*value).into(),
+        };
+        call_args.push(arg);
+    }
+
+    let call = ctx
+        .builder
+        .build_call(function, &call_args, "")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let value = match return_type {
+        Type::Void => i64_type.const_zero().into(),
+        _ => call
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CodegenError::LLVMBuildError(format!("{} returned void", runtime_fn)))?,
+    };
+
+    Ok(CodegenValue {
+        value,
+        ty: return_type,
+    })
+}
@@ -1,115 +1,111 @@
 //! String Operations Code Generation
 //!
-//! 字符串操作代码生成，包含 C 运行时函数声明
+//! 字符串操作代码生成。字符串是带长度头的 i8*（布局见 `lency_runtime::lstring`），
+//! 连接、比较与取长度都由运行时完成，不依赖 NUL 结尾。
 
-use inkwell::values::{BasicValueEnum, FunctionValue, PointerValue};
-use inkwell::AddressSpace;
+use inkwell::types::FunctionType;
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, IntValue, PointerValue};
+use inkwell::{AddressSpace, IntPredicate};
 
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 
 /// 生成字符串连接代码
-pub(super) fn concat<'ctx>(
+pub(crate) fn concat<'ctx>(
     ctx: &CodegenContext<'ctx>,
     lhs: PointerValue<'ctx>,
     rhs: PointerValue<'ctx>,
 ) -> CodegenResult<BasicValueEnum<'ctx>> {
-    let strlen_fn = get_or_declare_strlen(ctx);
-    let malloc_fn = get_or_declare_malloc(ctx);
-    let strcpy_fn = get_or_declare_strcpy(ctx);
-    let strcat_fn = get_or_declare_strcat(ctx);
-
-    let len1 = ctx
-        .builder
-        .build_call(strlen_fn, &[lhs.into()], "len1")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
-        .try_as_basic_value()
-        .left()
-        .unwrap()
-        .into_int_value();
-
-    let len2 = ctx
-        .builder
-        .build_call(strlen_fn, &[rhs.into()], "len2")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
-        .try_as_basic_value()
-        .left()
-        .unwrap()
-        .into_int_value();
-
-    let total_len = ctx
-        .builder
-        .build_int_add(len1, len2, "total_len")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-    let total_len_plus_one = ctx
-        .builder
-        .build_int_add(
-            total_len,
-            ctx.context.i64_type().const_int(1, false),
-            "total_len_p1",
-        )
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-    let result_ptr = ctx
-        .builder
-        .build_call(malloc_fn, &[total_len_plus_one.into()], "concat_result")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
-        .try_as_basic_value()
-        .left()
-        .unwrap()
-        .into_pointer_value();
-
-    ctx.builder
-        .build_call(strcpy_fn, &[result_ptr.into(), lhs.into()], "")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-    ctx.builder
-        .build_call(strcat_fn, &[result_ptr.into(), rhs.into()], "")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-
-    Ok(result_ptr.into())
+    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+    call_runtime(
+        ctx,
+        "lency_string_concat",
+        i8_ptr_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false),
+        &[lhs.into(), rhs.into()],
+        "concat_result",
+    )
 }
 
-fn get_or_declare_strlen<'ctx>(ctx: &CodegenContext<'ctx>) -> FunctionValue<'ctx> {
-    if let Some(func) = ctx.module.get_function("strlen") {
-        return func;
-    }
+/// 生成字符串内容比较，返回 i1（相等为 1）
+///
+/// 按头部记录的长度逐字节比较，内容中的 NUL 也参与比较。
+pub(crate) fn gen_string_eq<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    lhs: PointerValue<'ctx>,
+    rhs: PointerValue<'ctx>,
+) -> CodegenResult<IntValue<'ctx>> {
+    let i64_type = ctx.context.i64_type();
     let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
-    let fn_type = ctx.context.i64_type().fn_type(&[i8_ptr_type.into()], false);
-    ctx.module.add_function("strlen", fn_type, None)
+    let result = call_runtime(
+        ctx,
+        "lency_string_eq",
+        i64_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false),
+        &[lhs.into(), rhs.into()],
+        "str_eq",
+    )?
+    .into_int_value();
+    ctx.builder
+        .build_int_compare(IntPredicate::NE, result, i64_type.const_zero(), "streqtmp")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))
 }
 
-fn get_or_declare_malloc<'ctx>(ctx: &CodegenContext<'ctx>) -> FunctionValue<'ctx> {
-    if let Some(func) = ctx.module.get_function("malloc") {
-        return func;
-    }
+/// 字符串的字节长度（从头部读取，O(1)）
+pub(crate) fn gen_string_len<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    string: PointerValue<'ctx>,
+) -> CodegenResult<IntValue<'ctx>> {
+    let i64_type = ctx.context.i64_type();
     let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
-    let fn_type = i8_ptr_type.fn_type(&[ctx.context.i64_type().into()], false);
-    ctx.module.add_function("malloc", fn_type, None)
+    Ok(call_runtime(
+        ctx,
+        "lency_string_len",
+        i64_type.fn_type(&[i8_ptr_type.into()], false),
+        &[string.into()],
+        "str_len",
+    )?
+    .into_int_value())
 }
 
-fn get_or_declare_strcpy<'ctx>(ctx: &CodegenContext<'ctx>) -> FunctionValue<'ctx> {
-    if let Some(func) = ctx.module.get_function("strcpy") {
-        return func;
-    }
+/// 把 `extern` 函数返回的 NUL 结尾 C 字符串复制为 Lency 字符串（null 保持为 null）
+///
+/// 传给 `extern` 函数的字符串不需要转换：数据后总有一个 NUL。
+pub(crate) fn gen_from_cstr<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    cstr: PointerValue<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
     let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
-    let fn_type = i8_ptr_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false);
-    ctx.module.add_function("strcpy", fn_type, None)
+    call_runtime(
+        ctx,
+        "lency_string_from_cstr",
+        i8_ptr_type.fn_type(&[i8_ptr_type.into()], false),
+        &[cstr.into()],
+        "from_cstr",
+    )
 }
 
-fn get_or_declare_strcat<'ctx>(ctx: &CodegenContext<'ctx>) -> FunctionValue<'ctx> {
-    if let Some(func) = ctx.module.get_function("strcat") {
-        return func;
-    }
-    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
-    let fn_type = i8_ptr_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false);
-    ctx.module.add_function("strcat", fn_type, None)
+/// 调用（必要时先声明）返回值非 void 的运行时函数
+fn call_runtime<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    name: &str,
+    fn_type: FunctionType<'ctx>,
+    args: &[BasicMetadataValueEnum<'ctx>],
+    result_name: &str,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let function = ctx
+        .module
+        .get_function(name)
+        .unwrap_or_else(|| ctx.module.add_function(name, fn_type, None));
+    ctx.builder
+        .build_call(function, args, result_name)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError(format!("{} returned void", name)))
 }
 
 // ============== Sprint 12: 字符串内置函数 ==============
 
-use super::CodegenValue;
+use crate::expr::CodegenValue;
 use lency_syntax::ast::{Expr, Type};
 use std::collections::HashMap;
 
@@ -119,35 +115,13 @@ pub fn gen_len<'ctx>(
     locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, Type)>,
     arg: &Expr,
 ) -> CodegenResult<CodegenValue<'ctx>> {
-    use super::generate_expr;
+    use crate::expr::generate_expr;
 
     let arg_val = generate_expr(ctx, locals, arg)?;
-    let str_ptr = arg_val.value.into_pointer_value();
-
-    // 声明 lency_string_len
-    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
-    let i64_type = ctx.context.i64_type();
-
-    let string_len_fn = ctx
-        .module
-        .get_function("lency_string_len")
-        .unwrap_or_else(|| {
-            let fn_type = i64_type.fn_type(&[i8_ptr_type.into()], false);
-            ctx.module.add_function("lency_string_len", fn_type, None)
-        });
-
-    let result = ctx
-        .builder
-        .build_call(string_len_fn, &[str_ptr.into()], "str_len")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
-        .try_as_basic_value()
-        .left()
-        .ok_or(CodegenError::LLVMBuildError(
-            "lency_string_len returned void".to_string(),
-        ))?;
+    let len = gen_string_len(ctx, arg_val.value.into_pointer_value())?;
 
     Ok(CodegenValue {
-        value: result,
+        value: len.into(),
         ty: Type::Int,
     })
 }
@@ -158,7 +132,7 @@ pub fn gen_trim<'ctx>(
     locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, Type)>,
     arg: &Expr,
 ) -> CodegenResult<CodegenValue<'ctx>> {
-    use super::generate_expr;
+    use crate::expr::generate_expr;
 
     let arg_val = generate_expr(ctx, locals, arg)?;
     let str_ptr = arg_val.value.into_pointer_value();
@@ -196,7 +170,7 @@ pub fn gen_split<'ctx>(
     str_arg: &Expr,
     delim: &Expr,
 ) -> CodegenResult<CodegenValue<'ctx>> {
-    use super::generate_expr;
+    use crate::expr::generate_expr;
 
     let str_val = generate_expr(ctx, locals, str_arg)?;
     let str_ptr = str_val.value.into_pointer_value();
@@ -241,7 +215,7 @@ pub fn gen_join<'ctx>(
     vec_arg: &Expr,
     sep: &Expr,
 ) -> CodegenResult<CodegenValue<'ctx>> {
-    use super::generate_expr;
+    use crate::expr::generate_expr;
 
     let vec_val = generate_expr(ctx, locals, vec_arg)?;
     let vec_ptr = vec_val.value.into_pointer_value();
@@ -287,7 +261,7 @@ pub fn gen_substr<'ctx>(
     start: &Expr,
     len: &Expr,
 ) -> CodegenResult<CodegenValue<'ctx>> {
-    use super::generate_expr;
+    use crate::expr::generate_expr;
 
     let str_val = generate_expr(ctx, locals, str_arg)?;
     let str_ptr = str_val.value.into_pointer_value();
@@ -333,40 +307,17 @@ pub fn gen_substr<'ctx>(
     })
 }
 
-/// 生成 char_to_string(int) -> string
-/// 将字符码 (ASCII/Unicode) 转换为单字符字符串
+/// 生成 char_to_string(char) -> string
+/// 将字符（或 int 码点）编码为单字符的 UTF-8 字符串
 pub fn gen_char_to_string<'ctx>(
     ctx: &CodegenContext<'ctx>,
     locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, Type)>,
     arg: &Expr,
 ) -> CodegenResult<CodegenValue<'ctx>> {
-    use super::generate_expr;
+    use crate::expr::generate_expr;
 
     let arg_val = generate_expr(ctx, locals, arg)?;
-    let char_code = arg_val.value.into_int_value();
-
-    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
-    let i64_type = ctx.context.i64_type();
-
-    // 声明 C runtime 函数: char* lency_char_to_string(int64_t char_code)
-    let char_to_string_fn = ctx
-        .module
-        .get_function("lency_char_to_string")
-        .unwrap_or_else(|| {
-            let fn_type = i8_ptr_type.fn_type(&[i64_type.into()], false);
-            ctx.module
-                .add_function("lency_char_to_string", fn_type, None)
-        });
-
-    let result = ctx
-        .builder
-        .build_call(char_to_string_fn, &[char_code.into()], "char_str")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
-        .try_as_basic_value()
-        .left()
-        .ok_or(CodegenError::LLVMBuildError(
-            "lency_char_to_string returned void".to_string(),
-        ))?;
+    let result = super::chars::gen_char_to_string_value(ctx, arg_val.value.into_int_value())?;
 
     Ok(CodegenValue {
         value: result,
@@ -381,7 +332,7 @@ pub fn gen_format<'ctx>(
     template: &Expr,
     args: &Expr,
 ) -> CodegenResult<CodegenValue<'ctx>> {
-    use super::generate_expr;
+    use crate::expr::generate_expr;
 
     let template_val = generate_expr(ctx, locals, template)?;
     let template_ptr = template_val.value.into_pointer_value();
@@ -1,6 +1,7 @@
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
 use inkwell::values::PointerValue;
 use lency_syntax::ast::{Expr, Type};
 use std::collections::HashMap;
@@ -12,7 +13,7 @@ pub fn gen_struct_member_ptr<'ctx>(
     locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, lency_syntax::ast::Type)>,
     object_expr: &Expr,
     field_name: &str,
-    line: u32,
+    loc: SourceLocation,
 ) -> CodegenResult<PointerValue<'ctx>> {
     // 1. 计算对象表达式
     let object_val = generate_expr(ctx, locals, object_expr)?;
@@ -26,9 +27,7 @@ pub fn gen_struct_member_ptr<'ctx>(
     let ptr_val = object_val.value.into_pointer_value();
 
     // 运行时 Null 检查
-    if let Some(panic_func) = ctx.panic_func {
-        crate::runtime::gen_null_check(ctx.context, &ctx.builder, panic_func, ptr_val, line);
-    }
+    crate::runtime::gen_null_check(ctx, ptr_val, loc);
 
     // 3. 获取结构体名称和 LLVM 类型
     let struct_name = match &object_val.ty {
@@ -81,7 +80,7 @@ pub fn gen_struct_member_ptr_val<'ctx>(
     object_val: &CodegenValue<'ctx>,
     _object_span_start: usize, // Needed for line info
     field_name: &str,
-    _line: u32,
+    _loc: SourceLocation,
 ) -> CodegenResult<PointerValue<'ctx>> {
     // 2. 必须是指针类型（结构体是通过指针传递的）
     if !object_val.value.is_pointer_value() {
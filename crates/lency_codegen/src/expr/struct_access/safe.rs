@@ -3,6 +3,7 @@ use super::ptr::gen_struct_member_ptr_val;
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
 use lency_syntax::ast::{Expr, Type};
 use std::collections::HashMap;
 
@@ -12,7 +13,7 @@ pub fn gen_safe_member_access<'ctx>(
     locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, lency_syntax::ast::Type)>,
     object_expr: &Expr,
     field_name: &str,
-    line: u32,
+    loc: SourceLocation,
 ) -> CodegenResult<CodegenValue<'ctx>> {
     let object_val = generate_expr(ctx, locals, object_expr)?;
 
@@ -73,13 +74,13 @@ pub fn gen_safe_member_access<'ctx>(
                 &object_val,
                 object_expr.span.start,
                 field_name,
-                line,
+                loc,
             )?;
             load_field(ctx, &object_val, field_name, field_ptr)
         }
     } else if object_val.value.is_pointer_value() {
         let field_ptr =
-            gen_struct_member_ptr_val(ctx, &object_val, object_expr.span.start, field_name, line)?;
+            gen_struct_member_ptr_val(ctx, &object_val, object_expr.span.start, field_name, loc)?;
         load_field(ctx, &object_val, field_name, field_ptr)
     } else {
         // Struct Value (RValue Aggregate) - use ExtractValue
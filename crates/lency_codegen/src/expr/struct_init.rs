@@ -33,7 +33,7 @@ pub fn gen_struct_literal<'ctx>(
 
     let malloc = ctx
         .module
-        .get_function("malloc")
+        .get_function(crate::runtime::ALLOC_FN)
         .ok_or_else(|| CodegenError::LLVMBuildError("malloc function not found".to_string()))?;
 
     let malloc_call = ctx
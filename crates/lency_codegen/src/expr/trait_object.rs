@@ -0,0 +1,197 @@
+//! Trait Object Code Generation
+//!
+//! `dyn Trait` 运行时表示为堆上的胖指针 { i8* data, i8* vtable }：
+//! - data 指向具体类型的值（结构体指针）
+//! - vtable 指向 `__vtable_{Type}_{Trait}`，按 Trait 声明顺序存放方法指针
+
+use super::closure::build_malloc;
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
+use crate::types::{trait_object_struct_type, vtable_name, ToLLVMType};
+use inkwell::types::{BasicMetadataTypeEnum, BasicType};
+use inkwell::values::{BasicMetadataValueEnum, PointerValue};
+use inkwell::AddressSpace;
+use lency_syntax::ast::{Expr, Type};
+use std::collections::HashMap;
+
+/// 具体值 -> Trait 对象：打包数据指针与 vtable
+pub(crate) fn gen_coerce_dyn<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
+    value: &Expr,
+    trait_name: &str,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let value = generate_expr(ctx, locals, value)?;
+    if !value.value.is_pointer_value() {
+        return Err(CodegenError::UnsupportedType(format!(
+            "cannot convert '{}' to dyn {}",
+            value.ty, trait_name
+        )));
+    }
+
+    let type_name = lency_monomorph::mangling::mangle_type(&value.ty);
+    let vtable_global = ctx
+        .module
+        .get_global(&vtable_name(&type_name, trait_name))
+        .ok_or_else(|| {
+            CodegenError::UnsupportedFeature(format!(
+                "no vtable for '{}' as dyn {}",
+                type_name, trait_name
+            ))
+        })?;
+
+    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+    let data = ctx
+        .builder
+        .build_pointer_cast(value.value.into_pointer_value(), i8_ptr_type, "dyn_data")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let vtable = ctx
+        .builder
+        .build_pointer_cast(vtable_global.as_pointer_value(), i8_ptr_type, "dyn_vtable")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    let object_type = trait_object_struct_type(ctx);
+    let object_raw = build_malloc(ctx, object_type.size_of(), "dyn_obj")?;
+    let object_ptr = ctx
+        .builder
+        .build_pointer_cast(
+            object_raw,
+            object_type.ptr_type(AddressSpace::default()),
+            "dyn",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    for (index, field) in [data, vtable].into_iter().enumerate() {
+        let field_ptr = ctx
+            .builder
+            .build_struct_gep(object_type, object_ptr, index as u32, "dyn_field")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        ctx.builder
+            .build_store(field_ptr, field)
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    }
+
+    Ok(CodegenValue {
+        value: object_ptr.into(),
+        ty: Type::TraitObject(trait_name.to_string()),
+    })
+}
+
+/// 动态分派：从 vtable 取出方法指针，以数据指针作为 this 间接调用
+pub(crate) fn gen_dyn_method_call<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    locals: &HashMap<String, (PointerValue<'ctx>, Type)>,
+    object: PointerValue<'ctx>,
+    trait_name: &str,
+    method_name: &str,
+    args: &[Expr],
+    loc: SourceLocation,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let methods = ctx.trait_methods.get(trait_name).ok_or_else(|| {
+        CodegenError::UnsupportedFeature(format!("unknown trait '{}'", trait_name))
+    })?;
+    let (slot, (_, param_types, return_type)) = methods
+        .iter()
+        .enumerate()
+        .find(|(_, (name, _, _))| name == method_name)
+        .ok_or_else(|| CodegenError::FunctionNotFound(format!("{}.{}", trait_name, method_name)))?;
+
+    // 运行时 Null 检查
+    crate::runtime::gen_null_check(ctx, object, loc);
+
+    let i8_ptr_type = ctx.context.i8_type().ptr_type(AddressSpace::default());
+    let object_type = trait_object_struct_type(ctx);
+    let object_ptr = ctx
+        .builder
+        .build_pointer_cast(object, object_type.ptr_type(AddressSpace::default()), "dyn")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    let data_field = ctx
+        .builder
+        .build_struct_gep(object_type, object_ptr, 0, "dyn_data_ptr")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let data = ctx
+        .builder
+        .build_load(i8_ptr_type, data_field, "dyn_data")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let vtable_field = ctx
+        .builder
+        .build_struct_gep(object_type, object_ptr, 1, "dyn_vtable_ptr")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let vtable_raw = ctx
+        .builder
+        .build_load(i8_ptr_type, vtable_field, "dyn_vtable")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_pointer_value();
+    let vtable = ctx
+        .builder
+        .build_pointer_cast(
+            vtable_raw,
+            i8_ptr_type.ptr_type(AddressSpace::default()),
+            "vtable",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    // vtable[slot]
+    let slot_ptr = unsafe {
+        ctx.builder
+            .build_gep(
+                i8_ptr_type,
+                vtable,
+                &[ctx.context.i64_type().const_int(slot as u64, false)],
+                "vtable_slot",
+            )
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+    };
+    let fn_raw = ctx
+        .builder
+        .build_load(i8_ptr_type, slot_ptr, "dyn_fn")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_pointer_value();
+
+    // 方法签名: ret (i8* this, params...)
+    let mut llvm_params: Vec<BasicMetadataTypeEnum<'ctx>> = vec![i8_ptr_type.into()];
+    for ty in param_types {
+        llvm_params.push(ty.to_llvm_type(ctx)?.into());
+    }
+    let fn_type = match return_type {
+        Type::Void => ctx.context.void_type().fn_type(&llvm_params, false),
+        ty => ty.to_llvm_type(ctx)?.fn_type(&llvm_params, false),
+    };
+    let fn_ptr = ctx
+        .builder
+        .build_pointer_cast(
+            fn_raw,
+            fn_type.ptr_type(AddressSpace::default()),
+            "dyn_fn_typed",
+        )
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    let mut call_args: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(args.len() + 1);
+    call_args.push(data.into());
+    for arg in args {
+        call_args.push(generate_expr(ctx, locals, arg)?.value.into());
+    }
+
+    crate::runtime::gen_trace_push(ctx, &format!("{}.{}", trait_name, method_name), loc);
+    let call_site = ctx
+        .builder
+        .build_indirect_call(fn_type, fn_ptr, &call_args, "dyn_call")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    crate::runtime::gen_trace_pop(ctx);
+
+    if let Some(v) = call_site.try_as_basic_value().left() {
+        Ok(CodegenValue {
+            value: v,
+            ty: return_type.clone(),
+        })
+    } else {
+        let dummy = ctx.context.bool_type().const_int(0, false).into();
+        Ok(CodegenValue {
+            value: dummy,
+            ty: Type::Void,
+        })
+    }
+}
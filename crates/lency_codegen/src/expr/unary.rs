@@ -8,6 +8,9 @@ use std::collections::HashMap;
 
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
+use crate::expr::numeric::integer::gen_checked_arith;
+use crate::source_map::SourceLocation;
+use crate::types::is_int_type;
 
 use crate::expr::{generate_expr, CodegenValue};
 
@@ -17,13 +20,23 @@ pub(super) fn gen_unary<'ctx>(
     locals: &HashMap<String, (inkwell::values::PointerValue<'ctx>, lency_syntax::ast::Type)>,
     op: &UnaryOp,
     operand: &Expr,
+    loc: SourceLocation,
 ) -> CodegenResult<CodegenValue<'ctx>> {
     let val_wrapper = generate_expr(ctx, locals, operand)?;
     let val = val_wrapper.value;
 
+    // 非 release 构建中 `-MIN` 溢出时 panic（按 `0 - x` 检查）
+    if let (UnaryOp::Neg, BasicValueEnum::IntValue(v)) = (op, val) {
+        if ctx.overflow_checks && is_int_type(&val_wrapper.ty) {
+            let zero = v.get_type().const_zero();
+            return gen_checked_arith(ctx, "sub", zero, v, val_wrapper.ty, loc);
+        }
+    }
+
     let result_val = match op {
         UnaryOp::Neg => gen_neg(ctx, val)?,
-        UnaryOp::Not => gen_not(ctx, val)?,
+        // bool 的 `!` 与整数的 `~` 都是按位取反
+        UnaryOp::Not | UnaryOp::BitNot => gen_not(ctx, val)?,
     };
 
     Ok(CodegenValue {
@@ -3,6 +3,8 @@
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
 use crate::expr::{generate_expr, CodegenValue};
+use crate::source_map::SourceLocation;
+use crate::types::ToLLVMType;
 use inkwell::values::BasicValueEnum;
 use inkwell::AddressSpace;
 use lency_syntax::ast::{Expr, Type};
@@ -101,6 +103,7 @@ pub fn gen_vec_method_call<'ctx>(
     method_name: &str,
     args: &[Expr],
     inner_type: &Type,
+    loc: SourceLocation,
 ) -> CodegenResult<CodegenValue<'ctx>> {
     let vec_ptr = object_val.value.into_pointer_value();
 
@@ -150,6 +153,7 @@ pub fn gen_vec_method_call<'ctx>(
             let func = get_or_declare_vec_get(ctx)?;
             let index_val = generate_expr(ctx, locals, &args[0])?;
             let index_i64 = index_val.value.into_int_value(); // Assume int
+            gen_vec_bounds_check(ctx, vec_ptr, index_i64, loc)?;
 
             let call = ctx
                 .builder
@@ -168,6 +172,7 @@ pub fn gen_vec_method_call<'ctx>(
             let func = get_or_declare_vec_set(ctx)?;
             let index_val = generate_expr(ctx, locals, &args[0])?;
             let index_i64 = index_val.value.into_int_value();
+            gen_vec_bounds_check(ctx, vec_ptr, index_i64, loc)?;
 
             let val = generate_expr(ctx, locals, &args[1])?;
             let val_i64 = cast_to_i64(ctx, val.value)?;
@@ -189,6 +194,25 @@ pub fn gen_vec_method_call<'ctx>(
     }
 }
 
+/// Vec 越界检查：在调用点 panic，使报告带有源码位置
+pub(crate) fn gen_vec_bounds_check<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    vec_ptr: inkwell::values::PointerValue<'ctx>,
+    index: inkwell::values::IntValue<'ctx>,
+    loc: SourceLocation,
+) -> CodegenResult<()> {
+    let len_fn = get_or_declare_vec_len(ctx)?;
+    let len = ctx
+        .builder
+        .build_call(len_fn, &[vec_ptr.into()], "vec_len")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError("lency_vec_len returned void".into()))?;
+    crate::runtime::gen_bounds_check(ctx, index, len.into_int_value(), loc);
+    Ok(())
+}
+
 // Helpers for declaring missing functions
 pub(crate) fn get_or_declare_vec_pop<'ctx>(
     ctx: &CodegenContext<'ctx>,
@@ -281,6 +305,21 @@ pub(crate) fn cast_from_i64<'ctx>(
 ) -> CodegenResult<BasicValueEnum<'ctx>> {
     match ty {
         Type::Int => Ok(val.into()),
+        // 定宽整数在 Vec 中按 i64 存储，取出时截断回原位宽
+        Type::FixedInt(kind) => Ok(ctx
+            .builder
+            .build_int_truncate_or_bit_cast(
+                val,
+                ctx.context.custom_width_int_type(kind.bits()),
+                "i2fixed",
+            )
+            .unwrap()
+            .into()),
+        Type::Char => Ok(ctx
+            .builder
+            .build_int_truncate(val, ctx.context.i32_type(), "i2char")
+            .unwrap()
+            .into()),
         Type::Float => Ok(ctx
             .builder
             .build_bitcast(val, ctx.context.f64_type(), "i2f")
@@ -294,6 +333,15 @@ pub(crate) fn cast_from_i64<'ctx>(
                 .unwrap()
                 .into())
         }
+        Type::Function { .. } | Type::TraitObject(_) => {
+            // 闭包对象 / Trait 对象指针
+            let ptr_ty = ty.to_llvm_type(ctx)?.into_pointer_type();
+            Ok(ctx
+                .builder
+                .build_int_to_ptr(val, ptr_ty, "i2closure")
+                .unwrap()
+                .into())
+        }
         Type::Bool => Ok(ctx
             .builder
             .build_int_compare(
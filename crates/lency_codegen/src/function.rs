@@ -52,6 +52,7 @@ impl<'ctx, 'a> FunctionGenerator<'ctx, 'a> {
             params,
             return_type,
             body,
+            span,
             ..
         } = decl
         else {
@@ -101,13 +102,35 @@ impl<'ctx, 'a> FunctionGenerator<'ctx, 'a> {
             && llvm_name != name
         {
             // Fallback: 从函数名推断 struct 名称：StructName_methodName
-            // 注意：这种推断在 StructName 包含下划线时会失效 (例如 my_io__Module)
+            // 注意：这种推断在 StructName 包含下划线时会失效 (例如 my_io::File)
             // 所以首选 struct_name_context
             llvm_name.split('_').next().map(|s| s.to_string())
         } else {
             None
         };
 
+        // 源文件按函数名（方法按所属类型）查找；调试信息中方法按 "Type_method" 命名
+        let (display_name, owner) = match struct_name_context {
+            Some(type_name) => (llvm_name, type_name),
+            None => (name.as_str(), name.as_str()),
+        };
+        self.ctx.current_file.set(self.ctx.sources.file_of(owner));
+        if let Some(debug) = &self.ctx.debug {
+            let this_type = struct_name_opt.as_deref().map(Self::type_name_to_type);
+            let param_types: Vec<Type> = this_type
+                .into_iter()
+                .chain(params.iter().map(|p| p.ty.clone()))
+                .collect();
+            debug.enter_function(
+                self.ctx,
+                function,
+                display_name,
+                span,
+                &param_types,
+                return_type,
+            );
+        }
+
         let mut param_offset = 0;
 
         // 如果是方法，先处理 this 参数
@@ -134,6 +157,7 @@ impl<'ctx, 'a> FunctionGenerator<'ctx, 'a> {
                     .build_store(this_alloca, this_value)
                     .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
+                self.declare_debug_variable(this_alloca, "this", &this_type, 1);
                 locals.insert("this".to_string(), (this_alloca, this_type));
             } else {
                 // Struct 类型：this 是指针类型
@@ -151,6 +175,7 @@ impl<'ctx, 'a> FunctionGenerator<'ctx, 'a> {
                     .build_store(this_alloca, this_value)
                     .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
+                self.declare_debug_variable(this_alloca, "this", &this_type, 1);
                 locals.insert("this".to_string(), (this_alloca, this_type));
             }
 
@@ -175,6 +200,8 @@ impl<'ctx, 'a> FunctionGenerator<'ctx, 'a> {
                 .build_store(alloca, param_value)
                 .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
+            let arg_no = (i + param_offset) as u32 + 1;
+            self.declare_debug_variable(alloca, &param.name, &param.ty, arg_no);
             locals.insert(param.name.clone(), (alloca, param.ty.clone()));
         }
 
@@ -195,9 +222,26 @@ impl<'ctx, 'a> FunctionGenerator<'ctx, 'a> {
             }
         }
 
+        if let Some(debug) = &self.ctx.debug {
+            debug.exit_function(self.ctx);
+        }
+
         Ok(function)
     }
 
+    /// 为参数生成调试变量描述（未启用调试信息时为空操作）
+    fn declare_debug_variable(
+        &self,
+        storage: inkwell::values::PointerValue<'ctx>,
+        name: &str,
+        ty: &Type,
+        arg_no: u32,
+    ) {
+        if let Some(debug) = &self.ctx.debug {
+            debug.declare_variable(self.ctx, storage, name, ty, Some(arg_no));
+        }
+    }
+
     /// 声明函数（不生成函数体）
     pub fn declare(
         &self,
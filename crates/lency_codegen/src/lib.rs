@@ -7,6 +7,9 @@
 //! 模块化设计，遵循开闭原则：
 //! - `error.rs` - 错误类型定义
 //! - `context.rs` - LLVM 上下文管理
+//! - `debug_info.rs` - DWARF 调试信息
+//! - `source_map.rs` - 源码位置映射
+//! - `partition.rs` - 分模块代码生成
 //! - `types.rs` - 类型映射
 //! - `expr.rs` - 表达式代码生成
 //! - `stmt.rs` - 语句代码生成
@@ -14,17 +17,22 @@
 //! - `module.rs` - 模块代码生成
 
 pub mod context;
+pub mod debug_info;
 pub mod error;
 pub mod expr;
 pub mod function;
 pub mod module;
+pub mod partition;
 pub mod runtime;
+pub mod source_map;
 pub mod stmt;
 pub mod types;
 
 // 重新导出核心类型
 pub use context::CodegenContext;
 pub use error::{CodegenError, CodegenResult};
+pub use partition::Partition;
+pub use source_map::{SourceLocation, SourceMap};
 
 use inkwell::context::Context;
 use inkwell::module::Module;
@@ -61,6 +69,82 @@ pub fn compile_to_ir(
     Ok(ctx.print_to_string())
 }
 
+/// 按源码映射编译 Lency 程序为 LLVM IR
+///
+/// # Arguments
+/// * `program` - Lency AST 程序
+/// * `module_name` - 模块名称
+/// * `sources` - 入口文件与导入模块的源码映射（panic 位置与调试信息）
+/// * `debug_info` - 是否生成 DWARF 调试信息
+/// * `debug_checks` - 调试构建的运行时检查：整数溢出时 panic，调用前后维护 panic 调用栈
+///   （release 构建为 false：溢出按位宽回绕，不记录调用栈）
+pub fn compile_to_ir_with_sources(
+    program: &Program,
+    module_name: &str,
+    sources: SourceMap,
+    debug_info: bool,
+    debug_checks: bool,
+) -> CodegenResult<String> {
+    generate_ir(
+        program,
+        module_name,
+        sources,
+        None,
+        debug_info,
+        debug_checks,
+    )
+}
+
+/// 编译程序的一个分区为 LLVM IR（分模块编译，见 [`partition`]）
+///
+/// 参数同 [`compile_to_ir_with_sources`]；`partition` 之外的函数与全局变量只生成外部声明。
+pub fn compile_partition_to_ir(
+    program: &Program,
+    module_name: &str,
+    sources: SourceMap,
+    partition: Partition,
+    debug_info: bool,
+    debug_checks: bool,
+) -> CodegenResult<String> {
+    generate_ir(
+        program,
+        module_name,
+        sources,
+        Some(partition),
+        debug_info,
+        debug_checks,
+    )
+}
+
+fn generate_ir(
+    program: &Program,
+    module_name: &str,
+    sources: SourceMap,
+    partition: Option<Partition>,
+    debug_info: bool,
+    debug_checks: bool,
+) -> CodegenResult<String> {
+    let context = Context::create();
+    let mut ctx = CodegenContext::with_sources(&context, module_name, sources);
+    ctx.overflow_checks = debug_checks;
+    ctx.call_traces = debug_checks;
+    ctx.partition = partition;
+    if debug_info {
+        ctx.enable_debug_info();
+    }
+
+    let mut module_gen = ModuleGenerator::new(&mut ctx);
+    module_gen.generate(program)?;
+
+    // 解析调试元数据中的前向引用后再验证
+    if let Some(debug) = &ctx.debug {
+        debug.finalize();
+    }
+    ctx.verify()?;
+
+    Ok(ctx.print_to_string())
+}
+
 /// 编译 Lency 程序为 LLVM Module（用于进一步处理）
 ///
 /// # Arguments
@@ -100,6 +184,7 @@ mod tests {
         Program {
             decls: vec![Decl::Function {
                 span: 0..30,
+                is_pub: false,
                 name: "main".to_string(),
                 generic_params: vec![],
                 params: vec![],
@@ -132,12 +217,12 @@ mod tests {
         assert!(ir.contains("ret i64 42"));
     }
 
-    #[test]
-    fn test_compile_with_arithmetic() {
-        // int add(int a, int b) { return a + b; }
-        let program = Program {
+    /// int add(int a, int b) { return a + b; }
+    fn make_add_program() -> Program {
+        Program {
             decls: vec![Decl::Function {
                 span: 0..50,
+                is_pub: false,
                 name: "add".to_string(),
                 generic_params: vec![],
                 params: vec![
@@ -169,15 +254,33 @@ mod tests {
                     }),
                 }],
             }],
-        };
+        }
+    }
 
-        let result = compile_to_ir(&program, "test_add", None);
+    #[test]
+    fn test_compile_with_arithmetic() {
+        let result = compile_to_ir(&make_add_program(), "test_add", None);
         assert!(result.is_ok(), "Compilation failed: {:?}", result.err());
 
         let ir = result.unwrap();
 
         assert!(ir.contains("define i64 @add(i64"));
+        // 默认（非 release）检查溢出，溢出时带位置 panic
+        assert!(ir.contains("@llvm.sadd.with.overflow.i64"));
+        assert!(ir.contains("Integer Overflow"));
+    }
+
+    #[test]
+    fn test_compile_arithmetic_without_overflow_checks() {
+        let sources = SourceMap::new("test_add.lcy", "");
+        let result =
+            compile_to_ir_with_sources(&make_add_program(), "test_add", sources, false, false);
+        assert!(result.is_ok(), "Compilation failed: {:?}", result.err());
+
+        // release 构建按位宽回绕
+        let ir = result.unwrap();
         assert!(ir.contains("add i64"));
+        assert!(!ir.contains("with.overflow"));
     }
 
     #[test]
@@ -186,6 +289,7 @@ mod tests {
         let program = Program {
             decls: vec![Decl::Function {
                 span: 0..60,
+                is_pub: false,
                 name: "test".to_string(),
                 generic_params: vec![],
                 params: vec![],
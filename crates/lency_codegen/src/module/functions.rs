@@ -1,31 +1,35 @@
 use super::ModuleGenerator;
-use crate::error::CodegenResult;
+use crate::error::{CodegenError, CodegenResult};
 use crate::function::FunctionGenerator;
+use crate::source_map::SourceLocation;
 use crate::types::ToLLVMType;
+use inkwell::types::BasicType;
 use lency_syntax::ast::{Decl, Program};
 
 impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
-    /// 注入运行时函数 (__lency_panic, printf, exit, malloc)
+    /// 注入运行时函数 (lency_panic_at, 调用栈追踪, lency_gc_alloc)
     pub(crate) fn inject_runtime(&mut self) -> CodegenResult<()> {
         let panic_func =
             crate::runtime::inject_runtime_functions(self.ctx.context, &self.ctx.module);
         self.ctx.panic_func = Some(panic_func);
 
-        // 预定义 malloc: declare i8* @malloc(i64)
+        // 预定义堆分配函数: declare i8* @lency_gc_alloc(i64)
         let malloc_type = self
             .ctx
             .context
             .i8_type()
             .ptr_type(inkwell::AddressSpace::default())
             .fn_type(&[self.ctx.context.i64_type().into()], false);
-        self.ctx.module.add_function("malloc", malloc_type, None);
+        self.ctx
+            .module
+            .add_function(crate::runtime::ALLOC_FN, malloc_type, None);
 
         Ok(())
     }
 
     /// 第一遍：声明所有函数（支持前向引用）
     pub(crate) fn declare_functions(&mut self, program: &Program) -> CodegenResult<()> {
-        for decl in &program.decls {
+        for (index, decl) in program.decls.iter().enumerate() {
             match decl {
                 Decl::Function {
                     name,
@@ -69,6 +73,7 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
                     self.ctx
                         .function_signatures
                         .insert(name.clone(), return_type.clone());
+                    self.ctx.extern_functions.insert(name.clone());
                     let func_gen = FunctionGenerator::new(&*self.ctx);
                     func_gen.declare(name, params, return_type)?;
                 }
@@ -137,37 +142,9 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
                 // Enum 定义：在 to_llvm_type 时按需生成布局，这里跳过
                 Decl::Enum { .. } => {}
                 Decl::Import { .. } => {}
-                Decl::Var {
-                    name, ty, value, ..
-                } => {
-                    // Declare Global
-                    let llvm_ty = if let Some(t) = ty {
-                        t.to_llvm_type(&*self.ctx)?
-                    } else {
-                        // Must have type
-                        return Err(crate::error::CodegenError::UnsupportedFeature(format!(
-                            "Global {} missing type",
-                            name
-                        )));
-                    };
-
-                    let global = self.ctx.module.add_global(llvm_ty, None, name);
-                    self.ctx
-                        .global_var_types
-                        .insert(name.clone(), ty.clone().unwrap()); // ty is Some here
-
-                    // Set Initializer (Assume StructLiteral or Constant for now)
-                    if let lency_syntax::ast::ExprKind::StructLiteral { fields, .. } = &value.kind {
-                        if fields.is_empty() {
-                            // Initializer for empty struct
-                            if llvm_ty.is_struct_type() {
-                                let struct_ty = llvm_ty.into_struct_type();
-                                let const_val = struct_ty.const_named_struct(&[]);
-                                global.set_initializer(&const_val);
-                            }
-                        }
-                    }
-                    // TODO: Better global init handling
+                Decl::Var { name, ty, .. } => {
+                    let define = self.ctx.defines(index);
+                    self.declare_global(name, ty.as_ref(), define)?
                 }
             }
         }
@@ -177,7 +154,11 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
     /// 第二遍：生成函数体
     pub(crate) fn generate_function_bodies(&mut self, program: &Program) -> CodegenResult<()> {
         let mut func_gen = FunctionGenerator::new(&*self.ctx);
-        for decl in &program.decls {
+        for (index, decl) in program.decls.iter().enumerate() {
+            // 分模块编译时其他分区的函数只有声明
+            if !self.ctx.defines(index) {
+                continue;
+            }
             match decl {
                 Decl::Function {
                     name,
@@ -218,7 +199,10 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
     }
 
     /// Generate main wrapper if user main exists
-    pub(crate) fn generate_main_wrapper(&mut self) -> CodegenResult<()> {
+    pub(crate) fn generate_main_wrapper(&mut self, program: &Program) -> CodegenResult<()> {
+        if !self.ctx.defines_entry() {
+            return Ok(());
+        }
         if let Some(user_main) = self.ctx.module.get_function("__lency_main") {
             let i32_type = self.ctx.context.i32_type();
             let main_type = i32_type.fn_type(&[], false);
@@ -227,6 +211,11 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
             let entry = self.ctx.context.append_basic_block(main_func, "entry");
             self.ctx.builder.position_at_end(entry);
 
+            self.generate_gc_init()?;
+            self.generate_global_inits(program)?;
+
+            // 调用栈的根帧：main 没有调用点
+            crate::runtime::gen_trace_push(self.ctx, "main", SourceLocation::default());
             let call_inst = self
                 .ctx
                 .builder
@@ -262,4 +251,63 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
         }
         Ok(())
     }
+
+    /// 初始化垃圾回收器：以 main 栈帧中的局部变量作为栈底，并把全局变量注册为根
+    fn generate_gc_init(&mut self) -> CodegenResult<()> {
+        let context = self.ctx.context;
+        let i8_ptr_type = context.i8_type().ptr_type(inkwell::AddressSpace::default());
+        let void_type = context.void_type();
+
+        let init_type = void_type.fn_type(&[i8_ptr_type.into()], false);
+        let gc_init = self
+            .ctx
+            .module
+            .add_function("lency_gc_init", init_type, None);
+        let stack_bottom = self
+            .ctx
+            .builder
+            .build_alloca(context.i64_type(), "gc_stack_bottom")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        let stack_bottom = self
+            .ctx
+            .builder
+            .build_pointer_cast(stack_bottom, i8_ptr_type, "gc_stack_bottom_ptr")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        self.ctx
+            .builder
+            .build_call(gc_init, &[stack_bottom.into()], "")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+        if self.ctx.global_var_types.is_empty() {
+            return Ok(());
+        }
+        let add_root_type =
+            void_type.fn_type(&[i8_ptr_type.into(), context.i64_type().into()], false);
+        let add_root = self
+            .ctx
+            .module
+            .add_function("lency_gc_add_root", add_root_type, None);
+
+        let mut globals: Vec<(&String, &lency_syntax::ast::Type)> =
+            self.ctx.global_var_types.iter().collect();
+        globals.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, ty) in globals {
+            let (Some(global), Some(size)) = (
+                self.ctx.module.get_global(name),
+                ty.to_llvm_type(&*self.ctx)?.size_of(),
+            ) else {
+                continue;
+            };
+            let addr = self
+                .ctx
+                .builder
+                .build_pointer_cast(global.as_pointer_value(), i8_ptr_type, "gc_root")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            self.ctx
+                .builder
+                .build_call(add_root, &[addr.into(), size.into()], "")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,55 @@
+use super::ModuleGenerator;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::ExprGenerator;
+use crate::types::ToLLVMType;
+use lency_syntax::ast::{Decl, Program, Type};
+use std::collections::HashMap;
+
+impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
+    /// 声明全局变量：先以零值定义，初始值由 main 在调用用户代码前写入
+    ///
+    /// `define` 为 false 时（属于其他分区）只生成外部声明。
+    pub(crate) fn declare_global(
+        &mut self,
+        name: &str,
+        ty: Option<&Type>,
+        define: bool,
+    ) -> CodegenResult<()> {
+        // 类型检查会为未标注类型的全局变量填入推导出的类型
+        let Some(ty) = ty else {
+            return Err(CodegenError::UnsupportedFeature(format!(
+                "Global {} missing type",
+                name
+            )));
+        };
+        let llvm_ty = ty.to_llvm_type(&*self.ctx)?;
+        let global = self.ctx.module.add_global(llvm_ty, None, name);
+        if define {
+            global.set_initializer(&llvm_ty.const_zero());
+        }
+        self.ctx
+            .global_var_types
+            .insert(name.to_string(), ty.clone());
+        Ok(())
+    }
+
+    /// 按声明顺序计算全局变量的初始值（在 main 的入口块中，GC 初始化之后）
+    pub(crate) fn generate_global_inits(&mut self, program: &Program) -> CodegenResult<()> {
+        let locals = HashMap::new();
+        let expr_gen = ExprGenerator::new(&*self.ctx, &locals);
+        for decl in &program.decls {
+            let Decl::Var { name, value, .. } = decl else {
+                continue;
+            };
+            let Some(global) = self.ctx.module.get_global(name) else {
+                continue;
+            };
+            let value = expr_gen.generate(value)?;
+            self.ctx
+                .builder
+                .build_store(global.as_pointer_value(), value.value)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
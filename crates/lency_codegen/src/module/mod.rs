@@ -3,7 +3,9 @@
 //! 模块代码生成器，负责生成整个程序
 //! 逻辑分布：
 //! - types.rs: 负责类型注册（Struct/Enum/Result）和 Struct/Enum Body 生成
-//! - functions.rs: 负责函数声明、Globals 和函数体生成
+//! - functions.rs: 负责函数声明、函数体与 main 入口生成
+//! - globals.rs: 负责全局变量的声明与初始化
+//! - vtables.rs: 负责 Trait 方法表注册与 `dyn Trait` 的 vtable 生成
 
 use lency_syntax::ast::{Program, Type};
 
@@ -11,7 +13,9 @@ use crate::context::CodegenContext;
 use crate::error::CodegenResult;
 
 mod functions;
+mod globals;
 mod types;
+mod vtables;
 
 /// 模块代码生成器
 pub struct ModuleGenerator<'ctx, 'a> {
@@ -26,7 +30,7 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
 
     /// 生成整个程序
     pub fn generate(&mut self, program: &Program) -> CodegenResult<()> {
-        // 1. 注入运行时函数 (__lency_panic, printf, exit, malloc)
+        // 1. 注入运行时函数 (lency_panic_at, 调用栈追踪, lency_gc_alloc)
         self.inject_runtime()?;
 
         // 2. 第零遍：注册类型 (opaque)
@@ -68,13 +72,17 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
         self.define_enum_bodies(program)?;
 
         // 6. 第一遍：声明所有函数（支持前向引用）和 Globals
+        self.register_traits(program);
         self.declare_functions(program)?;
 
+        // 6.5 为 trait 实现生成 vtable（dyn Trait 动态分派）
+        self.generate_vtables(program)?;
+
         // 7. 第二遍：生成函数体
         self.generate_function_bodies(program)?;
 
         // 8. Generate main wrapper (entry point)
-        self.generate_main_wrapper()?;
+        self.generate_main_wrapper(program)?;
 
         Ok(())
     }
@@ -241,7 +241,8 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
                     let ret_ptr_type = enum_type.ptr_type(inkwell::AddressSpace::default());
                     let fn_type = ret_ptr_type.fn_type(&llvm_param_types, false);
 
-                    let function = self.ctx.module.add_function(&ctor_name, fn_type, None);
+                    let linkage = self.ctx.helper_linkage();
+                    let function = self.ctx.module.add_function(&ctor_name, fn_type, linkage);
 
                     // Generate Body
                     let basic_block = self.ctx.context.append_basic_block(function, "entry");
@@ -249,7 +250,11 @@ impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
 
                     // 1. Malloc Enum (Heap) 而不是 Alloca，以便返回指针
                     let size = enum_type.size_of().unwrap();
-                    let malloc = self.ctx.module.get_function("malloc").unwrap();
+                    let malloc = self
+                        .ctx
+                        .module
+                        .get_function(crate::runtime::ALLOC_FN)
+                        .unwrap();
                     let malloc_call = self
                         .ctx
                         .builder
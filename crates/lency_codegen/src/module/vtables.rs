@@ -0,0 +1,81 @@
+use super::ModuleGenerator;
+use crate::error::{CodegenError, CodegenResult};
+use crate::types::vtable_name;
+use inkwell::module::Linkage;
+use inkwell::values::BasicValue;
+use inkwell::AddressSpace;
+use lency_syntax::ast::{Decl, Program, Type};
+
+impl<'ctx, 'a> ModuleGenerator<'ctx, 'a> {
+    /// 注册 Trait 方法表（方法顺序即 vtable 槽位顺序）
+    pub(crate) fn register_traits(&mut self, program: &Program) {
+        for decl in &program.decls {
+            if let Decl::Trait {
+                name,
+                generic_params,
+                methods,
+                ..
+            } = decl
+            {
+                // 泛型 Trait 不能作为 dyn Trait 使用（sema 已检查）
+                if !generic_params.is_empty() {
+                    continue;
+                }
+                let entries = methods
+                    .iter()
+                    .map(|m| {
+                        let params = m.params.iter().map(|p| p.ty.clone()).collect();
+                        (m.name.clone(), params, m.return_type.clone())
+                    })
+                    .collect();
+                self.ctx.trait_methods.insert(name.clone(), entries);
+            }
+        }
+    }
+
+    /// 为每个 `impl Trait for Type` 生成 vtable 全局常量（需在函数声明之后）
+    pub(crate) fn generate_vtables(&mut self, program: &Program) -> CodegenResult<()> {
+        let i8_ptr_type = self.ctx.context.i8_type().ptr_type(AddressSpace::default());
+
+        for decl in &program.decls {
+            let Decl::Impl {
+                trait_ref: Some(Type::Struct(trait_name)),
+                type_name,
+                ..
+            } = decl
+            else {
+                continue;
+            };
+            let Some(methods) = self.ctx.trait_methods.get(trait_name) else {
+                continue;
+            };
+
+            let type_str = lency_monomorph::mangling::mangle_type(type_name);
+            let mut slots = Vec::with_capacity(methods.len());
+            for (method, _, _) in methods {
+                let mangled_name = format!("{}_{}", type_str, method);
+                let function = self
+                    .ctx
+                    .module
+                    .get_function(&mangled_name)
+                    .ok_or_else(|| CodegenError::FunctionNotFound(mangled_name.clone()))?;
+                slots.push(
+                    function
+                        .as_global_value()
+                        .as_pointer_value()
+                        .const_cast(i8_ptr_type),
+                );
+            }
+
+            let vtable_type = i8_ptr_type.array_type(slots.len() as u32);
+            let global =
+                self.ctx
+                    .module
+                    .add_global(vtable_type, None, &vtable_name(&type_str, trait_name));
+            global.set_initializer(&i8_ptr_type.const_array(&slots).as_basic_value_enum());
+            global.set_constant(true);
+            global.set_linkage(Linkage::Private);
+        }
+        Ok(())
+    }
+}
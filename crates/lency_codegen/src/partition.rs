@@ -0,0 +1,35 @@
+//! Partition
+//!
+//! 分模块代码生成：每个源文件生成一个独立的 LLVM Module，分别编译为目标文件后再链接。
+//!
+//! 每个 Module 都声明程序中的全部类型、函数与全局变量，但只定义本分区拥有的声明；
+//! 入口分区额外生成 `main` 包装函数。枚举构造函数与闭包在各 Module 中各自生成，
+//! 使用内部链接，避免链接时符号重复。
+
+use std::collections::HashSet;
+
+/// 一个 LLVM Module 负责定义的声明（`program.decls` 中的下标）
+#[derive(Debug, Clone, Default)]
+pub struct Partition {
+    decls: HashSet<usize>,
+    entry: bool,
+}
+
+impl Partition {
+    /// `entry` 为 true 时本分区包含 `main` 包装函数与全局变量初始化
+    pub fn new(decls: impl IntoIterator<Item = usize>, entry: bool) -> Self {
+        Self {
+            decls: decls.into_iter().collect(),
+            entry,
+        }
+    }
+
+    /// 是否定义第 `index` 个声明
+    pub fn owns(&self, index: usize) -> bool {
+        self.decls.contains(&index)
+    }
+
+    pub fn is_entry(&self) -> bool {
+        self.entry
+    }
+}
@@ -1,195 +1,231 @@
 use inkwell::context::Context;
 use inkwell::module::Module;
 
-use inkwell::values::{FunctionValue, PointerValue};
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::values::{BasicMetadataValueEnum, FunctionValue, PointerValue};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
 
-/// 注入运行时函数 (panic, printf, exit)
+use crate::context::CodegenContext;
+use crate::source_map::SourceLocation;
+
+/// 堆分配函数：由 lency_runtime 的垃圾回收器提供，替代 malloc
+pub const ALLOC_FN: &str = "lency_gc_alloc";
+
+/// 带源码位置的 panic：lency_runtime 打印消息、位置与 Lency 调用栈后退出
+pub const PANIC_FN: &str = "lency_panic_at";
+/// 影子调用栈：进入 / 离开被调函数
+pub const TRACE_PUSH_FN: &str = "lency_trace_push";
+pub const TRACE_POP_FN: &str = "lency_trace_pop";
+
+/// 注入运行时函数声明 (panic, 调用栈追踪)，返回 panic 函数
 pub fn inject_runtime_functions<'ctx>(
     context: &'ctx Context,
     module: &Module<'ctx>,
 ) -> FunctionValue<'ctx> {
-    // 1. Declare printf: i32 printf(i8*, ...)
     let i32_type = context.i32_type();
     let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::default());
+    let void_type = context.void_type();
 
-    let printf_type = i32_type.fn_type(&[i8_ptr_type.into()], true);
-    let printf_func = module.add_function("printf", printf_type, None);
+    // void lency_panic_at(i8* msg, i8* file, i32 line, i32 column) noreturn
+    let location_params = [
+        i8_ptr_type.into(),
+        i8_ptr_type.into(),
+        i32_type.into(),
+        i32_type.into(),
+    ];
+    let panic_type = void_type.fn_type(&location_params, false);
+    let panic_func = module.add_function(PANIC_FN, panic_type, None);
+    let noreturn = context.create_enum_attribute(Attribute::get_named_enum_kind_id("noreturn"), 0);
+    panic_func.add_attribute(AttributeLoc::Function, noreturn);
+
+    // void lency_trace_push(i8* function, i8* file, i32 line, i32 column)
+    module.add_function(TRACE_PUSH_FN, panic_type, None);
+    // void lency_trace_pop()
+    module.add_function(TRACE_POP_FN, void_type.fn_type(&[], false), None);
 
-    // 2. Declare exit: void exit(i32)
-    let void_type = context.void_type();
-    let exit_type = void_type.fn_type(&[i32_type.into()], false);
-    let exit_func = module.add_function("exit", exit_type, None);
-
-    // 3. Define __lency_panic(msg: i8*, line: i32)
-    let panic_type = void_type.fn_type(&[i8_ptr_type.into(), i32_type.into()], false);
-    let panic_func = module.add_function("__lency_panic", panic_type, None);
-
-    // Create entry block for panic
-    let builder = context.create_builder();
-    let entry = context.append_basic_block(panic_func, "entry");
-    builder.position_at_end(entry);
-
-    // Get args
-    let msg = panic_func.get_nth_param(0).unwrap().into_pointer_value();
-    let line = panic_func.get_nth_param(1).unwrap().into_int_value();
-
-    // Call printf("Panic at line %d: %s\n", line, msg)
-    // We need a format string global
-    let format_str = "Panic at line %d: %s\n";
-    let format_global = builder
-        .build_global_string_ptr(format_str, "panic_fmt")
-        .unwrap();
+    panic_func
+}
 
-    // printf args: format, line, msg
-    // Note: printf arg order in C is (fmt, ...). Here: fmt, line, msg.
-    builder
-        .build_call(
-            printf_func,
-            &[
-                format_global.as_pointer_value().into(),
-                line.into(),
-                msg.into(),
-            ],
-            "call_printf",
-        )
-        .unwrap();
+/// 源码位置参数：(i8* file, i32 line, i32 column)
+fn location_args<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    loc: SourceLocation,
+) -> [BasicMetadataValueEnum<'ctx>; 3] {
+    let i32_type = ctx.context.i32_type();
+    let file = ctx.sources.path(loc.file).to_string_lossy().into_owned();
+    [
+        ctx.const_string(&file).into(),
+        i32_type.const_int(loc.line as u64, false).into(),
+        i32_type.const_int(loc.column as u64, false).into(),
+    ]
+}
 
-    // Call exit(1)
-    let exit_code = i32_type.const_int(1, false);
-    builder
-        .build_call(exit_func, &[exit_code.into()], "call_exit")
+/// 调用 Lency 函数前压入调用栈帧：被调函数名与调用点
+pub fn gen_trace_push<'ctx>(ctx: &CodegenContext<'ctx>, function: &str, loc: SourceLocation) {
+    if !ctx.call_traces {
+        return;
+    }
+    let Some(push_fn) = ctx.module.get_function(TRACE_PUSH_FN) else {
+        return;
+    };
+    let [file, line, column] = location_args(ctx, loc);
+    let name = ctx.const_string(function).into();
+    ctx.builder
+        .build_call(push_fn, &[name, file, line, column], "")
         .unwrap();
+}
 
-    // Unreachable
-    builder.build_return(None).unwrap();
-
-    panic_func
+/// 被调函数返回后弹出调用栈帧
+pub fn gen_trace_pop(ctx: &CodegenContext<'_>) {
+    if !ctx.call_traces {
+        return;
+    }
+    if let Some(pop_fn) = ctx.module.get_function(TRACE_POP_FN) {
+        ctx.builder.build_call(pop_fn, &[], "").unwrap();
+    }
 }
 
 /// 如果 ptr 为空，调用 panic
 pub fn gen_null_check<'ctx>(
-    context: &'ctx Context,
-    builder: &inkwell::builder::Builder<'ctx>,
-    panic_func: FunctionValue<'ctx>,
+    ctx: &CodegenContext<'ctx>,
     ptr: PointerValue<'ctx>,
-    line: u32,
+    loc: SourceLocation,
 ) {
+    let builder = &ctx.builder;
     let null_ptr = ptr.get_type().const_null();
     let ptr_int = builder
-        .build_ptr_to_int(ptr, context.i64_type(), "ptr_int")
+        .build_ptr_to_int(ptr, ctx.context.i64_type(), "ptr_int")
         .unwrap();
     let null_int = builder
-        .build_ptr_to_int(null_ptr, context.i64_type(), "null_int")
+        .build_ptr_to_int(null_ptr, ctx.context.i64_type(), "null_int")
         .unwrap();
 
     let is_null = builder
         .build_int_compare(IntPredicate::EQ, ptr_int, null_int, "is_null")
         .unwrap();
 
-    let current_block = builder.get_insert_block().unwrap();
-    let panic_block = context.append_basic_block(current_block.get_parent().unwrap(), "null_panic");
-    let cont_block = context.append_basic_block(current_block.get_parent().unwrap(), "null_cont");
-
-    builder
-        .build_conditional_branch(is_null, panic_block, cont_block)
-        .unwrap();
-
-    // Panic block
-    builder.position_at_end(panic_block);
-    let msg_global = builder
-        .build_global_string_ptr("Null Reference Error", "null_msg")
-        .unwrap();
-    let line_val = context.i32_type().const_int(line as u64, false);
-    builder
-        .build_call(
-            panic_func,
-            &[msg_global.as_pointer_value().into(), line_val.into()],
-            "",
-        )
-        .unwrap();
-    builder.build_unreachable().unwrap();
-
-    // Continue block
-    builder.position_at_end(cont_block);
+    gen_panic_if(ctx, is_null, "Null Reference Error", "null", loc);
 }
 
 /// 数组越界检查 panic_if(index >= len) (unsigned compare covers negative)
 pub fn gen_bounds_check<'ctx>(
-    context: &'ctx Context,
-    builder: &inkwell::builder::Builder<'ctx>,
-    panic_func: FunctionValue<'ctx>,
+    ctx: &CodegenContext<'ctx>,
     index: inkwell::values::IntValue<'ctx>,
     len: inkwell::values::IntValue<'ctx>,
-    line: u32,
+    loc: SourceLocation,
 ) {
     // Treat as unsigned check: UGE (Unsigned Greater or Equal)
     // If index is negative, it becomes very large positive, >= len.
     // If index >= len, it triggers.
-    let is_out_of_bounds = builder
+    let is_out_of_bounds = ctx
+        .builder
         .build_int_compare(IntPredicate::UGE, index, len, "is_out_of_bounds")
         .unwrap();
 
-    let current_block = builder.get_insert_block().unwrap();
-    let panic_block =
-        context.append_basic_block(current_block.get_parent().unwrap(), "bounds_panic");
-    let cont_block = context.append_basic_block(current_block.get_parent().unwrap(), "bounds_cont");
+    gen_panic_if(ctx, is_out_of_bounds, "Index Out of Bounds", "bounds", loc);
+}
 
-    builder
-        .build_conditional_branch(is_out_of_bounds, panic_block, cont_block)
+/// 条件成立时 panic，否则继续在新的基本块中生成代码
+pub fn gen_panic_if<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    condition: inkwell::values::IntValue<'ctx>,
+    msg: &str,
+    block_prefix: &str,
+    loc: SourceLocation,
+) {
+    let current_block = ctx.builder.get_insert_block().unwrap();
+    let function = current_block.get_parent().unwrap();
+    let panic_block = ctx
+        .context
+        .append_basic_block(function, &format!("{}_panic", block_prefix));
+    let cont_block = ctx
+        .context
+        .append_basic_block(function, &format!("{}_cont", block_prefix));
+
+    ctx.builder
+        .build_conditional_branch(condition, panic_block, cont_block)
         .unwrap();
 
     // Panic block
-    builder.position_at_end(panic_block);
-    let msg_global = builder
-        .build_global_string_ptr("Index Out of Bounds", "bounds_msg")
-        .unwrap();
-    let line_val = context.i32_type().const_int(line as u64, false);
-    builder
-        .build_call(
-            panic_func,
-            &[msg_global.as_pointer_value().into(), line_val.into()],
-            "",
-        )
-        .unwrap();
-    builder.build_unreachable().unwrap();
+    ctx.builder.position_at_end(panic_block);
+    gen_panic(ctx, msg, loc);
 
     // Continue block
-    builder.position_at_end(cont_block);
+    ctx.builder.position_at_end(cont_block);
 }
 
 /// 生成 panic 调用
-pub fn gen_panic<'ctx>(
-    context: &'ctx Context,
-    builder: &inkwell::builder::Builder<'ctx>,
-    panic_func: FunctionValue<'ctx>,
-    msg: &str,
-    line: u32,
-) {
-    let msg_global = builder.build_global_string_ptr(msg, "panic_msg").unwrap();
-    let line_val = context.i32_type().const_int(line as u64, false);
-    builder
-        .build_call(
-            panic_func,
-            &[msg_global.as_pointer_value().into(), line_val.into()],
-            "",
-        )
-        .unwrap();
-    builder.build_unreachable().unwrap();
+pub fn gen_panic(ctx: &CodegenContext<'_>, msg: &str, loc: SourceLocation) {
+    let msg_ptr = ctx.const_string(msg);
+    gen_panic_val(ctx, msg_ptr.into(), loc);
 }
 
 /// 生成 panic 调用 (动态消息)
 pub fn gen_panic_val<'ctx>(
-    context: &'ctx Context,
-    builder: &inkwell::builder::Builder<'ctx>,
-    panic_func: FunctionValue<'ctx>,
+    ctx: &CodegenContext<'ctx>,
     msg_val: inkwell::values::BasicValueEnum<'ctx>,
-    line: u32,
+    loc: SourceLocation,
 ) {
-    let line_val = context.i32_type().const_int(line as u64, false);
-    builder
-        .build_call(panic_func, &[msg_val.into(), line_val.into()], "")
-        .unwrap();
-    builder.build_unreachable().unwrap();
+    if let Some(panic_func) = ctx.panic_func {
+        let [file, line, column] = location_args(ctx, loc);
+        ctx.builder
+            .build_call(panic_func, &[msg_val.into(), file, line, column], "")
+            .unwrap();
+    }
+    ctx.builder.build_unreachable().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TRACE_POP_FN, TRACE_PUSH_FN};
+    use crate::source_map::SourceMap;
+
+    const SOURCE: &str = "int twice(int x) {
+    return x * 2
+}
+
+int main() {
+    return twice(21)
+}
+";
+
+    fn compile(debug_checks: bool) -> String {
+        let program = lency_syntax::parser::parse_spanned(SOURCE).expect("parse failed");
+        let sources = SourceMap::new("trace.lcy", SOURCE);
+        crate::compile_to_ir_with_sources(&program, "trace", sources, false, debug_checks)
+            .expect("codegen failed")
+    }
+
+    #[test]
+    fn test_call_traces_only_in_debug_builds() {
+        let push_call = format!("call void @{}(", TRACE_PUSH_FN);
+        let pop_call = format!("call void @{}()", TRACE_POP_FN);
+
+        let debug = compile(true);
+        assert!(
+            debug.contains(&push_call),
+            "missing trace push in:\n{}",
+            debug
+        );
+        assert!(
+            debug.contains(&pop_call),
+            "missing trace pop in:\n{}",
+            debug
+        );
+
+        // release：调用不再维护调用栈
+        let release = compile(false);
+        assert!(
+            !release.contains(&push_call),
+            "unexpected trace push in:\n{}",
+            release
+        );
+        assert!(
+            !release.contains(&pop_call),
+            "unexpected trace pop in:\n{}",
+            release
+        );
+        assert!(release.contains("call i64 @twice("));
+    }
 }
@@ -0,0 +1,148 @@
+//! Source Map
+//!
+//! 字节偏移 -> 源文件、行、列的映射。
+//!
+//! 导入模块的声明被合并进同一个程序，它们的 Span 是相对各自源文件的偏移，
+//! 因此需要借助语义分析的 `module_origins`（全局名称 -> 模块路径）找回声明所在的文件。
+//! 调试信息与运行时 panic 位置都使用这里的映射。
+
+use lency_syntax::ast::Span;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// 源码位置：`file` 为 `SourceMap` 中的文件下标，行列从 1 开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SourceLocation {
+    pub file: usize,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// 一个源文件及其行首偏移表
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    fn new(path: PathBuf, source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { path, line_starts }
+    }
+
+    /// 字节偏移 -> (行, 列)，均从 1 开始
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next.saturating_sub(1),
+        };
+        let column = offset - self.line_starts.get(line).copied().unwrap_or(0);
+        (line as u32 + 1, column as u32 + 1)
+    }
+}
+
+/// 程序涉及的全部源文件（下标 0 为入口文件）
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    /// 模块路径 ("a.b") -> 文件下标
+    module_files: HashMap<String, usize>,
+    /// 全局名称 -> 定义它的模块路径
+    origins: HashMap<String, String>,
+}
+
+impl SourceMap {
+    /// 只有入口文件的映射
+    pub fn new(path: impl Into<PathBuf>, source: &str) -> Self {
+        Self {
+            files: vec![SourceFile::new(path.into(), source)],
+            module_files: HashMap::new(),
+            origins: HashMap::new(),
+        }
+    }
+
+    /// 登记导入模块的源文件
+    pub fn with_module(mut self, module: &str, path: impl Into<PathBuf>, source: &str) -> Self {
+        self.module_files
+            .insert(module.to_string(), self.files.len());
+        self.files.push(SourceFile::new(path.into(), source));
+        self
+    }
+
+    /// 设置全局名称的来源模块（语义分析的 `module_origins`）
+    pub fn with_origins(mut self, origins: HashMap<String, String>) -> Self {
+        self.origins = origins;
+        self
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// 文件路径（下标越界时回退到入口文件）
+    pub fn path(&self, file: usize) -> &Path {
+        self.files
+            .get(file)
+            .unwrap_or(&self.files[0])
+            .path
+            .as_path()
+    }
+
+    /// 定义 `owner`（函数名或方法所属类型名）的文件下标；未知时为入口文件
+    pub fn file_of(&self, owner: &str) -> usize {
+        self.origins
+            .get(owner)
+            .and_then(|module| self.module_files.get(module))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// `file` 中 `span` 起点的位置
+    pub fn location(&self, file: usize, span: &Span) -> SourceLocation {
+        let (line, column) = self
+            .files
+            .get(file)
+            .unwrap_or(&self.files[0])
+            .position(span.start);
+        SourceLocation { file, line, column }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_of_offsets() {
+        let map = SourceMap::new("main.lcy", "int main() {\n    return 0\n}\n");
+        assert_eq!(
+            map.location(0, &(0..3)),
+            SourceLocation {
+                file: 0,
+                line: 1,
+                column: 1
+            }
+        );
+        let loc = map.location(0, &(17..23));
+        assert_eq!((loc.line, loc.column), (2, 5));
+        // 换行符之后的第一个字节属于下一行
+        let loc = map.location(0, &(13..14));
+        assert_eq!((loc.line, loc.column), (2, 1));
+    }
+
+    #[test]
+    fn test_file_of_imported_definition() {
+        let origins = HashMap::from([("helper".to_string(), "util".to_string())]);
+        let map = SourceMap::new("main.lcy", "")
+            .with_module("util", "util.lcy", "\n\nint helper() {}\n")
+            .with_origins(origins);
+
+        assert_eq!(map.file_of("helper"), 1);
+        assert_eq!(map.file_of("main"), 0);
+        assert_eq!(map.path(1), Path::new("util.lcy"));
+        assert_eq!(map.location(1, &(2..5)).line, 3);
+    }
+}
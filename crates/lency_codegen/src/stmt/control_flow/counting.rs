@@ -0,0 +1,123 @@
+//! Range Counting Loop
+//!
+//! `for i in a..b` / `for i in a..=b`：只用一个 i64 计数器，不构造区间也不分配内存。
+//! 先判断区间是否为空，再在到达最后一个值时退出，`a..=i64::MAX` 也不会溢出。
+
+use inkwell::values::FunctionValue;
+use inkwell::IntPredicate;
+use lency_syntax::ast::{Stmt, Type};
+
+use super::iterate::gen_loop_body;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::numeric::range::RangeBounds;
+use crate::stmt::StmtGenerator;
+
+/// 生成区间计数循环
+pub(super) fn gen_counting_loop<'ctx>(
+    gen: &mut StmtGenerator<'ctx, '_>,
+    function: FunctionValue<'ctx>,
+    iterator: &str,
+    bounds: RangeBounds<'ctx>,
+    body: &[Stmt],
+) -> CodegenResult<()> {
+    let builder = &gen.ctx.builder;
+    let i64_type = gen.ctx.context.i64_type();
+    let RangeBounds {
+        start,
+        end,
+        inclusive,
+    } = bounds;
+
+    // 非空：a < b 或 a <= b；最后一个值：b - 1 或 b（非空时 b - 1 不会溢出）
+    let less = builder
+        .build_int_compare(IntPredicate::SLT, start, end, "range_lt")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let less_eq = builder
+        .build_int_compare(IntPredicate::SLE, start, end, "range_le")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let non_empty = builder
+        .build_select(inclusive, less_eq, less, "range_non_empty")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_int_value();
+    let end_minus_one = builder
+        .build_int_sub(end, i64_type.const_int(1, false), "range_end_dec")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let last = builder
+        .build_select(inclusive, end, end_minus_one, "range_last")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_int_value();
+
+    // 计数器与循环变量分开：循环体修改循环变量不影响迭代次数
+    let counter = builder
+        .build_alloca(i64_type, "range_counter")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    builder
+        .build_store(counter, start)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let iter_alloca = builder
+        .build_alloca(i64_type, iterator)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    let body_bb = gen
+        .ctx
+        .context
+        .append_basic_block(function, "forrange.body");
+    let inc_bb = gen.ctx.context.append_basic_block(function, "forrange.inc");
+    let step_bb = gen
+        .ctx
+        .context
+        .append_basic_block(function, "forrange.step");
+    let after_bb = gen.ctx.context.append_basic_block(function, "forrange.end");
+    builder
+        .build_conditional_branch(non_empty, body_bb, after_bb)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    // Body
+    builder.position_at_end(body_bb);
+    let current = builder
+        .build_load(i64_type, counter, "range_i")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    builder
+        .build_store(iter_alloca, current)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    let old_local = gen
+        .locals
+        .insert(iterator.to_string(), (iter_alloca, Type::Int));
+
+    gen_loop_body(gen, body, inc_bb, after_bb)?;
+
+    // Restore locals
+    if let Some(old) = old_local {
+        gen.locals.insert(iterator.to_string(), old);
+    } else {
+        gen.locals.remove(iterator);
+    }
+
+    // Increment：已到最后一个值则退出
+    let builder = &gen.ctx.builder;
+    builder.position_at_end(inc_bb);
+    let current = builder
+        .build_load(i64_type, counter, "range_i")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .into_int_value();
+    let done = builder
+        .build_int_compare(IntPredicate::EQ, current, last, "range_done")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    builder
+        .build_conditional_branch(done, after_bb, step_bb)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    builder.position_at_end(step_bb);
+    let next = builder
+        .build_int_add(current, i64_type.const_int(1, false), "range_next")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    builder
+        .build_store(counter, next)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    builder
+        .build_unconditional_branch(body_bb)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    builder.position_at_end(after_bb);
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! Pattern Statements
+//!
+//! `var` 解构、`if let` 与 `while let`：与 `match` 共用 `gen_pattern_check` 生成的决策树。
+//! 匹配成功后把绑定的变量加入局部变量表；`if let` / `while let` 的绑定离开分支后
+//! 恢复被遮蔽的同名变量。
+
+use inkwell::values::PointerValue;
+use lency_syntax::ast::{Expr, MatchPattern, Stmt, Type};
+
+use super::iterate::gen_loop_body;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::match_expr::pattern::gen_pattern_check;
+use crate::expr::ExprGenerator;
+use crate::stmt::StmtGenerator;
+
+type Shadowed<'ctx> = Vec<(String, Option<(PointerValue<'ctx>, Type)>)>;
+
+/// 生成 `var Pair { key, value } = p`
+pub fn gen_var_pattern<'ctx, 'a>(
+    gen: &mut StmtGenerator<'ctx, 'a>,
+    pattern: &MatchPattern,
+    value: &Expr,
+) -> CodegenResult<()> {
+    let function = gen
+        .ctx
+        .builder
+        .get_insert_block()
+        .and_then(|bb| bb.get_parent())
+        .ok_or_else(|| CodegenError::LLVMBuildError("not in a function".to_string()))?;
+
+    let subject = ExprGenerator::new(gen.ctx, gen.locals).generate(value)?;
+
+    // sema 保证模式不可反驳，不匹配的分支不可达
+    let fail_bb = gen
+        .ctx
+        .context
+        .append_basic_block(function, "destructure.fail");
+    let mut bindings = Vec::new();
+    gen_pattern_check(
+        gen.ctx,
+        gen.locals,
+        pattern,
+        subject,
+        &mut bindings,
+        fail_bb,
+    )?;
+    let matched_bb = gen.ctx.builder.get_insert_block().unwrap();
+
+    gen.ctx.builder.position_at_end(fail_bb);
+    gen.ctx
+        .builder
+        .build_unreachable()
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    gen.ctx.builder.position_at_end(matched_bb);
+
+    for (name, ptr, ty) in bindings {
+        if let Some(debug) = &gen.ctx.debug {
+            debug.declare_variable(gen.ctx, ptr, &name, &ty, None);
+        }
+        gen.locals.insert(name, (ptr, ty));
+    }
+    Ok(())
+}
+
+/// 生成 `if let pat = value { ... } else { ... }`
+pub fn gen_if_let<'ctx, 'a>(
+    gen: &mut StmtGenerator<'ctx, 'a>,
+    pattern: &MatchPattern,
+    value: &Expr,
+    then_block: &[Stmt],
+    else_block: Option<&[Stmt]>,
+) -> CodegenResult<()> {
+    let function = gen
+        .ctx
+        .builder
+        .get_insert_block()
+        .and_then(|bb| bb.get_parent())
+        .ok_or_else(|| CodegenError::LLVMBuildError("not in a function".to_string()))?;
+
+    let subject = ExprGenerator::new(gen.ctx, gen.locals).generate(value)?;
+
+    let else_bb = gen.ctx.context.append_basic_block(function, "iflet.else");
+
+    // 匹配成功时 builder 停在 then 分支的入口
+    let mut bindings = Vec::new();
+    gen_pattern_check(
+        gen.ctx,
+        gen.locals,
+        pattern,
+        subject,
+        &mut bindings,
+        else_bb,
+    )?;
+    let shadowed = bind_locals(gen, bindings);
+    gen.generate_block(then_block)?;
+    restore_locals(gen, shadowed);
+    let then_end = gen.ctx.builder.get_insert_block().unwrap();
+    let then_falls_through = !gen.block_ends_with_terminator(then_end);
+
+    // Else 分支
+    gen.ctx.builder.position_at_end(else_bb);
+    if let Some(else_stmts) = else_block {
+        gen.generate_block(else_stmts)?;
+    }
+    let else_end = gen.ctx.builder.get_insert_block().unwrap();
+    let else_falls_through = !gen.block_ends_with_terminator(else_end);
+
+    // 两个分支都以 return/break 等结束时没有合并点：builder 停在已终结的块上，
+    // 之后的语句不可达，不再生成
+    if !then_falls_through && !else_falls_through {
+        return Ok(());
+    }
+    let merge_bb = gen.ctx.context.append_basic_block(function, "iflet.end");
+    for (end, falls_through) in [
+        (then_end, then_falls_through),
+        (else_end, else_falls_through),
+    ] {
+        if falls_through {
+            gen.ctx.builder.position_at_end(end);
+            gen.ctx
+                .builder
+                .build_unconditional_branch(merge_bb)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        }
+    }
+
+    gen.ctx.builder.position_at_end(merge_bb);
+    Ok(())
+}
+
+/// 生成 `while let pat = value { ... }`：每轮重新求值 value，不匹配时结束循环
+pub fn gen_while_let<'ctx, 'a>(
+    gen: &mut StmtGenerator<'ctx, 'a>,
+    pattern: &MatchPattern,
+    value: &Expr,
+    body: &[Stmt],
+) -> CodegenResult<()> {
+    let function = gen
+        .ctx
+        .builder
+        .get_insert_block()
+        .and_then(|bb| bb.get_parent())
+        .ok_or_else(|| CodegenError::LLVMBuildError("not in a function".to_string()))?;
+
+    let cond_bb = gen
+        .ctx
+        .context
+        .append_basic_block(function, "whilelet.cond");
+    let after_bb = gen.ctx.context.append_basic_block(function, "whilelet.end");
+    gen.ctx
+        .builder
+        .build_unconditional_branch(cond_bb)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    // 条件块：求值并匹配，continue 也回到这里
+    gen.ctx.builder.position_at_end(cond_bb);
+    let subject = ExprGenerator::new(gen.ctx, gen.locals).generate(value)?;
+    let mut bindings = Vec::new();
+    gen_pattern_check(
+        gen.ctx,
+        gen.locals,
+        pattern,
+        subject,
+        &mut bindings,
+        after_bb,
+    )?;
+
+    let shadowed = bind_locals(gen, bindings);
+    gen_loop_body(gen, body, cond_bb, after_bb)?;
+    restore_locals(gen, shadowed);
+
+    gen.ctx.builder.position_at_end(after_bb);
+    Ok(())
+}
+
+/// 把模式变量加入局部变量表，返回被遮蔽的同名变量
+fn bind_locals<'ctx>(
+    gen: &mut StmtGenerator<'ctx, '_>,
+    bindings: Vec<(String, PointerValue<'ctx>, Type)>,
+) -> Shadowed<'ctx> {
+    bindings
+        .into_iter()
+        .map(|(name, ptr, ty)| {
+            let old = gen.locals.insert(name.clone(), (ptr, ty));
+            (name, old)
+        })
+        .collect()
+}
+
+fn restore_locals<'ctx>(gen: &mut StmtGenerator<'ctx, '_>, shadowed: Shadowed<'ctx>) {
+    for (name, old) in shadowed.into_iter().rev() {
+        match old {
+            Some(old) => gen.locals.insert(name, old),
+            None => gen.locals.remove(&name),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source_map::SourceMap;
+
+    fn compile(source: &str) -> String {
+        let mut program = lency_syntax::parser::parse_spanned(source).expect("parse failed");
+        lency_sema::analyze(&mut program).expect("sema failed");
+        let program = lency_monomorph::Monomorphizer::new().process(program);
+        let sources = SourceMap::new("destructure.lcy", source);
+        crate::compile_to_ir_with_sources(&program, "destructure", sources, false, true)
+            .expect("codegen failed")
+    }
+
+    #[test]
+    fn test_if_let_with_both_arms_returning() {
+        let ir = compile(
+            "enum Slot { Full(int), Empty }
+
+int first_or(Slot slot, int fallback) {
+    if let Slot.Full(x) = slot {
+        return x
+    } else {
+        return fallback
+    }
+}
+
+int main() {
+    return first_or(Slot.Full(7), 0)
+}
+",
+        );
+        // 两个分支都返回时不生成没有前驱的合并块
+        assert!(
+            !ir.contains("iflet.end"),
+            "unexpected merge block in:\n{}",
+            ir
+        );
+        assert!(ir.contains("iflet.else"));
+    }
+
+    #[test]
+    fn test_if_let_falling_through_merges() {
+        let ir = compile(
+            "enum Slot { Full(int), Empty }
+
+int value_or(Slot slot, int fallback) {
+    var result = fallback
+    if let Slot.Full(x) = slot {
+        result = x
+    }
+    return result
+}
+
+int main() {
+    return value_or(Slot.Empty, 3)
+}
+",
+        );
+        assert!(ir.contains("iflet.end"));
+    }
+}
@@ -1,9 +1,29 @@
+//! For-in Loop Code Generation
+//!
+//! 数组、字符串与 `Vec<T>` 按下标遍历；区间用计数循环（见 `counting`）；
+//! 其他类型走迭代器协议（见 `iterate`），与 sema 的 `type_check::stmt::iteration` 选择顺序一致。
+
 use crate::error::{CodegenError, CodegenResult};
-use crate::expr::ExprGenerator;
-use lency_syntax::ast::{Expr, Stmt};
+use crate::expr::numeric::range;
+use crate::expr::string::chars;
+use crate::expr::{vec, ExprGenerator};
+use inkwell::types::ArrayType;
+use inkwell::values::{BasicValueEnum, IntValue, PointerValue};
+use lency_syntax::ast::{Expr, ExprKind, Stmt, Type};
 
+use super::{counting, iterate};
 use crate::stmt::{LoopContext, StmtGenerator};
 
+/// for-in 的迭代来源
+enum IterSource<'ctx> {
+    /// 定长数组（已存到栈上以便 GEP）
+    Array(PointerValue<'ctx>, ArrayType<'ctx>),
+    /// 字符串解码后的码点 Vec 及其长度
+    Chars(PointerValue<'ctx>, IntValue<'ctx>),
+    /// Vec：每轮重新读取长度，循环体中增删元素也不会越界
+    Vec(PointerValue<'ctx>),
+}
+
 /// 生成 for-in 循环
 pub fn gen_for_in<'ctx, 'a>(
     gen: &mut StmtGenerator<'ctx, 'a>,
@@ -19,32 +39,74 @@ pub fn gen_for_in<'ctx, 'a>(
         .and_then(|bb| bb.get_parent())
         .ok_or_else(|| CodegenError::LLVMBuildError("not in a function".to_string()))?;
 
+    // 区间字面量：直接用两端计数，不构造区间值
+    if let ExprKind::Range {
+        start,
+        end,
+        inclusive,
+    } = &iterable.kind
+    {
+        let bounds = range::gen_bounds(gen.ctx, gen.locals, start, end, *inclusive)?;
+        return counting::gen_counting_loop(gen, function, iterator, bounds, body);
+    }
+
     // 1. Evaluate iterable
     let expr_gen = ExprGenerator::new(gen.ctx, gen.locals);
-    let array_val = expr_gen.generate(iterable)?;
+    let mut iterable_val = expr_gen.generate(iterable)?;
+    let loc = gen.ctx.location(&iterable.span);
+    let i64_type = gen.ctx.context.i64_type();
 
-    // Safety check: must be array type (Sema ensured this)
-    if !array_val.value.is_array_value() {
-        return Err(CodegenError::LLVMBuildError(
-            "For-in iterable must be an array".to_string(),
-        ));
+    // 没有 next() 但有 iter() 的类型：先取得迭代器
+    if let Type::Struct(name) = &iterable_val.ty {
+        if !iterate::has_method(gen.ctx, name, "next") && iterate::has_method(gen.ctx, name, "iter")
+        {
+            iterable_val = iterate::call_method(gen.ctx, iterable_val, "iter", loc)?;
+        }
     }
-    let array_type = array_val.value.get_type().into_array_type();
-    let size = array_type.len() as u64;
 
-    // Store array temporary on stack (to allow GEP)
-    let array_alloca = gen
-        .ctx
-        .builder
-        .build_alloca(array_type, "for_arr_temp")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
-    gen.ctx
-        .builder
-        .build_store(array_alloca, array_val.value)
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    // Sema 保证其余情况都可迭代
+    let (source, elem_ty) = match iterable_val.ty {
+        Type::String => {
+            // 字符串按 Unicode 标量值迭代：先解码为码点 Vec
+            let (codes, len) =
+                chars::gen_chars_source(gen.ctx, iterable_val.value.into_pointer_value())?;
+            (IterSource::Chars(codes, len), Type::Char)
+        }
+        Type::Vec(inner) => (
+            IterSource::Vec(iterable_val.value.into_pointer_value()),
+            *inner,
+        ),
+        Type::Array { element_type, .. } if iterable_val.value.is_array_value() => {
+            let array_type = iterable_val.value.get_type().into_array_type();
+
+            // Store array temporary on stack (to allow GEP)
+            let array_alloca = gen
+                .ctx
+                .builder
+                .build_alloca(array_type, "for_arr_temp")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            gen.ctx
+                .builder
+                .build_store(array_alloca, iterable_val.value)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            (IterSource::Array(array_alloca, array_type), *element_type)
+        }
+        Type::Range => {
+            let bounds = range::range_bounds(gen.ctx, iterable_val.value.into_struct_value())?;
+            return counting::gen_counting_loop(gen, function, iterator, bounds, body);
+        }
+        Type::Struct(_) => {
+            return iterate::gen_next_loop(gen, function, iterator, iterable_val, body, loc)
+        }
+        _ => {
+            return Err(CodegenError::LLVMBuildError(
+                "For-in iterable must be an array, a string, a Vec, a range or an iterator"
+                    .to_string(),
+            ))
+        }
+    };
 
     // 2. Index variable (alloca)
-    let i64_type = gen.ctx.context.i64_type();
     let idx_alloca = gen
         .ctx
         .builder
@@ -74,15 +136,27 @@ pub fn gen_for_in<'ctx, 'a>(
         .build_load(i64_type, idx_alloca, "curr_idx")
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
         .into_int_value();
+    let len = match &source {
+        IterSource::Array(_, array_type) => i64_type.const_int(array_type.len() as u64, false),
+        IterSource::Chars(_, len) => *len,
+        IterSource::Vec(list) => gen
+            .ctx
+            .builder
+            .build_call(
+                vec::get_or_declare_vec_len(gen.ctx)?,
+                &[(*list).into()],
+                "vec_len",
+            )
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CodegenError::LLVMBuildError("lency_vec_len returned void".into()))?
+            .into_int_value(),
+    };
     let cond = gen
         .ctx
         .builder
-        .build_int_compare(
-            inkwell::IntPredicate::SLT,
-            curr_idx,
-            i64_type.const_int(size, false),
-            "loop_cond",
-        )
+        .build_int_compare(inkwell::IntPredicate::SLT, curr_idx, len, "loop_cond")
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
     gen.ctx
         .builder
@@ -93,24 +167,44 @@ pub fn gen_for_in<'ctx, 'a>(
     gen.ctx.builder.position_at_end(body_bb);
 
     // Load element
-    let zero = i64_type.const_int(0, false);
-    let elem_ptr = unsafe {
-        gen.ctx
-            .builder
-            .build_gep(array_type, array_alloca, &[zero, curr_idx], "elem_ptr")
-            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+    let elem_val: BasicValueEnum<'ctx> = match source {
+        IterSource::Array(array_alloca, array_type) => {
+            let zero = i64_type.const_int(0, false);
+            let elem_ptr = unsafe {
+                gen.ctx
+                    .builder
+                    .build_gep(array_type, array_alloca, &[zero, curr_idx], "elem_ptr")
+                    .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            };
+            gen.ctx
+                .builder
+                .build_load(array_type.get_element_type(), elem_ptr, "elem_val")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        }
+        IterSource::Chars(codes, _) => chars::gen_char_at(gen.ctx, codes, curr_idx)?.into(),
+        IterSource::Vec(list) => {
+            let raw = gen
+                .ctx
+                .builder
+                .build_call(
+                    vec::get_or_declare_vec_get(gen.ctx)?,
+                    &[list.into(), curr_idx.into()],
+                    "vec_elem",
+                )
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| CodegenError::LLVMBuildError("lency_vec_get returned void".into()))?
+                .into_int_value();
+            vec::cast_from_i64(gen.ctx, raw, &elem_ty)?
+        }
     };
-    let elem_val = gen
-        .ctx
-        .builder
-        .build_load(array_type.get_element_type(), elem_ptr, "elem_val")
-        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
     // Create iterator variable local
     let iter_alloca = gen
         .ctx
         .builder
-        .build_alloca(array_type.get_element_type(), iterator)
+        .build_alloca(elem_val.get_type(), iterator)
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
     gen.ctx
         .builder
@@ -118,11 +212,6 @@ pub fn gen_for_in<'ctx, 'a>(
         .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
     // Add to locals (Handle shadowing)
-    let elem_ty = match array_val.ty {
-        lency_syntax::ast::Type::Array { element_type, .. } => *element_type,
-        _ => return Err(CodegenError::TypeMismatch),
-    };
-
     let old_local = gen
         .locals
         .insert(iterator.to_string(), (iter_alloca, elem_ty));
@@ -0,0 +1,161 @@
+//! Iterator Protocol Loop
+//!
+//! `for x in it`：反复调用 `it.next()`，返回 `Some(v)` 时绑定 v 执行循环体，
+//! 返回 `None` 时结束循环。迭代器本身是指针，`next()` 的推进对调用方可见。
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::{FunctionValue, PointerValue};
+use lency_syntax::ast::{MatchPattern, Stmt, Type};
+
+use crate::context::CodegenContext;
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::match_expr::pattern::gen_pattern_check;
+use crate::expr::CodegenValue;
+use crate::source_map::SourceLocation;
+use crate::stmt::{LoopContext, StmtGenerator};
+
+/// 结构体（或单态化后的泛型类型）是否定义了方法 `method`
+pub(super) fn has_method(ctx: &CodegenContext, struct_name: &str, method: &str) -> bool {
+    ctx.module
+        .get_function(&format!("{}_{}", struct_name, method))
+        .is_some()
+}
+
+/// 调用结构体值上的无参方法
+pub(super) fn call_method<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    value: CodegenValue<'ctx>,
+    method: &str,
+    loc: SourceLocation,
+) -> CodegenResult<CodegenValue<'ctx>> {
+    let Type::Struct(struct_name) = &value.ty else {
+        return Err(CodegenError::TypeMismatch);
+    };
+    let mangled_name = format!("{}_{}", struct_name, method);
+    let function = ctx
+        .module
+        .get_function(&mangled_name)
+        .ok_or_else(|| CodegenError::FunctionNotFound(mangled_name.clone()))?;
+    let return_type = ctx
+        .function_signatures
+        .get(&mangled_name)
+        .cloned()
+        .ok_or_else(|| CodegenError::FunctionNotFound(mangled_name.clone()))?;
+
+    let this_ptr = this_pointer(ctx, &value)?;
+    crate::runtime::gen_null_check(ctx, this_ptr, loc);
+    crate::runtime::gen_trace_push(ctx, &format!("{}.{}", struct_name, method), loc);
+    let result = ctx
+        .builder
+        .build_call(function, &[this_ptr.into()], "call_method")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| CodegenError::LLVMBuildError(format!("{} returned void", mangled_name)))?;
+    crate::runtime::gen_trace_pop(ctx);
+
+    Ok(CodegenValue {
+        value: result,
+        ty: return_type,
+    })
+}
+
+/// 右值结构体先放到栈上，方法总是接收 this 指针
+fn this_pointer<'ctx>(
+    ctx: &CodegenContext<'ctx>,
+    value: &CodegenValue<'ctx>,
+) -> CodegenResult<PointerValue<'ctx>> {
+    if value.value.is_pointer_value() {
+        return Ok(value.value.into_pointer_value());
+    }
+    let alloca = ctx
+        .builder
+        .build_alloca(value.value.get_type(), "iter_tmp")
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    ctx.builder
+        .build_store(alloca, value.value)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    Ok(alloca)
+}
+
+/// 由 `next()` 驱动的 for-in 循环
+pub(super) fn gen_next_loop<'ctx, 'a>(
+    gen: &mut StmtGenerator<'ctx, 'a>,
+    function: FunctionValue<'ctx>,
+    iterator: &str,
+    iter_val: CodegenValue<'ctx>,
+    body: &[Stmt],
+    loc: SourceLocation,
+) -> CodegenResult<()> {
+    // 迭代器只求值一次，每轮在同一个 this 上调用 next()
+    let iter_val = CodegenValue {
+        value: this_pointer(gen.ctx, &iter_val)?.into(),
+        ty: iter_val.ty,
+    };
+
+    let next_bb = gen.ctx.context.append_basic_block(function, "forin.next");
+    let after_bb = gen.ctx.context.append_basic_block(function, "forin.end");
+    gen.ctx
+        .builder
+        .build_unconditional_branch(next_bb)
+        .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+
+    // next() 返回 Option<T>：Some(x) 绑定 x，None 跳出循环
+    gen.ctx.builder.position_at_end(next_bb);
+    let item = call_method(gen.ctx, iter_val, "next", loc)?;
+    let some_pattern = MatchPattern::Variant {
+        name: "Some".to_string(),
+        sub_patterns: vec![MatchPattern::Variable(iterator.to_string())],
+    };
+    let mut bindings = Vec::new();
+    gen_pattern_check(
+        gen.ctx,
+        gen.locals,
+        &some_pattern,
+        item,
+        &mut bindings,
+        after_bb,
+    )?;
+    let Some((name, ptr, ty)) = bindings.pop() else {
+        return Err(CodegenError::LLVMBuildError(
+            "iterator item was not bound".to_string(),
+        ));
+    };
+    let old_local = gen.locals.insert(name, (ptr, ty));
+
+    gen_loop_body(gen, body, next_bb, after_bb)?;
+
+    // Restore locals
+    if let Some(old) = old_local {
+        gen.locals.insert(iterator.to_string(), old);
+    } else {
+        gen.locals.remove(iterator);
+    }
+
+    gen.ctx.builder.position_at_end(after_bb);
+    Ok(())
+}
+
+/// 生成循环体；未终止时跳回 `continue_bb`
+pub(super) fn gen_loop_body<'ctx>(
+    gen: &mut StmtGenerator<'ctx, '_>,
+    body: &[Stmt],
+    continue_bb: BasicBlock<'ctx>,
+    break_bb: BasicBlock<'ctx>,
+) -> CodegenResult<()> {
+    gen.loop_stack.push(LoopContext {
+        continue_block: continue_bb,
+        break_block: break_bb,
+    });
+    gen.generate_block(body)?;
+    gen.loop_stack.pop();
+
+    let current_body = gen.ctx.builder.get_insert_block().unwrap();
+    if !gen.block_ends_with_terminator(current_body) {
+        gen.ctx
+            .builder
+            .build_unconditional_branch(continue_bb)
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+    }
+    Ok(())
+}
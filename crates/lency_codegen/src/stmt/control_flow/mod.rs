@@ -1,7 +1,11 @@
 pub mod conditional;
+mod counting;
+pub mod destructure;
 pub mod for_in;
+mod iterate;
 pub mod loops;
 
 pub use conditional::gen_if;
+pub use destructure::{gen_if_let, gen_var_pattern, gen_while_let};
 pub use for_in::gen_for_in;
 pub use loops::{gen_break, gen_continue, gen_for, gen_while};
@@ -3,6 +3,7 @@
 //! 语句代码生成器，将 Lency 语句转换为 LLVM IR
 
 mod control_flow;
+mod string_append;
 
 use inkwell::values::PointerValue;
 use lency_syntax::ast::Expr;
@@ -32,6 +33,8 @@ pub struct StmtGenerator<'ctx, 'a> {
     pub(crate) loop_stack: Vec<LoopContext<'ctx>>,
     /// 当前函数返回类型（用于 Result 类型转换等）
     pub(crate) return_type: &'a Type,
+    /// 循环拼接改写：变量名 -> 保存 StringBuilder 句柄的栈槽
+    pub(crate) string_builders: HashMap<String, PointerValue<'ctx>>,
 }
 
 impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
@@ -46,15 +49,23 @@ impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
             locals,
             loop_stack: Vec::new(),
             return_type,
+            string_builders: HashMap::new(),
         }
     }
 
     /// 生成语句代码
     pub fn generate(&mut self, stmt: &Stmt) -> CodegenResult<()> {
+        if let (Some(debug), Some(span)) = (&self.ctx.debug, crate::debug_info::stmt_span(stmt)) {
+            debug.set_location(self.ctx, span);
+        }
+
         match stmt {
             Stmt::VarDecl {
                 name, ty, value, ..
             } => self.gen_var_decl(name, ty.as_ref(), value),
+            Stmt::VarPattern { pattern, value, .. } => {
+                control_flow::gen_var_pattern(self, pattern, value)
+            }
             Stmt::Assignment { target, value, .. } => self.gen_assignment(target, value),
             Stmt::Return { value, .. } => self.gen_return(value.as_ref()),
             Stmt::If {
@@ -63,22 +74,51 @@ impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
                 else_block,
                 ..
             } => control_flow::gen_if(self, condition, then_block, else_block.as_deref()),
+            Stmt::IfLet {
+                pattern,
+                value,
+                then_block,
+                else_block,
+                ..
+            } => control_flow::gen_if_let(self, pattern, value, then_block, else_block.as_deref()),
+            Stmt::WhileLet {
+                pattern,
+                value,
+                body,
+                ..
+            } => self.gen_loop_with_appends(&[value], &body.iter().collect::<Vec<_>>(), |gen| {
+                control_flow::gen_while_let(gen, pattern, value, body)
+            }),
             Stmt::While {
                 condition, body, ..
-            } => control_flow::gen_while(self, condition, body),
+            } => {
+                self.gen_loop_with_appends(&[condition], &body.iter().collect::<Vec<_>>(), |gen| {
+                    control_flow::gen_while(gen, condition, body)
+                })
+            }
             Stmt::For {
                 init,
                 condition,
                 update,
                 body,
                 ..
-            } => control_flow::gen_for(
-                self,
-                init.as_deref(),
-                condition.as_ref(),
-                update.as_deref(),
-                body,
-            ),
+            } => {
+                let parts: Vec<&Stmt> = init
+                    .as_deref()
+                    .into_iter()
+                    .chain(update.as_deref())
+                    .chain(body)
+                    .collect();
+                self.gen_loop_with_appends(&condition.iter().collect::<Vec<_>>(), &parts, |gen| {
+                    control_flow::gen_for(
+                        gen,
+                        init.as_deref(),
+                        condition.as_ref(),
+                        update.as_deref(),
+                        body,
+                    )
+                })
+            }
             Stmt::Break { .. } => control_flow::gen_break(self),
             Stmt::Continue { .. } => control_flow::gen_continue(self),
             Stmt::Expression(expr) => {
@@ -91,7 +131,9 @@ impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
                 iterable,
                 body,
                 ..
-            } => control_flow::gen_for_in(self, iterator, iterable, body),
+            } => self.gen_loop_with_appends(&[iterable], &body.iter().collect::<Vec<_>>(), |gen| {
+                control_flow::gen_for_in(gen, iterator, iterable, body)
+            }),
             Stmt::Block(stmts) => self.generate_block(stmts),
         }
     }
@@ -99,6 +141,12 @@ impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
     /// 生成代码块
     pub fn generate_block(&mut self, stmts: &[Stmt]) -> CodegenResult<()> {
         for stmt in stmts {
+            // 当前块已终结（return/break/continue 之后）：其余语句不可达
+            if let Some(bb) = self.ctx.builder.get_insert_block() {
+                if self.block_ends_with_terminator(bb) {
+                    break;
+                }
+            }
             self.generate(stmt)?;
         }
         Ok(())
@@ -136,6 +184,10 @@ impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
             .build_store(alloca, val)
             .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
 
+        if let Some(debug) = &self.ctx.debug {
+            debug.declare_variable(self.ctx, alloca, name, &var_ty, None);
+        }
+
         // 记录变量（保存指针和 Lency 类型）
         self.locals.insert(name.to_string(), (alloca, var_ty));
 
@@ -144,6 +196,11 @@ impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
 
     /// 生成赋值语句
     fn gen_assignment(&mut self, target: &Expr, value: &Expr) -> CodegenResult<()> {
+        // 循环中已改写为 StringBuilder 的 `s = s + x`
+        if self.gen_string_append(target, value)? {
+            return Ok(());
+        }
+
         // 生成目标地址（LValue）
         let expr_gen = ExprGenerator::new(self.ctx, self.locals);
         let (ptr, _ty) = expr_gen.generate_lvalue_addr(target)?;
@@ -218,10 +275,11 @@ impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
                                 .ok_or(CodegenError::LLVMBuildError(
                                     "Failed to get size of Result type".to_string(),
                                 ))?;
-                        let malloc =
-                            self.ctx.module.get_function("malloc").ok_or(
-                                CodegenError::LLVMBuildError("malloc not found".to_string()),
-                            )?;
+                        let malloc = self
+                            .ctx
+                            .module
+                            .get_function(crate::runtime::ALLOC_FN)
+                            .ok_or(CodegenError::LLVMBuildError("malloc not found".to_string()))?;
                         let malloc_call = self
                             .ctx
                             .builder
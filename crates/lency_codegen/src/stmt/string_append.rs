@@ -0,0 +1,371 @@
+//! Loop String Append Lowering
+//!
+//! 循环里的 `s = s + a + b` 每次都复制整个 s，整个循环是 O(n²)。
+//! 若循环中对局部字符串 s 的使用只有这种追加赋值，就在进入循环前以 s 的内容创建
+//! StringBuilder，循环内改为追加，循环结束（正常退出或 break）后把结果写回 s。
+//! 循环中对 s 的任何其他读取、赋值或同名声明都会放弃改写，保证语义不变。
+
+use inkwell::values::PointerValue;
+use lency_syntax::ast::{BinaryOp, Expr, ExprKind, Stmt, Type};
+
+use crate::error::{CodegenError, CodegenResult};
+use crate::expr::string::string_builder;
+use crate::expr::ExprGenerator;
+use crate::stmt::StmtGenerator;
+use crate::types::ToLLVMType;
+
+impl<'ctx, 'a> StmtGenerator<'ctx, 'a> {
+    /// 生成一个循环；`exprs` / `stmts` 是循环的全部组成部分（条件、初始化、更新、循环体等）
+    pub(crate) fn gen_loop_with_appends(
+        &mut self,
+        exprs: &[&Expr],
+        stmts: &[&Stmt],
+        gen_loop: impl FnOnce(&mut Self) -> CodegenResult<()>,
+    ) -> CodegenResult<()> {
+        let targets = self.append_targets(exprs, stmts);
+        let i64_type = self.ctx.context.i64_type();
+
+        for name in &targets {
+            let ptr = self.local_ptr(name)?;
+            let current = self
+                .ctx
+                .builder
+                .build_load(Type::String.to_llvm_type(self.ctx)?, ptr, name)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            let sb = string_builder::gen_from(self.ctx, current)?;
+            // 句柄放在栈上，保守扫描据此保持缓冲区存活
+            let slot = self
+                .ctx
+                .builder
+                .build_alloca(i64_type, &format!("{}.sb", name))
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            self.ctx
+                .builder
+                .build_store(slot, sb)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+            self.string_builders.insert(name.clone(), slot);
+        }
+
+        gen_loop(self)?;
+
+        // 此时位于循环结束块：正常退出与 break 都经过这里
+        for name in &targets {
+            let slot = self.string_builders.remove(name).expect("registered above");
+            let sb = self
+                .ctx
+                .builder
+                .build_load(i64_type, slot, "sb")
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+                .into_int_value();
+            let built = string_builder::gen_build(self.ctx, sb)?;
+            self.ctx
+                .builder
+                .build_store(self.local_ptr(name)?, built)
+                .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// 若 `target = value` 是对已改写变量的追加，生成追加调用并返回 true
+    pub(crate) fn gen_string_append(&mut self, target: &Expr, value: &Expr) -> CodegenResult<bool> {
+        let ExprKind::Variable(name) = &target.kind else {
+            return Ok(false);
+        };
+        let Some(slot) = self.string_builders.get(name).copied() else {
+            return Ok(false);
+        };
+        let Some(operands) = append_operands(name, value) else {
+            return Ok(false);
+        };
+
+        let sb = self
+            .ctx
+            .builder
+            .build_load(self.ctx.context.i64_type(), slot, "sb")
+            .map_err(|e| CodegenError::LLVMBuildError(e.to_string()))?
+            .into_int_value();
+        let expr_gen = ExprGenerator::new(self.ctx, self.locals);
+        for operand in operands {
+            let piece = expr_gen.generate(operand)?;
+            string_builder::gen_append(self.ctx, sb, piece.value)?;
+        }
+        Ok(true)
+    }
+
+    /// 循环中可以改写的局部字符串变量
+    fn append_targets(&self, exprs: &[&Expr], stmts: &[&Stmt]) -> Vec<String> {
+        let mut names = Vec::new();
+        for stmt in stmts {
+            collect_appends(stmt, &mut names);
+        }
+        names.sort();
+        names.dedup();
+        names.retain(|name| {
+            // 外层循环已改写的变量在内层照常追加
+            !self.string_builders.contains_key(name)
+                && matches!(self.locals.get(name), Some((_, Type::String)))
+                && only_appends(name, exprs, stmts)
+        });
+        names
+    }
+
+    fn local_ptr(&self, name: &str) -> CodegenResult<PointerValue<'ctx>> {
+        self.locals
+            .get(name)
+            .map(|(ptr, _)| *ptr)
+            .ok_or_else(|| CodegenError::UndefinedVariable(name.to_string()))
+    }
+}
+
+/// `value` 形如 `name + a + b + ...`（左结合）时，按顺序返回追加的操作数
+fn append_operands<'e>(name: &str, value: &'e Expr) -> Option<Vec<&'e Expr>> {
+    let mut operands = Vec::new();
+    let mut current = value;
+    loop {
+        match &current.kind {
+            ExprKind::Binary(lhs, BinaryOp::Add, rhs) => {
+                operands.push(&**rhs);
+                current = lhs;
+            }
+            ExprKind::Variable(var) if var == name && !operands.is_empty() => {
+                operands.reverse();
+                return Some(operands);
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// 收集语句中所有追加赋值的目标变量
+fn collect_appends(stmt: &Stmt, names: &mut Vec<String>) {
+    match stmt {
+        Stmt::Assignment { target, value, .. } => {
+            if let ExprKind::Variable(name) = &target.kind {
+                if append_operands(name, value).is_some() {
+                    names.push(name.clone());
+                }
+            }
+        }
+        Stmt::Block(body)
+        | Stmt::While { body, .. }
+        | Stmt::WhileLet { body, .. }
+        | Stmt::ForIn { body, .. } => {
+            body.iter().for_each(|s| collect_appends(s, names));
+        }
+        Stmt::If {
+            then_block,
+            else_block,
+            ..
+        }
+        | Stmt::IfLet {
+            then_block,
+            else_block,
+            ..
+        } => {
+            then_block.iter().for_each(|s| collect_appends(s, names));
+            if let Some(else_block) = else_block {
+                else_block.iter().for_each(|s| collect_appends(s, names));
+            }
+        }
+        Stmt::For {
+            init, update, body, ..
+        } => {
+            init.iter()
+                .chain(update)
+                .for_each(|s| collect_appends(s, names));
+            body.iter().for_each(|s| collect_appends(s, names));
+        }
+        Stmt::VarDecl { .. }
+        | Stmt::VarPattern { .. }
+        | Stmt::Expression(_)
+        | Stmt::Return { .. }
+        | Stmt::Break { .. }
+        | Stmt::Continue { .. } => {}
+    }
+}
+
+/// 循环中对 `name` 的使用是否只有追加赋值
+fn only_appends(name: &str, exprs: &[&Expr], stmts: &[&Stmt]) -> bool {
+    let mut check = UseCheck { name, clean: true };
+    exprs.iter().for_each(|e| check.expr(e));
+    stmts.iter().for_each(|s| check.stmt(s));
+    check.clean
+}
+
+/// 查找追加赋值以外对变量的使用；闭包与 match 分支中的同名绑定也保守地算作使用
+struct UseCheck<'n> {
+    name: &'n str,
+    clean: bool,
+}
+
+impl UseCheck<'_> {
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        stmts.iter().for_each(|s| self.stmt(s));
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Assignment { target, value, .. } => {
+                let is_self = matches!(&target.kind, ExprKind::Variable(v) if v == self.name);
+                match append_operands(self.name, value) {
+                    Some(operands) if is_self => operands.into_iter().for_each(|e| self.expr(e)),
+                    _ => {
+                        self.expr(target);
+                        self.expr(value);
+                    }
+                }
+            }
+            Stmt::VarDecl { name, value, .. } => {
+                self.clean &= name != self.name;
+                self.expr(value);
+            }
+            Stmt::VarPattern { pattern, value, .. } => {
+                self.clean &= !pattern.bindings().contains(&self.name);
+                self.expr(value);
+            }
+            Stmt::Expression(expr) => self.expr(expr),
+            Stmt::Block(body) => self.stmts(body),
+            Stmt::IfLet {
+                pattern,
+                value,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.clean &= !pattern.bindings().contains(&self.name);
+                self.expr(value);
+                self.stmts(then_block);
+                if let Some(else_block) = else_block {
+                    self.stmts(else_block);
+                }
+            }
+            Stmt::WhileLet {
+                pattern,
+                value,
+                body,
+                ..
+            } => {
+                self.clean &= !pattern.bindings().contains(&self.name);
+                self.expr(value);
+                self.stmts(body);
+            }
+            Stmt::If {
+                condition,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.expr(condition);
+                self.stmts(then_block);
+                if let Some(else_block) = else_block {
+                    self.stmts(else_block);
+                }
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                self.expr(condition);
+                self.stmts(body);
+            }
+            Stmt::For {
+                init,
+                condition,
+                update,
+                body,
+                ..
+            } => {
+                init.iter().chain(update).for_each(|s| self.stmt(s));
+                if let Some(condition) = condition {
+                    self.expr(condition);
+                }
+                self.stmts(body);
+            }
+            Stmt::ForIn {
+                iterator,
+                iterable,
+                body,
+                ..
+            } => {
+                self.clean &= iterator != self.name;
+                self.expr(iterable);
+                self.stmts(body);
+            }
+            Stmt::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.expr(value);
+                }
+            }
+            Stmt::Break { .. } | Stmt::Continue { .. } => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Variable(var) => self.clean &= var != self.name,
+            ExprKind::Binary(left, _, right) => {
+                self.expr(left);
+                self.expr(right);
+            }
+            ExprKind::Closure { body, .. } => self.expr(body),
+            ExprKind::Match {
+                value,
+                cases,
+                default,
+            } => {
+                self.expr(value);
+                for case in cases {
+                    case.guard.iter().for_each(|guard| self.expr(guard));
+                    self.expr(&case.body);
+                }
+                if let Some(default) = default {
+                    self.expr(default);
+                }
+            }
+            ExprKind::Call { callee, args } => {
+                self.expr(callee);
+                args.iter().for_each(|arg| self.expr(arg));
+            }
+            ExprKind::Array(items) | ExprKind::VecLiteral(items) => {
+                items.iter().for_each(|item| self.expr(item));
+            }
+            ExprKind::StructLiteral { fields, .. } => {
+                fields.iter().for_each(|(_, value)| self.expr(value));
+            }
+            ExprKind::Index { array, index }
+            | ExprKind::Range {
+                start: array,
+                end: index,
+                ..
+            } => {
+                self.expr(array);
+                self.expr(index);
+            }
+            ExprKind::Get { object, .. } | ExprKind::SafeGet { object, .. } => self.expr(object),
+            ExprKind::GenericInstantiation { base, .. } => self.expr(base),
+            ExprKind::CoerceDyn { value, .. } | ExprKind::Cast { value, .. } => self.expr(value),
+            ExprKind::Unary(_, inner)
+            | ExprKind::Print(inner)
+            | ExprKind::Try(inner)
+            | ExprKind::Ok(inner)
+            | ExprKind::Err(inner)
+            | ExprKind::ReadFile(inner)
+            | ExprKind::Len(inner)
+            | ExprKind::Trim(inner)
+            | ExprKind::CharToString(inner)
+            | ExprKind::Panic(inner) => self.expr(inner),
+            ExprKind::WriteFile(a, b)
+            | ExprKind::Split(a, b)
+            | ExprKind::Join(a, b)
+            | ExprKind::Format(a, b) => {
+                self.expr(a);
+                self.expr(b);
+            }
+            ExprKind::Substr(a, b, c) => {
+                self.expr(a);
+                self.expr(b);
+                self.expr(c);
+            }
+            ExprKind::Literal(_) | ExprKind::Unit | ExprKind::Error => {}
+        }
+    }
+}
@@ -4,7 +4,7 @@
 
 use crate::context::CodegenContext;
 use crate::error::{CodegenError, CodegenResult};
-use inkwell::types::{BasicType, BasicTypeEnum};
+use inkwell::types::{BasicType, BasicTypeEnum, StructType};
 use inkwell::AddressSpace;
 use lency_syntax::ast::Type;
 
@@ -19,8 +19,14 @@ impl<'ctx> ToLLVMType<'ctx> for Type {
         match self {
             // 基础类型映射
             Type::Int => Ok(context.context.i64_type().as_basic_type_enum()),
+            Type::FixedInt(kind) => Ok(context
+                .context
+                .custom_width_int_type(kind.bits())
+                .as_basic_type_enum()),
             Type::Float => Ok(context.context.f64_type().as_basic_type_enum()),
             Type::Bool => Ok(context.context.bool_type().as_basic_type_enum()),
+            // char 是一个 Unicode 标量值，按无符号 i32 处理
+            Type::Char => Ok(context.context.i32_type().as_basic_type_enum()),
 
             // 字符串用 i8* 表示 (C 风格字符串指针)
             Type::String => Ok(context
@@ -66,6 +72,9 @@ impl<'ctx> ToLLVMType<'ctx> for Type {
                 .ptr_type(AddressSpace::default())
                 .as_basic_type_enum()),
 
+            // 区间按值传递: { i64 start, i64 end, i1 inclusive }
+            Type::Range => Ok(range_struct_type(context).as_basic_type_enum()),
+
             // 泛型参数: T, U
             // 在单态化之前不应该遇到这种类型
             Type::GenericParam(_) => Err(CodegenError::UnsupportedType(
@@ -121,40 +130,66 @@ impl<'ctx> ToLLVMType<'ctx> for Type {
                     .as_basic_type_enum())
             }
 
-            // 函数类型: int(int, int) -> function pointer
-            Type::Function {
-                param_types,
-                return_type,
-            } => {
-                let param_llvm_types: Result<Vec<_>, _> = param_types
-                    .iter()
-                    .map(|t| t.to_llvm_type(context).map(|ty| ty.into()))
-                    .collect();
-                let param_llvm_types = param_llvm_types?;
-
-                let fn_type = if matches!(**return_type, Type::Void) {
-                    context
-                        .context
-                        .void_type()
-                        .fn_type(&param_llvm_types, false)
-                } else {
-                    let ret = return_type.to_llvm_type(context)?;
-                    ret.fn_type(&param_llvm_types, false)
-                };
+            // 函数类型: int(int, int) -> 闭包对象指针 { i8* fn, i8* env }*
+            // 具体的函数签名在调用点由 closure::closure_fn_type 恢复
+            Type::Function { .. } => Ok(closure_struct_type(context)
+                .ptr_type(AddressSpace::default())
+                .as_basic_type_enum()),
 
-                Ok(fn_type
-                    .ptr_type(AddressSpace::default())
-                    .as_basic_type_enum())
-            }
+            // Trait 对象: dyn Greeter -> 胖指针 { i8* data, i8* vtable }*
+            Type::TraitObject(_) => Ok(trait_object_struct_type(context)
+                .ptr_type(AddressSpace::default())
+                .as_basic_type_enum()),
 
             Type::Error => Err(CodegenError::UnsupportedType("error type".to_string())),
         }
     }
 }
 
+/// 闭包对象布局: { i8* fn_ptr, i8* env_ptr }
+pub fn closure_struct_type<'ctx>(context: &CodegenContext<'ctx>) -> StructType<'ctx> {
+    let i8_ptr = context
+        .context
+        .i8_type()
+        .ptr_type(AddressSpace::default())
+        .as_basic_type_enum();
+    context.context.struct_type(&[i8_ptr, i8_ptr], false)
+}
+
+/// 区间布局: { i64 start, i64 end, i1 inclusive }
+pub fn range_struct_type<'ctx>(context: &CodegenContext<'ctx>) -> StructType<'ctx> {
+    let i64_type = context.context.i64_type().as_basic_type_enum();
+    let bool_type = context.context.bool_type().as_basic_type_enum();
+    context
+        .context
+        .struct_type(&[i64_type, i64_type, bool_type], false)
+}
+
+/// Trait 对象布局: { i8* data_ptr, i8* vtable_ptr }
+pub fn trait_object_struct_type<'ctx>(context: &CodegenContext<'ctx>) -> StructType<'ctx> {
+    let i8_ptr = context
+        .context
+        .i8_type()
+        .ptr_type(AddressSpace::default())
+        .as_basic_type_enum();
+    context.context.struct_type(&[i8_ptr, i8_ptr], false)
+}
+
+/// (类型, Trait) 对应的 vtable 全局变量名
+pub fn vtable_name(type_name: &str, trait_name: &str) -> String {
+    format!("__vtable_{}_{}", type_name, trait_name)
+}
+
 /// 检查类型是否为整数类型
 pub fn is_int_type(ty: &Type) -> bool {
-    matches!(ty, Type::Int)
+    matches!(ty, Type::Int | Type::FixedInt(_))
+}
+
+/// 检查类型是否按无符号处理（决定除法、比较与扩展的方式）
+///
+/// 包括无符号定宽整数与 char（码点比较、零扩展）。
+pub fn is_unsigned_type(ty: &Type) -> bool {
+    matches!(ty, Type::FixedInt(kind) if !kind.is_signed()) || *ty == Type::Char
 }
 
 /// 检查类型是否为浮点类型
@@ -183,6 +218,28 @@ mod tests {
         assert!(!is_numeric_type(&Type::Bool));
     }
 
+    #[test]
+    fn test_fixed_int_types() {
+        use lency_syntax::ast::IntKind;
+
+        let context = Context::create();
+        let codegen_ctx = CodegenContext::new(&context, "test", None);
+
+        let byte = Type::FixedInt(IntKind::U8);
+        assert!(is_int_type(&byte));
+        assert!(is_unsigned_type(&byte));
+        assert!(!is_unsigned_type(&Type::FixedInt(IntKind::I32)));
+        assert!(!is_unsigned_type(&Type::Int));
+        assert!(is_unsigned_type(&Type::Char));
+        assert!(!is_int_type(&Type::Char));
+        assert_eq!(
+            byte.to_llvm_type(&codegen_ctx)
+                .ok()
+                .map(|t| t.into_int_type().get_bit_width()),
+            Some(8)
+        );
+    }
+
     #[test]
     fn test_to_llvm_type() {
         let context = Context::create();
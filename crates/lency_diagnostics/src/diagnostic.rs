@@ -6,10 +6,12 @@ use crate::level::DiagnosticLevel;
 use crate::span::Span;
 
 /// 修复建议
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Suggestion {
     /// 建议消息
     pub message: String,
+    /// 被替换的源码范围（如果有）
+    pub span: Option<Span>,
     /// 建议的替换内容（如果有）
     pub replacement: Option<String>,
 }
@@ -19,15 +21,29 @@ impl Suggestion {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            span: None,
             replacement: None,
         }
     }
 
-    /// 添加替换内容
-    pub fn with_replacement(mut self, replacement: impl Into<String>) -> Self {
+    /// 添加替换内容：用 `replacement` 替换 `span` 处的源码
+    pub fn with_replacement(mut self, span: Span, replacement: impl Into<String>) -> Self {
+        self.span = Some(span);
         self.replacement = Some(replacement.into());
         self
     }
+
+    /// 是否可以自动应用（同时给出了范围和替换内容）
+    pub fn is_machine_applicable(&self) -> bool {
+        self.span.is_some() && self.replacement.is_some()
+    }
+}
+
+/// 次要标注：指向与诊断相关的另一处源码
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
 }
 
 /// 诊断信息
@@ -35,12 +51,16 @@ impl Suggestion {
 pub struct Diagnostic {
     /// 诊断级别
     pub level: DiagnosticLevel,
+    /// 稳定的诊断代码（如 `E0001`、lint 名称）
+    pub code: Option<String>,
     /// 主要消息
     pub message: String,
     /// 源文件路径（可选）
     pub file_path: Option<String>,
     /// 源码位置（可选）
     pub span: Option<Span>,
+    /// 次要标注
+    pub labels: Vec<Label>,
     /// 补充注释
     pub notes: Vec<String>,
     /// 修复建议
@@ -52,9 +72,11 @@ impl Diagnostic {
     pub fn new(level: DiagnosticLevel, message: impl Into<String>) -> Self {
         Self {
             level,
+            code: None,
             message: message.into(),
             file_path: None,
             span: None,
+            labels: Vec::new(),
             notes: Vec::new(),
             suggestions: Vec::new(),
         }
@@ -80,6 +102,12 @@ impl Diagnostic {
         Self::new(DiagnosticLevel::Note, message)
     }
 
+    /// 设置诊断代码
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
     /// 设置文件路径
     pub fn with_file(mut self, path: impl Into<String>) -> Self {
         self.file_path = Some(path.into());
@@ -92,6 +120,15 @@ impl Diagnostic {
         self
     }
 
+    /// 添加次要标注
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
     /// 添加注释
     pub fn with_note(mut self, note: impl Into<String>) -> Self {
         self.notes.push(note.into());
@@ -122,6 +159,7 @@ mod tests {
             .suggest("try this");
 
         assert_eq!(diag.level, DiagnosticLevel::Error);
+        assert_eq!(diag.code, None);
         assert_eq!(diag.message, "test error");
         assert_eq!(diag.span, Some(0..10));
         assert_eq!(diag.notes.len(), 1);
@@ -130,6 +168,19 @@ mod tests {
         assert_eq!(diag.suggestions[0].message, "try this");
     }
 
+    #[test]
+    fn test_labels_and_replacements() {
+        let diag = Diagnostic::error("'x' is already defined in this scope")
+            .span(20..21)
+            .with_label(4..5, "previous definition of 'x' here")
+            .with_suggestion(Suggestion::new("did you mean `y`?").with_replacement(20..21, "y"))
+            .suggest("rename it");
+
+        assert_eq!(diag.labels[0].span, 4..5);
+        assert!(diag.suggestions[0].is_machine_applicable());
+        assert!(!diag.suggestions[1].is_machine_applicable());
+    }
+
     #[test]
     fn test_different_levels() {
         let error = Diagnostic::error("error");
@@ -3,12 +3,24 @@
 //! 负责将诊断信息格式化输出
 
 use crate::diagnostic::Diagnostic;
+use crate::span::Span;
 use colored::*;
 
+/// 诊断输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// 面向人的文本格式（默认）
+    #[default]
+    Human,
+    /// 每条诊断一行 JSON，见 [`crate::json`]
+    Json,
+}
+
 /// 诊断输出器
 pub struct Emitter {
     /// 是否使用颜色
     use_colors: bool,
+    format: ErrorFormat,
 }
 
 impl Default for Emitter {
@@ -20,17 +32,35 @@ impl Default for Emitter {
 impl Emitter {
     /// 创建新的输出器
     pub fn new() -> Self {
-        Self { use_colors: true }
+        Self::with_format(ErrorFormat::Human)
     }
 
     /// 创建无颜色的输出器
     pub fn without_colors() -> Self {
-        Self { use_colors: false }
+        Self {
+            use_colors: false,
+            format: ErrorFormat::Human,
+        }
+    }
+
+    /// 按输出格式创建输出器
+    pub fn with_format(format: ErrorFormat) -> Self {
+        Self {
+            use_colors: true,
+            format,
+        }
+    }
+
+    /// 输出格式
+    pub fn format(&self) -> ErrorFormat {
+        self.format
     }
 
     /// 输出单个诊断
     pub fn emit(&self, diagnostic: &Diagnostic) {
-        if self.use_colors {
+        if self.format == ErrorFormat::Json {
+            println!("{}", crate::json::to_json(diagnostic, None));
+        } else if self.use_colors {
             self.emit_colored(diagnostic, None);
         } else {
             self.emit_plain(diagnostic, None);
@@ -39,7 +69,9 @@ impl Emitter {
 
     /// 输出带源文件的诊断（支持 line:col）
     pub fn emit_with_source(&self, diagnostic: &Diagnostic, source: &str) {
-        if self.use_colors {
+        if self.format == ErrorFormat::Json {
+            println!("{}", crate::json::to_json(diagnostic, Some(source)));
+        } else if self.use_colors {
             self.emit_colored(diagnostic, Some(source));
         } else {
             self.emit_plain(diagnostic, Some(source));
@@ -50,16 +82,23 @@ impl Emitter {
     pub fn emit_all(&self, diagnostics: &[Diagnostic]) {
         for diagnostic in diagnostics {
             self.emit(diagnostic);
-            println!(); // 诊断之间空行
+            if self.format == ErrorFormat::Human {
+                println!(); // 诊断之间空行
+            }
         }
     }
 
     /// 输出带颜色的诊断
     fn emit_colored(&self, diagnostic: &Diagnostic, source: Option<&str>) {
-        // 级别和消息
+        // 级别、代码和消息
+        let code = match &diagnostic.code {
+            Some(code) => format!("[{}]", code),
+            None => String::new(),
+        };
         println!(
-            "{}: {}",
+            "{}{}: {}",
             diagnostic.level.colored_name(),
+            code.bold(),
             diagnostic.message.bold()
         );
 
@@ -70,12 +109,34 @@ impl Emitter {
                 println!("  {} {}:{}:{}", "-->".blue().bold(), file, line, col);
 
                 // 显示源代码片段
-                self.emit_source_snippet_colored(src, span.start, span.len(), line);
+                self.emit_source_snippet_colored(src, span, line, '^', None);
             } else {
                 println!("  {} {:?}", "-->".blue().bold(), span);
             }
         }
 
+        // 次要标注
+        for label in &diagnostic.labels {
+            match source {
+                Some(src) if diagnostic.file_path.is_some() => {
+                    let (line, _) = resolve_line_col(src, label.span.start);
+                    self.emit_source_snippet_colored(
+                        src,
+                        &label.span,
+                        line,
+                        '-',
+                        Some(&label.message),
+                    );
+                }
+                _ => println!(
+                    "  {} {:?} {}",
+                    ":::".blue().bold(),
+                    label.span,
+                    label.message
+                ),
+            }
+        }
+
         // 注释
         for note in &diagnostic.notes {
             println!(
@@ -110,19 +171,43 @@ impl Emitter {
             String::new()
         };
 
-        println!("{}:{} {}", diagnostic.level, pos, diagnostic.message);
+        let code = match &diagnostic.code {
+            Some(code) => format!("[{}]", code),
+            None => String::new(),
+        };
+        println!(
+            "{}{}:{} {}",
+            diagnostic.level, code, pos, diagnostic.message
+        );
 
         // 详细位置信息
         if let Some(span) = &diagnostic.span {
             if let (Some(src), Some(_)) = (source, &diagnostic.file_path) {
                 let (line, _) = resolve_line_col(src, span.start);
                 // 显示源代码片段 (Plain)
-                self.emit_source_snippet_plain(src, span.start, span.len(), line);
+                self.emit_source_snippet_plain(src, span, line, '^', None);
             } else {
                 println!("  --> {:?}", span);
             }
         }
 
+        // 次要标注
+        for label in &diagnostic.labels {
+            match source {
+                Some(src) if diagnostic.file_path.is_some() => {
+                    let (line, _) = resolve_line_col(src, label.span.start);
+                    self.emit_source_snippet_plain(
+                        src,
+                        &label.span,
+                        line,
+                        '-',
+                        Some(&label.message),
+                    );
+                }
+                _ => println!("  ::: {:?} {}", label.span, label.message),
+            }
+        }
+
         // 注释
         for note in &diagnostic.notes {
             println!("  = note: {}", note);
@@ -139,15 +224,22 @@ impl Emitter {
 
     // --- Snippet Helpers ---
 
-    fn emit_source_snippet_colored(&self, source: &str, start: usize, len: usize, line_num: usize) {
-        let (line_content, line_start_offset) = get_line_content(source, start);
-        if line_content.trim().is_empty() {
+    /// 源码片段：`marker` 标出 `span`，`label` 跟在标记之后
+    fn emit_source_snippet_colored(
+        &self,
+        source: &str,
+        span: &Span,
+        line_num: usize,
+        marker: char,
+        label: Option<&str>,
+    ) {
+        let Some((line_content, pointer_padding, pointer)) = snippet_parts(source, span, marker)
+        else {
             return;
-        }
+        };
 
         let line_num_str = line_num.to_string();
-        let gutter_width = line_num_str.len();
-        let padding = " ".repeat(gutter_width);
+        let padding = " ".repeat(line_num_str.len());
 
         // 1. Empty line before
         println!("  {} |", padding.blue().bold());
@@ -156,51 +248,81 @@ impl Emitter {
         println!("  {} | {}", line_num_str.blue().bold(), line_content);
 
         // 3. Pointer line
-        // Calculate offset within the line
-        let col_offset = start.saturating_sub(line_start_offset);
-        // Ensure len is at least 1, and doesn't overflow line
-        let mark_len = len
-            .max(1)
-            .min(line_content.len().saturating_sub(col_offset));
-
-        let pointer_padding = " ".repeat(col_offset);
-        let pointer = "^".repeat(mark_len);
-
-        println!(
-            "  {} | {}{}",
-            padding.blue().bold(),
-            pointer_padding,
+        let pointer = if marker == '^' {
             pointer.red().bold()
-        );
+        } else {
+            pointer.blue().bold()
+        };
+        match label {
+            Some(label) => println!(
+                "  {} | {}{} {}",
+                padding.blue().bold(),
+                pointer_padding,
+                pointer,
+                label.blue().bold()
+            ),
+            None => println!(
+                "  {} | {}{}",
+                padding.blue().bold(),
+                pointer_padding,
+                pointer
+            ),
+        }
     }
 
-    fn emit_source_snippet_plain(&self, source: &str, start: usize, len: usize, line_num: usize) {
-        let (line_content, line_start_offset) = get_line_content(source, start);
-        if line_content.trim().is_empty() {
+    fn emit_source_snippet_plain(
+        &self,
+        source: &str,
+        span: &Span,
+        line_num: usize,
+        marker: char,
+        label: Option<&str>,
+    ) {
+        let Some((line_content, pointer_padding, pointer)) = snippet_parts(source, span, marker)
+        else {
             return;
-        }
+        };
 
         let line_num_str = line_num.to_string();
-        let gutter_width = line_num_str.len();
-        let padding = " ".repeat(gutter_width);
+        let padding = " ".repeat(line_num_str.len());
 
         println!("  {} |", padding);
         println!("  {} | {}", line_num_str, line_content);
+        match label {
+            Some(label) => println!("  {} | {}{} {}", padding, pointer_padding, pointer, label),
+            None => println!("  {} | {}{}", padding, pointer_padding, pointer),
+        }
+    }
+}
 
-        let col_offset = start.saturating_sub(line_start_offset);
-        let mark_len = len
-            .max(1)
-            .min(line_content.len().saturating_sub(col_offset));
-
-        let pointer_padding = " ".repeat(col_offset);
-        let pointer = "^".repeat(mark_len);
-
-        println!("  {} | {}{}", padding, pointer_padding, pointer);
+/// 片段所在行的内容、标记前的缩进与标记；该行为空时返回 None
+fn snippet_parts<'a>(
+    source: &'a str,
+    span: &Span,
+    marker: char,
+) -> Option<(&'a str, String, String)> {
+    let (line_content, line_start_offset) = get_line_content(source, span.start);
+    if line_content.trim().is_empty() {
+        return None;
     }
+
+    // Calculate offset within the line
+    let col_offset = span.start.saturating_sub(line_start_offset);
+    // Ensure len is at least 1, and doesn't overflow line
+    let mark_len = span
+        .len()
+        .max(1)
+        .min(line_content.len().saturating_sub(col_offset));
+
+    Some((
+        line_content,
+        " ".repeat(col_offset),
+        marker.to_string().repeat(mark_len),
+    ))
 }
 
-/// 解析行列
-fn resolve_line_col(source: &str, offset: usize) -> (usize, usize) {
+/// 解析行列（从 1 开始，列按字符计数）
+pub(crate) fn resolve_line_col(source: &str, offset: usize) -> (usize, usize) {
     let mut line = 1;
     let mut col = 1;
     for (i, c) in source.char_indices() {
@@ -257,6 +379,10 @@ mod tests {
 
         let emitter_no_color = Emitter::without_colors();
         assert!(!emitter_no_color.use_colors);
+
+        let json = Emitter::with_format(ErrorFormat::Json);
+        assert_eq!(json.format(), ErrorFormat::Json);
+        json.emit(&Diagnostic::error("test error").with_code("E0001"));
     }
 
     #[test]
@@ -268,6 +394,23 @@ mod tests {
         emitter.emit(&diag);
     }
 
+    #[test]
+    fn test_emit_with_labels() {
+        let emitter = Emitter::without_colors();
+        let source = "var x = 1\nvar x = 2\n";
+        let diag = Diagnostic::error("'x' is already defined in this scope")
+            .with_file("main.lcy")
+            .span(14..15)
+            .with_label(4..5, "previous definition of 'x' here");
+
+        emitter.emit_with_source(&diag, source);
+        emitter.emit(&diag);
+        assert_eq!(
+            snippet_parts(source, &(4..5), '-'),
+            Some(("var x = 1", "    ".to_string(), "-".to_string()))
+        );
+    }
+
     #[test]
     fn test_emit_with_details() {
         let emitter = Emitter::without_colors();
@@ -0,0 +1,122 @@
+//! Fix - 应用修复建议
+//!
+//! 把可自动应用的建议（带范围和替换内容的 [`Suggestion`]）写回源码。
+
+use crate::diagnostic::{Diagnostic, Suggestion};
+use crate::span::Span;
+
+/// 一处已应用的替换
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedFix {
+    /// 替换前的范围（相对原始源码）
+    pub span: Span,
+    pub replacement: String,
+    /// 对应建议的消息
+    pub message: String,
+}
+
+/// 收集诊断中可自动应用的建议
+pub fn machine_applicable(diagnostics: &[Diagnostic]) -> Vec<&Suggestion> {
+    diagnostics
+        .iter()
+        .flat_map(|diag| &diag.suggestions)
+        .filter(|s| s.is_machine_applicable())
+        .collect()
+}
+
+/// 应用建议，返回新源码与实际应用的替换（按位置排序）
+///
+/// 与已应用的替换重叠的建议被跳过（重复报告的同一建议只应用一次），
+/// 超出源码范围或不在字符边界上的建议同样跳过。
+pub fn apply_suggestions(source: &str, suggestions: &[&Suggestion]) -> (String, Vec<AppliedFix>) {
+    let mut edits: Vec<AppliedFix> = suggestions
+        .iter()
+        .filter_map(|s| match (&s.span, &s.replacement) {
+            (Some(span), Some(replacement)) => Some(AppliedFix {
+                span: span.clone(),
+                replacement: replacement.clone(),
+                message: s.message.clone(),
+            }),
+            _ => None,
+        })
+        .filter(|e| {
+            e.span.start <= e.span.end
+                && source.is_char_boundary(e.span.start)
+                && source.is_char_boundary(e.span.end)
+        })
+        .collect();
+    edits.sort_by_key(|e| (e.span.start, e.span.end));
+
+    let mut applied: Vec<AppliedFix> = Vec::new();
+    for edit in edits {
+        let overlaps = applied.last().is_some_and(|last| {
+            edit.span.start < last.span.end || edit.span.start == last.span.start
+        });
+        if !overlaps {
+            applied.push(edit);
+        }
+    }
+
+    let mut output = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for edit in &applied {
+        output.push_str(&source[cursor..edit.span.start]);
+        output.push_str(&edit.replacement);
+        cursor = edit.span.end;
+    }
+    output.push_str(&source[cursor..]);
+    (output, applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(span: Span, text: &str) -> Suggestion {
+        Suggestion::new(format!("did you mean `{}`?", text)).with_replacement(span, text)
+    }
+
+    #[test]
+    fn test_apply_in_order() {
+        let source = "print(cnt + totl)";
+        let (a, b) = (replace(12..16, "total"), replace(6..9, "count"));
+        let (fixed, applied) = apply_suggestions(source, &[&a, &b]);
+        assert_eq!(fixed, "print(count + total)");
+        assert_eq!(applied.len(), 2);
+        assert_eq!(applied[0].span, 6..9);
+    }
+
+    #[test]
+    fn test_overlapping_and_duplicate_suggestions() {
+        let source = "var x = cnt";
+        let first = replace(8..11, "count");
+        let duplicate = replace(8..11, "count");
+        let overlapping = replace(4..9, "y = c");
+        let (fixed, applied) = apply_suggestions(source, &[&overlapping, &first, &duplicate]);
+        assert_eq!(fixed, "var y = cnt");
+        assert_eq!(applied.len(), 1);
+    }
+
+    #[test]
+    fn test_only_machine_applicable_suggestions() {
+        let diags = vec![
+            Diagnostic::error("undefined variable 'cnt'")
+                .with_suggestion(replace(0..3, "count"))
+                .suggest("declare it first"),
+            Diagnostic::error("type mismatch").suggest("use int_to_string()"),
+        ];
+        let suggestions = machine_applicable(&diags);
+        assert_eq!(suggestions.len(), 1);
+
+        let (fixed, _) = apply_suggestions("cnt", &suggestions);
+        assert_eq!(fixed, "count");
+    }
+
+    #[test]
+    fn test_out_of_range_suggestion_is_skipped() {
+        let bad = replace(10..20, "x");
+        let (fixed, applied) = apply_suggestions("short", &[&bad]);
+        assert_eq!(fixed, "short");
+        assert!(applied.is_empty());
+    }
+}
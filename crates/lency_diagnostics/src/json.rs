@@ -0,0 +1,102 @@
+//! JSON - 机器可读的诊断格式
+//!
+//! `--error-format=json` 时每条诊断输出为一行 JSON 对象，供编辑器与 CI 解析：
+//!
+//! ```json
+//! {"level":"error","code":"E0001","message":"undefined variable 'cont'","file":"main.lcy",
+//!  "span":{"start":31,"end":35,"line_start":3,"column_start":12,"line_end":3,"column_end":16},
+//!  "labels":[],"notes":[],"suggestions":[{"message":"did you mean `count`?","replacement":"count","span":{...}}]}
+//! ```
+//!
+//! 行列从 1 开始，列按字符计数；没有源码时 `span` 只包含字节偏移。
+
+use crate::diagnostic::Diagnostic;
+use crate::emitter::resolve_line_col;
+use crate::span::Span;
+use serde_json::{json, Value};
+
+/// 转换为 JSON 对象；`source` 为诊断所在文件的内容，用于计算行列
+pub fn to_json(diagnostic: &Diagnostic, source: Option<&str>) -> Value {
+    let span = |span: &Span| span_json(span, source);
+    json!({
+        "level": diagnostic.level.name(),
+        "code": diagnostic.code,
+        "message": diagnostic.message,
+        "file": diagnostic.file_path,
+        "span": diagnostic.span.as_ref().map(span),
+        "labels": diagnostic
+            .labels
+            .iter()
+            .map(|label| json!({ "message": label.message, "span": span(&label.span) }))
+            .collect::<Vec<_>>(),
+        "notes": diagnostic.notes,
+        "suggestions": diagnostic
+            .suggestions
+            .iter()
+            .map(|s| json!({
+                "message": s.message,
+                "replacement": s.replacement,
+                "span": s.span.as_ref().map(span),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn span_json(span: &Span, source: Option<&str>) -> Value {
+    match source {
+        Some(source) => {
+            let (line_start, column_start) = resolve_line_col(source, span.start);
+            let (line_end, column_end) = resolve_line_col(source, span.end);
+            json!({
+                "start": span.start,
+                "end": span.end,
+                "line_start": line_start,
+                "column_start": column_start,
+                "line_end": line_end,
+                "column_end": column_end,
+            })
+        }
+        None => json!({ "start": span.start, "end": span.end }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Suggestion;
+
+    #[test]
+    fn test_diagnostic_to_json() {
+        let source = "int main() {\n    return cont\n}\n";
+        let diag = Diagnostic::error("undefined variable 'cont'")
+            .with_code("E0001")
+            .with_file("main.lcy")
+            .span(24..28)
+            .with_suggestion(
+                Suggestion::new("did you mean `count`?").with_replacement(24..28, "count"),
+            );
+
+        let value = to_json(&diag, Some(source));
+        assert_eq!(value["level"], "error");
+        assert_eq!(value["code"], "E0001");
+        assert_eq!(value["file"], "main.lcy");
+        assert_eq!(value["span"]["start"], 24);
+        assert_eq!(value["span"]["line_start"], 2);
+        assert_eq!(value["span"]["column_start"], 12);
+        assert_eq!(value["span"]["column_end"], 16);
+        assert_eq!(value["suggestions"][0]["replacement"], "count");
+        assert_eq!(value["suggestions"][0]["span"]["line_end"], 2);
+        assert_eq!(value["notes"], json!([]));
+    }
+
+    #[test]
+    fn test_json_without_source_or_span() {
+        let value = to_json(&Diagnostic::warning("no location").with_note("n"), None);
+        assert_eq!(value["code"], Value::Null);
+        assert_eq!(value["span"], Value::Null);
+        assert_eq!(value["notes"][0], "n");
+
+        let value = to_json(&Diagnostic::error("e").span(3..5), None);
+        assert_eq!(value["span"], json!({ "start": 3, "end": 5 }));
+    }
+}
@@ -9,6 +9,8 @@
 //! - [`DiagnosticSink`] - 诊断收集器
 //! - [`Emitter`] - 诊断输出器
 //! - [`Span`] - 源码位置信息
+//! - [`fix`] - 应用可自动修复的建议
+//! - [`json`] - 机器可读的 JSON 诊断格式
 //!
 //! # 示例
 //!
@@ -34,13 +36,15 @@
 
 pub mod diagnostic;
 pub mod emitter;
+pub mod fix;
+pub mod json;
 pub mod level;
 pub mod sink;
 pub mod span;
 
 // 重新导出核心类型
-pub use diagnostic::{Diagnostic, Suggestion};
-pub use emitter::Emitter;
+pub use diagnostic::{Diagnostic, Label, Suggestion};
+pub use emitter::{Emitter, ErrorFormat};
 pub use level::DiagnosticLevel;
 pub use sink::DiagnosticSink;
 pub use span::{Span, SpanExt};
@@ -7,14 +7,6 @@ use lency_diagnostics::{Diagnostic, DiagnosticSink, Emitter};
 use lency_sema::SemanticError;
 use thiserror::Error;
 
-#[derive(Debug)]
-pub struct ParseErrorDetail {
-    pub span: std::ops::Range<usize>,
-    pub message: String,
-    pub label: Option<String>,
-    pub help: Option<String>,
-}
-
 /// 编译错误
 #[derive(Debug, Error)]
 pub enum CompileError {
@@ -22,10 +14,9 @@ pub enum CompileError {
     #[error("Lexical error: {0}")]
     LexError(String),
 
-    /// 语法错误
-    /// 语法错误
+    /// 语法错误，以及在恢复出的程序中发现的语义错误
     #[error("Parse error(s)")]
-    ParseError(Vec<ParseErrorDetail>),
+    ParseError(Vec<Diagnostic>),
 
     /// 语义错误（可能有多个）
     #[error("Semantic errors:\n{}", format_semantic_errors(.0))]
@@ -38,65 +29,66 @@ pub enum CompileError {
     /// IO 错误
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    /// 要运行的测试函数不存在
+    #[error("Test function not found: {0}")]
+    TestNotFound(String),
 }
 
 impl CompileError {
-    /// 转换为诊断列表并收集到 DiagnosticSink
-    pub fn collect_to_sink(&self, sink: &mut DiagnosticSink, file_path: Option<&str>) {
-        let mut add_diag = |diag: Diagnostic| {
-            if let Some(path) = file_path {
-                sink.add(diag.with_file(path));
-            } else {
-                sink.add(diag);
-            }
-        };
-
-        match self {
+    /// 转换为诊断列表；给出 `file_path`（入口源文件）时记录到每条诊断中，
+    /// 导入模块中的语义错误保留各自的源文件
+    pub fn diagnostics(&self, file_path: Option<&str>) -> Vec<Diagnostic> {
+        let diagnostics = match self {
             CompileError::LexError(msg) => {
-                add_diag(Diagnostic::error(format!("Lexical error: {}", msg)));
-            }
-            CompileError::ParseError(errors) => {
-                for err in errors {
-                    let mut diag = Diagnostic::error(err.message.clone()).span(err.span.clone());
-
-                    if let Some(label) = &err.label {
-                        diag = diag.with_note(label);
-                    }
-
-                    if let Some(help) = &err.help {
-                        diag = diag.suggest(help);
-                    }
-
-                    add_diag(diag);
-                }
+                vec![Diagnostic::error(format!("Lexical error: {}", msg))]
             }
+            CompileError::ParseError(errors) => errors.clone(),
             CompileError::SemanticErrors(errors) => {
-                for err in errors {
-                    let mut diag = err.to_diagnostic();
-                    if let Some(path) = file_path {
-                        diag.file_path = Some(path.to_string());
-                    }
-                    add_diag(diag);
-                }
-            }
-            CompileError::CodegenError(err) => {
-                add_diag(err.to_diagnostic());
-            }
-            CompileError::IoError(err) => {
-                add_diag(Diagnostic::error(format!("IO error: {}", err)));
+                errors.iter().map(SemanticError::to_diagnostic).collect()
             }
+            CompileError::CodegenError(err) => vec![err.to_diagnostic()],
+            CompileError::IoError(err) => vec![Diagnostic::error(format!("IO error: {}", err))],
+            CompileError::TestNotFound(name) => vec![Diagnostic::error(format!(
+                "Test function not found: {}",
+                name
+            ))],
+        };
+        match file_path {
+            Some(path) => diagnostics
+                .into_iter()
+                .map(|diag| match diag.file_path {
+                    Some(_) => diag,
+                    None => diag.with_file(path),
+                })
+                .collect(),
+            None => diagnostics,
+        }
+    }
+
+    /// 转换为诊断列表并收集到 DiagnosticSink
+    pub fn collect_to_sink(&self, sink: &mut DiagnosticSink, file_path: Option<&str>) {
+        for diag in self.diagnostics(file_path) {
+            sink.add(diag);
         }
     }
 
     /// 使用统一诊断系统输出错误
     pub fn emit(&self, file_path: Option<&str>, source: Option<&str>) {
+        self.emit_with(&Emitter::new(), file_path, source);
+    }
+
+    /// 使用指定的输出器（如 JSON 格式）输出错误
+    pub fn emit_with(&self, emitter: &Emitter, file_path: Option<&str>, source: Option<&str>) {
         let mut sink = DiagnosticSink::new();
         self.collect_to_sink(&mut sink, file_path);
 
-        let emitter = Emitter::new();
         if let Some(src) = source {
             for diag in sink.diagnostics() {
-                emitter.emit_with_source(diag, src);
+                match module_source(diag, file_path) {
+                    Some(module_src) => emitter.emit_with_source(diag, &module_src),
+                    None => emitter.emit_with_source(diag, src),
+                }
             }
         } else {
             emitter.emit_all(sink.diagnostics());
@@ -104,6 +96,15 @@ impl CompileError {
     }
 }
 
+/// 诊断位于导入模块时读取该模块的源码，行列与源码片段按模块文件计算
+pub(crate) fn module_source(diag: &Diagnostic, entry_path: Option<&str>) -> Option<String> {
+    let path = diag.file_path.as_deref()?;
+    if Some(path) == entry_path {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
 /// 格式化语义错误列表
 fn format_semantic_errors(errors: &[SemanticError]) -> String {
     errors
@@ -116,3 +117,55 @@ fn format_semantic_errors(errors: &[SemanticError]) -> String {
 
 /// 编译结果类型
 pub type CompileResult<T> = Result<T, CompileError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compile_with_options, CompileOptions};
+
+    #[test]
+    fn test_diagnostics_in_imported_modules_use_module_file() {
+        let dir = std::env::temp_dir().join(format!("lency_error_file_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let util_path = dir.join("util.lcy");
+        std::fs::write(
+            &util_path,
+            "int triple(int x) {\n    var count = x\n    return cont * 3\n}\n",
+        )
+        .unwrap();
+
+        let source = "import util.*\n\nint main() {\n    return triple(oops)\n}\n";
+        let options = CompileOptions {
+            search_paths: vec![dir.clone()],
+            ..CompileOptions::default()
+        };
+        let err = compile_with_options(source, &options).unwrap_err();
+        let diags = err.diagnostics(Some("app.lcy"));
+        let find = |name: &str| {
+            diags
+                .iter()
+                .find(|d| d.message.contains(name))
+                .unwrap_or_else(|| panic!("no diagnostic for {}", name))
+        };
+
+        // 入口源文件的诊断按入口源码计算行列
+        let oops = find("oops");
+        assert_eq!(oops.file_path.as_deref(), Some("app.lcy"));
+        assert_eq!(module_source(oops, Some("app.lcy")), None);
+        let json = lency_diagnostics::json::to_json(oops, Some(source));
+        assert_eq!(json["span"]["line_start"], 4);
+        assert_eq!(json["span"]["column_start"], 19);
+
+        // 导入模块的诊断记录模块文件，并按模块源码计算行列
+        let cont = find("cont");
+        let util = util_path.to_string_lossy().into_owned();
+        assert_eq!(cont.file_path.as_deref(), Some(util.as_str()));
+        let module_src = module_source(cont, Some("app.lcy")).expect("module source");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let json = lency_diagnostics::json::to_json(cont, Some(&module_src));
+        assert_eq!(json["file"], util.as_str());
+        assert_eq!(json["span"]["line_start"], 3);
+        assert_eq!(json["span"]["column_start"], 12);
+    }
+}
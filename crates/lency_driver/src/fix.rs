@@ -0,0 +1,103 @@
+//! Fix-its
+//!
+//! `lencyc fix` 的编译侧支持：编译源文件，把诊断中可自动应用的建议
+//! （如 "did you mean `count`?"）写回源码。
+
+use crate::{compile_program, parse_source, CompileError, CompileOptions, CompileResult};
+use lency_diagnostics::fix::{apply_suggestions, machine_applicable, AppliedFix};
+use lency_diagnostics::Diagnostic;
+
+/// 修复结果
+#[derive(Debug)]
+pub struct FixOutcome {
+    /// 修复后的源码
+    pub source: String,
+    /// 已应用的替换（位置相对原始源码）
+    pub applied: Vec<AppliedFix>,
+}
+
+/// 编译 `source` 并应用诊断中可自动修复的建议
+///
+/// 语法错误与代码生成错误没有可自动修复的建议，原样返回；
+/// 只应用入口源文件中的诊断，导入模块中的错误（位置属于模块文件）不会被应用。
+pub fn fix_source(source: &str, options: &CompileOptions) -> CompileResult<FixOutcome> {
+    let program = parse_source(source)?;
+
+    // lint 只检查入口源文件，警告都属于 `source`
+    let diagnostics: Vec<Diagnostic> = match compile_program(program, source, options) {
+        Ok(output) => output.warnings,
+        Err(CompileError::SemanticErrors(errors)) => errors
+            .iter()
+            .filter(|e| e.source_module().is_none())
+            .map(|e| e.to_diagnostic())
+            .collect(),
+        Err(err) => return Err(err),
+    };
+
+    let (source, applied) = apply_suggestions(source, &machine_applicable(&diagnostics));
+    Ok(FixOutcome { source, applied })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fix_misspelled_names() {
+        let source = "int square(int x) {\n    return x * x\n}\n\nint main() {\n    var count = 2\n    return sqare(cont)\n}\n";
+        let outcome = fix_source(source, &CompileOptions::default()).unwrap();
+        assert_eq!(
+            outcome.source,
+            "int square(int x) {\n    return x * x\n}\n\nint main() {\n    var count = 2\n    return square(count)\n}\n"
+        );
+        assert_eq!(outcome.applied.len(), 2);
+        assert_eq!(outcome.applied[0].message, "did you mean `square`?");
+
+        // 修复后的程序可以通过编译，再次运行没有可修复的内容
+        let again = fix_source(&outcome.source, &CompileOptions::default()).unwrap();
+        assert!(again.applied.is_empty());
+    }
+
+    #[test]
+    fn test_fix_leaves_unknown_names() {
+        let source = "int main() {\n    return missing_value\n}\n";
+        let outcome = fix_source(source, &CompileOptions::default()).unwrap();
+        assert_eq!(outcome.source, source);
+        assert!(outcome.applied.is_empty());
+    }
+
+    #[test]
+    fn test_fix_ignores_errors_in_imported_modules() {
+        let dir = std::env::temp_dir().join(format!("lency_fix_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // 模块中的拼写错误位于模块文件的偏移处，不能应用到入口源码
+        std::fs::write(
+            dir.join("util.lcy"),
+            "int triple(int x) {\n    var count = x\n    return cont * 3\n}\n",
+        )
+        .unwrap();
+
+        let source =
+            "import util.*\n\nint main() {\n    var total = 2\n    return triple(totl)\n}\n";
+        let options = CompileOptions {
+            search_paths: vec![dir.clone()],
+            ..CompileOptions::default()
+        };
+        let outcome = fix_source(source, &options);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let outcome = outcome.unwrap();
+        assert_eq!(
+            outcome.source,
+            "import util.*\n\nint main() {\n    var total = 2\n    return triple(total)\n}\n"
+        );
+        assert_eq!(outcome.applied.len(), 1);
+        assert_eq!(outcome.applied[0].message, "did you mean `total`?");
+    }
+
+    #[test]
+    fn test_fix_reports_parse_errors() {
+        let result = fix_source("int main( {", &CompileOptions::default());
+        assert!(matches!(result, Err(CompileError::ParseError(_))));
+    }
+}
@@ -0,0 +1,94 @@
+//! IDE Analysis
+//!
+//! 语言服务器的编译入口：容错解析文档，即使有语法或语义错误也继续分析，
+//! 返回文档中的诊断以及供悬停、跳转定义使用的分析结果。
+
+use crate::{syntax_diagnostic, CompileOptions};
+use lency_diagnostics::Diagnostic;
+use lency_sema::{analyze_for_ide_with_options, AnalysisResult};
+use std::collections::HashSet;
+
+/// 文档分析结果
+#[derive(Debug)]
+pub struct IdeAnalysis {
+    /// 文档中的语法与语义诊断（位置为文档中的字节偏移）
+    pub diagnostics: Vec<Diagnostic>,
+    /// 语义分析结果；无法从语法错误中恢复出程序时为 None
+    pub result: Option<AnalysisResult>,
+}
+
+/// 分析编辑器中的文档
+///
+/// 导入模块中的错误属于其他文件，不出现在 `diagnostics` 中。
+pub fn analyze_document(source: &str, options: &CompileOptions) -> IdeAnalysis {
+    let (program, syntax_errors) = lency_syntax::parser::parse_recovery(source);
+    let mut diagnostics: Vec<Diagnostic> = syntax_errors.iter().map(syntax_diagnostic).collect();
+
+    // 有语法错误时继续分析恢复后的程序，编辑中的文档仍可悬停与跳转
+    let Some(mut program) = program else {
+        return IdeAnalysis {
+            diagnostics,
+            result: None,
+        };
+    };
+
+    let (result, errors) = analyze_for_ide_with_options(&mut program, &options.analysis_options());
+
+    // 名称解析与类型检查可能对同一处报告相同的错误
+    let mut reported = HashSet::new();
+    diagnostics.extend(
+        errors
+            .iter()
+            .filter(|e| e.source_module().is_none())
+            .filter(|e| reported.insert((e.to_string(), e.span().clone())))
+            .map(|e| e.to_diagnostic()),
+    );
+
+    IdeAnalysis {
+        diagnostics,
+        result: Some(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_document_reports_only_document_errors() {
+        let dir = std::env::temp_dir().join(format!("lency_ide_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("util.lcy"),
+            "int helper() {\n    return missing\n}\n",
+        )
+        .unwrap();
+
+        let source = "import util.*\n\nint main() {\n    return helper() + oops\n}\n";
+        let options = CompileOptions {
+            search_paths: vec![dir.clone()],
+            ..CompileOptions::default()
+        };
+        let analysis = analyze_document(source, &options);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(analysis.diagnostics.len(), 1);
+        let span = analysis.diagnostics[0].span.clone().unwrap();
+        assert_eq!(&source[span], "oops");
+        assert!(analysis.result.is_some());
+    }
+
+    #[test]
+    fn test_analyze_document_recovers_from_syntax_errors() {
+        let analysis = analyze_document(
+            "int main() {\n    var x = ;\n    return x\n}\n",
+            &CompileOptions::default(),
+        );
+        assert_eq!(analysis.diagnostics.len(), 1);
+        assert_eq!(
+            analysis.diagnostics[0].code.as_deref(),
+            Some(crate::SYNTAX_ERROR)
+        );
+        assert!(analysis.result.is_some());
+    }
+}
@@ -3,114 +3,174 @@
 //! 编译器驱动模块，串联所有编译阶段
 
 pub mod error;
+pub mod fix;
+pub mod ide;
+pub mod manifest;
+mod partition;
+pub mod pipeline;
+pub mod session;
+pub mod testing;
 
 pub use error::{CompileError, CompileResult};
-
-use chumsky::Parser;
-use lency_codegen::compile_to_ir;
-use lency_sema::analyze;
+pub use fix::{fix_source, FixOutcome};
+pub use ide::{analyze_document, IdeAnalysis};
+pub use lency_diagnostics::{Emitter, ErrorFormat};
+pub use lency_sema::lint::ALL_LINTS;
+pub use lency_sema::{Lint, LintConfig, LintLevel};
+pub use manifest::{Manifest, ManifestError, MANIFEST_FILE};
+pub use pipeline::{CompiledUnit, Pipeline, PipelineOutput};
+pub use session::{BuildCache, ModuleGraph, Session};
+pub use testing::{compile_tests, discover_tests, TestCase, TEST_SELECTOR_ENV};
+
+use lency_codegen::{compile_to_ir_with_sources, SourceMap};
+use lency_diagnostics::Diagnostic;
+use lency_sema::resolver::search_path::find_module_source;
+use lency_sema::{
+    analyze_for_ide_with_options, analyze_with_options, AnalysisOptions, SemanticError,
+    SharedModuleCache,
+};
 use lency_syntax::ast::Program;
-use lency_syntax::lexer::Token;
-use lency_syntax::parser::program_parser;
-use logos::Logos;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 /// 编译结果
 #[derive(Debug)]
 pub struct CompilationOutput {
     /// 生成的 LLVM IR
     pub ir: String,
-    /// 警告信息
-    pub warnings: Vec<String>,
+    /// lint 警告（位置相对入口源文件）
+    pub warnings: Vec<Diagnostic>,
 }
 
+impl CompilationOutput {
+    /// 输出 lint 警告；`source` 为入口源文件内容，用于显示行列与源码片段
+    pub fn emit_warnings(&self, emitter: &Emitter, source: &str) {
+        for warning in &self.warnings {
+            emitter.emit_with_source(warning, source);
+        }
+    }
+}
+
+/// 编译选项
+#[derive(Debug, Clone, Default)]
+pub struct CompileOptions {
+    /// 生成 DWARF 调试信息 (`-g`)
+    pub debug_info: bool,
+    /// 入口源文件路径（panic 位置与调试信息中记录的文件名）
+    pub source_path: Option<PathBuf>,
+    /// import 模块的搜索路径（为空时为当前目录）
+    pub search_paths: Vec<PathBuf>,
+    /// 各 lint 的级别
+    pub lints: LintConfig,
+    /// release 构建：整数 `+ - *` 溢出时按位宽回绕，不做运行时检查
+    pub release: bool,
+    /// 导入模块的解析与分析缓存（见 [`lency_sema::module_cache`]）
+    pub module_cache: Option<SharedModuleCache>,
+}
+
+impl CompileOptions {
+    pub(crate) fn analysis_options(&self) -> AnalysisOptions {
+        AnalysisOptions {
+            search_paths: self.search_paths.clone(),
+            lints: self.lints.clone(),
+            module_cache: self.module_cache.clone(),
+        }
+    }
+}
+
+/// 语法错误的诊断代码
+pub const SYNTAX_ERROR: &str = "E1001";
+
 /// 解析源代码
-/// 解析源代码
-fn parse_source(source: &str) -> CompileResult<Program> {
-    use chumsky::Stream;
-
-    // 1. 词法分析 (保留 Span)
-    let token_iter = Token::lexer(source).spanned().map(|(tok, span)| match tok {
-        Ok(t) => (t, span),
-        Err(_) => (Token::Error, span),
-    });
-
-    let token_vec: Vec<(Token, std::ops::Range<usize>)> = token_iter.collect();
-    let len = source.len();
-
-    // 2. 创建 Stream
-    // Stream 需要一个迭代器，其中元素是 (Token, Span)
-    // 还需要指定 EOF 的 Span (这里用 source.len()..source.len())
-    let stream = Stream::from_iter(len..len, token_vec.into_iter());
-
-    // 3. 语法分析
-    let parser = program_parser();
-    parser.parse(stream).map_err(|e| {
-        let details = e
-            .into_iter()
-            .map(|err| {
-                // Simple<Token> has a public span field or method that doesn't need the trait imported if inherent,
-                // or if it is imported, it's seemingly not needed according to rustc.
-                // Actually Simple struct usually has span()
-                let span = err.span();
-                let msg = match err.reason() {
-                    chumsky::error::SimpleReason::Custom(msg) => msg.clone(),
-                    chumsky::error::SimpleReason::Unexpected => {
-                        format!(
-                            "Unexpected token found: {:?}",
-                            err.found()
-                                .map(|t| t.to_string())
-                                .unwrap_or_else(|| "EOF".to_string())
-                        )
-                    }
-                    chumsky::error::SimpleReason::Unclosed { span: _, delimiter } => {
-                        format!("Unclosed delimiter {:?}", delimiter)
-                    }
-                };
-
-                // Optional: Add "expected" info if useful, but keep it simple
-                // let expected = err.expected().map(|t| t.to_string()).collect::<Vec<_>>().join(", ");
-
-                let label = None;
-                let mut help = None;
-
-                // Simple heuristic for common errors
-                if let chumsky::error::SimpleReason::Unexpected = err.reason() {
-                    let expected: Vec<_> = err
-                        .expected()
-                        .map(|t| {
-                            t.as_ref()
-                                .map(|tok| tok.to_string())
-                                .unwrap_or_else(|| "EOF".to_string())
-                        })
-                        .collect();
-
-                    if !expected.is_empty() {
-                        if expected.len() < 5 {
-                            help = Some(format!("expected one of: {}", expected.join(", ")));
-                        } else {
-                            help = Some(format!(
-                                "expected one of: {}, ...",
-                                expected
-                                    .iter()
-                                    .take(4)
-                                    .cloned()
-                                    .collect::<Vec<_>>()
-                                    .join(", ")
-                            ));
-                        }
-                    }
-                }
-
-                crate::error::ParseErrorDetail {
-                    span,
-                    message: msg,
-                    label,
-                    help,
-                }
+pub(crate) fn parse_source(source: &str) -> CompileResult<Program> {
+    // 容错解析：一次报告文件中的所有语法错误
+    let (program, errors) = lency_syntax::parser::parse_recovery(source);
+    match program {
+        Some(program) if errors.is_empty() => Ok(program),
+        _ => Err(CompileError::ParseError(
+            errors.iter().map(syntax_diagnostic).collect(),
+        )),
+    }
+}
+
+/// 解析源代码；有语法错误时继续对恢复出的程序做语义分析，一并报告其中的语义错误
+///
+/// 涉及错误类型（语法错误恢复出的节点）的语义错误由语法错误引起，不再重复报告。
+pub(crate) fn parse_with_recovery(
+    source: &str,
+    options: &CompileOptions,
+) -> CompileResult<Program> {
+    let (program, errors) = lency_syntax::parser::parse_recovery(source);
+    let mut diagnostics: Vec<Diagnostic> = errors.iter().map(syntax_diagnostic).collect();
+    match program {
+        Some(program) if errors.is_empty() => return Ok(program),
+        Some(mut program) => {
+            let (_, errors) =
+                analyze_for_ide_with_options(&mut program, &options.analysis_options());
+            // 名称解析与类型检查可能对同一处报告相同的错误
+            let mut reported = HashSet::new();
+            diagnostics.extend(
+                errors
+                    .iter()
+                    .filter(|e| !e.involves_error_type())
+                    .filter(|e| reported.insert((e.to_string(), e.span().clone())))
+                    .map(SemanticError::to_diagnostic),
+            );
+        }
+        None => {}
+    }
+    Err(CompileError::ParseError(diagnostics))
+}
+
+/// 将 chumsky 的语法错误转换为诊断
+fn syntax_diagnostic(err: &lency_syntax::parser::ParserError) -> Diagnostic {
+    let span = err.span();
+    let msg = match err.reason() {
+        chumsky::error::SimpleReason::Custom(msg) => msg.clone(),
+        chumsky::error::SimpleReason::Unexpected => {
+            format!(
+                "Unexpected token found: {:?}",
+                err.found()
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "EOF".to_string())
+            )
+        }
+        chumsky::error::SimpleReason::Unclosed { span: _, delimiter } => {
+            format!("Unclosed delimiter {:?}", delimiter)
+        }
+    };
+
+    let mut diag = Diagnostic::error(msg).with_code(SYNTAX_ERROR).span(span);
+
+    // Simple heuristic for common errors
+    if let chumsky::error::SimpleReason::Unexpected = err.reason() {
+        let expected: Vec<_> = err
+            .expected()
+            .map(|t| {
+                t.as_ref()
+                    .map(|tok| tok.to_string())
+                    .unwrap_or_else(|| "EOF".to_string())
             })
             .collect();
-        CompileError::ParseError(details)
-    })
+
+        if !expected.is_empty() {
+            if expected.len() < 5 {
+                diag = diag.suggest(format!("expected one of: {}", expected.join(", ")));
+            } else {
+                diag = diag.suggest(format!(
+                    "expected one of: {}, ...",
+                    expected
+                        .iter()
+                        .take(4)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+    }
+
+    diag
 }
 
 /// 编译 Lency 源代码
@@ -131,32 +191,141 @@ fn parse_source(source: &str) -> CompileResult<Program> {
 /// println!("{}", output.ir);
 /// ```
 pub fn compile(source: &str) -> CompileResult<CompilationOutput> {
-    // 1. 词法 + 语法分析
-    let mut ast = parse_source(source)?;
+    compile_with_options(source, &CompileOptions::default())
+}
 
-    // 2. 语义分析
-    let _analysis_result = analyze(&mut ast).map_err(CompileError::SemanticErrors)?;
+/// 按选项编译 Lency 源代码
+pub fn compile_with_options(
+    source: &str,
+    options: &CompileOptions,
+) -> CompileResult<CompilationOutput> {
+    // 1. 词法 + 语法分析
+    let ast = parse_with_recovery(source, options)?;
+    compile_program(ast, source, options)
+}
 
-    // 3. 单态化 (Generic Monomorphization)
-    let mut monomorphizer = lency_monomorph::Monomorphizer::new();
-    let monomorphized_ast = monomorphizer.process(ast);
+/// 编译已解析的程序：语义分析、单态化与代码生成
+fn compile_program(
+    ast: Program,
+    source: &str,
+    options: &CompileOptions,
+) -> CompileResult<CompilationOutput> {
+    let checked = check_program(ast, source, options)?;
 
     // 4. 代码生成
-    let ir = compile_to_ir(&monomorphized_ast, "main", Some(source))?;
+    let ir = compile_to_ir_with_sources(
+        &checked.program,
+        "main",
+        checked.sources,
+        options.debug_info,
+        !options.release,
+    )?;
 
     Ok(CompilationOutput {
         ir,
-        warnings: Vec::new(),
+        warnings: checked.warnings,
     })
 }
 
+/// 通过语义分析并完成单态化、等待代码生成的程序
+pub(crate) struct CheckedProgram {
+    /// 单态化后的程序
+    pub(crate) program: Program,
+    /// 每个声明所在的导入模块源文件；入口文件的声明与单态化生成的实例为 None
+    pub(crate) decl_files: Vec<Option<PathBuf>>,
+    pub(crate) sources: SourceMap,
+    pub(crate) warnings: Vec<Diagnostic>,
+}
+
+/// 语义分析与单态化
+pub(crate) fn check_program(
+    mut ast: Program,
+    source: &str,
+    options: &CompileOptions,
+) -> CompileResult<CheckedProgram> {
+    // 2. 语义分析
+    let analysis_result = analyze_with_options(&mut ast, &options.analysis_options())
+        .map_err(CompileError::SemanticErrors)?;
+    let warnings = analysis_result
+        .warnings
+        .iter()
+        .map(|warning| match &options.source_path {
+            Some(path) => warning
+                .to_diagnostic()
+                .with_file(path.to_string_lossy().into_owned()),
+            None => warning.to_diagnostic(),
+        })
+        .collect();
+
+    // 单态化保留非模板声明的顺序，实例追加在末尾
+    let decl_files = ast
+        .decls
+        .iter()
+        .enumerate()
+        .filter(|(_, decl)| !lency_monomorph::is_template(decl))
+        .map(|(index, _)| {
+            analysis_result
+                .module_sources
+                .of_decl(index)
+                .map(|module| module.file.clone())
+        })
+        .collect();
+
+    // 3. 单态化 (Generic Monomorphization)
+    let mut monomorphizer = lency_monomorph::Monomorphizer::new();
+    let program = monomorphizer.process(ast);
+
+    Ok(CheckedProgram {
+        program,
+        decl_files,
+        sources: source_map(source, options, analysis_result.module_origins),
+        warnings,
+    })
+}
+
+/// 收集 panic 位置与调试信息需要的源文件：导入模块的查找与 import 解析一致
+fn source_map(
+    source: &str,
+    options: &CompileOptions,
+    module_origins: HashMap<String, String>,
+) -> SourceMap {
+    let entry = options
+        .source_path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("main.lcy"));
+    let mut map = SourceMap::new(entry, source);
+
+    let search_paths = if options.search_paths.is_empty() {
+        vec![PathBuf::new()]
+    } else {
+        options.search_paths.clone()
+    };
+    let mut seen = HashSet::new();
+    for module in module_origins.values() {
+        if !seen.insert(module) {
+            continue;
+        }
+        let components: Vec<String> = module.split('.').map(str::to_string).collect();
+        let path = find_module_source(&search_paths, &components);
+        if let Ok(text) = std::fs::read_to_string(&path) {
+            map = map.with_module(module, path, &text);
+        }
+    }
+
+    map.with_origins(module_origins)
+}
+
 /// 从文件编译
 ///
 /// # Arguments
 /// * `path` - 源文件路径
 pub fn compile_file(path: &str) -> CompileResult<CompilationOutput> {
     let source = std::fs::read_to_string(path)?;
-    compile(&source)
+    let options = CompileOptions {
+        source_path: Some(PathBuf::from(path)),
+        ..CompileOptions::default()
+    };
+    compile_with_options(&source, &options)
 }
 
 #[cfg(test)]
@@ -220,6 +389,106 @@ mod tests {
         assert!(output.ir.contains("load"));
     }
 
+    #[test]
+    fn test_compile_with_debug_info() {
+        let source = "int add(int a, int b) {\n    var sum = a + b\n    return sum\n}\n\nint main() {\n    return add(1, 2)\n}\n";
+        let options = CompileOptions {
+            debug_info: true,
+            source_path: Some(PathBuf::from("debug_test.lcy")),
+            ..CompileOptions::default()
+        };
+
+        let result = compile_with_options(source, &options);
+        assert!(result.is_ok(), "Compilation failed: {:?}", result.err());
+
+        let ir = result.unwrap().ir;
+        assert!(ir.contains("!DICompileUnit"));
+        assert!(ir.contains("filename: \"debug_test.lcy\""));
+        assert!(ir.contains("!DISubprogram(name: \"add\""));
+        assert!(ir.contains("linkageName: \"__lency_main\""));
+        assert!(ir.contains("!DILocalVariable(name: \"sum\""));
+        assert!(ir.contains("!DILocalVariable(name: \"a\", arg: 1"));
+        assert!(ir.contains("llvm.dbg.declare"));
+        // `return sum` 位于第 3 行第 5 列
+        assert!(ir.contains("!DILocation(line: 3, column: 5"));
+    }
+
+    #[test]
+    fn test_compile_without_debug_info() {
+        let output = compile("int main() {\n    return 0\n}\n").unwrap();
+        assert!(!output.ir.contains("!DICompileUnit"));
+    }
+
+    #[test]
+    fn test_compile_panic_records_location_and_trace() {
+        let source =
+            "void fail() {\n    panic(\"boom\")\n}\n\nint main() {\n    fail()\n    return 0\n}\n";
+        let options = CompileOptions {
+            source_path: Some(PathBuf::from("trace_test.lcy")),
+            ..CompileOptions::default()
+        };
+
+        let ir = compile_with_options(source, &options).unwrap().ir;
+        assert!(ir.contains("c\"trace_test.lcy\\00\""));
+        // panic(...) 位于第 2 行第 5 列
+        assert!(ir.contains("call void @lency_panic_at("));
+        assert!(ir.contains("i32 2, i32 5)"));
+        // 调用 fail() 前后维护影子调用栈
+        assert!(ir.contains("c\"fail\\00\""));
+        assert!(ir.contains("call void @lency_trace_push("));
+        assert!(ir.contains("call void @lency_trace_pop()"));
+    }
+
+    #[test]
+    fn test_compile_with_search_paths() {
+        let dir = std::env::temp_dir().join(format!("lency_search_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("util")).unwrap();
+        std::fs::write(
+            dir.join("util").join("math.lcy"),
+            "int triple(int x) {\n    return x * 3\n}\n",
+        )
+        .unwrap();
+
+        let source = "import util.math.*\n\nint main() {\n    return triple(2)\n}\n";
+        let options = CompileOptions {
+            search_paths: vec![dir.clone()],
+            ..CompileOptions::default()
+        };
+        let result = compile_with_options(source, &options);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let ir = result.unwrap().ir;
+        assert!(ir.contains("@triple"));
+    }
+
+    #[test]
+    fn test_compile_reports_lint_warnings() {
+        use lency_diagnostics::DiagnosticLevel;
+        use lency_sema::{Lint, LintLevel};
+
+        let source = "int main() {\n    var unused = 1\n    return 0\n}\n";
+        let options = CompileOptions {
+            source_path: Some(PathBuf::from("app.lcy")),
+            ..CompileOptions::default()
+        };
+        let warnings = compile_with_options(source, &options).unwrap().warnings;
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].level, DiagnosticLevel::Warning);
+        assert_eq!(warnings[0].message, "unused variable `unused`");
+        assert_eq!(warnings[0].file_path.as_deref(), Some("app.lcy"));
+
+        let denied = CompileOptions {
+            lints: LintConfig::new().with(Lint::UnusedVariable, LintLevel::Deny),
+            ..options
+        };
+        match compile_with_options(source, &denied) {
+            Err(CompileError::SemanticErrors(errors)) => {
+                assert_eq!(errors[0].to_string(), "unused variable `unused`");
+            }
+            other => panic!("Expected SemanticErrors, got {:?}", other.map(|o| o.ir)),
+        }
+    }
+
     #[test]
     fn test_compile_undefined_variable_error() {
         let source = r#"
@@ -240,6 +509,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_error_diagnostics_have_codes() {
+        let parse_error = compile("int main( {").unwrap_err();
+        let diags = parse_error.diagnostics(Some("bad.lcy"));
+        assert!(!diags.is_empty());
+        assert_eq!(diags[0].code.as_deref(), Some(SYNTAX_ERROR));
+        assert_eq!(diags[0].file_path.as_deref(), Some("bad.lcy"));
+
+        let semantic_error = compile("int main() {\n    return missing\n}\n").unwrap_err();
+        let diags = semantic_error.diagnostics(None);
+        assert_eq!(diags[0].code.as_deref(), Some("E0001"));
+        let json = lency_diagnostics::json::to_json(&diags[0], None);
+        assert_eq!(json["code"], "E0001");
+        assert_eq!(json["level"], "error");
+    }
+
+    #[test]
+    fn test_reports_every_syntax_error() {
+        let source = "int main() {\n    var a = 1 +* 2\n    return ) 0\n}\nint f(int a,, int b) { return a }\n";
+        let err = compile(source).unwrap_err();
+        let diags = err.diagnostics(None);
+        assert_eq!(diags.len(), 3);
+        assert!(diags
+            .iter()
+            .all(|d| d.code.as_deref() == Some(SYNTAX_ERROR)));
+        // 按源码位置排序
+        let starts: Vec<_> = diags
+            .iter()
+            .filter_map(|d| d.span.as_ref())
+            .map(|s| s.start)
+            .collect();
+        assert!(starts.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_reports_semantic_errors_after_syntax_errors() {
+        let source = "int main() {\n    var x = ;\n    return x + undefined_thing\n}\n";
+        let diags = compile(source).unwrap_err().diagnostics(None);
+        let codes: Vec<_> = diags.iter().map(|d| d.code.as_deref()).collect();
+        assert_eq!(codes, vec![Some(SYNTAX_ERROR), Some("E0001")]);
+        let span = diags[1].span.clone().unwrap();
+        assert_eq!(&source[span], "undefined_thing");
+    }
+
     #[test]
     fn test_compile_type_mismatch_error() {
         let source = r#"
@@ -0,0 +1,240 @@
+//! Package Manifest
+//!
+//! `lency.toml` 描述一个包：包名、入口文件、源码根目录、额外的库搜索路径与链接的 C 库。
+//! 在包目录中不带文件参数运行 `lencyc build` / `run` / `check` 时按清单构建。
+//!
+//! ```toml
+//! [package]
+//! name = "calc"
+//! version = "0.1.0"
+//! entry = "src/main.lcy"      # 默认 src/main.lcy
+//!
+//! [build]
+//! source-roots = ["src"]      # import 搜索路径，默认为包根目录
+//! lib-paths = ["../lency"]    # 额外的库搜索路径，在源码根目录之后查找
+//! links = ["m"]               # 链接的 C 库 (-lm)
+//! ```
+//!
+//! 清单由 `toml` 解析到与文件结构对应的 serde 结构，再转换为 [`Manifest`]；
+//! 未知的表或键视为错误，避免拼写错误被悄悄忽略。
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// 清单文件名
+pub const MANIFEST_FILE: &str = "lency.toml";
+
+/// 默认入口文件（相对包根目录）
+const DEFAULT_ENTRY: &str = "src/main.lcy";
+
+/// 清单错误
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("failed to read {}: {source}", .path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// TOML 语法错误，或清单结构不符（缺少键、未知键、值的类型不对）
+    #[error("{MANIFEST_FILE}{}: {message}", .line.map(|line| format!(":{}", line)).unwrap_or_default())]
+    Invalid {
+        line: Option<usize>,
+        message: String,
+    },
+}
+
+impl ManifestError {
+    fn from_toml(err: toml::de::Error, text: &str) -> Self {
+        // 文件末尾的错误（如未闭合的数组）算在最后一行
+        let last_line = text.lines().count().max(1);
+        let line = err.span().map(|span| {
+            let line = text[..span.start.min(text.len())].matches('\n').count() + 1;
+            line.min(last_line)
+        });
+        // 行号之外的信息只保留首行，源码片段由行号给出
+        let message = err.message().lines().next().unwrap_or_default().to_string();
+        ManifestError::Invalid { line, message }
+    }
+}
+
+/// 包清单
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// 包根目录（清单所在目录），其余路径相对于它
+    pub root: PathBuf,
+    pub name: String,
+    pub version: Option<String>,
+    pub entry: PathBuf,
+    pub source_roots: Vec<PathBuf>,
+    pub lib_paths: Vec<PathBuf>,
+    /// 链接的 C 库名（不含 `lib` 前缀与扩展名）
+    pub links: Vec<String>,
+}
+
+/// `lency.toml` 的文件结构
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    package: PackageSection,
+    #[serde(default)]
+    build: BuildSection,
+}
+
+/// `[package]` 表
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackageSection {
+    name: String,
+    version: Option<String>,
+    entry: Option<PathBuf>,
+}
+
+/// `[build]` 表
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct BuildSection {
+    source_roots: Vec<PathBuf>,
+    lib_paths: Vec<PathBuf>,
+    links: Vec<String>,
+}
+
+impl Manifest {
+    /// 读取 `dir` 中的清单；没有清单时返回 `Ok(None)`
+    pub fn discover(dir: &Path) -> Result<Option<Self>, ManifestError> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let text =
+            std::fs::read_to_string(&path).map_err(|source| ManifestError::Io { path, source })?;
+        Self::parse(&text, dir).map(Some)
+    }
+
+    /// 解析清单内容，`root` 为包根目录
+    pub fn parse(text: &str, root: &Path) -> Result<Self, ManifestError> {
+        let file: ManifestFile =
+            toml::from_str(text).map_err(|err| ManifestError::from_toml(err, text))?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            name: file.package.name,
+            version: file.package.version,
+            entry: file
+                .package
+                .entry
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_ENTRY)),
+            source_roots: file.build.source_roots,
+            lib_paths: file.build.lib_paths,
+            links: file.build.links,
+        })
+    }
+
+    /// 入口文件路径
+    pub fn entry_path(&self) -> PathBuf {
+        self.root.join(&self.entry)
+    }
+
+    /// import 搜索路径：源码根目录（默认为包根目录）之后是库路径
+    pub fn search_paths(&self) -> Vec<PathBuf> {
+        let roots = if self.source_roots.is_empty() {
+            vec![self.root.clone()]
+        } else {
+            self.source_roots
+                .iter()
+                .map(|dir| self.root.join(dir))
+                .collect()
+        };
+        roots
+            .into_iter()
+            .chain(self.lib_paths.iter().map(|dir| self.root.join(dir)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_manifest() {
+        let text = r#"
+# 计算器
+[package]
+name = "calc"       # 包名
+version = "0.1.0"
+entry = "src/app.lcy"
+
+[build]
+source-roots = ["src", "gen"]
+lib-paths = [
+    "../shared",   # 共享库
+    "vendor#1",
+]
+links = ["m"]
+"#;
+        let manifest = Manifest::parse(text, Path::new("/work/calc")).unwrap();
+        assert_eq!(manifest.name, "calc");
+        assert_eq!(manifest.version.as_deref(), Some("0.1.0"));
+        assert_eq!(
+            manifest.entry_path(),
+            PathBuf::from("/work/calc/src/app.lcy")
+        );
+        assert_eq!(manifest.links, vec!["m"]);
+        assert_eq!(
+            manifest.search_paths(),
+            vec![
+                PathBuf::from("/work/calc/src"),
+                PathBuf::from("/work/calc/gen"),
+                PathBuf::from("/work/calc/../shared"),
+                PathBuf::from("/work/calc/vendor#1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_defaults() {
+        let manifest = Manifest::parse("[package]\nname = \"app\"\n", Path::new("")).unwrap();
+        assert_eq!(manifest.entry_path(), PathBuf::from("src/main.lcy"));
+        assert_eq!(manifest.search_paths(), vec![PathBuf::new()]);
+        assert!(manifest.links.is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let parse = |text: &str| {
+            Manifest::parse(text, Path::new(""))
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            parse("[package]\nversion = \"1\"\n"),
+            "lency.toml:1: missing field `name`"
+        );
+        assert_eq!(
+            parse("[package]\nname = \"a\"\nentry = [\"x\"]\n"),
+            "lency.toml:3: invalid type: sequence, expected path string"
+        );
+        assert_eq!(
+            parse("[package]\nname = \"a\"\n[build]\nlink = [\"m\"]\n"),
+            "lency.toml:4: unknown field `link`, expected one of `source-roots`, `lib-paths`, `links`"
+        );
+        assert_eq!(
+            parse("[package]\nname = \"a\"\n[tools]\nx = 1\n"),
+            "lency.toml:3: unknown field `tools`, expected `package` or `build`"
+        );
+        assert_eq!(
+            parse("[package]\nname = \"a\"\nname = \"b\"\n"),
+            "lency.toml:3: duplicate key `name` in table `package`"
+        );
+        assert_eq!(
+            parse("[package]\nname = \"a\n"),
+            "lency.toml:2: invalid basic string"
+        );
+        assert_eq!(
+            parse("[package]\nname = \"a\"\n[build]\nlinks = [\"m\"\n"),
+            "lency.toml:4: invalid array"
+        );
+    }
+}
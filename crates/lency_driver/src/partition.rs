@@ -0,0 +1,79 @@
+//! Module Partitions
+//!
+//! 分模块代码生成：每个源文件对应一个编译单元，各自生成 LLVM IR 与目标文件。
+//!
+//! 入口文件的声明、单态化生成的泛型实例、`main` 包装函数与全局变量初始化属于入口单元；
+//! 导入模块的声明属于该模块的单元。一个单元的目标文件只取决于本模块的源码
+//! 与整个程序的接口（见 [`interface_hash`]），所以只修改某个模块的函数体时，
+//! 只有这个模块需要重新生成代码。
+
+use crate::session::StableHasher;
+use crate::{CheckedProgram, CompileOptions, CompileResult};
+use lency_codegen::{compile_partition_to_ir, Partition};
+use lency_sema::module_cache::signature;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// 把声明分配到编译单元
+///
+/// `modules` 为依赖图中的导入模块；不在其中的模块（如无法对应到依赖图的文件）归入口单元。
+pub(crate) fn unit_decls(
+    checked: &CheckedProgram,
+    entry: &Path,
+    modules: &BTreeSet<PathBuf>,
+) -> BTreeMap<PathBuf, Vec<usize>> {
+    let mut units: BTreeMap<PathBuf, Vec<usize>> = modules
+        .iter()
+        .map(|module| (module.clone(), Vec::new()))
+        .collect();
+    units.insert(entry.to_path_buf(), Vec::new());
+    for index in 0..checked.program.decls.len() {
+        let unit = checked
+            .decl_files
+            .get(index)
+            .and_then(Option::as_ref)
+            .filter(|file| modules.contains(*file))
+            .map_or(entry, PathBuf::as_path);
+        if let Some(decls) = units.get_mut(unit) {
+            decls.push(index);
+        }
+    }
+    units
+}
+
+/// 为一个编译单元生成 LLVM IR
+pub(crate) fn compile_unit(
+    checked: &CheckedProgram,
+    decls: &[usize],
+    entry: bool,
+    options: &CompileOptions,
+) -> CompileResult<String> {
+    Ok(compile_partition_to_ir(
+        &checked.program,
+        "main",
+        checked.sources.clone(),
+        Partition::new(decls.iter().copied(), entry),
+        options.debug_info,
+        !options.release,
+    )?)
+}
+
+/// 整个程序的接口哈希
+///
+/// 覆盖其他单元生成代码时可见的全部信息：函数签名、类型定义、Trait，
+/// 以及完整的全局变量声明（初始化代码位于入口单元）与泛型实例（定义在入口单元）。
+/// 普通函数体不在其中，修改函数体只使定义它的单元失效。
+pub(crate) fn interface_hash(checked: &CheckedProgram) -> u64 {
+    let mut hasher = StableHasher::new();
+    for (index, decl) in checked.program.decls.iter().enumerate() {
+        let generated = index >= checked.decl_files.len();
+        let signature = if generated {
+            Some(format!("{:?}", decl))
+        } else {
+            signature(decl)
+        };
+        signature.hash(&mut hasher);
+    }
+    hasher.finish()
+}
@@ -1 +1,289 @@
-// Placeholder for pipeline.rs
+//! Compilation Pipeline
+//!
+//! 带缓存的分模块编译流水线。每个源文件是一个编译单元（见 [`crate::partition`]），
+//! 单元的目标文件按单元键缓存在磁盘上：
+//!
+//! - **程序键**：编译器构建、编译选项与全部模块的源码。命中且所有单元的目标文件都在缓存中时，
+//!   直接复用，跳过词法、语法、语义分析与代码生成。
+//! - **单元键**：编译器构建、编译选项、本模块的源码与整个程序的接口哈希。
+//!   程序键未命中时仍需对整个程序做语义分析（导入模块被合并到同一个程序中检查），
+//!   但只为单元键变化的模块生成 LLVM IR，其余模块复用缓存的目标文件。
+//!
+//! 语义分析本身也按模块缓存（见 [`lency_sema::module_cache`]）：源码未变的导入模块不再解析，
+//! 自身与所导入模块的接口都未变的模块不再分析。
+//!
+//! 目标文件由调用方（`lency build`）用 llc 生成后通过 [`crate::BuildCache::store_object`] 写回。
+
+use crate::partition::{compile_unit, interface_hash, unit_decls};
+use crate::session::{compiler_build_id, Session, StableHasher};
+use crate::{check_program, parse_with_recovery, CompileOptions, CompileResult, Emitter};
+use lency_diagnostics::Diagnostic;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// 一个模块的编译结果
+#[derive(Debug)]
+pub struct CompiledUnit {
+    /// 模块源文件
+    pub module: PathBuf,
+    /// 单元键（目标文件在缓存中的位置见 [`crate::BuildCache::object_path`]）
+    pub key: u64,
+    /// 需要重新生成目标文件时为 LLVM IR；为 None 时复用缓存的目标文件
+    pub ir: Option<String>,
+}
+
+/// 流水线编译结果
+#[derive(Debug)]
+pub struct PipelineOutput {
+    /// lint 警告（位置相对入口源文件）
+    pub warnings: Vec<Diagnostic>,
+    /// 每个模块的编译单元，链接全部单元的目标文件得到程序
+    pub units: Vec<CompiledUnit>,
+    /// 是否完全命中缓存（没有单元需要重新生成）
+    pub cached: bool,
+    /// 与上次构建相比发生变化的模块
+    pub changed_modules: Vec<PathBuf>,
+    /// 重新解析的导入模块（其余复用缓存的语法树）
+    pub parsed_modules: Vec<PathBuf>,
+    /// 重新做语义分析的导入模块（其余复用缓存的分析结果）
+    pub analyzed_modules: Vec<PathBuf>,
+}
+
+impl PipelineOutput {
+    /// 输出 lint 警告；`source` 为入口源文件内容
+    pub fn emit_warnings(&self, emitter: &Emitter, source: &str) {
+        for warning in &self.warnings {
+            emitter.emit_with_source(warning, source);
+        }
+    }
+
+    /// 重新生成了 IR 的单元
+    pub fn rebuilt_units(&self) -> impl Iterator<Item = &CompiledUnit> {
+        self.units.iter().filter(|unit| unit.ir.is_some())
+    }
+}
+
+/// 编译流水线
+pub struct Pipeline<'a> {
+    session: &'a Session,
+    options: CompileOptions,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new(session: &'a Session) -> Self {
+        Self {
+            session,
+            options: CompileOptions::default(),
+        }
+    }
+
+    /// 使用指定的编译选项（源文件路径与搜索路径由 `compile_file` 填入）
+    pub fn with_options(mut self, options: CompileOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 编译文件，尽可能复用缓存
+    pub fn compile_file(&self, path: &Path) -> CompileResult<PipelineOutput> {
+        let graph = self.session.module_graph(path)?;
+        let options_key = self.options_key(path);
+        let program_key = combine(options_key, graph.fingerprint());
+        let cache = self.session.cache();
+        let changed_modules = match cache {
+            Some(cache) => cache.changed_modules(&graph),
+            None => graph.modules().map(|m| m.path.clone()).collect(),
+        };
+
+        // 所有单元的目标文件都在缓存中：跳过整个前端
+        if let Some(cache) = cache {
+            if let Some(program) = cache.load_program(program_key) {
+                if program
+                    .units
+                    .iter()
+                    .all(|(_, key)| cache.cached_object(*key).is_some())
+                {
+                    let _ = cache.record_modules(&graph);
+                    let units = program
+                        .units
+                        .into_iter()
+                        .map(|(module, key)| CompiledUnit {
+                            module,
+                            key,
+                            ir: None,
+                        })
+                        .collect();
+                    return Ok(PipelineOutput {
+                        warnings: program.warnings,
+                        units,
+                        cached: true,
+                        changed_modules,
+                        parsed_modules: Vec::new(),
+                        analyzed_modules: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        let module_cache = cache.map(|c| c.load_modules().shared());
+        let options = CompileOptions {
+            source_path: Some(path.to_path_buf()),
+            search_paths: self.session.search_paths(),
+            module_cache: module_cache.clone(),
+            ..self.options.clone()
+        };
+        let source = std::fs::read_to_string(path)?;
+        let checked = parse_with_recovery(&source, &options)
+            .and_then(|program| check_program(program, &source, &options));
+        // 分析出错时同样保存解析过的模块
+        let (mut parsed_modules, mut analyzed_modules) = (Vec::new(), Vec::new());
+        if let Some(modules) = module_cache.as_ref().and_then(|m| m.lock().ok()) {
+            parsed_modules = modules.parsed().to_vec();
+            analyzed_modules = modules.analyzed().map(Path::to_path_buf).collect();
+            if let Some(cache) = cache {
+                let _ = cache.store_modules(&modules);
+            }
+        }
+        let checked = checked?;
+        let interface = interface_hash(&checked);
+
+        let modules: BTreeSet<PathBuf> = graph
+            .modules()
+            .map(|m| m.path.clone())
+            .filter(|module| module != path)
+            .collect();
+        let mut units = Vec::new();
+        for (module, decls) in unit_decls(&checked, path, &modules) {
+            let content_hash = graph.get(&module).map(|m| m.content_hash);
+            let key = unit_key(options_key, &module, content_hash, interface);
+            let ir = match cache.and_then(|c| c.cached_object(key)) {
+                Some(_) => None,
+                None => Some(compile_unit(&checked, &decls, module == path, &options)?),
+            };
+            units.push(CompiledUnit { module, key, ir });
+        }
+
+        if let Some(cache) = cache {
+            let keys: Vec<(PathBuf, u64)> = units
+                .iter()
+                .map(|unit| (unit.module.clone(), unit.key))
+                .collect();
+            // 写缓存失败不影响本次编译结果
+            let _ = cache
+                .store_program(program_key, &checked.warnings, &keys)
+                .and_then(|_| cache.record_modules(&graph));
+        }
+
+        Ok(PipelineOutput {
+            warnings: checked.warnings,
+            units,
+            cached: false,
+            changed_modules,
+            parsed_modules,
+            analyzed_modules,
+        })
+    }
+}
+
+impl Pipeline<'_> {
+    /// 影响编译结果的全部外部因素：编译器构建、入口文件路径（调试信息与 panic 位置），
+    /// 调试信息与 release（溢出检查与 llc 优化级别），以及 lint 级别（警告与是否报错）
+    fn options_key(&self, entry: &Path) -> u64 {
+        let mut hasher = StableHasher::new();
+        compiler_build_id().hash(&mut hasher);
+        entry.hash(&mut hasher);
+        self.options.debug_info.hash(&mut hasher);
+        self.options.release.hash(&mut hasher);
+        self.options.lints.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+fn combine(options_key: u64, fingerprint: u64) -> u64 {
+    let mut hasher = StableHasher::new();
+    options_key.hash(&mut hasher);
+    fingerprint.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn unit_key(options_key: u64, module: &Path, content_hash: Option<u64>, interface: u64) -> u64 {
+    let mut hasher = StableHasher::new();
+    options_key.hash(&mut hasher);
+    module.hash(&mut hasher);
+    content_hash.hash(&mut hasher);
+    interface.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_only_changed_module_is_recompiled() {
+        let root = std::env::temp_dir().join(format!("lency_pipeline_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let main = root.join("main.lcy");
+        fs::write(
+            &main,
+            "import util.*\nimport other.*\nint main() { return f() + g() }\n",
+        )
+        .unwrap();
+        fs::write(root.join("util.lcy"), "pub int f() { return 1 }\n").unwrap();
+        fs::write(root.join("other.lcy"), "pub int g() { return 2 }\n").unwrap();
+
+        let session = Session::with_cache(&root, root.join("cache"));
+        let cache = session.cache().unwrap();
+        // 模拟 llc：把每个单元的 IR 当作目标文件存入缓存
+        let build = || {
+            let output = Pipeline::new(&session).compile_file(&main).unwrap();
+            for unit in output.rebuilt_units() {
+                let obj = root.join("unit.o");
+                fs::write(&obj, unit.ir.as_deref().unwrap()).unwrap();
+                cache.store_object(unit.key, &obj).unwrap();
+            }
+            output
+        };
+
+        let first = build();
+        assert!(!first.cached);
+        assert_eq!(first.analyzed_modules.len(), 2);
+        assert_eq!(first.units.len(), 3);
+        assert_eq!(first.rebuilt_units().count(), 3);
+        let util_ir = first
+            .units
+            .iter()
+            .find(|unit| unit.module == root.join("util.lcy"))
+            .and_then(|unit| unit.ir.as_deref())
+            .unwrap();
+        assert!(util_ir.contains("define i64 @f("));
+        assert!(util_ir.contains("declare i64 @g("));
+        assert!(!util_ir.contains("define i32 @main("));
+
+        let second = build();
+        assert!(second.cached);
+        assert_eq!(second.rebuilt_units().count(), 0);
+
+        // 只修改函数体：只有该模块重新解析、分析与生成
+        fs::write(root.join("util.lcy"), "pub int f() { return 3 }\n").unwrap();
+        let third = build();
+        assert!(!third.cached);
+        let rebuilt: Vec<_> = third.rebuilt_units().map(|u| u.module.clone()).collect();
+        assert_eq!(rebuilt, vec![root.join("util.lcy")]);
+        assert_eq!(third.parsed_modules, vec![root.join("util.lcy")]);
+        assert_eq!(third.analyzed_modules, vec![root.join("util.lcy")]);
+
+        // 修改签名：接口变化，所有单元重新生成
+        fs::write(root.join("other.lcy"), "pub int g(int x) { return x }\n").unwrap();
+        fs::write(
+            &main,
+            "import util.*\nimport other.*\nint main() { return f() + g(2) }\n",
+        )
+        .unwrap();
+        assert_eq!(build().rebuilt_units().count(), 3);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1 +0,0 @@
-// Placeholder for session.rs
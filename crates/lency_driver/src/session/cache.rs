@@ -0,0 +1,306 @@
+//! Build Cache
+//!
+//! 增量编译的磁盘缓存。
+//!
+//! 目录布局：
+//! - `modules.idx`：上次构建时每个模块的内容哈希，用于统计变化的模块
+//! - `<程序键>/units.txt`：程序由哪些编译单元组成，每行 `单元键\t模块路径`
+//! - `<程序键>/warnings.txt`：编译警告，每行一条（见 [`encode_warning`]）
+//! - `objects/<单元键>.o`：每个模块的目标文件
+//! - `modules/<编译器构建>/<路径哈希>.json`：导入模块的语法树与分析结果
+//!   （见 [`lency_sema::module_cache`]），JSON 格式
+//!
+//! 程序键与单元键的计算见 [`crate::pipeline`]。
+
+use super::{compiler_build_id, stable_hash, ModuleGraph};
+use lency_diagnostics::Diagnostic;
+use lency_sema::module_cache::{CachedModule, ModuleCache};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 缓存中记录的程序
+#[derive(Debug, Clone)]
+pub struct CachedProgram {
+    /// 编译警告
+    pub warnings: Vec<Diagnostic>,
+    /// 每个模块的单元键
+    pub units: Vec<(PathBuf, u64)>,
+}
+
+/// 增量编译磁盘缓存
+#[derive(Debug, Clone)]
+pub struct BuildCache {
+    dir: PathBuf,
+}
+
+impl BuildCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn program_dir(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}", key))
+    }
+
+    /// 读取缓存的程序；单元的目标文件是否存在由调用方检查
+    pub fn load_program(&self, key: u64) -> Option<CachedProgram> {
+        let dir = self.program_dir(key);
+        let units = read_index(&dir.join("units.txt"))?;
+        let warnings = fs::read_to_string(dir.join("warnings.txt"))
+            .map(|s| s.lines().filter_map(decode_warning).collect())
+            .unwrap_or_default();
+        Some(CachedProgram { warnings, units })
+    }
+
+    /// 写入程序的警告与编译单元（单元列表最后写入，先写临时文件再重命名，避免留下半截缓存）
+    pub fn store_program(
+        &self,
+        key: u64,
+        warnings: &[Diagnostic],
+        units: &[(PathBuf, u64)],
+    ) -> io::Result<()> {
+        let dir = self.program_dir(key);
+        fs::create_dir_all(&dir)?;
+        let lines: Vec<String> = warnings.iter().map(encode_warning).collect();
+        fs::write(dir.join("warnings.txt"), lines.join("\n"))?;
+        let tmp = dir.join("units.txt.tmp");
+        fs::write(&tmp, write_index(units.iter().map(|(p, k)| (p, *k))))?;
+        fs::rename(tmp, dir.join("units.txt"))
+    }
+
+    /// 编译单元的目标文件在缓存中的路径（不保证存在）
+    pub fn object_path(&self, unit: u64) -> PathBuf {
+        self.dir.join("objects").join(format!("{:016x}.o", unit))
+    }
+
+    /// 缓存中已有的目标文件
+    pub fn cached_object(&self, unit: u64) -> Option<PathBuf> {
+        let path = self.object_path(unit);
+        path.is_file().then_some(path)
+    }
+
+    /// 把新生成的目标文件存入缓存
+    pub fn store_object(&self, unit: u64, obj: &Path) -> io::Result<PathBuf> {
+        let path = self.object_path(unit);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("o.tmp");
+        fs::copy(obj, &tmp)?;
+        fs::rename(tmp, &path)?;
+        Ok(path)
+    }
+
+    /// 与上次构建相比内容发生变化（或新增）的模块
+    pub fn changed_modules(&self, graph: &ModuleGraph) -> Vec<PathBuf> {
+        let previous = self.load_index();
+        graph
+            .modules()
+            .filter(|m| previous.get(&m.path) != Some(&m.content_hash))
+            .map(|m| m.path.clone())
+            .collect()
+    }
+
+    /// 记录本次构建的模块内容哈希
+    pub fn record_modules(&self, graph: &ModuleGraph) -> io::Result<()> {
+        let mut index = self.load_index();
+        for module in graph.modules() {
+            index.insert(module.path.clone(), module.content_hash);
+        }
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            self.dir.join("modules.idx"),
+            write_index(index.iter().map(|(p, h)| (p, *h))),
+        )
+    }
+
+    /// 当前编译器构建的模块缓存目录（其他构建写入的语法树格式可能不同）
+    fn modules_dir(&self) -> PathBuf {
+        self.dir
+            .join("modules")
+            .join(format!("{:016x}", compiler_build_id()))
+    }
+
+    /// 读取导入模块的缓存；读不出或格式不符的条目忽略
+    pub fn load_modules(&self) -> ModuleCache {
+        let mut cache = ModuleCache::new();
+        let Ok(entries) = fs::read_dir(self.modules_dir()) else {
+            return cache;
+        };
+        for entry in entries.flatten() {
+            let module = fs::read(entry.path())
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<CachedModule>(&bytes).ok());
+            if let Some(module) = module {
+                cache.insert(module);
+            }
+        }
+        cache
+    }
+
+    /// 写入最近一次分析中更新过的模块
+    pub fn store_modules(&self, cache: &ModuleCache) -> io::Result<()> {
+        let dir = self.modules_dir();
+        fs::create_dir_all(&dir)?;
+        for module in cache.updated() {
+            let path = dir.join(format!("{:016x}.json", stable_hash(&module.file)));
+            let tmp = path.with_extension("json.tmp");
+            fs::write(&tmp, serde_json::to_vec(module)?)?;
+            fs::rename(tmp, path)?;
+        }
+        Ok(())
+    }
+
+    fn load_index(&self) -> BTreeMap<PathBuf, u64> {
+        read_index(&self.dir.join("modules.idx"))
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// 索引文件格式：每行 `哈希\t路径`
+fn write_index<'a>(entries: impl Iterator<Item = (&'a PathBuf, u64)>) -> String {
+    entries
+        .map(|(path, hash)| format!("{:016x}\t{}\n", hash, path.display()))
+        .collect()
+}
+
+fn read_index(path: &Path) -> Option<Vec<(PathBuf, u64)>> {
+    let content = fs::read_to_string(path).ok()?;
+    Some(
+        content
+            .lines()
+            .filter_map(|line| {
+                let (hash, path) = line.split_once('\t')?;
+                Some((PathBuf::from(path), u64::from_str_radix(hash, 16).ok()?))
+            })
+            .collect(),
+    )
+}
+
+/// 警告的缓存格式：`起点\t终点\t文件\t消息`，之后每个注释为 `\tnote:...`、
+/// 每个建议为 `\thelp:...`（没有位置时起止为空）
+fn encode_warning(warning: &Diagnostic) -> String {
+    let clean = |text: &str| text.replace(['\t', '\n'], " ");
+    let (start, end) = match &warning.span {
+        Some(span) => (span.start.to_string(), span.end.to_string()),
+        None => (String::new(), String::new()),
+    };
+    let mut line = format!(
+        "{}\t{}\t{}\t{}",
+        start,
+        end,
+        clean(warning.file_path.as_deref().unwrap_or("")),
+        clean(&warning.message)
+    );
+    if let Some(code) = &warning.code {
+        line.push_str(&format!("\tcode:{}", clean(code)));
+    }
+    for note in &warning.notes {
+        line.push_str(&format!("\tnote:{}", clean(note)));
+    }
+    for suggestion in &warning.suggestions {
+        line.push_str(&format!("\thelp:{}", clean(&suggestion.message)));
+    }
+    line
+}
+
+fn decode_warning(line: &str) -> Option<Diagnostic> {
+    let mut fields = line.split('\t');
+    let (start, end) = (fields.next()?, fields.next()?);
+    let file = fields.next()?;
+    let mut warning = Diagnostic::warning(fields.next()?);
+    if let (Ok(start), Ok(end)) = (start.parse(), end.parse()) {
+        warning = warning.span(start..end);
+    }
+    if !file.is_empty() {
+        warning = warning.with_file(file);
+    }
+    for field in fields {
+        if let Some(code) = field.strip_prefix("code:") {
+            warning = warning.with_code(code);
+        } else if let Some(note) = field.strip_prefix("note:") {
+            warning = warning.with_note(note);
+        } else if let Some(help) = field.strip_prefix("help:") {
+            warning = warning.suggest(help);
+        }
+    }
+    Some(warning)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Session;
+
+    #[test]
+    fn test_cache_roundtrip_and_changed_modules() {
+        let root = std::env::temp_dir().join(format!("lency_cache_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("main.lcy"),
+            "import util.*\nint main() { return 0; }",
+        )
+        .unwrap();
+        fs::write(root.join("util.lcy"), "int f() { return 1; }").unwrap();
+
+        let session = Session::with_cache(&root, root.join("cache"));
+        let cache = session.cache().unwrap();
+        let graph = session.module_graph(&root.join("main.lcy")).unwrap();
+        let key = graph.fingerprint();
+
+        assert!(cache.load_program(key).is_none());
+        assert_eq!(cache.changed_modules(&graph).len(), 2);
+
+        let warning = Diagnostic::warning("unused variable `x`")
+            .with_code("unused-variable")
+            .span(3..8)
+            .with_file("main.lcy")
+            .with_note("lint note")
+            .suggest("prefix it with an underscore");
+        let units = vec![(root.join("main.lcy"), 1), (root.join("util.lcy"), 2)];
+        cache
+            .store_program(key, &[warning, Diagnostic::warning("w")], &units)
+            .unwrap();
+        cache.record_modules(&graph).unwrap();
+        let program = cache.load_program(key).unwrap();
+        assert_eq!(program.units, units);
+        let warnings = program.warnings;
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].message, "unused variable `x`");
+        assert_eq!(warnings[0].code.as_deref(), Some("unused-variable"));
+        assert_eq!(warnings[0].span, Some(3..8));
+        assert_eq!(warnings[0].file_path.as_deref(), Some("main.lcy"));
+        assert_eq!(warnings[0].notes, vec!["lint note".to_string()]);
+        assert_eq!(
+            warnings[0].suggestions[0].message,
+            "prefix it with an underscore"
+        );
+        assert_eq!(warnings[1].message, "w");
+        assert_eq!(warnings[1].span, None);
+        assert_eq!(warnings[1].code, None);
+        assert!(cache.changed_modules(&graph).is_empty());
+
+        // 目标文件按单元键存放
+        assert!(cache.cached_object(2).is_none());
+        let obj = root.join("util.o");
+        fs::write(&obj, "obj").unwrap();
+        let stored = cache.store_object(2, &obj).unwrap();
+        assert_eq!(cache.cached_object(2), Some(stored));
+        assert!(cache.cached_object(1).is_none());
+
+        fs::write(root.join("util.lcy"), "int f() { return 5; }").unwrap();
+        let graph = session.module_graph(&root.join("main.lcy")).unwrap();
+        assert_eq!(cache.changed_modules(&graph), vec![root.join("util.lcy")]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,44 @@
+//! Stable Hashing
+//!
+//! 缓存键写入磁盘，必须在不同进程、不同次构建之间保持一致。
+//! 哈希函数与语义分析的模块缓存共用（见 [`lency_sema::module_cache`]）。
+
+pub use lency_sema::module_cache::{stable_hash, StableHasher};
+
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
+
+/// 当前编译器构建的标识
+///
+/// 由正在运行的可执行文件的路径、大小与修改时间决定：重新构建编译器后旧缓存自动失效，
+/// 不依赖手动维护的版本号。读取不到可执行文件时退回到包版本。
+pub fn compiler_build_id() -> u64 {
+    static BUILD_ID: OnceLock<u64> = OnceLock::new();
+    *BUILD_ID.get_or_init(|| {
+        let mut hasher = StableHasher::new();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        if let Ok(exe) = std::env::current_exe() {
+            exe.hash(&mut hasher);
+            if let Ok(metadata) = std::fs::metadata(&exe) {
+                metadata.len().hash(&mut hasher);
+                if let Ok(modified) = metadata.modified() {
+                    let since_epoch = modified
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default();
+                    since_epoch.as_nanos().hash(&mut hasher);
+                }
+            }
+        }
+        hasher.finish()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_id_is_stable_within_process() {
+        assert_eq!(compiler_build_id(), compiler_build_id());
+    }
+}
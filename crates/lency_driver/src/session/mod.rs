@@ -0,0 +1,307 @@
+//! Compilation Session
+//!
+//! 编译会话：记录项目根目录与增量编译缓存。
+//!
+//! 每个模块的指纹由其源码内容与它（传递）依赖的全部模块内容共同决定，
+//! 因此任何一个依赖发生变化，依赖它的模块指纹都会随之改变。
+//! 入口模块的指纹覆盖整个程序的源码。
+
+mod cache;
+mod hash;
+
+pub use cache::{BuildCache, CachedProgram};
+pub use hash::{compiler_build_id, stable_hash, StableHasher};
+
+use lency_sema::resolver::search_path::find_module_source;
+use lency_syntax::lexer::Token;
+use logos::Logos;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 默认缓存目录（相对项目根目录）
+pub const DEFAULT_CACHE_DIR: &str = "target/lency-cache";
+
+/// 编译会话
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// 项目根目录（未设置搜索路径时 import 相对于此目录解析）
+    root_dir: PathBuf,
+    /// import 模块的搜索路径（包的源码根目录与库路径）
+    search_paths: Vec<PathBuf>,
+    /// 增量编译缓存；为 None 时每次都完整编译
+    cache: Option<BuildCache>,
+}
+
+impl Session {
+    /// 创建不带缓存的会话
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+            search_paths: Vec::new(),
+            cache: None,
+        }
+    }
+
+    /// 创建带磁盘缓存的会话
+    pub fn with_cache(root_dir: impl Into<PathBuf>, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+            search_paths: Vec::new(),
+            cache: Some(BuildCache::new(cache_dir)),
+        }
+    }
+
+    /// 设置 import 模块的搜索路径
+    pub fn with_search_paths(mut self, search_paths: Vec<PathBuf>) -> Self {
+        self.search_paths = search_paths;
+        self
+    }
+
+    pub fn root_dir(&self) -> &Path {
+        &self.root_dir
+    }
+
+    /// 实际使用的搜索路径：未设置时只有项目根目录
+    pub fn search_paths(&self) -> Vec<PathBuf> {
+        if self.search_paths.is_empty() {
+            vec![self.root_dir.clone()]
+        } else {
+            self.search_paths.clone()
+        }
+    }
+
+    pub fn cache(&self) -> Option<&BuildCache> {
+        self.cache.as_ref()
+    }
+
+    /// 从入口文件出发扫描 import，构建模块依赖图
+    ///
+    /// 只做词法扫描，不解析也不做语义分析；读不到的模块按空内容处理，
+    /// 真正的错误留给后续编译阶段报告。
+    pub fn module_graph(&self, entry: &Path) -> io::Result<ModuleGraph> {
+        let entry_source = fs::read_to_string(entry)?;
+        let search_paths = self.search_paths();
+        let mut sources = BTreeMap::new();
+        let mut pending = vec![(entry.to_path_buf(), entry_source)];
+
+        while let Some((path, source)) = pending.pop() {
+            if sources.contains_key(&path) {
+                continue;
+            }
+            let deps: BTreeSet<PathBuf> = scan_imports(&source)
+                .iter()
+                .map(|components| find_module_source(&search_paths, components))
+                .collect();
+            for dep in &deps {
+                if !sources.contains_key(dep) {
+                    let dep_source = fs::read_to_string(dep).unwrap_or_default();
+                    pending.push((dep.clone(), dep_source));
+                }
+            }
+            sources.insert(path, (stable_hash(&source), deps));
+        }
+
+        Ok(ModuleGraph::build(entry.to_path_buf(), sources))
+    }
+}
+
+/// 模块信息
+#[derive(Debug, Clone)]
+pub struct ModuleInfo {
+    pub path: PathBuf,
+    /// 源码内容哈希
+    pub content_hash: u64,
+    /// 源码 + 全部传递依赖的哈希
+    pub fingerprint: u64,
+    /// 直接依赖
+    pub deps: BTreeSet<PathBuf>,
+}
+
+/// 模块依赖图
+#[derive(Debug, Clone)]
+pub struct ModuleGraph {
+    entry: PathBuf,
+    modules: BTreeMap<PathBuf, ModuleInfo>,
+}
+
+impl ModuleGraph {
+    fn build(entry: PathBuf, sources: BTreeMap<PathBuf, (u64, BTreeSet<PathBuf>)>) -> Self {
+        let mut modules = BTreeMap::new();
+        for (path, (content_hash, deps)) in &sources {
+            // 允许循环导入：对传递闭包（按路径排序）整体求哈希
+            let mut closure = BTreeSet::new();
+            let mut stack: Vec<&PathBuf> = deps.iter().collect();
+            while let Some(dep) = stack.pop() {
+                if dep != path && closure.insert(dep) {
+                    if let Some((_, next)) = sources.get(dep) {
+                        stack.extend(next.iter());
+                    }
+                }
+            }
+
+            let mut hasher = StableHasher::new();
+            content_hash.hash(&mut hasher);
+            for dep in closure {
+                dep.hash(&mut hasher);
+                sources.get(dep).map(|(h, _)| *h).hash(&mut hasher);
+            }
+
+            modules.insert(
+                path.clone(),
+                ModuleInfo {
+                    path: path.clone(),
+                    content_hash: *content_hash,
+                    fingerprint: hasher.finish(),
+                    deps: deps.clone(),
+                },
+            );
+        }
+        Self { entry, modules }
+    }
+
+    pub fn entry(&self) -> &Path {
+        &self.entry
+    }
+
+    /// 整个程序的指纹（入口模块的指纹）
+    pub fn fingerprint(&self) -> u64 {
+        self.modules
+            .get(&self.entry)
+            .map(|m| m.fingerprint)
+            .unwrap_or_default()
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = &ModuleInfo> {
+        self.modules.values()
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&ModuleInfo> {
+        self.modules.get(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
+
+/// 词法扫描源码中的 import 路径
+///
+/// 支持 `import a.b [as x]` 与 `import { a.b; c.d as y }` 两种形式。
+fn scan_imports(source: &str) -> Vec<Vec<String>> {
+    let tokens: Vec<Token> = Token::lexer(source).filter_map(Result::ok).collect();
+    let mut imports = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i] != Token::Import {
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let in_block = tokens.get(i) == Some(&Token::LBrace);
+        if in_block {
+            i += 1;
+        }
+        loop {
+            let mut path = Vec::new();
+            while let Some(Token::Ident(name)) = tokens.get(i) {
+                path.push(name.clone());
+                i += 1;
+                if tokens.get(i) != Some(&Token::Dot) {
+                    break;
+                }
+                i += 1;
+            }
+            if tokens.get(i) == Some(&Token::As) {
+                i += 2;
+            }
+            if !path.is_empty() {
+                imports.push(path);
+            }
+            if !in_block {
+                break;
+            }
+            match tokens.get(i) {
+                Some(Token::Semicolon) => i += 1,
+                Some(Token::Ident(_)) => {}
+                _ => break,
+            }
+        }
+    }
+    imports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("lency_session_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_scan_imports() {
+        let source = "import std.io as io\nimport { a.b; c }\nint main() { return 0; }";
+        assert_eq!(
+            scan_imports(source),
+            vec![
+                vec!["std".to_string(), "io".to_string()],
+                vec!["a".to_string(), "b".to_string()],
+                vec!["c".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_dependencies() {
+        let root = temp_project("deps");
+        fs::write(
+            root.join("main.lcy"),
+            "import util.*\nint main() { return f(); }",
+        )
+        .unwrap();
+        fs::write(root.join("util.lcy"), "int f() { return 1; }").unwrap();
+        fs::write(root.join("other.lcy"), "int g() { return 2; }").unwrap();
+
+        let session = Session::new(&root);
+        let before = session.module_graph(&root.join("main.lcy")).unwrap();
+        assert_eq!(before.len(), 2);
+
+        // 无关文件变化不影响指纹
+        fs::write(root.join("other.lcy"), "int g() { return 3; }").unwrap();
+        let unrelated = session.module_graph(&root.join("main.lcy")).unwrap();
+        assert_eq!(before.fingerprint(), unrelated.fingerprint());
+
+        // 依赖变化使入口指纹失效
+        fs::write(root.join("util.lcy"), "int f() { return 2; }").unwrap();
+        let after = session.module_graph(&root.join("main.lcy")).unwrap();
+        assert_ne!(before.fingerprint(), after.fingerprint());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_cyclic_imports() {
+        let root = temp_project("cycle");
+        fs::write(root.join("a.lcy"), "import b.*\nint fa() { return 1; }").unwrap();
+        fs::write(root.join("b.lcy"), "import a.*\nint fb() { return 2; }").unwrap();
+
+        let graph = Session::new(&root)
+            .module_graph(&root.join("a.lcy"))
+            .unwrap();
+        assert_eq!(graph.len(), 2);
+        assert_ne!(graph.fingerprint(), 0);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,249 @@
+//! Test Harness
+//!
+//! `lencyc test` 的编译侧支持：发现测试函数，并把一个文件的测试编译为一个可执行程序。
+//!
+//! 测试函数是文件中以 `test_` 开头、无参数、非泛型、返回 `void` 或 `int` 的顶层函数。
+//! 整个文件只编译一次：去掉文件自身的 `main`，换成按序号分派的入口，
+//! 运行时由环境变量 [`TEST_SELECTOR_ENV`] 选择调用哪个测试。每个测试在独立的进程中运行，
+//! 一个测试 panic 不会影响其他测试。返回 `int` 的测试以非零返回值表示失败。
+
+use crate::{
+    compile_program, parse_source, CompilationOutput, CompileError, CompileOptions, CompileResult,
+};
+use lency_syntax::ast::{
+    BinaryOp, Decl, Expr, ExprKind, Literal, Param, Program, Span, Stmt, Type,
+};
+
+/// 测试函数名前缀
+pub const TEST_PREFIX: &str = "test_";
+
+/// 选择测试的环境变量：值为测试在 [`compile_tests`] 参数中的序号（从 0 开始）
+pub const TEST_SELECTOR_ENV: &str = "LENCY_TEST";
+
+/// 运行时提供的测试选择函数：`int lency_test_selected(int count)`
+const SELECTOR_FN: &str = "lency_test_selected";
+
+/// 入口中保存所选序号的局部变量
+const SELECTED_VAR: &str = "__lency_test";
+
+/// 一个测试函数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub name: String,
+    /// 函数定义位置 (字节偏移)
+    pub span: Span,
+}
+
+/// 发现源文件中的测试函数（按定义顺序）
+pub fn discover_tests(source: &str) -> CompileResult<Vec<TestCase>> {
+    let program = parse_source(source)?;
+    Ok(test_functions(&program)
+        .map(|(name, span, _)| TestCase {
+            name: name.clone(),
+            span: span.clone(),
+        })
+        .collect())
+}
+
+/// 编译包含 `test_names` 中全部测试的程序
+///
+/// 以 `TEST_SELECTOR_ENV=<序号>` 运行程序时只调用第 `序号` 个测试。
+pub fn compile_tests(
+    source: &str,
+    test_names: &[String],
+    options: &CompileOptions,
+) -> CompileResult<CompilationOutput> {
+    let mut program = parse_source(source)?;
+
+    let tests = test_names
+        .iter()
+        .map(|test_name| {
+            test_functions(&program)
+                .find(|(name, _, _)| *name == test_name)
+                .map(|(_, span, return_type)| {
+                    (test_name.as_str(), span.clone(), *return_type == Type::Int)
+                })
+                .ok_or_else(|| CompileError::TestNotFound(test_name.to_string()))
+        })
+        .collect::<CompileResult<Vec<_>>>()?;
+
+    program
+        .decls
+        .retain(|decl| !matches!(decl, Decl::Function { name, .. } if name == "main"));
+    program.decls.push(selector_decl());
+    program.decls.push(test_main(&tests));
+
+    compile_program(program, source, options)
+}
+
+/// 满足测试函数签名的顶层函数：(名称, Span, 返回类型)
+fn test_functions(program: &Program) -> impl Iterator<Item = (&String, &Span, &Type)> {
+    program.decls.iter().filter_map(|decl| match decl {
+        Decl::Function {
+            span,
+            name,
+            generic_params,
+            params,
+            return_type,
+            ..
+        } if name.starts_with(TEST_PREFIX)
+            && generic_params.is_empty()
+            && params.is_empty()
+            && matches!(return_type, Type::Void | Type::Int) =>
+        {
+            Some((name, span, return_type))
+        }
+        _ => None,
+    })
+}
+
+/// `extern int lency_test_selected(int count)`
+fn selector_decl() -> Decl {
+    Decl::ExternFunction {
+        span: 0..0,
+        is_pub: false,
+        name: SELECTOR_FN.to_string(),
+        generic_params: Vec::new(),
+        params: vec![Param {
+            name: "count".to_string(),
+            ty: Type::Int,
+        }],
+        return_type: Type::Int,
+    }
+}
+
+/// 生成测试入口：
+///
+/// ```text
+/// int main() {
+///     var __lency_test = lency_test_selected(2)
+///     if __lency_test == 0 { return test_a() }
+///     if __lency_test == 1 { test_b() return 0 }
+///     return 0
+/// }
+/// ```
+///
+/// 调用节点沿用测试函数的 Span，调用栈中入口帧指向测试定义处。
+fn test_main(tests: &[(&str, Span, bool)]) -> Decl {
+    let expr = |kind, span: &Span| Expr {
+        kind,
+        span: span.clone(),
+    };
+    let int = |value: usize, span: &Span| expr(ExprKind::Literal(Literal::Int(value as i64)), span);
+    let call = |callee: &str, args, span: &Span| {
+        expr(
+            ExprKind::Call {
+                callee: Box::new(expr(ExprKind::Variable(callee.to_string()), span)),
+                args,
+            },
+            span,
+        )
+    };
+    let entry_span = 0..0;
+    let return_zero = |span: &Span| Stmt::Return {
+        span: span.clone(),
+        value: Some(int(0, span)),
+    };
+
+    let mut body = vec![Stmt::VarDecl {
+        span: entry_span.clone(),
+        name: SELECTED_VAR.to_string(),
+        ty: Some(Type::Int),
+        value: call(
+            SELECTOR_FN,
+            vec![int(tests.len(), &entry_span)],
+            &entry_span,
+        ),
+    }];
+    for (index, (name, span, returns_int)) in tests.iter().enumerate() {
+        let run = call(name, Vec::new(), span);
+        let then_block = if *returns_int {
+            vec![Stmt::Return {
+                span: span.clone(),
+                value: Some(run),
+            }]
+        } else {
+            vec![Stmt::Expression(run), return_zero(span)]
+        };
+        body.push(Stmt::If {
+            span: span.clone(),
+            condition: expr(
+                ExprKind::Binary(
+                    Box::new(expr(ExprKind::Variable(SELECTED_VAR.to_string()), span)),
+                    BinaryOp::Eq,
+                    Box::new(int(index, span)),
+                ),
+                span,
+            ),
+            then_block,
+            else_block: None,
+        });
+    }
+    // 运行时保证序号有效，不会执行到这里
+    body.push(return_zero(&entry_span));
+
+    Decl::Function {
+        span: entry_span,
+        is_pub: false,
+        name: "main".to_string(),
+        generic_params: Vec::new(),
+        params: Vec::new(),
+        return_type: Type::Int,
+        body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "int add(int a, int b) {\n    return a + b\n}\n\nvoid test_add() {\n    if add(1, 2) != 3 {\n        panic(\"wrong sum\")\n    }\n}\n\nint test_returns_code() {\n    return 0\n}\n\nvoid test_with_param(int x) {\n}\n\nint main() {\n    return 1\n}\n";
+
+    #[test]
+    fn test_discover_tests_by_prefix_and_signature() {
+        let names: Vec<String> = discover_tests(SOURCE)
+            .unwrap()
+            .into_iter()
+            .map(|case| case.name)
+            .collect();
+        assert_eq!(names, vec!["test_add", "test_returns_code"]);
+    }
+
+    #[test]
+    fn test_compile_tests_dispatches_on_selected_index() {
+        let names = vec!["test_add".to_string(), "test_returns_code".to_string()];
+        let ir = compile_tests(SOURCE, &names, &CompileOptions::default())
+            .unwrap()
+            .ir;
+        assert!(ir.contains("define i32 @main()"));
+        assert!(ir.contains("declare i64 @lency_test_selected(i64"));
+        assert!(ir.contains("call i64 @lency_test_selected(i64 2)"));
+        assert!(ir.contains("call void @test_add()"));
+        assert!(ir.contains("call i64 @test_returns_code()"));
+        // 文件自身的 main 被替换
+        assert_eq!(ir.matches("define i64 @__lency_main()").count(), 1);
+    }
+
+    #[test]
+    fn test_compile_unknown_test() {
+        let names = vec!["test_add".to_string(), "test_missing".to_string()];
+        assert!(matches!(
+            compile_tests(SOURCE, &names, &CompileOptions::default()),
+            Err(CompileError::TestNotFound(name)) if name == "test_missing"
+        ));
+    }
+
+    #[test]
+    fn test_compile_tests_importing_test_library() {
+        let source =
+            "import test.assert.*\n\nvoid test_sum() {\n    assert(1 + 1 == 2, \"sum\")\n}\n";
+        let options = CompileOptions {
+            search_paths: vec![std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")],
+            ..CompileOptions::default()
+        };
+        let ir = compile_tests(source, &["test_sum".to_string()], &options)
+            .unwrap()
+            .ir;
+        assert!(ir.contains("call void @test_sum()"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Document Analysis
+//!
+//! 通过 `lency_driver::analyze_document` 对打开的文档运行语法分析与语义分析，
+//! 把诊断转换为 LSP 诊断，并保留类型表以响应悬停与跳转定义请求。
+
+use crate::line_index::LineIndex;
+use lency_diagnostics::DiagnosticLevel;
+use lency_driver::CompileOptions;
+use lency_sema::resolver::namespace::module_source_path;
+use lency_sema::{TypeTable, TypedExpr};
+use lency_syntax::ast::Span;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position};
+
+/// 文档分析结果
+#[derive(Debug, Default)]
+pub struct Analysis {
+    pub diagnostics: Vec<Diagnostic>,
+    pub types: TypeTable,
+    /// 导入模块引入的全局名称 -> 模块路径
+    pub module_origins: HashMap<String, String>,
+}
+
+/// 定义位置：所在文件（None 表示当前文档）与源码范围
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefinitionSite {
+    pub file: Option<PathBuf>,
+    pub span: Span,
+}
+
+impl Analysis {
+    /// 光标处表达式的推导类型
+    pub fn hover(&self, index: &LineIndex, position: Position) -> Option<&TypedExpr> {
+        self.types.expr_at(index.offset(position))
+    }
+
+    /// 光标处引用的定义位置；`root_dir` 用于定位导入模块的源文件
+    pub fn definition(
+        &self,
+        index: &LineIndex,
+        position: Position,
+        root_dir: &Path,
+    ) -> Option<DefinitionSite> {
+        let definition = self
+            .types
+            .reference_at(index.offset(position))?
+            .definition
+            .as_ref()?;
+
+        // 内置符号没有源码位置
+        if definition.span.is_empty() {
+            return None;
+        }
+
+        let file = definition
+            .owner
+            .as_ref()
+            .and_then(|owner| self.module_origins.get(owner))
+            .map(|module| {
+                let components: Vec<String> = module.split('.').map(str::to_string).collect();
+                module_source_path(root_dir, &components)
+            });
+        Some(DefinitionSite {
+            file,
+            span: definition.span.clone(),
+        })
+    }
+}
+
+/// 分析文档源码；import 相对于项目根目录 `root_dir` 解析
+pub fn analyze_document(index: &LineIndex, root_dir: &Path) -> Analysis {
+    let options = CompileOptions {
+        search_paths: vec![root_dir.to_path_buf()],
+        ..CompileOptions::default()
+    };
+    let analysis = lency_driver::analyze_document(index.text(), &options);
+
+    let diagnostics = analysis
+        .diagnostics
+        .iter()
+        .map(|diag| {
+            let severity = match diag.level {
+                DiagnosticLevel::Error => DiagnosticSeverity::ERROR,
+                DiagnosticLevel::Warning => DiagnosticSeverity::WARNING,
+                DiagnosticLevel::Info => DiagnosticSeverity::INFORMATION,
+                DiagnosticLevel::Note => DiagnosticSeverity::HINT,
+            };
+            let mut message = diag.message.clone();
+            for note in &diag.notes {
+                message.push_str("\nnote: ");
+                message.push_str(note);
+            }
+            for suggestion in &diag.suggestions {
+                message.push_str("\nhelp: ");
+                message.push_str(&suggestion.message);
+            }
+            let span = diag.span.clone().unwrap_or_default();
+            Diagnostic {
+                code: diag.code.clone().map(NumberOrString::String),
+                ..make_diagnostic(index, &span, severity, message)
+            }
+        })
+        .collect();
+
+    match analysis.result {
+        Some(result) => Analysis {
+            diagnostics,
+            types: result.types,
+            module_origins: result.module_origins,
+        },
+        None => Analysis {
+            diagnostics,
+            ..Default::default()
+        },
+    }
+}
+
+fn make_diagnostic(
+    index: &LineIndex,
+    span: &Span,
+    severity: DiagnosticSeverity,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range: index.range(span),
+        severity: Some(severity),
+        source: Some("lency".to_string()),
+        message,
+        ..Default::default()
+    }
+}
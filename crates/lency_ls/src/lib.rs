@@ -1,22 +1,115 @@
+pub mod analysis;
+pub mod line_index;
+
+use analysis::{Analysis, analyze_document};
+use line_index::LineIndex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
+/// 已打开的文档及其最近一次分析结果
+#[derive(Debug)]
+struct Document {
+    index: LineIndex,
+    analysis: Analysis,
+}
+
 #[derive(Debug)]
 pub struct Backend {
     pub client: Client,
+    documents: RwLock<HashMap<Url, Arc<Document>>>,
+    /// 项目根目录（import 路径相对于此目录解析）
+    root_dir: RwLock<PathBuf>,
+}
+
+impl Backend {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            documents: RwLock::new(HashMap::new()),
+            root_dir: RwLock::new(std::env::current_dir().unwrap_or_default()),
+        }
+    }
+
+    fn document(&self, uri: &Url) -> Option<Arc<Document>> {
+        self.documents.read().ok()?.get(uri).cloned()
+    }
+
+    /// 分析文档并发布诊断
+    async fn update(&self, uri: Url, text: String, version: Option<i32>) {
+        let root_dir = self
+            .root_dir
+            .read()
+            .map(|root| root.clone())
+            .unwrap_or_default();
+        let document = tokio::task::spawn_blocking(move || {
+            let index = LineIndex::new(&text);
+            let analysis = analyze_document(&index, &root_dir);
+            Document { index, analysis }
+        })
+        .await;
+
+        let Ok(document) = document else {
+            eprintln!("[LencyLS] Analysis of {} panicked", uri);
+            return;
+        };
+        let diagnostics = document.analysis.diagnostics.clone();
+        if let Ok(mut documents) = self.documents.write() {
+            documents.insert(uri.clone(), Arc::new(document));
+        }
+        self.client
+            .publish_diagnostics(uri, diagnostics, version)
+            .await;
+    }
+
+    /// 定义所在文件的 URI 与范围
+    fn definition_location(&self, uri: &Url, position: Position) -> Option<Location> {
+        let document = self.document(uri)?;
+        let root_dir = self.root_dir.read().ok()?.clone();
+        let site = document
+            .analysis
+            .definition(&document.index, position, &root_dir)?;
+
+        match site.file {
+            None => Some(Location::new(uri.clone(), document.index.range(&site.span))),
+            Some(file) => {
+                let text = std::fs::read_to_string(&file).ok()?;
+                let target = Url::from_file_path(&file).ok()?;
+                Some(Location::new(
+                    target,
+                    LineIndex::new(&text).range(&site.span),
+                ))
+            }
+        }
+    }
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
         eprintln!("[LencyLS] Received initialize request");
+        #[allow(deprecated)]
+        let root = params
+            .root_uri
+            .as_ref()
+            .and_then(|uri| uri.to_file_path().ok());
+        // import 相对于项目根目录解析（分析时作为搜索路径传给 driver）
+        if let Some(root) = root
+            && let Ok(mut root_dir) = self.root_dir.write()
+        {
+            *root_dir = root;
+        }
+
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
                     TextDocumentSyncKind::FULL,
                 )),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
                 completion_provider: Some(CompletionOptions::default()),
                 ..Default::default()
             },
@@ -31,19 +124,55 @@ impl LanguageServer for Backend {
             .await;
     }
 
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let doc = params.text_document;
+        self.update(doc.uri, doc.text, Some(doc.version)).await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        // FULL 同步：最后一次变更即完整文本
+        if let Some(change) = params.content_changes.into_iter().last() {
+            let doc = params.text_document;
+            self.update(doc.uri, change.text, Some(doc.version)).await;
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        if let Ok(mut documents) = self.documents.write() {
+            documents.remove(&uri);
+        }
+        self.client.publish_diagnostics(uri, Vec::new(), None).await;
+    }
+
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
-        let pos = params.text_document_position_params.position;
-        eprintln!(
-            "[LencyLS] Hover request at line {}, col {}",
-            pos.line, pos.character
-        );
+        let position = params.text_document_position_params.position;
+        let uri = params.text_document_position_params.text_document.uri;
+        let Some(document) = self.document(&uri) else {
+            return Ok(None);
+        };
+
+        Ok(document
+            .analysis
+            .hover(&document.index, position)
+            .map(|expr| Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: format!("```lency\n{}\n```", expr.ty),
+                }),
+                range: Some(document.index.range(&expr.span)),
+            }))
+    }
 
-        Ok(Some(Hover {
-            contents: HoverContents::Scalar(MarkedString::String(
-                "✨ 正在由 Lency Rust LSP 提供实时语义支持".to_string(),
-            )),
-            range: None,
-        }))
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params.position;
+        let uri = params.text_document_position_params.text_document.uri;
+        Ok(self
+            .definition_location(&uri, position)
+            .map(GotoDefinitionResponse::Scalar))
     }
 
     async fn shutdown(&self) -> Result<()> {
@@ -55,6 +184,6 @@ pub async fn run_server() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let (service, socket) = LspService::new(|client| Backend { client });
+    let (service, socket) = LspService::new(Backend::new);
     Server::new(stdin, stdout, socket).serve(service).await;
 }
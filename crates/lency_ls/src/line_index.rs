@@ -0,0 +1,96 @@
+//! Line Index
+//!
+//! 源码字节偏移 <-> LSP 位置（行号 + UTF-16 列号）互相转换
+
+use tower_lsp::lsp_types::{Position, Range};
+
+/// 行首偏移表
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    text: String,
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self {
+            text: text.to_string(),
+            line_starts,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// 字节偏移 -> LSP 位置（越界时截断到文本末尾）
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = self.floor_char_boundary(offset.min(self.text.len()));
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let character: usize = self.text[line_start..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        Position::new(line as u32, character as u32)
+    }
+
+    pub fn range(&self, span: &std::ops::Range<usize>) -> Range {
+        Range::new(self.position(span.start), self.position(span.end))
+    }
+
+    /// LSP 位置 -> 字节偏移（越界时截断到行尾 / 文本末尾）
+    pub fn offset(&self, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return self.text.len();
+        };
+        let line_end = self
+            .line_starts
+            .get(position.line as usize + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+
+        let mut units = 0;
+        for (i, ch) in self.text[line_start..line_end].char_indices() {
+            if units >= position.character as usize || ch == '\n' {
+                return line_start + i;
+            }
+            units += ch.len_utf16();
+        }
+        line_end
+    }
+
+    fn floor_char_boundary(&self, mut offset: usize) -> usize {
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_roundtrip() {
+        let index = LineIndex::new("int main() {\n    return 0;\n}\n");
+        assert_eq!(index.position(0), Position::new(0, 0));
+        assert_eq!(index.position(17), Position::new(1, 4));
+        assert_eq!(index.offset(Position::new(1, 4)), 17);
+        assert_eq!(index.offset(Position::new(2, 0)), 27);
+        assert_eq!(index.position(100), Position::new(3, 0));
+    }
+
+    #[test]
+    fn test_utf16_columns() {
+        // "你" 占 3 个字节、1 个 UTF-16 单元
+        let prefix = "var s = \"你好\"; ";
+        let index = LineIndex::new(&format!("{}x", prefix));
+        let x = prefix.len();
+        assert_eq!(index.position(x), Position::new(0, 14));
+        assert_eq!(index.offset(Position::new(0, 14)), x);
+    }
+}
@@ -1,11 +1,122 @@
+use futures::StreamExt;
 use lency_ls::Backend;
-use serde_json::json;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
 use tower::Service;
 use tower_lsp::LspService;
+use tower_lsp::jsonrpc::{Request, Response};
+
+const URI: &str = "file:///tmp/lency_ls_test/main.lcy";
+
+/// 测试客户端：驱动 LspService，并收集服务端发往客户端的通知
+struct TestClient {
+    service: LspService<Backend>,
+    notifications: mpsc::UnboundedReceiver<Request>,
+    next_id: i64,
+}
+
+impl TestClient {
+    async fn start() -> Self {
+        Self::start_in(None).await
+    }
+
+    /// 以 `root` 为项目根目录启动（import 相对于根目录解析）
+    async fn start_in(root: Option<&std::path::Path>) -> Self {
+        let root_uri = root.map(|r| tower_lsp::lsp_types::Url::from_file_path(r).unwrap());
+        let (service, socket) = LspService::new(Backend::new);
+        let (tx, notifications) = mpsc::unbounded_channel();
+        tokio::spawn(socket.for_each(move |msg| {
+            let _ = tx.send(msg);
+            async {}
+        }));
+
+        let mut client = Self {
+            service,
+            notifications,
+            next_id: 1,
+        };
+        client
+            .request(
+                "initialize",
+                json!({ "capabilities": {}, "processId": null, "rootUri": root_uri }),
+            )
+            .await;
+        client.notify("initialized", json!({})).await;
+        client
+    }
+
+    async fn call(&mut self, msg: Value) -> Option<Response> {
+        let request: Request = serde_json::from_value(msg).unwrap();
+        self.service.call(request).await.unwrap()
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        let response = self
+            .call(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await
+            .expect("request should have a response");
+        assert!(response.error().is_none(), "{:?}", response.error());
+        response.result().cloned().unwrap_or(Value::Null)
+    }
+
+    async fn notify(&mut self, method: &str, params: Value) {
+        let response = self
+            .call(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await;
+        assert!(response.is_none());
+    }
+
+    async fn open(&mut self, text: &str) -> Vec<Value> {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": URI, "languageId": "lency", "version": 1, "text": text }
+            }),
+        )
+        .await;
+        self.diagnostics().await
+    }
+
+    /// 等待下一条 publishDiagnostics 通知
+    async fn diagnostics(&mut self) -> Vec<Value> {
+        loop {
+            let msg = self.notifications.recv().await.expect("server closed");
+            if msg.method() == "textDocument/publishDiagnostics" {
+                let params = msg.params().cloned().unwrap();
+                assert_eq!(params["uri"], URI);
+                return params["diagnostics"].as_array().cloned().unwrap();
+            }
+        }
+    }
+
+    async fn hover(&mut self, line: u32, character: u32) -> Value {
+        self.request(
+            "textDocument/hover",
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character }
+            }),
+        )
+        .await
+    }
+
+    async fn definition(&mut self, line: u32, character: u32) -> Value {
+        self.request(
+            "textDocument/definition",
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character }
+            }),
+        )
+        .await
+    }
+}
 
 #[tokio::test]
 async fn test_lsp_initialize() {
-    let (mut service, _) = LspService::new(|client| Backend { client });
+    let (mut service, _) = LspService::new(Backend::new);
 
     let init_request = json!({
         "jsonrpc": "2.0",
@@ -29,7 +140,150 @@ async fn test_lsp_initialize() {
             assert!(res.error().is_none());
             assert!(res.result().is_some());
             assert_eq!(res.id(), &tower_lsp::jsonrpc::Id::Number(1));
+            let capabilities = &res.result().unwrap()["capabilities"];
+            assert_eq!(capabilities["hoverProvider"], true);
+            assert_eq!(capabilities["definitionProvider"], true);
         }
         None => panic!("LSP returned None"),
     }
 }
+
+#[tokio::test]
+async fn test_lsp_publishes_semantic_errors() {
+    let mut client = TestClient::start().await;
+
+    let diagnostics = client.open("int main() {\n    return missing;\n}\n").await;
+    assert_eq!(diagnostics.len(), 1);
+    let diag = &diagnostics[0];
+    assert!(diag["message"].as_str().unwrap().contains("missing"));
+    assert_eq!(diag["severity"], 1);
+    assert_eq!(
+        diag["range"]["start"],
+        json!({ "line": 1, "character": 11 })
+    );
+    assert_eq!(diag["range"]["end"], json!({ "line": 1, "character": 18 }));
+
+    // 修复后诊断被清空
+    client
+        .notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": URI, "version": 2 },
+                "contentChanges": [{ "text": "int main() {\n    return 0;\n}\n" }]
+            }),
+        )
+        .await;
+    assert!(client.diagnostics().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_lsp_publishes_parse_errors() {
+    let mut client = TestClient::start().await;
+
+    let diagnostics = client.open("int main() {\n    return 1 +;\n}\n").await;
+    assert!(!diagnostics.is_empty());
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+}
+
+#[tokio::test]
+async fn test_lsp_hover_shows_inferred_type() {
+    let mut client = TestClient::start().await;
+
+    let source = "struct Point {\n    int x\n    float y\n}\n\nint main() {\n    var p = Point { x: 1, y: 2.0 }\n    var total = p.y\n    return p.x\n}\n";
+    assert!(client.open(source).await.is_empty());
+
+    // `p.y` 中的 y
+    let hover = client.hover(7, 18).await;
+    assert_eq!(hover["contents"]["value"], "```lency\nfloat\n```");
+
+    // `return p.x` 中的 p
+    let hover = client.hover(8, 11).await;
+    assert_eq!(hover["contents"]["value"], "```lency\nPoint\n```");
+
+    // 空白处没有悬停信息
+    assert_eq!(client.hover(4, 0).await, Value::Null);
+}
+
+#[tokio::test]
+async fn test_lsp_goto_definition() {
+    let mut client = TestClient::start().await;
+
+    let source = "int twice(int n) {\n    return n * 2\n}\n\nint main() {\n    var value = 21\n    return twice(value)\n}\n";
+    assert!(client.open(source).await.is_empty());
+
+    // twice(...) -> 函数声明
+    let location = client.definition(6, 12).await;
+    assert_eq!(location["uri"], URI);
+    assert_eq!(
+        location["range"]["start"],
+        json!({ "line": 0, "character": 0 })
+    );
+
+    // value -> var 声明
+    let location = client.definition(6, 19).await;
+    assert_eq!(
+        location["range"]["start"],
+        json!({ "line": 5, "character": 4 })
+    );
+
+    // n -> 参数
+    let location = client.definition(1, 11).await;
+    assert_eq!(location["range"]["start"]["line"], 0);
+
+    // 字面量没有定义
+    assert_eq!(client.definition(5, 16).await, Value::Null);
+}
+
+#[tokio::test]
+async fn test_lsp_goto_definition_in_imported_module() {
+    let root = std::env::temp_dir().join(format!("lency_ls_goto_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let util = root.join("util.lcy");
+    std::fs::write(
+        &util,
+        "// helpers\npub int helper(int x) {\n    return x\n}\n",
+    )
+    .unwrap();
+
+    let mut client = TestClient::start_in(Some(&root)).await;
+    let source = "import util as u\n\nint main() {\n    return u.helper(1)\n}\n";
+    assert!(client.open(source).await.is_empty());
+
+    let location = client.definition(3, 15).await;
+    let expected = tower_lsp::lsp_types::Url::from_file_path(&util).unwrap();
+    assert_eq!(location["uri"], expected.as_str());
+    assert_eq!(
+        location["range"]["start"],
+        json!({ "line": 1, "character": 4 })
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[tokio::test]
+async fn test_lsp_ignores_errors_in_imported_modules() {
+    let root = std::env::temp_dir().join(format!("lency_ls_module_errors_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(
+        root.join("util.lcy"),
+        "int helper(int x) {\n    var count = x\n    return cont\n}\n",
+    )
+    .unwrap();
+
+    let cwd = std::env::current_dir().unwrap();
+    let mut client = TestClient::start_in(Some(&root)).await;
+    // import 相对于 rootUri 解析，服务器不改变进程的当前目录
+    assert_eq!(std::env::current_dir().unwrap(), cwd);
+
+    let diagnostics = client
+        .open("import util.*\n\nint main() {\n    return helper(oops)\n}\n")
+        .await;
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert!(diagnostics[0]["message"].as_str().unwrap().contains("oops"));
+    assert_eq!(
+        diagnostics[0]["range"]["start"],
+        json!({ "line": 3, "character": 18 })
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -6,6 +6,9 @@
 use lency_syntax::ast::*;
 use std::collections::HashSet;
 
+/// 返回 `Option<T>` 的整数内置方法
+const CHECKED_METHODS: [&str; 4] = ["checked_add", "checked_sub", "checked_mul", "checked_div"];
+
 pub struct Collector {
     /// 收集到的泛型类型实例化
     /// 例如: Type::Generic("Box", [int])
@@ -96,6 +99,7 @@ impl Collector {
                 }
                 self.collect_expr(value);
             }
+            Stmt::VarPattern { value, .. } => self.collect_expr(value),
             Stmt::Assignment { target, value, .. } => {
                 self.collect_expr(target);
                 self.collect_expr(value);
@@ -122,8 +126,29 @@ impl Collector {
                     }
                 }
             }
+            Stmt::IfLet {
+                value,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.collect_expr(value);
+                for s in then_block {
+                    self.collect_stmt(s);
+                }
+                if let Some(else_stmts) = else_block {
+                    for s in else_stmts {
+                        self.collect_stmt(s);
+                    }
+                }
+            }
             Stmt::While {
                 condition, body, ..
+            }
+            | Stmt::WhileLet {
+                value: condition,
+                body,
+                ..
             } => {
                 self.collect_expr(condition);
                 for s in body {
@@ -168,7 +193,7 @@ impl Collector {
     fn collect_expr(&mut self, expr: &Expr) {
         match &expr.kind {
             ExprKind::Literal(_) => {}
-            ExprKind::Unit => {}
+            ExprKind::Unit | ExprKind::Error => {}
             ExprKind::Variable(_) => {}
             ExprKind::Binary(l, _, r) => {
                 self.collect_expr(l);
@@ -176,6 +201,11 @@ impl Collector {
             }
             ExprKind::Unary(_, e) => self.collect_expr(e),
             ExprKind::Call { callee, args } => {
+                if let ExprKind::Get { name, .. } = &callee.kind {
+                    if CHECKED_METHODS.contains(&name.as_str()) {
+                        self.collect_checked_results();
+                    }
+                }
                 self.collect_expr(callee);
                 for arg in args {
                     self.collect_expr(arg);
@@ -193,7 +223,16 @@ impl Collector {
                 self.collect_expr(array);
                 self.collect_expr(index);
             }
+            ExprKind::Range { start, end, .. } => {
+                self.collect_expr(start);
+                self.collect_expr(end);
+            }
             ExprKind::Print(e) => self.collect_expr(e),
+            ExprKind::CoerceDyn { value, .. } => self.collect_expr(value),
+            ExprKind::Cast { value, target } => {
+                self.collect_type(target);
+                self.collect_expr(value);
+            }
             ExprKind::StructLiteral { type_, fields } => {
                 self.collect_type(type_);
                 for (_, e) in fields {
@@ -212,6 +251,9 @@ impl Collector {
             } => {
                 self.collect_expr(value);
                 for c in cases {
+                    if let Some(g) = &c.guard {
+                        self.collect_expr(g);
+                    }
                     self.collect_expr(&c.body);
                 }
                 if let Some(d) = default {
@@ -232,10 +274,18 @@ impl Collector {
             ExprKind::Try(inner) => self.collect_expr(inner),
             ExprKind::Ok(inner) => self.collect_expr(inner),
             ExprKind::Err(inner) => self.collect_expr(inner),
-            ExprKind::Closure { params, body } => {
-                for param in params {
+            ExprKind::Closure {
+                params,
+                body,
+                captures,
+                return_type,
+            } => {
+                for param in params.iter().chain(captures) {
                     self.collect_type(&param.ty);
                 }
+                if let Some(ret) = return_type {
+                    self.collect_type(ret);
+                }
                 self.collect_expr(body);
             }
             // File I/O intrinsics
@@ -269,6 +319,17 @@ impl Collector {
         }
     }
 
+    /// `x.checked_add(y)` 的结果是 `Option<T>`，T 为接收者的整数类型。
+    /// AST 中没有接收者的类型，因此为每种整数类型实例化 Option（只生成类型与构造函数）
+    fn collect_checked_results(&mut self) {
+        let integers =
+            std::iter::once(Type::Int).chain(IntKind::ALL.into_iter().map(Type::FixedInt));
+        for ty in integers {
+            self.instantiations
+                .insert(Type::Generic("Option".to_string(), vec![ty]));
+        }
+    }
+
     fn collect_type(&mut self, ty: &Type) {
         match ty {
             Type::Generic(_, args) => {
@@ -23,6 +23,19 @@ pub struct Monomorphizer {
     generic_impls: HashMap<String, Vec<Decl>>,
 }
 
+/// 声明是否为泛型模板（单态化后从程序中移除，只保留其实例）
+///
+/// 其余声明按原顺序保留在输出程序的开头，实例追加在之后。
+pub fn is_template(decl: &Decl) -> bool {
+    match decl {
+        Decl::Struct { generic_params, .. }
+        | Decl::Function { generic_params, .. }
+        | Decl::Enum { generic_params, .. }
+        | Decl::Impl { generic_params, .. } => !generic_params.is_empty(),
+        _ => false,
+    }
+}
+
 impl Default for Monomorphizer {
     fn default() -> Self {
         Self::new()
@@ -44,59 +57,25 @@ impl Monomorphizer {
         let mut concrete_decls = Vec::new();
 
         for decl in program.decls {
+            if !is_template(&decl) {
+                concrete_decls.push(decl);
+                continue;
+            }
             match &decl {
-                Decl::Struct {
-                    name,
-                    generic_params,
-                    ..
-                } if !generic_params.is_empty() => {
-                    self.generic_definitions.insert(name.clone(), decl);
+                // `impl<T> Box<T>` 按结构体名收集，实例化结构体时一并特化
+                Decl::Impl { type_name, .. } => {
+                    let key = match type_name {
+                        Type::Struct(n) => n.clone(),
+                        Type::Generic(n, _) => n.clone(),
+                        _ => format!("{:?}", type_name),
+                    };
+                    self.generic_impls.entry(key).or_default().push(decl);
                 }
-                Decl::Function {
-                    name,
-                    generic_params,
-                    ..
-                } if !generic_params.is_empty() => {
+                Decl::Struct { name, .. }
+                | Decl::Function { name, .. }
+                | Decl::Enum { name, .. } => {
                     self.generic_definitions.insert(name.clone(), decl);
                 }
-                Decl::Enum {
-                    name,
-                    generic_params,
-                    ..
-                } if !generic_params.is_empty() => {
-                    self.generic_definitions.insert(name.clone(), decl);
-                }
-                // Impl blocks with generics?
-                // Currently Lency parser might have Impl<T> Box<T>.
-                // We treat Impl as generic if it has params.
-                Decl::Impl {
-                    type_name,
-                    generic_params,
-                    ..
-                } => {
-                    // Collect all impl blocks for potentially generic structs.
-                    // If generic_params is not empty, it's definitely a generic impl template.
-                    // Even if generic_params is empty, it might be `impl Box<int>`, which we treat as concrete for now,
-                    // UNLESS we want to attach it to the Monomorphization process?
-                    // "Concrete" decls are simply kept.
-                    // "Generic" templates are stored and instantiated on demand.
-                    // `impl<T> Box<T>` is a template.
-                    // `impl Box<int>` is concrete.
-
-                    if !generic_params.is_empty() {
-                        let key = match type_name {
-                            Type::Struct(n) => n.clone(),
-                            Type::Generic(n, _) => n.clone(),
-                            _ => format!("{:?}", type_name),
-                        };
-                        self.generic_impls
-                            .entry(key)
-                            .or_default()
-                            .push(decl.clone());
-                    } else {
-                        concrete_decls.push(decl);
-                    }
-                }
                 _ => concrete_decls.push(decl),
             }
         }
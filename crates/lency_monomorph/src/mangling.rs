@@ -36,7 +36,10 @@ fn mangle_type_internal(ty: &Type) -> String {
         Type::Float => "float".to_string(),
         Type::String => "string".to_string(),
         Type::Bool => "bool".to_string(),
+        Type::Char => "char".to_string(),
         Type::Void => "void".to_string(),
+        Type::Range => "Range".to_string(),
+        Type::FixedInt(kind) => kind.name().to_string(),
 
         // 结构体名直接使用（假设非泛型）
         Type::Struct(name) => name.clone(),
@@ -79,6 +82,9 @@ fn mangle_type_internal(ty: &Type) -> String {
             format!("Fn__{}_{}", mangle_type(return_type), params.join("_"))
         }
 
+        // dyn Trait -> dyn__Trait
+        Type::TraitObject(name) => format!("dyn__{}", name),
+
         Type::Error => "Error".to_string(),
     }
 }
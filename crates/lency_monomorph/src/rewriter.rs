@@ -51,6 +51,14 @@ impl Rewriter {
 
             Type::Nullable(inner) => Type::Nullable(Box::new(Self::rewrite_type_impl(inner))),
 
+            Type::Function {
+                param_types,
+                return_type,
+            } => Type::Function {
+                param_types: param_types.iter().map(Self::rewrite_type_impl).collect(),
+                return_type: Box::new(Self::rewrite_type_impl(return_type)),
+            },
+
             _ => ty.clone(),
         }
     }
@@ -59,17 +67,20 @@ impl Rewriter {
         match decl {
             Decl::Struct {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 fields,
             } => Decl::Struct {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 fields: fields.into_iter().map(|f| self.rewrite_field(f)).collect(),
             },
             Decl::Function {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 params,
@@ -77,6 +88,7 @@ impl Rewriter {
                 body,
             } => Decl::Function {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 params: params.into_iter().map(|p| self.rewrite_param(p)).collect(),
@@ -88,12 +100,14 @@ impl Rewriter {
             },
             Decl::ExternFunction {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 params,
                 return_type,
             } => Decl::ExternFunction {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 params: params.into_iter().map(|p| self.rewrite_param(p)).collect(),
@@ -115,22 +129,26 @@ impl Rewriter {
             // Trait 定义：目前不需要重写，直接保留
             Decl::Trait {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 methods,
             } => Decl::Trait {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 methods,
             },
             Decl::Enum {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 variants,
             } => Decl::Enum {
                 span,
+                is_pub,
                 name,
                 generic_params,
                 variants: variants
@@ -146,11 +164,13 @@ impl Rewriter {
             },
             Decl::Var {
                 span,
+                is_pub,
                 name,
                 ty,
                 value,
             } => Decl::Var {
                 span,
+                is_pub,
                 name,
                 ty: ty.map(|t| self.rewrite_type(&t)),
                 value: self.rewrite_expr(value),
@@ -189,6 +209,15 @@ impl Rewriter {
                 ty: ty.map(|t| self.rewrite_type(&t)),
                 value: self.rewrite_expr(value),
             },
+            Stmt::VarPattern {
+                span,
+                pattern,
+                value,
+            } => Stmt::VarPattern {
+                span,
+                pattern,
+                value: self.rewrite_expr(value),
+            },
             Stmt::Assignment {
                 span,
                 target,
@@ -221,6 +250,34 @@ impl Rewriter {
                 else_block: else_block
                     .map(|b| b.into_iter().map(|s| self.rewrite_stmt(s)).collect()),
             },
+            Stmt::IfLet {
+                span,
+                pattern,
+                value,
+                then_block,
+                else_block,
+            } => Stmt::IfLet {
+                span,
+                pattern,
+                value: self.rewrite_expr(value),
+                then_block: then_block
+                    .into_iter()
+                    .map(|s| self.rewrite_stmt(s))
+                    .collect(),
+                else_block: else_block
+                    .map(|b| b.into_iter().map(|s| self.rewrite_stmt(s)).collect()),
+            },
+            Stmt::WhileLet {
+                span,
+                pattern,
+                value,
+                body,
+            } => Stmt::WhileLet {
+                span,
+                pattern,
+                value: self.rewrite_expr(value),
+                body: body.into_iter().map(|s| self.rewrite_stmt(s)).collect(),
+            },
             Stmt::While {
                 span,
                 condition,
@@ -292,7 +349,20 @@ impl Rewriter {
                 array: Box::new(self.rewrite_expr(*array)),
                 index: Box::new(self.rewrite_expr(*index)),
             },
+            ExprKind::Range {
+                start,
+                end,
+                inclusive,
+            } => ExprKind::Range {
+                start: Box::new(self.rewrite_expr(*start)),
+                end: Box::new(self.rewrite_expr(*end)),
+                inclusive,
+            },
             ExprKind::Print(e) => ExprKind::Print(Box::new(self.rewrite_expr(*e))),
+            ExprKind::CoerceDyn { value, trait_name } => ExprKind::CoerceDyn {
+                value: Box::new(self.rewrite_expr(*value)),
+                trait_name,
+            },
 
             ExprKind::StructLiteral { type_, fields } => ExprKind::StructLiteral {
                 type_: self.rewrite_type(&type_),
@@ -316,6 +386,7 @@ impl Rewriter {
                     .into_iter()
                     .map(|c| MatchCase {
                         pattern: c.pattern,
+                        guard: c.guard.map(|g| Box::new(self.rewrite_expr(*g))),
                         body: Box::new(self.rewrite_expr(*c.body)),
                         span: c.span,
                     })
@@ -323,6 +394,21 @@ impl Rewriter {
                 default: default.map(|e| Box::new(self.rewrite_expr(*e))),
             },
 
+            ExprKind::Closure {
+                params,
+                body,
+                captures,
+                return_type,
+            } => ExprKind::Closure {
+                params: params.into_iter().map(|p| self.rewrite_param(p)).collect(),
+                body: Box::new(self.rewrite_expr(*body)),
+                captures: captures
+                    .into_iter()
+                    .map(|c| self.rewrite_param(c))
+                    .collect(),
+                return_type: return_type.map(|t| self.rewrite_type(&t)),
+            },
+
             ExprKind::GenericInstantiation { base, args } => {
                 // Rewriting `func::<int>` -> `func__int` (Variable)
                 // Assuming base is Variable.
@@ -5,11 +5,13 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
     match decl {
         Decl::Var {
             span,
+            is_pub,
             name,
             ty,
             value,
         } => Decl::Var {
             span: span.clone(),
+            is_pub: *is_pub,
             name: name.clone(),
             ty: ty.clone(),       // Should specialize type
             value: value.clone(), // Should specialize expr
@@ -20,6 +22,7 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
         },
         Decl::Struct {
             span,
+            is_pub,
             name,
             generic_params,
             fields,
@@ -33,6 +36,7 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
 
             Decl::Struct {
                 span: span.clone(),
+                is_pub: *is_pub,
                 name: name.clone(),
                 generic_params: remaining_params,
                 fields: fields.iter().map(|f| spec.specialize_field(f)).collect(),
@@ -40,6 +44,7 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
         }
         Decl::Function {
             span,
+            is_pub,
             name,
             generic_params,
             params,
@@ -54,6 +59,7 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
 
             Decl::Function {
                 span: span.clone(),
+                is_pub: *is_pub,
                 name: name.clone(),
                 generic_params: remaining_params,
                 params: params.iter().map(|p| spec.specialize_param(p)).collect(),
@@ -63,6 +69,7 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
         }
         Decl::ExternFunction {
             span,
+            is_pub,
             name,
             generic_params,
             params,
@@ -76,6 +83,7 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
 
             Decl::ExternFunction {
                 span: span.clone(),
+                is_pub: *is_pub,
                 name: name.clone(),
                 generic_params: remaining_params,
                 params: params.iter().map(|p| spec.specialize_param(p)).collect(),
@@ -106,17 +114,20 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
         // Trait 定义：目前不需要特化，直接保留
         Decl::Trait {
             span,
+            is_pub,
             name,
             generic_params,
             methods,
         } => Decl::Trait {
             span: span.clone(),
+            is_pub: *is_pub,
             name: name.clone(),
             generic_params: generic_params.clone(),
             methods: methods.clone(),
         },
         Decl::Enum {
             span,
+            is_pub,
             name,
             generic_params,
             variants,
@@ -129,6 +140,7 @@ pub fn specialize(spec: &Specializer, decl: &Decl) -> Decl {
 
             Decl::Enum {
                 span: span.clone(),
+                is_pub: *is_pub,
                 name: name.clone(),
                 generic_params: remaining_params,
                 variants: variants
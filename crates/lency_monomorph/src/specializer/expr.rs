@@ -5,6 +5,7 @@ pub fn specialize(spec: &Specializer, expr: &Expr) -> Expr {
     let new_kind = match &expr.kind {
         ExprKind::Literal(lit) => ExprKind::Literal(lit.clone()), // Lit 不变
         ExprKind::Unit => ExprKind::Unit,
+        ExprKind::Error => ExprKind::Error,
         ExprKind::Variable(name) => ExprKind::Variable(name.clone()),
         ExprKind::Binary(lhs, op, rhs) => ExprKind::Binary(
             Box::new(spec.specialize_expr(lhs)),
@@ -33,7 +34,24 @@ pub fn specialize(spec: &Specializer, expr: &Expr) -> Expr {
             array: Box::new(spec.specialize_expr(array)),
             index: Box::new(spec.specialize_expr(index)),
         },
+        ExprKind::Range {
+            start,
+            end,
+            inclusive,
+        } => ExprKind::Range {
+            start: Box::new(spec.specialize_expr(start)),
+            end: Box::new(spec.specialize_expr(end)),
+            inclusive: *inclusive,
+        },
         ExprKind::Print(e) => ExprKind::Print(Box::new(spec.specialize_expr(e))),
+        ExprKind::CoerceDyn { value, trait_name } => ExprKind::CoerceDyn {
+            value: Box::new(spec.specialize_expr(value)),
+            trait_name: trait_name.clone(),
+        },
+        ExprKind::Cast { value, target } => ExprKind::Cast {
+            value: Box::new(spec.specialize_expr(value)),
+            target: spec.specialize_type(target),
+        },
 
         ExprKind::StructLiteral { type_, fields } => ExprKind::StructLiteral {
             type_: spec.specialize_type(type_),
@@ -62,6 +80,7 @@ pub fn specialize(spec: &Specializer, expr: &Expr) -> Expr {
                 .iter()
                 .map(|c| MatchCase {
                     pattern: c.pattern.clone(),
+                    guard: c.guard.as_ref().map(|g| Box::new(spec.specialize_expr(g))),
                     body: Box::new(spec.specialize_expr(&c.body)),
                     span: c.span.clone(),
                 })
@@ -73,7 +92,12 @@ pub fn specialize(spec: &Specializer, expr: &Expr) -> Expr {
         ExprKind::Ok(inner) => ExprKind::Ok(Box::new(spec.specialize_expr(inner))),
         ExprKind::Err(inner) => ExprKind::Err(Box::new(spec.specialize_expr(inner))),
         // 闭包
-        ExprKind::Closure { params, body } => ExprKind::Closure {
+        ExprKind::Closure {
+            params,
+            body,
+            captures,
+            return_type,
+        } => ExprKind::Closure {
             params: params
                 .iter()
                 .map(|p| Param {
@@ -82,6 +106,15 @@ pub fn specialize(spec: &Specializer, expr: &Expr) -> Expr {
                 })
                 .collect(),
             body: Box::new(spec.specialize_expr(body)),
+            // 捕获变量的类型同样依赖泛型参数 (如捕获 T x)
+            captures: captures
+                .iter()
+                .map(|c| Param {
+                    name: c.name.clone(),
+                    ty: spec.specialize_type(&c.ty),
+                })
+                .collect(),
+            return_type: return_type.as_ref().map(|t| spec.specialize_type(t)),
         },
         // File I/O intrinsics
         ExprKind::ReadFile(path) => ExprKind::ReadFile(Box::new(spec.specialize_expr(path))),
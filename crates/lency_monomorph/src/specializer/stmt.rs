@@ -14,6 +14,15 @@ pub fn specialize(spec: &Specializer, stmt: &Stmt) -> Stmt {
             ty: ty.as_ref().map(|t| spec.specialize_type(t)),
             value: spec.specialize_expr(value),
         },
+        Stmt::VarPattern {
+            span,
+            pattern,
+            value,
+        } => Stmt::VarPattern {
+            span: span.clone(),
+            pattern: pattern.clone(),
+            value: spec.specialize_expr(value),
+        },
         Stmt::Assignment {
             span,
             target,
@@ -42,6 +51,32 @@ pub fn specialize(spec: &Specializer, stmt: &Stmt) -> Stmt {
                 .as_ref()
                 .map(|b| b.iter().map(|s| spec.specialize_stmt(s)).collect()),
         },
+        Stmt::IfLet {
+            span,
+            pattern,
+            value,
+            then_block,
+            else_block,
+        } => Stmt::IfLet {
+            span: span.clone(),
+            pattern: pattern.clone(),
+            value: spec.specialize_expr(value),
+            then_block: then_block.iter().map(|s| spec.specialize_stmt(s)).collect(),
+            else_block: else_block
+                .as_ref()
+                .map(|b| b.iter().map(|s| spec.specialize_stmt(s)).collect()),
+        },
+        Stmt::WhileLet {
+            span,
+            pattern,
+            value,
+            body,
+        } => Stmt::WhileLet {
+            span: span.clone(),
+            pattern: pattern.clone(),
+            value: spec.specialize_expr(value),
+            body: body.iter().map(|s| spec.specialize_stmt(s)).collect(),
+        },
         Stmt::While {
             span,
             condition,
@@ -38,6 +38,18 @@ pub fn specialize(spec: &Specializer, ty: &Type) -> Type {
 
         Type::Nullable(inner) => Type::Nullable(Box::new(spec.specialize_type(inner))),
 
+        // T(T) -> int(int)
+        Type::Function {
+            param_types,
+            return_type,
+        } => Type::Function {
+            param_types: param_types
+                .iter()
+                .map(|t| spec.specialize_type(t))
+                .collect(),
+            return_type: Box::new(spec.specialize_type(return_type)),
+        },
+
         // 基础类型不变
         _ => ty.clone(),
     }
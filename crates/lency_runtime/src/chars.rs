@@ -0,0 +1,100 @@
+//! Lency Char Runtime
+//!
+//! 字符与字符串视图的运行时支持。Lency 字符串是带长度头的 UTF-8 字节串：
+//! `lency_string_len` / `lency_string_substr` 按字节计，
+//! 这里的 `lency_string_chars` 按 Unicode 标量值（char）拆分。
+
+use std::os::raw::c_char;
+
+use crate::gc;
+use crate::lstring;
+use crate::LencyVec;
+
+/// 将码点编码为单字符的 UTF-8 字符串
+/// 返回新分配的字符串；不是合法 Unicode 标量值时返回 U+FFFD
+///
+/// # Safety
+/// 返回的字符串由垃圾回收器管理
+#[no_mangle]
+pub unsafe extern "C" fn lency_char_to_string(char_code: i64) -> *mut c_char {
+    let c = u32::try_from(char_code)
+        .ok()
+        .and_then(char::from_u32)
+        .unwrap_or(char::REPLACEMENT_CHARACTER);
+    gc::new_string(c.encode_utf8(&mut [0; 4]))
+}
+
+/// 将字符串拆分为码点数组 (`s.chars()`、`for c in s`)
+/// 返回新分配的 Vec；非法的 UTF-8 序列解码为 U+FFFD
+///
+/// # Safety
+/// `ptr` must be a Lency string or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_chars(ptr: *const c_char) -> *mut LencyVec {
+    let vec = crate::new_vec_handle(0);
+    if ptr.is_null() {
+        return vec;
+    }
+
+    for c in unsafe { lstring::to_str_lossy(ptr) }.chars() {
+        unsafe { (*vec).push(c as i64) };
+    }
+    vec
+}
+
+/// 将字符串拆分为 UTF-8 字节数组 (`s.bytes()`)
+/// 返回新分配的 Vec
+///
+/// # Safety
+/// `ptr` must be a Lency string or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_bytes(ptr: *const c_char) -> *mut LencyVec {
+    if ptr.is_null() {
+        return crate::new_vec_handle(0);
+    }
+
+    let bytes = unsafe { lstring::bytes(ptr) };
+    let vec = crate::new_vec_handle(bytes.len() as i64);
+    for &byte in bytes {
+        unsafe { (*vec).push(byte as i64) };
+    }
+    vec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec_items(vec: *mut LencyVec) -> Vec<i64> {
+        unsafe { (0..(*vec).len()).map(|i| (*vec).get(i)).collect() }
+    }
+
+    #[test]
+    fn test_char_to_string_utf8() {
+        for (code, expected) in [
+            (65, "A"),
+            (0x4E2D, "中"),
+            (0x1F600, "😀"),
+            (0xD800, "\u{FFFD}"),
+            (0, "\0"),
+        ] {
+            let result = unsafe { lency_char_to_string(code) };
+            assert_eq!(unsafe { lstring::bytes(result) }, expected.as_bytes());
+            unsafe { crate::lency_free_string(result) };
+        }
+    }
+
+    #[test]
+    fn test_string_chars_and_bytes() {
+        let s = gc::new_string("a中😀");
+
+        let chars = unsafe { lency_string_chars(s) };
+        assert_eq!(vec_items(chars), vec![0x61, 0x4E2D, 0x1F600]);
+        unsafe { crate::lency_vec_free(chars) };
+
+        let bytes = unsafe { lency_string_bytes(s) };
+        assert_eq!(vec_items(bytes).len(), 1 + 3 + 4);
+        assert_eq!(vec_items(bytes)[..2], [0x61, 0xE4]);
+        unsafe { crate::lency_vec_free(bytes) };
+    }
+}
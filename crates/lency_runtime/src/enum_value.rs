@@ -10,26 +10,54 @@ impl LencyEnumValue {
     fn new(tag: i64, payloads: Vec<i64>) -> Box<Self> {
         Box::new(Self { tag, payloads })
     }
+
+    /// 转为受垃圾回收器管理的句柄
+    fn into_handle(self: Box<Self>) -> i64 {
+        crate::gc::manage(
+            Box::into_raw(self),
+            std::mem::size_of::<Self>(),
+            crate::gc::ObjectKind::Enum,
+        ) as i64
+    }
+}
+
+/// 遍历句柄的所有 payload（供垃圾回收器扫描引用）
+///
+/// # Safety
+/// `handle` must be a live pointer returned by one of the `lency_enum_new*` functions.
+pub(crate) unsafe fn for_each_payload(handle: usize, f: &mut dyn FnMut(usize)) {
+    let value = unsafe { &*(handle as *const LencyEnumValue) };
+    for &payload in &value.payloads {
+        f(payload as usize);
+    }
+}
+
+/// 释放句柄
+///
+/// # Safety
+/// `handle` must be a live pointer returned by one of the `lency_enum_new*` functions.
+pub(crate) unsafe fn free_handle(handle: usize) {
+    drop(unsafe { Box::from_raw(handle as *mut LencyEnumValue) });
 }
 
 #[no_mangle]
 pub extern "C" fn lency_enum_new0(tag: i64) -> i64 {
-    Box::into_raw(LencyEnumValue::new(tag, vec![])) as i64
+    LencyEnumValue::new(tag, vec![]).into_handle()
 }
 
 #[no_mangle]
 pub extern "C" fn lency_enum_new1(tag: i64, payload0: i64) -> i64 {
-    Box::into_raw(LencyEnumValue::new(tag, vec![payload0])) as i64
+    LencyEnumValue::new(tag, vec![payload0]).into_handle()
 }
 
 #[no_mangle]
 pub extern "C" fn lency_enum_new2(tag: i64, payload0: i64, payload1: i64) -> i64 {
-    Box::into_raw(LencyEnumValue::new(tag, vec![payload0, payload1])) as i64
+    LencyEnumValue::new(tag, vec![payload0, payload1]).into_handle()
 }
 
 #[no_mangle]
 pub extern "C" fn lency_enum_new3(tag: i64, payload0: i64, payload1: i64, payload2: i64) -> i64 {
-    Box::into_raw(LencyEnumValue::new(tag, vec![payload0, payload1, payload2])) as i64
+    LencyEnumValue::new(tag, vec![payload0, payload1, payload2]).into_handle()
 }
 
 #[no_mangle]
@@ -40,10 +68,7 @@ pub extern "C" fn lency_enum_new4(
     payload2: i64,
     payload3: i64,
 ) -> i64 {
-    Box::into_raw(LencyEnumValue::new(
-        tag,
-        vec![payload0, payload1, payload2, payload3],
-    )) as i64
+    LencyEnumValue::new(tag, vec![payload0, payload1, payload2, payload3]).into_handle()
 }
 
 #[no_mangle]
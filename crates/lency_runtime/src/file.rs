@@ -2,11 +2,12 @@
 //!
 //! 提供 Lency 语言的文件 I/O 运行时支持
 
-use std::ffi::CStr;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::os::raw::c_char;
 
+use crate::lstring;
+
 /// Lency 文件句柄
 #[repr(C)]
 pub struct LencyFile {
@@ -32,24 +33,30 @@ impl LencyFile {
     }
 }
 
+/// 路径参数；null 或不是合法 UTF-8 时返回 None
+///
+/// # Safety
+/// `path` must be a Lency string or null
+unsafe fn path_arg<'a>(path: *const c_char) -> Option<&'a str> {
+    if path.is_null() {
+        return None;
+    }
+    std::str::from_utf8(unsafe { lstring::bytes(path) }).ok()
+}
+
 // FFI Functions
 
 /// 打开文件
-/// path: 文件路径 (C 字符串)
+/// path: 文件路径 (Lency 字符串)
 /// mode: 模式 - 0=读, 1=写, 2=追加
 /// 返回: 文件句柄指针, 如果失败返回 NULL
 ///
 /// # Safety
-/// `path` must be a valid null-terminated C string.
+/// `path` must be a Lency string or null.
 #[no_mangle]
 pub unsafe extern "C" fn lency_file_open(path: *const c_char, mode: i64) -> *mut LencyFile {
-    if path.is_null() {
+    let Some(path_str) = (unsafe { path_arg(path) }) else {
         return std::ptr::null_mut();
-    }
-
-    let path_str = match unsafe { CStr::from_ptr(path) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return std::ptr::null_mut(),
     };
 
     let file_result = match mode {
@@ -81,20 +88,15 @@ pub unsafe extern "C" fn lency_file_close(handle: *mut LencyFile) {
     }
 }
 
-/// 读取文件全部内容到缓冲区
-/// 返回: 已读取字节数, -1 表示错误
+/// 读取文件剩余的全部内容
+/// 返回: 新分配的字符串（按原样保存所有字节，包括 NUL）, NULL 表示错误
 ///
 /// # Safety
-/// - `handle` must be a valid pointer returned by `lency_file_open`.
-/// - `buffer` must be a valid writable buffer of at least `buffer_size` bytes.
+/// `handle` must be a valid pointer returned by `lency_file_open` or NULL.
 #[no_mangle]
-pub unsafe extern "C" fn lency_file_read_all(
-    handle: *mut LencyFile,
-    buffer: *mut c_char,
-    buffer_size: i64,
-) -> i64 {
-    if handle.is_null() || buffer.is_null() {
-        return -1;
+pub unsafe extern "C" fn lency_file_read_all(handle: *mut LencyFile) -> *mut c_char {
+    if handle.is_null() {
+        return std::ptr::null_mut();
     }
 
     let lency_file = unsafe { &mut *handle };
@@ -102,18 +104,11 @@ pub unsafe extern "C" fn lency_file_read_all(
     if let Some(ref mut file) = lency_file.file {
         let mut contents = Vec::new();
         match file.read_to_end(&mut contents) {
-            Ok(bytes_read) => {
-                let copy_len = std::cmp::min(bytes_read, (buffer_size - 1) as usize);
-                unsafe {
-                    std::ptr::copy_nonoverlapping(contents.as_ptr(), buffer as *mut u8, copy_len);
-                    *buffer.add(copy_len) = 0; // Null terminate
-                }
-                copy_len as i64
-            }
-            Err(_) => -1,
+            Ok(_) => lstring::from_bytes(&contents),
+            Err(_) => std::ptr::null_mut(),
         }
     } else {
-        -1
+        std::ptr::null_mut()
     }
 }
 
@@ -122,7 +117,7 @@ pub unsafe extern "C" fn lency_file_read_all(
 ///
 /// # Safety
 /// - `handle` must be a valid pointer returned by `lency_file_open`.
-/// - `data` must be a valid null-terminated C string.
+/// - `data` must be a Lency string.
 #[no_mangle]
 pub unsafe extern "C" fn lency_file_write(handle: *mut LencyFile, data: *const c_char) -> i64 {
     if handle.is_null() || data.is_null() {
@@ -132,8 +127,7 @@ pub unsafe extern "C" fn lency_file_write(handle: *mut LencyFile, data: *const c
     let lency_file = unsafe { &mut *handle };
 
     if let Some(ref mut file) = lency_file.file {
-        let c_str = unsafe { CStr::from_ptr(data) };
-        let bytes = c_str.to_bytes();
+        let bytes = unsafe { lstring::bytes(data) };
 
         match file.write_all(bytes) {
             Ok(_) => bytes.len() as i64,
@@ -165,15 +159,10 @@ pub unsafe extern "C" fn lency_file_is_valid(handle: *const LencyFile) -> i64 {
 /// Check if a file or directory exists
 ///
 /// # Safety
-/// `path` must be a valid null-terminated C string
+/// `path` must be a Lency string or null
 #[no_mangle]
 pub unsafe extern "C" fn lency_file_exists(path: *const c_char) -> i64 {
-    if path.is_null() {
-        return 0;
-    }
-
-    let c_str = unsafe { CStr::from_ptr(path) };
-    if let Ok(path_str) = c_str.to_str() {
+    if let Some(path_str) = unsafe { path_arg(path) } {
         if std::path::Path::new(path_str).exists() {
             return 1;
         }
@@ -184,15 +173,10 @@ pub unsafe extern "C" fn lency_file_exists(path: *const c_char) -> i64 {
 /// Check if a path is a directory
 ///
 /// # Safety
-/// `path` must be a valid null-terminated C string
+/// `path` must be a Lency string or null
 #[no_mangle]
 pub unsafe extern "C" fn lency_file_is_dir(path: *const c_char) -> i64 {
-    if path.is_null() {
-        return 0;
-    }
-
-    let c_str = unsafe { CStr::from_ptr(path) };
-    if let Ok(path_str) = c_str.to_str() {
+    if let Some(path_str) = unsafe { path_arg(path) } {
         if std::path::Path::new(path_str).is_dir() {
             return 1;
         }
@@ -203,31 +187,32 @@ pub unsafe extern "C" fn lency_file_is_dir(path: *const c_char) -> i64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ffi::CString;
+    use crate::gc::new_string;
 
     #[test]
     fn test_file_write_read() {
         // 写入（跨平台临时目录）
         let test_file = std::env::temp_dir().join("lency_test.txt");
-        let path = CString::new(test_file.to_string_lossy().as_bytes()).unwrap();
-        let write_handle = unsafe { lency_file_open(path.as_ptr(), 1) };
+        let path = new_string(&test_file.to_string_lossy());
+        let write_handle = unsafe { lency_file_open(path, 1) };
         assert!(!write_handle.is_null());
 
-        let content = CString::new("Hello, Lency!").unwrap();
-        let written = unsafe { lency_file_write(write_handle, content.as_ptr()) };
-        assert!(written > 0);
+        // 内容包含 NUL 字节，读写都不能在此截断
+        let content = lstring::from_bytes(b"Hello,\0Lency!");
+        let written = unsafe { lency_file_write(write_handle, content) };
+        assert_eq!(written, 13);
 
         unsafe { lency_file_close(write_handle) };
 
         // 读取
-        let read_handle = unsafe { lency_file_open(path.as_ptr(), 0) };
+        let read_handle = unsafe { lency_file_open(path, 0) };
         assert!(!read_handle.is_null());
 
-        let mut buffer = vec![0i8; 100];
-        let read_bytes = unsafe { lency_file_read_all(read_handle, buffer.as_mut_ptr(), 100) };
-        assert!(read_bytes > 0);
+        let read = unsafe { lency_file_read_all(read_handle) };
+        assert_eq!(unsafe { lstring::bytes(read) }, b"Hello,\0Lency!");
 
         unsafe { lency_file_close(read_handle) };
+        assert!(unsafe { lency_file_read_all(std::ptr::null_mut()) }.is_null());
 
         let _ = std::fs::remove_file(test_file);
     }
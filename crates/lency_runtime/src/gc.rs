@@ -0,0 +1,506 @@
+//! Garbage Collector
+//!
+//! 保守式标记-清除（mark-sweep）垃圾回收器
+//!
+//! - 运行时创建的对象（Vec、HashMap、字符串、enum 盒子）与生成代码通过
+//!   `lency_gc_alloc` 分配的内存（结构体、闭包环境、Result 等）都登记在堆表中
+//! - 根集合：机器栈（`lency_gc_init` 记录的栈底到当前栈顶，含被调用者保存寄存器）
+//!   以及通过 `lency_gc_add_root` 注册的全局变量区间
+//! - 保守扫描：任何看起来指向已登记对象（包括对象内部）的字都视为引用
+//! - 只有调用过 `lency_gc_init` 后才会按分配量自动触发回收；
+//!   未初始化时（例如自举编译器的 LIR 路径、单元测试）只记账不回收
+
+use crate::enum_value;
+use crate::hashmap::LencyHashMap;
+use crate::hashmap_string::LencyHashMapString;
+use crate::LencyVec;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::os::raw::c_char;
+
+const WORD: usize = std::mem::size_of::<usize>();
+
+/// 自上次回收以来分配多少字节后触发下一次回收（下限）
+const MIN_THRESHOLD: usize = 4 * 1024 * 1024;
+
+/// 堆对象种类：决定如何遍历子引用与如何释放
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    /// 生成代码分配的原始内存，逐字保守扫描
+    Raw,
+    /// 不含指针的内存（字符串）
+    Leaf,
+    Vec,
+    HashMap,
+    StringMap,
+    Enum,
+}
+
+#[derive(Debug)]
+struct Object {
+    size: usize,
+    kind: ObjectKind,
+    marked: bool,
+}
+
+/// 堆统计信息
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GcStats {
+    /// 当前存活的对象数
+    pub live_objects: u64,
+    /// 当前存活对象占用的字节数（含 Vec/HashMap 的元素缓冲区）
+    pub live_bytes: u64,
+    /// 累计分配的对象数
+    pub total_objects: u64,
+    /// 累计分配的字节数
+    pub total_bytes: u64,
+    /// 累计回收（含显式释放）的对象数
+    pub freed_objects: u64,
+    /// 已执行的回收次数
+    pub collections: u64,
+}
+
+/// 对象表与回收状态
+#[derive(Debug)]
+pub struct Heap {
+    objects: BTreeMap<usize, Object>,
+    /// 全局根区间 (起始地址, 字节数)
+    roots: Vec<(usize, usize)>,
+    stack_bottom: Option<usize>,
+    allocated_since_gc: usize,
+    threshold: usize,
+    stats: GcStats,
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Heap {
+    pub fn new() -> Self {
+        Self {
+            objects: BTreeMap::new(),
+            roots: Vec::new(),
+            stack_bottom: None,
+            allocated_since_gc: 0,
+            threshold: MIN_THRESHOLD,
+            stats: GcStats::default(),
+        }
+    }
+
+    /// 登记一个新对象
+    pub fn register(&mut self, ptr: usize, size: usize, kind: ObjectKind) {
+        if ptr == 0 {
+            return;
+        }
+        self.objects.insert(
+            ptr,
+            Object {
+                size: size.max(1),
+                kind,
+                marked: false,
+            },
+        );
+        self.allocated_since_gc += size;
+        self.stats.total_objects += 1;
+        self.stats.total_bytes += size as u64;
+    }
+
+    /// 显式释放对象；指针不在堆表中时返回 false
+    pub fn release(&mut self, ptr: usize) -> bool {
+        match self.objects.remove(&ptr) {
+            Some(object) => {
+                unsafe { free_object(ptr, object.kind) };
+                self.stats.freed_objects += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn add_root(&mut self, addr: usize, size: usize) {
+        if addr != 0 && size > 0 {
+            self.roots.push((addr, size));
+        }
+    }
+
+    fn should_collect(&self) -> bool {
+        self.stack_bottom.is_some() && self.allocated_since_gc >= self.threshold
+    }
+
+    /// 包含地址 `addr` 的对象起始地址
+    fn find_object(&self, addr: usize) -> Option<usize> {
+        let (&start, object) = self.objects.range(..=addr).next_back()?;
+        (addr < start + object.size).then_some(start)
+    }
+
+    /// 以 `stack_words` 与已注册的全局区间为根执行一次回收，返回回收的对象数
+    pub fn collect(&mut self, stack_words: &[usize]) -> usize {
+        let mut worklist: Vec<usize> = stack_words.to_vec();
+        for &(addr, size) in &self.roots {
+            unsafe { scan_range(addr, addr + size, &mut |word| worklist.push(word)) };
+        }
+
+        // 标记
+        while let Some(word) = worklist.pop() {
+            let Some(start) = self.find_object(word) else {
+                continue;
+            };
+            let object = self.objects.get_mut(&start).expect("object exists");
+            if object.marked {
+                continue;
+            }
+            object.marked = true;
+            let (size, kind) = (object.size, object.kind);
+            unsafe { trace_object(start, size, kind, &mut |child| worklist.push(child)) };
+        }
+
+        // 清除
+        let garbage: Vec<(usize, ObjectKind)> = self
+            .objects
+            .iter()
+            .filter(|(_, object)| !object.marked)
+            .map(|(&ptr, object)| (ptr, object.kind))
+            .collect();
+        for &(ptr, kind) in &garbage {
+            self.objects.remove(&ptr);
+            unsafe { free_object(ptr, kind) };
+        }
+        for object in self.objects.values_mut() {
+            object.marked = false;
+        }
+
+        self.stats.freed_objects += garbage.len() as u64;
+        self.stats.collections += 1;
+        self.allocated_since_gc = 0;
+        self.threshold = MIN_THRESHOLD.max(self.live_bytes() * 2);
+        garbage.len()
+    }
+
+    fn live_bytes(&self) -> usize {
+        self.objects
+            .iter()
+            .map(|(&ptr, object)| unsafe { footprint(ptr, object.size, object.kind) })
+            .sum()
+    }
+
+    pub fn stats(&self) -> GcStats {
+        GcStats {
+            live_objects: self.objects.len() as u64,
+            live_bytes: self.live_bytes() as u64,
+            ..self.stats
+        }
+    }
+}
+
+/// 逐字读取 [start, end) 区间
+unsafe fn scan_range(start: usize, end: usize, f: &mut dyn FnMut(usize)) {
+    let mut addr = (start + WORD - 1) & !(WORD - 1);
+    while addr + WORD <= end {
+        f(std::ptr::read_volatile(addr as *const usize));
+        addr += WORD;
+    }
+}
+
+/// 遍历对象中可能是引用的字
+unsafe fn trace_object(ptr: usize, size: usize, kind: ObjectKind, f: &mut dyn FnMut(usize)) {
+    match kind {
+        ObjectKind::Raw => scan_range(ptr, ptr + size, f),
+        ObjectKind::Leaf => {}
+        ObjectKind::Vec => {
+            for &word in (*(ptr as *const LencyVec)).as_slice() {
+                f(word as usize);
+            }
+        }
+        ObjectKind::HashMap => (*(ptr as *const LencyHashMap)).for_each_word(f),
+        ObjectKind::StringMap => (*(ptr as *const LencyHashMapString)).for_each_value(f),
+        ObjectKind::Enum => enum_value::for_each_payload(ptr, f),
+    }
+}
+
+/// 对象及其附属缓冲区占用的字节数
+unsafe fn footprint(ptr: usize, size: usize, kind: ObjectKind) -> usize {
+    match kind {
+        ObjectKind::Raw | ObjectKind::Leaf | ObjectKind::StringMap | ObjectKind::Enum => size,
+        ObjectKind::Vec => size + (*(ptr as *const LencyVec)).buffer_size(),
+        ObjectKind::HashMap => size + (*(ptr as *const LencyHashMap)).buffer_size(),
+    }
+}
+
+unsafe fn free_object(ptr: usize, kind: ObjectKind) {
+    match kind {
+        ObjectKind::Raw | ObjectKind::Leaf => libc::free(ptr as *mut libc::c_void),
+        ObjectKind::Vec => drop(Box::from_raw(ptr as *mut LencyVec)),
+        ObjectKind::HashMap => drop(Box::from_raw(ptr as *mut LencyHashMap)),
+        ObjectKind::StringMap => drop(Box::from_raw(ptr as *mut LencyHashMapString)),
+        ObjectKind::Enum => enum_value::free_handle(ptr),
+    }
+}
+
+thread_local! {
+    static HEAP: RefCell<Heap> = RefCell::new(Heap::new());
+}
+
+/// 把被调用者保存寄存器写入栈上数组，使只存在于寄存器中的指针也能被扫描到
+#[inline(always)]
+fn spill_registers(regs: &mut [usize; 12]) {
+    let ptr = regs.as_mut_ptr();
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        std::arch::asm!(
+            "mov [{0}], rbx",
+            "mov [{0} + 8], rbp",
+            "mov [{0} + 16], r12",
+            "mov [{0} + 24], r13",
+            "mov [{0} + 32], r14",
+            "mov [{0} + 40], r15",
+            in(reg) ptr,
+            options(nostack, preserves_flags)
+        );
+    }
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        std::arch::asm!(
+            "stp x19, x20, [{0}]",
+            "stp x21, x22, [{0}, #16]",
+            "stp x23, x24, [{0}, #32]",
+            "stp x25, x26, [{0}, #48]",
+            "stp x27, x28, [{0}, #64]",
+            "str x29, [{0}, #80]",
+            in(reg) ptr,
+            options(nostack, preserves_flags)
+        );
+    }
+    let _ = ptr;
+}
+
+/// 收集从当前栈顶到 `bottom` 之间的所有字
+#[inline(never)]
+fn scan_stack(bottom: usize) -> Vec<usize> {
+    let mut regs = [0usize; 12];
+    spill_registers(&mut regs);
+    let top = std::hint::black_box(&regs) as *const _ as usize;
+    let (lo, hi) = if top < bottom {
+        (top, bottom)
+    } else {
+        (bottom, top)
+    };
+    let mut words = Vec::new();
+    unsafe { scan_range(lo, hi, &mut |word| words.push(word)) };
+    words
+}
+
+fn collect_current_thread(heap: &mut Heap) -> usize {
+    match heap.stack_bottom {
+        Some(bottom) => {
+            let words = scan_stack(bottom);
+            heap.collect(&words)
+        }
+        None => 0,
+    }
+}
+
+/// 登记运行时创建的对象；必要时先执行一次回收
+pub fn manage<T>(ptr: *mut T, size: usize, kind: ObjectKind) -> *mut T {
+    HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+        if heap.should_collect() {
+            collect_current_thread(&mut heap);
+        }
+        heap.register(ptr as usize, size, kind);
+    });
+    ptr
+}
+
+/// 分配一块受管理的内存（清零）
+pub fn alloc(size: usize, kind: ObjectKind) -> *mut u8 {
+    let ptr = unsafe { libc::calloc(1, size.max(1)) as *mut u8 };
+    if ptr.is_null() {
+        panic!("Out of memory: failed to allocate {} bytes", size);
+    }
+    manage(ptr, size, kind)
+}
+
+/// 把 Rust 字符串复制为受管理的 Lency 字符串（布局见 `lstring`）
+pub fn new_string(s: &str) -> *mut c_char {
+    crate::lstring::from_bytes(s.as_bytes())
+}
+
+/// 显式释放受管理的对象；不在堆表中的指针返回 false
+pub fn release<T>(ptr: *mut T) -> bool {
+    HEAP.with(|heap| heap.borrow_mut().release(ptr as usize))
+}
+
+/// 立即执行一次回收，返回回收的对象数（未初始化时不回收）
+pub fn collect() -> usize {
+    HEAP.with(|heap| collect_current_thread(&mut heap.borrow_mut()))
+}
+
+/// 当前线程的堆统计
+pub fn stats() -> GcStats {
+    HEAP.with(|heap| heap.borrow().stats())
+}
+
+// ============== FFI Functions ==============
+
+/// 初始化回收器：记录栈底地址，此后按分配量自动回收
+///
+/// # Safety
+/// `stack_bottom` must point into the stack frame of the outermost Lency frame
+/// (e.g. a local of `main`) and stay valid for the lifetime of the program.
+#[no_mangle]
+pub unsafe extern "C" fn lency_gc_init(stack_bottom: *const u8) {
+    HEAP.with(|heap| heap.borrow_mut().stack_bottom = Some(stack_bottom as usize));
+}
+
+/// 注册一段全局变量内存为根
+///
+/// # Safety
+/// `addr` must point to `size` readable bytes that stay valid for the lifetime of the program.
+#[no_mangle]
+pub unsafe extern "C" fn lency_gc_add_root(addr: *const u8, size: i64) {
+    HEAP.with(|heap| {
+        heap.borrow_mut()
+            .add_root(addr as usize, size.max(0) as usize)
+    });
+}
+
+/// 分配受管理的内存（生成代码中替代 malloc）
+#[no_mangle]
+pub extern "C" fn lency_gc_alloc(size: i64) -> *mut u8 {
+    alloc(size.max(0) as usize, ObjectKind::Raw)
+}
+
+/// 立即执行一次回收，返回回收的对象数
+#[no_mangle]
+pub extern "C" fn lency_gc_collect() -> i64 {
+    collect() as i64
+}
+
+#[no_mangle]
+pub extern "C" fn lency_gc_live_objects() -> i64 {
+    stats().live_objects as i64
+}
+
+#[no_mangle]
+pub extern "C" fn lency_gc_live_bytes() -> i64 {
+    stats().live_bytes as i64
+}
+
+#[no_mangle]
+pub extern "C" fn lency_gc_collections() -> i64 {
+    stats().collections as i64
+}
+
+#[no_mangle]
+pub extern "C" fn lency_gc_total_allocated() -> i64 {
+    stats().total_bytes as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(heap: &mut Heap, size: usize) -> usize {
+        let ptr = unsafe { libc::calloc(1, size) } as usize;
+        heap.register(ptr, size, ObjectKind::Raw);
+        ptr
+    }
+
+    #[test]
+    fn test_unreachable_objects_are_freed() {
+        let mut heap = Heap::new();
+        let kept = raw(&mut heap, 16);
+        raw(&mut heap, 16);
+        raw(&mut heap, 32);
+
+        assert_eq!(heap.collect(&[kept]), 2);
+        let stats = heap.stats();
+        assert_eq!(stats.live_objects, 1);
+        assert_eq!(stats.live_bytes, 16);
+        assert_eq!(stats.collections, 1);
+        assert_eq!(stats.freed_objects, 2);
+    }
+
+    #[test]
+    fn test_trace_through_objects_and_interior_pointers() {
+        let mut heap = Heap::new();
+        let outer = raw(&mut heap, 16);
+        let inner = raw(&mut heap, 8);
+        unsafe { *((outer + 8) as *mut usize) = inner };
+
+        let vec = Box::into_raw(LencyVec::new(2));
+        heap.register(
+            vec as usize,
+            std::mem::size_of::<LencyVec>(),
+            ObjectKind::Vec,
+        );
+        let leaf = raw(&mut heap, 6);
+        unsafe { (*vec).push(leaf as i64) };
+
+        // 指向 outer 内部的指针同样使其存活
+        assert_eq!(heap.collect(&[outer + 8, vec as usize]), 0);
+        assert_eq!(heap.stats().live_objects, 4);
+
+        assert_eq!(heap.collect(&[]), 4);
+        assert_eq!(heap.stats().live_objects, 0);
+    }
+
+    #[test]
+    fn test_global_roots() {
+        let mut heap = Heap::new();
+        let target = raw(&mut heap, 8);
+        let global = Box::new(target);
+        heap.add_root(&*global as *const usize as usize, WORD);
+
+        assert_eq!(heap.collect(&[]), 0);
+        assert_eq!(heap.stats().live_objects, 1);
+    }
+
+    #[test]
+    fn test_memory_stays_flat_across_collections() {
+        let mut heap = Heap::new();
+        let mut peak = 0;
+        for _ in 0..10 {
+            for _ in 0..100 {
+                raw(&mut heap, 64);
+            }
+            heap.collect(&[]);
+            peak = peak.max(heap.stats().live_bytes);
+        }
+        let stats = heap.stats();
+        assert_eq!(peak, 0);
+        assert_eq!(stats.total_objects, 1000);
+        assert_eq!(stats.freed_objects, 1000);
+    }
+
+    #[test]
+    fn test_stack_roots_keep_objects_alive() {
+        let bottom = 0u64;
+        unsafe { lency_gc_init(std::hint::black_box(&bottom) as *const u64 as *const u8) };
+
+        let vec = crate::lency_vec_new(4);
+        let s = new_string("kept");
+        unsafe { crate::lency_vec_push(vec, s as i64) };
+        std::hint::black_box(&vec);
+
+        collect();
+        let stats = stats();
+        assert_eq!(stats.collections, 1);
+        assert!(stats.live_objects >= 2);
+        assert_eq!(unsafe { crate::lency_vec_get(vec, 0) }, s as i64);
+        assert_eq!(unsafe { crate::string::lency_string_len(s) }, 4);
+    }
+
+    #[test]
+    fn test_explicit_release() {
+        let s = new_string("bye");
+        assert_eq!(stats().live_objects, 1);
+        assert!(unsafe { crate::lstring::release(s) });
+        assert!(!unsafe { crate::lstring::release(s) });
+        assert_eq!(stats().live_objects, 0);
+    }
+}
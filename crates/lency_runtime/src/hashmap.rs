@@ -185,6 +185,22 @@ impl LencyHashMap {
         }
     }
 
+    /// 遍历所有已占用条目的键与值（供垃圾回收器扫描引用）
+    pub(crate) fn for_each_word(&self, f: &mut dyn FnMut(usize)) {
+        for i in 0..self.capacity as usize {
+            let entry = unsafe { &*self.entries.add(i) };
+            if entry.state == EntryState::Occupied {
+                f(entry.key as usize);
+                f(entry.value as usize);
+            }
+        }
+    }
+
+    /// 条目缓冲区的字节数
+    pub(crate) fn buffer_size(&self) -> usize {
+        self.capacity as usize * std::mem::size_of::<Entry>()
+    }
+
     /// 获取长度
     pub fn len(&self) -> i64 {
         self.len
@@ -213,7 +229,11 @@ impl Drop for LencyHashMap {
 /// Create a new HashMap
 #[no_mangle]
 pub extern "C" fn lency_hashmap_new(initial_capacity: i64) -> *mut LencyHashMap {
-    Box::into_raw(LencyHashMap::new(initial_capacity))
+    crate::gc::manage(
+        Box::into_raw(LencyHashMap::new(initial_capacity)),
+        std::mem::size_of::<LencyHashMap>(),
+        crate::gc::ObjectKind::HashMap,
+    )
 }
 
 /// Insert a key-value pair
@@ -282,7 +302,7 @@ pub unsafe extern "C" fn lency_hashmap_len(map: *const LencyHashMap) -> i64 {
 /// `map` must be a valid pointer returned by `lency_hashmap_new` and not already freed
 #[no_mangle]
 pub unsafe extern "C" fn lency_hashmap_free(map: *mut LencyHashMap) {
-    if !map.is_null() {
+    if !map.is_null() && !crate::gc::release(map) {
         let _ = Box::from_raw(map);
     }
 }
@@ -3,12 +3,13 @@
 //! 为 Lency 提供字符串键的哈希表支持，用于符号表等核心数据结构
 
 use std::collections::HashMap as StdHashMap;
-use std::ffi::CStr;
 use std::os::raw::c_char;
 
-/// String → Int 的哈希表
+use crate::lstring::bytes;
+
+/// String → Int 的哈希表（键按字节比较，可以包含 NUL）
 pub struct LencyHashMapString {
-    map: StdHashMap<String, i64>,
+    map: StdHashMap<Vec<u8>, i64>,
 }
 
 impl LencyHashMapString {
@@ -18,25 +19,32 @@ impl LencyHashMapString {
         }
     }
 
-    fn insert(&mut self, key: String, value: i64) {
+    fn insert(&mut self, key: Vec<u8>, value: i64) {
         self.map.insert(key, value);
     }
 
-    fn get(&self, key: &str) -> Option<i64> {
+    fn get(&self, key: &[u8]) -> Option<i64> {
         self.map.get(key).copied()
     }
 
-    fn contains(&self, key: &str) -> bool {
+    fn contains(&self, key: &[u8]) -> bool {
         self.map.contains_key(key)
     }
 
-    fn remove(&mut self, key: &str) -> bool {
+    fn remove(&mut self, key: &[u8]) -> bool {
         self.map.remove(key).is_some()
     }
 
     fn len(&self) -> usize {
         self.map.len()
     }
+
+    /// 遍历所有值（供垃圾回收器扫描引用）
+    pub(crate) fn for_each_value(&self, f: &mut dyn FnMut(usize)) {
+        for &value in self.map.values() {
+            f(value as usize);
+        }
+    }
 }
 
 // ============== FFI 函数 ==============
@@ -44,14 +52,18 @@ impl LencyHashMapString {
 /// 创建新的 HashMap<String, Int>
 #[no_mangle]
 pub extern "C" fn lency_hashmap_string_new() -> *mut LencyHashMapString {
-    Box::into_raw(Box::new(LencyHashMapString::new()))
+    crate::gc::manage(
+        Box::into_raw(Box::new(LencyHashMapString::new())),
+        std::mem::size_of::<LencyHashMapString>(),
+        crate::gc::ObjectKind::StringMap,
+    )
 }
 
 /// 插入键值对
 ///
 /// # Safety
 /// - map 必须是有效的指针
-/// - key 必须是有效的 C 字符串
+/// - key 必须是有效的 Lency 字符串
 #[no_mangle]
 pub unsafe extern "C" fn lency_hashmap_string_insert(
     map: *mut LencyHashMapString,
@@ -63,8 +75,7 @@ pub unsafe extern "C" fn lency_hashmap_string_insert(
     }
 
     let map = &mut *map;
-    let key_str = CStr::from_ptr(key).to_string_lossy().into_owned();
-    map.insert(key_str, value);
+    map.insert(bytes(key).to_vec(), value);
 }
 
 /// 获取值
@@ -75,7 +86,7 @@ pub unsafe extern "C" fn lency_hashmap_string_insert(
 ///
 /// # Safety
 /// - map 必须是有效的指针
-/// - key 必须是有效的 C 字符串
+/// - key 必须是有效的 Lency 字符串
 #[no_mangle]
 pub unsafe extern "C" fn lency_hashmap_string_get(
     map: *const LencyHashMapString,
@@ -86,15 +97,14 @@ pub unsafe extern "C" fn lency_hashmap_string_get(
     }
 
     let map = &*map;
-    let key_str = CStr::from_ptr(key).to_string_lossy();
-    map.get(&key_str).unwrap_or(0)
+    map.get(bytes(key)).unwrap_or(0)
 }
 
 /// 检查键是否存在
 ///
 /// # Safety
 /// - map 必须是有效的指针
-/// - key 必须是有效的 C 字符串
+/// - key 必须是有效的 Lency 字符串
 #[no_mangle]
 pub unsafe extern "C" fn lency_hashmap_string_contains(
     map: *const LencyHashMapString,
@@ -105,8 +115,7 @@ pub unsafe extern "C" fn lency_hashmap_string_contains(
     }
 
     let map = &*map;
-    let key_str = CStr::from_ptr(key).to_string_lossy();
-    map.contains(&key_str)
+    map.contains(bytes(key))
 }
 
 /// 删除键值对
@@ -117,7 +126,7 @@ pub unsafe extern "C" fn lency_hashmap_string_contains(
 ///
 /// # Safety
 /// - map 必须是有效的指针
-/// - key 必须是有效的 C 字符串
+/// - key 必须是有效的 Lency 字符串
 #[no_mangle]
 pub unsafe extern "C" fn lency_hashmap_string_remove(
     map: *mut LencyHashMapString,
@@ -128,8 +137,7 @@ pub unsafe extern "C" fn lency_hashmap_string_remove(
     }
 
     let map = &mut *map;
-    let key_str = CStr::from_ptr(key).to_string_lossy();
-    map.remove(&key_str)
+    map.remove(bytes(key))
 }
 
 /// 获取大小
@@ -153,7 +161,7 @@ pub unsafe extern "C" fn lency_hashmap_string_len(map: *const LencyHashMapString
 /// - 释放后不能再使用该指针
 #[no_mangle]
 pub unsafe extern "C" fn lency_hashmap_string_free(map: *mut LencyHashMapString) {
-    if !map.is_null() {
+    if !map.is_null() && !crate::gc::release(map) {
         let _ = Box::from_raw(map);
     }
 }
@@ -163,7 +171,7 @@ pub unsafe extern "C" fn lency_hashmap_string_free(map: *mut LencyHashMapString)
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ffi::CString;
+    use crate::gc::new_string;
 
     #[test]
     fn test_hashmap_string_basic() {
@@ -172,27 +180,27 @@ mod tests {
             assert!(!map.is_null());
 
             // 插入
-            let key1 = CString::new("hello").unwrap();
-            let key2 = CString::new("world").unwrap();
-            lency_hashmap_string_insert(map, key1.as_ptr(), 42);
-            lency_hashmap_string_insert(map, key2.as_ptr(), 100);
+            let key1 = new_string("hello");
+            let key2 = new_string("world");
+            lency_hashmap_string_insert(map, key1, 42);
+            lency_hashmap_string_insert(map, key2, 100);
 
             // 获取
-            assert_eq!(lency_hashmap_string_get(map, key1.as_ptr()), 42);
-            assert_eq!(lency_hashmap_string_get(map, key2.as_ptr()), 100);
+            assert_eq!(lency_hashmap_string_get(map, key1), 42);
+            assert_eq!(lency_hashmap_string_get(map, key2), 100);
 
             // 检查存在
-            assert!(lency_hashmap_string_contains(map, key1.as_ptr()));
+            assert!(lency_hashmap_string_contains(map, key1));
 
-            let key3 = CString::new("notfound").unwrap();
-            assert!(!lency_hashmap_string_contains(map, key3.as_ptr()));
+            let key3 = new_string("notfound");
+            assert!(!lency_hashmap_string_contains(map, key3));
 
             // 长度
             assert_eq!(lency_hashmap_string_len(map), 2);
 
             // 删除
-            assert!(lency_hashmap_string_remove(map, key1.as_ptr()));
-            assert!(!lency_hashmap_string_contains(map, key1.as_ptr()));
+            assert!(lency_hashmap_string_remove(map, key1));
+            assert!(!lency_hashmap_string_contains(map, key1));
             assert_eq!(lency_hashmap_string_len(map), 1);
 
             // 清理
@@ -204,13 +212,13 @@ mod tests {
     fn test_hashmap_string_overwrite() {
         unsafe {
             let map = lency_hashmap_string_new();
-            let key = CString::new("key").unwrap();
+            let key = new_string("key");
 
-            lency_hashmap_string_insert(map, key.as_ptr(), 1);
-            assert_eq!(lency_hashmap_string_get(map, key.as_ptr()), 1);
+            lency_hashmap_string_insert(map, key, 1);
+            assert_eq!(lency_hashmap_string_get(map, key), 1);
 
-            lency_hashmap_string_insert(map, key.as_ptr(), 2);
-            assert_eq!(lency_hashmap_string_get(map, key.as_ptr()), 2);
+            lency_hashmap_string_insert(map, key, 2);
+            assert_eq!(lency_hashmap_string_get(map, key), 2);
             assert_eq!(lency_hashmap_string_len(map), 1);
 
             lency_hashmap_string_free(map);
@@ -223,9 +231,9 @@ mod tests {
             let map = lency_hashmap_string_new();
             assert_eq!(lency_hashmap_string_len(map), 0);
 
-            let key = CString::new("notexist").unwrap();
-            assert_eq!(lency_hashmap_string_get(map, key.as_ptr()), 0);
-            assert!(!lency_hashmap_string_remove(map, key.as_ptr()));
+            let key = new_string("notexist");
+            assert_eq!(lency_hashmap_string_get(map, key), 0);
+            assert!(!lency_hashmap_string_remove(map, key));
 
             lency_hashmap_string_free(map);
         }
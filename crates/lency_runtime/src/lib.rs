@@ -2,33 +2,40 @@
 //!
 //! 提供 Lency 语言的运行时支持，包括动态数组、哈希表、文件 I/O 和字符串处理
 
+pub mod chars;
 pub mod enum_value;
 pub mod file;
+pub mod gc;
 pub mod hashmap;
 pub mod hashmap_string;
+pub mod lstring;
+pub mod slice;
 pub mod string;
+pub mod string_builder;
+pub mod testing;
+pub mod trace;
 use std::alloc::{alloc, dealloc, realloc, Layout};
-use std::ffi::{CStr, CString};
+use std::ffi::CStr;
 use std::os::raw::c_char;
 
 // ============== Panic Runtime ==============
 
-/// Panic 函数 - 打印错误并退出程序
+/// Panic 函数 - 打印错误与调用栈并退出程序
+///
+/// 编译器生成的代码使用带源码位置的 `trace::lency_panic_at`
 ///
 /// # Safety
 /// `msg` must be a valid null-terminated C string
 #[no_mangle]
 pub unsafe extern "C" fn lency_panic(msg: *const c_char) {
     if msg.is_null() {
-        eprintln!("panic: (null message)");
-    } else {
-        let c_str = unsafe { CStr::from_ptr(msg) };
-        match c_str.to_str() {
-            Ok(s) => eprintln!("panic: {}", s),
-            Err(_) => eprintln!("panic: (invalid UTF-8)"),
-        }
+        trace::fatal("(null message)");
+    }
+    let c_str = unsafe { CStr::from_ptr(msg) };
+    match c_str.to_str() {
+        Ok(s) => trace::fatal(s),
+        Err(_) => trace::fatal("(invalid UTF-8)"),
     }
-    std::process::exit(1);
 }
 
 /// Lency 动态数组
@@ -113,7 +120,10 @@ impl LencyVec {
     /// 获取元素
     pub fn get(&self, index: i64) -> i64 {
         if index < 0 || index >= self.len {
-            panic!("Vec index out of bounds: {} (len: {})", index, self.len);
+            trace::fatal(&format!(
+                "Vec index out of bounds: {} (len: {})",
+                index, self.len
+            ));
         }
         unsafe { *self.data.offset(index as isize) }
     }
@@ -121,12 +131,25 @@ impl LencyVec {
     /// 设置元素
     pub fn set(&mut self, index: i64, value: i64) {
         if index < 0 || index >= self.len {
-            panic!("Vec index out of bounds: {} (len: {})", index, self.len);
+            trace::fatal(&format!(
+                "Vec index out of bounds: {} (len: {})",
+                index, self.len
+            ));
         }
         unsafe {
             *self.data.offset(index as isize) = value;
         }
     }
+
+    /// 已使用的元素
+    pub(crate) fn as_slice(&self) -> &[i64] {
+        unsafe { std::slice::from_raw_parts(self.data, self.len as usize) }
+    }
+
+    /// 元素缓冲区的字节数
+    pub(crate) fn buffer_size(&self) -> usize {
+        self.capacity as usize * std::mem::size_of::<i64>()
+    }
 }
 
 impl Drop for LencyVec {
@@ -145,7 +168,16 @@ impl Drop for LencyVec {
 /// Create a new Vec
 #[no_mangle]
 pub extern "C" fn lency_vec_new(initial_capacity: i64) -> *mut LencyVec {
-    Box::into_raw(LencyVec::new(initial_capacity))
+    new_vec_handle(initial_capacity)
+}
+
+/// 创建受垃圾回收器管理的 Vec
+pub(crate) fn new_vec_handle(initial_capacity: i64) -> *mut LencyVec {
+    gc::manage(
+        Box::into_raw(LencyVec::new(initial_capacity)),
+        std::mem::size_of::<LencyVec>(),
+        gc::ObjectKind::Vec,
+    )
 }
 
 /// Push an element to the Vec
@@ -222,7 +254,7 @@ pub unsafe extern "C" fn lency_vec_set(vec: *mut LencyVec, index: i64, value: i6
 /// `vec` must be a valid pointer returned by `lency_vec_new` and not already freed
 #[no_mangle]
 pub unsafe extern "C" fn lency_vec_free(vec: *mut LencyVec) {
-    if !vec.is_null() {
+    if !vec.is_null() && !gc::release(vec) {
         unsafe {
             let _ = Box::from_raw(vec);
         }
@@ -232,33 +264,25 @@ pub unsafe extern "C" fn lency_vec_free(vec: *mut LencyVec) {
 // ============== Type Conversion FFI ==============
 
 /// Convert int to string
-/// Returns a newly allocated C string that must be freed
+/// Returns a newly allocated Lency string
 #[no_mangle]
-pub extern "C" fn lency_int_to_string(n: i64) -> *mut i8 {
-    let s = n.to_string();
-    match CString::new(s) {
-        Ok(cs) => cs.into_raw(),
-        Err(_) => std::ptr::null_mut(),
-    }
+pub extern "C" fn lency_int_to_string(n: i64) -> *mut c_char {
+    gc::new_string(&n.to_string())
 }
 
 /// Convert float to string
 #[no_mangle]
-pub extern "C" fn lency_float_to_string(f: f64) -> *mut i8 {
-    let s = format!("{}", f);
-    match CString::new(s) {
-        Ok(cs) => cs.into_raw(),
-        Err(_) => std::ptr::null_mut(),
-    }
+pub extern "C" fn lency_float_to_string(f: f64) -> *mut c_char {
+    gc::new_string(&format!("{}", f))
 }
 
 /// Parse int from string, returns 0 on error
 /// Sets is_ok to 1 on success, 0 on failure
 ///
 /// # Safety
-/// `s` must be a valid null-terminated C string
+/// `s` must be a Lency string or null
 #[no_mangle]
-pub unsafe extern "C" fn lency_parse_int(s: *const i8, is_ok: *mut i32) -> i64 {
+pub unsafe extern "C" fn lency_parse_int(s: *const c_char, is_ok: *mut i32) -> i64 {
     if s.is_null() {
         if !is_ok.is_null() {
             *is_ok = 0;
@@ -266,8 +290,7 @@ pub unsafe extern "C" fn lency_parse_int(s: *const i8, is_ok: *mut i32) -> i64 {
         return 0;
     }
 
-    let c_str = CStr::from_ptr(s);
-    match c_str.to_str() {
+    match std::str::from_utf8(lstring::bytes(s)) {
         Ok(str_slice) => match str_slice.trim().parse::<i64>() {
             Ok(n) => {
                 if !is_ok.is_null() {
@@ -294,9 +317,9 @@ pub unsafe extern "C" fn lency_parse_int(s: *const i8, is_ok: *mut i32) -> i64 {
 /// Parse float from string, returns 0.0 on error
 ///
 /// # Safety
-/// `s` must be a valid null-terminated C string
+/// `s` must be a Lency string or null
 #[no_mangle]
-pub unsafe extern "C" fn lency_parse_float(s: *const i8, is_ok: *mut i32) -> f64 {
+pub unsafe extern "C" fn lency_parse_float(s: *const c_char, is_ok: *mut i32) -> f64 {
     if s.is_null() {
         if !is_ok.is_null() {
             *is_ok = 0;
@@ -304,8 +327,7 @@ pub unsafe extern "C" fn lency_parse_float(s: *const i8, is_ok: *mut i32) -> f64
         return 0.0;
     }
 
-    let c_str = CStr::from_ptr(s);
-    match c_str.to_str() {
+    match std::str::from_utf8(lstring::bytes(s)) {
         Ok(str_slice) => match str_slice.trim().parse::<f64>() {
             Ok(f) => {
                 if !is_ok.is_null() {
@@ -329,15 +351,15 @@ pub unsafe extern "C" fn lency_parse_float(s: *const i8, is_ok: *mut i32) -> f64
     }
 }
 
-/// Free a string allocated by lency_int_to_string or lency_float_to_string
+/// Free a string allocated by the runtime (lency_int_to_string, lency_string_* ...)
+///
+/// 字符串由垃圾回收器管理，显式释放只是提前回收；未登记的指针被忽略
 ///
 /// # Safety
-/// `s` must be a valid pointer returned by one of the above functions
+/// `s` must be a valid pointer returned by one of the above functions and not used afterwards
 #[no_mangle]
-pub unsafe extern "C" fn lency_free_string(s: *mut i8) {
-    if !s.is_null() {
-        let _ = CString::from_raw(s);
-    }
+pub unsafe extern "C" fn lency_free_string(s: *mut c_char) {
+    lstring::release(s);
 }
 
 /// Return process argument count (including executable path).
@@ -346,20 +368,17 @@ pub extern "C" fn lency_arg_count() -> i64 {
     std::env::args().count() as i64
 }
 
-/// Return process argument at index as newly allocated C string.
+/// Return process argument at index as newly allocated Lency string.
 ///
 /// # Safety
 /// Caller must eventually free returned string with `lency_free_string`.
 #[no_mangle]
-pub unsafe extern "C" fn lency_arg_at(index: i64) -> *mut i8 {
+pub unsafe extern "C" fn lency_arg_at(index: i64) -> *mut c_char {
     if index < 0 {
         return std::ptr::null_mut();
     }
     match std::env::args().nth(index as usize) {
-        Some(s) => match CString::new(s) {
-            Ok(cs) => cs.into_raw(),
-            Err(_) => std::ptr::null_mut(),
-        },
+        Some(s) => gc::new_string(&s),
         None => std::ptr::null_mut(),
     }
 }
@@ -0,0 +1,201 @@
+//! Lency String Representation
+//!
+//! 字符串是带长度头的字节串，对外仍以指向数据首字节的 `char*` 传递：
+//!
+//! ```text
+//! [len: i64][cap: i64][data: cap 字节][NUL]
+//!                     ^ 字符串指针
+//! ```
+//!
+//! - 长度 O(1) 读取，内容可以包含 NUL 字节
+//! - 数据后始终多留一个 NUL（不计入长度），字符串可以直接传给 C 函数
+//! - 生成代码中的字符串字面量是相同布局的常量全局变量
+//! - `extern` 函数返回的 C 字符串经 `lency_string_from_cstr` 复制后才是 Lency 字符串
+
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+use crate::gc::{self, ObjectKind};
+
+/// 位于字符串数据之前的头部
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StringHeader {
+    /// 内容字节数
+    pub len: i64,
+    /// 可容纳的内容字节数（不含结尾的 NUL）
+    pub cap: i64,
+}
+
+/// 头部字节数；数据指针减去它就是分配的起始地址
+pub const HEADER_SIZE: usize = std::mem::size_of::<StringHeader>();
+
+/// 分配可容纳 `cap` 字节、当前长度为 0 的字符串（内容清零），返回数据指针
+pub fn with_capacity(cap: usize) -> *mut c_char {
+    let base = gc::alloc(HEADER_SIZE + cap + 1, ObjectKind::Leaf);
+    unsafe {
+        (base as *mut StringHeader).write(StringHeader {
+            len: 0,
+            cap: cap as i64,
+        });
+        base.add(HEADER_SIZE) as *mut c_char
+    }
+}
+
+/// 把字节复制为受管理的字符串
+pub fn from_bytes(bytes: &[u8]) -> *mut c_char {
+    let ptr = with_capacity(bytes.len());
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr as *mut u8, bytes.len());
+        header_mut(ptr).len = bytes.len() as i64;
+    }
+    ptr
+}
+
+/// 字符串的头部
+///
+/// # Safety
+/// `ptr` must be a non-null Lency string (see module docs)
+pub unsafe fn header<'a>(ptr: *const c_char) -> &'a StringHeader {
+    &*(ptr.sub(HEADER_SIZE) as *const StringHeader)
+}
+
+/// # Safety
+/// `ptr` must be a non-null, heap-allocated Lency string
+unsafe fn header_mut<'a>(ptr: *mut c_char) -> &'a mut StringHeader {
+    &mut *(ptr.sub(HEADER_SIZE) as *mut StringHeader)
+}
+
+/// 字符串内容（不含结尾的 NUL）；null 视为空串
+///
+/// # Safety
+/// `ptr` must be a Lency string or null
+pub unsafe fn bytes<'a>(ptr: *const c_char) -> &'a [u8] {
+    if ptr.is_null() {
+        return &[];
+    }
+    let len = header(ptr).len.max(0) as usize;
+    std::slice::from_raw_parts(ptr as *const u8, len)
+}
+
+/// 按 UTF-8 解码内容，无效字节替换为 U+FFFD
+///
+/// # Safety
+/// `ptr` must be a Lency string or null
+pub unsafe fn to_str_lossy<'a>(ptr: *const c_char) -> Cow<'a, str> {
+    String::from_utf8_lossy(bytes(ptr))
+}
+
+/// 把 `more` 追加到 `buf` 末尾：容量足够时原地写入，否则按倍数扩容后复制，返回（可能新的）数据指针
+///
+/// # Safety
+/// `buf` must be a heap-allocated Lency string that no one else references
+/// (e.g. the buffer owned by a `StringBuilder`)
+pub(crate) unsafe fn append(buf: *mut c_char, more: &[u8]) -> *mut c_char {
+    let StringHeader { len, cap } = *header(buf);
+    let (len, cap) = (len as usize, cap as usize);
+    let needed = len + more.len();
+    let buf = if needed <= cap {
+        buf
+    } else {
+        let grown = with_capacity(needed.max(cap * 2));
+        std::ptr::copy_nonoverlapping(buf as *const u8, grown as *mut u8, len);
+        grown
+    };
+    std::ptr::copy_nonoverlapping(more.as_ptr(), (buf as *mut u8).add(len), more.len());
+    // 维持数据后的 NUL
+    *buf.add(needed) = 0;
+    header_mut(buf).len = needed as i64;
+    buf
+}
+
+/// 显式释放堆上的字符串；字面量等未登记的字符串返回 false
+///
+/// # Safety
+/// `ptr` must be a Lency string and not used afterwards
+pub unsafe fn release(ptr: *mut c_char) -> bool {
+    !ptr.is_null() && gc::release(ptr.sub(HEADER_SIZE))
+}
+
+// ============== FFI Functions ==============
+
+/// 把 NUL 结尾的 C 字符串复制为 Lency 字符串（`extern` 函数返回值的边界转换）
+///
+/// # Safety
+/// `ptr` must be a valid null-terminated C string or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_from_cstr(ptr: *const c_char) -> *mut c_char {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    from_bytes(CStr::from_ptr(ptr).to_bytes())
+}
+
+/// 连接两个字符串，返回新分配的字符串
+///
+/// # Safety
+/// `lhs` and `rhs` must be Lency strings or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_concat(
+    lhs: *const c_char,
+    rhs: *const c_char,
+) -> *mut c_char {
+    let (lhs, rhs) = (bytes(lhs), bytes(rhs));
+    let ptr = with_capacity(lhs.len() + rhs.len());
+    let data = ptr as *mut u8;
+    std::ptr::copy_nonoverlapping(lhs.as_ptr(), data, lhs.len());
+    std::ptr::copy_nonoverlapping(rhs.as_ptr(), data.add(lhs.len()), rhs.len());
+    header_mut(ptr).len = (lhs.len() + rhs.len()) as i64;
+    ptr
+}
+
+/// 经 C stdio 输出字符串（与生成代码中的 printf 共用缓冲区），内容中的 NUL 也原样输出
+///
+/// # Safety
+/// `ptr` must be a Lency string or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_print_string(ptr: *const c_char) {
+    // printf 的 %s 遇到 NUL 即停止，因此按 NUL 分段输出
+    for (i, segment) in bytes(ptr).split(|&b| b == 0).enumerate() {
+        if i > 0 {
+            libc::putchar(0);
+        }
+        libc::printf(
+            c"%.*s".as_ptr(),
+            segment.len() as libc::c_int,
+            segment.as_ptr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_and_embedded_nul() {
+        let s = from_bytes(b"a\0b");
+        unsafe {
+            assert_eq!(*header(s), StringHeader { len: 3, cap: 3 });
+            assert_eq!(bytes(s), b"a\0b");
+            // 数据后保留 NUL，可以直接当作 C 字符串
+            assert_eq!(*s.add(3), 0);
+            assert_eq!(bytes(std::ptr::null()), b"");
+        }
+    }
+
+    #[test]
+    fn test_concat_and_from_cstr() {
+        let c = std::ffi::CString::new("héllo").unwrap();
+        unsafe {
+            let lhs = lency_string_from_cstr(c.as_ptr());
+            let rhs = from_bytes(b" \0!");
+            let joined = lency_string_concat(lhs, rhs);
+            assert_eq!(bytes(joined), "héllo \0!".as_bytes());
+            assert_eq!(header(joined).len, 9);
+            assert!(lency_string_from_cstr(std::ptr::null()).is_null());
+            assert!(release(joined));
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Range Slicing Runtime
+//!
+//! 区间下标 `v[a..b]` / `s[a..b]` 的运行时支持：返回新分配的副本。
+//! `a..=b` 由 codegen 换算为 `a..b+1`，这里只处理左闭右开区间。
+
+use std::os::raw::c_char;
+
+use crate::lstring;
+use crate::trace;
+use crate::LencyVec;
+
+/// 检查 `start..end` 是否落在长度为 `len` 的序列内
+fn slice_bounds(start: i64, end: i64, len: i64) -> Result<(usize, usize), String> {
+    if start < 0 || start > end || end > len {
+        return Err(format!(
+            "slice out of bounds: {}..{} (len: {})",
+            start, end, len
+        ));
+    }
+    Ok((start as usize, end as usize))
+}
+
+/// 字节下标是否位于 UTF-8 字符边界（末尾也算边界）
+fn is_char_boundary(content: &[u8], index: usize) -> bool {
+    content.get(index).is_none_or(|b| (*b as i8) >= -0x40)
+}
+
+/// Vec 切片 `v[start..end]`
+/// 返回新分配的 Vec；越界时终止程序
+///
+/// # Safety
+/// `vec` must be a valid pointer returned by `lency_vec_new` or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_vec_slice(
+    vec: *const LencyVec,
+    start: i64,
+    end: i64,
+) -> *mut LencyVec {
+    let items = if vec.is_null() {
+        &[][..]
+    } else {
+        unsafe { (*vec).as_slice() }
+    };
+    let (from, to) =
+        slice_bounds(start, end, items.len() as i64).unwrap_or_else(|msg| trace::fatal(&msg));
+
+    let result = crate::new_vec_handle(end - start);
+    for item in &items[from..to] {
+        unsafe { (*result).push(*item) };
+    }
+    result
+}
+
+/// 字符串切片 `s[start..end]`（按字节）
+/// 返回新分配的字符串；越界或切在多字节字符中间时终止程序
+///
+/// # Safety
+/// `ptr` must be a Lency string or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_slice(
+    ptr: *const c_char,
+    start: i64,
+    end: i64,
+) -> *mut c_char {
+    let content = if ptr.is_null() {
+        &[][..]
+    } else {
+        unsafe { lstring::bytes(ptr) }
+    };
+    let (from, to) =
+        slice_bounds(start, end, content.len() as i64).unwrap_or_else(|msg| trace::fatal(&msg));
+    if !is_char_boundary(content, from) || !is_char_boundary(content, to) {
+        trace::fatal(&format!(
+            "string slice {}..{} is not on a char boundary",
+            start, end
+        ));
+    }
+    lstring::from_bytes(&content[from..to])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gc;
+
+    #[test]
+    fn test_slice_bounds() {
+        assert_eq!(slice_bounds(1, 3, 5), Ok((1, 3)));
+        assert_eq!(slice_bounds(2, 2, 2), Ok((2, 2)));
+        assert!(slice_bounds(-1, 2, 5).is_err());
+        assert!(slice_bounds(3, 2, 5).is_err());
+        assert!(slice_bounds(0, 6, 5).is_err());
+    }
+
+    #[test]
+    fn test_vec_and_string_slice() {
+        let vec = crate::lency_vec_new(4);
+        for i in 0..5 {
+            unsafe { crate::lency_vec_push(vec, i * 10) };
+        }
+        let part = unsafe { lency_vec_slice(vec, 1, 4) };
+        assert_eq!(unsafe { (*part).as_slice() }, [10, 20, 30]);
+        unsafe {
+            crate::lency_vec_free(part);
+            crate::lency_vec_free(vec);
+        }
+
+        let s = gc::new_string("a中b");
+        let part = unsafe { lency_string_slice(s, 1, 4) };
+        assert_eq!(unsafe { lstring::bytes(part) }, "中".as_bytes());
+        assert!(is_char_boundary("a中b".as_bytes(), 4));
+        assert!(!is_char_boundary("a中b".as_bytes(), 2));
+        unsafe { crate::lency_free_string(part) };
+    }
+}
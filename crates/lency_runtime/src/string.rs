@@ -1,10 +1,10 @@
 //! Lency String Runtime
 //!
-//! 提供 Lency 语言的字符串处理运行时支持
+//! 提供 Lency 语言的字符串处理运行时支持（字符串布局见 `lstring`）
 
-use std::ffi::CStr;
 use std::os::raw::c_char;
 
+use crate::lstring::{self, bytes};
 use crate::LencyVec;
 
 fn is_obviously_invalid_c_string_ptr(ptr: *const c_char) -> bool {
@@ -15,7 +15,7 @@ fn is_obviously_invalid_c_string_ptr(ptr: *const c_char) -> bool {
 /// 比较两个字符串是否内容相等
 ///
 /// # Safety
-/// `lhs` and `rhs` must be valid null-terminated C string pointers unless null
+/// `lhs` and `rhs` must be Lency strings unless null
 #[no_mangle]
 pub unsafe extern "C" fn lency_string_eq(lhs: *const c_char, rhs: *const c_char) -> i64 {
     if lhs.is_null() || rhs.is_null() {
@@ -27,67 +27,51 @@ pub unsafe extern "C" fn lency_string_eq(lhs: *const c_char, rhs: *const c_char)
         return 0;
     }
 
-    let lhs_str = unsafe { CStr::from_ptr(lhs) };
-    let rhs_str = unsafe { CStr::from_ptr(rhs) };
-    if lhs_str.to_bytes() == rhs_str.to_bytes() {
+    // 长度在头部，长度不同时不必逐字节比较
+    if unsafe { bytes(lhs) == bytes(rhs) } {
         1
     } else {
         0
     }
 }
 
-/// 获取字符串长度
+/// 获取字符串长度（UTF-8 字节数，不是字符数），直接读取头部
 ///
 /// # Safety
-/// `ptr` must be a valid null-terminated C string
+/// `ptr` must be a Lency string or null
 #[no_mangle]
 pub unsafe extern "C" fn lency_string_len(ptr: *const c_char) -> i64 {
     if ptr.is_null() {
         return 0;
     }
-    let c_str = unsafe { CStr::from_ptr(ptr) };
-    c_str.to_bytes().len() as i64
+    unsafe { lstring::header(ptr) }.len
 }
 
 /// 去除字符串首尾空白
 /// 返回新分配的字符串
 ///
 /// # Safety
-/// `ptr` must be a valid null-terminated C string
+/// `ptr` must be a Lency string
 #[no_mangle]
 pub unsafe extern "C" fn lency_string_trim(ptr: *const c_char) -> *mut c_char {
     if ptr.is_null() {
         return std::ptr::null_mut();
     }
 
-    let c_str = unsafe { CStr::from_ptr(ptr) };
-    let s = match c_str.to_str() {
+    let s = match std::str::from_utf8(unsafe { bytes(ptr) }) {
         Ok(s) => s,
         Err(_) => return std::ptr::null_mut(),
     };
 
-    let trimmed = s.trim();
-
-    // 分配新内存并复制
-    let len = trimmed.len();
-    let result = unsafe { libc::malloc(len + 1) as *mut c_char };
-    if result.is_null() {
-        return std::ptr::null_mut();
-    }
-
-    unsafe {
-        std::ptr::copy_nonoverlapping(trimmed.as_ptr(), result as *mut u8, len);
-        *result.add(len) = 0; // null terminator
-    }
-
-    result
+    // 分配新字符串并复制
+    lstring::from_bytes(s.trim().as_bytes())
 }
 
 /// 按分隔符拆分字符串
 /// 返回 LencyVec (存储字符串指针)
 ///
 /// # Safety
-/// `str_ptr` and `delim_ptr` must be valid null-terminated C strings
+/// `str_ptr` and `delim_ptr` must be Lency strings
 #[no_mangle]
 pub unsafe extern "C" fn lency_string_split(
     str_ptr: *const c_char,
@@ -97,33 +81,23 @@ pub unsafe extern "C" fn lency_string_split(
         return std::ptr::null_mut();
     }
 
-    let c_str = unsafe { CStr::from_ptr(str_ptr) };
-    let s = match c_str.to_str() {
+    let s = match std::str::from_utf8(unsafe { bytes(str_ptr) }) {
         Ok(s) => s,
         Err(_) => return std::ptr::null_mut(),
     };
 
-    let c_delim = unsafe { CStr::from_ptr(delim_ptr) };
-    let delim = match c_delim.to_str() {
+    let delim = match std::str::from_utf8(unsafe { bytes(delim_ptr) }) {
         Ok(d) => d,
         Err(_) => return std::ptr::null_mut(),
     };
 
     let parts: Vec<&str> = s.split(delim).collect();
-    let vec = Box::into_raw(LencyVec::new(parts.len() as i64));
+    let vec = crate::new_vec_handle(parts.len() as i64);
 
     for part in parts {
-        // 分配每个子串
-        let len = part.len();
-        let part_ptr = unsafe { libc::malloc(len + 1) as *mut c_char };
-        if !part_ptr.is_null() {
-            unsafe {
-                std::ptr::copy_nonoverlapping(part.as_ptr(), part_ptr as *mut u8, len);
-                *part_ptr.add(len) = 0;
-                // 将指针作为 i64 存储 (因为 LencyVec 存储 i64)
-                (*vec).push(part_ptr as i64);
-            }
-        }
+        // 分配每个子串，将指针作为 i64 存储 (因为 LencyVec 存储 i64)
+        let part_ptr = lstring::from_bytes(part.as_bytes());
+        unsafe { (*vec).push(part_ptr as i64) };
     }
 
     vec
@@ -133,8 +107,8 @@ pub unsafe extern "C" fn lency_string_split(
 /// 返回新分配的字符串
 ///
 /// # Safety
-/// `vec_ptr` must be a valid LencyVec containing string pointers
-/// `sep_ptr` must be a valid null-terminated C string
+/// `vec_ptr` must be a valid LencyVec containing Lency strings
+/// `sep_ptr` must be a Lency string
 #[no_mangle]
 pub unsafe extern "C" fn lency_string_join(
     vec_ptr: *const LencyVec,
@@ -144,56 +118,26 @@ pub unsafe extern "C" fn lency_string_join(
         return std::ptr::null_mut();
     }
 
-    let c_sep = unsafe { CStr::from_ptr(sep_ptr) };
-    let sep = match c_sep.to_str() {
-        Ok(s) => s,
-        Err(_) => return std::ptr::null_mut(),
-    };
-
+    let sep = unsafe { bytes(sep_ptr) };
     let vec = unsafe { &*vec_ptr };
-    let len = vec.len();
-
-    if len == 0 {
-        // 返回空字符串
-        let result = unsafe { libc::malloc(1) as *mut c_char };
-        if !result.is_null() {
-            unsafe { *result = 0 };
-        }
-        return result;
-    }
 
-    // 收集所有字符串
-    let mut parts: Vec<String> = Vec::new();
-    for i in 0..len {
-        let str_ptr = vec.get(i) as *const c_char;
-        if !str_ptr.is_null() {
-            let c_str = unsafe { CStr::from_ptr(str_ptr) };
-            if let Ok(s) = c_str.to_str() {
-                parts.push(s.to_string());
-            }
-        }
-    }
+    // 按字节拼接，内容中的 NUL 与非 UTF-8 字节原样保留；null 元素被跳过
+    let parts: Vec<&[u8]> = vec
+        .as_slice()
+        .iter()
+        .map(|&word| word as *const c_char)
+        .filter(|ptr| !ptr.is_null())
+        .map(|ptr| unsafe { bytes(ptr) })
+        .collect();
 
-    let joined = parts.join(sep);
-    let result_len = joined.len();
-    let result = unsafe { libc::malloc(result_len + 1) as *mut c_char };
-    if result.is_null() {
-        return std::ptr::null_mut();
-    }
-
-    unsafe {
-        std::ptr::copy_nonoverlapping(joined.as_ptr(), result as *mut u8, result_len);
-        *result.add(result_len) = 0;
-    }
-
-    result
+    lstring::from_bytes(&parts.join(sep))
 }
 
-/// 提取子串
-/// 返回新分配的字符串
+/// 提取子串：`start` 与 `len` 都以字节计
+/// 返回新分配的字符串；切在多字节字符中间时，残缺的部分替换为 U+FFFD
 ///
 /// # Safety
-/// `ptr` must be a valid null-terminated C string
+/// `ptr` must be a Lency string
 #[no_mangle]
 pub unsafe extern "C" fn lency_string_substr(
     ptr: *const c_char,
@@ -204,76 +148,25 @@ pub unsafe extern "C" fn lency_string_substr(
         return std::ptr::null_mut();
     }
 
-    let c_str = unsafe { CStr::from_ptr(ptr) };
-    let s = match c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => return std::ptr::null_mut(),
-    };
+    let content = unsafe { bytes(ptr) };
 
     let start_idx = start as usize;
-    let end_idx = std::cmp::min(start_idx + len as usize, s.len());
+    let end_idx = std::cmp::min(start_idx.saturating_add(len as usize), content.len());
 
-    if start_idx >= s.len() {
+    if start_idx >= content.len() {
         // 返回空字符串
-        let result = unsafe { libc::malloc(1) as *mut c_char };
-        if !result.is_null() {
-            unsafe { *result = 0 };
-        }
-        return result;
+        return lstring::from_bytes(b"");
     }
 
-    let substr = &s[start_idx..end_idx];
-    let substr_len = substr.len();
-    let result = unsafe { libc::malloc(substr_len + 1) as *mut c_char };
-    if result.is_null() {
-        return std::ptr::null_mut();
-    }
-
-    unsafe {
-        std::ptr::copy_nonoverlapping(substr.as_ptr(), result as *mut u8, substr_len);
-        *result.add(substr_len) = 0;
-    }
-
-    result
-}
-
-/// 将字符码转换为单字符字符串
-/// 返回新分配的字符串
-///
-/// # Safety
-/// 调用者负责释放返回的内存
-#[no_mangle]
-pub unsafe extern "C" fn lency_char_to_string(char_code: i64) -> *mut c_char {
-    // 分配2字节: 1个字符 + null terminator
-    let result = unsafe { libc::malloc(2) as *mut c_char };
-    if result.is_null() {
-        return std::ptr::null_mut();
-    }
-
-    // 简单处理: 只支持 ASCII (0-127)
-    // 对于 Unicode，需要更复杂的 UTF-8 编码
-    if (0..=127).contains(&char_code) {
-        unsafe {
-            *result = char_code as c_char;
-            *result.add(1) = 0; // null terminator
-        }
-    } else {
-        // 非 ASCII: 返回空字符串或 '?'
-        unsafe {
-            *result = b'?' as c_char;
-            *result.add(1) = 0;
-        }
-    }
-
-    result
+    lstring::from_bytes(String::from_utf8_lossy(&content[start_idx..end_idx]).as_bytes())
 }
 
 /// 字符串格式化：将模板中的 {} 占位符按顺序替换为 Vec 中的字符串
 /// 返回新分配的字符串
 ///
 /// # Safety
-/// `template_ptr` must be a valid null-terminated C string
-/// `vec_ptr` must be a valid LencyVec containing string pointers
+/// `template_ptr` must be a Lency string
+/// `vec_ptr` must be a valid LencyVec containing Lency strings
 #[no_mangle]
 pub unsafe extern "C" fn lency_string_format(
     template_ptr: *const c_char,
@@ -283,26 +176,21 @@ pub unsafe extern "C" fn lency_string_format(
         return std::ptr::null_mut();
     }
 
-    let c_template = unsafe { CStr::from_ptr(template_ptr) };
-    let template = match c_template.to_str() {
+    let template = match std::str::from_utf8(unsafe { bytes(template_ptr) }) {
         Ok(s) => s,
         Err(_) => return std::ptr::null_mut(),
     };
 
     let vec = unsafe { &*vec_ptr };
-    let arg_count = vec.len();
 
     // 收集所有参数字符串
-    let mut args: Vec<String> = Vec::new();
-    for i in 0..arg_count {
-        let str_ptr = vec.get(i) as *const c_char;
-        if !str_ptr.is_null() {
-            let c_str = unsafe { CStr::from_ptr(str_ptr) };
-            if let Ok(s) = c_str.to_str() {
-                args.push(s.to_string());
-            }
-        }
-    }
+    let args: Vec<std::borrow::Cow<str>> = vec
+        .as_slice()
+        .iter()
+        .map(|&word| word as *const c_char)
+        .filter(|ptr| !ptr.is_null())
+        .map(|ptr| unsafe { lstring::to_str_lossy(ptr) })
+        .collect();
 
     // 执行替换：逐字符扫描，遇到 {} 则替换
     let mut result = String::with_capacity(template.len() * 2);
@@ -328,138 +216,113 @@ pub unsafe extern "C" fn lency_string_format(
         }
     }
 
-    let result_len = result.len();
-    let buf = unsafe { libc::malloc(result_len + 1) as *mut c_char };
-    if buf.is_null() {
-        return std::ptr::null_mut();
-    }
-
-    unsafe {
-        std::ptr::copy_nonoverlapping(result.as_ptr(), buf as *mut u8, result_len);
-        *buf.add(result_len) = 0;
-    }
-
-    buf
+    lstring::from_bytes(result.as_bytes())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ffi::CString;
+    use crate::gc::new_string;
+
+    fn text<'a>(ptr: *const c_char) -> &'a str {
+        std::str::from_utf8(unsafe { bytes(ptr) }).unwrap()
+    }
 
     #[test]
     fn test_string_len() {
-        let s = CString::new("hello").unwrap();
-        assert_eq!(unsafe { lency_string_len(s.as_ptr()) }, 5);
+        let s = new_string("hello");
+        assert_eq!(unsafe { lency_string_len(s) }, 5);
+
+        let empty = new_string("");
+        assert_eq!(unsafe { lency_string_len(empty) }, 0);
 
-        let empty = CString::new("").unwrap();
-        assert_eq!(unsafe { lency_string_len(empty.as_ptr()) }, 0);
+        // 长度来自头部，内嵌的 NUL 也计入
+        let nul = lstring::from_bytes(b"a\0b");
+        assert_eq!(unsafe { lency_string_len(nul) }, 3);
     }
 
     #[test]
     fn test_string_trim() {
-        let s = CString::new("  hello world  ").unwrap();
-        let result = unsafe { lency_string_trim(s.as_ptr()) };
+        let s = new_string("  hello world  ");
+        let result = unsafe { lency_string_trim(s) };
         assert!(!result.is_null());
+        assert_eq!(text(result), "hello world");
 
-        let trimmed = unsafe { CStr::from_ptr(result) }.to_str().unwrap();
-        assert_eq!(trimmed, "hello world");
-
-        unsafe { libc::free(result as *mut libc::c_void) };
+        unsafe { crate::lency_free_string(result) };
     }
 
     #[test]
     fn test_string_split() {
-        let s = CString::new("a,b,c").unwrap();
-        let delim = CString::new(",").unwrap();
+        let s = new_string("a,b,c");
+        let delim = new_string(",");
 
-        let vec = unsafe { lency_string_split(s.as_ptr(), delim.as_ptr()) };
+        let vec = unsafe { lency_string_split(s, delim) };
         assert!(!vec.is_null());
 
         unsafe {
             assert_eq!((*vec).len(), 3);
-
-            let part0 = CStr::from_ptr((*vec).get(0) as *const c_char)
-                .to_str()
-                .unwrap();
-            assert_eq!(part0, "a");
-
-            let part1 = CStr::from_ptr((*vec).get(1) as *const c_char)
-                .to_str()
-                .unwrap();
-            assert_eq!(part1, "b");
-
-            let part2 = CStr::from_ptr((*vec).get(2) as *const c_char)
-                .to_str()
-                .unwrap();
-            assert_eq!(part2, "c");
+            assert_eq!(text((*vec).get(0) as *const c_char), "a");
+            assert_eq!(text((*vec).get(1) as *const c_char), "b");
+            assert_eq!(text((*vec).get(2) as *const c_char), "c");
 
             // 清理
             for i in 0..(*vec).len() {
-                libc::free((*vec).get(i) as *mut libc::c_void);
+                crate::lency_free_string((*vec).get(i) as *mut c_char);
             }
-            let _ = Box::from_raw(vec);
+            crate::lency_vec_free(vec);
         }
     }
 
     #[test]
     fn test_string_join() {
-        // 创建 vec 并填充
         let vec = Box::into_raw(LencyVec::new(3));
-        let parts = ["hello", "world", "test"];
-
         unsafe {
-            for part in &parts {
-                let cs = CString::new(*part).unwrap();
-                let ptr = libc::malloc(part.len() + 1) as *mut c_char;
-                std::ptr::copy_nonoverlapping(cs.as_ptr(), ptr, part.len() + 1);
-                (*vec).push(ptr as i64);
+            for part in ["hello", "world", "test"] {
+                (*vec).push(new_string(part) as i64);
             }
+            (*vec).push(lstring::from_bytes(b"\0x") as i64);
 
-            let sep = CString::new("-").unwrap();
-            let result = lency_string_join(vec, sep.as_ptr());
+            let sep = new_string("-");
+            let result = lency_string_join(vec, sep);
             assert!(!result.is_null());
+            assert_eq!(bytes(result), b"hello-world-test-\0x");
 
-            let joined = CStr::from_ptr(result).to_str().unwrap();
-            assert_eq!(joined, "hello-world-test");
-
-            // 清理
-            libc::free(result as *mut libc::c_void);
-            for i in 0..(*vec).len() {
-                libc::free((*vec).get(i) as *mut libc::c_void);
-            }
+            crate::lency_free_string(result);
             let _ = Box::from_raw(vec);
         }
     }
 
     #[test]
     fn test_string_substr() {
-        let s = CString::new("hello world").unwrap();
+        let s = new_string("hello world");
 
-        let result = unsafe { lency_string_substr(s.as_ptr(), 0, 5) };
+        let result = unsafe { lency_string_substr(s, 0, 5) };
         assert!(!result.is_null());
-        let substr = unsafe { CStr::from_ptr(result) }.to_str().unwrap();
-        assert_eq!(substr, "hello");
-        unsafe { libc::free(result as *mut libc::c_void) };
-
-        let result2 = unsafe { lency_string_substr(s.as_ptr(), 6, 5) };
-        let substr2 = unsafe { CStr::from_ptr(result2) }.to_str().unwrap();
-        assert_eq!(substr2, "world");
-        unsafe { libc::free(result2 as *mut libc::c_void) };
+        assert_eq!(text(result), "hello");
+        unsafe { crate::lency_free_string(result) };
+
+        let result2 = unsafe { lency_string_substr(s, 6, 5) };
+        assert_eq!(text(result2), "world");
+        unsafe { crate::lency_free_string(result2) };
     }
 
     #[test]
     fn test_string_eq() {
-        let lhs = CString::new("hello").unwrap();
-        let rhs = CString::new("hello").unwrap();
-        let other = CString::new("world").unwrap();
+        let lhs = new_string("hello");
+        let rhs = new_string("hello");
+        let other = new_string("world");
 
-        assert_eq!(unsafe { lency_string_eq(lhs.as_ptr(), rhs.as_ptr()) }, 1);
-        assert_eq!(unsafe { lency_string_eq(lhs.as_ptr(), other.as_ptr()) }, 0);
+        assert_eq!(unsafe { lency_string_eq(lhs, rhs) }, 1);
+        assert_eq!(unsafe { lency_string_eq(lhs, other) }, 0);
         assert_eq!(
             unsafe { lency_string_eq(std::ptr::null(), std::ptr::null()) },
             1
         );
+
+        // 前缀相同但内嵌 NUL 之后不同的字符串不相等
+        let a = lstring::from_bytes(b"ab\0c");
+        let b = lstring::from_bytes(b"ab\0d");
+        assert_eq!(unsafe { lency_string_eq(a, b) }, 0);
     }
 
     #[test]
@@ -474,52 +337,41 @@ mod tests {
         assert!(!is_obviously_invalid_c_string_ptr(std::ptr::null()));
         assert!(!is_obviously_invalid_c_string_ptr(ptr4096));
 
-        let rhs = CString::new("hello").unwrap();
-        assert_eq!(unsafe { lency_string_eq(ptr8, rhs.as_ptr()) }, 0);
+        let rhs = new_string("hello");
+        assert_eq!(unsafe { lency_string_eq(ptr8, rhs) }, 0);
     }
 
     #[test]
     fn test_string_format() {
-        use crate::LencyVec;
-
         // 测试基础替换
-        let template = CString::new("hello {}!").unwrap();
-        let arg = CString::new("world").unwrap();
-
+        let template = new_string("hello {}!");
         let mut vec = LencyVec::new(4);
-        vec.push(arg.as_ptr() as i64);
+        vec.push(new_string("world") as i64);
 
-        let result = unsafe { lency_string_format(template.as_ptr(), &*vec) };
+        let result = unsafe { lency_string_format(template, &*vec) };
         assert!(!result.is_null());
-        let formatted = unsafe { CStr::from_ptr(result) }.to_str().unwrap();
-        assert_eq!(formatted, "hello world!");
-        unsafe { libc::free(result as *mut libc::c_void) };
+        assert_eq!(text(result), "hello world!");
+        unsafe { crate::lency_free_string(result) };
 
         // 测试多参数替换
-        let template2 = CString::new("{} + {} = {}").unwrap();
-        let a1 = CString::new("1").unwrap();
-        let a2 = CString::new("2").unwrap();
-        let a3 = CString::new("3").unwrap();
-
+        let template2 = new_string("{} + {} = {}");
         let mut vec2 = LencyVec::new(4);
-        vec2.push(a1.as_ptr() as i64);
-        vec2.push(a2.as_ptr() as i64);
-        vec2.push(a3.as_ptr() as i64);
+        for arg in ["1", "2", "3"] {
+            vec2.push(new_string(arg) as i64);
+        }
 
-        let result2 = unsafe { lency_string_format(template2.as_ptr(), &*vec2) };
+        let result2 = unsafe { lency_string_format(template2, &*vec2) };
         assert!(!result2.is_null());
-        let formatted2 = unsafe { CStr::from_ptr(result2) }.to_str().unwrap();
-        assert_eq!(formatted2, "1 + 2 = 3");
-        unsafe { libc::free(result2 as *mut libc::c_void) };
+        assert_eq!(text(result2), "1 + 2 = 3");
+        unsafe { crate::lency_free_string(result2) };
 
         // 测试无占位符
-        let template3 = CString::new("no placeholders").unwrap();
+        let template3 = new_string("no placeholders");
         let vec3 = LencyVec::new(4);
 
-        let result3 = unsafe { lency_string_format(template3.as_ptr(), &*vec3) };
+        let result3 = unsafe { lency_string_format(template3, &*vec3) };
         assert!(!result3.is_null());
-        let formatted3 = unsafe { CStr::from_ptr(result3) }.to_str().unwrap();
-        assert_eq!(formatted3, "no placeholders");
-        unsafe { libc::free(result3 as *mut libc::c_void) };
+        assert_eq!(text(result3), "no placeholders");
+        unsafe { crate::lency_free_string(result3) };
     }
 }
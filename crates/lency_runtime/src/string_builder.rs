@@ -0,0 +1,162 @@
+//! StringBuilder Runtime Implementation
+//!
+//! 可增长的字符串缓冲区，把循环中的反复拼接从 O(n²) 降为均摊 O(n)。
+//! 缓冲区本身是一个带空余容量的 Lency 字符串（见 `lstring`），
+//! `build` 复制出独立的结果，之后仍可继续追加。
+//!
+//! 编译器把循环里的 `s = s + x` 改写为 `from` / `append` / `build` 调用；
+//! `std.str` 的 `StringBuilder` 结构体通过同一组函数提供显式接口。
+
+use std::os::raw::c_char;
+
+use crate::gc::{self, ObjectKind};
+use crate::lstring;
+
+/// 初始容量
+const INITIAL_CAPACITY: usize = 16;
+
+/// Lency StringBuilder
+///
+/// 作为 `Raw` 对象登记到 GC：保守扫描会找到 `buf`，缓冲区随之存活
+#[repr(C)]
+pub struct LencyStringBuilder {
+    buf: *mut c_char,
+}
+
+impl LencyStringBuilder {
+    /// 已追加的内容
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { lstring::bytes(self.buf) }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.buf = unsafe { lstring::append(self.buf, bytes) };
+    }
+}
+
+fn new_builder(cap: usize) -> *mut LencyStringBuilder {
+    let ptr = gc::alloc(std::mem::size_of::<LencyStringBuilder>(), ObjectKind::Raw)
+        as *mut LencyStringBuilder;
+    unsafe {
+        (*ptr).buf = lstring::with_capacity(cap);
+    }
+    ptr
+}
+
+// ============== FFI Functions ==============
+
+/// 创建空的 StringBuilder
+#[no_mangle]
+pub extern "C" fn lency_string_builder_new() -> *mut LencyStringBuilder {
+    new_builder(INITIAL_CAPACITY)
+}
+
+/// 以 `s` 的内容为初始值创建 StringBuilder（循环拼接改写的入口）
+///
+/// # Safety
+/// `s` must be a Lency string or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_builder_from(s: *const c_char) -> *mut LencyStringBuilder {
+    let bytes = lstring::bytes(s);
+    let sb = new_builder(INITIAL_CAPACITY.max(bytes.len() * 2));
+    (*sb).push(bytes);
+    sb
+}
+
+/// 追加字符串
+///
+/// # Safety
+/// `sb` must be a valid StringBuilder; `s` must be a Lency string or null
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_builder_append(
+    sb: *mut LencyStringBuilder,
+    s: *const c_char,
+) {
+    (*sb).push(lstring::bytes(s));
+}
+
+/// 追加整数的十进制表示
+///
+/// # Safety
+/// `sb` must be a valid StringBuilder
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_builder_append_int(sb: *mut LencyStringBuilder, n: i64) {
+    (*sb).push(n.to_string().as_bytes());
+}
+
+/// 追加一个字符的 UTF-8 编码；不是合法 Unicode 标量值时追加 U+FFFD
+///
+/// # Safety
+/// `sb` must be a valid StringBuilder
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_builder_append_char(
+    sb: *mut LencyStringBuilder,
+    char_code: i64,
+) {
+    let c = u32::try_from(char_code)
+        .ok()
+        .and_then(char::from_u32)
+        .unwrap_or(char::REPLACEMENT_CHARACTER);
+    (*sb).push(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+/// 已追加的字节数
+///
+/// # Safety
+/// `sb` must be a valid StringBuilder
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_builder_len(sb: *const LencyStringBuilder) -> i64 {
+    (*sb).as_bytes().len() as i64
+}
+
+/// 复制出当前内容；StringBuilder 之后仍可继续使用
+///
+/// # Safety
+/// `sb` must be a valid StringBuilder
+#[no_mangle]
+pub unsafe extern "C" fn lency_string_builder_build(sb: *const LencyStringBuilder) -> *mut c_char {
+    lstring::from_bytes((*sb).as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_build() {
+        unsafe {
+            let sb = lency_string_builder_new();
+            lency_string_builder_append(sb, gc::new_string("x = "));
+            lency_string_builder_append_int(sb, -42);
+            lency_string_builder_append_char(sb, 'é' as i64);
+            lency_string_builder_append_char(sb, -1);
+            lency_string_builder_append(sb, std::ptr::null());
+            assert_eq!(lency_string_builder_len(sb), 12);
+
+            let built = lency_string_builder_build(sb);
+            assert_eq!(lstring::bytes(built), "x = -42é\u{FFFD}".as_bytes());
+
+            // build 的结果独立于后续追加
+            lency_string_builder_append(sb, gc::new_string("!"));
+            assert_eq!(lstring::bytes(built), "x = -42é\u{FFFD}".as_bytes());
+            assert_eq!(lency_string_builder_len(sb), 13);
+        }
+    }
+
+    #[test]
+    fn test_growth_keeps_content_and_nul() {
+        unsafe {
+            let sb = lency_string_builder_from(lstring::from_bytes(b"a\0"));
+            let mut expected = b"a\0".to_vec();
+            for i in 0..100 {
+                let piece = i.to_string();
+                lency_string_builder_append(sb, gc::new_string(&piece));
+                expected.extend_from_slice(piece.as_bytes());
+            }
+            let buf = (*sb).buf;
+            assert_eq!(lstring::bytes(buf), expected.as_slice());
+            assert!(lstring::header(buf).cap as usize >= expected.len());
+            assert_eq!(*buf.add(expected.len()), 0);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Test Selection
+//!
+//! `lencyc test` 把一个文件中的全部测试编译进同一个程序，每次运行时通过环境变量
+//! `LENCY_TEST` 选择要调用的测试（按发现顺序的序号，从 0 开始）。
+
+/// 选择测试的环境变量（与 `lency_driver::testing::TEST_SELECTOR_ENV` 一致）
+pub const TEST_SELECTOR_ENV: &str = "LENCY_TEST";
+
+/// 返回要运行的测试序号；`count` 为程序中的测试数量
+///
+/// 未设置或不在 `0..count` 内时报错并以退出码 2 结束程序，与测试失败（退出码 1）区分。
+#[no_mangle]
+pub extern "C" fn lency_test_selected(count: i64) -> i64 {
+    match selected(std::env::var(TEST_SELECTOR_ENV).ok().as_deref(), count) {
+        Some(index) => index,
+        None => {
+            eprintln!(
+                "error: {} must select a test between 0 and {}",
+                TEST_SELECTOR_ENV,
+                count - 1
+            );
+            std::process::exit(2);
+        }
+    }
+}
+
+fn selected(value: Option<&str>, count: i64) -> Option<i64> {
+    value?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|index| (0..count).contains(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selected_index_must_be_in_range() {
+        assert_eq!(selected(Some("0"), 2), Some(0));
+        assert_eq!(selected(Some(" 1\n"), 2), Some(1));
+        assert_eq!(selected(Some("2"), 2), None);
+        assert_eq!(selected(Some("-1"), 2), None);
+        assert_eq!(selected(Some("first"), 2), None);
+        assert_eq!(selected(None, 2), None);
+    }
+}
@@ -0,0 +1,227 @@
+//! Lency Stack Traces
+//!
+//! 运行时维护一个影子调用栈：编译器在每次调用 Lency 函数前后插入
+//! `lency_trace_push` / `lency_trace_pop`，记录被调函数名与调用点的源码位置。
+//! panic 时据此打印 Lency 层面的调用栈，而不依赖 DWARF 或平台的栈回溯。
+//!
+//! 帧中的字符串指向编译器生成的常量全局字符串，生命周期与程序相同。
+
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::fmt::Write;
+use std::os::raw::c_char;
+
+/// 调用栈最多打印的帧数（深递归时省略中间部分）
+const MAX_PRINTED_FRAMES: usize = 64;
+
+/// 源码位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    file: *const c_char,
+    line: u32,
+    column: u32,
+}
+
+/// 一次调用：被调函数与调用点
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    function: *const c_char,
+    call_site: Location,
+}
+
+thread_local! {
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// 进入函数：记录函数名与调用点
+///
+/// # Safety
+/// `function` 与 `file` 必须为 null 或在程序运行期间有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn lency_trace_push(
+    function: *const c_char,
+    file: *const c_char,
+    line: i32,
+    column: i32,
+) {
+    let frame = Frame {
+        function,
+        call_site: Location::new(file, line, column),
+    };
+    FRAMES.with(|frames| frames.borrow_mut().push(frame));
+}
+
+/// 离开最近一次进入的函数
+#[no_mangle]
+pub extern "C" fn lency_trace_pop() {
+    FRAMES.with(|frames| {
+        frames.borrow_mut().pop();
+    });
+}
+
+/// 带源码位置的 panic：打印消息、位置与调用栈后以退出码 1 结束程序
+///
+/// # Safety
+/// `msg` 与 `file` 必须为 null 或有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn lency_panic_at(
+    msg: *const c_char,
+    file: *const c_char,
+    line: i32,
+    column: i32,
+) -> ! {
+    let message = unsafe { c_str_or(msg, "(null message)") };
+    abort_with(&message, Some(Location::new(file, line, column)))
+}
+
+/// 运行时内部的致命错误（如 Vec 越界）：位置未知，仍打印调用栈
+pub fn fatal(message: &str) -> ! {
+    abort_with(message, None)
+}
+
+fn abort_with(message: &str, location: Option<Location>) -> ! {
+    eprint!("{}", report(message, location));
+    std::process::exit(1);
+}
+
+impl Location {
+    fn new(file: *const c_char, line: i32, column: i32) -> Self {
+        Self {
+            file,
+            line: line.max(0) as u32,
+            column: column.max(0) as u32,
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file = unsafe { c_str_or(self.file, "<unknown>") };
+        match (self.line, self.column) {
+            (0, _) => write!(f, "{}", file),
+            (line, 0) => write!(f, "{}:{}", file, line),
+            (line, column) => write!(f, "{}:{}:{}", file, line, column),
+        }
+    }
+}
+
+/// 生成 panic 报告
+///
+/// 第 i 层帧的函数是栈中第 i 个（自顶向下）被调函数，其当前位置是
+/// 上一层调用点；最顶层的位置就是 panic 发生处。
+pub fn report(message: &str, location: Option<Location>) -> String {
+    let mut out = format!("panic: {}\n", message);
+    if let Some(location) = location {
+        let _ = writeln!(out, "  --> {}", location);
+    }
+
+    let frames = FRAMES.with(|frames| frames.borrow().clone());
+    if frames.is_empty() {
+        return out;
+    }
+
+    out.push_str("stack backtrace:\n");
+    let mut position = location;
+    for (depth, frame) in frames.iter().rev().enumerate() {
+        let elided = frames.len() > MAX_PRINTED_FRAMES
+            && depth >= MAX_PRINTED_FRAMES / 2
+            && depth < frames.len() - MAX_PRINTED_FRAMES / 2;
+        if !elided {
+            let name = unsafe { c_str_or(frame.function, "<unknown>") };
+            match position {
+                Some(at) => {
+                    let _ = writeln!(out, "  {:>3}: {} at {}", depth, name, at);
+                }
+                None => {
+                    let _ = writeln!(out, "  {:>3}: {}", depth, name);
+                }
+            }
+        } else if depth == MAX_PRINTED_FRAMES / 2 {
+            let hidden = frames.len() - MAX_PRINTED_FRAMES;
+            let _ = writeln!(out, "       ... {} frames omitted ...", hidden);
+        }
+        position = Some(frame.call_site).filter(|site| site.line > 0);
+    }
+    out
+}
+
+/// 当前调用栈深度
+pub fn depth() -> usize {
+    FRAMES.with(|frames| frames.borrow().len())
+}
+
+unsafe fn c_str_or(ptr: *const c_char, fallback: &str) -> String {
+    if ptr.is_null() {
+        return fallback.to_string();
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(function: &CStr, file: &CStr, line: i32, column: i32) {
+        unsafe { lency_trace_push(function.as_ptr(), file.as_ptr(), line, column) };
+    }
+
+    #[test]
+    fn test_report_lists_frames_from_innermost() {
+        push(c"main", c"app.lcy", 0, 0);
+        push(c"compute", c"app.lcy", 20, 13);
+        push(c"divide", c"app.lcy", 10, 9);
+        assert_eq!(depth(), 3);
+
+        let report = report(
+            "division by zero",
+            Some(Location::new(c"app.lcy".as_ptr(), 4, 5)),
+        );
+        assert_eq!(
+            report,
+            "panic: division by zero\n  --> app.lcy:4:5\nstack backtrace:\n    0: divide at app.lcy:4:5\n    1: compute at app.lcy:10:9\n    2: main at app.lcy:20:13\n"
+        );
+
+        for _ in 0..3 {
+            lency_trace_pop();
+        }
+        assert_eq!(depth(), 0);
+    }
+
+    #[test]
+    fn test_report_without_location() {
+        push(c"main", c"app.lcy", 0, 0);
+        push(c"get", c"app.lcy", 7, 12);
+
+        let report = report("Vec index out of bounds", None);
+        assert!(report.starts_with("panic: Vec index out of bounds\nstack backtrace:\n"));
+        assert!(report.contains("    0: get\n"));
+        assert!(report.contains("    1: main at app.lcy:7:12\n"));
+
+        lency_trace_pop();
+        lency_trace_pop();
+    }
+
+    #[test]
+    fn test_report_elides_deep_recursion() {
+        for line in 0..100 {
+            push(c"recurse", c"app.lcy", line + 1, 1);
+        }
+
+        let report = report("stack too deep", None);
+        assert!(report.contains("... 36 frames omitted ..."));
+        assert_eq!(report.matches("recurse").count(), MAX_PRINTED_FRAMES);
+
+        for _ in 0..100 {
+            lency_trace_pop();
+        }
+    }
+
+    #[test]
+    fn test_pop_on_empty_stack_is_harmless() {
+        lency_trace_pop();
+        assert_eq!(depth(), 0);
+        assert_eq!(report("boom", None), "panic: boom\n");
+    }
+}
@@ -3,20 +3,30 @@
 //! 语义分析错误定义，遵循 Lency "Crystal Clear" 哲学，
 //! 错误信息必须清晰明了，帮助开发者快速定位问题。
 
-use lency_syntax::ast::Span;
+use lency_diagnostics::{Diagnostic, Suggestion};
+use lency_syntax::ast::{Span, Type};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// 语义分析错误
 #[derive(Debug, Clone, Error)]
 pub enum SemanticError {
     // ============ 名称解析错误 ============
-    /// 未定义的变量
+    /// 未定义的变量；`suggestion` 为作用域中拼写相近的名称
     #[error("undefined variable '{name}'")]
-    UndefinedVariable { name: String, span: Span },
+    UndefinedVariable {
+        name: String,
+        span: Span,
+        suggestion: Option<String>,
+    },
 
-    /// 未定义的函数
+    /// 未定义的函数；`suggestion` 为作用域中拼写相近的名称
     #[error("undefined function '{name}'")]
-    UndefinedFunction { name: String, span: Span },
+    UndefinedFunction {
+        name: String,
+        span: Span,
+        suggestion: Option<String>,
+    },
 
     /// 未定义的类型
     #[error("undefined type '{name}'")]
@@ -34,6 +44,34 @@ pub enum SemanticError {
     #[error("import error: {message}")]
     ImportError { message: String, span: Span },
 
+    /// 导入的符号与已有定义冲突
+    #[error("'{name}' imported from module '{module}' conflicts with an existing definition")]
+    ImportConflict {
+        name: String,
+        module: String,
+        span: Span,
+    },
+
+    /// 模块中不存在该成员
+    #[error("module '{module}' has no member '{name}'")]
+    UndefinedModuleMember {
+        module: String,
+        name: String,
+        span: Span,
+    },
+
+    /// 访问模块的私有成员
+    #[error("'{name}' is private to module '{module}' (mark it `pub` to export it)")]
+    PrivateModuleMember {
+        module: String,
+        name: String,
+        span: Span,
+    },
+
+    /// 模块别名被当作值使用
+    #[error("module '{name}' cannot be used as a value")]
+    ModuleNotAValue { name: String, span: Span },
+
     // ============ 类型检查错误 ============
     /// 类型不匹配
     #[error("type mismatch: expected '{expected}', found '{found}'")]
@@ -64,6 +102,18 @@ pub enum SemanticError {
         span: Span,
     },
 
+    /// `as` 转换的源类型或目标类型不是数值类型
+    #[error("cannot cast '{from}' to '{to}'")]
+    InvalidCast {
+        from: String,
+        to: String,
+        span: Span,
+    },
+
+    /// 整数字面量超出定宽整数的取值范围
+    #[error("integer literal {value} is out of range for type '{ty}'")]
+    IntegerLiteralOutOfRange { value: i128, ty: String, span: Span },
+
     // ============ Null Safety 错误 (Lency 核心特性) ============
     /// 将 null 赋给非空类型
     #[error("cannot assign 'null' to non-nullable type '{ty}'")]
@@ -136,6 +186,10 @@ pub enum SemanticError {
     #[error("'continue' outside loop")]
     ContinueOutsideLoop { span: Span },
 
+    /// for-in 的对象不可迭代
+    #[error("'{ty}' is not iterable (expected an array, string, Vec, range, or a type with `next()` or `iter()`)")]
+    NotIterable { ty: String, span: Span },
+
     // ============ 数组相关错误 ============
     /// 数组索引编译期越界
     #[error(
@@ -166,6 +220,22 @@ pub enum SemanticError {
     #[error("undefined trait '{name}'")]
     UndefinedTrait { name: String, span: Span },
 
+    /// Trait 不满足对象安全，不能用作 `dyn Trait`
+    #[error("trait '{name}' cannot be made into an object: {reason}")]
+    TraitNotObjectSafe {
+        name: String,
+        reason: String,
+        span: Span,
+    },
+
+    /// 类型未实现 Trait，不能转换为 `dyn Trait`
+    #[error("type '{type_name}' does not implement trait '{trait_name}'")]
+    TraitNotImplemented {
+        type_name: String,
+        trait_name: String,
+        span: Span,
+    },
+
     /// 缺少 Trait 方法实现
     #[error("missing method '{method_name}' required by trait '{trait_name}'")]
     MissingTraitMethod {
@@ -185,12 +255,38 @@ pub enum SemanticError {
     },
 
     // ============ 模式匹配错误 ============
-    /// 模式匹配不穷尽
-    #[error("pattern not exhaustive. Missing variants: {missing_variants:?}")]
+    /// 模式匹配不穷尽（`missing_variants` 为未覆盖的值，写成模式）
+    #[error("pattern not exhaustive, not covered: {}", .missing_variants.join(", "))]
     PatternNotExhaustive {
         missing_variants: Vec<String>,
         span: Span,
     },
+
+    /// 或模式的各分支绑定的变量不同
+    #[error("variable '{name}' is not bound in every alternative of the or-pattern")]
+    OrPatternBindingMismatch { name: String, span: Span },
+
+    /// 区间模式不包含任何值
+    #[error("range pattern '{pattern}' is empty")]
+    EmptyRangePattern { pattern: String, span: Span },
+
+    /// 解构声明的模式可能不匹配（应改用 `if let` 或 `match`）
+    #[error("refutable pattern in variable declaration, not covered: {}", .missing.join(", "))]
+    RefutablePattern { missing: Vec<String>, span: Span },
+
+    // ============ Lint ============
+    /// 被设为 deny 的 lint
+    #[error("{}", .0.message)]
+    DeniedLint(crate::lint::LintWarning),
+
+    // ============ 错误来源 ============
+    /// 导入模块中的错误：`error` 的位置是模块源文件 `file` 中的偏移
+    #[error("{error}")]
+    InModule {
+        module: String,
+        file: PathBuf,
+        error: Box<SemanticError>,
+    },
 }
 
 impl SemanticError {
@@ -202,10 +298,16 @@ impl SemanticError {
             Self::UndefinedType { span, .. } => span,
             Self::DuplicateDefinition { span, .. } => span,
             Self::ImportError { span, .. } => span,
+            Self::ImportConflict { span, .. } => span,
+            Self::UndefinedModuleMember { span, .. } => span,
+            Self::PrivateModuleMember { span, .. } => span,
+            Self::ModuleNotAValue { span, .. } => span,
             Self::TypeMismatch { span, .. } => span,
             Self::CannotInferType { span, .. } => span,
             Self::InvalidBinaryOp { span, .. } => span,
             Self::InvalidUnaryOp { span, .. } => span,
+            Self::InvalidCast { span, .. } => span,
+            Self::IntegerLiteralOutOfRange { span, .. } => span,
             Self::NullAssignmentToNonNullable { span, .. } => span,
             Self::PossibleNullAccess { span, .. } => span,
             Self::ArgumentCountMismatch { span, .. } => span,
@@ -218,37 +320,171 @@ impl SemanticError {
             Self::NotCallable { span, .. } => span,
             Self::BreakOutsideLoop { span } => span,
             Self::ContinueOutsideLoop { span } => span,
+            Self::NotIterable { span, .. } => span,
             Self::ArrayIndexOutOfBounds { span, .. } => span,
             Self::GenericArityMismatch { span, .. } => span,
             Self::NotAGenericType { span, .. } => span,
             Self::InvalidGenericArg { span, .. } => span,
             Self::UndefinedTrait { span, .. } => span,
+            Self::TraitNotObjectSafe { span, .. } => span,
+            Self::TraitNotImplemented { span, .. } => span,
             Self::MissingTraitMethod { span, .. } => span,
             Self::TraitMethodSignatureMismatch { span, .. } => span,
             Self::PatternNotExhaustive { span, .. } => span,
+            Self::OrPatternBindingMismatch { span, .. } => span,
+            Self::EmptyRangePattern { span, .. } => span,
+            Self::RefutablePattern { span, .. } => span,
+            Self::DeniedLint(lint) => &lint.span,
+            Self::InModule { error, .. } => error.span(),
         }
     }
 
-    /// 转换为统一诊断格式
-    pub fn to_diagnostic(&self) -> lency_diagnostics::Diagnostic {
-        let mut diag =
-            lency_diagnostics::Diagnostic::error(self.to_string()).span(self.span().clone());
+    /// 稳定的错误代码，工具可以据此识别错误种类
+    ///
+    /// 按类别分段：E00xx 名称解析、E01xx 类型检查、E02xx null safety、E03xx 函数调用、
+    /// E04xx 类型成员与控制流、E05xx 数组、E06xx 泛型、E07xx trait、E08xx 模式匹配。
+    /// 已分配的代码不再改变含义；deny 的 lint 使用 lint 名称，导入模块中的错误沿用原错误的代码。
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UndefinedVariable { .. } => "E0001",
+            Self::UndefinedFunction { .. } => "E0002",
+            Self::UndefinedType { .. } => "E0003",
+            Self::DuplicateDefinition { .. } => "E0004",
+            Self::ImportError { .. } => "E0005",
+            Self::ImportConflict { .. } => "E0006",
+            Self::UndefinedModuleMember { .. } => "E0007",
+            Self::PrivateModuleMember { .. } => "E0008",
+            Self::ModuleNotAValue { .. } => "E0009",
+            Self::TypeMismatch { .. } => "E0101",
+            Self::CannotInferType { .. } => "E0102",
+            Self::InvalidBinaryOp { .. } => "E0103",
+            Self::InvalidUnaryOp { .. } => "E0104",
+            Self::InvalidCast { .. } => "E0105",
+            Self::IntegerLiteralOutOfRange { .. } => "E0106",
+            Self::NullAssignmentToNonNullable { .. } => "E0201",
+            Self::PossibleNullAccess { .. } => "E0202",
+            Self::ArgumentCountMismatch { .. } => "E0301",
+            Self::ReturnTypeMismatch { .. } => "E0302",
+            Self::MissingReturn { .. } => "E0303",
+            Self::UndefinedField { .. } => "E0401",
+            Self::UndefinedMethod { .. } => "E0402",
+            Self::NotAClass { .. } => "E0403",
+            Self::NotAStruct { .. } => "E0404",
+            Self::NotCallable { .. } => "E0405",
+            Self::BreakOutsideLoop { .. } => "E0406",
+            Self::ContinueOutsideLoop { .. } => "E0407",
+            Self::NotIterable { .. } => "E0408",
+            Self::ArrayIndexOutOfBounds { .. } => "E0501",
+            Self::GenericArityMismatch { .. } => "E0601",
+            Self::NotAGenericType { .. } => "E0602",
+            Self::InvalidGenericArg { .. } => "E0603",
+            Self::UndefinedTrait { .. } => "E0701",
+            Self::TraitNotObjectSafe { .. } => "E0702",
+            Self::TraitNotImplemented { .. } => "E0703",
+            Self::MissingTraitMethod { .. } => "E0704",
+            Self::TraitMethodSignatureMismatch { .. } => "E0705",
+            Self::PatternNotExhaustive { .. } => "E0801",
+            Self::OrPatternBindingMismatch { .. } => "E0802",
+            Self::EmptyRangePattern { .. } => "E0803",
+            Self::RefutablePattern { .. } => "E0804",
+            Self::DeniedLint(lint) => lint.lint.name(),
+            Self::InModule { error, .. } => error.code(),
+        }
+    }
 
+    /// 标记错误来自导入模块 `module`（源文件 `file`）；已标记的错误保持原来的模块
+    pub fn in_module(self, module: &str, file: &Path) -> Self {
         match self {
-            Self::UndefinedVariable { name, .. } => {
-                diag = diag.with_note(format!("Did you declare the variable '{}' before using it? (If this is during Lency bootstrapping, check your definitions in the parser)", name));
-            }
-            Self::UndefinedFunction { name, .. } => {
-                diag = diag.with_note(format!("Did you define the function '{}'? (If this is during Lency bootstrapping, ensure the function is imported or defined locally)", name));
+            Self::InModule { .. } => self,
+            error => Self::InModule {
+                module: module.to_string(),
+                file: file.to_path_buf(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// 错误所在的导入模块路径；入口源文件中的错误返回 None
+    pub fn source_module(&self) -> Option<&str> {
+        match self {
+            Self::InModule { module, .. } => Some(module),
+            _ => None,
+        }
+    }
+
+    /// 错误所在的导入模块源文件；入口源文件中的错误返回 None
+    pub fn source_file(&self) -> Option<&Path> {
+        match self {
+            Self::InModule { file, .. } => Some(file),
+            _ => None,
+        }
+    }
+
+    /// 去掉来源标记后的错误
+    pub fn kind(&self) -> &SemanticError {
+        match self {
+            Self::InModule { error, .. } => error.kind(),
+            _ => self,
+        }
+    }
+
+    /// 错误是否涉及错误类型 `Type::Error`（语法错误恢复出的节点），这类错误由语法错误引起
+    pub fn involves_error_type(&self) -> bool {
+        self.kind().to_string().contains(&Type::Error.to_string())
+    }
+
+    /// 转换为统一诊断格式
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            Self::DeniedLint(lint) => return lint.to_error_diagnostic(),
+            // 诊断记录模块的源文件，位置按该文件计算行列
+            Self::InModule { file, error, .. } => {
+                return error
+                    .to_diagnostic()
+                    .with_file(file.to_string_lossy().into_owned());
             }
             _ => {}
         }
+        let diag = Diagnostic::error(self.to_string())
+            .with_code(self.code())
+            .span(self.span().clone());
 
-        diag
+        match self {
+            Self::UndefinedVariable {
+                name,
+                span,
+                suggestion,
+            } => match suggestion {
+                Some(similar) => diag.with_suggestion(did_you_mean(span, similar)),
+                None => diag.with_note(format!("Did you declare the variable '{}' before using it? (If this is during Lency bootstrapping, check your definitions in the parser)", name)),
+            },
+            Self::UndefinedFunction {
+                name,
+                span,
+                suggestion,
+            } => match suggestion {
+                Some(similar) => diag.with_suggestion(did_you_mean(span, similar)),
+                None => diag.with_note(format!("Did you define the function '{}'? (If this is during Lency bootstrapping, ensure the function is imported or defined locally)", name)),
+            },
+            Self::DuplicateDefinition {
+                name,
+                previous_span,
+                ..
+            } => diag.with_label(
+                previous_span.clone(),
+                format!("previous definition of '{}' here", name),
+            ),
+            _ => diag,
+        }
     }
 }
 
-impl From<SemanticError> for lency_diagnostics::Diagnostic {
+/// 用拼写相近的名称替换 `span` 处的名称
+fn did_you_mean(span: &Span, similar: &str) -> Suggestion {
+    Suggestion::new(format!("did you mean `{}`?", similar)).with_replacement(span.clone(), similar)
+}
+
+impl From<SemanticError> for Diagnostic {
     fn from(err: SemanticError) -> Self {
         err.to_diagnostic()
     }
@@ -5,6 +5,7 @@
 //! - 类型推导 (TypeInferer)
 //! - 类型检查 (TypeChecker)
 //! - 空安全检查 (NullSafetyChecker)
+//! - 警告检查 (lint)
 //!
 //! # 设计原则
 //!
@@ -17,6 +18,9 @@
 //! - 核心数据结构稳定不变
 
 pub mod error;
+pub mod lint;
+pub mod module_cache;
+pub mod module_source;
 pub mod null_safety;
 pub mod operators;
 pub mod resolver;
@@ -24,6 +28,7 @@ pub mod scope;
 pub mod symbol;
 pub mod type_check;
 pub mod type_infer;
+pub mod type_table;
 pub mod types;
 
 // 重新导出核心类型
@@ -32,209 +37,168 @@ pub use crate::symbol::{
     TraitMethodSignature, TraitSymbol, VariableSymbol,
 };
 pub use error::SemanticError;
+pub use lint::{Lint, LintConfig, LintLevel, LintWarning};
+pub use module_cache::{ModuleCache, SharedModuleCache};
+pub use module_source::{ModuleSource, ModuleSources};
 pub use null_safety::NullSafetyChecker;
 pub use operators::{BinaryOpRegistry, UnaryOpRegistry};
 pub use resolver::Resolver;
 pub use scope::{Scope, ScopeId, ScopeKind, ScopeStack};
 pub use type_check::TypeChecker;
 pub use type_infer::TypeInferer;
+pub use type_table::{Definition, TypeTable, TypedExpr};
 pub use types::{TypeInfo, TypeRegistry};
 
 use lency_syntax::ast::Program;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// 语义分析结果
 #[derive(Debug)]
 pub struct AnalysisResult {
     /// 符号表（包含所有定义的符号）
     pub scopes: ScopeStack,
+    /// 入口源文件中表达式的推导类型
+    pub types: TypeTable,
+    /// 导入模块引入的全局名称 -> 模块路径（如 `std.io`）
+    pub module_origins: HashMap<String, String>,
+    /// 导入模块在合并后的 program.decls 中的区间与源文件
+    pub module_sources: ModuleSources,
+    /// 入口源文件的 lint 警告（程序有错误时不检查）
+    pub warnings: Vec<LintWarning>,
+}
+
+/// 语义分析选项
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisOptions {
+    /// import 模块的搜索路径（为空时为当前目录）
+    pub search_paths: Vec<PathBuf>,
+    /// 各 lint 的级别
+    pub lints: LintConfig,
+    /// 导入模块的解析与分析缓存；为 None 时每次都重新解析并分析全部模块
+    pub module_cache: Option<SharedModuleCache>,
 }
 
 /// 分析整个程序
 ///
-/// 按顺序执行三个 Pass：
+/// 按顺序执行四个 Pass：
 /// 1. **Resolver**: 收集定义，解析名称引用
 /// 2. **TypeChecker**: 类型推导和检查
 /// 3. **NullSafetyChecker**: 空安全检查
+/// 4. **lint**: 前三个 Pass 没有错误时检查警告
 ///
 /// # Errors
 ///
 /// 返回所有收集到的语义错误
 pub fn analyze(program: &mut Program) -> Result<AnalysisResult, Vec<SemanticError>> {
+    analyze_with_paths(program, &[])
+}
+
+/// 分析整个程序，import 模块在 `search_paths` 中查找（为空时为当前目录）
+pub fn analyze_with_paths(
+    program: &mut Program,
+    search_paths: &[PathBuf],
+) -> Result<AnalysisResult, Vec<SemanticError>> {
+    let options = AnalysisOptions {
+        search_paths: search_paths.to_vec(),
+        ..AnalysisOptions::default()
+    };
+    analyze_with_options(program, &options)
+}
+
+/// 按选项分析整个程序；被设为 deny 的 lint 作为错误返回
+pub fn analyze_with_options(
+    program: &mut Program,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult, Vec<SemanticError>> {
+    let (result, errors) = analyze_for_ide_with_options(program, options);
+    if errors.is_empty() {
+        Ok(result)
+    } else {
+        Err(errors)
+    }
+}
+
+/// 分析整个程序，出错时仍返回分析结果
+///
+/// 供语言服务器使用：即使存在语义错误，也能提供悬停类型与跳转定义。
+pub fn analyze_for_ide(program: &mut Program) -> (AnalysisResult, Vec<SemanticError>) {
+    analyze_for_ide_with_options(program, &AnalysisOptions::default())
+}
+
+/// 同 [`analyze_for_ide`]，按选项查找 import 模块并运行 lint
+pub fn analyze_for_ide_with_options(
+    program: &mut Program,
+    options: &AnalysisOptions,
+) -> (AnalysisResult, Vec<SemanticError>) {
     let mut all_errors: Vec<SemanticError> = Vec::new();
 
     // Pass 1: 名称解析
     let mut resolver = Resolver::new();
+    resolver.set_search_paths(options.search_paths.clone());
+    let shared_cache = options.module_cache.as_ref();
+    if let Some(mut cache) = shared_cache.and_then(|c| c.lock().ok()) {
+        resolver.set_module_cache(std::mem::take(&mut *cache));
+    }
     if let Err(errors) = resolver.resolve(program) {
         all_errors.extend(errors);
     }
 
     // 即使有错误也继续，收集尽可能多的错误信息
+    let module_origins = resolver.module_origins().clone();
+    let module_deps = resolver.module_deps().clone();
+    let source_decls = resolver.source_decl_count();
+    let module_sources = resolver.module_sources().clone();
+    let module_cache = resolver.take_module_cache();
     let mut scopes = resolver.into_scopes();
 
     // Pass 2: 类型检查
-    let mut type_checker = TypeChecker::new(&mut scopes);
+    let mut type_checker = TypeChecker::new(&mut scopes)
+        .with_type_table(source_decls)
+        .with_module_sources(module_sources.clone());
     if let Err(errors) = type_checker.check(program) {
         all_errors.extend(errors);
     }
+    let types = type_checker.take_types();
 
     // Pass 3: 空安全检查
-    let mut null_checker = NullSafetyChecker::new(&mut scopes);
+    let mut null_checker =
+        NullSafetyChecker::new(&mut scopes).with_module_sources(module_sources.clone());
     if let Err(errors) = null_checker.check(program) {
         all_errors.extend(errors);
     }
 
+    // Pass 4: lint（只在程序合法时检查，避免错误引出的误报）
+    let mut warnings = Vec::new();
     if all_errors.is_empty() {
-        Ok(AnalysisResult { scopes })
-    } else {
-        Err(all_errors)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use lency_syntax::ast::*;
-
-    /// 创建一个简单的测试程序
-    fn make_simple_program() -> Program {
-        // int main() { var x = 10; return x; }
-        Program {
-            decls: vec![Decl::Function {
-                span: 0..50,
-                name: "main".to_string(),
-                generic_params: vec![],
-                params: vec![],
-                return_type: Type::Int,
-                body: vec![
-                    Stmt::VarDecl {
-                        span: 10..20,
-                        name: "x".to_string(),
-                        ty: Some(Type::Int),
-                        value: Expr {
-                            kind: ExprKind::Literal(Literal::Int(10)),
-                            span: 15..17,
-                        },
-                    },
-                    Stmt::Return {
-                        span: 20..30,
-                        value: Some(Expr {
-                            kind: ExprKind::Variable("x".to_string()),
-                            span: 27..28,
-                        }),
-                    },
-                ],
-            }],
-        }
-    }
-
-    #[test]
-    fn test_analyze_simple_program() {
-        let mut program = make_simple_program();
-        let result = analyze(&mut program);
-        assert!(
-            result.is_ok(),
-            "Analysis should succeed: {:?}",
-            result.err()
-        );
-    }
-
-    #[test]
-    fn test_undefined_variable_error() {
-        // int main() { return y; }  -- y 未定义
-        let mut program = Program {
-            decls: vec![Decl::Function {
-                span: 0..30,
-                name: "main".to_string(),
-                generic_params: vec![],
-                params: vec![],
-                return_type: Type::Int,
-                body: vec![Stmt::Return {
-                    span: 10..20,
-                    value: Some(Expr {
-                        kind: ExprKind::Variable("y".to_string()),
-                        span: 17..18,
-                    }),
-                }],
-            }],
+        let input = lint::LintInput {
+            source_decls,
+            types: &types,
+            module_origins: &module_origins,
+            module_deps: &module_deps,
         };
-
-        let result = analyze(&mut program);
-        assert!(result.is_err());
-
-        let errors = result.unwrap_err();
-        assert!(errors
-            .iter()
-            .any(|e| matches!(e, SemanticError::UndefinedVariable { name, .. } if name == "y")));
+        let (lint_warnings, denied) = lint::check(program, &input, &options.lints);
+        warnings = lint_warnings;
+        all_errors.extend(denied);
     }
 
-    #[test]
-    fn test_type_mismatch_error() {
-        // int main() { var x: int = "hello"; return x; }
-        let mut program = Program {
-            decls: vec![Decl::Function {
-                span: 0..50,
-                name: "main".to_string(),
-                generic_params: vec![],
-                params: vec![],
-                return_type: Type::Int,
-                body: vec![
-                    Stmt::VarDecl {
-                        span: 10..30,
-                        name: "x".to_string(),
-                        ty: Some(Type::Int),
-                        value: Expr {
-                            kind: ExprKind::Literal(Literal::String("hello".to_string())),
-                            span: 20..27,
-                        },
-                    },
-                    Stmt::Return {
-                        span: 30..40,
-                        value: Some(Expr {
-                            kind: ExprKind::Variable("x".to_string()),
-                            span: 37..38,
-                        }),
-                    },
-                ],
-            }],
-        };
-
-        let result = analyze(&mut program);
-        assert!(result.is_err());
-
-        let errors = result.unwrap_err();
-        assert!(errors
-            .iter()
-            .any(|e| matches!(e, SemanticError::TypeMismatch { .. })));
+    // 保存重新分析的模块，放回共享缓存
+    if let (Some(mut cache), Some(shared)) = (module_cache, shared_cache) {
+        cache.finish(program, &module_sources, all_errors.is_empty());
+        if let Ok(mut shared) = shared.lock() {
+            *shared = cache;
+        }
     }
 
-    #[test]
-    fn test_null_safety_error() {
-        // void test() { var s: string = null; }  -- null 赋给非空类型
-        let mut program = Program {
-            decls: vec![Decl::Function {
-                span: 0..50,
-                name: "test".to_string(),
-                generic_params: vec![],
-                params: vec![],
-                return_type: Type::Void,
-                body: vec![Stmt::VarDecl {
-                    span: 10..40,
-                    name: "s".to_string(),
-                    ty: Some(Type::String),
-                    value: Expr {
-                        kind: ExprKind::Literal(Literal::Null),
-                        span: 30..34,
-                    },
-                }],
-            }],
-        };
-
-        let result = analyze(&mut program);
-        assert!(result.is_err());
-
-        let errors = result.unwrap_err();
-        assert!(errors
-            .iter()
-            .any(|e| matches!(e, SemanticError::NullAssignmentToNonNullable { .. })));
-    }
+    let result = AnalysisResult {
+        scopes,
+        types,
+        module_origins,
+        module_sources,
+        warnings,
+    };
+    (result, all_errors)
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,436 @@
+//! 函数体 lint：unused-variable、shadowing、unreachable-code、unnecessary-elvis
+//!
+//! 自行维护局部变量作用域：读取变量时标记离它最近的同名声明；
+//! 作用域结束时报告从未读取的声明。单纯被赋值不算读取。
+
+use super::{Lint, LintWarning};
+use crate::type_table::TypeTable;
+use lency_syntax::ast::{
+    BinaryOp, Decl, Expr, ExprKind, Literal, MatchPattern, Param, Span, Stmt, Type,
+};
+
+/// 局部变量声明
+struct Binding {
+    name: String,
+    span: Span,
+    /// "variable" 或 "parameter"
+    kind: &'static str,
+    used: bool,
+    /// 未使用时是否报告（`_` 开头的名称、模式变量不报告）
+    tracked: bool,
+}
+
+struct BodyLinter<'a> {
+    types: &'a TypeTable,
+    scopes: Vec<Vec<Binding>>,
+    findings: Vec<LintWarning>,
+}
+
+/// 检查一组顶层声明的函数体
+pub(super) fn check_decls(decls: &[Decl], types: &TypeTable) -> Vec<LintWarning> {
+    let mut linter = BodyLinter {
+        types,
+        scopes: Vec::new(),
+        findings: Vec::new(),
+    };
+    for decl in decls {
+        linter.check_decl(decl);
+    }
+    linter.findings
+}
+
+impl BodyLinter<'_> {
+    fn check_decl(&mut self, decl: &Decl) {
+        match decl {
+            Decl::Function {
+                params, body, span, ..
+            } => self.check_function(params, body, span, true),
+            // 方法签名受 trait / 调用约定约束，参数不报告未使用
+            Decl::Impl { methods, .. } => {
+                for method in methods {
+                    if let Decl::Function {
+                        params, body, span, ..
+                    } = method
+                    {
+                        self.check_function(params, body, span, false);
+                    }
+                }
+            }
+            Decl::Var { value, .. } => self.check_expr(value),
+            _ => {}
+        }
+    }
+
+    fn check_function(&mut self, params: &[Param], body: &[Stmt], span: &Span, track: bool) {
+        self.scopes.push(Vec::new());
+        for param in params {
+            self.bind(&param.name, span, "parameter", track);
+        }
+        self.check_stmts(body);
+        self.exit_scope();
+    }
+
+    // --- 作用域 ---
+
+    /// 声明局部变量；遮蔽外层作用域的同名变量时报告 shadowing
+    fn declare(&mut self, name: &str, span: &Span, kind: &'static str) {
+        let shadows = self
+            .scopes
+            .iter()
+            .rev()
+            .skip(1)
+            .flatten()
+            .any(|b| b.name == name);
+        if shadows && !name.starts_with('_') {
+            self.findings.push(
+                LintWarning::new(
+                    Lint::Shadowing,
+                    format!("`{}` shadows a variable from an outer scope", name),
+                    span.clone(),
+                )
+                .suggest("rename one of the variables"),
+            );
+        }
+        self.bind(name, span, kind, true);
+    }
+
+    fn bind(&mut self, name: &str, span: &Span, kind: &'static str, track: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(Binding {
+                name: name.to_string(),
+                span: span.clone(),
+                kind,
+                used: false,
+                tracked: track && !name.starts_with('_'),
+            });
+        }
+    }
+
+    /// 读取变量：标记最近的同名声明（找不到时是全局名称）
+    fn use_variable(&mut self, name: &str) {
+        let binding = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|b| b.name == name);
+        if let Some(binding) = binding {
+            binding.used = true;
+        }
+    }
+
+    fn exit_scope(&mut self) {
+        let Some(scope) = self.scopes.pop() else {
+            return;
+        };
+        for binding in scope.into_iter().filter(|b| b.tracked && !b.used) {
+            self.findings.push(
+                LintWarning::new(
+                    Lint::UnusedVariable,
+                    format!("unused {} `{}`", binding.kind, binding.name),
+                    binding.span,
+                )
+                .suggest(format!(
+                    "if this is intentional, prefix it with an underscore: `_{}`",
+                    binding.name
+                )),
+            );
+        }
+    }
+
+    fn with_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(Vec::new());
+        f(self);
+        self.exit_scope();
+    }
+
+    // --- 语句 ---
+
+    /// 检查语句序列，报告第一条不可达的语句
+    fn check_stmts(&mut self, stmts: &[Stmt]) {
+        let mut terminated = false;
+        let mut reported = false;
+        for stmt in stmts {
+            if terminated && !reported {
+                if let Some(span) = stmt_span(stmt) {
+                    self.findings.push(LintWarning::new(
+                        Lint::UnreachableCode,
+                        "unreachable statement",
+                        span,
+                    ));
+                    reported = true;
+                }
+            }
+            self.check_stmt(stmt);
+            terminated |= terminates(stmt);
+        }
+    }
+
+    fn check_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VarDecl {
+                name, value, span, ..
+            } => {
+                self.check_expr(value);
+                self.declare(name, span, "variable");
+            }
+            Stmt::VarPattern {
+                pattern,
+                value,
+                span,
+            } => {
+                self.check_expr(value);
+                self.bind_pattern(pattern, span);
+            }
+            Stmt::Assignment { target, value, .. } => {
+                // 直接给变量赋值不算读取
+                if !matches!(target.kind, ExprKind::Variable(_)) {
+                    self.check_expr(target);
+                }
+                self.check_expr(value);
+            }
+            Stmt::Expression(expr) => self.check_expr(expr),
+            Stmt::Block(stmts) => self.with_scope(|l| l.check_stmts(stmts)),
+            Stmt::If {
+                condition,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.check_expr(condition);
+                self.with_scope(|l| l.check_stmts(then_block));
+                if let Some(else_block) = else_block {
+                    self.with_scope(|l| l.check_stmts(else_block));
+                }
+            }
+            Stmt::IfLet {
+                pattern,
+                value,
+                then_block,
+                else_block,
+                span,
+            } => {
+                self.check_expr(value);
+                self.with_scope(|l| {
+                    l.bind_pattern(pattern, span);
+                    l.check_stmts(then_block);
+                });
+                if let Some(else_block) = else_block {
+                    self.with_scope(|l| l.check_stmts(else_block));
+                }
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                self.check_expr(condition);
+                self.with_scope(|l| l.check_stmts(body));
+            }
+            Stmt::WhileLet {
+                pattern,
+                value,
+                body,
+                span,
+            } => {
+                self.check_expr(value);
+                self.with_scope(|l| {
+                    l.bind_pattern(pattern, span);
+                    l.check_stmts(body);
+                });
+            }
+            Stmt::For {
+                init,
+                condition,
+                update,
+                body,
+                ..
+            } => self.with_scope(|l| {
+                if let Some(init) = init {
+                    l.check_stmt(init);
+                }
+                if let Some(condition) = condition {
+                    l.check_expr(condition);
+                }
+                if let Some(update) = update {
+                    l.check_stmt(update);
+                }
+                l.check_stmts(body);
+            }),
+            Stmt::ForIn {
+                iterator,
+                iterable,
+                body,
+                span,
+            } => {
+                self.check_expr(iterable);
+                self.with_scope(|l| {
+                    l.declare(iterator, span, "variable");
+                    l.check_stmts(body);
+                });
+            }
+            Stmt::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.check_expr(value);
+                }
+            }
+            Stmt::Break { .. } | Stmt::Continue { .. } => {}
+        }
+    }
+
+    // --- 表达式 ---
+
+    fn check_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Variable(name) => self.use_variable(name),
+            ExprKind::Binary(left, op, right) => {
+                if *op == BinaryOp::Elvis {
+                    self.check_elvis(expr, left);
+                }
+                self.check_expr(left);
+                self.check_expr(right);
+            }
+            ExprKind::Closure { params, body, .. } => self.with_scope(|l| {
+                for param in params {
+                    l.declare(&param.name, &expr.span, "parameter");
+                }
+                l.check_expr(body);
+            }),
+            ExprKind::Match {
+                value,
+                cases,
+                default,
+            } => {
+                self.check_expr(value);
+                for case in cases {
+                    self.with_scope(|l| {
+                        l.bind_pattern(&case.pattern, &case.span);
+                        if let Some(guard) = &case.guard {
+                            l.check_expr(guard);
+                        }
+                        l.check_expr(&case.body);
+                    });
+                }
+                if let Some(default) = default {
+                    self.check_expr(default);
+                }
+            }
+            ExprKind::Call { callee, args } => {
+                self.check_expr(callee);
+                args.iter().for_each(|arg| self.check_expr(arg));
+            }
+            ExprKind::Array(items) | ExprKind::VecLiteral(items) => {
+                items.iter().for_each(|item| self.check_expr(item));
+            }
+            ExprKind::StructLiteral { fields, .. } => {
+                fields.iter().for_each(|(_, value)| self.check_expr(value));
+            }
+            ExprKind::Index { array, index } => {
+                self.check_expr(array);
+                self.check_expr(index);
+            }
+            ExprKind::Range { start, end, .. } => {
+                self.check_expr(start);
+                self.check_expr(end);
+            }
+            ExprKind::Get { object, .. } | ExprKind::SafeGet { object, .. } => {
+                self.check_expr(object)
+            }
+            ExprKind::GenericInstantiation { base, .. } => self.check_expr(base),
+            ExprKind::CoerceDyn { value, .. } | ExprKind::Cast { value, .. } => {
+                self.check_expr(value)
+            }
+            ExprKind::Unary(_, inner)
+            | ExprKind::Print(inner)
+            | ExprKind::Try(inner)
+            | ExprKind::Ok(inner)
+            | ExprKind::Err(inner)
+            | ExprKind::ReadFile(inner)
+            | ExprKind::Len(inner)
+            | ExprKind::Trim(inner)
+            | ExprKind::CharToString(inner)
+            | ExprKind::Panic(inner) => self.check_expr(inner),
+            ExprKind::WriteFile(a, b)
+            | ExprKind::Split(a, b)
+            | ExprKind::Join(a, b)
+            | ExprKind::Format(a, b) => {
+                self.check_expr(a);
+                self.check_expr(b);
+            }
+            ExprKind::Substr(a, b, c) => {
+                self.check_expr(a);
+                self.check_expr(b);
+                self.check_expr(c);
+            }
+            ExprKind::Literal(_) | ExprKind::Unit | ExprKind::Error => {}
+        }
+    }
+
+    /// 模式变量遮蔽外层同名变量，但不报告未使用（没有单独的位置可指）
+    fn bind_pattern(&mut self, pattern: &MatchPattern, span: &Span) {
+        for name in pattern.bindings() {
+            self.bind(name, span, "variable", false);
+        }
+    }
+
+    /// `a ?? b` 中 `a` 的类型不可空时 `??` 没有意义
+    fn check_elvis(&mut self, expr: &Expr, left: &Expr) {
+        if matches!(left.kind, ExprKind::Literal(Literal::Null)) {
+            return;
+        }
+        let Some(ty) = self.types.type_of(&left.span) else {
+            return;
+        };
+        if matches!(ty, Type::Nullable(_) | Type::GenericParam(_) | Type::Error) {
+            return;
+        }
+        self.findings.push(
+            LintWarning::new(
+                Lint::UnnecessaryElvis,
+                format!(
+                    "unnecessary `??`: the left operand has non-nullable type '{}'",
+                    ty
+                ),
+                expr.span.clone(),
+            )
+            .suggest("remove the `??` and its right-hand side"),
+        );
+    }
+}
+
+/// 执行完该语句后控制流不会继续到下一条语句
+fn terminates(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Return { .. } | Stmt::Break { .. } | Stmt::Continue { .. } => true,
+        Stmt::Expression(expr) => matches!(expr.kind, ExprKind::Panic(_)),
+        Stmt::Block(stmts) => stmts.iter().any(terminates),
+        Stmt::If {
+            then_block,
+            else_block: Some(else_block),
+            ..
+        }
+        | Stmt::IfLet {
+            then_block,
+            else_block: Some(else_block),
+            ..
+        } => then_block.iter().any(terminates) && else_block.iter().any(terminates),
+        _ => false,
+    }
+}
+
+fn stmt_span(stmt: &Stmt) -> Option<Span> {
+    match stmt {
+        Stmt::VarDecl { span, .. }
+        | Stmt::VarPattern { span, .. }
+        | Stmt::Assignment { span, .. }
+        | Stmt::If { span, .. }
+        | Stmt::IfLet { span, .. }
+        | Stmt::While { span, .. }
+        | Stmt::WhileLet { span, .. }
+        | Stmt::For { span, .. }
+        | Stmt::ForIn { span, .. }
+        | Stmt::Return { span, .. }
+        | Stmt::Break { span }
+        | Stmt::Continue { span } => Some(span.clone()),
+        Stmt::Expression(expr) => Some(expr.span.clone()),
+        Stmt::Block(stmts) => stmts.first().and_then(stmt_span),
+    }
+}
@@ -0,0 +1,371 @@
+//! unused-import
+//!
+//! glob 导入的名称直接进入全局作用域（其余导入经 Resolver 改写为带模块前缀的名称），
+//! 因此收集入口源文件引用的全部名称，借助 `module_origins` 找到用到的模块。
+//!
+//! import 会传递地引入被导入模块自己 import 的名称，所以每个可到达的模块都归属于
+//! 一个 import（见 [`owner`]）。一个 import 只有在它直接导入的模块没有用到、
+//! 归属于它的模块也都没有用到时才报告；为模块外类型（如 `int`）提供 impl 的模块
+//! （如 `std.core`）是否用到无法从名称引用判断，总是视为用到。
+
+use super::{Lint, LintInput, LintWarning};
+use crate::resolver::namespace::split_qualified;
+use lency_syntax::ast::{Decl, Expr, ExprKind, ImportItem, MatchPattern, Span, Stmt, Type};
+use std::collections::HashSet;
+
+pub(super) fn check_imports(decls: &[Decl], input: &LintInput) -> Vec<LintWarning> {
+    let mut names = NameCollector::default();
+    decls.iter().for_each(|decl| names.decl(decl));
+    for typed in input.types.iter() {
+        if let Some(owner) = typed.definition.as_ref().and_then(|d| d.owner.as_ref()) {
+            names.insert(owner);
+        }
+        names.ty(&typed.ty);
+    }
+
+    let used_modules: HashSet<&str> = names
+        .names
+        .iter()
+        .filter_map(|name| input.module_origins.get(name))
+        .map(String::as_str)
+        .collect();
+
+    let imports: Vec<_> = decls
+        .iter()
+        .filter_map(|decl| match decl {
+            Decl::Import { items, span } => Some(items.iter().map(move |item| (item, span))),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let reachable: Vec<HashSet<String>> = imports
+        .iter()
+        .map(|(item, _)| reachable_modules(&item.path.join("."), input))
+        .collect();
+
+    let mut findings = Vec::new();
+    for (i, (item, span)) in imports.iter().enumerate() {
+        let module = item.path.join(".");
+        let binding_used = item
+            .binding()
+            .is_some_and(|binding| names.names.contains(binding));
+        if binding_used || used_modules.contains(module.as_str()) {
+            continue;
+        }
+        // 模块未加载成功时无从判断
+        if !input.module_deps.imports.contains_key(&module) {
+            continue;
+        }
+        // 其他 import 负责的模块不受删除影响
+        let needed = reachable[i].iter().any(|m| {
+            owner(m, &imports, &reachable) == i
+                && (used_modules.contains(m.as_str()) || input.module_deps.extensions.contains(m))
+        });
+        if !needed {
+            findings.push(
+                LintWarning::new(
+                    Lint::UnusedImport,
+                    format!("unused import `{}`", module),
+                    (*span).clone(),
+                )
+                .suggest("remove the import"),
+            );
+        }
+    }
+    findings
+}
+
+/// 负责引入模块 `module` 的 import：直接导入它的 import 优先，否则为第一个能到达它的 import。
+/// 这样同时删除所有被报告的 import 也不会丢失用到的模块。
+fn owner(module: &str, imports: &[(&ImportItem, &Span)], reachable: &[HashSet<String>]) -> usize {
+    imports
+        .iter()
+        .position(|(item, _)| item.path.join(".") == module)
+        .or_else(|| reachable.iter().position(|r| r.contains(module)))
+        .unwrap_or(usize::MAX)
+}
+
+/// 经 `module` 可见的全部模块（import 会传递地引入名称）
+fn reachable_modules(module: &str, input: &LintInput) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    let mut pending = vec![module.to_string()];
+    while let Some(module) = pending.pop() {
+        if let Some(deps) = input.module_deps.imports.get(&module) {
+            pending.extend(deps.iter().filter(|d| !seen.contains(*d)).cloned());
+        }
+        seen.insert(module);
+    }
+    seen
+}
+
+/// 收集声明中引用到的名称（变量、函数、类型、trait）
+#[derive(Default)]
+struct NameCollector {
+    names: HashSet<String>,
+}
+
+impl NameCollector {
+    fn insert(&mut self, name: &str) {
+        // 未改写的限定名 `io.File`：别名 `io` 被用到
+        if let Some((alias, _)) = split_qualified(name) {
+            self.names.insert(alias.to_string());
+        }
+        self.names.insert(name.to_string());
+    }
+
+    fn decl(&mut self, decl: &Decl) {
+        match decl {
+            Decl::Function {
+                params,
+                return_type,
+                body,
+                generic_params,
+                ..
+            } => {
+                params.iter().for_each(|p| self.ty(&p.ty));
+                self.ty(return_type);
+                generic_params
+                    .iter()
+                    .flat_map(|gp| &gp.bounds)
+                    .for_each(|bound| self.ty(bound));
+                body.iter().for_each(|stmt| self.stmt(stmt));
+            }
+            Decl::ExternFunction {
+                params,
+                return_type,
+                ..
+            } => {
+                params.iter().for_each(|p| self.ty(&p.ty));
+                self.ty(return_type);
+            }
+            Decl::Struct { fields, .. } => fields.iter().for_each(|f| self.ty(&f.ty)),
+            Decl::Impl {
+                trait_ref,
+                type_name,
+                methods,
+                ..
+            } => {
+                if let Some(trait_ref) = trait_ref {
+                    self.ty(trait_ref);
+                }
+                self.ty(type_name);
+                methods.iter().for_each(|m| self.decl(m));
+            }
+            Decl::Trait { methods, .. } => {
+                for method in methods {
+                    method.params.iter().for_each(|p| self.ty(&p.ty));
+                    self.ty(&method.return_type);
+                }
+            }
+            Decl::Enum { variants, .. } => {
+                for variant in variants {
+                    if let lency_syntax::ast::EnumVariant::Tuple(_, types) = variant {
+                        types.iter().for_each(|ty| self.ty(ty));
+                    }
+                }
+            }
+            Decl::Var { ty, value, .. } => {
+                if let Some(ty) = ty {
+                    self.ty(ty);
+                }
+                self.expr(value);
+            }
+            Decl::Import { .. } => {}
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VarDecl { ty, value, .. } => {
+                if let Some(ty) = ty {
+                    self.ty(ty);
+                }
+                self.expr(value);
+            }
+            Stmt::VarPattern { pattern, value, .. } => {
+                self.pattern(pattern);
+                self.expr(value);
+            }
+            Stmt::Assignment { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            }
+            Stmt::Expression(expr) => self.expr(expr),
+            Stmt::Block(stmts) => stmts.iter().for_each(|s| self.stmt(s)),
+            Stmt::If {
+                condition,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.expr(condition);
+                then_block.iter().for_each(|s| self.stmt(s));
+                else_block.iter().flatten().for_each(|s| self.stmt(s));
+            }
+            Stmt::IfLet {
+                pattern,
+                value,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.pattern(pattern);
+                self.expr(value);
+                then_block.iter().for_each(|s| self.stmt(s));
+                else_block.iter().flatten().for_each(|s| self.stmt(s));
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                self.expr(condition);
+                body.iter().for_each(|s| self.stmt(s));
+            }
+            Stmt::WhileLet {
+                pattern,
+                value,
+                body,
+                ..
+            } => {
+                self.pattern(pattern);
+                self.expr(value);
+                body.iter().for_each(|s| self.stmt(s));
+            }
+            Stmt::For {
+                init,
+                condition,
+                update,
+                body,
+                ..
+            } => {
+                init.iter().for_each(|s| self.stmt(s));
+                condition.iter().for_each(|e| self.expr(e));
+                update.iter().for_each(|s| self.stmt(s));
+                body.iter().for_each(|s| self.stmt(s));
+            }
+            Stmt::ForIn { iterable, body, .. } => {
+                self.expr(iterable);
+                body.iter().for_each(|s| self.stmt(s));
+            }
+            Stmt::Return { value, .. } => value.iter().for_each(|e| self.expr(e)),
+            Stmt::Break { .. } | Stmt::Continue { .. } => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Variable(name) => self.insert(name),
+            ExprKind::StructLiteral { type_, fields } => {
+                self.ty(type_);
+                fields.iter().for_each(|(_, value)| self.expr(value));
+            }
+            ExprKind::GenericInstantiation { base, args } => {
+                self.expr(base);
+                args.iter().for_each(|ty| self.ty(ty));
+            }
+            ExprKind::Closure { params, body, .. } => {
+                params.iter().for_each(|p| self.ty(&p.ty));
+                self.expr(body);
+            }
+            ExprKind::CoerceDyn { value, trait_name } => {
+                self.insert(trait_name);
+                self.expr(value);
+            }
+            ExprKind::Match {
+                value,
+                cases,
+                default,
+            } => {
+                self.expr(value);
+                for case in cases {
+                    self.pattern(&case.pattern);
+                    case.guard.iter().for_each(|guard| self.expr(guard));
+                    self.expr(&case.body);
+                }
+                default.iter().for_each(|e| self.expr(e));
+            }
+            ExprKind::Binary(a, _, b)
+            | ExprKind::Index { array: a, index: b }
+            | ExprKind::Range {
+                start: a, end: b, ..
+            }
+            | ExprKind::WriteFile(a, b)
+            | ExprKind::Split(a, b)
+            | ExprKind::Join(a, b)
+            | ExprKind::Format(a, b) => {
+                self.expr(a);
+                self.expr(b);
+            }
+            ExprKind::Substr(a, b, c) => {
+                self.expr(a);
+                self.expr(b);
+                self.expr(c);
+            }
+            ExprKind::Call { callee, args } => {
+                self.expr(callee);
+                args.iter().for_each(|arg| self.expr(arg));
+            }
+            ExprKind::Array(items) | ExprKind::VecLiteral(items) => {
+                items.iter().for_each(|item| self.expr(item));
+            }
+            ExprKind::Get { object, .. } | ExprKind::SafeGet { object, .. } => self.expr(object),
+            ExprKind::Unary(_, inner)
+            | ExprKind::Print(inner)
+            | ExprKind::Try(inner)
+            | ExprKind::Ok(inner)
+            | ExprKind::Err(inner)
+            | ExprKind::ReadFile(inner)
+            | ExprKind::Len(inner)
+            | ExprKind::Trim(inner)
+            | ExprKind::CharToString(inner)
+            | ExprKind::Panic(inner) => self.expr(inner),
+            ExprKind::Cast { value, target } => {
+                self.expr(value);
+                self.ty(target);
+            }
+            ExprKind::Literal(_) | ExprKind::Unit | ExprKind::Error => {}
+        }
+    }
+
+    fn pattern(&mut self, pattern: &MatchPattern) {
+        pattern.walk(&mut |pattern| {
+            if let MatchPattern::Variant { name, .. } | MatchPattern::Struct { name, .. } = pattern
+            {
+                self.insert(name);
+            }
+        });
+    }
+
+    fn ty(&mut self, ty: &Type) {
+        match ty {
+            Type::Struct(name) | Type::TraitObject(name) | Type::GenericParam(name) => {
+                self.insert(name)
+            }
+            Type::Generic(name, args) => {
+                self.insert(name);
+                args.iter().for_each(|arg| self.ty(arg));
+            }
+            Type::Nullable(inner) | Type::Vec(inner) => self.ty(inner),
+            Type::Array { element_type, .. } => self.ty(element_type),
+            Type::Result { ok_type, err_type } => {
+                self.ty(ok_type);
+                self.ty(err_type);
+            }
+            Type::Function {
+                param_types,
+                return_type,
+            } => {
+                param_types.iter().for_each(|p| self.ty(p));
+                self.ty(return_type);
+            }
+            Type::Int
+            | Type::FixedInt(_)
+            | Type::Char
+            | Type::Float
+            | Type::Bool
+            | Type::String
+            | Type::Void
+            | Type::Range
+            | Type::Error => {}
+        }
+    }
+}
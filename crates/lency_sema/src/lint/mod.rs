@@ -0,0 +1,205 @@
+//! Lints
+//!
+//! 语义检查通过后运行的警告检查，不影响程序是否合法：
+//! - `unused-variable`：声明后从未读取的局部变量与参数（`_` 开头的名称除外）
+//! - `unused-import`：没有用到模块中任何名称的 import
+//! - `unreachable-code`：return / break / continue / panic 之后的语句
+//! - `unnecessary-elvis`：左侧不可能为 null 的 `??`
+//! - `shadowing`：遮蔽外层局部变量的声明
+//!
+//! 每个 lint 可设为 allow / warn / deny，deny 的 lint 作为语义错误报告。
+//! 只检查入口源文件中的声明。
+
+mod body;
+mod imports;
+
+use crate::error::SemanticError;
+use crate::resolver::ModuleDeps;
+use crate::type_table::TypeTable;
+use lency_diagnostics::Diagnostic;
+use lency_syntax::ast::{Program, Span};
+use std::collections::{BTreeMap, HashMap};
+
+/// Lint 种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Lint {
+    UnusedVariable,
+    UnusedImport,
+    UnreachableCode,
+    UnnecessaryElvis,
+    Shadowing,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 5] = [
+        Lint::UnusedVariable,
+        Lint::UnusedImport,
+        Lint::UnreachableCode,
+        Lint::UnnecessaryElvis,
+        Lint::Shadowing,
+    ];
+
+    /// 命令行中使用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedImport => "unused-import",
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::UnnecessaryElvis => "unnecessary-elvis",
+            Lint::Shadowing => "shadowing",
+        }
+    }
+
+    /// 按名称查找，`_` 与 `-` 等价（`unused_variable` 也可以）
+    pub fn from_name(name: &str) -> Option<Lint> {
+        let name = name.replace('_', "-");
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Lint 级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintLevel {
+    /// 不检查
+    Allow,
+    /// 报告警告（默认）
+    Warn,
+    /// 作为错误报告，编译失败
+    Deny,
+}
+
+/// 各 lint 的级别；未设置的 lint 为 warn
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LintConfig {
+    levels: BTreeMap<Lint, LintLevel>,
+}
+
+/// 代表全部 lint 的名称（`-D warnings`）
+pub const ALL_LINTS: &str = "warnings";
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        self.levels.get(&lint).copied().unwrap_or(LintLevel::Warn)
+    }
+
+    pub fn set(&mut self, lint: Lint, level: LintLevel) {
+        self.levels.insert(lint, level);
+    }
+
+    /// 按名称设置级别，[`ALL_LINTS`] 设置全部 lint；名称未知时返回 false
+    pub fn set_by_name(&mut self, name: &str, level: LintLevel) -> bool {
+        if name == ALL_LINTS {
+            for lint in Lint::ALL {
+                self.set(lint, level);
+            }
+            return true;
+        }
+        match Lint::from_name(name) {
+            Some(lint) => {
+                self.set(lint, level);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn with(mut self, lint: Lint, level: LintLevel) -> Self {
+        self.set(lint, level);
+        self
+    }
+}
+
+/// 一条 lint 结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintWarning {
+    pub lint: Lint,
+    pub message: String,
+    pub span: Span,
+    /// 修复建议
+    pub suggestion: Option<String>,
+}
+
+impl LintWarning {
+    fn new(lint: Lint, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            lint,
+            message: message.into(),
+            span,
+            suggestion: None,
+        }
+    }
+
+    fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// 转换为警告级别的诊断
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        self.with_details(Diagnostic::warning(&self.message))
+            .with_note(format!(
+                "lint `{}` warns by default; use `-A {}` to allow it",
+                self.lint, self.lint
+            ))
+    }
+
+    /// 转换为错误级别的诊断（lint 被设为 deny）
+    pub fn to_error_diagnostic(&self) -> Diagnostic {
+        self.with_details(Diagnostic::error(&self.message))
+            .with_note(format!("lint `{}` is set to deny", self.lint))
+    }
+
+    fn with_details(&self, diag: Diagnostic) -> Diagnostic {
+        let diag = diag.with_code(self.lint.name()).span(self.span.clone());
+        match &self.suggestion {
+            Some(suggestion) => diag.suggest(suggestion),
+            None => diag,
+        }
+    }
+}
+
+/// 运行 lint 需要的语义分析结果
+pub struct LintInput<'a> {
+    /// 入口源文件的声明数量
+    pub source_decls: usize,
+    pub types: &'a TypeTable,
+    pub module_origins: &'a HashMap<String, String>,
+    pub module_deps: &'a ModuleDeps,
+}
+
+/// 检查入口源文件，按 `config` 返回 (警告, deny 的 lint 对应的错误)
+pub fn check(
+    program: &Program,
+    input: &LintInput,
+    config: &LintConfig,
+) -> (Vec<LintWarning>, Vec<SemanticError>) {
+    let decls = &program.decls[..input.source_decls.min(program.decls.len())];
+
+    let mut findings = body::check_decls(decls, input.types);
+    findings.extend(imports::check_imports(decls, input));
+    findings.sort_by_key(|w| w.span.start);
+
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    for finding in findings {
+        match config.level(finding.lint) {
+            LintLevel::Allow => {}
+            LintLevel::Warn => warnings.push(finding),
+            LintLevel::Deny => errors.push(SemanticError::DeniedLint(finding)),
+        }
+    }
+    (warnings, errors)
+}
+
+#[cfg(test)]
+mod tests;
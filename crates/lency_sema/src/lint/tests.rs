@@ -0,0 +1,268 @@
+//! Tests for lint module
+
+use super::*;
+use crate::{analyze_with_options, AnalysisOptions};
+use std::path::PathBuf;
+
+fn lint_with(source: &str, options: &AnalysisOptions) -> Result<Vec<LintWarning>, Vec<String>> {
+    let mut program = lency_syntax::parser::parse_spanned(source).expect("parse failed");
+    analyze_with_options(&mut program, options)
+        .map(|result| result.warnings)
+        .map_err(|errors| errors.iter().map(|e| e.to_string()).collect())
+}
+
+/// 返回 (lint 名称, 消息)
+fn lint(source: &str) -> Vec<(&'static str, String)> {
+    lint_with(source, &AnalysisOptions::default())
+        .expect("analysis failed")
+        .into_iter()
+        .map(|w| (w.lint.name(), w.message))
+        .collect()
+}
+
+#[test]
+fn test_unused_variable_and_parameter() {
+    let warnings = lint(
+        "int add(int a, int b, int _c) {
+            var unused = 1
+            var _ignored = 2
+            return a
+        }
+        int main() { return add(1, 2, 3) }",
+    );
+    assert_eq!(
+        warnings,
+        vec![
+            ("unused-variable", "unused parameter `b`".to_string()),
+            ("unused-variable", "unused variable `unused`".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_assignment_is_not_a_read() {
+    let warnings = lint(
+        "int main() {
+            var written = 0
+            written = 5
+            var read = 0
+            read = read + 1
+            return read
+        }",
+    );
+    assert_eq!(
+        warnings,
+        vec![("unused-variable", "unused variable `written`".to_string())]
+    );
+}
+
+#[test]
+fn test_closure_capture_counts_as_use() {
+    let warnings = lint(
+        "int main() {
+            var base = 10
+            var f = |int x| => x + base
+            return f(1)
+        }",
+    );
+    assert!(warnings.is_empty(), "{:?}", warnings);
+}
+
+#[test]
+fn test_unreachable_after_return() {
+    let warnings = lint(
+        "int main() {
+            if true {
+                return 1
+            } else {
+                return 2
+            }
+            print(\"never\")
+            return 0
+        }",
+    );
+    assert_eq!(
+        warnings,
+        vec![("unreachable-code", "unreachable statement".to_string())]
+    );
+}
+
+#[test]
+fn test_loop_body_after_break_is_unreachable() {
+    let warnings = lint(
+        "int main() {
+            while true {
+                break
+                print(\"never\")
+            }
+            return 0
+        }",
+    );
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].0, "unreachable-code");
+}
+
+#[test]
+fn test_unnecessary_elvis() {
+    let warnings = lint(
+        "int main() {
+            var a = 1
+            var b: int? = null
+            return (a ?? 0) + (b ?? 0)
+        }",
+    );
+    assert_eq!(
+        warnings,
+        vec![(
+            "unnecessary-elvis",
+            "unnecessary `??`: the left operand has non-nullable type 'int'".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_shadowing_outer_local() {
+    let warnings = lint(
+        "int main() {
+            var total = 0
+            for var i = 0; i < 3; i = i + 1 {
+                var total = i
+                print(total)
+            }
+            return total
+        }",
+    );
+    assert_eq!(
+        warnings,
+        vec![(
+            "shadowing",
+            "`total` shadows a variable from an outer scope".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_allow_and_deny_levels() {
+    let source = "int main() {
+        var x = 1
+        return 0
+    }";
+
+    let allow = AnalysisOptions {
+        lints: LintConfig::new().with(Lint::UnusedVariable, LintLevel::Allow),
+        ..AnalysisOptions::default()
+    };
+    assert_eq!(lint_with(source, &allow), Ok(Vec::new()));
+
+    let deny = AnalysisOptions {
+        lints: LintConfig::new().with(Lint::UnusedVariable, LintLevel::Deny),
+        ..AnalysisOptions::default()
+    };
+    assert_eq!(
+        lint_with(source, &deny),
+        Err(vec!["unused variable `x`".to_string()])
+    );
+}
+
+#[test]
+fn test_lint_names() {
+    assert_eq!(Lint::from_name("unused-import"), Some(Lint::UnusedImport));
+    assert_eq!(Lint::from_name("unused_import"), Some(Lint::UnusedImport));
+    assert_eq!(Lint::from_name("unknown"), None);
+
+    let mut config = LintConfig::new();
+    assert!(config.set_by_name(ALL_LINTS, LintLevel::Deny));
+    assert!(Lint::ALL
+        .iter()
+        .all(|&lint| config.level(lint) == LintLevel::Deny));
+    assert!(!config.set_by_name("unknown", LintLevel::Allow));
+}
+
+#[test]
+fn test_denied_lint_renders_as_error() {
+    let warning = LintWarning::new(Lint::Shadowing, "shadowed", 3..4).suggest("rename");
+    let diag = SemanticError::DeniedLint(warning.clone()).to_diagnostic();
+    assert_eq!(diag.level, lency_diagnostics::DiagnosticLevel::Error);
+    assert_eq!(diag.span, Some(3..4));
+
+    let diag = warning.to_diagnostic();
+    assert_eq!(diag.level, lency_diagnostics::DiagnosticLevel::Warning);
+    assert_eq!(diag.suggestions[0].message, "rename");
+}
+
+#[test]
+fn test_unused_import() {
+    let dir = std::env::temp_dir().join(format!("lency_lint_imports_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("util")).expect("create dir");
+    std::fs::write(
+        dir.join("util").join("math.lcy"),
+        "int square(int x) { return x * x }\n",
+    )
+    .expect("write module");
+    std::fs::write(
+        dir.join("util").join("text.lcy"),
+        "string shout(string s) { return s }\n",
+    )
+    .expect("write module");
+    // util.all 自己的名称没有用到，但它传递地引入了 util.math
+    std::fs::write(
+        dir.join("util").join("all.lcy"),
+        "import util.math.*\nint helper() { return 0 }\n",
+    )
+    .expect("write module");
+
+    let options = AnalysisOptions {
+        search_paths: vec![PathBuf::from(&dir)],
+        ..AnalysisOptions::default()
+    };
+    let unused_imports = |source: &str| -> Vec<String> {
+        lint_with(source, &options)
+            .expect("analysis failed")
+            .into_iter()
+            .map(|w| w.message)
+            .collect()
+    };
+
+    assert_eq!(
+        unused_imports(
+            "import util.text.*
+            import util.all.*
+            int main() { return square(3) }"
+        ),
+        vec!["unused import `util.text`"]
+    );
+    // 直接导入了 util.math 时，util.all 可以删除
+    assert_eq!(
+        unused_imports(
+            "import util.math.*
+            import util.all.*
+            int main() { return square(3) }"
+        ),
+        vec!["unused import `util.all`"]
+    );
+
+    let warnings = lint_with(
+        "import util.text as text
+        int main() {
+            print(text.shout(\"hi\"))
+            return 0
+        }",
+        &options,
+    )
+    .expect("analysis failed");
+    assert!(warnings.is_empty(), "{:?}", warnings);
+
+    // 不带别名的导入通过模块名使用
+    let warnings = lint_with(
+        "import util.text
+        int main() {
+            print(text.shout(\"hi\"))
+            return 0
+        }",
+        &options,
+    )
+    .expect("analysis failed");
+    assert!(warnings.is_empty(), "{:?}", warnings);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,68 @@
+//! Module Interfaces
+//!
+//! 声明对其他模块可见的部分。导入方的分析结果只取决于被导入模块的接口，
+//! 修改函数体不会使导入方的缓存失效。
+
+use super::StableHasher;
+use lency_syntax::ast::Decl;
+use std::hash::{Hash, Hasher};
+
+/// 声明对其他模块可见的部分（不含函数体与位置信息）
+pub fn signature(decl: &Decl) -> Option<String> {
+    match decl {
+        Decl::Function {
+            is_pub,
+            name,
+            params,
+            return_type,
+            ..
+        } => Some(format!(
+            "fn {} {} {:?} {:?}",
+            is_pub, name, params, return_type
+        )),
+        Decl::ExternFunction {
+            name,
+            params,
+            return_type,
+            ..
+        } => Some(format!("extern {} {:?} {:?}", name, params, return_type)),
+        Decl::Struct { name, fields, .. } => Some(format!("struct {} {:?}", name, fields)),
+        Decl::Enum { name, variants, .. } => Some(format!("enum {} {:?}", name, variants)),
+        Decl::Trait {
+            name,
+            generic_params,
+            methods,
+            ..
+        } => {
+            let generics: Vec<_> = generic_params
+                .iter()
+                .map(|p| (&p.name, &p.bounds))
+                .collect();
+            Some(format!("trait {} {:?} {:?}", name, generics, methods))
+        }
+        Decl::Impl {
+            trait_ref,
+            type_name,
+            methods,
+            ..
+        } => {
+            let methods: Vec<_> = methods.iter().filter_map(signature).collect();
+            Some(format!(
+                "impl {:?} {:?} {:?}",
+                trait_ref, type_name, methods
+            ))
+        }
+        // 未标注类型的全局变量由初始值推导类型；全局变量的位置信息用于 panic 报告
+        Decl::Var { .. } => Some(format!("{:?}", decl)),
+        Decl::Import { .. } => None,
+    }
+}
+
+/// 一组声明的接口哈希
+pub fn interface_hash(decls: &[Decl]) -> u64 {
+    let mut hasher = StableHasher::new();
+    for decl in decls {
+        signature(decl).hash(&mut hasher);
+    }
+    hasher.finish()
+}
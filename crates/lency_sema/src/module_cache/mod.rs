@@ -0,0 +1,165 @@
+//! Module Cache
+//!
+//! 导入模块的解析与分析缓存：
+//!
+//! - **语法树**：按源码内容哈希复用，内容不变的模块不再做词法与语法分析。
+//! - **分析结果**：模块经过名称解析、类型检查与空安全检查后的声明。分析键由模块源码、
+//!   导入方式与它（传递）导入的全部模块的接口（见 [`interface_hash`]）决定；
+//!   键不变时直接复用分析后的声明，只重新登记顶层符号，不再检查函数体。
+//!
+//! 因此只修改一个模块的函数体时只需重新分析这个模块；修改它的接口时，
+//! 导入它的模块也随之重新分析。缓存保存在内存中，是否写入磁盘由调用方决定。
+
+mod hash;
+mod interface;
+
+pub use hash::{stable_hash, StableHasher};
+pub use interface::{interface_hash, signature};
+
+use crate::module_source::ModuleSources;
+use lency_syntax::ast::{Decl, Program};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// 在多次分析之间共享的模块缓存（见 [`crate::AnalysisOptions::module_cache`]）
+pub type SharedModuleCache = Arc<Mutex<ModuleCache>>;
+
+/// 一个模块的缓存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedModule {
+    /// 模块源文件
+    pub file: PathBuf,
+    /// 源码内容哈希
+    pub content_hash: u64,
+    /// 解析得到的语法树
+    pub program: Program,
+    /// 最近一次没有错误的分析结果
+    pub analysis: Option<ModuleAnalysis>,
+}
+
+/// 模块分析后的声明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleAnalysis {
+    /// 分析键
+    pub key: u64,
+    /// 名称已加模块前缀、类型已推导的声明
+    pub decls: Vec<Decl>,
+}
+
+/// 导入模块的解析与分析缓存
+#[derive(Debug, Clone, Default)]
+pub struct ModuleCache {
+    modules: HashMap<PathBuf, CachedModule>,
+    /// 最近一次分析中重新解析的模块
+    parsed: Vec<PathBuf>,
+    /// 最近一次分析中重新分析的模块及其分析键（同一文件分析了多次时不缓存，键为 None）
+    analyzed: Vec<(PathBuf, Option<u64>)>,
+}
+
+impl ModuleCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 转为可在多次分析之间共享的缓存
+    pub fn shared(self) -> SharedModuleCache {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// 加入一个模块（如从磁盘读取的缓存）
+    pub fn insert(&mut self, module: CachedModule) {
+        self.modules.insert(module.file.clone(), module);
+    }
+
+    pub fn get(&self, file: &Path) -> Option<&CachedModule> {
+        self.modules.get(file)
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = &CachedModule> {
+        self.modules.values()
+    }
+
+    /// 最近一次分析中重新解析的模块
+    pub fn parsed(&self) -> &[PathBuf] {
+        &self.parsed
+    }
+
+    /// 最近一次分析中重新分析的模块
+    pub fn analyzed(&self) -> impl Iterator<Item = &Path> {
+        self.analyzed.iter().map(|(file, _)| file.as_path())
+    }
+
+    /// 最近一次分析中更新过的模块
+    pub fn updated(&self) -> impl Iterator<Item = &CachedModule> {
+        let mut files: Vec<&Path> = self.parsed.iter().map(PathBuf::as_path).collect();
+        files.extend(self.analyzed());
+        files.sort();
+        files.dedup();
+        files.into_iter().filter_map(|file| self.modules.get(file))
+    }
+
+    /// 开始一次新的分析
+    pub(crate) fn start(&mut self) {
+        self.parsed.clear();
+        self.analyzed.clear();
+    }
+
+    /// 内容哈希相同的已解析语法树
+    pub(crate) fn program(&self, file: &Path, content_hash: u64) -> Option<Program> {
+        self.modules
+            .get(file)
+            .filter(|module| module.content_hash == content_hash)
+            .map(|module| module.program.clone())
+    }
+
+    /// 记录新解析的语法树（此前的分析结果随之作废）
+    pub(crate) fn store_program(&mut self, file: &Path, content_hash: u64, program: Program) {
+        self.parsed.push(file.to_path_buf());
+        self.insert(CachedModule {
+            file: file.to_path_buf(),
+            content_hash,
+            program,
+            analysis: None,
+        });
+    }
+
+    /// 分析键相同的分析结果
+    pub(crate) fn analysis(&self, file: &Path, key: u64) -> Option<Vec<Decl>> {
+        self.modules
+            .get(file)
+            .and_then(|module| module.analysis.as_ref())
+            .filter(|analysis| analysis.key == key)
+            .map(|analysis| analysis.decls.clone())
+    }
+
+    /// 记录需要重新分析的模块，分析没有错误时由 [`Self::finish`] 保存结果
+    pub(crate) fn mark_analyzed(&mut self, file: &Path, key: u64) {
+        match self.analyzed.iter_mut().find(|(f, _)| f == file) {
+            Some((_, previous)) => *previous = None,
+            None => self.analyzed.push((file.to_path_buf(), Some(key))),
+        }
+    }
+
+    /// 保存本次重新分析的模块的声明；程序有错误时不保存
+    pub(crate) fn finish(&mut self, program: &Program, sources: &ModuleSources, ok: bool) {
+        if !ok {
+            return;
+        }
+        for (file, key) in &self.analyzed {
+            let Some(key) = key else {
+                continue;
+            };
+            let source = sources.iter().find(|s| &s.file == file && !s.reused);
+            let decls = source.and_then(|source| program.decls.get(source.decls.clone()));
+            let (Some(decls), Some(module)) = (decls, self.modules.get_mut(file)) else {
+                continue;
+            };
+            module.analysis = Some(ModuleAnalysis {
+                key: *key,
+                decls: decls.to_vec(),
+            });
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Module Sources
+//!
+//! Resolver 把导入模块的声明合并到入口程序的 `program.decls` 之后，
+//! 这些声明中的 span 是各自源文件里的偏移。这里记录每个模块占据的声明区间，
+//! 各 Pass 据此把错误标记为来自哪个模块，调用方才能按正确的文件显示位置。
+
+use crate::error::SemanticError;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// 导入模块在合并后的 `program.decls` 中的位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleSource {
+    /// 模块路径（如 `std.io`）
+    pub module: String,
+    /// 模块源文件
+    pub file: PathBuf,
+    /// 模块声明在 `program.decls` 中的下标区间
+    pub decls: Range<usize>,
+    /// 复用了缓存的分析结果（见 [`crate::module_cache`]），各 Pass 跳过这些声明
+    pub reused: bool,
+}
+
+/// 所有导入模块的声明区间（按合并顺序）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleSources(Vec<ModuleSource>);
+
+impl ModuleSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, source: ModuleSource) {
+        self.0.push(source);
+    }
+
+    /// 第 `index` 个声明所属的导入模块；入口源文件的声明返回 None
+    pub fn of_decl(&self, index: usize) -> Option<&ModuleSource> {
+        self.0.iter().find(|source| source.decls.contains(&index))
+    }
+
+    /// 把检查第 `index` 个声明时产生的错误标记为来自其所属模块
+    pub(crate) fn attribute(&self, errors: &mut [SemanticError], index: usize) {
+        let Some(source) = self.of_decl(index) else {
+            return;
+        };
+        for error in errors {
+            *error = error.clone().in_module(&source.module, &source.file);
+        }
+    }
+
+    /// 第 `index` 个声明是否复用了缓存的分析结果
+    pub(crate) fn is_reused(&self, index: usize) -> bool {
+        self.of_decl(index).is_some_and(|source| source.reused)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ModuleSource> {
+        self.0.iter()
+    }
+}
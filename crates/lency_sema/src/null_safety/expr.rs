@@ -73,6 +73,9 @@ pub fn check_expr(checker: &mut NullSafetyChecker, expr: &mut Expr) {
         } => {
             checker.check_expr(value);
             for case in cases {
+                if let Some(guard) = &mut case.guard {
+                    checker.check_expr(guard);
+                }
                 checker.check_expr(&mut case.body);
             }
             if let Some(def) = default {
@@ -10,6 +10,7 @@
 //! 遵循 "Safety by Default" 哲学：编译时捕获所有潜在的 null 错误。
 
 use crate::error::SemanticError;
+use crate::module_source::ModuleSources;
 use crate::scope::{ScopeId, ScopeStack};
 use lency_syntax::ast::{Decl, Expr, ExprKind, Literal, Program, Stmt, Type};
 use std::collections::HashSet;
@@ -25,6 +26,8 @@ pub struct NullSafetyChecker<'a> {
     pub(crate) errors: Vec<SemanticError>,
     /// 当前已知非空的变量（通过 if != null 检查后）
     pub(crate) known_non_null: HashSet<String>,
+    /// 导入模块的声明区间（用于标记错误来源）
+    module_sources: ModuleSources,
 }
 
 impl<'a> NullSafetyChecker<'a> {
@@ -35,16 +38,30 @@ impl<'a> NullSafetyChecker<'a> {
             next_child_index: 0,
             errors: Vec::new(),
             known_non_null: HashSet::new(),
+            module_sources: ModuleSources::new(),
         }
     }
 
+    /// 按导入模块的声明区间标记错误来源
+    pub fn with_module_sources(mut self, sources: ModuleSources) -> Self {
+        self.module_sources = sources;
+        self
+    }
+
     /// 检查整个程序
     pub fn check(&mut self, program: &mut Program) -> Result<(), Vec<SemanticError>> {
         // 全局作用域
         self.next_child_index = 0;
 
-        for decl in &mut program.decls {
+        // 复用缓存分析结果的模块已经检查过，Resolver 也没有为它们创建作用域
+        for (index, decl) in program.decls.iter_mut().enumerate() {
+            if self.module_sources.is_reused(index) {
+                continue;
+            }
+            let mark = self.errors.len();
             self.check_decl(decl);
+            self.module_sources
+                .attribute(&mut self.errors[mark..], index);
         }
 
         if self.errors.is_empty() {
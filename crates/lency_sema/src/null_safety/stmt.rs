@@ -13,6 +13,9 @@ pub fn check_stmt(checker: &mut NullSafetyChecker, stmt: &mut Stmt) {
         } => {
             check_var_decl(checker, name, ty.as_ref(), value, span);
         }
+        Stmt::VarPattern { value, .. } => {
+            checker.check_expr(value);
+        }
         Stmt::Assignment {
             target,
             value,
@@ -38,6 +41,23 @@ pub fn check_stmt(checker: &mut NullSafetyChecker, stmt: &mut Stmt) {
         } => {
             check_if(checker, condition, then_block, else_block.as_deref_mut());
         }
+        Stmt::IfLet {
+            value,
+            then_block,
+            else_block,
+            ..
+        } => {
+            check_if_let(checker, value, then_block, else_block.as_deref_mut());
+        }
+        Stmt::WhileLet { value, body, .. } => {
+            checker.check_expr(value);
+            // 模式变量定义在循环体的作用域中
+            checker.with_child_scope(|checker: &mut NullSafetyChecker| {
+                for stmt in body {
+                    checker.check_stmt(stmt);
+                }
+            });
+        }
         Stmt::While {
             condition, body, ..
         } => {
@@ -190,3 +210,33 @@ fn check_if(
     // 退出 if 后恢复状态
     checker.known_non_null = prev_known;
 }
+
+/// 检查 if let 语句
+///
+/// 模式变量的类型由 TypeChecker 按 `match` 的规则写入 then 分支的作用域，
+/// 这里只需保证两个分支共享进入语句前的智能转换状态
+fn check_if_let(
+    checker: &mut NullSafetyChecker,
+    value: &mut Expr,
+    then_block: &mut [Stmt],
+    else_block: Option<&mut [Stmt]>,
+) {
+    checker.check_expr(value);
+
+    let prev_known = checker.known_non_null.clone();
+    checker.with_child_scope(|checker| {
+        for stmt in then_block {
+            checker.check_stmt(stmt);
+        }
+    });
+    checker.known_non_null = prev_known.clone();
+
+    if let Some(else_stmts) = else_block {
+        checker.with_child_scope(|checker| {
+            for stmt in else_stmts {
+                checker.check_stmt(stmt);
+            }
+        });
+    }
+    checker.known_non_null = prev_known;
+}
@@ -3,7 +3,7 @@
 //! 使用表驱动方法管理二元运算符的类型签名
 
 use crate::error::SemanticError;
-use lency_syntax::ast::{BinaryOp, Type};
+use lency_syntax::ast::{BinaryOp, IntKind, Type};
 
 /// 二元运算符签名
 ///
@@ -38,6 +38,7 @@ impl BinaryOpRegistry {
         self.register_arithmetic();
         self.register_comparison();
         self.register_logical();
+        self.register_bitwise();
     }
 
     /// 注册算术运算符
@@ -63,6 +64,13 @@ impl BinaryOpRegistry {
             self.add(op.clone(), Float, Int, Float);
         }
 
+        // 定宽整数只与同种类运算，不与 int 混用（需要 `as`）
+        for kind in IntKind::ALL {
+            for op in [Add, Sub, Mul, Div, Mod] {
+                self.add(op.clone(), FixedInt(kind), FixedInt(kind), FixedInt(kind));
+            }
+        }
+
         // 字符串连接: string + string -> string
         self.add(Add, String, String, String);
     }
@@ -79,6 +87,13 @@ impl BinaryOpRegistry {
             self.add(op.clone(), Int, Int, Bool);
         }
 
+        // 定宽整数比较（无符号种类按无符号比较）
+        for kind in IntKind::ALL {
+            for op in [Eq, Neq, Lt, Gt, Leq, Geq] {
+                self.add(op.clone(), FixedInt(kind), FixedInt(kind), Bool);
+            }
+        }
+
         // float 比较
         for op in [Eq, Neq, Lt, Gt, Leq, Geq] {
             self.add(op.clone(), Float, Float, Bool);
@@ -93,6 +108,11 @@ impl BinaryOpRegistry {
         for op in [Eq, Neq] {
             self.add(op.clone(), String, String, Bool);
         }
+
+        // char 按码点比较
+        for op in [Eq, Neq, Lt, Gt, Leq, Geq] {
+            self.add(op.clone(), Char, Char, Bool);
+        }
     }
 
     /// 注册逻辑运算符
@@ -109,6 +129,27 @@ impl BinaryOpRegistry {
         }
     }
 
+    /// 注册按位运算符
+    ///
+    /// 包括：&, |, ^, <<, >>
+    fn register_bitwise(&mut self) {
+        use BinaryOp::*;
+        use Type::*;
+
+        // 整数按位运算与移位，两侧类型相同（移位量也不例外）
+        let integers = std::iter::once(Int).chain(IntKind::ALL.into_iter().map(FixedInt));
+        for ty in integers {
+            for op in [BitAnd, BitOr, BitXor, Shl, Shr] {
+                self.add(op, ty.clone(), ty.clone(), ty.clone());
+            }
+        }
+
+        // bool 的 &, |, ^ 不短路，两侧都会求值
+        for op in [BitAnd, BitOr, BitXor] {
+            self.add(op, Bool, Bool, Bool);
+        }
+    }
+
     /// 添加运算符签名
     fn add(&mut self, op: BinaryOp, lhs: Type, rhs: Type, result: Type) {
         self.signatures.push(BinaryOpSignature {
@@ -147,6 +188,11 @@ impl BinaryOpRegistry {
             }
         }
 
+        // 已报告过错误的操作数（如语法错误的占位）不再产生级联错误
+        if *lhs == Type::Error || *rhs == Type::Error {
+            return Ok(Type::Error);
+        }
+
         // 处理 Nullable 类型的相等性检查
         if *op == BinaryOp::Eq || *op == BinaryOp::Neq {
             let is_lhs_null_lit =
@@ -222,6 +268,25 @@ mod tests {
         assert_eq!(result.unwrap(), Type::Float);
     }
 
+    #[test]
+    fn test_fixed_int_same_kind_only() {
+        let registry = BinaryOpRegistry::new();
+        let u8_ty = Type::FixedInt(IntKind::U8);
+
+        let result = registry.lookup(&BinaryOp::Add, &u8_ty, &u8_ty, &(0..1));
+        assert_eq!(result.unwrap(), u8_ty);
+
+        let result = registry.lookup(&BinaryOp::Lt, &u8_ty, &u8_ty, &(0..1));
+        assert_eq!(result.unwrap(), Type::Bool);
+
+        // u8 + int / u8 + i32 需要显式 `as`
+        let result = registry.lookup(&BinaryOp::Add, &u8_ty, &Type::Int, &(0..1));
+        assert!(result.is_err());
+        let i32_ty = Type::FixedInt(IntKind::I32);
+        let result = registry.lookup(&BinaryOp::Add, &u8_ty, &i32_ty, &(0..1));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_string_concatenation() {
         let registry = BinaryOpRegistry::new();
@@ -289,6 +354,29 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_bitwise() {
+        let registry = BinaryOpRegistry::new();
+        let u8_ty = Type::FixedInt(IntKind::U8);
+
+        let result = registry.lookup(&BinaryOp::BitAnd, &Type::Int, &Type::Int, &(0..1));
+        assert_eq!(result.unwrap(), Type::Int);
+
+        let result = registry.lookup(&BinaryOp::Shr, &u8_ty, &u8_ty, &(0..1));
+        assert_eq!(result.unwrap(), u8_ty);
+
+        let result = registry.lookup(&BinaryOp::BitXor, &Type::Bool, &Type::Bool, &(0..1));
+        assert_eq!(result.unwrap(), Type::Bool);
+
+        // 浮点数没有按位运算，bool 不能移位，移位量不与 int 混用
+        let result = registry.lookup(&BinaryOp::BitOr, &Type::Float, &Type::Float, &(0..1));
+        assert!(result.is_err());
+        let result = registry.lookup(&BinaryOp::Shl, &Type::Bool, &Type::Bool, &(0..1));
+        assert!(result.is_err());
+        let result = registry.lookup(&BinaryOp::Shl, &u8_ty, &Type::Int, &(0..1));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_invalid_operation() {
         let registry = BinaryOpRegistry::new();
@@ -3,7 +3,7 @@
 //! 使用表驱动方法管理一元运算符的类型签名
 
 use crate::error::SemanticError;
-use lency_syntax::ast::{Type, UnaryOp};
+use lency_syntax::ast::{IntKind, Type, UnaryOp};
 
 /// 一元运算符签名
 ///
@@ -41,8 +41,19 @@ impl UnaryOpRegistry {
         self.add(Neg, Int, Int);
         self.add(Neg, Float, Float);
 
+        // 有符号定宽整数取负；无符号整数没有负号
+        for kind in IntKind::ALL.into_iter().filter(|k| k.is_signed()) {
+            self.add(Neg, FixedInt(kind), FixedInt(kind));
+        }
+
         // 逻辑非：!bool -> bool
         self.add(Not, Bool, Bool);
+
+        // 按位取反：~int -> int，定宽整数同理
+        self.add(BitNot, Int, Int);
+        for kind in IntKind::ALL {
+            self.add(BitNot, FixedInt(kind), FixedInt(kind));
+        }
     }
 
     /// 添加运算符签名
@@ -80,6 +91,11 @@ impl UnaryOpRegistry {
             }
         }
 
+        // 已报告过错误的操作数不再产生级联错误
+        if *operand == Type::Error {
+            return Ok(Type::Error);
+        }
+
         // 未找到匹配的运算符签名
         Err(SemanticError::InvalidUnaryOp {
             op: format!("{:?}", op),
@@ -115,6 +131,19 @@ mod tests {
         assert_eq!(result.unwrap(), Type::Float);
     }
 
+    #[test]
+    fn test_negation_fixed_int() {
+        let registry = UnaryOpRegistry::new();
+
+        let i8_ty = Type::FixedInt(IntKind::I8);
+        let result = registry.lookup(&UnaryOp::Neg, &i8_ty, &(0..1));
+        assert_eq!(result.unwrap(), i8_ty);
+
+        // 无符号整数不能取负
+        let result = registry.lookup(&UnaryOp::Neg, &Type::FixedInt(IntKind::U32), &(0..1));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_logical_not() {
         let registry = UnaryOpRegistry::new();
@@ -123,6 +152,22 @@ mod tests {
         assert_eq!(result.unwrap(), Type::Bool);
     }
 
+    #[test]
+    fn test_bitwise_not() {
+        let registry = UnaryOpRegistry::new();
+
+        let result = registry.lookup(&UnaryOp::BitNot, &Type::Int, &(0..1));
+        assert_eq!(result.unwrap(), Type::Int);
+
+        let u16_ty = Type::FixedInt(IntKind::U16);
+        let result = registry.lookup(&UnaryOp::BitNot, &u16_ty, &(0..1));
+        assert_eq!(result.unwrap(), u16_ty);
+
+        // ~bool 不支持（用 !）
+        let result = registry.lookup(&UnaryOp::BitNot, &Type::Bool, &(0..1));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_invalid_negation() {
         let registry = UnaryOpRegistry::new();
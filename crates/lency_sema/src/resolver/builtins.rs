@@ -49,6 +49,30 @@ pub fn register_builtins(scopes: &mut ScopeStack) {
     );
     define_extern_fn("hashmap_int_len", vec![("map", Type::Int)], Type::Int);
 
+    // StringBuilder FFI functions（句柄同 HashMap，以 int 传递）
+    define_extern_fn("string_builder_new", vec![], Type::Int);
+    define_extern_fn(
+        "string_builder_append",
+        vec![("sb", Type::Int), ("s", Type::String)],
+        Type::Void,
+    );
+    define_extern_fn(
+        "string_builder_append_int",
+        vec![("sb", Type::Int), ("n", Type::Int)],
+        Type::Void,
+    );
+    define_extern_fn(
+        "string_builder_append_char",
+        vec![("sb", Type::Int), ("c", Type::Char)],
+        Type::Void,
+    );
+    define_extern_fn("string_builder_len", vec![("sb", Type::Int)], Type::Int);
+    define_extern_fn(
+        "string_builder_build",
+        vec![("sb", Type::Int)],
+        Type::String,
+    );
+
     // Type conversion FFI functions
     define_extern_fn("int_to_string", vec![("n", Type::Int)], Type::String);
     define_extern_fn("float_to_string", vec![("f", Type::Float)], Type::String);
@@ -62,4 +86,10 @@ pub fn register_builtins(scopes: &mut ScopeStack) {
     // CLI/runtime argument FFI functions
     define_extern_fn("arg_count", vec![], Type::Int);
     define_extern_fn("arg_at", vec![("index", Type::Int)], Type::String);
+
+    // Garbage collector FFI functions
+    define_extern_fn("gc_collect", vec![], Type::Int);
+    define_extern_fn("gc_live_objects", vec![], Type::Int);
+    define_extern_fn("gc_live_bytes", vec![], Type::Int);
+    define_extern_fn("gc_collections", vec![], Type::Int);
 }
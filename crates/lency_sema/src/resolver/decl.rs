@@ -13,10 +13,9 @@ pub fn collect_decl(resolver: &mut Resolver, decl: &Decl) -> Vec<Decl> {
         Decl::Import { items, span } => {
             let mut all_new_decls = Vec::new();
             for item in items {
-                let mut decls = if let Some(alias_name) = &item.alias {
-                    resolver.resolve_import_as(&item.path, alias_name, span)
-                } else {
-                    resolver.resolve_import(&item.path, span)
+                let mut decls = match item.binding() {
+                    Some(binding) => resolver.resolve_import_as(&item.path, binding, span),
+                    None => resolver.resolve_import(&item.path, span),
                 };
                 all_new_decls.append(&mut decls);
             }
@@ -41,7 +40,7 @@ pub fn collect_decl(resolver: &mut Resolver, decl: &Decl) -> Vec<Decl> {
         } => {
             let generic_param_symbols: Vec<GenericParamSymbol> = generic_params
                 .iter()
-                .map(|p| GenericParamSymbol::new(p.name.clone(), p.bound.clone(), p.span.clone()))
+                .map(|p| GenericParamSymbol::new(p.name.clone(), p.bounds.clone(), p.span.clone()))
                 .collect();
 
             let normalized_params: Vec<_> = params
@@ -83,7 +82,7 @@ pub fn collect_decl(resolver: &mut Resolver, decl: &Decl) -> Vec<Decl> {
             for param in generic_params {
                 gps.push(GenericParamSymbol::new(
                     param.name.clone(),
-                    param.bound.clone(),
+                    param.bounds.clone(),
                     param.span.clone(),
                 ));
             }
@@ -126,7 +125,7 @@ pub fn collect_decl(resolver: &mut Resolver, decl: &Decl) -> Vec<Decl> {
             for param in generic_params {
                 gps.push(GenericParamSymbol::new(
                     param.name.clone(),
-                    param.bound.clone(),
+                    param.bounds.clone(),
                     param.span.clone(),
                 ));
             }
@@ -155,12 +154,13 @@ pub fn collect_decl(resolver: &mut Resolver, decl: &Decl) -> Vec<Decl> {
             generic_params,
             methods,
             span,
+            ..
         } => {
             let mut gps = Vec::new();
             for param in generic_params {
                 gps.push(GenericParamSymbol::new(
                     param.name.clone(),
-                    param.bound.clone(),
+                    param.bounds.clone(),
                     param.span.clone(),
                 ));
             }
@@ -195,12 +195,13 @@ pub fn collect_decl(resolver: &mut Resolver, decl: &Decl) -> Vec<Decl> {
             generic_params,
             variants,
             span,
+            ..
         } => {
             let mut gps = Vec::new();
             for param in generic_params {
                 gps.push(GenericParamSymbol::new(
                     param.name.clone(),
-                    param.bound.clone(),
+                    param.bounds.clone(),
                     param.span.clone(),
                 ));
             }
@@ -270,7 +271,7 @@ pub fn collect_impl_methods(resolver: &mut Resolver, decl: &Decl) {
         // Collect generic param symbols from Impl block
         let generic_param_symbols: Vec<GenericParamSymbol> = generic_params
             .iter()
-            .map(|p| GenericParamSymbol::new(p.name.clone(), p.bound.clone(), p.span.clone()))
+            .map(|p| GenericParamSymbol::new(p.name.clone(), p.bounds.clone(), p.span.clone()))
             .collect();
 
         // 1. Pre-process methods (Normalize types using resolver)
@@ -20,7 +20,7 @@ pub fn resolve_function(resolver: &mut Resolver, decl: &mut Decl) {
         for param in generic_params {
             gps.push(GenericParamSymbol::new(
                 param.name.clone(),
-                param.bound.clone(),
+                param.bounds.clone(),
                 param.span.clone(),
             ));
         }
@@ -1,11 +1,47 @@
 use crate::resolver::Resolver;
 use crate::scope::ScopeKind;
-use crate::symbol::{FunctionSymbol, GenericParamSymbol, ParameterSymbol, Symbol};
+use crate::symbol::{FunctionSymbol, GenericParamSymbol, ParameterSymbol, Symbol, TraitSymbol};
+use crate::type_infer::substitute_type;
 use crate::SemanticError;
 use lency_syntax::ast::{Decl, Type};
 
+/// 检查 Trait 能否作为 `dyn Trait` 使用（对象安全）
+///
+/// trait 对象的 vtable 在编译期为每个 (类型, Trait) 生成一份，
+/// 泛型 Trait 的方法签名依赖实例化参数，无法确定唯一的 vtable 布局。
+pub fn check_object_safety(trait_sym: &TraitSymbol) -> Result<(), String> {
+    if trait_sym.is_generic() {
+        return Err("generic traits cannot be used as trait objects".to_string());
+    }
+    for method in &trait_sym.methods {
+        let mentions_generic = method
+            .params
+            .iter()
+            .map(|(_, ty)| ty)
+            .chain(std::iter::once(&method.return_type))
+            .any(|ty| matches!(ty, Type::GenericParam(_)));
+        if mentions_generic {
+            return Err(format!(
+                "method '{}' has a generic parameter or return type",
+                method.name
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn resolve_impl(resolver: &mut Resolver, decl: &mut Decl) {
-    if let Decl::Impl {
+    if register_impl(resolver, decl, true) {
+        resolve_impl_methods(resolver, decl);
+    }
+}
+
+/// 把 impl 块的方法登记到目标类型，并记录 Trait 实现关系
+///
+/// `check_trait` 为 true 时检查方法是否符合 Trait 的签名（复用缓存的分析结果时已检查过）。
+/// 目标类型无效时记录错误并返回 false。
+pub fn register_impl(resolver: &mut Resolver, decl: &Decl, check_trait: bool) -> bool {
+    let Decl::Impl {
         trait_ref,
         type_name,
         generic_params,
@@ -13,51 +49,55 @@ pub fn resolve_impl(resolver: &mut Resolver, decl: &mut Decl) {
         span,
         ..
     } = decl
-    {
-        // Helper to extract base name from Type
-        let target_name = match type_name {
-            Type::Struct(name) => name.clone(),
-            Type::Generic(name, _) => name.clone(),
-            Type::Int => "int".to_string(),
-            Type::Bool => "bool".to_string(),
-            Type::String => "string".to_string(),
-            Type::Float => "float".to_string(),
-            Type::Result { .. } => "Result".to_string(), // Sprint 15: 支持为Result<T,E>定义impl
-            _ => "unknown".to_string(),
-        };
+    else {
+        return false;
+    };
 
-        let struct_id = resolver.scopes.lookup_id(&target_name);
-        if struct_id.is_none() {
-            resolver.errors.push(SemanticError::UndefinedType {
-                name: target_name.clone(),
-                span: span.clone(),
-            });
-            return;
-        }
+    // Helper to extract base name from Type
+    let target_name = match type_name {
+        Type::Struct(name) => name.clone(),
+        Type::Generic(name, _) => name.clone(),
+        Type::Int => "int".to_string(),
+        Type::Bool => "bool".to_string(),
+        Type::String => "string".to_string(),
+        Type::Float => "float".to_string(),
+        Type::Result { .. } => "Result".to_string(), // Sprint 15: 支持为Result<T,E>定义impl
+        _ => "unknown".to_string(),
+    };
 
-        let struct_id = struct_id.unwrap(); // allow: unwrap (logic guarantee)
-                                            // Sprint 15: Allow impl for both Struct and Enum (e.g., Result<T,E>)
-        let is_valid_target = matches!(
-            resolver.scopes.get_symbol(struct_id),
-            Some(Symbol::Struct(_)) | Some(Symbol::Enum(_))
-        );
-        if !is_valid_target {
-            resolver.errors.push(SemanticError::NotAStruct {
-                name: target_name.clone(),
-                span: span.clone(),
-            });
-            return;
-        }
+    let struct_id = resolver.scopes.lookup_id(&target_name);
+    if struct_id.is_none() {
+        resolver.errors.push(SemanticError::UndefinedType {
+            name: target_name.clone(),
+            span: span.clone(),
+        });
+        return false;
+    }
 
-        if let Some(trait_ty) = trait_ref {
-            let trait_name_str = match trait_ty {
-                Type::Struct(name) => name.clone(),
-                Type::Generic(name, _) => name.clone(),
-                _ => "unknown_trait".to_string(),
-            };
+    let struct_id = struct_id.unwrap(); // allow: unwrap (logic guarantee)
+                                        // Sprint 15: Allow impl for both Struct and Enum (e.g., Result<T,E>)
+    let is_valid_target = matches!(
+        resolver.scopes.get_symbol(struct_id),
+        Some(Symbol::Struct(_)) | Some(Symbol::Enum(_))
+    );
+    if !is_valid_target {
+        resolver.errors.push(SemanticError::NotAStruct {
+            name: target_name.clone(),
+            span: span.clone(),
+        });
+        return false;
+    }
+
+    if let Some(trait_ty) = trait_ref {
+        let trait_name_str = match trait_ty {
+            Type::Struct(name) => name.clone(),
+            Type::Generic(name, _) => name.clone(),
+            _ => "unknown_trait".to_string(),
+        };
 
-            if let Some(trait_id) = resolver.scopes.lookup_id(&trait_name_str) {
-                if let Some(Symbol::Trait(trait_sym)) = resolver.scopes.get_symbol(trait_id) {
+        if let Some(trait_id) = resolver.scopes.lookup_id(&trait_name_str) {
+            if let Some(Symbol::Trait(trait_sym)) = resolver.scopes.get_symbol(trait_id) {
+                if check_trait {
                     let impl_method_names: Vec<&str> = methods
                         .iter()
                         .filter_map(|m| {
@@ -104,31 +144,14 @@ pub fn resolve_impl(resolver: &mut Resolver, decl: &mut Decl) {
                                     }
                                 }
 
-                                // Helper to substitute
+                                // Helper to substitute（递归代入，如 Option<T> -> Option<int>）
                                 let substitute = |ty: &Type| -> Type {
                                     match ty {
-                                        Type::Generic(name, args) if args.is_empty() => {
-                                            if let Some(replacement) = subst_map.get(name) {
-                                                replacement.clone()
-                                            } else {
-                                                ty.clone()
-                                            }
-                                        }
-                                        Type::Struct(name) => {
-                                            if let Some(replacement) = subst_map.get(name) {
-                                                replacement.clone()
-                                            } else {
-                                                ty.clone()
-                                            }
-                                        }
-                                        Type::Generic(_name, _args) => {
-                                            // Handle nested generics e.g. List<T> -> List<int>
-                                            // Recursion needed? For Phase 2 simple substitution is likely enough
-                                            // Assuming args can be substituted
-                                            // Simple specific handling for standard generic types
-                                            ty.clone()
-                                        }
-                                        _ => ty.clone(),
+                                        Type::Generic(name, args) if args.is_empty() => subst_map
+                                            .get(name)
+                                            .cloned()
+                                            .unwrap_or_else(|| ty.clone()),
+                                        _ => substitute_type(ty, &subst_map),
                                     }
                                 };
 
@@ -177,117 +200,131 @@ pub fn resolve_impl(resolver: &mut Resolver, decl: &mut Decl) {
                             }
                         }
                     }
+                }
 
-                    // 注册 trait 方法到 StructSymbol
-                    let trait_generic_params = trait_sym.generic_params.clone();
-                    for method in methods.iter() {
-                        if let Decl::Function {
-                            name: method_name,
-                            params,
-                            return_type,
-                            span: method_span,
-                            ..
-                        } = method
-                        {
-                            // Normalize types for Symbol using generic params
-                            let normalized_params: Vec<_> = params
-                                .iter()
-                                .map(|p| {
-                                    let mut ty = p.ty.clone();
-                                    resolver.normalize_type_with_generics(
-                                        &mut ty,
-                                        &trait_generic_params,
-                                    );
-                                    (p.name.clone(), ty)
-                                })
-                                .collect();
-
-                            let mut normalized_return_type = return_type.clone();
-                            resolver.normalize_type_with_generics(
-                                &mut normalized_return_type,
-                                &trait_generic_params,
-                            );
-
-                            let func_sym = FunctionSymbol::new(
-                                method_name.clone(),
-                                normalized_params,
-                                normalized_return_type,
-                                method_span.clone(),
-                            );
-
-                            // Sprint 15: Support adding methods to both Struct and Enum
-                            match resolver.scopes.get_symbol_mut(struct_id) {
-                                Some(Symbol::Struct(ref mut struct_sym)) => {
-                                    struct_sym.add_method(method_name.clone(), func_sym);
-                                }
-                                Some(Symbol::Enum(ref mut enum_sym)) => {
-                                    enum_sym.methods.insert(method_name.clone(), func_sym);
-                                }
-                                _ => {}
+                // 注册 trait 方法到 StructSymbol
+                let trait_generic_params = trait_sym.generic_params.clone();
+                for method in methods.iter() {
+                    if let Decl::Function {
+                        name: method_name,
+                        params,
+                        return_type,
+                        span: method_span,
+                        ..
+                    } = method
+                    {
+                        // Normalize types for Symbol using generic params
+                        let normalized_params: Vec<_> = params
+                            .iter()
+                            .map(|p| {
+                                let mut ty = p.ty.clone();
+                                resolver
+                                    .normalize_type_with_generics(&mut ty, &trait_generic_params);
+                                (p.name.clone(), ty)
+                            })
+                            .collect();
+
+                        let mut normalized_return_type = return_type.clone();
+                        resolver.normalize_type_with_generics(
+                            &mut normalized_return_type,
+                            &trait_generic_params,
+                        );
+
+                        let func_sym = FunctionSymbol::new(
+                            method_name.clone(),
+                            normalized_params,
+                            normalized_return_type,
+                            method_span.clone(),
+                        );
+
+                        // Sprint 15: Support adding methods to both Struct and Enum
+                        match resolver.scopes.get_symbol_mut(struct_id) {
+                            Some(Symbol::Struct(ref mut struct_sym)) => {
+                                struct_sym.add_method(method_name.clone(), func_sym);
                             }
+                            Some(Symbol::Enum(ref mut enum_sym)) => {
+                                enum_sym.methods.insert(method_name.clone(), func_sym);
+                            }
+                            _ => {}
                         }
                     }
                 }
-            } else {
-                resolver.errors.push(SemanticError::UndefinedTrait {
-                    name: trait_name_str.clone(),
-                    span: span.clone(),
-                });
+
+                // 记录实现关系，供 `dyn Trait` 转换检查
+                if let Some(Symbol::Trait(trait_sym)) = resolver.scopes.get_symbol_mut(trait_id) {
+                    trait_sym.add_implementor(target_name.clone());
+                }
             }
         } else {
-            // 普通 impl 块 (无 trait): 也需要注册方法到 StructSymbol
-            // Coerce AST Generic Params to Symbols for normalization
-            let generic_param_symbols: Vec<GenericParamSymbol> = generic_params
-                .iter()
-                .map(|p| GenericParamSymbol::new(p.name.clone(), p.bound.clone(), p.span.clone()))
-                .collect();
-
-            for method in methods.iter() {
-                if let Decl::Function {
-                    name: method_name,
-                    params,
-                    return_type,
-                    span: method_span,
-                    ..
-                } = method
-                {
-                    // Normalize types using normalize_type_with_generics
-                    let normalized_params: Vec<_> = params
-                        .iter()
-                        .map(|p| {
-                            let mut ty = p.ty.clone();
-                            resolver.normalize_type_with_generics(&mut ty, &generic_param_symbols);
-                            (p.name.clone(), ty)
-                        })
-                        .collect();
+            resolver.errors.push(SemanticError::UndefinedTrait {
+                name: trait_name_str.clone(),
+                span: span.clone(),
+            });
+        }
+    } else {
+        // 普通 impl 块 (无 trait): 也需要注册方法到 StructSymbol
+        // Coerce AST Generic Params to Symbols for normalization
+        let generic_param_symbols: Vec<GenericParamSymbol> = generic_params
+            .iter()
+            .map(|p| GenericParamSymbol::new(p.name.clone(), p.bounds.clone(), p.span.clone()))
+            .collect();
 
-                    let mut normalized_return_type = return_type.clone();
-                    resolver.normalize_type_with_generics(
-                        &mut normalized_return_type,
-                        &generic_param_symbols,
-                    );
+        for method in methods.iter() {
+            if let Decl::Function {
+                name: method_name,
+                params,
+                return_type,
+                span: method_span,
+                ..
+            } = method
+            {
+                // Normalize types using normalize_type_with_generics
+                let normalized_params: Vec<_> = params
+                    .iter()
+                    .map(|p| {
+                        let mut ty = p.ty.clone();
+                        resolver.normalize_type_with_generics(&mut ty, &generic_param_symbols);
+                        (p.name.clone(), ty)
+                    })
+                    .collect();
 
-                    let func_sym = FunctionSymbol::new(
-                        method_name.clone(),
-                        normalized_params,
-                        normalized_return_type,
-                        method_span.clone(),
-                    );
+                let mut normalized_return_type = return_type.clone();
+                resolver.normalize_type_with_generics(
+                    &mut normalized_return_type,
+                    &generic_param_symbols,
+                );
 
-                    // Sprint 15: Support adding methods to both Struct and Enum
-                    match resolver.scopes.get_symbol_mut(struct_id) {
-                        Some(Symbol::Struct(ref mut struct_sym)) => {
-                            struct_sym.add_method(method_name.clone(), func_sym);
-                        }
-                        Some(Symbol::Enum(ref mut enum_sym)) => {
-                            enum_sym.methods.insert(method_name.clone(), func_sym);
-                        }
-                        _ => {}
+                let func_sym = FunctionSymbol::new(
+                    method_name.clone(),
+                    normalized_params,
+                    normalized_return_type,
+                    method_span.clone(),
+                );
+
+                // Sprint 15: Support adding methods to both Struct and Enum
+                match resolver.scopes.get_symbol_mut(struct_id) {
+                    Some(Symbol::Struct(ref mut struct_sym)) => {
+                        struct_sym.add_method(method_name.clone(), func_sym);
                     }
+                    Some(Symbol::Enum(ref mut enum_sym)) => {
+                        enum_sym.methods.insert(method_name.clone(), func_sym);
+                    }
+                    _ => {}
                 }
             }
         }
+    }
+    true
+}
 
+fn resolve_impl_methods(resolver: &mut Resolver, decl: &mut Decl) {
+    if let Decl::Impl {
+        type_name,
+        generic_params,
+        methods,
+        ..
+    } = decl
+    {
         for method in methods {
             if let Decl::Function {
                 params,
@@ -300,15 +337,21 @@ pub fn resolve_impl(resolver: &mut Resolver, decl: &mut Decl) {
                 resolver.scopes.enter_scope(ScopeKind::Function);
 
                 for gp in generic_params.iter() {
-                    if let Some(bound) = &gp.bound {
-                        resolver.resolve_type(bound, &gp.span);
-                    }
-                    let gp_symbol =
-                        GenericParamSymbol::new(gp.name.clone(), gp.bound.clone(), gp.span.clone());
+                    let gp_symbol = GenericParamSymbol::new(
+                        gp.name.clone(),
+                        gp.bounds.clone(),
+                        gp.span.clone(),
+                    );
                     if let Err(e) = resolver.scopes.define(Symbol::GenericParam(gp_symbol)) {
                         resolver.errors.push(e);
                     }
                 }
+                // 约束可以引用同一列表中的参数（如 K: Eq<K>），全部定义后再解析
+                for gp in generic_params.iter() {
+                    for bound in &gp.bounds {
+                        resolver.resolve_type(bound, &gp.span);
+                    }
+                }
 
                 // Make `this` type generic-aware if needed
                 // Currently using implicit type_name assumption
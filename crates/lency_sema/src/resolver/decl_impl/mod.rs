@@ -7,5 +7,5 @@ pub mod impl_block;
 pub mod types;
 
 pub use function::resolve_function;
-pub use impl_block::resolve_impl;
+pub use impl_block::{register_impl, resolve_impl};
 pub use types::{resolve_enum, resolve_struct, resolve_trait};
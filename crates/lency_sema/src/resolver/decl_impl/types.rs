@@ -15,9 +15,9 @@ pub fn resolve_struct(resolver: &mut Resolver, decl: &mut Decl) {
         let has_generics = !generic_params.is_empty();
         if has_generics {
             resolver.scopes.enter_scope(ScopeKind::Block);
-            for gp in generic_params {
+            for gp in generic_params.iter() {
                 let gp_symbol =
-                    GenericParamSymbol::new(gp.name.clone(), gp.bound.clone(), gp.span.clone());
+                    GenericParamSymbol::new(gp.name.clone(), gp.bounds.clone(), gp.span.clone());
                 if let Err(e) = resolver.scopes.define(Symbol::GenericParam(gp_symbol)) {
                     resolver.errors.push(e);
                 }
@@ -46,16 +46,19 @@ pub fn resolve_trait(resolver: &mut Resolver, decl: &mut Decl) {
         let has_generics = !generic_params.is_empty();
         if has_generics {
             resolver.scopes.enter_scope(ScopeKind::Block);
-            for gp in generic_params {
-                if let Some(bound) = &gp.bound {
-                    resolver.resolve_type(bound, &gp.span);
-                }
+            for gp in generic_params.iter() {
                 let gp_symbol =
-                    GenericParamSymbol::new(gp.name.clone(), gp.bound.clone(), gp.span.clone());
+                    GenericParamSymbol::new(gp.name.clone(), gp.bounds.clone(), gp.span.clone());
                 if let Err(e) = resolver.scopes.define(Symbol::GenericParam(gp_symbol)) {
                     resolver.errors.push(e);
                 }
             }
+            // 约束可以引用同一列表中的参数（如 K: Eq<K>），全部定义后再解析
+            for gp in generic_params.iter() {
+                for bound in &gp.bounds {
+                    resolver.resolve_type(bound, &gp.span);
+                }
+            }
         }
 
         for method in methods {
@@ -84,16 +87,19 @@ pub fn resolve_enum(resolver: &mut Resolver, decl: &mut Decl) {
         let has_generics = !generic_params.is_empty();
         if has_generics {
             resolver.scopes.enter_scope(ScopeKind::Block);
-            for gp in generic_params {
-                if let Some(bound) = &gp.bound {
-                    resolver.resolve_type(bound, &gp.span);
-                }
+            for gp in generic_params.iter() {
                 let gp_symbol =
-                    GenericParamSymbol::new(gp.name.clone(), gp.bound.clone(), gp.span.clone());
+                    GenericParamSymbol::new(gp.name.clone(), gp.bounds.clone(), gp.span.clone());
                 if let Err(e) = resolver.scopes.define(Symbol::GenericParam(gp_symbol)) {
                     resolver.errors.push(e);
                 }
             }
+            // 约束可以引用同一列表中的参数（如 K: Eq<K>），全部定义后再解析
+            for gp in generic_params.iter() {
+                for bound in &gp.bounds {
+                    resolver.resolve_type(bound, &gp.span);
+                }
+            }
         }
 
         for variant in variants {
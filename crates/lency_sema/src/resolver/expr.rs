@@ -8,11 +8,17 @@ pub fn resolve_expr(resolver: &mut Resolver, expr: &mut Expr) {
     match &mut expr.kind {
         ExprKind::Variable(name) => {
             // 检查变量是否已定义
-            if resolver.scopes.lookup(name).is_none() {
-                resolver.errors.push(SemanticError::UndefinedVariable {
+            match resolver.scopes.lookup(name) {
+                None => resolver.errors.push(SemanticError::UndefinedVariable {
                     name: name.clone(),
                     span: expr.span.clone(),
-                });
+                    suggestion: resolver.scopes.similar_name(name, Symbol::is_value),
+                }),
+                Some(Symbol::Module(_)) => resolver.errors.push(SemanticError::ModuleNotAValue {
+                    name: name.clone(),
+                    span: expr.span.clone(),
+                }),
+                Some(_) => {}
             }
         }
         ExprKind::Binary(left, _, right) => {
@@ -28,7 +34,17 @@ pub fn resolve_expr(resolver: &mut Resolver, expr: &mut Expr) {
                 resolver.resolve_expr(arg);
             }
         }
-        ExprKind::Get { object, .. } => {
+        ExprKind::Get { object, name } => {
+            // 模块成员访问: io.println -> std::io::println
+            if let ExprKind::Variable(module) = &object.kind {
+                if let Some(Symbol::Module(_)) = resolver.scopes.lookup(module) {
+                    if let Some(mangled) = resolver.resolve_module_member(module, name, &expr.span)
+                    {
+                        expr.kind = ExprKind::Variable(mangled);
+                    }
+                    return;
+                }
+            }
             resolver.resolve_expr(object);
             // 字段名的解析在类型检查阶段完成
         }
@@ -58,7 +74,7 @@ pub fn resolve_expr(resolver: &mut Resolver, expr: &mut Expr) {
         ExprKind::Literal(_) => {
             // 字面量不需要解析
         }
-        ExprKind::Unit => {}
+        ExprKind::Unit | ExprKind::Error => {}
         ExprKind::Match {
             value,
             cases,
@@ -66,8 +82,12 @@ pub fn resolve_expr(resolver: &mut Resolver, expr: &mut Expr) {
         } => {
             resolver.resolve_expr(value);
             for case in cases {
-                resolver.scopes.enter_scope(ScopeKind::Block);
+                resolver.qualify_pattern(&mut case.pattern);
+                resolver.scopes.enter_scope(ScopeKind::MatchArm);
                 declare_pattern_vars(resolver, &case.pattern);
+                if let Some(guard) = &mut case.guard {
+                    resolver.resolve_expr(guard);
+                }
                 resolver.resolve_expr(&mut case.body);
                 resolver.scopes.exit_scope();
             }
@@ -82,6 +102,10 @@ pub fn resolve_expr(resolver: &mut Resolver, expr: &mut Expr) {
             resolver.resolve_expr(array);
             resolver.resolve_expr(index);
         }
+        ExprKind::Range { start, end, .. } => {
+            resolver.resolve_expr(start);
+            resolver.resolve_expr(end);
+        }
         ExprKind::StructLiteral { type_, fields } => {
             // Check Struct type (handles generics)
             resolver.normalize_type(type_);
@@ -97,9 +121,9 @@ pub fn resolve_expr(resolver: &mut Resolver, expr: &mut Expr) {
         ExprKind::Ok(inner) => resolver.resolve_expr(inner),
         ExprKind::Err(inner) => resolver.resolve_expr(inner),
         // 闭包
-        ExprKind::Closure { params, body } => {
+        ExprKind::Closure { params, body, .. } => {
             // 进入闭包作用域
-            resolver.scopes.enter_scope(ScopeKind::Function);
+            resolver.scopes.enter_scope(ScopeKind::Closure);
             // 注册参数
             for param in params {
                 resolver.normalize_type(&mut param.ty);
@@ -156,27 +180,27 @@ pub fn resolve_expr(resolver: &mut Resolver, expr: &mut Expr) {
             resolver.resolve_expr(template);
             resolver.resolve_expr(args);
         }
+        // 由类型检查插入，名称解析阶段不会出现
+        ExprKind::CoerceDyn { value, .. } => {
+            resolver.resolve_expr(value);
+        }
+        ExprKind::Cast { value, target } => {
+            resolver.resolve_expr(value);
+            resolver.normalize_type(target);
+        }
     }
 }
 
 fn declare_pattern_vars(resolver: &mut Resolver, pattern: &MatchPattern) {
-    match pattern {
-        MatchPattern::Variable(name) => {
-            let var_sym = VariableSymbol::new(
-                name.clone(),
-                Type::Void, // TypeChecker will infer later
-                false,      // Immutable binding
-                0..0,       // Span dummy? Or we should pass span?
-            );
-            if let Err(e) = resolver.scopes.define(Symbol::Variable(var_sym)) {
-                resolver.errors.push(e);
-            }
-        }
-        MatchPattern::Variant { sub_patterns, .. } => {
-            for pat in sub_patterns {
-                declare_pattern_vars(resolver, pat);
-            }
+    for name in pattern.bindings() {
+        let var_sym = VariableSymbol::new(
+            name.to_string(),
+            Type::Void, // TypeChecker will infer later
+            false,      // Immutable binding
+            0..0,       // Span dummy? Or we should pass span?
+        );
+        if let Err(e) = resolver.scopes.define(Symbol::Variable(var_sym)) {
+            resolver.errors.push(e);
         }
-        _ => {}
     }
 }
@@ -1,204 +1,388 @@
-use super::decl;
+use super::namespace::{self, NameMangler};
+use super::search_path::find_module_source;
 use super::Resolver;
 use crate::error::SemanticError;
-use lency_syntax::ast::{Decl, Expr, ExprKind, Span, Type};
+use crate::module_cache::{interface_hash, stable_hash, StableHasher};
+use crate::symbol::{ModuleMember, ModuleSymbol, Symbol};
+use lency_syntax::ast::{Decl, Program, Span, Type};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 
-/// 解析导入模块
-pub fn resolve_import(
-    resolver: &mut Resolver,
-    path_components: &[String],
-    span: &Span,
-) -> Vec<Decl> {
-    // 1. 构建路径
-    let mut path_buf = resolver.root_dir.clone();
-    if !path_components.is_empty() && path_components[0] == "std" {
-        path_buf.push("lib");
-        path_buf.push("std");
-        for comp in &path_components[1..] {
-            path_buf.push(comp);
-        }
-    } else {
-        for comp in path_components {
-            path_buf.push(comp);
-        }
-    }
-    path_buf.set_extension("lcy");
+/// 导入模块之间的依赖关系
+#[derive(Debug, Clone, Default)]
+pub struct ModuleDeps {
+    /// 模块路径 -> 该模块自身 import 的模块路径
+    pub imports: HashMap<String, Vec<String>>,
+    /// 为模块外的类型（如 `int`）提供 impl 的模块：是否用到无法从名称引用判断
+    pub extensions: HashSet<String>,
+}
 
-    // 2. 检查环形依赖
-    if resolver.visited_modules.contains(&path_buf) {
-        return Vec::new();
-    }
+/// 已加载的导入模块（Pass 2 后合并进入口程序）
+#[derive(Debug)]
+pub(crate) struct LoadedModule {
+    /// 模块路径（如 `std.io`）
+    pub module: String,
+    /// 模块源文件
+    pub file: PathBuf,
+    pub program: Program,
+    /// 声明来自缓存的分析结果，不再做 Pass 2 与之后的检查
+    pub reused: bool,
+}
 
-    // 3. 将当前模块加入栈
-    resolver.visited_modules.insert(path_buf.clone());
+/// 模块路径 -> 源文件路径
+fn module_file_path(resolver: &Resolver, path_components: &[String]) -> PathBuf {
+    find_module_source(&resolver.search_paths, path_components)
+}
 
-    // 4. 读取并解析文件
-    let source = match std::fs::read_to_string(&path_buf) {
+/// 读取并解析模块源文件，返回语法树与源码内容哈希（内容未变时复用缓存的语法树）
+fn load_module(resolver: &mut Resolver, path_buf: &PathBuf, span: &Span) -> Option<(Program, u64)> {
+    let source = match std::fs::read_to_string(path_buf) {
         Ok(s) => s,
         Err(e) => {
-            let err = SemanticError::ImportError {
+            resolver.errors.push(SemanticError::ImportError {
                 message: format!("failed to read file '{:?}': {}", path_buf, e),
                 span: span.clone(),
-            };
-            resolver.errors.push(err);
-            return Vec::new();
+            });
+            return None;
         }
     };
 
-    // 5. Parse
-    match lency_syntax::parser::parse(&source) {
+    let content_hash = stable_hash(&source);
+    let cache = resolver.module_cache.as_mut();
+    if let Some(prog) = cache
+        .as_ref()
+        .and_then(|c| c.program(path_buf, content_hash))
+    {
+        return Some((prog, content_hash));
+    }
+
+    match lency_syntax::parser::parse_spanned(&source) {
         Ok(prog) => {
-            // 6. 递归收集 (Pass 1)
-            let mut all_synthetics = Vec::new();
-            for decl in &prog.decls {
-                let mut synthetics = decl::collect_decl(resolver, decl);
-                all_synthetics.append(&mut synthetics);
+            if let Some(cache) = cache {
+                cache.store_program(path_buf, content_hash, prog.clone());
             }
-
-            // Store program
-            let mut prog = prog;
-            prog.decls.append(&mut all_synthetics);
-            resolver.loaded_programs.push(prog);
-
-            Vec::new()
+            Some((prog, content_hash))
         }
         Err(e) => {
-            let err = SemanticError::ImportError {
+            resolver.errors.push(SemanticError::ImportError {
                 message: format!("parse error in '{:?}': {:?}", path_buf, e),
                 span: span.clone(),
-            };
-            resolver.errors.push(err);
-            Vec::new()
+            });
+            None
         }
     }
 }
 
-/// 解析带别名的导入 `import path as alias`
+/// 解析 glob 导入 `import path.*`
+///
+/// 模块的导出名称直接进入全局作用域；若模块使用了 `pub`，
+/// 其私有名称会加上模块前缀，对导入方不可见。
+pub fn resolve_import(
+    resolver: &mut Resolver,
+    path_components: &[String],
+    span: &Span,
+) -> Vec<Decl> {
+    let path_buf = module_file_path(resolver, path_components);
+
+    // 检查环形依赖 / 重复导入
+    if resolver.visited_modules.contains(&path_buf) {
+        return Vec::new();
+    }
+    resolver.visited_modules.insert(path_buf.clone());
+
+    let Some((mut prog, content_hash)) = load_module(resolver, &path_buf, span) else {
+        return Vec::new();
+    };
+
+    // 模块内 import 绑定的模块名总是留在该模块的命名空间中
+    let prefix = namespace::module_prefix(path_components);
+    let explicit_exports = namespace::has_explicit_exports(&prog.decls);
+    let renames: HashMap<String, String> = prog
+        .decls
+        .iter()
+        .filter(|d| !matches!(d, Decl::ExternFunction { .. }))
+        .filter_map(namespace::decl_visibility)
+        .filter(|(_, is_pub)| explicit_exports && !is_pub)
+        .map(|(name, _)| name)
+        .chain(namespace::import_aliases(&prog.decls))
+        .map(|name| (name.to_string(), namespace::mangle_member(&prefix, name)))
+        .collect();
+    NameMangler::new(&renames).mangle_decls(&mut prog.decls);
+
+    let seed = stable_hash(&(content_hash, "glob"));
+    collect_module(resolver, prog, path_components, path_buf, seed);
+    Vec::new()
+}
+
+/// 解析绑定模块名的导入 `import path` / `import path as alias`
+///
+/// 模块的所有顶层名称都加上模块前缀，模块名（路径最后一段或别名）注册为模块符号，
+/// 通过 `alias.name` 访问（由 Resolver 改写为带前缀的全局名称）。
 pub fn resolve_import_as(
     resolver: &mut Resolver,
     path_components: &[String],
     alias: &str,
     span: &Span,
 ) -> Vec<Decl> {
-    // 1. 构建路径
-    let mut path_buf = resolver.root_dir.clone();
-    if !path_components.is_empty() && path_components[0] == "std" {
-        path_buf.push("lib");
-        path_buf.push("std");
-        for comp in &path_components[1..] {
-            path_buf.push(comp);
-        }
-    } else {
-        for comp in path_components {
-            path_buf.push(comp);
-        }
+    let path_buf = module_file_path(resolver, path_components);
+    let module_path = path_components.join(".");
+
+    // 同一模块只加载一次，其余别名共享成员表
+    if let Some(members) = resolver.namespaces.get(&path_buf).cloned() {
+        define_module_alias(resolver, alias, &module_path, members, span);
+        return Vec::new();
     }
-    path_buf.set_extension("lcy");
 
-    let already_loaded = resolver.visited_modules.contains(&path_buf);
+    let Some((mut prog, content_hash)) = load_module(resolver, &path_buf, span) else {
+        return Vec::new();
+    };
 
-    if !already_loaded {
-        resolver.visited_modules.insert(path_buf.clone());
+    let prefix = namespace::module_prefix(path_components);
+    let explicit_exports = namespace::has_explicit_exports(&prog.decls);
+    let mut members = HashMap::new();
+    let mut renames = HashMap::new();
+    for decl in &prog.decls {
+        let Some((name, is_pub)) = namespace::decl_visibility(decl) else {
+            continue;
+        };
+        // 外部函数对应链接符号，名称保持不变
+        let mangled_name = if matches!(decl, Decl::ExternFunction { .. }) {
+            name.to_string()
+        } else {
+            let mangled = namespace::mangle_member(&prefix, name);
+            renames.insert(name.to_string(), mangled.clone());
+            mangled
+        };
+        members.insert(
+            name.to_string(),
+            ModuleMember {
+                mangled_name,
+                is_pub: is_pub || !explicit_exports,
+            },
+        );
     }
 
-    let source = match std::fs::read_to_string(&path_buf) {
-        Ok(s) => s,
-        Err(e) => {
-            resolver.errors.push(SemanticError::ImportError {
-                message: format!("failed to read file '{:?}': {}", path_buf, e),
-                span: span.clone(),
-            });
-            return Vec::new();
-        }
-    };
+    // 模块内部的别名同样放入该模块的命名空间，不对外导出
+    for alias in namespace::import_aliases(&prog.decls) {
+        renames.insert(alias.to_string(), namespace::mangle_member(&prefix, alias));
+    }
 
-    match lency_syntax::parser::parse(&source) {
-        Ok(prog) => {
-            // Synthesize Wrapper
-            let mut synthetic_decls = Vec::new();
-            let struct_name = format!("{}__Module", alias);
+    // 先登记成员表，使循环导入也能解析到该模块
+    resolver
+        .namespaces
+        .insert(path_buf.clone(), members.clone());
+    define_module_alias(resolver, alias, &module_path, members, span);
 
-            // Struct
-            synthetic_decls.push(Decl::Struct {
-                span: span.clone(),
-                name: struct_name.clone(),
-                generic_params: Vec::new(),
-                fields: Vec::new(),
-            });
+    NameMangler::new(&renames).mangle_decls(&mut prog.decls);
+    let seed = stable_hash(&(content_hash, "namespace"));
+    collect_module(resolver, prog, path_components, path_buf, seed);
+    Vec::new()
+}
 
-            let mut methods = Vec::new();
-            let mut other_decls = Vec::new();
-
-            for decl in prog.decls {
-                match decl {
-                    Decl::Function {
-                        span,
-                        name,
-                        generic_params,
-                        params,
-                        return_type,
-                        body,
-                    } => {
-                        methods.push(Decl::Function {
-                            span,
-                            name,
-                            generic_params,
-                            params,
-                            return_type,
-                            body,
-                        });
-                    }
-                    _ => {
-                        if !already_loaded {
-                            other_decls.push(decl);
-                        }
-                    }
-                }
-            }
+/// 注册模块别名符号
+fn define_module_alias(
+    resolver: &mut Resolver,
+    alias: &str,
+    module_path: &str,
+    members: HashMap<String, ModuleMember>,
+    span: &Span,
+) {
+    let sym = ModuleSymbol::new(
+        alias.to_string(),
+        module_path.to_string(),
+        members,
+        span.clone(),
+    );
+    if let Err(e) = resolver.scopes.define(Symbol::Module(sym)) {
+        resolver.errors.push(e);
+    }
+}
 
-            // Impl
-            synthetic_decls.push(Decl::Impl {
-                span: span.clone(),
-                trait_ref: None,
-                type_name: Type::Struct(struct_name.clone()),
-                generic_params: Vec::new(),
-                methods,
-            });
+/// 收集模块的顶层声明 (Pass 1) 并保存模块 AST
+///
+/// `seed` 为源码内容与导入方式的哈希。分析键未变时用缓存中分析后的声明替换本模块的声明，
+/// 之后的 Pass 跳过它们。
+fn collect_module(
+    resolver: &mut Resolver,
+    mut prog: Program,
+    path_components: &[String],
+    file: PathBuf,
+    seed: u64,
+) {
+    // 同一模块以不同方式导入时，外部函数与私有定义只保留一份
+    prog.decls.retain(|d| match d {
+        Decl::ExternFunction { name, .. } => resolver.scopes.lookup(name).is_none(),
+        _ => match namespace::decl_visibility(d) {
+            Some((name, _)) if name.contains("::") => resolver.scopes.lookup(name).is_none(),
+            _ => true,
+        },
+    });
 
-            // Globals (if not loaded)
-            synthetic_decls.append(&mut other_decls);
+    // 记录进入全局作用域的名称来自哪个模块，用于冲突报错
+    let module_path = path_components.join(".");
+    for decl in &prog.decls {
+        if let Some((name, _)) = namespace::decl_visibility(decl) {
+            resolver
+                .module_origins
+                .entry(name.to_string())
+                .or_insert_with(|| module_path.clone());
+        }
+    }
 
-            // Global Var
-            synthetic_decls.push(Decl::Var {
-                span: span.clone(),
-                name: alias.to_string(),
-                ty: Some(Type::Struct(struct_name.clone())),
-                value: Expr {
-                    kind: ExprKind::StructLiteral {
-                        type_: Type::Struct(struct_name.clone()),
-                        fields: Vec::new(),
-                    },
-                    span: span.clone(),
-                },
-            });
+    record_module_deps(resolver, &prog.decls, &module_path);
+    // 在加载导入的模块之前登记接口，循环导入的模块也能据此计算分析键
+    resolver
+        .interfaces
+        .insert(module_path.clone(), interface_hash(&prog.decls));
 
-            // Recursively collect symbols for synthetics
-            let mut deep_synthetics = Vec::new();
-            for decl in &synthetic_decls {
-                let mut nested = decl::collect_decl(resolver, decl);
-                deep_synthetics.append(&mut nested);
+    // 收集时产生的错误位于本模块（嵌套导入的模块已先标记为各自的来源）
+    let mark = resolver.errors.len();
+    let mut synthetics = collect_imports(resolver, &prog.decls);
+    let mut reused = false;
+    if resolver.module_cache.is_some() {
+        let key = analysis_key(resolver, seed, &module_path, &prog.decls);
+        if let Some(cache) = resolver.module_cache.as_mut() {
+            match cache.analysis(&file, key) {
+                Some(decls) => {
+                    prog.decls = decls;
+                    reused = true;
+                }
+                None => cache.mark_analyzed(&file, key),
             }
+        }
+    }
+    synthetics.append(&mut collect_definitions(resolver, &prog.decls));
+    prog.decls.append(&mut synthetics);
+    for error in &mut resolver.errors[mark..] {
+        *error = error.clone().in_module(&module_path, &file);
+    }
 
-            synthetic_decls.append(&mut deep_synthetics);
+    resolver.loaded_programs.push(LoadedModule {
+        module: module_path,
+        file,
+        program: prog,
+        reused,
+    });
+}
 
-            synthetic_decls
-        }
-        Err(e) => {
-            resolver.errors.push(SemanticError::ImportError {
-                message: format!("parse error in '{:?}': {:?}", path_buf, e),
-                span: span.clone(),
-            });
-            Vec::new()
+/// 模块分析结果的缓存键：源码与导入方式、保留下来的顶层名称，
+/// 以及它（传递）导入的全部模块的接口
+fn analysis_key(resolver: &Resolver, seed: u64, module_path: &str, decls: &[Decl]) -> u64 {
+    let mut hasher = StableHasher::new();
+    seed.hash(&mut hasher);
+    module_path.hash(&mut hasher);
+    for decl in decls {
+        namespace::decl_visibility(decl)
+            .map(|(name, _)| name)
+            .hash(&mut hasher);
+    }
+
+    let mut imported = BTreeSet::new();
+    let mut pending = vec![module_path];
+    while let Some(module) = pending.pop() {
+        for dep in resolver
+            .module_deps
+            .imports
+            .get(module)
+            .into_iter()
+            .flatten()
+        {
+            if imported.insert(dep.as_str()) {
+                pending.push(dep);
+            }
         }
     }
+    for module in imported {
+        module.hash(&mut hasher);
+        resolver.interfaces.get(module).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// 记录模块的 import 以及是否扩展了模块外的类型
+fn record_module_deps(resolver: &mut Resolver, decls: &[Decl], module_path: &str) {
+    let imports = decls
+        .iter()
+        .flat_map(|d| match d {
+            Decl::Import { items, .. } => items.iter().map(|item| item.path.join(".")).collect(),
+            _ => Vec::new(),
+        })
+        .collect();
+    resolver
+        .module_deps
+        .imports
+        .insert(module_path.to_string(), imports);
+
+    let own_names: HashSet<&str> = decls
+        .iter()
+        .filter_map(namespace::decl_visibility)
+        .map(|(name, _)| name)
+        .collect();
+    let extends_foreign = decls.iter().any(|d| match d {
+        Decl::Impl { type_name, .. } => match type_name {
+            Type::Struct(name) | Type::Generic(name, _) => !own_names.contains(name.as_str()),
+            _ => true,
+        },
+        _ => false,
+    });
+    if extends_foreign {
+        resolver
+            .module_deps
+            .extensions
+            .insert(module_path.to_string());
+    }
+}
+
+/// 在 Pass 1 中收集一组声明：先处理 import（注册模块别名），再收集其余声明，
+/// 保证签名中的限定类型（`io.File`）能解析到模块成员
+pub fn collect_decls(resolver: &mut Resolver, decls: &[Decl]) -> Vec<Decl> {
+    let mut synthetics = collect_imports(resolver, decls);
+    synthetics.append(&mut collect_definitions(resolver, decls));
+    synthetics
+}
+
+fn collect_imports(resolver: &mut Resolver, decls: &[Decl]) -> Vec<Decl> {
+    decls
+        .iter()
+        .filter(|d| matches!(d, Decl::Import { .. }))
+        .flat_map(|decl| resolver.collect_decl(decl))
+        .collect()
+}
+
+fn collect_definitions(resolver: &mut Resolver, decls: &[Decl]) -> Vec<Decl> {
+    decls
+        .iter()
+        .filter(|d| !matches!(d, Decl::Import { .. }))
+        .flat_map(|decl| resolver.collect_decl(decl))
+        .collect()
+}
+
+/// 查找模块成员 `module.member`，返回其在全局符号表中的名称
+pub fn module_member(
+    resolver: &Resolver,
+    module: &str,
+    member: &str,
+    span: &Span,
+) -> Result<String, SemanticError> {
+    let Some(Symbol::Module(module_sym)) = resolver.scopes.lookup(module) else {
+        return Err(SemanticError::UndefinedVariable {
+            name: module.to_string(),
+            span: span.clone(),
+            suggestion: None,
+        });
+    };
+    match module_sym.get_member(member) {
+        Some(m) if m.is_pub => Ok(m.mangled_name.clone()),
+        Some(_) => Err(SemanticError::PrivateModuleMember {
+            module: module_sym.path.clone(),
+            name: member.to_string(),
+            span: span.clone(),
+        }),
+        None => Err(SemanticError::UndefinedModuleMember {
+            module: module_sym.path.clone(),
+            name: member.to_string(),
+            span: span.clone(),
+        }),
+    }
 }
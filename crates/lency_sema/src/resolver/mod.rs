@@ -8,13 +8,19 @@ pub mod decl;
 mod decl_impl;
 pub mod expr;
 mod imports;
+pub mod namespace;
+pub mod search_path;
 pub mod stmt;
 mod types;
 
+pub use imports::ModuleDeps;
+
 use crate::error::SemanticError;
+use crate::module_cache::ModuleCache;
+use crate::module_source::{ModuleSource, ModuleSources};
 use crate::scope::ScopeStack;
 use crate::symbol::Symbol;
-use lency_syntax::ast::{Decl, Expr, Program, Span, Stmt, Type};
+use lency_syntax::ast::{Decl, Expr, MatchPattern, Program, Span, Stmt, Type};
 
 /// 名称解析器
 pub struct Resolver {
@@ -23,9 +29,26 @@ pub struct Resolver {
     /// 已访问的模块路径（防止循环导入）
     pub(crate) visited_modules: std::collections::HashSet<std::path::PathBuf>,
     /// 已加载的程序模块（保持 AST 所有权）
-    pub(crate) loaded_programs: Vec<Program>,
-    /// 项目根目录
-    pub(crate) root_dir: std::path::PathBuf,
+    pub(crate) loaded_programs: Vec<imports::LoadedModule>,
+    /// import 模块的搜索路径（按顺序查找）
+    pub(crate) search_paths: Vec<std::path::PathBuf>,
+    /// 以命名空间方式加载的模块（模块文件 -> 成员表）
+    pub(crate) namespaces: std::collections::HashMap<
+        std::path::PathBuf,
+        std::collections::HashMap<String, crate::symbol::ModuleMember>,
+    >,
+    /// 由导入模块引入全局作用域的名称 -> 模块路径（用于冲突报错）
+    pub(crate) module_origins: std::collections::HashMap<String, String>,
+    /// 导入模块之间的依赖（供 unused-import lint 使用）
+    pub(crate) module_deps: ModuleDeps,
+    /// 入口源文件的声明数量（resolve 后 program.decls 中其后为导入模块的声明）
+    pub(crate) source_decl_count: usize,
+    /// 导入模块在合并后的 program.decls 中的区间
+    pub(crate) module_sources: ModuleSources,
+    /// 导入模块的解析与分析缓存
+    pub(crate) module_cache: Option<ModuleCache>,
+    /// 已加载模块的接口哈希（模块路径 -> 哈希），用于计算分析键
+    pub(crate) interfaces: std::collections::HashMap<String, u64>,
 }
 
 impl Resolver {
@@ -50,8 +73,8 @@ impl Resolver {
         let result_symbol = Symbol::Enum(crate::symbol::EnumSymbol {
             name: "Result".to_string(),
             generic_params: vec![
-                crate::symbol::GenericParamSymbol::new("T".to_string(), None, dummy_span.clone()),
-                crate::symbol::GenericParamSymbol::new("E".to_string(), None, dummy_span.clone()),
+                crate::symbol::GenericParamSymbol::new("T".to_string(), vec![], dummy_span.clone()),
+                crate::symbol::GenericParamSymbol::new("E".to_string(), vec![], dummy_span.clone()),
             ],
             variants: std::collections::HashMap::new(), // Ok and Err are handled by compiler
             methods: std::collections::HashMap::new(),  // Will be populated by user impl
@@ -67,13 +90,38 @@ impl Resolver {
             errors: Vec::new(),
             visited_modules: std::collections::HashSet::new(),
             loaded_programs: Vec::new(),
-            root_dir: std::env::current_dir().unwrap_or_default(),
+            search_paths: vec![std::env::current_dir().unwrap_or_default()],
+            namespaces: std::collections::HashMap::new(),
+            module_origins: std::collections::HashMap::new(),
+            module_deps: ModuleDeps::default(),
+            source_decl_count: 0,
+            module_sources: ModuleSources::new(),
+            module_cache: None,
+            interfaces: std::collections::HashMap::new(),
         }
     }
 
-    /// 设置项目根目录
+    /// 设置项目根目录（唯一的搜索路径）
     pub fn set_root_dir(&mut self, path: std::path::PathBuf) {
-        self.root_dir = path;
+        self.search_paths = vec![path];
+    }
+
+    /// 设置 import 模块的搜索路径；为空时保持默认（当前目录）
+    pub fn set_search_paths(&mut self, paths: Vec<std::path::PathBuf>) {
+        if !paths.is_empty() {
+            self.search_paths = paths;
+        }
+    }
+
+    /// 使用模块缓存：内容未变的模块不再解析，分析键未变的模块复用分析结果
+    pub fn set_module_cache(&mut self, mut cache: ModuleCache) {
+        cache.start();
+        self.module_cache = Some(cache);
+    }
+
+    /// 取回模块缓存
+    pub fn take_module_cache(&mut self) -> Option<ModuleCache> {
+        self.module_cache.take()
     }
 
     /// Normalize types (delegated to types.rs)
@@ -81,6 +129,11 @@ impl Resolver {
         types::normalize_type(self, ty);
     }
 
+    /// 解析结构体模式中的模块限定名 (delegated to types.rs)
+    pub(crate) fn qualify_pattern(&self, pattern: &mut MatchPattern) {
+        types::qualify_pattern(self, pattern);
+    }
+
     /// Normalize types with generics (delegated to types.rs)
     pub fn normalize_type_with_generics(
         &mut self,
@@ -93,11 +146,7 @@ impl Resolver {
     /// 解析整个程序
     pub fn resolve(&mut self, program: &mut Program) -> Result<(), Vec<SemanticError>> {
         // Pass 1: 收集顶层声明
-        let mut synthetics_to_add = Vec::new();
-        for decl in &program.decls {
-            let mut synthetics = self.collect_decl(decl);
-            synthetics_to_add.append(&mut synthetics);
-        }
+        let mut synthetics_to_add = self.collect_decls(&program.decls);
         program.decls.append(&mut synthetics_to_add);
 
         // Pass 2: 解析函数体 (Main Program)
@@ -105,18 +154,48 @@ impl Resolver {
             self.resolve_decl(decl);
         }
 
-        // Merge loaded modules into program
-        let mut all_loaded_decls: Vec<Decl> = Vec::new();
-        for mut prog in std::mem::take(&mut self.loaded_programs) {
-            all_loaded_decls.append(&mut prog.decls);
-        }
+        // Merge loaded modules into program, running Pass 2 on each module's decls
+        self.source_decl_count = program.decls.len();
+        for mut loaded in std::mem::take(&mut self.loaded_programs) {
+            let mark = self.errors.len();
+            for decl in &mut loaded.program.decls {
+                // 复用分析结果的模块只需把 impl 方法登记到目标类型
+                if loaded.reused {
+                    decl_impl::register_impl(self, decl, false);
+                } else {
+                    self.resolve_decl(decl);
+                }
+            }
+            for error in &mut self.errors[mark..] {
+                *error = error.clone().in_module(&loaded.module, &loaded.file);
+            }
 
-        // Run Pass 2 on loaded decls
-        for decl in &mut all_loaded_decls {
-            self.resolve_decl(decl);
+            let start = program.decls.len();
+            program.decls.append(&mut loaded.program.decls);
+            self.module_sources.push(ModuleSource {
+                module: loaded.module,
+                file: loaded.file,
+                decls: start..program.decls.len(),
+                reused: loaded.reused,
+            });
         }
 
-        program.decls.append(&mut all_loaded_decls);
+        // 与导入名称冲突的重复定义：指明冲突来自哪个模块
+        for err in self.errors.iter_mut() {
+            let err = match err {
+                SemanticError::InModule { error, .. } => error.as_mut(),
+                err => err,
+            };
+            if let SemanticError::DuplicateDefinition { name, span, .. } = err {
+                if let Some(module) = self.module_origins.get(name.as_str()) {
+                    *err = SemanticError::ImportConflict {
+                        name: name.clone(),
+                        module: module.clone(),
+                        span: span.clone(),
+                    };
+                }
+            }
+        }
 
         if self.errors.is_empty() {
             Ok(())
@@ -144,12 +223,33 @@ impl Resolver {
         imports::resolve_import_as(self, path_components, alias, span)
     }
 
+    /// 解析模块成员访问 `module.member`，失败时记录错误 (delegated to imports.rs)
+    pub(crate) fn resolve_module_member(
+        &mut self,
+        module: &str,
+        member: &str,
+        span: &Span,
+    ) -> Option<String> {
+        match imports::module_member(self, module, member, span) {
+            Ok(name) => Some(name),
+            Err(e) => {
+                self.errors.push(e);
+                None
+            }
+        }
+    }
+
     // --- Delegation ---
 
     pub(crate) fn collect_decl(&mut self, decl: &Decl) -> Vec<Decl> {
         decl::collect_decl(self, decl)
     }
 
+    /// 收集一组顶层声明，import 优先 (delegated to imports.rs)
+    pub(crate) fn collect_decls(&mut self, decls: &[Decl]) -> Vec<Decl> {
+        imports::collect_decls(self, decls)
+    }
+
     pub(crate) fn resolve_decl(&mut self, decl: &mut Decl) {
         decl::resolve_decl(self, decl);
     }
@@ -173,6 +273,26 @@ impl Resolver {
         &self.scopes
     }
 
+    /// 导入模块引入全局作用域的名称 -> 模块路径
+    pub fn module_origins(&self) -> &std::collections::HashMap<String, String> {
+        &self.module_origins
+    }
+
+    /// 导入模块的依赖信息
+    pub fn module_deps(&self) -> &ModuleDeps {
+        &self.module_deps
+    }
+
+    /// 入口源文件的声明数量
+    pub fn source_decl_count(&self) -> usize {
+        self.source_decl_count
+    }
+
+    /// 导入模块在合并后的 program.decls 中的区间
+    pub fn module_sources(&self) -> &ModuleSources {
+        &self.module_sources
+    }
+
     pub fn into_scopes(self) -> ScopeStack {
         self.scopes
     }
@@ -0,0 +1,544 @@
+//! Module Namespaces
+//!
+//! 模块命名空间：导入的模块仍然合并到全局符号表中，但其顶层名称会被改写为
+//! 带模块前缀的唯一名称（`std.io` 中的 `File` -> `std::io::File`），
+//! 因此不同模块的同名定义互不冲突，后续 Pass（类型检查、单态化、代码生成）
+//! 也无需感知模块的存在。
+//!
+//! - `import std.io` / `import std.io as m`：模块的全部顶层名称都加前缀，
+//!   通过 `io.xxx` / `m.xxx` 访问
+//! - `import std.io.*`：只有非 `pub` 名称加前缀（模块没有任何 `pub` 声明时全部导出）
+
+use lency_syntax::ast::{Decl, Expr, ExprKind, MatchPattern, Stmt, Type};
+use std::collections::{HashMap, HashSet};
+
+pub use super::search_path::module_source_path;
+
+/// 模块路径 -> 名称前缀：["std", "io"] -> "std::io"
+pub fn module_prefix(path: &[String]) -> String {
+    path.join("::")
+}
+
+/// 带前缀的成员名：("std::io", "File") -> "std::io::File"
+pub fn mangle_member(prefix: &str, name: &str) -> String {
+    format!("{}::{}", prefix, name)
+}
+
+/// 拆分模块限定名：`io.File` -> ("io", "File")
+pub fn split_qualified(name: &str) -> Option<(&str, &str)> {
+    name.rsplit_once('.')
+}
+
+/// 模块是否使用了显式导出（至少有一个 `pub` 声明）
+pub fn has_explicit_exports(decls: &[Decl]) -> bool {
+    decls
+        .iter()
+        .any(|d| decl_visibility(d).is_some_and(|(_, is_pub)| is_pub))
+}
+
+/// 顶层声明的名称与可见性（impl / import 没有可导出的名称）
+pub fn decl_visibility(decl: &Decl) -> Option<(&str, bool)> {
+    match decl {
+        Decl::Function { name, is_pub, .. }
+        | Decl::ExternFunction { name, is_pub, .. }
+        | Decl::Struct { name, is_pub, .. }
+        | Decl::Trait { name, is_pub, .. }
+        | Decl::Enum { name, is_pub, .. }
+        | Decl::Var { name, is_pub, .. } => Some((name, *is_pub)),
+        Decl::Impl { .. } | Decl::Import { .. } => None,
+    }
+}
+
+/// 模块内 import 绑定的模块名（`import a.x` / `import a.y as z`）
+pub fn import_aliases(decls: &[Decl]) -> impl Iterator<Item = &str> {
+    decls.iter().flat_map(|d| match d {
+        Decl::Import { items, .. } => items
+            .iter()
+            .filter_map(|item| item.binding())
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    })
+}
+
+/// 顶层名称改写器
+///
+/// 将模块内对顶层名称的定义和引用统一替换为带前缀的名称。
+/// 局部变量、参数和泛型参数会遮蔽同名的顶层名称，不做替换。
+pub struct NameMangler<'a> {
+    renames: &'a HashMap<String, String>,
+    /// 局部变量作用域
+    locals: Vec<HashSet<String>>,
+    /// 当前声明的泛型参数
+    generics: Vec<String>,
+}
+
+impl<'a> NameMangler<'a> {
+    pub fn new(renames: &'a HashMap<String, String>) -> Self {
+        Self {
+            renames,
+            locals: Vec::new(),
+            generics: Vec::new(),
+        }
+    }
+
+    pub fn mangle_decls(&mut self, decls: &mut [Decl]) {
+        for decl in decls {
+            self.mangle_decl(decl, true);
+        }
+    }
+
+    fn rename(&self, name: &mut String) {
+        if let Some(new_name) = self.renames.get(name.as_str()) {
+            *name = new_name.clone();
+        }
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.locals.iter().rev().any(|scope| scope.contains(name))
+    }
+
+    fn define_local(&mut self, name: &str) {
+        if let Some(scope) = self.locals.last_mut() {
+            scope.insert(name.to_string());
+        }
+    }
+
+    fn mangle_decl(&mut self, decl: &mut Decl, top_level: bool) {
+        match decl {
+            Decl::Function {
+                name,
+                generic_params,
+                params,
+                return_type,
+                body,
+                ..
+            } => {
+                if top_level {
+                    self.rename(name);
+                }
+                let saved = self.generics.len();
+                self.generics
+                    .extend(generic_params.iter().map(|gp| gp.name.clone()));
+                for gp in generic_params.iter_mut() {
+                    for bound in &mut gp.bounds {
+                        self.mangle_type(bound);
+                    }
+                }
+                self.locals.push(HashSet::new());
+                for param in params.iter_mut() {
+                    self.mangle_type(&mut param.ty);
+                    self.define_local(&param.name);
+                }
+                self.mangle_type(return_type);
+                self.mangle_block(body);
+                self.locals.pop();
+                self.generics.truncate(saved);
+            }
+            Decl::ExternFunction {
+                generic_params,
+                params,
+                return_type,
+                ..
+            } => {
+                // 外部函数名对应链接符号，保持不变
+                let saved = self.generics.len();
+                self.generics
+                    .extend(generic_params.iter().map(|gp| gp.name.clone()));
+                for param in params.iter_mut() {
+                    self.mangle_type(&mut param.ty);
+                }
+                self.mangle_type(return_type);
+                self.generics.truncate(saved);
+            }
+            Decl::Struct {
+                name,
+                generic_params,
+                fields,
+                ..
+            } => {
+                self.rename(name);
+                let saved = self.generics.len();
+                self.generics
+                    .extend(generic_params.iter().map(|gp| gp.name.clone()));
+                for field in fields.iter_mut() {
+                    self.mangle_type(&mut field.ty);
+                }
+                self.generics.truncate(saved);
+            }
+            Decl::Impl {
+                trait_ref,
+                type_name,
+                generic_params,
+                methods,
+                ..
+            } => {
+                let saved = self.generics.len();
+                self.generics
+                    .extend(generic_params.iter().map(|gp| gp.name.clone()));
+                if let Some(trait_ty) = trait_ref {
+                    self.mangle_type(trait_ty);
+                }
+                self.mangle_type(type_name);
+                for method in methods.iter_mut() {
+                    self.mangle_decl(method, false);
+                }
+                self.generics.truncate(saved);
+            }
+            Decl::Trait {
+                name,
+                generic_params,
+                methods,
+                ..
+            } => {
+                self.rename(name);
+                let saved = self.generics.len();
+                self.generics
+                    .extend(generic_params.iter().map(|gp| gp.name.clone()));
+                for method in methods.iter_mut() {
+                    for param in method.params.iter_mut() {
+                        self.mangle_type(&mut param.ty);
+                    }
+                    self.mangle_type(&mut method.return_type);
+                }
+                self.generics.truncate(saved);
+            }
+            Decl::Enum {
+                name,
+                generic_params,
+                variants,
+                ..
+            } => {
+                self.rename(name);
+                let saved = self.generics.len();
+                self.generics
+                    .extend(generic_params.iter().map(|gp| gp.name.clone()));
+                for variant in variants.iter_mut() {
+                    if let lency_syntax::ast::EnumVariant::Tuple(_, types) = variant {
+                        for ty in types.iter_mut() {
+                            self.mangle_type(ty);
+                        }
+                    }
+                }
+                self.generics.truncate(saved);
+            }
+            Decl::Var {
+                name, ty, value, ..
+            } => {
+                self.rename(name);
+                if let Some(ty) = ty {
+                    self.mangle_type(ty);
+                }
+                self.mangle_expr(value);
+            }
+            Decl::Import { items, .. } => {
+                // 模块内绑定的模块名同样属于该模块的命名空间
+                for item in items.iter_mut() {
+                    if let Some(binding) = item.binding() {
+                        let mut binding = binding.to_string();
+                        self.rename(&mut binding);
+                        item.alias = Some(binding);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 类型名（可能带模块限定）的改写
+    fn rename_type_name(&self, name: &mut String) {
+        if let Some((qualifier, member)) = split_qualified(name) {
+            // 限定名只改写模块别名部分：m.File -> std::io::m.File
+            if let Some(new_qualifier) = self.renames.get(qualifier) {
+                *name = format!("{}.{}", new_qualifier, member);
+            }
+        } else {
+            self.rename(name);
+        }
+    }
+
+    /// 模式中的结构体名与类型名一样改写（变体名由被匹配值的类型决定，保持不变）
+    fn mangle_pattern(&mut self, pattern: &mut MatchPattern) {
+        pattern.walk_mut(&mut |pattern| {
+            if let MatchPattern::Struct { name, .. } = pattern {
+                self.rename_type_name(name);
+            }
+        });
+    }
+
+    pub fn mangle_type(&mut self, ty: &mut Type) {
+        match ty {
+            Type::Struct(name) | Type::Generic(name, _) | Type::TraitObject(name) => {
+                if !self.generics.contains(name) {
+                    self.rename_type_name(name);
+                }
+                if let Type::Generic(_, args) = ty {
+                    for arg in args.iter_mut() {
+                        self.mangle_type(arg);
+                    }
+                }
+            }
+            Type::Vec(inner) | Type::Nullable(inner) => self.mangle_type(inner),
+            Type::Array { element_type, .. } => self.mangle_type(element_type),
+            Type::Result { ok_type, err_type } => {
+                self.mangle_type(ok_type);
+                self.mangle_type(err_type);
+            }
+            Type::Function {
+                param_types,
+                return_type,
+            } => {
+                for param in param_types.iter_mut() {
+                    self.mangle_type(param);
+                }
+                self.mangle_type(return_type);
+            }
+            _ => {}
+        }
+    }
+
+    fn mangle_block(&mut self, stmts: &mut [Stmt]) {
+        self.locals.push(HashSet::new());
+        for stmt in stmts {
+            self.mangle_stmt(stmt);
+        }
+        self.locals.pop();
+    }
+
+    fn mangle_stmt(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::VarDecl {
+                name, ty, value, ..
+            } => {
+                if let Some(ty) = ty {
+                    self.mangle_type(ty);
+                }
+                self.mangle_expr(value);
+                self.define_local(name);
+            }
+            Stmt::VarPattern { pattern, value, .. } => {
+                self.mangle_expr(value);
+                self.mangle_pattern(pattern);
+                self.define_pattern_locals(pattern);
+            }
+            Stmt::Assignment { target, value, .. } => {
+                self.mangle_expr(target);
+                self.mangle_expr(value);
+            }
+            Stmt::Expression(expr) => self.mangle_expr(expr),
+            Stmt::Block(stmts) => self.mangle_block(stmts),
+            Stmt::IfLet {
+                pattern,
+                value,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.mangle_expr(value);
+                self.mangle_pattern(pattern);
+                self.locals.push(HashSet::new());
+                self.define_pattern_locals(pattern);
+                self.mangle_block(then_block);
+                self.locals.pop();
+                if let Some(else_block) = else_block {
+                    self.mangle_block(else_block);
+                }
+            }
+            Stmt::WhileLet {
+                pattern,
+                value,
+                body,
+                ..
+            } => {
+                self.mangle_expr(value);
+                self.mangle_pattern(pattern);
+                self.locals.push(HashSet::new());
+                self.define_pattern_locals(pattern);
+                self.mangle_block(body);
+                self.locals.pop();
+            }
+            Stmt::If {
+                condition,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.mangle_expr(condition);
+                self.mangle_block(then_block);
+                if let Some(else_block) = else_block {
+                    self.mangle_block(else_block);
+                }
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                self.mangle_expr(condition);
+                self.mangle_block(body);
+            }
+            Stmt::For {
+                init,
+                condition,
+                update,
+                body,
+                ..
+            } => {
+                self.locals.push(HashSet::new());
+                if let Some(init) = init {
+                    self.mangle_stmt(init);
+                }
+                if let Some(condition) = condition {
+                    self.mangle_expr(condition);
+                }
+                if let Some(update) = update {
+                    self.mangle_stmt(update);
+                }
+                self.mangle_block(body);
+                self.locals.pop();
+            }
+            Stmt::ForIn {
+                iterator,
+                iterable,
+                body,
+                ..
+            } => {
+                self.mangle_expr(iterable);
+                self.locals.push(HashSet::new());
+                self.define_local(iterator);
+                self.mangle_block(body);
+                self.locals.pop();
+            }
+            Stmt::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.mangle_expr(value);
+                }
+            }
+            Stmt::Break { .. } | Stmt::Continue { .. } => {}
+        }
+    }
+
+    fn mangle_expr(&mut self, expr: &mut Expr) {
+        match &mut expr.kind {
+            ExprKind::Variable(name) => {
+                if !self.is_local(name) {
+                    self.rename(name);
+                }
+            }
+            ExprKind::Literal(_) | ExprKind::Unit | ExprKind::Error => {}
+            ExprKind::Binary(left, _, right) => {
+                self.mangle_expr(left);
+                self.mangle_expr(right);
+            }
+            ExprKind::Call { callee, args } => {
+                self.mangle_expr(callee);
+                for arg in args {
+                    self.mangle_expr(arg);
+                }
+            }
+            ExprKind::Get { object, .. } | ExprKind::SafeGet { object, .. } => {
+                self.mangle_expr(object)
+            }
+            ExprKind::Array(elements) | ExprKind::VecLiteral(elements) => {
+                for elem in elements {
+                    self.mangle_expr(elem);
+                }
+            }
+            ExprKind::Match {
+                value,
+                cases,
+                default,
+            } => {
+                self.mangle_expr(value);
+                for case in cases {
+                    self.mangle_pattern(&mut case.pattern);
+                    self.locals.push(HashSet::new());
+                    self.define_pattern_locals(&case.pattern);
+                    if let Some(guard) = &mut case.guard {
+                        self.mangle_expr(guard);
+                    }
+                    self.mangle_expr(&mut case.body);
+                    self.locals.pop();
+                }
+                if let Some(default) = default {
+                    self.mangle_expr(default);
+                }
+            }
+            ExprKind::Index { array, index } => {
+                self.mangle_expr(array);
+                self.mangle_expr(index);
+            }
+            ExprKind::Range { start, end, .. } => {
+                self.mangle_expr(start);
+                self.mangle_expr(end);
+            }
+            ExprKind::GenericInstantiation { base, args } => {
+                self.mangle_expr(base);
+                for ty in args {
+                    self.mangle_type(ty);
+                }
+            }
+            ExprKind::StructLiteral { type_, fields } => {
+                self.mangle_type(type_);
+                for (_, value) in fields {
+                    self.mangle_expr(value);
+                }
+            }
+            ExprKind::Closure {
+                params,
+                body,
+                captures,
+                return_type,
+            } => {
+                self.locals.push(HashSet::new());
+                for param in params.iter_mut() {
+                    self.mangle_type(&mut param.ty);
+                    self.define_local(&param.name);
+                }
+                for capture in captures.iter_mut() {
+                    self.mangle_type(&mut capture.ty);
+                }
+                if let Some(ty) = return_type {
+                    self.mangle_type(ty);
+                }
+                self.mangle_expr(body);
+                self.locals.pop();
+            }
+            ExprKind::Unary(_, inner)
+            | ExprKind::Print(inner)
+            | ExprKind::Try(inner)
+            | ExprKind::Ok(inner)
+            | ExprKind::Err(inner)
+            | ExprKind::ReadFile(inner)
+            | ExprKind::Len(inner)
+            | ExprKind::Trim(inner)
+            | ExprKind::CharToString(inner)
+            | ExprKind::Panic(inner) => self.mangle_expr(inner),
+            ExprKind::CoerceDyn { value, trait_name } => {
+                self.rename(trait_name);
+                self.mangle_expr(value);
+            }
+            ExprKind::Cast { value, target } => {
+                self.mangle_type(target);
+                self.mangle_expr(value);
+            }
+            ExprKind::WriteFile(a, b)
+            | ExprKind::Split(a, b)
+            | ExprKind::Join(a, b)
+            | ExprKind::Format(a, b) => {
+                self.mangle_expr(a);
+                self.mangle_expr(b);
+            }
+            ExprKind::Substr(a, b, c) => {
+                self.mangle_expr(a);
+                self.mangle_expr(b);
+                self.mangle_expr(c);
+            }
+        }
+    }
+
+    fn define_pattern_locals(&mut self, pattern: &MatchPattern) {
+        for name in pattern.bindings() {
+            self.define_local(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,103 @@
+//! Tests for namespace module
+
+use super::*;
+
+fn mangle(source: &str, names: &[&str]) -> Vec<Decl> {
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let renames: HashMap<String, String> = names
+        .iter()
+        .map(|n| (n.to_string(), mangle_member("geo", n)))
+        .collect();
+    NameMangler::new(&renames).mangle_decls(&mut program.decls);
+    program.decls
+}
+
+#[test]
+fn test_mangle_renames_definitions_and_references() {
+    let decls = mangle(
+        "struct Rect { int w } int area(Rect r) { return helper(r.w) } int helper(int n) { return n }",
+        &["Rect", "area", "helper"],
+    );
+
+    match &decls[1] {
+        Decl::Function {
+            name, params, body, ..
+        } => {
+            assert_eq!(name, "geo::area");
+            assert_eq!(params[0].ty, Type::Struct("geo::Rect".to_string()));
+            let Stmt::Return {
+                value: Some(value), ..
+            } = &body[0]
+            else {
+                panic!("expected return");
+            };
+            let ExprKind::Call { callee, .. } = &value.kind else {
+                panic!("expected call");
+            };
+            assert_eq!(callee.kind, ExprKind::Variable("geo::helper".to_string()));
+        }
+        _ => panic!("expected function"),
+    }
+}
+
+#[test]
+fn test_mangle_respects_local_shadowing() {
+    let decls = mangle(
+        "int helper(int n) { return n } int main() { var helper = 1 return helper }",
+        &["helper", "main"],
+    );
+
+    let Decl::Function { body, .. } = &decls[1] else {
+        panic!("expected function");
+    };
+    let Stmt::Return {
+        value: Some(value), ..
+    } = &body[1]
+    else {
+        panic!("expected return");
+    };
+    assert_eq!(value.kind, ExprKind::Variable("helper".to_string()));
+}
+
+#[test]
+fn test_mangle_renames_struct_patterns() {
+    let decls = mangle(
+        "struct Rect { int w  int h } int f(Rect r) { var Rect { w, h } = r return match r { case Rect { w: 0 } | Rect { h: 0 } => 0, case _ => w * h } }",
+        &["Rect", "f"],
+    );
+
+    let Decl::Function { body, .. } = &decls[1] else {
+        panic!("expected function");
+    };
+    let Stmt::VarPattern { pattern, .. } = &body[0] else {
+        panic!("expected var pattern");
+    };
+    let Stmt::Return {
+        value: Some(value), ..
+    } = &body[1]
+    else {
+        panic!("expected return");
+    };
+    let ExprKind::Match { cases, .. } = &value.kind else {
+        panic!("expected match");
+    };
+    let mut names = Vec::new();
+    for pattern in [pattern, &cases[0].pattern] {
+        pattern.walk(&mut |p| {
+            if let MatchPattern::Struct { name, .. } = p {
+                names.push(name.clone());
+            }
+        });
+    }
+    assert_eq!(names, ["geo::Rect", "geo::Rect", "geo::Rect"]);
+}
+
+#[test]
+fn test_has_explicit_exports() {
+    let program = lency_syntax::parser::parse("int a() { return 1 } pub int b() { return 2 }")
+        .expect("parse failed");
+    assert!(has_explicit_exports(&program.decls));
+
+    let program = lency_syntax::parser::parse("int a() { return 1 }").expect("parse failed");
+    assert!(!has_explicit_exports(&program.decls));
+}
@@ -0,0 +1,44 @@
+//! Module Search Paths
+//!
+//! 模块路径 -> 源文件路径。`import a.b` 在每个搜索路径下查找 `a/b.lcy`，
+//! 随编译器分发的库（`std.*`、`test.*`）映射到搜索路径下的 `lib/std`、`lib/test`。单文件编译时唯一的搜索路径是当前目录；
+//! 包（`lency.toml`）的搜索路径为源码根目录加上额外的库路径。
+
+use std::path::{Path, PathBuf};
+
+/// 位于 `lib/` 下、随编译器分发的库
+const BUNDLED_LIBRARIES: &[&str] = &["std", "test"];
+
+/// 模块路径 -> `root_dir` 下的源文件路径（`std.*` 映射到 `lib/std`，`test.*` 映射到 `lib/test`）
+pub fn module_source_path(root_dir: &Path, path: &[String]) -> PathBuf {
+    let mut path_buf = root_dir.to_path_buf();
+    if path
+        .first()
+        .is_some_and(|first| BUNDLED_LIBRARIES.contains(&first.as_str()))
+    {
+        path_buf.push("lib");
+        path_buf.extend(path);
+    } else {
+        path_buf.extend(path);
+    }
+    path_buf.set_extension("lcy");
+    path_buf
+}
+
+/// 按顺序在搜索路径中查找模块，返回第一个存在的源文件
+///
+/// 都不存在时返回第一个搜索路径下的候选路径，由读取文件时报告错误。
+pub fn find_module_source(search_paths: &[PathBuf], path: &[String]) -> PathBuf {
+    let candidates = || {
+        search_paths
+            .iter()
+            .map(|root| module_source_path(root, path))
+    };
+    candidates()
+        .find(|candidate| candidate.is_file())
+        .or_else(|| candidates().next())
+        .unwrap_or_else(|| module_source_path(Path::new(""), path))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,46 @@
+//! Tests for search_path module
+
+use super::*;
+
+fn components(path: &str) -> Vec<String> {
+    path.split('.').map(str::to_string).collect()
+}
+
+#[test]
+fn test_module_source_path_maps_std() {
+    let root = Path::new("/project");
+    assert_eq!(
+        module_source_path(root, &components("std.io")),
+        PathBuf::from("/project/lib/std/io.lcy")
+    );
+    assert_eq!(
+        module_source_path(root, &components("test.assert")),
+        PathBuf::from("/project/lib/test/assert.lcy")
+    );
+    assert_eq!(
+        module_source_path(root, &components("util.math")),
+        PathBuf::from("/project/util/math.lcy")
+    );
+}
+
+#[test]
+fn test_find_module_source_uses_first_existing_path() {
+    let dir = std::env::temp_dir().join(format!("lency_search_path_{}", std::process::id()));
+    let (first, second) = (dir.join("src"), dir.join("vendor"));
+    std::fs::create_dir_all(second.join("util")).unwrap();
+    std::fs::create_dir_all(&first).unwrap();
+    std::fs::write(second.join("util").join("math.lcy"), "").unwrap();
+
+    let paths = vec![first.clone(), second.clone()];
+    assert_eq!(
+        find_module_source(&paths, &components("util.math")),
+        second.join("util").join("math.lcy")
+    );
+    // 找不到时回退到第一个搜索路径
+    assert_eq!(
+        find_module_source(&paths, &components("util.missing")),
+        first.join("util").join("missing.lcy")
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
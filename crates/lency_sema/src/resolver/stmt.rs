@@ -1,7 +1,7 @@
 use super::Resolver;
 use crate::scope::ScopeKind;
 use crate::symbol::{Symbol, VariableSymbol};
-use lency_syntax::ast::{Stmt, Type};
+use lency_syntax::ast::{Expr, MatchPattern, Stmt, Type};
 
 pub fn resolve_stmt(resolver: &mut Resolver, stmt: &mut Stmt) {
     match stmt {
@@ -41,6 +41,16 @@ pub fn resolve_stmt(resolver: &mut Resolver, stmt: &mut Stmt) {
                 resolver.errors.push(e);
             }
         }
+        Stmt::VarPattern {
+            pattern,
+            value,
+            span,
+        } => {
+            resolver.resolve_expr(value);
+            resolver.qualify_pattern(pattern);
+            let bindings = pattern_binding_types(resolver, pattern, value, span);
+            define_bindings(resolver, bindings, true, span);
+        }
         Stmt::Assignment { target, value, .. } => {
             resolver.resolve_expr(target);
             resolver.resolve_expr(value);
@@ -80,6 +90,50 @@ pub fn resolve_stmt(resolver: &mut Resolver, stmt: &mut Stmt) {
                 resolver.scopes.exit_scope();
             }
         }
+        Stmt::IfLet {
+            pattern,
+            value,
+            then_block,
+            else_block,
+            span,
+        } => {
+            resolver.resolve_expr(value);
+            resolver.qualify_pattern(pattern);
+            let bindings = pattern_binding_types(resolver, pattern, value, span);
+
+            // 模式绑定的变量只在 then 分支可见
+            resolver.scopes.enter_scope(ScopeKind::Block);
+            define_bindings(resolver, bindings, false, span);
+            for stmt in then_block {
+                resolver.resolve_stmt(stmt);
+            }
+            resolver.scopes.exit_scope();
+
+            if let Some(else_stmts) = else_block {
+                resolver.scopes.enter_scope(ScopeKind::Block);
+                for stmt in else_stmts {
+                    resolver.resolve_stmt(stmt);
+                }
+                resolver.scopes.exit_scope();
+            }
+        }
+        Stmt::WhileLet {
+            pattern,
+            value,
+            body,
+            span,
+        } => {
+            resolver.resolve_expr(value);
+            resolver.qualify_pattern(pattern);
+            let bindings = pattern_binding_types(resolver, pattern, value, span);
+
+            resolver.scopes.enter_scope(ScopeKind::Block);
+            define_bindings(resolver, bindings, false, span);
+            for stmt in body {
+                resolver.resolve_stmt(stmt);
+            }
+            resolver.scopes.exit_scope();
+        }
         Stmt::While {
             condition, body, ..
         } => {
@@ -165,3 +219,38 @@ pub fn resolve_stmt(resolver: &mut Resolver, stmt: &mut Stmt) {
         }
     }
 }
+
+/// 推导模式绑定的变量类型；推导失败时记为 Error，错误由 TypeChecker 报告
+fn pattern_binding_types(
+    resolver: &mut Resolver,
+    pattern: &MatchPattern,
+    value: &mut Expr,
+    span: &std::ops::Range<usize>,
+) -> Vec<(String, Type)> {
+    let scope_id = resolver.scopes.current_scope();
+    let mut inferer = crate::type_infer::TypeInferer::with_scope(&mut resolver.scopes, scope_id);
+    inferer
+        .infer(value)
+        .and_then(|ty| inferer.pattern_bindings(pattern, &ty, span))
+        .unwrap_or_else(|_| {
+            pattern
+                .bindings()
+                .into_iter()
+                .map(|name| (name.to_string(), Type::Error))
+                .collect()
+        })
+}
+
+fn define_bindings(
+    resolver: &mut Resolver,
+    bindings: Vec<(String, Type)>,
+    is_mutable: bool,
+    span: &std::ops::Range<usize>,
+) {
+    for (name, ty) in bindings {
+        let var_symbol = VariableSymbol::new(name, ty, is_mutable, span.clone());
+        if let Err(e) = resolver.scopes.define(Symbol::Variable(var_symbol)) {
+            resolver.errors.push(e);
+        }
+    }
+}
@@ -1,10 +1,39 @@
+use super::namespace;
 use super::Resolver;
 use crate::error::SemanticError;
 use crate::symbol::{GenericParamSymbol, Symbol};
-use lency_syntax::ast::{Span, Type};
+use lency_syntax::ast::{MatchPattern, Span, Type};
+
+/// 模块限定类型名: io.File -> std::io::File（无法解析时保持原样，由 resolve_type 报错）
+fn qualify_type_name(resolver: &Resolver, ty: &mut Type) {
+    if let Type::Struct(name) | Type::Generic(name, _) | Type::TraitObject(name) = ty {
+        if let Some((module, member)) = namespace::split_qualified(name) {
+            if let Ok(mangled) = super::imports::module_member(resolver, module, member, &(0..0)) {
+                *name = mangled;
+            }
+        }
+    }
+}
+
+/// 结构体模式中的模块限定名: `geo.Point { x, y }` -> `app::geo::Point { x, y }`
+/// （无法解析时保持原样，由类型检查报告未定义类型）
+pub fn qualify_pattern(resolver: &Resolver, pattern: &mut MatchPattern) {
+    pattern.walk_mut(&mut |pattern| {
+        if let MatchPattern::Struct { name, .. } = pattern {
+            if let Some((module, member)) = namespace::split_qualified(name) {
+                if let Ok(mangled) =
+                    super::imports::module_member(resolver, module, member, &(0..0))
+                {
+                    *name = mangled;
+                }
+            }
+        }
+    });
+}
 
 /// Normalize types (e.g., Vec<T> -> Type::Vec(T))
 pub fn normalize_type(resolver: &mut Resolver, ty: &mut Type) {
+    qualify_type_name(resolver, ty);
     match ty {
         Type::Generic(name, args) if name == "Vec" => {
             if args.len() != 1 {
@@ -49,6 +78,7 @@ pub fn normalize_type_with_generics(
     ty: &mut Type,
     generics: &[GenericParamSymbol],
 ) {
+    qualify_type_name(resolver, ty);
     match ty {
         Type::Generic(name, args) if name == "Vec" => {
             if args.len() != 1 {
@@ -91,6 +121,15 @@ pub fn normalize_type_with_generics(
 
 /// 验证类型引用（包括泛型参数检查）
 pub fn resolve_type(resolver: &mut Resolver, ty: &Type, span: &Span) {
+    // 未能解析的模块限定名：报告具体的模块成员错误
+    if let Type::Struct(name) | Type::Generic(name, _) | Type::TraitObject(name) = ty {
+        if let Some((module, member)) = namespace::split_qualified(name) {
+            if let Err(e) = super::imports::module_member(resolver, module, member, span) {
+                resolver.errors.push(e);
+                return;
+            }
+        }
+    }
     match ty {
         Type::Generic(name, args) => {
             let sym = resolver.scopes.lookup(name);
@@ -191,6 +230,23 @@ pub fn resolve_type(resolver: &mut Resolver, ty: &Type, span: &Span) {
                 });
             }
         },
+        Type::TraitObject(name) => match resolver.scopes.lookup(name) {
+            Some(Symbol::Trait(t)) => {
+                if let Err(reason) = super::decl_impl::impl_block::check_object_safety(t) {
+                    resolver.errors.push(SemanticError::TraitNotObjectSafe {
+                        name: name.clone(),
+                        reason,
+                        span: span.clone(),
+                    });
+                }
+            }
+            _ => {
+                resolver.errors.push(SemanticError::UndefinedTrait {
+                    name: name.clone(),
+                    span: span.clone(),
+                });
+            }
+        },
         Type::Vec(inner)
         | Type::Array {
             element_type: inner,
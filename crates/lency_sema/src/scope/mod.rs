@@ -33,6 +33,10 @@ pub enum ScopeKind {
     Class,
     /// 块作用域 (if, while, {} 等)
     Block,
+    /// 闭包作用域（表达式级，不参与语句块的作用域同步）
+    Closure,
+    /// match 分支的模式绑定作用域（表达式级，同样不参与同步）
+    MatchArm,
 }
 
 impl Scope {
@@ -60,4 +64,9 @@ impl Scope {
     pub fn lookup_local(&self, name: &str) -> Option<SymbolId> {
         self.symbols.get(name).copied()
     }
+
+    /// 当前作用域定义的全部符号
+    pub fn symbols(&self) -> impl Iterator<Item = (&str, SymbolId)> {
+        self.symbols.iter().map(|(name, id)| (name.as_str(), *id))
+    }
 }
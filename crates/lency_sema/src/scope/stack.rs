@@ -94,6 +94,79 @@ impl ScopeStack {
         None
     }
 
+    /// 查找与 `name` 拼写相近、满足 `accept` 的可见符号名，用于 "did you mean" 提示
+    ///
+    /// 编辑距离不超过名称长度的三分之一（至少为 1）；距离相同时内层作用域优先。
+    pub fn similar_name_from(
+        &self,
+        name: &str,
+        start_scope: ScopeId,
+        accept: impl Fn(&Symbol) -> bool,
+    ) -> Option<String> {
+        let max_distance = (name.chars().count() / 3).max(1);
+        let mut best: Option<(usize, &str)> = None;
+        let mut current_id = Some(start_scope);
+        while let Some(id) = current_id {
+            let scope = &self.scopes[id];
+            let mut candidates: Vec<(usize, &str)> = scope
+                .symbols()
+                .filter(|(candidate, _)| *candidate != name && is_identifier(candidate))
+                .filter(|(_, symbol_id)| self.symbols.get(*symbol_id).is_some_and(&accept))
+                .map(|(candidate, _)| (edit_distance(name, candidate), candidate))
+                .filter(|(distance, _)| *distance <= max_distance)
+                .collect();
+            candidates.sort();
+            if let Some(&candidate) = candidates.first() {
+                if best.is_none_or(|(distance, _)| candidate.0 < distance) {
+                    best = Some(candidate);
+                }
+            }
+            current_id = scope.parent;
+        }
+        best.map(|(_, candidate)| candidate.to_string())
+    }
+
+    /// 从当前作用域开始查找拼写相近的符号名，见 [`Self::similar_name_from`]
+    pub fn similar_name(&self, name: &str, accept: impl Fn(&Symbol) -> bool) -> Option<String> {
+        self.similar_name_from(name, self.current, accept)
+    }
+
+    /// 从指定作用域开始查找符号，返回定义该符号的作用域 ID
+    pub fn lookup_scope_from(&self, name: &str, start_scope: ScopeId) -> Option<ScopeId> {
+        let mut current_id = start_scope;
+        loop {
+            if self.scopes[current_id].lookup_local(name).is_some() {
+                return Some(current_id);
+            }
+
+            if let Some(parent) = self.scopes[current_id].parent {
+                current_id = parent;
+            } else {
+                break;
+            }
+        }
+        None
+    }
+
+    /// 检查 `scope_id` 是否为 `ancestor` 本身或其后代作用域
+    pub fn is_within(&self, scope_id: ScopeId, ancestor: ScopeId) -> bool {
+        let mut current_id = scope_id;
+        loop {
+            if current_id == ancestor {
+                return true;
+            }
+            match self.scopes[current_id].parent {
+                Some(parent) => current_id = parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// 检查作用域是否为全局作用域
+    pub fn is_global(&self, scope_id: ScopeId) -> bool {
+        self.scopes[scope_id].kind == ScopeKind::Global
+    }
+
     /// 仅在全局作用域查找符号
     pub fn lookup_global(&self, name: &str) -> Option<&Symbol> {
         self.scopes[0]
@@ -138,12 +211,17 @@ impl ScopeStack {
     }
 
     /// 获取子作用域列表（用于遍历）
+    ///
+    /// 闭包作用域属于表达式，不计入语句块的遍历顺序
     pub fn get_child_scopes(&self, parent_id: ScopeId) -> Vec<ScopeId> {
         // 这效率不高，但对于 ScopeStack 结构来说是最简单的实现
         // 优化方案：Scope 结构存储 children 列表
         self.scopes
             .iter()
-            .filter(|s| s.parent == Some(parent_id))
+            .filter(|s| {
+                s.parent == Some(parent_id)
+                    && !matches!(s.kind, ScopeKind::Closure | ScopeKind::MatchArm)
+            })
             .map(|s| s.id)
             .collect()
     }
@@ -228,6 +306,26 @@ impl ScopeStack {
     }
 }
 
+/// 源码中可以直接写出的名称（排除导入模块成员的内部名称等）
+fn is_identifier(name: &str) -> bool {
+    name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Levenshtein 编辑距离
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != *cb);
+            current[j + 1] = substitute.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
 impl Default for ScopeStack {
     fn default() -> Self {
         Self::new()
@@ -287,4 +385,46 @@ mod tests {
             assert_eq!(name, "x");
         }
     }
+
+    #[test]
+    fn test_similar_name() {
+        let mut scopes = ScopeStack::new();
+        let define = |scopes: &mut ScopeStack, name: &str| {
+            let var =
+                Symbol::Variable(VariableSymbol::new(name.to_string(), Type::Int, true, 0..1));
+            scopes.define(var).unwrap(); // allow: unwrap
+        };
+        define(&mut scopes, "counter");
+        define(&mut scopes, "total");
+        scopes.enter_scope(ScopeKind::Function);
+        define(&mut scopes, "count");
+
+        let any = |_: &Symbol| true;
+        assert_eq!(scopes.similar_name("cont", any).as_deref(), Some("count"));
+        assert_eq!(scopes.similar_name("totl", any).as_deref(), Some("total"));
+        assert_eq!(scopes.similar_name("xyz", any), None);
+        assert_eq!(
+            scopes.similar_name("cont", |s| !matches!(s, Symbol::Variable(_))),
+            None
+        );
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_closure_scopes_skipped_in_children() {
+        let mut scopes = ScopeStack::new();
+        let func = scopes.enter_scope(ScopeKind::Function);
+
+        // 闭包与 match 分支作用域先于 if 块出现，但不应打乱语句块的同步顺序
+        let closure = scopes.enter_scope(ScopeKind::Closure);
+        scopes.exit_scope();
+        scopes.enter_scope(ScopeKind::MatchArm);
+        scopes.exit_scope();
+        let block = scopes.enter_scope(ScopeKind::Block);
+        scopes.exit_scope();
+
+        assert_eq!(scopes.get_child_scopes(func), vec![block]);
+        assert!(scopes.is_within(closure, func));
+        assert!(!scopes.is_within(func, closure));
+    }
 }
@@ -16,13 +16,21 @@ pub type SymbolId = usize;
 #[derive(Debug, Clone)]
 pub struct GenericParamSymbol {
     pub name: String,
-    pub bound: Option<Type>, // 约束类型，如 T: Display
+    pub bounds: Vec<Type>, // 约束 Trait，如 T: Display 或 K: Hash + Eq<K>
     pub span: Span,
 }
 
 impl GenericParamSymbol {
-    pub fn new(name: String, bound: Option<Type>, span: Span) -> Self {
-        Self { name, bound, span }
+    pub fn new(name: String, bounds: Vec<Type>, span: Span) -> Self {
+        Self { name, bounds, span }
+    }
+
+    /// 约束中的 Trait 名（`Eq<K>` 取 `Eq`）
+    pub fn bound_traits(&self) -> impl Iterator<Item = &str> {
+        self.bounds.iter().filter_map(|bound| match bound {
+            Type::Struct(name) | Type::Generic(name, _) => Some(name.as_str()),
+            _ => None,
+        })
     }
 }
 
@@ -40,6 +48,7 @@ pub enum Symbol {
     GenericParam(GenericParamSymbol), // 泛型参数符号
     Trait(TraitSymbol),               // Trait 符号
     Enum(EnumSymbol),                 // Enum 符号
+    Module(ModuleSymbol),             // 模块别名符号 (import x as m)
 }
 
 impl Symbol {
@@ -53,6 +62,7 @@ impl Symbol {
             Symbol::GenericParam(g) => &g.name,
             Symbol::Trait(t) => &t.name,
             Symbol::Enum(e) => &e.name,
+            Symbol::Module(m) => &m.name,
         }
     }
 
@@ -66,6 +76,7 @@ impl Symbol {
             Symbol::GenericParam(g) => &g.span,
             Symbol::Trait(t) => &t.span,
             Symbol::Enum(e) => &e.span,
+            Symbol::Module(m) => &m.span,
         }
     }
 
@@ -79,8 +90,17 @@ impl Symbol {
             Symbol::GenericParam(_) => None, // 泛型参数本身不是值类型
             Symbol::Trait(_) => None,        // Trait 不是值类型
             Symbol::Enum(_) => None,         // Enum本身是类型
+            Symbol::Module(_) => None,       // 模块不是值
         }
     }
+
+    /// 能否作为表达式中的名称使用（变量、参数、函数）
+    pub fn is_value(&self) -> bool {
+        matches!(
+            self,
+            Symbol::Variable(_) | Symbol::Parameter(_) | Symbol::Function(_)
+        )
+    }
 }
 
 /// 变量符号
@@ -284,6 +304,8 @@ pub struct TraitSymbol {
     pub name: String,
     pub generic_params: Vec<GenericParamSymbol>,
     pub methods: Vec<TraitMethodSignature>,
+    /// 实现了该 Trait 的类型名（用于 `dyn Trait` 转换检查）
+    pub implementors: Vec<String>,
     pub span: Span,
 }
 
@@ -293,6 +315,7 @@ impl TraitSymbol {
             name,
             generic_params: Vec::new(),
             methods: Vec::new(),
+            implementors: Vec::new(),
             span,
         }
     }
@@ -303,6 +326,7 @@ impl TraitSymbol {
             name,
             generic_params,
             methods: Vec::new(),
+            implementors: Vec::new(),
             span,
         }
     }
@@ -321,6 +345,18 @@ impl TraitSymbol {
     pub fn is_generic(&self) -> bool {
         !self.generic_params.is_empty()
     }
+
+    /// 记录实现了该 Trait 的类型
+    pub fn add_implementor(&mut self, type_name: String) {
+        if !self.implementors.contains(&type_name) {
+            self.implementors.push(type_name);
+        }
+    }
+
+    /// 类型是否实现了该 Trait
+    pub fn is_implemented_by(&self, type_name: &str) -> bool {
+        self.implementors.iter().any(|t| t == type_name)
+    }
 }
 
 /// Enum 符号
@@ -376,3 +412,46 @@ impl EnumSymbol {
         self.methods.get(name)
     }
 }
+
+/// 模块成员
+#[derive(Debug, Clone)]
+pub struct ModuleMember {
+    /// 成员在全局符号表中的名称（如 `std::io::File`）
+    pub mangled_name: String,
+    /// 是否对导入方可见
+    pub is_pub: bool,
+}
+
+/// 模块符号
+///
+/// 对应 `import std.io as io` 中的 `io`，成员通过 `io.println` / `io.File` 访问
+#[derive(Debug, Clone)]
+pub struct ModuleSymbol {
+    /// 别名
+    pub name: String,
+    /// 模块路径（如 `std.io`），用于错误信息
+    pub path: String,
+    /// 源码中的成员名 -> 成员信息
+    pub members: HashMap<String, ModuleMember>,
+    pub span: Span,
+}
+
+impl ModuleSymbol {
+    pub fn new(
+        name: String,
+        path: String,
+        members: HashMap<String, ModuleMember>,
+        span: Span,
+    ) -> Self {
+        Self {
+            name,
+            path,
+            members,
+            span,
+        }
+    }
+
+    pub fn get_member(&self, name: &str) -> Option<&ModuleMember> {
+        self.members.get(name)
+    }
+}
@@ -0,0 +1,407 @@
+//! Tests for semantic analysis entry points
+
+use super::*;
+use lency_syntax::ast::*;
+
+mod modules;
+mod patterns;
+mod types;
+
+/// 创建一个简单的测试程序
+fn make_simple_program() -> Program {
+    // int main() { var x = 10; return x; }
+    Program {
+        decls: vec![Decl::Function {
+            span: 0..50,
+            is_pub: false,
+            name: "main".to_string(),
+            generic_params: vec![],
+            params: vec![],
+            return_type: Type::Int,
+            body: vec![
+                Stmt::VarDecl {
+                    span: 10..20,
+                    name: "x".to_string(),
+                    ty: Some(Type::Int),
+                    value: Expr {
+                        kind: ExprKind::Literal(Literal::Int(10)),
+                        span: 15..17,
+                    },
+                },
+                Stmt::Return {
+                    span: 20..30,
+                    value: Some(Expr {
+                        kind: ExprKind::Variable("x".to_string()),
+                        span: 27..28,
+                    }),
+                },
+            ],
+        }],
+    }
+}
+
+#[test]
+fn test_analyze_simple_program() {
+    let mut program = make_simple_program();
+    let result = analyze(&mut program);
+    assert!(
+        result.is_ok(),
+        "Analysis should succeed: {:?}",
+        result.err()
+    );
+}
+
+#[test]
+fn test_analyze_records_expression_types() {
+    let mut program = make_simple_program();
+    let (result, errors) = analyze_for_ide(&mut program);
+    assert!(errors.is_empty());
+
+    // `return x;` 中的 x: int，定义位于 `var x = 10;`
+    let x = result.types.expr_at(27).expect("x should be recorded");
+    assert_eq!(x.ty, Type::Int);
+    let def = x.definition.as_ref().expect("x should have a definition");
+    assert_eq!(def.span, 10..20);
+    assert_eq!(def.owner, None);
+
+    assert_eq!(result.types.expr_at(15).map(|e| &e.ty), Some(&Type::Int));
+    assert!(result.types.expr_at(40).is_none());
+}
+
+#[test]
+fn test_undefined_variable_error() {
+    // int main() { return y; }  -- y 未定义
+    let mut program = Program {
+        decls: vec![Decl::Function {
+            span: 0..30,
+            is_pub: false,
+            name: "main".to_string(),
+            generic_params: vec![],
+            params: vec![],
+            return_type: Type::Int,
+            body: vec![Stmt::Return {
+                span: 10..20,
+                value: Some(Expr {
+                    kind: ExprKind::Variable("y".to_string()),
+                    span: 17..18,
+                }),
+            }],
+        }],
+    };
+
+    let result = analyze(&mut program);
+    assert!(result.is_err());
+
+    let errors = result.unwrap_err();
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, SemanticError::UndefinedVariable { name, .. } if name == "y")));
+}
+
+#[test]
+fn test_type_mismatch_error() {
+    // int main() { var x: int = "hello"; return x; }
+    let mut program = Program {
+        decls: vec![Decl::Function {
+            span: 0..50,
+            is_pub: false,
+            name: "main".to_string(),
+            generic_params: vec![],
+            params: vec![],
+            return_type: Type::Int,
+            body: vec![
+                Stmt::VarDecl {
+                    span: 10..30,
+                    name: "x".to_string(),
+                    ty: Some(Type::Int),
+                    value: Expr {
+                        kind: ExprKind::Literal(Literal::String("hello".to_string())),
+                        span: 20..27,
+                    },
+                },
+                Stmt::Return {
+                    span: 30..40,
+                    value: Some(Expr {
+                        kind: ExprKind::Variable("x".to_string()),
+                        span: 37..38,
+                    }),
+                },
+            ],
+        }],
+    };
+
+    let result = analyze(&mut program);
+    assert!(result.is_err());
+
+    let errors = result.unwrap_err();
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, SemanticError::TypeMismatch { .. })));
+}
+
+#[test]
+fn test_null_safety_error() {
+    // void test() { var s: string = null; }  -- null 赋给非空类型
+    let mut program = Program {
+        decls: vec![Decl::Function {
+            span: 0..50,
+            is_pub: false,
+            name: "test".to_string(),
+            generic_params: vec![],
+            params: vec![],
+            return_type: Type::Void,
+            body: vec![Stmt::VarDecl {
+                span: 10..40,
+                name: "s".to_string(),
+                ty: Some(Type::String),
+                value: Expr {
+                    kind: ExprKind::Literal(Literal::Null),
+                    span: 30..34,
+                },
+            }],
+        }],
+    };
+
+    let result = analyze(&mut program);
+    assert!(result.is_err());
+
+    let errors = result.unwrap_err();
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, SemanticError::NullAssignmentToNonNullable { .. })));
+}
+
+const DYN_SOURCE: &str = r#"
+    trait Shape { int area(); }
+    struct Square { int side }
+    struct Rect { int w  int h }
+    impl Shape for Square { int area() { return this.side * this.side } }
+    impl Shape for Rect { int area() { return this.w * this.h } }
+
+    int total(Vec<dyn Shape> shapes) {
+        var sum = 0
+        var i = 0
+        while i < shapes.len() {
+            sum = sum + shapes.get(i).area()
+            i = i + 1
+        }
+        return sum
+    }
+
+    int main() {
+        var shapes: Vec<dyn Shape> = vec![Square { side: 2 }, Rect { w: 1, h: 1 }]
+        shapes.push(Rect { w: 2, h: 3 })
+        var first: dyn Shape = Square { side: 1 }
+        return total(shapes) + first.area()
+    }
+"#;
+
+#[test]
+fn test_dyn_trait_coercion_inserted() {
+    let mut program = lency_syntax::parser::parse(DYN_SOURCE).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    let Some(Decl::Function { body, .. }) = program.decls.last() else {
+        panic!("main not found");
+    };
+    let Stmt::VarDecl { ty, value, .. } = &body[2] else {
+        panic!("expected var decl");
+    };
+    assert_eq!(ty, &Some(Type::TraitObject("Shape".to_string())));
+    assert!(matches!(
+        &value.kind,
+        ExprKind::CoerceDyn { trait_name, .. } if trait_name == "Shape"
+    ));
+}
+
+#[test]
+fn test_dyn_trait_requires_impl() {
+    let source = DYN_SOURCE.replace("impl Shape for Rect", "impl Rect");
+    let mut program = lency_syntax::parser::parse(&source).expect("parse failed");
+    let errors = analyze(&mut program).unwrap_err();
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        SemanticError::TraitNotImplemented { type_name, trait_name, .. }
+            if type_name == "Rect" && trait_name == "Shape"
+    )));
+}
+
+#[test]
+fn test_generic_trait_not_object_safe() {
+    let source = r#"
+        trait Eq<T> { bool equals(T other); }
+        void check(dyn Eq value) { }
+    "#;
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let errors = analyze(&mut program).unwrap_err();
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, SemanticError::TraitNotObjectSafe { name, .. } if name == "Eq")));
+}
+
+fn analyze_errors(source: &str) -> Vec<SemanticError> {
+    let mut program = lency_syntax::parser::parse_spanned(source).expect("parse failed");
+    analyze(&mut program).expect_err("expected semantic errors")
+}
+
+#[test]
+fn test_undefined_variable_suggests_similar_name() {
+    let source = "int main() {\n    var count = 1\n    return cont\n}";
+    let errors = analyze_errors(source);
+    let diag = errors[0].to_diagnostic();
+    assert_eq!(diag.message, "undefined variable 'cont'");
+
+    let suggestion = &diag.suggestions[0];
+    assert_eq!(suggestion.message, "did you mean `count`?");
+    assert_eq!(suggestion.replacement.as_deref(), Some("count"));
+    let span = suggestion.span.clone().expect("replacement span");
+    assert_eq!(&source[span], "cont");
+}
+
+#[test]
+fn test_undefined_function_suggests_similar_name() {
+    let source = "int square(int x) { return x * x }\nint main() { return sqare(2) }";
+    let errors = analyze_errors(source);
+    let diag = errors[0].to_diagnostic();
+    let suggestion = &diag.suggestions[0];
+    assert_eq!(suggestion.replacement.as_deref(), Some("square"));
+    let span = suggestion.span.clone().expect("replacement span");
+    assert_eq!(&source[span], "sqare");
+}
+
+#[test]
+fn test_duplicate_definition_labels_previous_span() {
+    let source = "int main() {\n    var x = 1\n    var x = 2\n    return x\n}";
+    let errors = analyze_errors(source);
+    let diag = errors[0].to_diagnostic();
+    assert_eq!(diag.labels.len(), 1);
+    assert_eq!(diag.labels[0].message, "previous definition of 'x' here");
+    assert!(diag.labels[0].span.start < diag.span.clone().expect("span").start);
+}
+
+#[test]
+fn test_error_codes() {
+    let errors = analyze_errors("int main() {\n    var x: int = \"s\"\n    return y\n}");
+    let codes: Vec<&str> = errors.iter().map(SemanticError::code).collect();
+    assert!(codes.contains(&"E0001"), "{:?}", codes);
+    assert_eq!(
+        errors[0].to_diagnostic().code.as_deref(),
+        Some(errors[0].code())
+    );
+
+    let samples = [
+        SemanticError::BreakOutsideLoop { span: 0..1 },
+        SemanticError::ContinueOutsideLoop { span: 0..1 },
+        SemanticError::InvalidGenericArg { span: 0..1 },
+        SemanticError::ModuleNotAValue {
+            name: "m".to_string(),
+            span: 0..1,
+        },
+        SemanticError::NotIterable {
+            ty: "int".to_string(),
+            span: 0..1,
+        },
+        SemanticError::OrPatternBindingMismatch {
+            name: "x".to_string(),
+            span: 0..1,
+        },
+        SemanticError::EmptyRangePattern {
+            pattern: "9..1".to_string(),
+            span: 0..1,
+        },
+        SemanticError::RefutablePattern {
+            missing: vec!["None".to_string()],
+            span: 0..1,
+        },
+    ];
+    let codes: std::collections::HashSet<&str> = samples.iter().map(|e| e.code()).collect();
+    assert_eq!(codes.len(), samples.len());
+    assert!(codes
+        .iter()
+        .all(|code| code.len() == 5 && code.starts_with('E')));
+}
+
+#[test]
+fn test_recovered_program_has_no_cascading_errors() {
+    // 语法错误恢复后的占位表达式为 Type::Error，不再引起语义错误
+    let source = "int main() {\n    var x = ;\n    var y = x + 1\n    x.foo(+)\n    return y\n}";
+    let (program, syntax_errors) = lency_syntax::parser::parse_recovery(source);
+    assert_eq!(syntax_errors.len(), 2);
+
+    let mut program = program.expect("recovered program");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // 占位之外的错误照常报告
+    let source = "int main() {\n    var x = ;\n    return missing\n}";
+    let (program, _) = lency_syntax::parser::parse_recovery(source);
+    let mut program = program.expect("recovered program");
+    let errors = analyze(&mut program).expect_err("expected semantic errors");
+    assert!(errors
+        .iter()
+        .all(|e| matches!(e, SemanticError::UndefinedVariable { name, .. } if name == "missing")));
+    assert!(errors.iter().all(|e| !e.involves_error_type()));
+
+    let cascading = SemanticError::TypeMismatch {
+        expected: "int".to_string(),
+        found: Type::Error.to_string(),
+        span: 0..1,
+    };
+    assert!(cascading.involves_error_type());
+    assert!(cascading
+        .in_module("util", std::path::Path::new("util.lcy"))
+        .involves_error_type());
+}
+
+#[test]
+fn test_missing_return_requires_every_branch() {
+    let ok = [
+        "int f(bool c) {\n    if c {\n        return 1\n    } else {\n        return 2\n    }\n}",
+        "int f(bool c) {\n    {\n        return 1\n    }\n}",
+    ];
+    for source in ok {
+        let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+        let result = analyze(&mut program);
+        assert!(result.is_ok(), "{}: {:?}", source, result.err());
+    }
+
+    let missing = [
+        "int f(bool c) {\n    if c {\n        return 1\n    }\n}",
+        "int f(bool c) {\n    if c {\n        return 1\n    } else {\n        var x = 2\n    }\n}",
+        "int f(bool c) {\n    {\n        var x = 1\n    }\n}",
+    ];
+    for source in missing {
+        let errors = analyze_errors(source);
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, SemanticError::MissingReturn { .. })),
+            "{}: {:?}",
+            source,
+            errors
+        );
+    }
+}
+
+#[test]
+fn test_operators_on_trait_bounded_generic_params() {
+    let traits = "trait Eq {\n}\ntrait Comparable {\n}\n";
+    let source = format!(
+        "{}bool same<T: Eq>(T a, T b) {{\n    return a != b\n}}\nbool less<T: Comparable>(T a, T b) {{\n    return a < b && a == b\n}}\n",
+        traits
+    );
+    let mut program = lency_syntax::parser::parse(&source).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // Eq 只提供 == 与 !=
+    let source = format!(
+        "{}bool less<T: Eq>(T a, T b) {{\n    return a < b\n}}\n",
+        traits
+    );
+    let errors = analyze_errors(&source);
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e, SemanticError::InvalidBinaryOp { .. })));
+}
@@ -0,0 +1,224 @@
+//! 模块导入：模块名绑定、glob 导入、pub 可见性与导入模块中的错误来源
+
+use super::*;
+
+#[test]
+fn test_errors_in_imported_modules_record_source() {
+    let dir = std::env::temp_dir().join(format!("lency_sema_sources_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let util = "import helpers.*\n\nint triple(int x) {\n    var count = x\n    return cont * 3\n}\n\nint label() {\n    return \"three\"\n}\n";
+    std::fs::write(dir.join("util.lcy"), util).unwrap();
+    std::fs::write(
+        dir.join("helpers.lcy"),
+        "int helper() {\n    return missing\n}\n",
+    )
+    .unwrap();
+
+    let source = "import util.*\n\nint main() {\n    return triple(1) + oops\n}\n";
+    let mut program = lency_syntax::parser::parse_spanned(source).expect("parse failed");
+    let errors =
+        analyze_with_paths(&mut program, std::slice::from_ref(&dir)).expect_err("expected errors");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let find = |name: &str| {
+        errors
+            .iter()
+            .find(|e| e.to_string().contains(name))
+            .unwrap_or_else(|| panic!("no error mentioning {}: {:?}", name, errors))
+    };
+
+    // 入口源文件中的错误没有来源标记
+    let oops = find("oops");
+    assert_eq!(oops.source_module(), None);
+    assert_eq!(&source[oops.span().clone()], "oops");
+
+    // 导入模块中的错误记录模块与源文件，位置是该文件中的偏移
+    let cont = find("cont");
+    assert_eq!(cont.source_module(), Some("util"));
+    assert_eq!(cont.source_file(), Some(dir.join("util.lcy").as_path()));
+    assert_eq!(&util[cont.span().clone()], "cont");
+    assert!(matches!(
+        cont.kind(),
+        SemanticError::UndefinedVariable { .. }
+    ));
+    assert_eq!(cont.code(), "E0001");
+    let diag = cont.to_diagnostic();
+    assert_eq!(
+        diag.file_path,
+        Some(dir.join("util.lcy").to_string_lossy().into_owned())
+    );
+
+    // 类型检查与嵌套导入中的错误同样标记
+    let mismatch = errors
+        .iter()
+        .find(|e| matches!(e.kind(), SemanticError::ReturnTypeMismatch { .. }))
+        .expect("return type mismatch");
+    assert_eq!(mismatch.source_module(), Some("util"));
+    assert_eq!(find("missing").source_module(), Some("helpers"));
+}
+
+#[test]
+fn test_pub_global_vars() {
+    let dir = std::env::temp_dir().join(format!("lency_sema_globals_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("config.lcy"),
+        "pub var limit = 3\nvar hits = 0\n\npub int bump() {\n    hits = hits + 1\n    return hits\n}\n",
+    )
+    .unwrap();
+    let analyze_source = |source: &str| {
+        let mut program = lency_syntax::parser::parse_spanned(source).expect("parse failed");
+        analyze_with_paths(&mut program, std::slice::from_ref(&dir)).map(|_| program)
+    };
+
+    // pub 全局变量可以通过模块别名读写；未标注的类型由初始值推导
+    let program = analyze_source(
+        "import config as config\n\nint main() {\n    config.limit = config.limit + 1\n    return config.limit + config.bump()\n}\n",
+    )
+    .expect("analysis failed");
+    let limit = program.decls.iter().find_map(|d| match d {
+        Decl::Var { name, ty, .. } if name.ends_with("limit") => Some(ty.clone()),
+        _ => None,
+    });
+    assert_eq!(limit, Some(Some(Type::Int)));
+
+    // 私有全局变量不能通过别名访问
+    let errors =
+        analyze_source("import config as config\n\nint main() {\n    return config.hits\n}\n")
+            .expect_err("expected errors");
+    assert!(matches!(
+        errors[0].kind(),
+        SemanticError::PrivateModuleMember { name, .. } if name == "hits"
+    ));
+
+    // glob 导入只引入 pub 全局变量
+    let errors = analyze_source("import config.*\n\nint main() {\n    return limit + hits\n}\n")
+        .expect_err("expected errors");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(errors
+        .iter()
+        .all(|e| matches!(e, SemanticError::UndefinedVariable { name, .. } if name == "hits")));
+}
+
+#[test]
+fn test_plain_import_binds_module_name() {
+    let dir = std::env::temp_dir().join(format!("lency_sema_plain_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("geo")).unwrap();
+    std::fs::write(
+        dir.join("geo").join("shapes.lcy"),
+        "pub int area(int w, int h) {\n    return w * h\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("grid.lcy"),
+        "pub int area(int cells) {\n    return cells\n}\n",
+    )
+    .unwrap();
+    let analyze_source = |source: &str| {
+        let mut program = lency_syntax::parser::parse_spanned(source).expect("parse failed");
+        analyze_with_paths(&mut program, std::slice::from_ref(&dir))
+    };
+
+    // 两个模块的同名成员分别通过模块名访问
+    let result = analyze_source(
+        "import geo.shapes\nimport grid\n\nint main() {\n    return shapes.area(2, 3) + grid.area(4)\n}\n",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // 成员不会进入导入方的全局作用域
+    let errors = analyze_source("import grid\n\nint main() {\n    return area(4)\n}\n")
+        .expect_err("expected errors");
+    assert!(errors.iter().any(
+        |e| matches!(e.kind(), SemanticError::UndefinedFunction { name, .. } if name == "area")
+    ));
+
+    // glob 导入仍把导出名称引入全局作用域，同名时报告冲突
+    let errors = analyze_source(
+        "import geo.shapes.*\nimport grid.*\n\nint main() {\n    return area(4)\n}\n",
+    )
+    .expect_err("expected errors");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e.kind(), SemanticError::ImportConflict { name, .. } if name == "area")));
+}
+
+#[test]
+fn test_import_bundled_test_library() {
+    let source = "import test.assert.*\n\nint main() {\n    assert_eq_int(1 + 1, 2, \"sum\")\n    assert_true(true, \"truth\")\n    return 0\n}\n";
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let repo_root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let options = AnalysisOptions {
+        search_paths: vec![repo_root],
+        ..AnalysisOptions::default()
+    };
+    let result = analyze_with_options(&mut program, &options);
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_module_cache_reuses_unchanged_modules() {
+    let dir = std::env::temp_dir().join(format!("lency_sema_cache_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let write = |name: &str, source: &str| {
+        std::fs::write(dir.join(format!("{}.lcy", name)), source).unwrap();
+    };
+    write("helpers", "pub int helper() {\n    return 1\n}\n");
+    write(
+        "util",
+        "import helpers.*\n\npub int twice() {\n    return helper() * 2\n}\n",
+    );
+    write(
+        "shapes",
+        "pub struct Square { int side }\n\nimpl Square {\n    int area() {\n        return this.side * this.side\n    }\n}\n\npub var unit = 1\n",
+    );
+    let source = "import util.*\nimport shapes.*\n\nint main() {\n    var sq = Square { side: 2 }\n    return twice() + sq.area() + unit\n}\n";
+
+    let cache = ModuleCache::new().shared();
+    let options = AnalysisOptions {
+        search_paths: vec![dir.clone()],
+        module_cache: Some(cache.clone()),
+        ..AnalysisOptions::default()
+    };
+    let analyze = || {
+        let mut program = lency_syntax::parser::parse_spanned(source).expect("parse failed");
+        analyze_with_options(&mut program, &options).map(|_| program)
+    };
+    let files = |names: &[&str]| -> Vec<std::path::PathBuf> {
+        names
+            .iter()
+            .map(|name| dir.join(format!("{}.lcy", name)))
+            .collect()
+    };
+    // 最近一次分析中重新解析与重新分析的模块
+    let activity = || {
+        let cache = cache.lock().unwrap();
+        let mut parsed = cache.parsed().to_vec();
+        let mut analyzed: Vec<_> = cache.analyzed().map(|f| f.to_path_buf()).collect();
+        parsed.sort();
+        analyzed.sort();
+        (parsed, analyzed)
+    };
+
+    let all = files(&["helpers", "shapes", "util"]);
+    let first = analyze().expect("analysis failed");
+    assert_eq!(activity(), (all.clone(), all));
+
+    // 没有变化：不再解析也不再分析，复用的声明与重新分析的结果一致
+    let second = analyze().expect("analysis failed");
+    assert_eq!(activity(), (Vec::new(), Vec::new()));
+    assert_eq!(format!("{:?}", first.decls), format!("{:?}", second.decls));
+
+    // 只修改函数体：只有这个模块重新解析与分析
+    write("helpers", "pub int helper() {\n    return 3\n}\n");
+    analyze().expect("analysis failed");
+    let helpers = files(&["helpers"]);
+    assert_eq!(activity(), (helpers.clone(), helpers.clone()));
+
+    // 修改接口：导入它的模块随之重新分析，其余模块不受影响
+    write("helpers", "pub string helper() {\n    return \"one\"\n}\n");
+    let errors = analyze().expect_err("expected errors");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(activity(), (helpers, files(&["helpers", "util"])));
+    assert!(errors.iter().any(|e| e.source_module() == Some("util")));
+}
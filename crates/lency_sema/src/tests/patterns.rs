@@ -0,0 +1,310 @@
+//! 模式匹配：结构体解构、或模式、守卫、区间模式、`@` 绑定与穷尽性检查
+
+use super::*;
+
+const SHAPES_SOURCE: &str = r#"
+    enum Option<T> { Some(T), None }
+    enum Shape { Circle(int), Rect(int, int), Empty }
+    struct Point { int x  int y }
+
+    string classify(int n) {
+        return match n {
+            case 0 => "zero",
+            case 1 | 2 | 3 => "few",
+            case k @ 4..=9 if k % 2 == 0 => "even digit",
+            case 4..=9 => "odd digit",
+            case _ => "many"
+        }
+    }
+
+    int area(Shape s) {
+        return match s {
+            case Shape.Circle(r) | Shape.Rect(r, 0) => r,
+            case Shape.Rect(w, h) => w * h,
+            case Shape.Empty => 0
+        }
+    }
+
+    int quadrant(Point p) {
+        return match p {
+            case Point { x: 0, y: 0 } => 0,
+            case Point { x, y } if x > 0 && y > 0 => 1,
+            case Point { y: 0 } => -1,
+            case _ => 2
+        }
+    }
+
+    bool letter(char c) {
+        return match c {
+            case 'a'..='z' | 'A'..='Z' => true,
+            case _ => false
+        }
+    }
+
+    int nested(Option<Option<int>> o) {
+        return match o {
+            case Option.Some(Option.Some(n)) => n,
+            case Option.Some(Option.None) => -1,
+            case Option.None => -2
+        }
+    }
+
+    int bits(bool a, u8 b) {
+        var x = match a { case true => 1, case false => 0 }
+        return match b { case 0..128 => x, case 128..=255 => x + 1 }
+    }
+
+    int main() {
+        return area(Shape.Rect(2, 3)) + quadrant(Point { x: 1, y: 1 })
+    }
+"#;
+
+#[test]
+fn test_rich_patterns() {
+    let mut program = lency_syntax::parser::parse(SHAPES_SOURCE).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+/// 把 `cases` 放进 `match value { ... }` 检查
+fn match_errors(decls: &str, value: &str, cases: &str) -> Vec<SemanticError> {
+    analyze_errors(&format!(
+        "enum Option<T> {{ Some(T), None }}\n{}\nint main() {{\n    var v = {}\n    return match v {{\n{}\n    }}\n}}",
+        decls, value, cases
+    ))
+}
+
+fn missing(errors: &[SemanticError]) -> Vec<String> {
+    match errors.first() {
+        Some(SemanticError::PatternNotExhaustive {
+            missing_variants, ..
+        }) => missing_variants.clone(),
+        other => panic!("expected non-exhaustive match, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_exhaustiveness_witnesses() {
+    // 整数必须有通配符，区间之间的空隙会被报告
+    let errors = match_errors("", "5", "case 0..10 => 1, case 20..=30 => 2");
+    assert_eq!(errors[0].code(), "E0801");
+    assert!(missing(&errors).contains(&"10..=19".to_string()));
+
+    // 守卫可能失败，不计入穷尽性
+    let errors = match_errors("", "true", "case true => 1, case false if 1 > 0 => 0");
+    assert_eq!(missing(&errors), vec!["false"]);
+
+    // 嵌套模式给出具体的缺失值
+    let errors = match_errors(
+        "",
+        "Option::<Option<int>>.None",
+        "case Option.Some(Option.Some(_)) => 1, case Option.None => 0",
+    );
+    assert_eq!(missing(&errors), vec!["Some(None)"]);
+
+    // 结构体字段
+    let errors = match_errors(
+        "struct Pair { bool a  bool b }",
+        "Pair { a: true, b: false }",
+        "case Pair { a: true } => 1, case Pair { b: true } => 2",
+    );
+    assert_eq!(missing(&errors), vec!["Pair { a: false, b: false }"]);
+
+    // u8 的全部取值被区间覆盖时无需通配符
+    let source = "int main() {\n    var v = 1 as u8\n    return match v { case 0..=99 => 0, case 100..=255 => 1 }\n}";
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_pattern_errors() {
+    let errors = match_errors(
+        "",
+        "Option::<int>.None",
+        "case Option.Some(x) | Option.None => 1",
+    );
+    assert!(matches!(
+        &errors[0],
+        SemanticError::OrPatternBindingMismatch { name, .. } if name == "x"
+    ));
+    assert_eq!(errors[0].code(), "E0802");
+
+    let errors = match_errors("", "3", "case 9..1 => 1, case _ => 0");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::EmptyRangePattern { pattern, .. } if pattern == "9..1"
+    ));
+    assert_eq!(errors[0].code(), "E0803");
+
+    let errors = match_errors("", "3", "case n if n => 1, case _ => 0");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::TypeMismatch { expected, found, .. } if expected == "bool" && found == "int"
+    ));
+
+    let errors = match_errors(
+        "struct Point { int x  int y }",
+        "Point { x: 1, y: 2 }",
+        "case Point { z } => 1, case _ => 0",
+    );
+    assert!(matches!(&errors[0], SemanticError::UndefinedField { field, .. } if field == "z"));
+
+    // 拼错或未定义的结构体名
+    let errors = match_errors(
+        "struct Point { int x  int y }",
+        "Point { x: 1, y: 2 }",
+        "case Pont { x, y } => x + y, case _ => 0",
+    );
+    assert!(matches!(&errors[0], SemanticError::UndefinedType { name, .. } if name == "Pont"));
+    assert_eq!(errors[0].code(), "E0003");
+
+    let errors = match_errors("", "'a'", "case 0..=9 => 1, case _ => 0");
+    assert!(matches!(&errors[0], SemanticError::TypeMismatch { .. }));
+}
+
+const LET_SOURCE: &str = r#"
+    enum Option<T> { Some(T), None }
+    struct Pair { int key  string value }
+    struct Counter { int n }
+    impl Counter {
+        Option<int> next() {
+            if this.n == 0 {
+                return Option::<int>.None
+            }
+            this.n = this.n - 1
+            return Option::<int>.Some(this.n)
+        }
+    }
+
+    int unwrap_or(Option<int> o, int fallback) {
+        if let Option.Some(x) = o {
+            return x + 1
+        } else {
+            return fallback
+        }
+    }
+
+    int drain(Counter c) {
+        var total = 0
+        while let Option.Some(n) = c.next() {
+            if n == 3 { continue }
+            if n > 100 { break }
+            total = total + n
+        }
+        return total
+    }
+
+    string value_of(Pair p) {
+        var Pair { key, value } = p
+        key = key + 1
+        return value
+    }
+
+    int main() {
+        return unwrap_or(Option::<int>.Some(1), 0) + drain(Counter { n: 5 })
+    }
+"#;
+
+#[test]
+fn test_pattern_statements() {
+    let mut program = lency_syntax::parser::parse(LET_SOURCE).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_pattern_statement_errors() {
+    let prelude = "enum Option<T> { Some(T), None }\nstruct Pair { int key  string value }";
+
+    // 解构声明必须不可反驳，缺失的值与 match 的报告一致
+    let errors = analyze_errors(&format!(
+        "{}\nint main() {{\n    var Option.Some(x) = Option::<int>.None\n    return x\n}}",
+        prelude
+    ));
+    assert!(matches!(
+        &errors[0],
+        SemanticError::RefutablePattern { missing, .. } if missing == &vec!["None".to_string()]
+    ));
+    assert_eq!(errors[0].code(), "E0804");
+
+    // 绑定的变量只在 then 分支可见
+    let errors = analyze_errors(&format!(
+        "{}\nint main() {{\n    var o = Option::<int>.None\n    if let Option.Some(x) = o {{ print(x) }} else {{ return x }}\n    return 0\n}}",
+        prelude
+    ));
+    assert!(matches!(&errors[0], SemanticError::UndefinedVariable { name, .. } if name == "x"));
+
+    // 绑定变量的类型来自模式检查
+    let errors = analyze_errors(&format!(
+        "{}\nbool main() {{\n    var p = Pair {{ key: 1, value: \"a\" }}\n    var Pair {{ value }} = p\n    return value\n}}",
+        prelude
+    ));
+    assert!(matches!(
+        &errors[0],
+        SemanticError::ReturnTypeMismatch { found, .. } if found == "string"
+    ));
+
+    // 模式与值的类型不符
+    let errors = analyze_errors(&format!(
+        "{}\nint main() {{\n    while let Option.Some(n) = 3 {{ print(n) }}\n    return 0\n}}",
+        prelude
+    ));
+    assert!(matches!(&errors[0], SemanticError::TypeMismatch { .. }));
+}
+
+#[test]
+fn test_destructure_struct_from_other_module() {
+    let dir = std::env::temp_dir().join(format!("lency_sema_patterns_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // 模块内部的模式引用改写后的私有结构体名
+    std::fs::write(
+        dir.join("geo.lcy"),
+        r#"
+        pub struct Point { int x  int y }
+        struct Pair { int a  int b }
+
+        pub int sum(Point p) {
+            var Point { x, y } = p
+            return x + y + spread(Pair { a: 1, b: 2 })
+        }
+
+        int spread(Pair p) {
+            if let Pair { a: 0, b } = p { return b }
+            return match p { case Pair { a, b } => a + b }
+        }
+        "#,
+    )
+    .unwrap();
+    let analyze_source = |source: &str| {
+        let mut program = lency_syntax::parser::parse_spanned(source).expect("parse failed");
+        analyze_with_paths(&mut program, std::slice::from_ref(&dir))
+    };
+
+    // 模块限定的结构体模式
+    let result = analyze_source(
+        r#"
+        import geo as geo
+
+        int main() {
+            var p = geo.Point { x: 1, y: 2 }
+            var geo.Point { x, y } = p
+            return match p {
+                case geo.Point { x: 0 } => y,
+                case _ => x + geo.sum(p)
+            }
+        }
+        "#,
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    let errors = analyze_source(
+        "import geo as geo\n\nint main() {\n    var geo.Nope { x } = geo.Point { x: 1, y: 2 }\n    return x\n}\n",
+    )
+    .expect_err("expected errors");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(matches!(
+        errors[0].kind(),
+        SemanticError::UndefinedType { name, .. } if name == "geo.Nope"
+    ));
+}
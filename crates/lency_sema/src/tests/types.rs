@@ -0,0 +1,331 @@
+//! 类型系统扩展：定长整数、char、多重泛型约束与 for-in 迭代
+
+use super::*;
+
+#[test]
+fn test_fixed_int_literals_and_casts() {
+    let source = r#"
+        u8 low(int x) { return (x % 256) as u8 }
+        int main() {
+            var b: u8 = 200
+            var c = b + 1
+            var d: i8 = -128
+            var n = low(300) as int + c as int
+            var w = b.wrapping_add(100)
+            var f = d as float
+            return n
+        }
+    "#;
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // 字面量被包装为目标类型的转换
+    let Some(Decl::Function { body, .. }) = program.decls.last() else {
+        panic!("main not found");
+    };
+    let Stmt::VarDecl { value, .. } = &body[0] else {
+        panic!("expected var decl");
+    };
+    assert!(matches!(
+        &value.kind,
+        ExprKind::Cast {
+            target: Type::FixedInt(IntKind::U8),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_fixed_int_errors() {
+    let errors = analyze_errors("int main() {\n    var b: u8 = 256\n    return 0\n}");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::IntegerLiteralOutOfRange { value: 256, ty, .. } if ty == "u8"
+    ));
+    assert_eq!(errors[0].code(), "E0106");
+
+    // 定宽整数与 int 之间不做隐式转换
+    let errors = analyze_errors("int main() {\n    var x = 1\n    var b: u8 = x\n    return 0\n}");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::TypeMismatch { expected, found, .. } if expected == "u8" && found == "int"
+    ));
+    let errors = analyze_errors(
+        "int main() {\n    var b: u8 = 1\n    var x = 2\n    var y = b + x\n    return 0\n}",
+    );
+    assert!(matches!(&errors[0], SemanticError::InvalidBinaryOp { .. }));
+
+    let errors = analyze_errors("int main() {\n    var s = \"a\" as u8\n    return 0\n}");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::InvalidCast { from, to, .. } if from == "string" && to == "u8"
+    ));
+    assert_eq!(errors[0].code(), "E0105");
+}
+
+#[test]
+fn test_checked_methods_return_option() {
+    let source = r#"
+        enum Option<T> { Some(T), None }
+        int main() {
+            var b: u8 = 200
+            var sum: Option<u8> = b.checked_add(100)
+            var x = 7
+            var product = x.checked_mul(6).unwrap_or(0)
+            var quotient = match x.checked_div(0) {
+                case Option.Some(q) => q,
+                case Option.None => -1
+            }
+            return product + quotient
+        }
+    "#;
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // 结果不能直接当作整数使用
+    let errors = analyze_errors(
+        "enum Option<T> { Some(T), None }\nint main() {\n    var b: u8 = 1\n    var c: u8 = b.checked_add(1)\n    return 0\n}",
+    );
+    assert!(matches!(
+        &errors[0],
+        SemanticError::TypeMismatch { expected, found, .. } if expected == "u8" && found == "Option<u8>"
+    ));
+
+    // Option 未定义（没有 import std.core）
+    let errors = analyze_errors(
+        "int main() {\n    var x = 1\n    var y = x.checked_add(1)\n    return 0\n}",
+    );
+    assert!(matches!(
+        &errors[0],
+        SemanticError::UndefinedType { name, .. } if name == "Option"
+    ));
+}
+
+#[test]
+fn test_char_type() {
+    let source = r#"
+        int main() {
+            var c = '中'
+            var b: u8 = 65
+            var a = b as char
+            var before = a < c
+            var count = 0
+            for ch in "héllo" {
+                if ch == 'é' {
+                    count = count + 1
+                }
+            }
+            var first: char = "abc".chars().get(0)
+            var first_byte: u8 = "abc".bytes().get(0)
+            var s = char_to_string(c) + char_to_string(97)
+            var kind = match first {
+                case 'a' => 1,
+                case _ => 0
+            }
+            return c as int + count + kind
+        }
+    "#;
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_char_errors() {
+    // char 不是整数，也不能与 int 比较
+    let errors = analyze_errors("int main() {\n    var c = 'a' + 1\n    return 0\n}");
+    assert!(matches!(&errors[0], SemanticError::InvalidBinaryOp { .. }));
+
+    // 只有 u8 能直接转换为 char
+    let errors = analyze_errors("int main() {\n    var c = 97 as char\n    return 0\n}");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::InvalidCast { from, to, .. } if from == "int" && to == "char"
+    ));
+
+    let errors = analyze_errors("int main() {\n    var v = \"abc\".chars(1)\n    return 0\n}");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::ArgumentCountMismatch {
+            expected: 0,
+            found: 1,
+            ..
+        }
+    ));
+}
+
+const BOUNDS_SOURCE: &str = r#"
+    trait Hash { int hash(); }
+    trait Eq<T> { bool eq(T other); }
+    trait Show { string show(); }
+    trait Pick<T> { T pick(); }
+
+    int slot<K: Hash + Eq<K> + Pick<K>>(K key, K other) {
+        var picked: K = key.pick()
+        if key.eq(picked) {
+            return key.hash()
+        }
+        return 0
+    }
+
+    int main() {
+        return 0
+    }
+"#;
+
+#[test]
+fn test_multiple_generic_bounds() {
+    let mut program = lency_syntax::parser::parse(BOUNDS_SOURCE).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // 方法不在任何约束中
+    let source = BOUNDS_SOURCE.replace("key.hash()", "key.show()");
+    let mut program = lency_syntax::parser::parse(&source).expect("parse failed");
+    let errors = analyze(&mut program).unwrap_err();
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        SemanticError::UndefinedMethod { class, method, .. }
+            if class == "Trait Hash + Eq + Pick" && method == "show"
+    )));
+
+    // Pick<K> 的返回类型代入为 K
+    let source = BOUNDS_SOURCE.replace("var picked: K", "var picked: int");
+    let mut program = lency_syntax::parser::parse(&source).expect("parse failed");
+    let errors = analyze(&mut program).unwrap_err();
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        SemanticError::TypeMismatch { expected, found, .. } if expected == "int" && found == "K"
+    )));
+}
+
+#[test]
+fn test_match_statement_does_not_shift_block_scopes() {
+    let source = r#"
+        enum Choice { Yes(int), No }
+        int main() {
+            var c = Choice.Yes(1)
+            match c {
+                case Choice.Yes(n) => print(n),
+                case Choice.No => print(0)
+            }
+            var total = 0
+            for var i = 0; i < 3; i = i + 1 {
+                total = total + i
+            }
+            return total
+        }
+    "#;
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+const ITERATION_SOURCE: &str = r#"
+    enum Option<T> { Some(T), None }
+    trait Iterator<T> { Option<T> next(); }
+
+    struct Countdown { int n }
+    impl Countdown {
+        Option<int> next() {
+            if this.n == 0 {
+                return Option::<int>.None
+            }
+            this.n = this.n - 1
+            return Option::<int>.Some(this.n)
+        }
+    }
+
+    struct Bag { Vec<string> items }
+    impl Bag {
+        Countdown iter() { return Countdown { n: this.items.len() } }
+    }
+
+    int sum<I: Iterator<int>>(I it) {
+        var total = 0
+        for x in it {
+            total = total + x
+        }
+        return total
+    }
+
+    int main() {
+        var total = 0
+        for x in vec![1, 2, 3] {
+            total = total + x
+        }
+        for i in Countdown { n: 3 } {
+            total = total + i
+        }
+        for j in Bag { items: vec!["a"] } {
+            var k: int = j
+        }
+        for c in "héllo" {
+            var d: char = c
+        }
+        return total
+    }
+"#;
+
+#[test]
+fn test_for_in_iteration_protocol() {
+    let mut program = lency_syntax::parser::parse(ITERATION_SOURCE).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // 元素类型来自 Vec<T> / next() 的 Option<T>
+    let source = ITERATION_SOURCE.replace("var k: int = j", "var k: string = j");
+    let mut program = lency_syntax::parser::parse(&source).expect("parse failed");
+    let errors = analyze(&mut program).unwrap_err();
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        SemanticError::TypeMismatch { expected, found, .. } if expected == "string" && found == "int"
+    )));
+
+    let errors =
+        analyze_errors("int main() {\n    for x in 42 {\n        print(x)\n    }\n    return 0\n}");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::NotIterable { ty, .. } if ty == "int"
+    ));
+}
+
+#[test]
+fn test_range_loops_and_slices() {
+    let source = r#"
+        int main() {
+            var total = 0
+            for i in 0..10 {
+                total = total + i
+            }
+            var r: Range = 1..=3
+            for j in r {
+                total = total + j
+            }
+            var v = vec![1, 2, 3, 4]
+            var part: Vec<int> = v[1..3]
+            var s: string = "hello"[1..=2]
+            return total + part.len()
+        }
+    "#;
+    let mut program = lency_syntax::parser::parse(source).expect("parse failed");
+    let result = analyze(&mut program);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // 两端必须是 int
+    let errors = analyze_errors("int main() {\n    var r = 0..\"a\"\n    return 0\n}");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::TypeMismatch { expected, found, .. } if expected == "int" && found == "string"
+    ));
+
+    // 定长数组不能切片
+    let errors =
+        analyze_errors("int main() {\n    var a = [1, 2, 3]\n    var b = a[0..2]\n    return 0\n}");
+    assert!(matches!(
+        &errors[0],
+        SemanticError::TypeMismatch { expected, .. } if expected == "Vec or string"
+    ));
+}
@@ -45,42 +45,17 @@ pub fn check_decl(checker: &mut TypeChecker, decl: &mut Decl) {
             }
         }
         Decl::Var {
-            span: _,
+            span,
             name,
             ty,
             value,
+            ..
         } => {
-            // Global Scopes are already active?
-            // TypeChecker runs in global scope by default.
-
-            // Infer value type
-            // Note: infer_type needs &mut Expr
-            let value_ty = match checker.infer_type(value) {
-                Ok(t) => t,
-                Err(e) => {
-                    checker.errors.push(e);
-                    lency_syntax::ast::Type::Void // Placeholder
-                }
-            };
-
-            // Check type annotation
-            if let Some(annotation) = ty {
-                if annotation != &value_ty {
-                    // Simple check for now
-                    checker.errors.push(SemanticError::TypeMismatch {
-                        expected: annotation.to_string(),
-                        found: value_ty.to_string(),
-                        span: value.span.clone(),
-                    });
-                }
-            } else {
-                // Update symbol type if inferred
-                if let Some(id) = checker.scopes.lookup_id(name) {
-                    if let Some(crate::symbol::Symbol::Variable(sym)) =
-                        checker.scopes.get_symbol_mut(id)
-                    {
-                        sym.ty = value_ty;
-                    }
+            super::stmt::check_var_decl(checker, name, ty.as_ref(), value, span);
+            // 未标注类型的全局变量记录推导出的类型，供代码生成声明全局符号
+            if ty.is_none() {
+                if let Some(crate::symbol::Symbol::Variable(sym)) = checker.scopes.lookup(name) {
+                    *ty = Some(sym.ty.clone());
                 }
             }
         }
@@ -1,7 +1,10 @@
 use super::TypeChecker;
 use crate::error::SemanticError;
 use crate::symbol::{FunctionSymbol, Symbol};
-use crate::type_infer::{is_compatible, substitute_type};
+use crate::type_infer::{
+    bound_method, integer_method, integer_method_result, is_compatible, string_method,
+    substitute_type,
+};
 use lency_syntax::ast::{Expr, ExprKind, Type};
 use std::collections::HashMap;
 
@@ -88,6 +91,41 @@ pub fn check_call(
             // 方法调用处理
             let obj_type = checker.infer_type(object)?;
 
+            // 整数的 wrapping_* / checked_* 内置方法：一个同类型实参
+            if let Some(operand) = integer_method(&obj_type, name) {
+                if args.len() != 1 {
+                    return Err(SemanticError::ArgumentCountMismatch {
+                        name: name.clone(),
+                        expected: 1,
+                        found: args.len(),
+                        span: span.clone(),
+                    });
+                }
+                let arg_ty = checker.infer_type(&mut args[0])?;
+                let arg_ty = checker.coerce_to(&mut args[0], &operand, arg_ty)?;
+                if !is_compatible(&operand, &arg_ty) {
+                    return Err(SemanticError::TypeMismatch {
+                        expected: operand.to_string(),
+                        found: arg_ty.to_string(),
+                        span: args[0].span.clone(),
+                    });
+                }
+                return integer_method_result(checker.scopes, operand, name, span);
+            }
+
+            // 字符串的 chars() / bytes() 视图：无参数
+            if let Some(view) = string_method(&obj_type, name) {
+                if !args.is_empty() {
+                    return Err(SemanticError::ArgumentCountMismatch {
+                        name: name.clone(),
+                        expected: 0,
+                        found: args.len(),
+                        span: span.clone(),
+                    });
+                }
+                return Ok(view);
+            }
+
             // Check if it's a type that supports method lookup via name (Structs or Primitives)
             let type_name_opt = match &obj_type {
                 Type::Struct(n) => Some(n.clone()),
@@ -153,68 +191,58 @@ pub fn check_call(
             } else {
                 match obj_type {
                     Type::GenericParam(param_name) => {
-                        // 泛型参数方法调用: t.foo() where T: Trait
-                        if let Some(Symbol::GenericParam(gp_sym)) =
-                            checker.scopes.lookup(&param_name)
-                        {
-                            if let Some(bound_ty) = &gp_sym.bound {
-                                // 解析约束类型
-                                // 目前假设 bound 是 Type::Struct(TraitName) 形式（由 Parser 生成）
-                                if let Type::Struct(trait_name) = bound_ty {
-                                    if let Some(Symbol::Trait(trait_sym)) =
-                                        checker.scopes.lookup(trait_name)
-                                    {
-                                        if let Some(trait_method) = trait_sym.get_method(name) {
-                                            // 从 Trait 方法签名构造 FunctionSymbol
-                                            // 需要添加隐式 this 参数，类型为 GenericParam(T)
-                                            let mut params = trait_method.params.clone();
-                                            params.insert(
-                                                0,
-                                                (
-                                                    "this".to_string(),
-                                                    Type::GenericParam(param_name.clone()),
-                                                ),
-                                            );
+                        // 泛型参数方法调用: t.foo() where T: A + B
+                        let trait_method =
+                            bound_method(|n| checker.scopes.lookup(n), &param_name, name, span)?;
+                        // 从 Trait 方法签名构造 FunctionSymbol
+                        // 需要添加隐式 this 参数，类型为 GenericParam(T)
+                        let mut params = trait_method.params;
+                        params.insert(0, ("this".to_string(), Type::GenericParam(param_name)));
 
-                                            let func_sym = FunctionSymbol {
-                                                name: trait_method.name.clone(),
-                                                params,
-                                                return_type: trait_method.return_type.clone(),
-                                                generic_params: vec![], // Trait 方法特定的泛型参数？暂不支持
-                                                span: trait_sym.span.clone(), // 使用 Trait 的 span 作为近似
-                                                is_public: true, // Trait 方法通过接口总是可见的
-                                            };
-                                            (func_sym, true, HashMap::new())
-                                        } else {
-                                            return Err(SemanticError::UndefinedMethod {
-                                                class: format!("Trait {}", trait_name),
-                                                method: name.clone(),
-                                                span: span.clone(),
-                                            });
-                                        }
-                                    } else {
-                                        return Err(SemanticError::UndefinedTrait {
-                                            name: trait_name.clone(),
-                                            span: span.clone(),
-                                        });
-                                    }
-                                } else {
-                                    return Err(SemanticError::NotCallable {
-                                        ty: format!("Bounded type {:?}", bound_ty),
+                        let func_sym = FunctionSymbol {
+                            name: trait_method.name,
+                            params,
+                            return_type: trait_method.return_type,
+                            generic_params: vec![], // Trait 方法特定的泛型参数？暂不支持
+                            span: span.clone(),
+                            is_public: true, // Trait 方法通过接口总是可见的
+                        };
+                        (func_sym, true, HashMap::new())
+                    }
+                    Type::TraitObject(trait_name) => {
+                        // dyn Trait 方法调用：签名取自 Trait 定义
+                        match checker.scopes.lookup(&trait_name) {
+                            Some(Symbol::Trait(trait_sym)) => match trait_sym.get_method(name) {
+                                Some(trait_method) => {
+                                    let mut params = trait_method.params.clone();
+                                    params.insert(
+                                        0,
+                                        ("this".to_string(), Type::TraitObject(trait_name.clone())),
+                                    );
+                                    let func_sym = FunctionSymbol {
+                                        name: trait_method.name.clone(),
+                                        params,
+                                        return_type: trait_method.return_type.clone(),
+                                        generic_params: vec![],
+                                        span: trait_sym.span.clone(),
+                                        is_public: true,
+                                    };
+                                    (func_sym, true, HashMap::new())
+                                }
+                                None => {
+                                    return Err(SemanticError::UndefinedMethod {
+                                        class: format!("dyn {}", trait_name),
+                                        method: name.clone(),
                                         span: span.clone(),
                                     });
                                 }
-                            } else {
-                                return Err(SemanticError::NotCallable {
-                                    ty: format!("Generic {} has no bounds", param_name),
+                            },
+                            _ => {
+                                return Err(SemanticError::UndefinedTrait {
+                                    name: trait_name,
                                     span: span.clone(),
                                 });
                             }
-                        } else {
-                            return Err(SemanticError::UndefinedType {
-                                name: param_name,
-                                span: object.span.clone(),
-                            });
                         }
                     }
                     Type::Vec(inner_type) => {
@@ -231,6 +259,8 @@ pub fn check_call(
                                     });
                                 }
                                 let arg_ty = checker.infer_type(&mut args[0])?;
+                                let arg_ty =
+                                    checker.coerce_to(&mut args[0], &inner_type, arg_ty)?;
                                 if !is_compatible(&inner_type, &arg_ty) {
                                     return Err(SemanticError::TypeMismatch {
                                         expected: inner_type.to_string(),
@@ -303,6 +333,8 @@ pub fn check_call(
                                     });
                                 }
                                 let val_ty = checker.infer_type(&mut args[1])?;
+                                let val_ty =
+                                    checker.coerce_to(&mut args[1], &inner_type, val_ty)?;
                                 if !is_compatible(&inner_type, &val_ty) {
                                     return Err(SemanticError::TypeMismatch {
                                         expected: inner_type.to_string(),
@@ -361,6 +393,13 @@ pub fn check_call(
         let arg_ty = checker.infer_type(arg)?;
         // 关键：检查参数前先替换其中的泛型参数
         let expected_ty = substitute_type(param_ty, &subst_map);
+        let arg_ty = match checker.coerce_to(arg, &expected_ty, arg_ty) {
+            Ok(ty) => ty,
+            Err(e) => {
+                checker.errors.push(e);
+                continue;
+            }
+        };
 
         if !is_compatible(&expected_ty, &arg_ty) {
             checker.errors.push(SemanticError::TypeMismatch {
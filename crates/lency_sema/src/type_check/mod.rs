@@ -4,8 +4,10 @@
 //! 遵循 Lency "Safety by Default" 哲学：严格的类型检查，拒绝隐式错误。
 
 use crate::error::SemanticError;
+use crate::module_source::ModuleSources;
 use crate::scope::ScopeStack;
 use crate::type_infer::TypeInferer;
+use crate::type_table::TypeTable;
 use lency_syntax::ast::{Decl, Expr, Program, Stmt, Type};
 
 pub mod decl;
@@ -22,6 +24,13 @@ pub struct TypeChecker<'a> {
     pub(crate) next_child_index: usize,
     /// 当前循环嵌套深度
     pub(crate) loop_depth: usize,
+    /// 入口源文件中表达式的推导类型
+    pub(crate) types: TypeTable,
+    /// 入口源文件的声明数量（其后为导入模块的声明，不记录类型）
+    pub(crate) source_decls: usize,
+    /// 导入模块的声明区间（用于标记错误来源）
+    module_sources: ModuleSources,
+    recording: bool,
 }
 
 impl<'a> TypeChecker<'a> {
@@ -32,14 +41,50 @@ impl<'a> TypeChecker<'a> {
             current_return_type: None,
             next_child_index: 0,
             loop_depth: 0,
+            types: TypeTable::new(),
+            source_decls: 0,
+            module_sources: ModuleSources::new(),
+            recording: false,
         }
     }
 
+    /// 记录前 `count` 个（来自入口源文件的）声明中的表达式类型
+    pub fn with_type_table(mut self, count: usize) -> Self {
+        self.source_decls = count;
+        self
+    }
+
+    /// 按导入模块的声明区间标记错误来源
+    pub fn with_module_sources(mut self, sources: ModuleSources) -> Self {
+        self.module_sources = sources;
+        self
+    }
+
+    /// 取出记录的类型表
+    pub fn take_types(&mut self) -> TypeTable {
+        std::mem::take(&mut self.types)
+    }
+
     /// 检查整个程序
     pub fn check(&mut self, program: &mut Program) -> Result<(), Vec<SemanticError>> {
-        for decl in &mut program.decls {
+        // 全局变量先于其他声明检查：函数体可以使用在它之后声明的全局变量
+        let (globals, others): (Vec<_>, Vec<_>) = program
+            .decls
+            .iter_mut()
+            .enumerate()
+            .partition(|(_, decl)| matches!(decl, Decl::Var { .. }));
+        // 复用缓存分析结果的模块已经检查过，Resolver 也没有为它们创建作用域
+        for (index, decl) in globals.into_iter().chain(others) {
+            if self.module_sources.is_reused(index) {
+                continue;
+            }
+            self.recording = index < self.source_decls;
+            let mark = self.errors.len();
             self.check_decl(decl);
+            self.module_sources
+                .attribute(&mut self.errors[mark..], index);
         }
+        self.recording = false;
 
         if self.errors.is_empty() {
             Ok(())
@@ -71,9 +116,35 @@ impl<'a> TypeChecker<'a> {
     /// 推导表达式类型（封装 TypeInferer）
     pub(crate) fn infer_type(&mut self, expr: &mut Expr) -> Result<Type, SemanticError> {
         let mut inferer = TypeInferer::new(self.scopes);
+        if self.recording {
+            inferer = inferer.recording(&mut self.types);
+        }
         inferer.infer(expr)
     }
 
+    /// 按期望类型推导表达式类型，必要时插入 trait 对象转换
+    pub(crate) fn infer_type_expected(
+        &mut self,
+        expr: &mut Expr,
+        expected: &Type,
+    ) -> Result<Type, SemanticError> {
+        let mut inferer = TypeInferer::new(self.scopes);
+        if self.recording {
+            inferer = inferer.recording(&mut self.types);
+        }
+        inferer.infer_expected(expr, expected)
+    }
+
+    /// 期望 `dyn Trait` 的位置插入 trait 对象转换（封装 TypeInferer）
+    pub(crate) fn coerce_to(
+        &mut self,
+        expr: &mut Expr,
+        expected: &Type,
+        actual: Type,
+    ) -> Result<Type, SemanticError> {
+        TypeInferer::new(self.scopes).coerce_to(expr, expected, actual)
+    }
+
     /// 检查代码块是否有返回语句
     pub(crate) fn has_return(&self, stmts: &[Stmt]) -> bool {
         Self::has_return_impl(stmts)
@@ -83,24 +154,25 @@ impl<'a> TypeChecker<'a> {
         for stmt in stmts {
             match stmt {
                 Stmt::Return { .. } => return true,
+                // 只有两个分支都有 return 才算完整覆盖
                 Stmt::If {
                     then_block,
                     else_block,
                     ..
-                } => {
-                    // 只有两个分支都有 return 才算完整覆盖
-                    if Self::has_return_impl(then_block) {
-                        if let Some(else_stmts) = else_block {
-                            if Self::has_return_impl(else_stmts) {
-                                return true;
-                            }
-                        }
-                    }
                 }
-                Stmt::Block(inner) => {
-                    if Self::has_return_impl(inner) {
-                        return true;
-                    }
+                | Stmt::IfLet {
+                    then_block,
+                    else_block,
+                    ..
+                } if Self::has_return_impl(then_block)
+                    && else_block
+                        .as_ref()
+                        .is_some_and(|else_stmts| Self::has_return_impl(else_stmts)) =>
+                {
+                    return true;
+                }
+                Stmt::Block(inner) if Self::has_return_impl(inner) => {
+                    return true;
                 }
                 _ => {}
             }
@@ -1,3 +1,4 @@
+use super::iteration::iteration_element;
 use super::{check_block_with_scope, check_stmt};
 use crate::error::SemanticError;
 use crate::symbol::Symbol;
@@ -195,15 +196,17 @@ pub fn check_for_in(
 ) {
     // 1. check iterable (outside of loop scope)
     let elem_ty = match checker.infer_type(iterable) {
-        Ok(Type::Array { element_type, .. }) => *element_type,
-        Ok(ty) => {
-            checker.errors.push(SemanticError::TypeMismatch {
-                expected: "Array".to_string(),
-                found: ty.to_string(),
-                span: iterable.span.clone(),
-            });
-            Type::Error
-        }
+        Ok(Type::Error) => Type::Error,
+        Ok(ty) => match iteration_element(checker, &ty) {
+            Some(elem_ty) => elem_ty,
+            None => {
+                checker.errors.push(SemanticError::NotIterable {
+                    ty: ty.to_string(),
+                    span: iterable.span.clone(),
+                });
+                Type::Error
+            }
+        },
         Err(e) => {
             checker.errors.push(e);
             Type::Error
@@ -0,0 +1,110 @@
+//! Pattern Statements
+//!
+//! `var` 解构、`if let` 与 `while let`：模式检查与穷尽性复用 `match` 的实现
+//! （`TypeInferer::pattern_bindings` / `TypeInferer::uncovered`），
+//! 因此绑定变量的类型（包括可空性）与对应的 `match` 分支一致。
+
+use super::{check_block_with_bindings, check_block_with_scope};
+use crate::error::SemanticError;
+use crate::symbol::Symbol;
+use crate::type_check::TypeChecker;
+use crate::type_infer::TypeInferer;
+use lency_syntax::ast::{Expr, MatchPattern, Stmt, Type};
+
+type Bindings = Vec<(String, Type)>;
+
+/// `var Pair { key, value } = p`：模式必须覆盖值的所有可能
+pub fn check_var_pattern(
+    checker: &mut TypeChecker,
+    pattern: &MatchPattern,
+    value: &mut Expr,
+    span: &std::ops::Range<usize>,
+) {
+    let (value_ty, bindings) = infer_bindings(checker, pattern, value, span);
+    set_binding_types(checker, bindings);
+
+    let missing = TypeInferer::new(checker.scopes).uncovered(&[pattern], &value_ty);
+    if !missing.is_empty() {
+        checker.errors.push(SemanticError::RefutablePattern {
+            missing,
+            span: span.clone(),
+        });
+    }
+}
+
+/// `if let pat = value { ... } else { ... }`：绑定只在 then 分支可见
+pub fn check_if_let(
+    checker: &mut TypeChecker,
+    pattern: &MatchPattern,
+    value: &mut Expr,
+    then_block: &mut [Stmt],
+    else_block: Option<&mut [Stmt]>,
+    span: &std::ops::Range<usize>,
+) {
+    let (_, bindings) = infer_bindings(checker, pattern, value, span);
+    check_block_with_bindings(checker, bindings, then_block);
+
+    if let Some(else_stmts) = else_block {
+        check_block_with_scope(checker, else_stmts);
+    }
+}
+
+/// `while let pat = value { ... }`
+pub fn check_while_let(
+    checker: &mut TypeChecker,
+    pattern: &MatchPattern,
+    value: &mut Expr,
+    body: &mut [Stmt],
+    span: &std::ops::Range<usize>,
+) {
+    let (_, bindings) = infer_bindings(checker, pattern, value, span);
+
+    checker.loop_depth += 1;
+    check_block_with_bindings(checker, bindings, body);
+    checker.loop_depth -= 1;
+}
+
+/// 推导被匹配值的类型并检查模式；出错时绑定的变量记为 Error 类型
+fn infer_bindings(
+    checker: &mut TypeChecker,
+    pattern: &MatchPattern,
+    value: &mut Expr,
+    span: &std::ops::Range<usize>,
+) -> (Type, Bindings) {
+    let value_ty = match checker.infer_type(value) {
+        Ok(ty) => ty,
+        Err(e) => {
+            checker.errors.push(e);
+            Type::Error
+        }
+    };
+    let unknown = || -> Bindings {
+        pattern
+            .bindings()
+            .into_iter()
+            .map(|name| (name.to_string(), Type::Error))
+            .collect()
+    };
+    if value_ty == Type::Error {
+        return (value_ty, unknown());
+    }
+
+    match TypeInferer::new(checker.scopes).pattern_bindings(pattern, &value_ty, span) {
+        Ok(bindings) => (value_ty, bindings),
+        Err(e) => {
+            checker.errors.push(e);
+            (Type::Error, unknown())
+        }
+    }
+}
+
+/// 更新当前作用域中模式变量的类型（Resolver 阶段只是初步推导）
+pub(super) fn set_binding_types(checker: &mut TypeChecker, bindings: Bindings) {
+    for (name, ty) in bindings {
+        if let Some(symbol_id) = checker.scopes.lookup_id(&name) {
+            if let Some(Symbol::Variable(var_sym)) = checker.scopes.get_symbol_mut(symbol_id) {
+                var_sym.ty = ty;
+            }
+        }
+    }
+}
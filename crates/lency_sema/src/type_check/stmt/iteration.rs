@@ -0,0 +1,65 @@
+//! For-in Iteration Protocol
+//!
+//! `for x in expr` 的元素类型：数组、字符串（按 char）、`Vec<T>` 与区间（int）内置支持；
+//! 其他类型走迭代器协议：无参 `next()` 返回 `Option<T>` 的类型逐个取出 T，
+//! 否则调用无参 `iter()`，再按上述规则迭代其结果。
+//! codegen（`stmt::control_flow::for_in`）按同样的顺序选择生成方式。
+
+use std::collections::HashMap;
+
+use crate::symbol::Symbol;
+use crate::type_check::TypeChecker;
+use crate::type_infer::{bound_method, substitute_type};
+use lency_syntax::ast::Type;
+
+/// `ty` 可以被 for-in 迭代时返回元素类型
+pub(super) fn iteration_element(checker: &TypeChecker, ty: &Type) -> Option<Type> {
+    direct_element(checker, ty).or_else(|| {
+        let iter_ty = method_return_type(checker, ty, "iter")?;
+        direct_element(checker, &iter_ty)
+    })
+}
+
+/// 不经 `iter()` 即可迭代的类型
+fn direct_element(checker: &TypeChecker, ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Array { element_type, .. } => Some((**element_type).clone()),
+        Type::String => Some(Type::Char),
+        Type::Vec(inner) => Some((**inner).clone()),
+        Type::Range => Some(Type::Int),
+        _ => match method_return_type(checker, ty, "next")? {
+            Type::Generic(name, mut args) if name == "Option" && args.len() == 1 => args.pop(),
+            _ => None,
+        },
+    }
+}
+
+/// 类型上无参方法的返回类型（已代入泛型实参）
+fn method_return_type(checker: &TypeChecker, ty: &Type, method: &str) -> Option<Type> {
+    let (type_name, type_args) = match ty {
+        // T: Iterator<U> 之类的约束
+        Type::GenericParam(param) => {
+            let signature =
+                bound_method(|n| checker.scopes.lookup(n), param, method, &(0..0)).ok()?;
+            return signature.params.is_empty().then_some(signature.return_type);
+        }
+        Type::Struct(name) => (name, &[][..]),
+        Type::Generic(name, args) => (name, args.as_slice()),
+        _ => return None,
+    };
+
+    let (generic_params, function) = match checker.scopes.lookup(type_name)? {
+        Symbol::Struct(sym) => (&sym.generic_params, sym.get_method(method)?),
+        Symbol::Enum(sym) => (&sym.generic_params, sym.methods.get(method)?),
+        _ => return None,
+    };
+    if !function.params.is_empty() {
+        return None;
+    }
+    let mapping: HashMap<String, Type> = generic_params
+        .iter()
+        .zip(type_args)
+        .map(|(param, arg)| (param.name.clone(), arg.clone()))
+        .collect();
+    Some(substitute_type(&function.return_type, &mapping))
+}
@@ -4,10 +4,22 @@ use crate::type_infer::is_compatible;
 use lency_syntax::ast::{Expr, Stmt, Type};
 
 pub mod control_flow;
+mod destructure;
+mod iteration;
 use control_flow::{check_for, check_for_in, check_if, check_while};
+use destructure::{check_if_let, check_var_pattern, check_while_let};
 
 /// 辅助函数：进入新的作用域并检查代码块
 pub(crate) fn check_block_with_scope(checker: &mut TypeChecker, stmts: &mut [Stmt]) {
+    check_block_with_bindings(checker, Vec::new(), stmts);
+}
+
+/// 进入新的作用域，先更新其中模式变量的类型，再检查代码块
+pub(crate) fn check_block_with_bindings(
+    checker: &mut TypeChecker,
+    bindings: Vec<(String, Type)>,
+    stmts: &mut [Stmt],
+) {
     // 保存当前作用域
     let parent_scope = checker.scopes.current_scope();
     let children = checker.scopes.get_child_scopes(parent_scope);
@@ -16,6 +28,7 @@ pub(crate) fn check_block_with_scope(checker: &mut TypeChecker, stmts: &mut [Stm
     if let Some(&child_scope) = children.get(checker.next_child_index) {
         checker.scopes.set_current(child_scope);
         checker.next_child_index += 1;
+        destructure::set_binding_types(checker, bindings);
 
         // 保存并重置子索引
         let prev_child_index = checker.next_child_index;
@@ -47,6 +60,13 @@ pub fn check_stmt(checker: &mut TypeChecker, stmt: &mut Stmt) {
         } => {
             check_var_decl(checker, name, ty.as_ref(), value, span);
         }
+        Stmt::VarPattern {
+            pattern,
+            value,
+            span,
+        } => {
+            check_var_pattern(checker, pattern, value, span);
+        }
         Stmt::Assignment {
             target,
             value,
@@ -77,6 +97,22 @@ pub fn check_stmt(checker: &mut TypeChecker, stmt: &mut Stmt) {
                 span,
             );
         }
+        Stmt::IfLet {
+            pattern,
+            value,
+            then_block,
+            else_block,
+            span,
+        } => {
+            check_if_let(
+                checker,
+                pattern,
+                value,
+                then_block,
+                else_block.as_deref_mut(),
+                span,
+            );
+        }
         Stmt::While {
             condition,
             body,
@@ -84,6 +120,14 @@ pub fn check_stmt(checker: &mut TypeChecker, stmt: &mut Stmt) {
         } => {
             check_while(checker, condition, body, span);
         }
+        Stmt::WhileLet {
+            pattern,
+            value,
+            body,
+            span,
+        } => {
+            check_while_let(checker, pattern, value, body, span);
+        }
         Stmt::For {
             init,
             condition,
@@ -128,15 +172,19 @@ pub fn check_stmt(checker: &mut TypeChecker, stmt: &mut Stmt) {
     }
 }
 
-fn check_var_decl(
+pub(super) fn check_var_decl(
     checker: &mut TypeChecker,
     name: &str,
     declared_ty: Option<&Type>,
     value: &mut Expr,
     span: &std::ops::Range<usize>,
 ) {
-    // 推导初始化表达式的类型
-    let value_ty = match checker.infer_type(value) {
+    // 推导初始化表达式的类型（有显式类型声明时按声明类型推导）
+    let inferred = match declared_ty {
+        Some(expected) => checker.infer_type_expected(value, expected),
+        None => checker.infer_type(value),
+    };
+    let value_ty = match inferred {
         Ok(ty) => ty,
         Err(e) => {
             checker.errors.push(e);
@@ -187,7 +235,7 @@ fn check_assignment(
         }
     };
 
-    let value_ty = match checker.infer_type(value) {
+    let value_ty = match checker.infer_type_expected(value, &target_ty) {
         Ok(ty) => ty,
         Err(e) => {
             checker.errors.push(e);
@@ -218,7 +266,7 @@ fn check_return(
     };
 
     match (value, &expected) {
-        (Some(expr), _) => match checker.infer_type(expr) {
+        (Some(expr), _) => match checker.infer_type_expected(expr, &expected) {
             Ok(actual) => {
                 if !is_compatible(&expected, &actual) {
                     checker.errors.push(SemanticError::ReturnTypeMismatch {
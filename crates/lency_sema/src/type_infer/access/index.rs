@@ -49,6 +49,11 @@ impl<'a> TypeInferer<'a> {
         let array_ty = self.infer(array)?;
         let index_ty = self.infer(index)?;
 
+        // 区间下标是切片
+        if index_ty == Type::Range {
+            return self.infer_slice(&array_ty, span);
+        }
+
         // 索引必须是 int 类型
         if index_ty != Type::Int {
             return Err(SemanticError::TypeMismatch {
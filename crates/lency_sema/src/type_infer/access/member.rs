@@ -89,6 +89,8 @@ impl<'a> TypeInferer<'a> {
         let obj_ty = self.infer(object)?;
 
         match &obj_ty {
+            // 对象本身已出错（错误已报告），避免级联报错
+            Type::Error => Ok(Type::Error),
             // 结构体成员访问
             Type::Struct(struct_name) => {
                 // 查找结构体定义并获取字段
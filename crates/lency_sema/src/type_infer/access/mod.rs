@@ -8,11 +8,18 @@ use lency_syntax::ast::{Expr, Type};
 
 impl<'a> TypeInferer<'a> {
     pub(crate) fn infer_variable(
-        &self,
+        &mut self,
         name: &str,
         span: &std::ops::Range<usize>,
     ) -> Result<Type, SemanticError> {
-        self.infer_variable_impl(name, span)
+        let ty = self.infer_variable_impl(name, span)?;
+        if self.lookup(name).is_some() {
+            self.record_capture(name);
+        } else {
+            // 隐式 this 字段访问：闭包需要捕获 this
+            self.record_capture("this");
+        }
+        Ok(ty)
     }
 
     pub(crate) fn infer_get(
@@ -49,6 +49,7 @@ impl<'a> TypeInferer<'a> {
                 Err(SemanticError::UndefinedVariable {
                     name: name.to_string(),
                     span: span.clone(),
+                    suggestion: self.similar_name(name),
                 })
             }
         }
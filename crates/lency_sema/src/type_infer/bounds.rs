@@ -0,0 +1,89 @@
+//! Generic Parameter Bounds
+//!
+//! 泛型参数上的方法调用 `t.method()`：在 T 的所有约束 Trait（`T: A + B`）中查找方法签名。
+//! 泛型 Trait 约束（如 `K: Eq<K>`）的类型实参会代入签名。
+
+use std::collections::HashMap;
+
+use crate::error::SemanticError;
+use crate::symbol::{Symbol, TraitMethodSignature};
+use lency_syntax::ast::{Span, Type};
+
+use super::substitute_type;
+
+/// 查找泛型参数 `param_name` 的约束中名为 `method` 的方法
+pub(crate) fn bound_method<'s>(
+    lookup: impl Fn(&str) -> Option<&'s Symbol>,
+    param_name: &str,
+    method: &str,
+    span: &Span,
+) -> Result<TraitMethodSignature, SemanticError> {
+    let Some(Symbol::GenericParam(gp)) = lookup(param_name) else {
+        return Err(SemanticError::UndefinedType {
+            name: param_name.to_string(),
+            span: span.clone(),
+        });
+    };
+    if gp.bounds.is_empty() {
+        return Err(SemanticError::NotCallable {
+            ty: format!("Generic {} has no bounds", param_name),
+            span: span.clone(),
+        });
+    }
+
+    for bound in &gp.bounds {
+        let (trait_name, args) = match bound {
+            Type::Struct(name) => (name, &[][..]),
+            Type::Generic(name, args) => (name, args.as_slice()),
+            _ => {
+                return Err(SemanticError::NotCallable {
+                    ty: format!("Bounded type {:?}", bound),
+                    span: span.clone(),
+                })
+            }
+        };
+        let Some(Symbol::Trait(trait_sym)) = lookup(trait_name) else {
+            return Err(SemanticError::UndefinedTrait {
+                name: trait_name.clone(),
+                span: span.clone(),
+            });
+        };
+        let Some(signature) = trait_sym.get_method(method) else {
+            continue;
+        };
+
+        // Trait 的泛型参数 -> 约束中的类型实参（约束里的 `K` 解析为泛型参数）
+        let mapping: HashMap<String, Type> = trait_sym
+            .generic_params
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| {
+                let arg = match arg {
+                    Type::Struct(name) if matches!(lookup(name), Some(Symbol::GenericParam(_))) => {
+                        Type::GenericParam(name.clone())
+                    }
+                    _ => arg.clone(),
+                };
+                (param.name.clone(), arg)
+            })
+            .collect();
+        return Ok(TraitMethodSignature::new(
+            signature.name.clone(),
+            signature
+                .params
+                .iter()
+                .map(|(name, ty)| (name.clone(), substitute_type(ty, &mapping)))
+                .collect(),
+            substitute_type(&signature.return_type, &mapping),
+        ));
+    }
+
+    Err(SemanticError::UndefinedMethod {
+        class: format!(
+            "Trait {}",
+            gp.bound_traits().collect::<Vec<_>>().join(" + ")
+        ),
+        method: method.to_string(),
+        span: span.clone(),
+    })
+}
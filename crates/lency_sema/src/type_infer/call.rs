@@ -1,4 +1,4 @@
-use super::TypeInferer;
+use super::{bound_method, TypeInferer};
 use crate::error::SemanticError;
 use crate::symbol::Symbol;
 use lency_syntax::ast::{Expr, ExprKind, Type};
@@ -8,21 +8,39 @@ impl<'a> TypeInferer<'a> {
     pub(crate) fn infer_call(
         &mut self,
         callee: &mut Expr,
-        _args: &mut [Expr],
+        args: &mut [Expr],
         span: &std::ops::Range<usize>,
     ) -> Result<Type, SemanticError> {
+        // 推导实参（闭包实参需要在此完成捕获分析）
+        let arg_types = args
+            .iter_mut()
+            .map(|arg| self.infer(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+
         match &mut callee.kind {
             ExprKind::Variable(name) => {
-                match self.lookup(name) {
-                    Some(Symbol::Function(func)) => Ok(func.return_type.clone()),
+                // 调用闭包变量时同样可能发生捕获
+                self.record_capture(name);
+                match self.lookup(name).cloned() {
+                    Some(Symbol::Function(func)) => {
+                        let params: Vec<Type> =
+                            func.params.iter().map(|(_, t)| t.clone()).collect();
+                        self.coerce_args(args, &arg_types, &params)?;
+                        Ok(func.return_type)
+                    }
                     Some(Symbol::Struct(s)) => {
                         // Constructor
-                        Ok(Type::Struct(s.name.clone()))
+                        Ok(Type::Struct(s.name))
                     }
                     // 支持调用函数类型的变量 (闭包)
                     Some(Symbol::Variable(var)) => {
-                        if let Type::Function { return_type, .. } = &var.ty {
-                            Ok(*return_type.clone())
+                        if let Type::Function {
+                            param_types,
+                            return_type,
+                        } = var.ty
+                        {
+                            self.coerce_args(args, &arg_types, &param_types)?;
+                            Ok(*return_type)
                         } else {
                             Err(SemanticError::NotCallable {
                                 ty: var.ty.to_string(),
@@ -31,8 +49,13 @@ impl<'a> TypeInferer<'a> {
                         }
                     }
                     Some(Symbol::Parameter(param)) => {
-                        if let Type::Function { return_type, .. } = &param.ty {
-                            Ok(*return_type.clone())
+                        if let Type::Function {
+                            param_types,
+                            return_type,
+                        } = param.ty
+                        {
+                            self.coerce_args(args, &arg_types, &param_types)?;
+                            Ok(*return_type)
                         } else {
                             Err(SemanticError::NotCallable {
                                 ty: param.ty.to_string(),
@@ -46,7 +69,8 @@ impl<'a> TypeInferer<'a> {
                     }),
                     None => Err(SemanticError::UndefinedFunction {
                         name: name.clone(),
-                        span: span.clone(),
+                        span: callee.span.clone(),
+                        suggestion: self.similar_name(name),
                     }),
                 }
             }
@@ -136,6 +160,32 @@ impl<'a> TypeInferer<'a> {
 
                 // 2. Check Method Call: obj.method(...)
                 let obj_ty = self.infer(object)?;
+                // 对象本身已出错（错误已报告），避免级联报错
+                if obj_ty == Type::Error {
+                    return Ok(Type::Error);
+                }
+                // 整数的 wrapping_* / checked_* 内置方法
+                if let Some(operand) = crate::type_infer::integer_method(&obj_ty, name) {
+                    self.coerce_args(args, &arg_types, std::slice::from_ref(&operand))?;
+                    return crate::type_infer::integer_method_result(
+                        self.scopes,
+                        operand,
+                        name,
+                        span,
+                    );
+                }
+                // 字符串的 chars() / bytes() 视图
+                if let Some(view) = crate::type_infer::string_method(&obj_ty, name) {
+                    if !args.is_empty() {
+                        return Err(SemanticError::ArgumentCountMismatch {
+                            name: name.clone(),
+                            expected: 0,
+                            found: args.len(),
+                            span: span.clone(),
+                        });
+                    }
+                    return Ok(view);
+                }
                 // Check if it's a type that supports method lookup via name (Structs, Enums, or Primitives)
                 let type_name_opt = match &obj_ty {
                     Type::Struct(n) => Some(n.clone()),
@@ -155,15 +205,18 @@ impl<'a> TypeInferer<'a> {
                     }
                     // Sprint 15: Support Option<T> method calls
                     // 内置方法: is_some, is_none, unwrap, unwrap_or
-                    Type::Generic(base_name, args) if base_name == "Option" => {
+                    Type::Generic(base_name, type_args) if base_name == "Option" => {
                         match name.as_str() {
                             "is_some" | "is_none" => return Ok(Type::Bool),
                             "unwrap" | "unwrap_or" => {
-                                if args.len() == 1 {
-                                    return Ok(args[0].clone());
-                                } else {
+                                let [inner] = type_args.as_slice() else {
                                     return Ok(Type::Error);
+                                };
+                                // 默认值按 T 收窄（如 Option<u8> 的 unwrap_or(0)）
+                                if let ([default], [default_ty]) = (&mut *args, &arg_types[..]) {
+                                    self.coerce_to(default, inner, default_ty.clone())?;
                                 }
+                                return Ok(inner.clone());
                             }
                             _ => Some(base_name.clone()),
                         }
@@ -192,6 +245,9 @@ impl<'a> TypeInferer<'a> {
                     match symbol {
                         Some(Symbol::Struct(struct_sym)) => {
                             if let Some(method) = struct_sym.get_method(name) {
+                                let params: Vec<Type> =
+                                    method.params.iter().map(|(_, t)| t.clone()).collect();
+                                self.coerce_args(args, &arg_types, &params)?;
                                 // 对于泛型实例化类型，替换返回类型中的泛型参数
                                 let return_type = if let Type::Generic(_, type_args) = &obj_ty {
                                     let mut map = std::collections::HashMap::new();
@@ -269,48 +325,41 @@ impl<'a> TypeInferer<'a> {
                 } else {
                     match obj_ty {
                         Type::GenericParam(param_name) => {
-                            if let Some(Symbol::GenericParam(gp)) = self.lookup(&param_name) {
-                                if let Some(bound_ty) = &gp.bound {
-                                    if let Type::Struct(trait_name) = bound_ty {
-                                        if let Some(Symbol::Trait(trait_sym)) =
-                                            self.lookup(trait_name)
-                                        {
-                                            if let Some(method) = trait_sym.get_method(name) {
-                                                Ok(method.return_type.clone())
-                                            } else {
-                                                Err(SemanticError::UndefinedMethod {
-                                                    class: format!("Trait {}", trait_name),
-                                                    method: name.clone(),
-                                                    span: span.clone(),
-                                                })
-                                            }
-                                        } else {
-                                            Err(SemanticError::UndefinedTrait {
-                                                name: trait_name.clone(),
-                                                span: span.clone(),
-                                            })
-                                        }
-                                    } else {
-                                        Err(SemanticError::NotCallable {
-                                            ty: format!("{:?}", bound_ty),
-                                            span: span.clone(),
-                                        })
-                                    }
-                                } else {
-                                    Err(SemanticError::NotCallable {
-                                        ty: format!("Generic {} has no bounds", param_name),
-                                        span: span.clone(),
-                                    })
+                            let method = bound_method(|n| self.lookup(n), &param_name, name, span)?;
+                            let params: Vec<Type> =
+                                method.params.into_iter().map(|(_, t)| t).collect();
+                            self.coerce_args(args, &arg_types, &params)?;
+                            Ok(method.return_type)
+                        }
+                        // dyn Trait: 方法签名来自 Trait 定义，运行时经 vtable 分派
+                        Type::TraitObject(trait_name) => match self.lookup(&trait_name).cloned() {
+                            Some(Symbol::Trait(trait_sym)) => match trait_sym.get_method(name) {
+                                Some(method) => {
+                                    let params: Vec<Type> =
+                                        method.params.iter().map(|(_, t)| t.clone()).collect();
+                                    self.coerce_args(args, &arg_types, &params)?;
+                                    Ok(method.return_type.clone())
                                 }
-                            } else {
-                                Err(SemanticError::UndefinedType {
-                                    name: param_name.clone(),
+                                None => Err(SemanticError::UndefinedMethod {
+                                    class: format!("dyn {}", trait_name),
+                                    method: name.clone(),
                                     span: span.clone(),
-                                })
-                            }
-                        }
+                                }),
+                            },
+                            _ => Err(SemanticError::UndefinedTrait {
+                                name: trait_name.clone(),
+                                span: span.clone(),
+                            }),
+                        },
                         Type::Vec(inner) => match name.as_str() {
-                            "push" | "set" => Ok(Type::Void),
+                            "push" => {
+                                self.coerce_args(args, &arg_types, &[*inner])?;
+                                Ok(Type::Void)
+                            }
+                            "set" => {
+                                self.coerce_args(args, &arg_types, &[Type::Int, *inner])?;
+                                Ok(Type::Void)
+                            }
                             "pop" | "get" => Ok(*inner),
                             "len" => Ok(Type::Int),
                             _ => Err(SemanticError::UndefinedMethod {
@@ -327,9 +376,29 @@ impl<'a> TypeInferer<'a> {
                 }
             }
             _ => {
-                // 复杂调用表达式（如 (f())()），暂时返回 Error
-                Ok(Type::Error)
+                // 复杂调用表达式（如 callbacks.get(0)(x)）：被调用者必须是函数类型
+                match self.infer(callee)? {
+                    Type::Function { return_type, .. } => Ok(*return_type),
+                    Type::Error => Ok(Type::Error),
+                    ty => Err(SemanticError::NotCallable {
+                        ty: ty.to_string(),
+                        span: span.clone(),
+                    }),
+                }
             }
         }
     }
+
+    /// 按形参类型为实参插入 `dyn Trait` 转换（其余类型不在此检查）
+    fn coerce_args(
+        &mut self,
+        args: &mut [Expr],
+        arg_types: &[Type],
+        params: &[Type],
+    ) -> Result<(), SemanticError> {
+        for ((arg, actual), expected) in args.iter_mut().zip(arg_types).zip(params) {
+            self.coerce_to(arg, expected, actual.clone())?;
+        }
+        Ok(())
+    }
 }
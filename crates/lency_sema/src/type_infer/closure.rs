@@ -0,0 +1,111 @@
+//! Closure Inference
+//!
+//! 闭包类型推导与捕获分析：
+//! - 闭包体在独立的 Closure 作用域中推导，参数在其中定义
+//! - 推导过程中访问到的外层局部变量/参数被记录为按值捕获
+//! - 推导结果（捕获列表、返回类型）写回 AST，供单态化与代码生成使用
+
+use super::TypeInferer;
+use crate::error::SemanticError;
+use crate::scope::{ScopeId, ScopeKind};
+use crate::symbol::Symbol;
+use lency_syntax::ast::{Expr, Param, Type};
+
+/// 正在推导的闭包（用于收集捕获变量）
+#[derive(Debug)]
+pub(crate) struct ClosureFrame {
+    /// 闭包自身的作用域
+    pub(crate) scope: ScopeId,
+    /// 已收集的捕获变量（按首次出现顺序）
+    pub(crate) captures: Vec<Param>,
+}
+
+impl<'a> TypeInferer<'a> {
+    /// 推导闭包类型，并将捕获列表和返回类型写回 AST
+    pub(crate) fn infer_closure(
+        &mut self,
+        params: &[Param],
+        body: &mut Expr,
+        captures: &mut Vec<Param>,
+        return_type: &mut Option<Type>,
+        span: &std::ops::Range<usize>,
+    ) -> Result<Type, SemanticError> {
+        // 闭包作用域挂在推导器的当前作用域下（而不是 ScopeStack 的游标）
+        let outer_current = self.scopes.current_scope();
+        let parent_scope = self.current_scope;
+        self.scopes.set_current(parent_scope);
+        let scope_id = self.scopes.enter_scope(ScopeKind::Closure);
+        self.current_scope = scope_id;
+
+        // 注册参数
+        for (i, param) in params.iter().enumerate() {
+            let param_sym = crate::symbol::ParameterSymbol::new(
+                param.name.clone(),
+                param.ty.clone(),
+                span.clone(),
+                i,
+            );
+            let _ = self.scopes.define(Symbol::Parameter(param_sym));
+        }
+
+        // 推导闭包体类型，同时收集捕获
+        self.closure_frames.push(ClosureFrame {
+            scope: scope_id,
+            captures: Vec::new(),
+        });
+        let body_ty = self.infer(body);
+        let frame = self.closure_frames.pop();
+
+        self.scopes.exit_scope();
+        self.scopes.set_current(outer_current);
+        self.current_scope = parent_scope;
+
+        let body_ty = body_ty?;
+        if let Some(frame) = frame {
+            *captures = frame.captures;
+        }
+        *return_type = Some(body_ty.clone());
+
+        // 返回函数类型
+        Ok(Type::Function {
+            param_types: params.iter().map(|p| p.ty.clone()).collect(),
+            return_type: Box::new(body_ty),
+        })
+    }
+
+    /// 记录闭包对外层变量的捕获
+    ///
+    /// 只有定义在闭包作用域之外、且不在全局作用域的变量/参数需要捕获；
+    /// 嵌套闭包中，外层闭包同样要捕获该变量，以便构造内层闭包的环境。
+    pub(crate) fn record_capture(&mut self, name: &str) {
+        if self.closure_frames.is_empty() {
+            return;
+        }
+
+        let Some(def_scope) = self.scopes.lookup_scope_from(name, self.current_scope) else {
+            return;
+        };
+        // 全局符号（函数、全局变量）直接通过模块访问，无需捕获
+        if self.scopes.is_global(def_scope) {
+            return;
+        }
+
+        let ty = match self.lookup(name) {
+            Some(Symbol::Variable(var)) => var.ty.clone(),
+            Some(Symbol::Parameter(param)) => param.ty.clone(),
+            _ => return,
+        };
+
+        for frame in self.closure_frames.iter_mut() {
+            if self.scopes.is_within(def_scope, frame.scope) {
+                continue;
+            }
+            if !frame.captures.iter().any(|c| c.name == name) {
+                frame.captures.push(Param {
+                    name: name.to_string(),
+                    ty: ty.clone(),
+                });
+            }
+        }
+    }
+}
@@ -1,274 +0,0 @@
-use super::{is_compatible, TypeInferer};
-use crate::error::SemanticError;
-use crate::symbol::Symbol;
-use lency_syntax::ast::{Expr, MatchCase, MatchPattern, Type};
-
-impl<'a> TypeInferer<'a> {
-    pub(crate) fn infer_match(
-        &mut self,
-        value: &mut Expr,
-        cases: &mut [MatchCase],
-        _default: Option<&mut Expr>, // Deprecated/Unused
-        span: &std::ops::Range<usize>,
-    ) -> Result<Type, SemanticError> {
-        let value_ty = self.infer(value)?;
-
-        // Ensure we can match on this type (Int, Bool, String, Enum, Struct?)
-        // Design doc: Match expression must return value.
-        // Cases must have same return type.
-
-        let mut ret_ty = Type::Error;
-        let mut first = true;
-
-        for case in cases.iter_mut() {
-            // Enter scope for pattern bindings
-            let scope_id = self.scopes.enter_scope(crate::scope::ScopeKind::Block);
-            let parent_scope = self.current_scope;
-            self.current_scope = scope_id;
-
-            let pat_result = self.check_pattern(&case.pattern, &value_ty, &case.span);
-            if let Err(e) = pat_result {
-                self.scopes.exit_scope();
-                self.current_scope = parent_scope;
-                return Err(e);
-            }
-
-            let body_ty = self.infer(case.body.as_mut());
-            self.scopes.exit_scope(); // Exit scope after inferring body
-            self.current_scope = parent_scope;
-            let body_ty = body_ty?;
-
-            if first {
-                ret_ty = body_ty;
-                first = false;
-            } else if !is_compatible(&ret_ty, &body_ty) {
-                return Err(SemanticError::TypeMismatch {
-                    expected: ret_ty.to_string(),
-                    found: body_ty.to_string(),
-                    span: case.body.span.clone(),
-                });
-            }
-        }
-
-        // Exhaustiveness check
-        // 1. Check for Wildcard/Variable (always exhaust)
-        let has_catch_all = cases.iter().any(|c| {
-            matches!(
-                c.pattern,
-                MatchPattern::Wildcard | MatchPattern::Variable(_)
-            )
-        });
-
-        if !has_catch_all {
-            // 2. If matching on an Enum, check all variants are covered
-            let enum_name = match &value_ty {
-                Type::Struct(n) => Some(n.clone()),
-                Type::Generic(n, _) => Some(n.clone()),
-                _ => None,
-            };
-
-            if let Some(ref name) = enum_name {
-                if let Some(Symbol::Enum(e)) = self.lookup(name) {
-                    let all_variants: std::collections::HashSet<String> =
-                        e.variants.keys().cloned().collect();
-
-                    let matched_variants: std::collections::HashSet<String> = cases
-                        .iter()
-                        .filter_map(|c| {
-                            if let MatchPattern::Variant { name, .. } = &c.pattern {
-                                Some(name.clone())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-
-                    let missing: Vec<String> = all_variants
-                        .difference(&matched_variants)
-                        .cloned()
-                        .collect();
-
-                    if !missing.is_empty() {
-                        return Err(SemanticError::PatternNotExhaustive {
-                            missing_variants: missing,
-                            span: span.clone(),
-                        });
-                    }
-                }
-            }
-        }
-
-        if cases.is_empty() {
-            return Err(SemanticError::TypeMismatch {
-                expected: "non-empty match".to_string(),
-                found: "empty match".to_string(),
-                span: span.clone(),
-            });
-        }
-
-        Ok(ret_ty)
-    }
-
-    fn check_pattern(
-        &mut self,
-        pattern: &MatchPattern,
-        target_ty: &Type,
-        span: &std::ops::Range<usize>,
-    ) -> Result<(), SemanticError> {
-        match pattern {
-            MatchPattern::Literal(lit) => {
-                let pat_ty = self.infer_literal(lit);
-                if pat_ty != *target_ty {
-                    return Err(SemanticError::TypeMismatch {
-                        expected: target_ty.to_string(),
-                        found: pat_ty.to_string(),
-                        span: span.clone(),
-                    });
-                }
-                Ok(())
-            }
-            MatchPattern::Wildcard => Ok(()),
-            MatchPattern::Variable(name) => {
-                // Bind variable 'name' with type 'target_ty'
-                // Check if shadowing? Shadowing allowed in new scope.
-                let var_sym = crate::symbol::VariableSymbol::new(
-                    name.clone(),
-                    target_ty.clone(),
-                    false, // immutable binding
-                    span.clone(),
-                );
-
-                self.scopes.define(Symbol::Variable(var_sym))?;
-                Ok(())
-            }
-            MatchPattern::Variant { name, sub_patterns } => {
-                // Check if target_ty is Enum
-                // Could be Type::Struct(enum_name) or Type::Generic(enum_name, args)
-                // Sprint 15: Result<T, E> is a special case - need to create a temporary for "Result"
-                let result_str = "Result".to_string();
-                let (enum_name, generic_args) = match target_ty {
-                    Type::Struct(n) => (n.as_str(), vec![]),
-                    Type::Generic(n, args) => (n.as_str(), args.clone()),
-                    // Sprint 15: Treat Result<T, E> as Generic("Result", [T, E])
-                    Type::Result { ok_type, err_type } => (
-                        result_str.as_str(),
-                        vec![(**ok_type).clone(), (**err_type).clone()],
-                    ),
-                    _ => {
-                        return Err(SemanticError::TypeMismatch {
-                            expected: "Enum type".to_string(),
-                            found: target_ty.to_string(),
-                            span: span.clone(),
-                        });
-                    }
-                };
-
-                // Lookup Enum and Variant Info (Clone to avoid holding borrow)
-                let (enum_generic_params, variant_field_types) =
-                    if let Some(Symbol::Enum(e)) = self.lookup(enum_name) {
-                        // Sprint 15: Special handling for Result.Ok and Result.Err
-                        if let Some(types) = e.get_variant(name) {
-                            (e.generic_params.clone(), types.clone())
-                        } else if enum_name == "Result" && (name == "Ok" || name == "Err") {
-                            // Result.Ok and Result.Err are compiler built-ins
-                            // They each have one field of the appropriate generic type
-                            // Ok has field of type T, Err has field of type E
-                            let field_type = if name == "Ok" {
-                                Type::GenericParam("T".to_string())
-                            } else {
-                                Type::GenericParam("E".to_string())
-                            };
-                            (e.generic_params.clone(), vec![field_type])
-                        } else {
-                            return Err(SemanticError::UndefinedField {
-                                class: enum_name.to_string(),
-                                field: name.clone(),
-                                span: span.clone(),
-                            });
-                        }
-                    } else {
-                        return Err(SemanticError::UndefinedType {
-                            name: enum_name.to_string(),
-                            span: span.clone(),
-                        });
-                    };
-
-                // Check arity
-                if sub_patterns.len() != variant_field_types.len() {
-                    // Make nice error
-                    return Err(SemanticError::TypeMismatch {
-                        expected: format!("{} fields", variant_field_types.len()),
-                        found: format!("{} patterns", sub_patterns.len()),
-                        span: span.clone(),
-                    });
-                }
-
-                // Check sub-patterns recursively
-                // Need to substitute generics in field_types!
-                // Map: Enum Generic Params -> generic_args
-
-                for (i, sub_pat) in sub_patterns.iter().enumerate() {
-                    let field_ty = &variant_field_types[i];
-
-                    // Subst
-                    let concrete_field_ty = if !generic_args.is_empty() {
-                        Self::substitute_generics(field_ty, &enum_generic_params, &generic_args)
-                    } else {
-                        field_ty.clone()
-                    };
-
-                    self.check_pattern(sub_pat, &concrete_field_ty, span)?;
-                }
-
-                Ok(())
-            }
-        }
-    }
-
-    /// 泛型类型替换辅助函数
-    fn substitute_generics(
-        ty: &Type,
-        params: &[crate::symbol::GenericParamSymbol],
-        args: &[Type],
-    ) -> Type {
-        match ty {
-            // Sprint 15: Handle Type::GenericParam for Result<T,E> substitution
-            Type::GenericParam(name) => {
-                // Check if name matches any param
-                for (i, param) in params.iter().enumerate() {
-                    if *name == param.name && i < args.len() {
-                        return args[i].clone();
-                    }
-                }
-                ty.clone()
-            }
-            Type::Struct(name) => {
-                // Check if name matches any param
-                for (i, param) in params.iter().enumerate() {
-                    if *name == param.name && i < args.len() {
-                        return args[i].clone();
-                    }
-                }
-                Type::Struct(name.clone())
-            }
-            Type::Generic(name, inner_args) => {
-                // Substitute args recursively
-                let new_args: Vec<Type> = inner_args
-                    .iter()
-                    .map(|arg| Self::substitute_generics(arg, params, args))
-                    .collect();
-                Type::Generic(name.clone(), new_args)
-            }
-            Type::Vec(inner) => Type::Vec(Box::new(Self::substitute_generics(inner, params, args))),
-            Type::Array { element_type, size } => Type::Array {
-                element_type: Box::new(Self::substitute_generics(element_type, params, args)),
-                size: *size,
-            },
-            Type::Nullable(inner) => {
-                Type::Nullable(Box::new(Self::substitute_generics(inner, params, args)))
-            }
-            // ... handle other types
-            _ => ty.clone(),
-        }
-    }
-}
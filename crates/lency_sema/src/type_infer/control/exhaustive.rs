@@ -0,0 +1,388 @@
+//! Match Exhaustiveness
+//!
+//! 基于 usefulness 矩阵的穷尽性检查（Maranget, "Warnings for pattern matching"）。
+//! 每个 case 的模式是矩阵的一行；若存在某个值不被任何一行匹配，
+//! 算法按列拆分构造器（枚举变体、结构体、bool、整数区间）并构造出这样的值作为见证，
+//! 报错时以模式的形式列出。
+//!
+//! 整数与 char 的区间模式按所有端点切分成互不相交的小区间，每个小区间视为一个构造器；
+//! 字符串、浮点等取值无限的类型只有通配符能覆盖。
+
+use lency_syntax::ast::{Literal, MatchPattern, Type};
+
+/// 类型的构造器集合
+pub(crate) enum Shape {
+    /// 枚举：变体名与（已代入泛型实参的）字段类型
+    Variants(Vec<(String, Vec<Type>)>),
+    /// 结构体：唯一的构造器及其字段
+    Struct(Vec<(String, Type)>),
+    Bool,
+    /// 整数与 char：取值范围（闭区间）
+    Ints(Vec<(i128, i128)>),
+    /// 取值无限或无法枚举的类型
+    Open,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ctor {
+    Variant(String),
+    Struct,
+    Bool(bool),
+    /// 闭区间 [lo, hi]
+    Ints(i128, i128),
+    /// 字符串、浮点等字面量，永远不能覆盖整个类型
+    Opaque(String),
+}
+
+impl Ctor {
+    /// `self` 匹配的值是否包含 `other` 匹配的全部值（区间已切分，不会部分重叠）
+    fn covers(&self, other: &Ctor) -> bool {
+        match (self, other) {
+            (Ctor::Ints(lo, hi), Ctor::Ints(x, y)) => lo <= x && y <= hi,
+            (a, b) => a == b,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+    Or(Vec<Pat>),
+}
+
+type Row = Vec<Pat>;
+
+pub(crate) struct PatternSpace<F> {
+    shape_of: F,
+}
+
+impl<F: Fn(&Type) -> Shape> PatternSpace<F> {
+    pub(crate) fn new(shape_of: F) -> Self {
+        Self { shape_of }
+    }
+
+    /// 这些模式未覆盖的值（写成模式）；为空表示穷尽
+    pub(crate) fn missing(&self, patterns: &[&MatchPattern], ty: &Type) -> Vec<String> {
+        let rows: Vec<Row> = patterns.iter().map(|p| vec![self.lower(p, ty)]).collect();
+        let tys = [ty.clone()];
+        self.witnesses(&rows, &tys)
+            .into_iter()
+            .filter_map(|mut w| w.pop())
+            .map(|w| self.display(&w, ty))
+            .collect()
+    }
+
+    fn lower(&self, pattern: &MatchPattern, ty: &Type) -> Pat {
+        match pattern {
+            MatchPattern::Wildcard | MatchPattern::Variable(_) => Pat::Wild,
+            MatchPattern::Binding { pattern, .. } => self.lower(pattern, ty),
+            MatchPattern::Or(alternatives) => {
+                Pat::Or(alternatives.iter().map(|p| self.lower(p, ty)).collect())
+            }
+            MatchPattern::Literal(lit) => Pat::Ctor(literal_ctor(lit), vec![]),
+            MatchPattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let (lo, hi) = (literal_value(start), literal_value(end));
+                let hi = if *inclusive { hi } else { hi - 1 };
+                Pat::Ctor(Ctor::Ints(lo, hi), vec![])
+            }
+            MatchPattern::Variant { name, sub_patterns } => {
+                let fields = match (self.shape_of)(ty) {
+                    Shape::Variants(variants) => variants
+                        .into_iter()
+                        .find(|(v, _)| v == name)
+                        .map(|(_, fields)| fields)
+                        .unwrap_or_default(),
+                    _ => Vec::new(),
+                };
+                let subs = sub_patterns
+                    .iter()
+                    .zip(&fields)
+                    .map(|(p, t)| self.lower(p, t))
+                    .collect();
+                Pat::Ctor(Ctor::Variant(name.clone()), subs)
+            }
+            MatchPattern::Struct { fields, .. } => {
+                let Shape::Struct(declared) = (self.shape_of)(ty) else {
+                    return Pat::Wild;
+                };
+                // 未列出的字段视为通配符
+                let subs = declared
+                    .iter()
+                    .map(|(field, field_ty)| {
+                        fields
+                            .iter()
+                            .find(|(f, _)| f == field)
+                            .map_or(Pat::Wild, |(_, p)| self.lower(p, field_ty))
+                    })
+                    .collect();
+                Pat::Ctor(Ctor::Struct, subs)
+            }
+        }
+    }
+
+    /// 不被矩阵 `rows` 中任何一行匹配的值向量（列类型为 `tys`）
+    fn witnesses(&self, rows: &[Row], tys: &[Type]) -> Vec<Row> {
+        let Some((head_ty, rest_tys)) = tys.split_first() else {
+            return if rows.is_empty() {
+                vec![vec![]]
+            } else {
+                vec![]
+            };
+        };
+        let rows = expand_or_heads(rows);
+        let heads: Vec<&Ctor> = rows
+            .iter()
+            .filter_map(|row| match &row[0] {
+                Pat::Ctor(c, _) => Some(c),
+                _ => None,
+            })
+            .collect();
+
+        let shape = (self.shape_of)(head_ty);
+        let (ctors, complete) = split_ctors(&shape, &heads);
+
+        if complete {
+            let mut result = Vec::new();
+            for ctor in &ctors {
+                let field_tys = ctor_fields(&shape, ctor);
+                let arity = field_tys.len();
+                let specialized = specialize(&rows, ctor, arity);
+                let sub_tys: Vec<Type> = field_tys
+                    .into_iter()
+                    .chain(rest_tys.iter().cloned())
+                    .collect();
+                for mut w in self.witnesses(&specialized, &sub_tys) {
+                    let rest = w.split_off(arity);
+                    let mut row = vec![Pat::Ctor(ctor.clone(), w)];
+                    row.extend(rest);
+                    result.push(row);
+                }
+            }
+            return result;
+        }
+
+        // 某些构造器没有出现在首列：只需看首列为通配符的行
+        let default: Vec<Row> = rows
+            .iter()
+            .filter(|row| matches!(row[0], Pat::Wild))
+            .map(|row| row[1..].to_vec())
+            .collect();
+        let rest_witnesses = self.witnesses(&default, rest_tys);
+        let missing: Vec<Pat> = if heads.is_empty() || ctors.is_empty() {
+            vec![Pat::Wild]
+        } else {
+            let uncovered = ctors
+                .into_iter()
+                .filter(|c| !heads.iter().any(|h| h.covers(c)));
+            merge_adjacent(uncovered)
+                .into_iter()
+                .map(|c| {
+                    let arity = ctor_fields(&shape, &c).len();
+                    Pat::Ctor(c, vec![Pat::Wild; arity])
+                })
+                .collect()
+        };
+        rest_witnesses
+            .into_iter()
+            .flat_map(|w| {
+                missing.iter().map(move |m| {
+                    let mut row = vec![m.clone()];
+                    row.extend(w.iter().cloned());
+                    row
+                })
+            })
+            .collect()
+    }
+
+    fn display(&self, pat: &Pat, ty: &Type) -> String {
+        match pat {
+            Pat::Wild => "_".to_string(),
+            Pat::Or(alternatives) => alternatives
+                .iter()
+                .map(|p| self.display(p, ty))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Pat::Ctor(ctor, subs) => {
+                let shape = (self.shape_of)(ty);
+                let field_tys = ctor_fields(&shape, ctor);
+                match ctor {
+                    Ctor::Variant(name) if subs.is_empty() => name.clone(),
+                    Ctor::Variant(name) => {
+                        let subs: Vec<String> = subs
+                            .iter()
+                            .zip(&field_tys)
+                            .map(|(p, t)| self.display(p, t))
+                            .collect();
+                        format!("{}({})", name, subs.join(", "))
+                    }
+                    Ctor::Struct => {
+                        let Shape::Struct(fields) = &shape else {
+                            return "_".to_string();
+                        };
+                        let shown: Vec<String> = fields
+                            .iter()
+                            .zip(subs)
+                            .filter(|(_, p)| !matches!(p, Pat::Wild))
+                            .map(|((name, t), p)| format!("{}: {}", name, self.display(p, t)))
+                            .collect();
+                        if shown.is_empty() {
+                            return "_".to_string();
+                        }
+                        let name = match ty {
+                            Type::Struct(n) | Type::Generic(n, _) => n.as_str(),
+                            _ => "_",
+                        };
+                        format!("{} {{ {} }}", name, shown.join(", "))
+                    }
+                    Ctor::Bool(b) => b.to_string(),
+                    Ctor::Ints(lo, hi) => {
+                        let show =
+                            |v: i128| match (ty, u32::try_from(v).ok().and_then(char::from_u32)) {
+                                (Type::Char, Some(c)) => format!("{:?}", c),
+                                _ => v.to_string(),
+                            };
+                        if lo == hi {
+                            show(*lo)
+                        } else {
+                            format!("{}..={}", show(*lo), show(*hi))
+                        }
+                    }
+                    Ctor::Opaque(_) => "_".to_string(),
+                }
+            }
+        }
+    }
+}
+
+fn literal_ctor(lit: &Literal) -> Ctor {
+    match lit {
+        Literal::Bool(b) => Ctor::Bool(*b),
+        Literal::Int(_) | Literal::Char(_) => {
+            let v = literal_value(lit);
+            Ctor::Ints(v, v)
+        }
+        other => Ctor::Opaque(format!("{:?}", other)),
+    }
+}
+
+fn literal_value(lit: &Literal) -> i128 {
+    match lit {
+        Literal::Int(v) => *v as i128,
+        Literal::Char(c) => *c as i128,
+        _ => 0,
+    }
+}
+
+/// 首列为或模式的行展开为多行
+fn expand_or_heads(rows: &[Row]) -> Vec<Row> {
+    let mut expanded = Vec::with_capacity(rows.len());
+    for row in rows {
+        match &row[0] {
+            Pat::Or(alternatives) => {
+                let alternatives: Vec<Row> = alternatives
+                    .iter()
+                    .map(|alt| {
+                        let mut r = vec![alt.clone()];
+                        r.extend(row[1..].iter().cloned());
+                        r
+                    })
+                    .collect();
+                expanded.extend(expand_or_heads(&alternatives));
+            }
+            _ => expanded.push(row.clone()),
+        }
+    }
+    expanded
+}
+
+/// 类型的全部构造器（整数按首列出现的区间切分），以及首列是否覆盖了全部构造器
+fn split_ctors(shape: &Shape, heads: &[&Ctor]) -> (Vec<Ctor>, bool) {
+    let all = match shape {
+        Shape::Variants(variants) => variants
+            .iter()
+            .map(|(name, _)| Ctor::Variant(name.clone()))
+            .collect(),
+        Shape::Struct(_) => vec![Ctor::Struct],
+        Shape::Bool => vec![Ctor::Bool(false), Ctor::Bool(true)],
+        Shape::Ints(domain) => split_ranges(domain, heads),
+        Shape::Open => return (Vec::new(), false),
+    };
+    let complete = all.iter().all(|c| heads.iter().any(|h| h.covers(c)));
+    (all, complete)
+}
+
+/// 用首列各区间的端点把取值范围切成互不相交的区间
+fn split_ranges(domain: &[(i128, i128)], heads: &[&Ctor]) -> Vec<Ctor> {
+    // 每个区间 [lo, hi] 贡献切点 lo 与 hi + 1
+    let mut cuts: Vec<i128> = heads
+        .iter()
+        .filter_map(|c| match c {
+            Ctor::Ints(lo, hi) => Some([*lo, *hi + 1]),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let mut pieces = Vec::new();
+    for &(start, end) in domain {
+        let mut lo = start;
+        for &cut in cuts.iter().filter(|&&c| c > start && c <= end) {
+            pieces.push(Ctor::Ints(lo, cut - 1));
+            lo = cut;
+        }
+        pieces.push(Ctor::Ints(lo, end));
+    }
+    pieces
+}
+
+/// 合并相邻的整数区间，报错时 `0..=9` 比逐段列出更易读
+fn merge_adjacent(ctors: impl Iterator<Item = Ctor>) -> Vec<Ctor> {
+    let mut merged: Vec<Ctor> = Vec::new();
+    for ctor in ctors {
+        if let (Some(Ctor::Ints(_, last_hi)), Ctor::Ints(lo, hi)) = (merged.last_mut(), &ctor) {
+            if *last_hi + 1 == *lo {
+                *last_hi = *hi;
+                continue;
+            }
+        }
+        merged.push(ctor);
+    }
+    merged
+}
+
+/// 构造器的字段类型
+fn ctor_fields(shape: &Shape, ctor: &Ctor) -> Vec<Type> {
+    match (shape, ctor) {
+        (Shape::Variants(variants), Ctor::Variant(name)) => variants
+            .iter()
+            .find(|(v, _)| v == name)
+            .map(|(_, fields)| fields.clone())
+            .unwrap_or_default(),
+        (Shape::Struct(fields), Ctor::Struct) => fields.iter().map(|(_, t)| t.clone()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 只保留首列能匹配 `ctor` 的行，并把首列展开为 `arity` 个子模式
+fn specialize(rows: &[Row], ctor: &Ctor, arity: usize) -> Vec<Row> {
+    rows.iter()
+        .filter_map(|row| {
+            let mut head = match &row[0] {
+                Pat::Wild => vec![Pat::Wild; arity],
+                Pat::Ctor(c, subs) if c.covers(ctor) => subs.clone(),
+                _ => return None,
+            };
+            // 语义分析已报告数量不符；这里补齐以免越界
+            head.resize(arity, Pat::Wild);
+            head.extend(row[1..].iter().cloned());
+            Some(head)
+        })
+        .collect()
+}
@@ -0,0 +1,240 @@
+//! Match Expression
+//!
+//! `match` 的类型推导：逐个检查 case 的模式、守卫与分支体，
+//! 再用 usefulness 矩阵（见 `exhaustive`）检查穷尽性。
+
+mod exhaustive;
+mod pattern;
+
+use super::{is_compatible, TypeInferer};
+use crate::error::SemanticError;
+use crate::symbol::Symbol;
+use exhaustive::{PatternSpace, Shape};
+use lency_syntax::ast::{Expr, MatchCase, MatchPattern, Type};
+
+impl<'a> TypeInferer<'a> {
+    pub(crate) fn infer_match(
+        &mut self,
+        value: &mut Expr,
+        cases: &mut [MatchCase],
+        _default: Option<&mut Expr>, // Deprecated/Unused
+        span: &std::ops::Range<usize>,
+    ) -> Result<Type, SemanticError> {
+        let value_ty = self.infer(value)?;
+
+        // Design doc: Match expression must return value.
+        // Cases must have same return type.
+
+        let mut ret_ty = Type::Error;
+        let mut first = true;
+
+        for case in cases.iter_mut() {
+            // Enter scope for pattern bindings
+            let scope_id = self.scopes.enter_scope(crate::scope::ScopeKind::MatchArm);
+            let parent_scope = self.current_scope;
+            self.current_scope = scope_id;
+
+            let arm_result = self.infer_arm(case, &value_ty);
+            self.scopes.exit_scope(); // Exit scope after inferring body
+            self.current_scope = parent_scope;
+            let body_ty = arm_result?;
+
+            if first {
+                ret_ty = body_ty;
+                first = false;
+            } else if !is_compatible(&ret_ty, &body_ty) {
+                return Err(SemanticError::TypeMismatch {
+                    expected: ret_ty.to_string(),
+                    found: body_ty.to_string(),
+                    span: case.body.span.clone(),
+                });
+            }
+        }
+
+        if cases.is_empty() {
+            return Err(SemanticError::TypeMismatch {
+                expected: "non-empty match".to_string(),
+                found: "empty match".to_string(),
+                span: span.clone(),
+            });
+        }
+
+        // 带守卫的分支可能不匹配，不参与穷尽性检查
+        let patterns: Vec<_> = cases
+            .iter()
+            .filter(|c| c.guard.is_none())
+            .map(|c| &c.pattern)
+            .collect();
+        let missing = self.uncovered(&patterns, &value_ty);
+        if !missing.is_empty() {
+            return Err(SemanticError::PatternNotExhaustive {
+                missing_variants: missing,
+                span: span.clone(),
+            });
+        }
+
+        Ok(ret_ty)
+    }
+
+    /// 在分支作用域中检查模式与守卫，返回分支体的类型
+    fn infer_arm(&mut self, case: &mut MatchCase, value_ty: &Type) -> Result<Type, SemanticError> {
+        self.bind_pattern(&case.pattern, value_ty, &case.span)?;
+
+        if let Some(guard) = &mut case.guard {
+            let guard_ty = self.infer(guard)?;
+            if !matches!(guard_ty, Type::Bool | Type::Error) {
+                return Err(SemanticError::TypeMismatch {
+                    expected: "bool".to_string(),
+                    found: guard_ty.to_string(),
+                    span: guard.span.clone(),
+                });
+            }
+        }
+
+        self.infer(case.body.as_mut())
+    }
+
+    /// 检查模式，返回它绑定的变量及其类型
+    pub(crate) fn pattern_bindings(
+        &mut self,
+        pattern: &MatchPattern,
+        target_ty: &Type,
+        span: &std::ops::Range<usize>,
+    ) -> Result<Vec<(String, Type)>, SemanticError> {
+        let mut bindings = Vec::new();
+        self.check_pattern(pattern, target_ty, span, &mut bindings)?;
+        Ok(bindings)
+    }
+
+    /// `patterns` 合起来没有覆盖的值（写成模式）；为空表示穷尽
+    pub(crate) fn uncovered(&self, patterns: &[&MatchPattern], ty: &Type) -> Vec<String> {
+        if *ty == Type::Error {
+            return Vec::new();
+        }
+        PatternSpace::new(|ty: &Type| self.pattern_shape(ty)).missing(patterns, ty)
+    }
+
+    /// 检查模式并在当前作用域定义它绑定的变量
+    pub(crate) fn bind_pattern(
+        &mut self,
+        pattern: &MatchPattern,
+        target_ty: &Type,
+        span: &std::ops::Range<usize>,
+    ) -> Result<(), SemanticError> {
+        for (name, ty) in self.pattern_bindings(pattern, target_ty, span)? {
+            let var_sym = crate::symbol::VariableSymbol::new(
+                name,
+                ty,
+                false, // immutable binding
+                span.clone(),
+            );
+            self.scopes.define(Symbol::Variable(var_sym))?;
+        }
+        Ok(())
+    }
+
+    /// 穷尽性检查所需的类型结构
+    fn pattern_shape(&self, ty: &Type) -> Shape {
+        match ty {
+            Type::Bool => Shape::Bool,
+            Type::Int => Shape::Ints(vec![(i64::MIN as i128, i64::MAX as i128)]),
+            Type::FixedInt(kind) => Shape::Ints(vec![(kind.min_value(), kind.max_value())]),
+            // Unicode 标量值不含代理区
+            Type::Char => Shape::Ints(vec![(0, 0xD7FF), (0xE000, 0x10FFFF)]),
+            Type::Result { ok_type, err_type } => Shape::Variants(vec![
+                ("Ok".to_string(), vec![(**ok_type).clone()]),
+                ("Err".to_string(), vec![(**err_type).clone()]),
+            ]),
+            Type::Struct(name) | Type::Generic(name, _) => {
+                let args = match ty {
+                    Type::Generic(_, args) => args.as_slice(),
+                    _ => &[],
+                };
+                match self.lookup(name) {
+                    Some(Symbol::Enum(e)) => {
+                        let mut variants: Vec<(String, Vec<Type>)> = e
+                            .variants
+                            .iter()
+                            .map(|(name, fields)| {
+                                let fields = fields
+                                    .iter()
+                                    .map(|f| Self::substitute_generics(f, &e.generic_params, args))
+                                    .collect();
+                                (name.clone(), fields)
+                            })
+                            .collect();
+                        variants.sort_by(|a, b| a.0.cmp(&b.0));
+                        Shape::Variants(variants)
+                    }
+                    Some(Symbol::Struct(s)) => {
+                        // 字段表无序，按名称排列使报告稳定
+                        let mut fields: Vec<_> = s.fields.iter().collect();
+                        fields.sort_by(|a, b| a.0.cmp(b.0));
+                        Shape::Struct(
+                            fields
+                                .into_iter()
+                                .map(|(name, info)| {
+                                    let ty = Self::substitute_generics(
+                                        &info.ty,
+                                        &s.generic_params,
+                                        args,
+                                    );
+                                    (name.clone(), ty)
+                                })
+                                .collect(),
+                        )
+                    }
+                    _ => Shape::Open,
+                }
+            }
+            _ => Shape::Open,
+        }
+    }
+
+    /// 泛型类型替换辅助函数
+    fn substitute_generics(
+        ty: &Type,
+        params: &[crate::symbol::GenericParamSymbol],
+        args: &[Type],
+    ) -> Type {
+        match ty {
+            // Sprint 15: Handle Type::GenericParam for Result<T,E> substitution
+            Type::GenericParam(name) => {
+                // Check if name matches any param
+                for (i, param) in params.iter().enumerate() {
+                    if *name == param.name && i < args.len() {
+                        return args[i].clone();
+                    }
+                }
+                ty.clone()
+            }
+            Type::Struct(name) => {
+                // Check if name matches any param
+                for (i, param) in params.iter().enumerate() {
+                    if *name == param.name && i < args.len() {
+                        return args[i].clone();
+                    }
+                }
+                Type::Struct(name.clone())
+            }
+            Type::Generic(name, inner_args) => {
+                // Substitute args recursively
+                let new_args: Vec<Type> = inner_args
+                    .iter()
+                    .map(|arg| Self::substitute_generics(arg, params, args))
+                    .collect();
+                Type::Generic(name.clone(), new_args)
+            }
+            Type::Vec(inner) => Type::Vec(Box::new(Self::substitute_generics(inner, params, args))),
+            Type::Array { element_type, size } => Type::Array {
+                element_type: Box::new(Self::substitute_generics(element_type, params, args)),
+                size: *size,
+            },
+            Type::Nullable(inner) => {
+                Type::Nullable(Box::new(Self::substitute_generics(inner, params, args)))
+            }
+            // ... handle other types
+            _ => ty.clone(),
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Pattern Checking
+//!
+//! 检查模式与被匹配值的类型是否一致，并收集模式绑定的变量及其类型。
+
+use super::super::TypeInferer;
+use crate::error::SemanticError;
+use crate::symbol::Symbol;
+use lency_syntax::ast::{Literal, MatchPattern, Type};
+
+type Bindings = Vec<(String, Type)>;
+
+impl<'a> TypeInferer<'a> {
+    pub(super) fn check_pattern(
+        &mut self,
+        pattern: &MatchPattern,
+        target_ty: &Type,
+        span: &std::ops::Range<usize>,
+        bindings: &mut Bindings,
+    ) -> Result<(), SemanticError> {
+        match pattern {
+            MatchPattern::Literal(lit) => self.check_literal_pattern(lit, target_ty, span),
+            MatchPattern::Wildcard => Ok(()),
+            MatchPattern::Variable(name) => {
+                // Shadowing allowed in new scope.
+                bindings.push((name.clone(), target_ty.clone()));
+                Ok(())
+            }
+            MatchPattern::Binding { name, pattern } => {
+                bindings.push((name.clone(), target_ty.clone()));
+                self.check_pattern(pattern, target_ty, span, bindings)
+            }
+            MatchPattern::Or(alternatives) => {
+                let Some((first, rest)) = alternatives.split_first() else {
+                    return Ok(());
+                };
+                let mut expected = Vec::new();
+                self.check_pattern(first, target_ty, span, &mut expected)?;
+                for alternative in rest {
+                    let mut found = Vec::new();
+                    self.check_pattern(alternative, target_ty, span, &mut found)?;
+                    check_same_bindings(&expected, &found, span)?;
+                }
+                bindings.extend(expected);
+                Ok(())
+            }
+            MatchPattern::Range {
+                start,
+                end,
+                inclusive,
+            } => self.check_range_pattern(start, end, *inclusive, target_ty, span),
+            MatchPattern::Struct { name, fields } => {
+                self.check_struct_pattern(name, fields, target_ty, span, bindings)
+            }
+            MatchPattern::Variant { name, sub_patterns } => {
+                self.check_variant_pattern(name, sub_patterns, target_ty, span, bindings)
+            }
+        }
+    }
+
+    fn check_literal_pattern(
+        &mut self,
+        lit: &Literal,
+        target_ty: &Type,
+        span: &std::ops::Range<usize>,
+    ) -> Result<(), SemanticError> {
+        let pat_ty = self.infer_literal(lit);
+        // 整数字面量模式可以匹配定宽整数，只要在其取值范围内
+        if let (Literal::Int(value), Type::FixedInt(kind)) = (lit, target_ty) {
+            let value = *value as i128;
+            if value < kind.min_value() || value > kind.max_value() {
+                return Err(SemanticError::IntegerLiteralOutOfRange {
+                    value,
+                    ty: kind.to_string(),
+                    span: span.clone(),
+                });
+            }
+            return Ok(());
+        }
+        if pat_ty != *target_ty {
+            return Err(SemanticError::TypeMismatch {
+                expected: target_ty.to_string(),
+                found: pat_ty.to_string(),
+                span: span.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// `a..b` / `a..=b`：两端都是整数或都是 char，且区间非空
+    fn check_range_pattern(
+        &mut self,
+        start: &Literal,
+        end: &Literal,
+        inclusive: bool,
+        target_ty: &Type,
+        span: &std::ops::Range<usize>,
+    ) -> Result<(), SemanticError> {
+        if !matches!(target_ty, Type::Int | Type::FixedInt(_) | Type::Char) {
+            return Err(SemanticError::TypeMismatch {
+                expected: "int or char".to_string(),
+                found: target_ty.to_string(),
+                span: span.clone(),
+            });
+        }
+        self.check_literal_pattern(start, target_ty, span)?;
+        self.check_literal_pattern(end, target_ty, span)?;
+
+        let bound = |lit: &Literal| match lit {
+            Literal::Int(v) => *v as i128,
+            Literal::Char(c) => *c as i128,
+            _ => 0,
+        };
+        let (low, high) = (bound(start), bound(end));
+        if low > high || (low == high && !inclusive) {
+            let fmt = |lit: &Literal| match lit {
+                Literal::Char(c) => format!("{:?}", c),
+                Literal::Int(v) => v.to_string(),
+                other => format!("{:?}", other),
+            };
+            let op = if inclusive { "..=" } else { ".." };
+            return Err(SemanticError::EmptyRangePattern {
+                pattern: format!("{}{}{}", fmt(start), op, fmt(end)),
+                span: span.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// `Point { x, y: 0 }`：模式中的类型名只用于可读性，字段按被匹配值的类型检查
+    fn check_struct_pattern(
+        &mut self,
+        name: &str,
+        fields: &[(String, MatchPattern)],
+        target_ty: &Type,
+        span: &std::ops::Range<usize>,
+        bindings: &mut Bindings,
+    ) -> Result<(), SemanticError> {
+        let (struct_name, generic_args) = match target_ty {
+            Type::Struct(n) => (n.as_str(), &[][..]),
+            Type::Generic(n, args) => (n.as_str(), args.as_slice()),
+            _ => {
+                return Err(SemanticError::TypeMismatch {
+                    expected: name.to_string(),
+                    found: target_ty.to_string(),
+                    span: span.clone(),
+                })
+            }
+        };
+        let Some(Symbol::Struct(s)) = self.lookup(struct_name) else {
+            return Err(SemanticError::NotAStruct {
+                name: target_ty.to_string(),
+                span: span.clone(),
+            });
+        };
+        // 模式中的名称必须是结构体，且与值的类型一致
+        match self.lookup(name) {
+            Some(Symbol::Struct(named)) if named.name == s.name => {}
+            Some(Symbol::Struct(_)) => {
+                return Err(SemanticError::TypeMismatch {
+                    expected: target_ty.to_string(),
+                    found: name.to_string(),
+                    span: span.clone(),
+                });
+            }
+            _ => {
+                return Err(SemanticError::UndefinedType {
+                    name: name.to_string(),
+                    span: span.clone(),
+                });
+            }
+        }
+
+        let mut field_types = Vec::with_capacity(fields.len());
+        for (field, _) in fields {
+            let info = s
+                .get_field(field)
+                .ok_or_else(|| SemanticError::UndefinedField {
+                    class: struct_name.to_string(),
+                    field: field.clone(),
+                    span: span.clone(),
+                })?;
+            field_types.push(Self::substitute_generics(
+                &info.ty,
+                &s.generic_params,
+                generic_args,
+            ));
+        }
+        for ((_, sub_pat), field_ty) in fields.iter().zip(&field_types) {
+            self.check_pattern(sub_pat, field_ty, span, bindings)?;
+        }
+        Ok(())
+    }
+
+    fn check_variant_pattern(
+        &mut self,
+        name: &str,
+        sub_patterns: &[MatchPattern],
+        target_ty: &Type,
+        span: &std::ops::Range<usize>,
+        bindings: &mut Bindings,
+    ) -> Result<(), SemanticError> {
+        // Check if target_ty is Enum
+        // Could be Type::Struct(enum_name) or Type::Generic(enum_name, args)
+        // Sprint 15: Result<T, E> is a special case - need to create a temporary for "Result"
+        let result_str = "Result".to_string();
+        let (enum_name, generic_args) = match target_ty {
+            Type::Struct(n) => (n.as_str(), vec![]),
+            Type::Generic(n, args) => (n.as_str(), args.clone()),
+            // Sprint 15: Treat Result<T, E> as Generic("Result", [T, E])
+            Type::Result { ok_type, err_type } => (
+                result_str.as_str(),
+                vec![(**ok_type).clone(), (**err_type).clone()],
+            ),
+            _ => {
+                return Err(SemanticError::TypeMismatch {
+                    expected: "Enum type".to_string(),
+                    found: target_ty.to_string(),
+                    span: span.clone(),
+                });
+            }
+        };
+
+        // Lookup Enum and Variant Info (Clone to avoid holding borrow)
+        let (enum_generic_params, variant_field_types) =
+            if let Some(Symbol::Enum(e)) = self.lookup(enum_name) {
+                // Sprint 15: Special handling for Result.Ok and Result.Err
+                if let Some(types) = e.get_variant(name) {
+                    (e.generic_params.clone(), types.clone())
+                } else if enum_name == "Result" && (name == "Ok" || name == "Err") {
+                    // Result.Ok and Result.Err are compiler built-ins
+                    // They each have one field of the appropriate generic type
+                    // Ok has field of type T, Err has field of type E
+                    let field_type = if name == "Ok" {
+                        Type::GenericParam("T".to_string())
+                    } else {
+                        Type::GenericParam("E".to_string())
+                    };
+                    (e.generic_params.clone(), vec![field_type])
+                } else {
+                    return Err(SemanticError::UndefinedField {
+                        class: enum_name.to_string(),
+                        field: name.to_string(),
+                        span: span.clone(),
+                    });
+                }
+            } else {
+                return Err(SemanticError::UndefinedType {
+                    name: enum_name.to_string(),
+                    span: span.clone(),
+                });
+            };
+
+        // Check arity
+        if sub_patterns.len() != variant_field_types.len() {
+            return Err(SemanticError::TypeMismatch {
+                expected: format!("{} fields", variant_field_types.len()),
+                found: format!("{} patterns", sub_patterns.len()),
+                span: span.clone(),
+            });
+        }
+
+        // Check sub-patterns recursively, substituting enum generic params
+        for (sub_pat, field_ty) in sub_patterns.iter().zip(&variant_field_types) {
+            let concrete_field_ty = if !generic_args.is_empty() {
+                Self::substitute_generics(field_ty, &enum_generic_params, &generic_args)
+            } else {
+                field_ty.clone()
+            };
+
+            self.check_pattern(sub_pat, &concrete_field_ty, span, bindings)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 或模式的每个分支必须绑定同名、同类型的变量
+fn check_same_bindings(
+    expected: &Bindings,
+    found: &Bindings,
+    span: &std::ops::Range<usize>,
+) -> Result<(), SemanticError> {
+    let mismatch = |name: &str| SemanticError::OrPatternBindingMismatch {
+        name: name.to_string(),
+        span: span.clone(),
+    };
+    for (name, ty) in expected {
+        match found.iter().find(|(n, _)| n == name) {
+            Some((_, found_ty)) if found_ty != ty => {
+                return Err(SemanticError::TypeMismatch {
+                    expected: ty.to_string(),
+                    found: found_ty.to_string(),
+                    span: span.clone(),
+                })
+            }
+            Some(_) => {}
+            None => return Err(mismatch(name)),
+        }
+    }
+    match found
+        .iter()
+        .find(|(n, _)| !expected.iter().any(|(e, _)| e == n))
+    {
+        Some((name, _)) => Err(mismatch(name)),
+        None => Ok(()),
+    }
+}
@@ -144,11 +144,11 @@ impl<'a> TypeInferer<'a> {
                 Ok(Type::String)
             }
             ExprKind::CharToString(arg) => {
-                // char_to_string(int) -> string
+                // char_to_string(char) -> string，也接受 int 码点
                 let arg_ty = self.infer(arg)?;
-                if arg_ty != Type::Int {
+                if arg_ty != Type::Char && arg_ty != Type::Int {
                     return Err(SemanticError::TypeMismatch {
-                        expected: "int".to_string(),
+                        expected: "char".to_string(),
                         found: arg_ty.to_string(),
                         span: arg.span.clone(),
                     });
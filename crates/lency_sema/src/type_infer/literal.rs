@@ -9,6 +9,7 @@ impl<'a> TypeInferer<'a> {
             Literal::Float(_) => Type::Float,
             Literal::Bool(_) => Type::Bool,
             Literal::String(_) => Type::String,
+            Literal::Char(_) => Type::Char,
             Literal::Null => Type::Nullable(Box::new(Type::Error)), // null 需要上下文推导
         }
     }
@@ -7,22 +7,34 @@
 
 mod access;
 mod adt;
+mod bounds;
 mod call;
+mod closure;
 mod control;
 mod intrinsics;
 mod literal;
+mod numeric;
 mod operators;
+mod range;
+mod string;
 
 #[cfg(test)]
 mod tests;
 
 // Re-export specific items for internal or external use
 pub(crate) use adt::substitute_type; // Re-export for other sema modules
+pub(crate) use bounds::bound_method;
+pub use numeric::INTEGER_METHODS;
+pub(crate) use numeric::{integer_method, integer_method_result};
+pub(crate) use string::string_method;
+pub use string::STRING_METHODS;
 
 use crate::error::SemanticError;
 use crate::operators::{BinaryOpRegistry, UnaryOpRegistry};
 use crate::scope::{ScopeId, ScopeStack};
 use crate::symbol::Symbol;
+use crate::type_table::{Definition, TypeTable};
+use closure::ClosureFrame;
 use lency_syntax::ast::{Expr, ExprKind, Type};
 
 /// 类型推导器
@@ -34,6 +46,10 @@ pub struct TypeInferer<'a> {
     pub(crate) binary_ops: BinaryOpRegistry,
     /// 一元运算符注册表
     pub(crate) unary_ops: UnaryOpRegistry,
+    /// 正在推导的闭包栈（由内向外收集捕获变量）
+    pub(crate) closure_frames: Vec<ClosureFrame>,
+    /// 推导结果记录表（仅类型检查入口源文件时设置）
+    pub(crate) types: Option<&'a mut TypeTable>,
 }
 
 impl<'a> TypeInferer<'a> {
@@ -43,6 +59,8 @@ impl<'a> TypeInferer<'a> {
             scopes,
             binary_ops: BinaryOpRegistry::new(),
             unary_ops: UnaryOpRegistry::new(),
+            closure_frames: Vec::new(),
+            types: None,
         }
     }
 
@@ -53,6 +71,8 @@ impl<'a> TypeInferer<'a> {
             current_scope: scope_id,
             binary_ops: BinaryOpRegistry::new(),
             unary_ops: UnaryOpRegistry::new(),
+            closure_frames: Vec::new(),
+            types: None,
         }
     }
 
@@ -66,13 +86,197 @@ impl<'a> TypeInferer<'a> {
         self.scopes.lookup_from(name, self.current_scope)
     }
 
+    /// 拼写与 `name` 相近的可见值名称
+    fn similar_name(&self, name: &str) -> Option<String> {
+        self.scopes
+            .similar_name_from(name, self.current_scope, Symbol::is_value)
+    }
+
+    /// 推导结果同时写入类型表
+    pub fn recording(mut self, types: &'a mut TypeTable) -> Self {
+        self.types = Some(types);
+        self
+    }
+
     /// 推导表达式的类型
     pub fn infer(&mut self, expr: &mut Expr) -> Result<Type, SemanticError> {
+        let result = self.infer_kind(expr);
+        if self.types.is_some() {
+            if let Ok(ty) = &result {
+                self.record(expr, ty.clone());
+            }
+            // 被调用者不经过 infer，单独记录其签名与定义
+            if let ExprKind::Call { callee, .. } = &expr.kind {
+                if let Some(ty) = self.callee_type(callee) {
+                    self.record(callee, ty);
+                }
+            }
+        }
+        result
+    }
+
+    fn record(&mut self, expr: &Expr, ty: Type) {
+        let definition = self.definition_of(expr);
+        if let Some(types) = self.types.as_deref_mut() {
+            types.record(expr.span.clone(), ty, definition);
+        }
+    }
+
+    /// 被调用的函数 / 方法的类型
+    fn callee_type(&self, callee: &Expr) -> Option<Type> {
+        let function_type = |func: &crate::symbol::FunctionSymbol| Type::Function {
+            param_types: func.params.iter().map(|(_, ty)| ty.clone()).collect(),
+            return_type: Box::new(func.return_type.clone()),
+        };
+        match &callee.kind {
+            ExprKind::Variable(name) => match self.lookup(name)? {
+                Symbol::Function(func) => Some(function_type(func)),
+                symbol => symbol.ty().cloned(),
+            },
+            ExprKind::Get { object, name } | ExprKind::SafeGet { object, name } => {
+                let object_ty = self.types.as_deref()?.type_of(&object.span)?;
+                let type_name = Self::nominal_name(object_ty)?;
+                match self.lookup(type_name)? {
+                    Symbol::Struct(s) => s
+                        .get_method(name)
+                        .map(function_type)
+                        .or_else(|| s.get_field(name).map(|f| f.ty.clone())),
+                    Symbol::Enum(e) => e.get_method(name).map(function_type),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// 结构体 / 枚举类型（含可空、泛型实例）的名称
+    fn nominal_name(ty: &Type) -> Option<&String> {
+        match ty {
+            Type::Struct(n) | Type::Generic(n, _) => Some(n),
+            Type::Nullable(inner) => Self::nominal_name(inner),
+            _ => None,
+        }
+    }
+
+    /// 表达式引用的定义（变量、函数、字段）
+    fn definition_of(&self, expr: &Expr) -> Option<Definition> {
+        match &expr.kind {
+            ExprKind::Variable(name) => {
+                let scope_id = self.scopes.lookup_scope_from(name, self.current_scope)?;
+                let symbol = self.lookup(name)?;
+                let owner = self.scopes.is_global(scope_id).then(|| name.clone());
+                Some(Definition {
+                    span: symbol.span().clone(),
+                    owner,
+                })
+            }
+            ExprKind::Get { object, name } | ExprKind::SafeGet { object, name } => {
+                let object_ty = self.types.as_deref()?.type_of(&object.span)?;
+                let type_name = Self::nominal_name(object_ty)?;
+                let span = match self.lookup(type_name)? {
+                    Symbol::Struct(s) => s
+                        .get_field(name)
+                        .map(|f| f.span.clone())
+                        .or_else(|| s.get_method(name).map(|m| m.span.clone()))?,
+                    Symbol::Enum(e) => e.get_method(name)?.span.clone(),
+                    _ => return None,
+                };
+                Some(Definition {
+                    span,
+                    owner: Some(type_name.clone()),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// 在已知期望类型的位置（带类型标注的变量、赋值、返回）推导表达式类型，
+    /// 并按需插入 `dyn Trait` 或定宽整数转换。`Vec<dyn Trait>` 与 `Vec<u8>` 等
+    /// 向量字面量逐元素转换，使 `vec![Circle {..}, Square {..}]` 这样的异构集合成立。
+    pub fn infer_expected(
+        &mut self,
+        expr: &mut Expr,
+        expected: &Type,
+    ) -> Result<Type, SemanticError> {
+        if let (Type::Vec(inner), ExprKind::VecLiteral(elements)) = (expected, &mut expr.kind) {
+            if matches!(**inner, Type::TraitObject(_) | Type::FixedInt(_)) {
+                for elem in elements.iter_mut() {
+                    let actual = self.infer(elem)?;
+                    let actual = self.coerce_to(elem, inner, actual)?;
+                    if !is_compatible(inner, &actual) {
+                        return Err(SemanticError::TypeMismatch {
+                            expected: inner.to_string(),
+                            found: actual.to_string(),
+                            span: elem.span.clone(),
+                        });
+                    }
+                }
+                return Ok(expected.clone());
+            }
+        }
+        let actual = self.infer(expr)?;
+        self.coerce_to(expr, expected, actual)
+    }
+
+    /// 期望 `dyn Trait` 的位置接收具体类型的值时，将表达式包装为 `CoerceDyn`；
+    /// 期望定宽整数的位置接收整数字面量时，插入 `as` 转换。
+    ///
+    /// 返回转换后的类型；无需转换时原样返回 `actual`，交由调用方做兼容性检查。
+    pub(crate) fn coerce_to(
+        &mut self,
+        expr: &mut Expr,
+        expected: &Type,
+        actual: Type,
+    ) -> Result<Type, SemanticError> {
+        let trait_name = match expected {
+            Type::TraitObject(name) => name,
+            Type::FixedInt(kind) => return self.coerce_int_literal(expr, *kind, actual),
+            Type::Nullable(inner) if !matches!(actual, Type::Nullable(_)) => {
+                return self.coerce_to(expr, inner, actual);
+            }
+            _ => return Ok(actual),
+        };
+        let type_name = match &actual {
+            Type::Struct(name) | Type::Generic(name, _) => name,
+            _ => return Ok(actual),
+        };
+
+        let implemented = matches!(
+            self.lookup(trait_name),
+            Some(Symbol::Trait(t)) if t.is_implemented_by(type_name)
+        );
+        if !implemented {
+            return Err(SemanticError::TraitNotImplemented {
+                type_name: actual.to_string(),
+                trait_name: trait_name.clone(),
+                span: expr.span.clone(),
+            });
+        }
+
+        let span = expr.span.clone();
+        let value = std::mem::replace(
+            expr,
+            Expr {
+                kind: ExprKind::Unit,
+                span: span.clone(),
+            },
+        );
+        expr.kind = ExprKind::CoerceDyn {
+            value: Box::new(value),
+            trait_name: trait_name.clone(),
+        };
+        Ok(expected.clone())
+    }
+
+    fn infer_kind(&mut self, expr: &mut Expr) -> Result<Type, SemanticError> {
         match &mut expr.kind {
             ExprKind::Literal(lit) => Ok(self.infer_literal(lit)),
 
             ExprKind::Unit => Ok(Type::Void),
 
+            // 语法错误的占位：与任何类型兼容，不再产生级联错误
+            ExprKind::Error => Ok(Type::Error),
+
             ExprKind::Variable(name) => self.infer_variable(name, &expr.span),
 
             ExprKind::Binary(left, op, right) => self.infer_binary(left, op, right, &expr.span),
@@ -89,6 +293,8 @@ impl<'a> TypeInferer<'a> {
 
             ExprKind::Index { array, index } => self.infer_index(array, index, &expr.span),
 
+            ExprKind::Range { start, end, .. } => self.infer_range(start, end),
+
             ExprKind::Match {
                 value,
                 cases,
@@ -100,14 +306,29 @@ impl<'a> TypeInferer<'a> {
                 Ok(Type::Void)
             }
 
-            // ADT (Structs, Enums, Closures, Result, Vec) -> adt.rs
+            ExprKind::Cast { value, target } => self.infer_cast(value, target, &expr.span),
+
+            // 类型检查插入的 trait 对象装箱
+            ExprKind::CoerceDyn { value, trait_name } => {
+                self.infer(value)?;
+                Ok(Type::TraitObject(trait_name.clone()))
+            }
+
+            // ADT (Structs, Enums, Result, Vec) -> adt.rs
             ExprKind::StructLiteral { .. }
             | ExprKind::VecLiteral(_)
             | ExprKind::GenericInstantiation { .. }
             | ExprKind::Try(_)
             | ExprKind::Ok(_)
-            | ExprKind::Err(_)
-            | ExprKind::Closure { .. } => self.infer_adt(expr),
+            | ExprKind::Err(_) => self.infer_adt(expr),
+
+            // 闭包 -> closure.rs
+            ExprKind::Closure {
+                params,
+                body,
+                captures,
+                return_type,
+            } => self.infer_closure(params, body, captures, return_type, &expr.span),
 
             // Intrinsics -> intrinsics.rs
             ExprKind::ReadFile(_)
@@ -172,19 +393,15 @@ pub fn is_compatible(expected: &Type, actual: &Type) -> bool {
         (Type::Generic(name1, args1), Type::Generic(name2, args2))
             if name1 == "Result" && name2 == "Result" =>
         {
-            if args1.len() == 2 && args2.len() == 2 {
-                let expected_ok = &args1[0];
-                let expected_err = &args1[1];
-                let actual_ok = &args2[0];
-                let actual_err = &args2[1];
-
-                let ok_compat =
-                    matches!(actual_ok, Type::Void) || is_compatible(expected_ok, actual_ok);
-                let err_compat =
-                    matches!(actual_err, Type::Void) || is_compatible(expected_err, actual_err);
-                ok_compat && err_compat
-            } else {
-                false
+            match (args1.as_slice(), args2.as_slice()) {
+                ([expected_ok, expected_err], [actual_ok, actual_err]) => {
+                    let ok_compat =
+                        matches!(actual_ok, Type::Void) || is_compatible(expected_ok, actual_ok);
+                    let err_compat =
+                        matches!(actual_err, Type::Void) || is_compatible(expected_err, actual_err);
+                    ok_compat && err_compat
+                }
+                _ => false,
             }
         }
 
@@ -0,0 +1,149 @@
+//! Numeric Conversions
+//!
+//! 定宽整数相关的推导：`as` 显式转换、整数字面量适配定宽整数类型、
+//! 整数的 `wrapping_*` / `checked_*` 内置方法。
+
+use super::TypeInferer;
+use crate::error::SemanticError;
+use crate::scope::ScopeStack;
+use crate::symbol::Symbol;
+use crate::types::TypeInfo;
+use lency_syntax::ast::{Expr, ExprKind, IntKind, Literal, Span, Type, UnaryOp};
+
+/// 整数的内置算术方法：`x.wrapping_add(y)`、`x.checked_div(y)` 等
+///
+/// `wrapping_*` 按位宽回绕；`checked_*` 返回 `Option<T>`，溢出或除以零时为 `None`。
+pub const INTEGER_METHODS: [&str; 7] = [
+    "wrapping_add",
+    "wrapping_sub",
+    "wrapping_mul",
+    "checked_add",
+    "checked_sub",
+    "checked_mul",
+    "checked_div",
+];
+
+/// 整数内置方法的参数类型（与接收者相同）；不是整数方法时返回 None
+pub fn integer_method(receiver: &Type, name: &str) -> Option<Type> {
+    (receiver.is_integer() && INTEGER_METHODS.contains(&name)).then(|| receiver.clone())
+}
+
+/// 整数内置方法的返回类型：`wrapping_*` 与接收者相同，`checked_*` 为 `Option<T>`
+///
+/// `Option` 是普通的泛型枚举（由 std.core 定义），作用域中没有时报错。
+pub(crate) fn integer_method_result(
+    scopes: &ScopeStack,
+    operand: Type,
+    name: &str,
+    span: &Span,
+) -> Result<Type, SemanticError> {
+    if !name.starts_with("checked_") {
+        return Ok(operand);
+    }
+    match scopes.lookup_global("Option") {
+        Some(Symbol::Enum(option)) if option.generic_params.len() == 1 => {
+            Ok(Type::Generic("Option".to_string(), vec![operand]))
+        }
+        _ => Err(SemanticError::UndefinedType {
+            name: "Option".to_string(),
+            span: span.clone(),
+        }),
+    }
+}
+
+/// 整数字面量（含取负）的值
+fn int_literal_value(expr: &Expr) -> Option<i128> {
+    match &expr.kind {
+        ExprKind::Literal(Literal::Int(value)) => Some(*value as i128),
+        ExprKind::Unary(UnaryOp::Neg, inner) => int_literal_value(inner).map(|v| -v),
+        _ => None,
+    }
+}
+
+impl<'a> TypeInferer<'a> {
+    /// 推导 `value as target`：数值类型之间、bool 与 char 到整数、u8 到 char
+    ///
+    /// 任意整数到 char 不一定是合法的 Unicode 标量值，因此只允许 u8。
+    pub(crate) fn infer_cast(
+        &mut self,
+        value: &mut Expr,
+        target: &Type,
+        span: &std::ops::Range<usize>,
+    ) -> Result<Type, SemanticError> {
+        let source = self.infer(value)?;
+        let valid = match (&source, target) {
+            (Type::Error, _) | (_, Type::Error) => true,
+            (Type::Bool | Type::Char, to) => to.is_integer(),
+            (from, Type::Char) => *from == Type::FixedInt(IntKind::U8),
+            (from, to) => from.is_numeric() && to.is_numeric(),
+        };
+        if valid {
+            Ok(target.clone())
+        } else {
+            Err(SemanticError::InvalidCast {
+                from: source.to_string(),
+                to: target.to_string(),
+                span: span.clone(),
+            })
+        }
+    }
+
+    /// 期望定宽整数的位置出现整数字面量时，检查范围并插入 `as` 转换
+    ///
+    /// 只有字面量会被适配，其它 `int` 表达式仍需显式 `as`。
+    pub(crate) fn coerce_int_literal(
+        &mut self,
+        expr: &mut Expr,
+        kind: IntKind,
+        actual: Type,
+    ) -> Result<Type, SemanticError> {
+        if actual != Type::Int {
+            return Ok(actual);
+        }
+        let Some(value) = int_literal_value(expr) else {
+            return Ok(actual);
+        };
+        if value < kind.min_value() || value > kind.max_value() {
+            return Err(SemanticError::IntegerLiteralOutOfRange {
+                value,
+                ty: kind.to_string(),
+                span: expr.span.clone(),
+            });
+        }
+
+        let span = expr.span.clone();
+        let literal = std::mem::replace(
+            expr,
+            Expr {
+                kind: ExprKind::Unit,
+                span: span.clone(),
+            },
+        );
+        expr.kind = ExprKind::Cast {
+            value: Box::new(literal),
+            target: Type::FixedInt(kind),
+        };
+        Ok(Type::FixedInt(kind))
+    }
+
+    /// 二元运算一侧是定宽整数、另一侧是整数字面量时，字面量取该定宽类型
+    pub(crate) fn adapt_int_operands(
+        &mut self,
+        left: &mut Expr,
+        left_ty: Type,
+        right: &mut Expr,
+        right_ty: Type,
+    ) -> Result<(Type, Type), SemanticError> {
+        match (&left_ty, &right_ty) {
+            (Type::FixedInt(kind), Type::Int) => {
+                let right_ty = self.coerce_int_literal(right, *kind, right_ty)?;
+                Ok((left_ty, right_ty))
+            }
+            (Type::Int, Type::FixedInt(kind)) => {
+                let left_ty = self.coerce_int_literal(left, *kind, left_ty)?;
+                Ok((left_ty, right_ty))
+            }
+            _ => Ok((left_ty, right_ty)),
+        }
+    }
+}
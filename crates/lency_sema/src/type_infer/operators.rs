@@ -14,6 +14,7 @@ impl<'a> TypeInferer<'a> {
     ) -> Result<Type, SemanticError> {
         let left_ty = self.infer(left)?;
         let right_ty = self.infer(right)?;
+        let (left_ty, right_ty) = self.adapt_int_operands(left, left_ty, right, right_ty)?;
 
         // Special handling for Elvis Operator (??)
         if matches!(op, lency_syntax::ast::BinaryOp::Elvis) {
@@ -33,8 +34,8 @@ impl<'a> TypeInferer<'a> {
                     });
                 }
                 _ => {
-                    // Left is not nullable, so result is left_ty.
-                    // Warning could be emitted here (unnecessary elvis).
+                    // 左侧不可为空，结果即 left_ty；多余的 `??` 由 lint::body 的
+                    // unnecessary-elvis 报告
                     return Ok(left_ty);
                 }
             }
@@ -52,23 +53,19 @@ impl<'a> TypeInferer<'a> {
             if left_ty == right_ty {
                 // Currently restrict to T op T
                 if let Some(Symbol::GenericParam(gp)) = self.lookup(name) {
-                    if let Some(Type::Struct(tit_name)) = &gp.bound {
-                        // Check if trait supports the operator
-                        // For now, hardcode standard traits mapping since we don't have operator overloading fully generic mapped yet
-                        // Eq -> ==, !=
-                        // Comparable -> <, >, <=, >=
-                        use lency_syntax::ast::BinaryOp::*;
+                    // Check if any bound trait supports the operator
+                    // For now, hardcode standard traits mapping since we don't have operator overloading fully generic mapped yet
+                    // Eq -> ==, !=
+                    // Comparable -> <, >, <=, >=
+                    use lency_syntax::ast::BinaryOp::*;
+                    for tit_name in gp.bound_traits() {
                         match op {
-                            Eq | Neq => {
-                                if tit_name == "Eq" || tit_name == "Comparable" {
-                                    // Comparable usually implies Eq
-                                    return Ok(Type::Bool);
-                                }
+                            // Comparable usually implies Eq
+                            Eq | Neq if tit_name == "Eq" || tit_name == "Comparable" => {
+                                return Ok(Type::Bool);
                             }
-                            Lt | Leq | Gt | Geq => {
-                                if tit_name == "Comparable" {
-                                    return Ok(Type::Bool);
-                                }
+                            Lt | Leq | Gt | Geq if tit_name == "Comparable" => {
+                                return Ok(Type::Bool);
                             }
                             _ => {}
                         }
@@ -0,0 +1,40 @@
+//! Range Expressions
+//!
+//! `a..b` / `a..=b`：两端都是 int，结果为 `Type::Range`。
+//! 区间可被 for-in 迭代（元素为 int），也可作为 Vec 与字符串的切片下标。
+
+use super::TypeInferer;
+use crate::error::SemanticError;
+use lency_syntax::ast::{Expr, Span, Type};
+
+impl<'a> TypeInferer<'a> {
+    pub(crate) fn infer_range(
+        &mut self,
+        start: &mut Expr,
+        end: &mut Expr,
+    ) -> Result<Type, SemanticError> {
+        for bound in [start, end] {
+            let ty = self.infer(bound)?;
+            if !matches!(ty, Type::Int | Type::Error) {
+                return Err(SemanticError::TypeMismatch {
+                    expected: "int".to_string(),
+                    found: ty.to_string(),
+                    span: bound.span.clone(),
+                });
+            }
+        }
+        Ok(Type::Range)
+    }
+
+    /// 以区间为下标的切片：`v[1..3]` 为 Vec 副本，`s[1..3]` 为按字节的子串
+    pub(crate) fn infer_slice(&self, target: &Type, span: &Span) -> Result<Type, SemanticError> {
+        match target {
+            Type::Vec(_) | Type::String | Type::Error => Ok(target.clone()),
+            _ => Err(SemanticError::TypeMismatch {
+                expected: "Vec or string".to_string(),
+                found: target.to_string(),
+                span: span.clone(),
+            }),
+        }
+    }
+}
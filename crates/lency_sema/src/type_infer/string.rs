@@ -0,0 +1,18 @@
+//! String Views
+//!
+//! 字符串的内置方法：`s.chars()` 按 Unicode 标量值拆分，`s.bytes()` 按 UTF-8 字节拆分。
+//! `len(s)`、`substr(s, start, len)` 与下标 `s[i]` 都以字节计。
+
+use lency_syntax::ast::{IntKind, Type};
+
+/// 字符串的内置方法（均无参数）
+pub const STRING_METHODS: [&str; 2] = ["chars", "bytes"];
+
+/// 字符串内置方法的返回类型；不是字符串方法时返回 None
+pub fn string_method(receiver: &Type, name: &str) -> Option<Type> {
+    match (receiver, name) {
+        (Type::String, "chars") => Some(Type::Vec(Box::new(Type::Char))),
+        (Type::String, "bytes") => Some(Type::Vec(Box::new(Type::FixedInt(IntKind::U8)))),
+        _ => None,
+    }
+}
@@ -4,8 +4,9 @@
 
 use super::TypeInferer;
 use crate::error::SemanticError;
-use crate::scope::ScopeStack;
-use lency_syntax::ast::{Expr, ExprKind, Literal, Type};
+use crate::scope::{ScopeKind, ScopeStack};
+use crate::symbol::{Symbol, VariableSymbol};
+use lency_syntax::ast::{BinaryOp, Expr, ExprKind, Literal, Param, Type};
 
 /// Helper: Create a test scope stack
 fn create_test_scopes() -> ScopeStack {
@@ -233,3 +234,97 @@ fn test_infer_array_invalid_property() {
         SemanticError::UndefinedField { .. }
     ));
 }
+
+/// Helper: |int x| => x + <captured>
+fn make_adder_closure(captured: &str) -> Expr {
+    make_expr(ExprKind::Closure {
+        params: vec![Param {
+            name: "x".to_string(),
+            ty: Type::Int,
+        }],
+        body: Box::new(make_expr(ExprKind::Binary(
+            Box::new(make_expr(ExprKind::Variable("x".to_string()))),
+            BinaryOp::Add,
+            Box::new(make_expr(ExprKind::Variable(captured.to_string()))),
+        ))),
+        captures: vec![],
+        return_type: None,
+    })
+}
+
+#[test]
+fn test_infer_closure_captures_local() {
+    let mut scopes = create_test_scopes();
+    scopes.enter_scope(ScopeKind::Function);
+    let base = VariableSymbol::new("base".to_string(), Type::Int, true, 0..1);
+    scopes.define(Symbol::Variable(base)).unwrap(); // allow: unwrap
+
+    let mut inferer = TypeInferer::new(&mut scopes);
+    let mut closure = make_adder_closure("base");
+
+    let ty = inferer.infer(&mut closure).unwrap(); // allow: unwrap
+    assert_eq!(
+        ty,
+        Type::Function {
+            param_types: vec![Type::Int],
+            return_type: Box::new(Type::Int),
+        }
+    );
+
+    // sema 将捕获列表与返回类型写回 AST
+    match &closure.kind {
+        ExprKind::Closure {
+            captures,
+            return_type,
+            ..
+        } => {
+            assert_eq!(captures.len(), 1);
+            assert_eq!(captures[0].name, "base");
+            assert_eq!(captures[0].ty, Type::Int);
+            assert_eq!(return_type, &Some(Type::Int));
+        }
+        _ => panic!("Expected closure"),
+    }
+}
+
+#[test]
+fn test_infer_closure_does_not_capture_globals() {
+    let mut scopes = create_test_scopes();
+    let limit = VariableSymbol::new("limit".to_string(), Type::Int, true, 0..1);
+    scopes.define(Symbol::Variable(limit)).unwrap(); // allow: unwrap
+    scopes.enter_scope(ScopeKind::Function);
+
+    let mut inferer = TypeInferer::new(&mut scopes);
+    let mut closure = make_adder_closure("limit");
+
+    assert!(inferer.infer(&mut closure).is_ok());
+    match &closure.kind {
+        ExprKind::Closure { captures, .. } => assert!(captures.is_empty()),
+        _ => panic!("Expected closure"),
+    }
+}
+
+#[test]
+fn test_result_compatibility() {
+    use super::is_compatible;
+    let result = |ok: Type, err: Type| Type::Generic("Result".to_string(), vec![ok, err]);
+    let builtin = |ok: Type, err: Type| Type::Result {
+        ok_type: Box::new(ok),
+        err_type: Box::new(err),
+    };
+
+    let expected = result(Type::Int, Type::String);
+    assert!(is_compatible(&expected, &result(Type::Int, Type::String)));
+    // Ok/Err 构造器的另一侧为 Void
+    assert!(is_compatible(&expected, &result(Type::Int, Type::Void)));
+    assert!(is_compatible(&expected, &result(Type::Void, Type::String)));
+    assert!(is_compatible(&expected, &builtin(Type::Void, Type::String)));
+    assert!(is_compatible(&builtin(Type::Int, Type::String), &expected));
+    assert!(!is_compatible(
+        &expected,
+        &result(Type::String, Type::String)
+    ));
+    // 类型参数个数不对的 Result 不兼容
+    let malformed = Type::Generic("Result".to_string(), vec![Type::Int]);
+    assert!(!is_compatible(&expected, &malformed));
+}
@@ -0,0 +1,82 @@
+//! Type Table
+//!
+//! 类型检查时记录入口源文件中每个表达式的推导类型与其引用的定义，
+//! 供 IDE 功能（悬停类型、跳转定义）按源码位置查询。
+
+use lency_syntax::ast::{Span, Type};
+
+/// 表达式引用的定义位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    /// 定义的源码位置
+    pub span: Span,
+    /// 拥有该定义的全局符号名（字段/方法为所属类型名），局部变量与参数为 None。
+    /// 借助 `AnalysisResult::module_origins` 可判断定义是否来自导入模块。
+    pub owner: Option<String>,
+}
+
+/// 已推导类型的表达式
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedExpr {
+    pub span: Span,
+    pub ty: Type,
+    pub definition: Option<Definition>,
+}
+
+/// 表达式类型表
+#[derive(Debug, Clone, Default)]
+pub struct TypeTable {
+    entries: Vec<TypedExpr>,
+}
+
+impl TypeTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, span: Span, ty: Type, definition: Option<Definition>) {
+        self.entries.push(TypedExpr {
+            span,
+            ty,
+            definition,
+        });
+    }
+
+    /// 最近一次记录的、位于 `span` 的表达式类型
+    pub fn type_of(&self, span: &Span) -> Option<&Type> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| &e.span == span)
+            .map(|e| &e.ty)
+    }
+
+    /// 包含 `offset` 的最内层表达式
+    pub fn expr_at(&self, offset: usize) -> Option<&TypedExpr> {
+        self.entries
+            .iter()
+            .filter(|e| e.span.start <= offset && offset < e.span.end)
+            .min_by_key(|e| e.span.end - e.span.start)
+    }
+
+    /// 包含 `offset` 且引用了某个定义的最内层表达式
+    pub fn reference_at(&self, offset: usize) -> Option<&TypedExpr> {
+        self.entries
+            .iter()
+            .filter(|e| e.definition.is_some())
+            .filter(|e| e.span.start <= offset && offset < e.span.end)
+            .min_by_key(|e| e.span.end - e.span.start)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TypedExpr> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
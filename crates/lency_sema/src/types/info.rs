@@ -10,7 +10,7 @@ use lency_syntax::ast::Type;
 /// - 添加新类型时，只需在此处更新实现
 /// - 避免在多处散落 match 语句
 pub trait TypeInfo {
-    /// 是否是数值类型（int, float）
+    /// 是否是数值类型（int, 定宽整数, float）
     ///
     /// # Examples
     ///
@@ -24,6 +24,20 @@ pub trait TypeInfo {
     /// ```
     fn is_numeric(&self) -> bool;
 
+    /// 是否是整数类型（int 与 i8/u8/i32/u64 等定宽整数）
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lency_sema::types::TypeInfo;
+    /// use lency_syntax::ast::{IntKind, Type};
+    ///
+    /// assert!(Type::Int.is_integer());
+    /// assert!(Type::FixedInt(IntKind::U8).is_integer());
+    /// assert!(!Type::Float.is_integer());
+    /// ```
+    fn is_integer(&self) -> bool;
+
     /// 是否可空
     ///
     /// # Examples
@@ -40,7 +54,7 @@ pub trait TypeInfo {
 
     /// 是否是基础类型
     ///
-    /// 基础类型包括：int, float, bool, string, void
+    /// 基础类型包括：int, 定宽整数, float, bool, string, char, void
     fn is_primitive(&self) -> bool;
 
     /// 是否是数组类型
@@ -75,7 +89,11 @@ pub trait TypeInfo {
 /// 为 Type 实现 TypeInfo
 impl TypeInfo for Type {
     fn is_numeric(&self) -> bool {
-        matches!(self, Type::Int | Type::Float)
+        self.is_integer() || matches!(self, Type::Float)
+    }
+
+    fn is_integer(&self) -> bool {
+        matches!(self, Type::Int | Type::FixedInt(_))
     }
 
     fn is_nullable(&self) -> bool {
@@ -85,7 +103,13 @@ impl TypeInfo for Type {
     fn is_primitive(&self) -> bool {
         matches!(
             self,
-            Type::Int | Type::Float | Type::Bool | Type::String | Type::Void
+            Type::Int
+                | Type::FixedInt(_)
+                | Type::Float
+                | Type::Bool
+                | Type::String
+                | Type::Char
+                | Type::Void
         )
     }
 
@@ -3,7 +3,7 @@
 //! Extracted from inline tests to keep source files clean
 
 use super::info::TypeInfo;
-use lency_syntax::ast::Type;
+use lency_syntax::ast::{IntKind, Type};
 
 #[test]
 fn test_is_numeric() {
@@ -14,6 +14,25 @@ fn test_is_numeric() {
     assert!(!Type::Void.is_numeric());
 }
 
+#[test]
+fn test_fixed_int_info() {
+    let byte = Type::FixedInt(IntKind::U8);
+    assert!(byte.is_numeric());
+    assert!(byte.is_integer());
+    assert!(byte.is_primitive());
+    assert!(!Type::Float.is_integer());
+    assert_eq!(byte.display_name(), "u8");
+    assert_eq!(Type::FixedInt(IntKind::I32).display_name(), "i32");
+}
+
+#[test]
+fn test_char_info() {
+    assert!(Type::Char.is_primitive());
+    assert!(!Type::Char.is_numeric());
+    assert!(!Type::Char.is_integer());
+    assert_eq!(Type::Char.display_name(), "char");
+}
+
 #[test]
 fn test_is_nullable() {
     let nullable_int = Type::Nullable(Box::new(Type::Int));
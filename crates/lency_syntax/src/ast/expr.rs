@@ -1,13 +1,15 @@
+use serde::{Deserialize, Serialize};
+
 // 简单的 Span 定义 (也就是源码中的起止位置: 0..5)
 pub type Span = std::ops::Range<usize>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Expr {
     pub kind: ExprKind,
     pub span: Span,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ExprKind {
     // 字面量: 1, "hello", true, null
     Literal(Literal),
@@ -61,6 +63,14 @@ pub enum ExprKind {
         index: Box<Expr>,
     },
 
+    // 区间: a..b（不含 b）或 a..=b（含 b），两端为 int
+    // 用于 for-in 计数循环与切片 v[1..3]
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        inclusive: bool,
+    },
+
     // 泛型实例化: func::<int>
     GenericInstantiation {
         base: Box<Expr>,
@@ -86,9 +96,28 @@ pub enum ExprKind {
     Err(Box<Expr>),
 
     // 闭包: |int a, int b| => a + b
+    // captures / return_type 由 sema 填充，codegen 据此构造环境结构体
     Closure {
         params: Vec<crate::ast::Param>,
         body: Box<Expr>,
+        /// 按值捕获的外部变量（名称 + 类型）
+        captures: Vec<crate::ast::Param>,
+        /// 闭包体的返回类型（类型推导后写入）
+        return_type: Option<crate::ast::Type>,
+    },
+
+    // 显式数值转换: x as u8, b as int, n as float
+    // 整数之间按目标位宽截断或扩展（源类型有符号时符号扩展）
+    Cast {
+        value: Box<Expr>,
+        target: crate::ast::Type,
+    },
+
+    // 转换为 Trait 对象: 由 sema 在期望 `dyn Trait` 的位置插入（赋值、传参、返回、push）
+    // codegen 据此把具体值与对应的 vtable 打包成胖指针
+    CoerceDyn {
+        value: Box<Expr>,
+        trait_name: String,
     },
 
     // 文件 I/O 内置函数 (Sprint 12)
@@ -114,16 +143,22 @@ pub enum ExprKind {
     Panic(Box<Expr>),
     // format("template {}", args_vec) -> string
     Format(Box<Expr>, Box<Expr>),
+
+    // 语法错误恢复产生的占位表达式，类型为 Type::Error
+    // 只出现在容错解析的结果中，不会进入代码生成
+    Error,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MatchCase {
     pub pattern: MatchPattern,
+    // `case n if n > 0 =>`：模式匹配成功后还需守卫为 true
+    pub guard: Option<Box<Expr>>,
     pub body: Box<Expr>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MatchPattern {
     Literal(Literal),
     Variant {
@@ -132,18 +167,103 @@ pub enum MatchPattern {
     },
     Variable(String),
     Wildcard,
+    // 结构体解构: Point { x, y: 0 }，未列出的字段不参与匹配
+    // `x` 是 `x: x` 的简写
+    Struct {
+        name: String,
+        fields: Vec<(String, MatchPattern)>,
+    },
+    // 或模式: A | B，各分支必须绑定相同的变量
+    Or(Vec<MatchPattern>),
+    // 区间模式: 1..9 / 'a'..='z'，两端为整数或 char 字面量
+    Range {
+        start: Literal,
+        end: Literal,
+        inclusive: bool,
+    },
+    // 绑定整个值并继续匹配: n @ 1..=9
+    Binding {
+        name: String,
+        pattern: Box<MatchPattern>,
+    },
+}
+
+impl MatchPattern {
+    /// 先序遍历模式及其所有子模式
+    pub fn walk<'a>(&'a self, f: &mut impl FnMut(&'a MatchPattern)) {
+        f(self);
+        match self {
+            MatchPattern::Variant { sub_patterns, .. } | MatchPattern::Or(sub_patterns) => {
+                sub_patterns.iter().for_each(|sub| sub.walk(f))
+            }
+            MatchPattern::Struct { fields, .. } => fields.iter().for_each(|(_, sub)| sub.walk(f)),
+            MatchPattern::Binding { pattern, .. } => pattern.walk(f),
+            MatchPattern::Literal(_)
+            | MatchPattern::Variable(_)
+            | MatchPattern::Wildcard
+            | MatchPattern::Range { .. } => {}
+        }
+    }
+
+    /// 先序遍历并修改模式及其所有子模式
+    pub fn walk_mut(&mut self, f: &mut impl FnMut(&mut MatchPattern)) {
+        f(self);
+        match self {
+            MatchPattern::Variant { sub_patterns, .. } | MatchPattern::Or(sub_patterns) => {
+                sub_patterns.iter_mut().for_each(|sub| sub.walk_mut(f))
+            }
+            MatchPattern::Struct { fields, .. } => {
+                fields.iter_mut().for_each(|(_, sub)| sub.walk_mut(f))
+            }
+            MatchPattern::Binding { pattern, .. } => pattern.walk_mut(f),
+            MatchPattern::Literal(_)
+            | MatchPattern::Variable(_)
+            | MatchPattern::Wildcard
+            | MatchPattern::Range { .. } => {}
+        }
+    }
+
+    /// 模式引入的变量名（或模式只取第一个分支，各分支绑定相同）
+    pub fn bindings(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_bindings(&mut names);
+        names
+    }
+
+    fn collect_bindings<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            MatchPattern::Variable(name) => names.push(name),
+            MatchPattern::Binding { name, pattern } => {
+                names.push(name);
+                pattern.collect_bindings(names);
+            }
+            MatchPattern::Variant { sub_patterns, .. } => sub_patterns
+                .iter()
+                .for_each(|sub| sub.collect_bindings(names)),
+            MatchPattern::Struct { fields, .. } => fields
+                .iter()
+                .for_each(|(_, sub)| sub.collect_bindings(names)),
+            MatchPattern::Or(alternatives) => {
+                if let Some(first) = alternatives.first() {
+                    first.collect_bindings(names);
+                }
+            }
+            MatchPattern::Literal(_) | MatchPattern::Wildcard | MatchPattern::Range { .. } => {}
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Literal {
     Int(i64),
     Float(f64),
     Bool(bool),
     String(String),
+    Char(char),
     Null,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BinaryOp {
     Add,
     Sub,
@@ -157,12 +277,18 @@ pub enum BinaryOp {
     Leq,
     Geq, // <, >, <=, >=
     And,
-    Or,    // &&, ||
+    Or, // &&, ||
+    BitAnd,
+    BitOr,
+    BitXor, // &, |, ^
+    Shl,
+    Shr,   // <<, >>
     Elvis, // ??
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UnaryOp {
-    Neg, // -x
-    Not, // !x
+    Neg,    // -x
+    Not,    // !x
+    BitNot, // ~x
 }
@@ -1,13 +1,16 @@
-use crate::ast::expr::{Expr, Span};
+use crate::ast::expr::{Expr, MatchPattern, Span};
 use crate::ast::types::Type;
+use serde::{Deserialize, Serialize};
 
 // 顶层定义：只能出现在文件最外层
-#[derive(Debug, Clone)]
+// is_pub: 是否带 `pub` 修饰（模块导出），impl 中的方法恒为 false
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Decl {
     // 函数定义: int add(int a, int b) { ... }
     // 泛型函数: T identity<T>(T x) { ... }
     Function {
         span: Span,
+        is_pub: bool,
         name: String,
         generic_params: Vec<GenericParam>, // 泛型参数
         params: Vec<Param>,
@@ -18,6 +21,7 @@ pub enum Decl {
     // 外部函数声明: extern int print(int n);
     ExternFunction {
         span: Span,
+        is_pub: bool,
         name: String,
         generic_params: Vec<GenericParam>,
         params: Vec<Param>,
@@ -28,6 +32,7 @@ pub enum Decl {
     // 泛型结构体: struct Box<T> { T value }
     Struct {
         span: Span,
+        is_pub: bool,
         name: String,
         generic_params: Vec<GenericParam>,
         fields: Vec<Field>,
@@ -55,6 +60,7 @@ pub enum Decl {
     // 泛型Trait: trait Comparable<T> { bool equals(T other); }
     Trait {
         span: Span,
+        is_pub: bool,
         name: String,
         generic_params: Vec<GenericParam>,
         methods: Vec<TraitMethod>,
@@ -66,6 +72,7 @@ pub enum Decl {
     // enum Option<T> { Some(T), None }
     Enum {
         span: Span,
+        is_pub: bool,
         name: String,
         generic_params: Vec<GenericParam>,
         variants: Vec<EnumVariant>,
@@ -74,33 +81,48 @@ pub enum Decl {
     // Global Variable: var x = 1;
     Var {
         span: Span,
+        is_pub: bool,
         name: String,
         ty: Option<Type>,
         value: Expr,
     },
 
     // Sprint 13: 模块系统
-    // import std.io;
+    // import std.io / import std.io as io / import std.io.*
     Import {
         span: Span,
         items: Vec<ImportItem>,
     },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportItem {
     pub path: Vec<String>,
     pub alias: Option<String>,
+    /// `import std.io.*`：模块的导出名称直接进入导入方的作用域
+    pub glob: bool,
 }
 
-#[derive(Debug, Clone)]
+impl ImportItem {
+    /// 导入绑定的模块名：别名或路径最后一段（`import std.io` -> `io`），glob 导入没有
+    pub fn binding(&self) -> Option<&str> {
+        if self.glob {
+            return None;
+        }
+        self.alias
+            .as_deref()
+            .or(self.path.last().map(String::as_str))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenericParam {
     pub span: Span,
     pub name: String,
-    pub bound: Option<Type>, // 约束类型，如 T: Display
+    pub bounds: Vec<Type>, // 约束 Trait，如 T: Display 或 K: Hash + Eq<K>
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EnumVariant {
     // Unit Variant: None
     Unit(String),
@@ -108,13 +130,13 @@ pub enum EnumVariant {
     Tuple(String, Vec<Type>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Param {
     pub name: String,
     pub ty: Type,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Field {
     pub name: String,
     pub ty: Type,
@@ -122,7 +144,7 @@ pub struct Field {
 
 /// Trait 方法签名（无函数体）
 /// 例如: void greet(); 或 bool equals(T other);
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraitMethod {
     pub name: String,
     pub params: Vec<Param>,
@@ -130,7 +152,7 @@ pub struct TraitMethod {
 }
 
 // 语句：出现在函数体内部
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Stmt {
     // 变量声明: var x: int = 1; 或 var x = 1;
     VarDecl {
@@ -140,6 +162,13 @@ pub enum Stmt {
         value: Expr,
     },
 
+    // 解构声明: var Pair { key, value } = p（模式必须不可反驳）
+    VarPattern {
+        span: Span,
+        pattern: MatchPattern,
+        value: Expr,
+    },
+
     // 赋值: x = x + 1; (注意赋值在 Lency 里是语句，不是表达式)
     Assignment {
         span: Span,
@@ -161,6 +190,15 @@ pub enum Stmt {
         else_block: Option<Vec<Stmt>>,
     },
 
+    // if let Some(x) = opt { ... } else { ... }
+    IfLet {
+        span: Span,
+        pattern: MatchPattern,
+        value: Expr,
+        then_block: Vec<Stmt>,
+        else_block: Option<Vec<Stmt>>,
+    },
+
     // 循环: while (expr) { ... }
     While {
         span: Span,
@@ -168,6 +206,14 @@ pub enum Stmt {
         body: Vec<Stmt>,
     },
 
+    // while let Some(tok) = it.next() { ... }：每轮求值 value，不匹配时结束循环
+    WhileLet {
+        span: Span,
+        pattern: MatchPattern,
+        value: Expr,
+        body: Vec<Stmt>,
+    },
+
     // For 循环: for init; condition; update { ... }
     For {
         span: Span,
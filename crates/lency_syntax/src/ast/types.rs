@@ -1,14 +1,20 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Type {
     // --- 基础类型 ---
     Int,    // i64
     Float,  // f64
     Bool,   // bool
     String, // string
+    Char,   // char: 一个 Unicode 标量值 (u32)
     Void,   // void
 
+    // 定宽整数: i8, u8, i32, u64 ... (int 即 i64，byte 即 u8)
+    // 与 int 之间不做隐式转换，需要 `as`
+    FixedInt(IntKind),
+
     // --- 复杂类型 ---
     // 类引用: User, MyClass
 
@@ -35,6 +41,9 @@ pub enum Type {
     // 动态数组类型: Vec<T>
     Vec(Box<Type>),
 
+    // 区间类型: 0..n / 0..=n 的类型，元素为 int
+    Range,
+
     // Result 类型: Result<T, E> 或 T! (语法糖)
     // 用于错误处理，符合 "Safety by Default" 哲学
     Result {
@@ -49,10 +58,91 @@ pub enum Type {
         return_type: Box<Type>,
     },
 
+    // Trait 对象: dyn Greeter
+    // 运行时为 { 数据指针, vtable 指针 }，通过 vtable 动态分发方法调用
+    TraitObject(String),
+
     // 错误占位符 (当用户写错类型时，编译器用这个占位，防止崩溃)
     Error,
 }
 
+/// 定宽整数的种类（`int` 本身是 i64，不在此列）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IntKind {
+    I8,
+    I16,
+    I32,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntKind {
+    /// 全部定宽整数种类
+    pub const ALL: [IntKind; 7] = [
+        IntKind::I8,
+        IntKind::I16,
+        IntKind::I32,
+        IntKind::U8,
+        IntKind::U16,
+        IntKind::U32,
+        IntKind::U64,
+    ];
+
+    /// 位宽
+    pub fn bits(self) -> u32 {
+        match self {
+            IntKind::I8 | IntKind::U8 => 8,
+            IntKind::I16 | IntKind::U16 => 16,
+            IntKind::I32 | IntKind::U32 => 32,
+            IntKind::U64 => 64,
+        }
+    }
+
+    /// 是否有符号（决定比较、除法与扩展的方式）
+    pub fn is_signed(self) -> bool {
+        matches!(self, IntKind::I8 | IntKind::I16 | IntKind::I32)
+    }
+
+    /// 可表示的最小值
+    pub fn min_value(self) -> i128 {
+        if self.is_signed() {
+            -(1i128 << (self.bits() - 1))
+        } else {
+            0
+        }
+    }
+
+    /// 可表示的最大值
+    pub fn max_value(self) -> i128 {
+        if self.is_signed() {
+            (1i128 << (self.bits() - 1)) - 1
+        } else {
+            (1i128 << self.bits()) - 1
+        }
+    }
+
+    /// 源码中的类型名
+    pub fn name(self) -> &'static str {
+        match self {
+            IntKind::I8 => "i8",
+            IntKind::I16 => "i16",
+            IntKind::I32 => "i32",
+            IntKind::U8 => "u8",
+            IntKind::U16 => "u16",
+            IntKind::U32 => "u32",
+            IntKind::U64 => "u64",
+        }
+    }
+}
+
+impl Display for IntKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 // 让类型能打印成好看的字符串: "List<int>", "string?"
 impl Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -61,7 +151,9 @@ impl Display for Type {
             Type::Float => write!(f, "float"),
             Type::Bool => write!(f, "bool"),
             Type::String => write!(f, "string"),
+            Type::Char => write!(f, "char"),
             Type::Void => write!(f, "void"),
+            Type::FixedInt(kind) => write!(f, "{}", kind),
 
             Type::GenericParam(name) => write!(f, "{}", name),
             Type::Generic(name, args) => {
@@ -78,6 +170,7 @@ impl Display for Type {
             Type::Array { element_type, size } => write!(f, "[{}]{}", size, element_type),
             Type::Struct(name) => write!(f, "{}", name),
             Type::Vec(inner) => write!(f, "Vec<{}>", inner),
+            Type::Range => write!(f, "Range"),
             Type::Result { ok_type, err_type } => write!(f, "Result<{}, {}>", ok_type, err_type),
             Type::Function {
                 param_types,
@@ -92,6 +185,7 @@ impl Display for Type {
                 }
                 write!(f, ")")
             }
+            Type::TraitObject(name) => write!(f, "dyn {}", name),
             Type::Error => write!(f, "<?>"),
         }
     }
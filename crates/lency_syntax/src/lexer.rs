@@ -1,3 +1,4 @@
+use crate::ast::IntKind;
 use logos::Logos;
 use std::fmt;
 
@@ -15,6 +16,9 @@ pub enum Token {
     Else,
     #[token("while")]
     While,
+    // if let / while let
+    #[token("let")]
+    Let,
     #[token("for")]
     For,
     #[token("in")]
@@ -29,6 +33,8 @@ pub enum Token {
     Import,
     #[token("extern")]
     Extern,
+    #[token("pub")]
+    Pub,
     #[token("print")]
     Print,
     #[token("read_file")]
@@ -58,6 +64,8 @@ pub enum Token {
     Impl,
     #[token("trait")]
     Trait,
+    #[token("dyn")]
+    Dyn,
     #[token("vec")]
     Vec,
     #[token("Ok")]
@@ -80,13 +88,26 @@ pub enum Token {
 
     // --- 基础类型关键字 ---
     #[token("int")]
+    #[token("i64")]
     TypeInt,
+    // 定宽整数: i8 i16 i32 u8 u16 u32 u64，byte 即 u8
+    #[token("i8", |_| IntKind::I8)]
+    #[token("i16", |_| IntKind::I16)]
+    #[token("i32", |_| IntKind::I32)]
+    #[token("u8", |_| IntKind::U8)]
+    #[token("byte", |_| IntKind::U8)]
+    #[token("u16", |_| IntKind::U16)]
+    #[token("u32", |_| IntKind::U32)]
+    #[token("u64", |_| IntKind::U64)]
+    TypeFixedInt(IntKind),
     #[token("float")]
     TypeFloat,
     #[token("bool")]
     TypeBool,
     #[token("string")]
     TypeString,
+    #[token("char")]
+    TypeChar,
     #[token("void")]
     TypeVoid,
 
@@ -116,6 +137,10 @@ pub enum Token {
     Leq,
     #[token(">=")]
     Geq,
+    // `>>` 不作为单独的 token，以免与嵌套泛型 `Vec<Vec<int>>` 冲突；
+    // 右移由解析器识别为两个相邻的 `>`
+    #[token("<<")]
+    Shl,
 
     #[token("!")]
     Bang,
@@ -125,6 +150,12 @@ pub enum Token {
     Or,
     #[token("|")]
     Pipe,
+    #[token("&")]
+    Amp,
+    #[token("^")]
+    Caret,
+    #[token("~")]
+    Tilde,
 
     #[token("(")]
     LParen,
@@ -143,6 +174,10 @@ pub enum Token {
     Comma,
     #[token(".")]
     Dot,
+    #[token("..")]
+    DotDot,
+    #[token("..=")]
+    DotDotEq,
     #[token(":")]
     Colon,
     #[token(";")]
@@ -155,6 +190,8 @@ pub enum Token {
     QuestionQuestion,
     #[token("=>")]
     Arrow,
+    #[token("@")]
+    At,
     #[regex("_", priority = 3)]
     Underscore,
 
@@ -199,6 +236,25 @@ pub enum Token {
     })]
     String(String),
 
+    // 字符字面量: 'a'、'中'、'\n'、'\u{1F600}'，值为一个 Unicode 标量值
+    #[regex(r#"'([^'\\\n]|\\[nrt0'"\\]|\\u\{[0-9a-fA-F]{1,6}\})'"#, |lex| {
+        let s = lex.slice();
+        let inner = &s[1..s.len() - 1];
+        match inner.strip_prefix('\\') {
+            None => inner.chars().next(),
+            Some("n") => Some('\n'),
+            Some("r") => Some('\r'),
+            Some("t") => Some('\t'),
+            Some("0") => Some('\0'),
+            Some(escaped) if escaped.starts_with("u{") => {
+                let hex = &escaped[2..escaped.len() - 1];
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            }
+            Some(escaped) => escaped.chars().next(),
+        }
+    })]
+    Char(char),
+
     #[regex(r"//[^\n]*", logos::skip)]
     Comment,
 
@@ -213,6 +269,7 @@ impl fmt::Display for Token {
             Token::If => write!(f, "if"),
             Token::Else => write!(f, "else"),
             Token::While => write!(f, "while"),
+            Token::Let => write!(f, "let"),
             Token::For => write!(f, "for"),
             Token::In => write!(f, "in"),
             Token::Break => write!(f, "break"),
@@ -220,6 +277,7 @@ impl fmt::Display for Token {
             Token::Return => write!(f, "return"),
             Token::Import => write!(f, "import"),
             Token::Extern => write!(f, "extern"),
+            Token::Pub => write!(f, "pub"),
             Token::Print => write!(f, "print"),
             Token::ReadFile => write!(f, "read_file"),
             Token::WriteFile => write!(f, "write_file"),
@@ -234,6 +292,7 @@ impl fmt::Display for Token {
             Token::Struct => write!(f, "struct"),
             Token::Impl => write!(f, "impl"),
             Token::Trait => write!(f, "trait"),
+            Token::Dyn => write!(f, "dyn"),
             Token::Vec => write!(f, "vec"),
             Token::Ok => write!(f, "Ok"),
             Token::Err => write!(f, "Err"),
@@ -243,9 +302,11 @@ impl fmt::Display for Token {
             Token::True => write!(f, "true"),
             Token::False => write!(f, "false"),
             Token::TypeInt => write!(f, "int"),
+            Token::TypeFixedInt(kind) => write!(f, "{}", kind),
             Token::TypeFloat => write!(f, "float"),
             Token::TypeBool => write!(f, "bool"),
             Token::TypeString => write!(f, "string"),
+            Token::TypeChar => write!(f, "char"),
             Token::TypeVoid => write!(f, "void"),
             Token::Plus => write!(f, "+"),
             Token::Minus => write!(f, "-"),
@@ -259,10 +320,14 @@ impl fmt::Display for Token {
             Token::Gt => write!(f, ">"),
             Token::Leq => write!(f, "<="),
             Token::Geq => write!(f, ">="),
+            Token::Shl => write!(f, "<<"),
             Token::Bang => write!(f, "!"),
             Token::And => write!(f, "&&"),
             Token::Or => write!(f, "||"),
             Token::Pipe => write!(f, "|"),
+            Token::Amp => write!(f, "&"),
+            Token::Caret => write!(f, "^"),
+            Token::Tilde => write!(f, "~"),
             Token::LParen => write!(f, "("),
             Token::RParen => write!(f, ")"),
             Token::LBrace => write!(f, "{{"), // Escaped brace
@@ -271,12 +336,15 @@ impl fmt::Display for Token {
             Token::RBracket => write!(f, "]"),
             Token::Comma => write!(f, ","),
             Token::Dot => write!(f, "."),
+            Token::DotDot => write!(f, ".."),
+            Token::DotDotEq => write!(f, "..="),
             Token::Colon => write!(f, ":"),
             Token::Semicolon => write!(f, ";"),
             Token::Question => write!(f, "?"),
             Token::QuestionDot => write!(f, "?."),
             Token::QuestionQuestion => write!(f, "??"),
             Token::Arrow => write!(f, "=>"),
+            Token::At => write!(f, "@"),
             Token::Underscore => write!(f, "_"),
             Token::Match => write!(f, "match"),
             Token::Case => write!(f, "case"),
@@ -284,6 +352,7 @@ impl fmt::Display for Token {
             Token::Int(i) => write!(f, "{}", i),
             Token::Float(s) => write!(f, "{}", s),
             Token::String(s) => write!(f, "\"{}\"", s), // Quote string
+            Token::Char(c) => write!(f, "{:?}", c),
             Token::Comment => write!(f, "<comment>"),
             Token::Error => write!(f, "<error>"),
         }
@@ -6,6 +6,7 @@ pub mod parser; // Now points to parser/mod.rs
 mod tests {
     // use super::*;
     use crate::lexer::Token;
+    use crate::parser;
     // use chumsky::Parser;
     use logos::Logos; // 修复: 引入 Parser trait
 
@@ -32,6 +33,365 @@ mod tests {
         assert!(tokens.contains(&Token::Ident("b".to_string())));
     }
 
+    #[test]
+    fn test_lexer_pub_keyword() {
+        let code = "pub int add(int a, int b)";
+        let mut lexer = Token::lexer(code);
+
+        assert_eq!(lexer.next(), Some(Ok(Token::Pub)));
+        assert_eq!(lexer.next(), Some(Ok(Token::TypeInt)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Ident("add".to_string()))));
+    }
+
+    #[test]
+    fn test_lexer_dyn_keyword() {
+        let code = "dyn Greeter g";
+        let mut lexer = Token::lexer(code);
+
+        assert_eq!(lexer.next(), Some(Ok(Token::Dyn)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Ident("Greeter".to_string()))));
+    }
+
+    #[test]
+    fn test_lexer_fixed_int_types() {
+        use crate::ast::IntKind;
+
+        let code = "u8 byte i64 x as i32";
+        let tokens: Vec<Token> = Token::lexer(code).filter_map(Result::ok).collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::TypeFixedInt(IntKind::U8),
+                Token::TypeFixedInt(IntKind::U8),
+                Token::TypeInt,
+                Token::Ident("x".to_string()),
+                Token::As,
+                Token::TypeFixedInt(IntKind::I32),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parser_global_vars() {
+        use crate::ast::*;
+
+        let program = parser::parse("pub var limit = 3\nvar name: string = \"lency\"")
+            .map_err(|e| format!("{:?}", e));
+        let Ok(Program { decls }) = program else {
+            panic!("parse failed: {:?}", program.err()); // allow: unwrap
+        };
+        assert!(matches!(
+            &decls[0],
+            Decl::Var { is_pub: true, name, ty: None, .. } if name == "limit"
+        ));
+        assert!(matches!(
+            &decls[1],
+            Decl::Var { is_pub: false, name, ty: Some(Type::String), .. } if name == "name"
+        ));
+        assert!(parser::parse("pub impl Foo {}").is_err());
+    }
+
+    #[test]
+    fn test_parser_cast_binds_tighter_than_product() {
+        use crate::ast::*;
+
+        let program = parser::parse_spanned("int main() { return -a as u8 * 2 as int }")
+            .map_err(|e| format!("{:?}", e));
+        let Ok(Program { decls }) = program else {
+            panic!("parse failed: {:?}", program.err()); // allow: unwrap
+        };
+        let Some(Decl::Function { body, .. }) = decls.first() else {
+            panic!("main not found"); // allow: unwrap
+        };
+        let Some(Stmt::Return {
+            value: Some(value), ..
+        }) = body.first()
+        else {
+            panic!("expected return"); // allow: unwrap
+        };
+        // ((-a) as u8) * (2 as int)
+        let ExprKind::Binary(lhs, BinaryOp::Mul, rhs) = &value.kind else {
+            panic!("expected product, got {:?}", value.kind); // allow: unwrap
+        };
+        assert!(matches!(
+            &lhs.kind,
+            ExprKind::Cast { value, target: Type::FixedInt(IntKind::U8) }
+                if matches!(value.kind, ExprKind::Unary(UnaryOp::Neg, _))
+        ));
+        assert!(matches!(
+            &rhs.kind,
+            ExprKind::Cast {
+                target: Type::Int,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_lexer_bitwise_operators() {
+        let code = "a & b | c ^ ~d << 1 >> 2 && e";
+        let tokens: Vec<Token> = Token::lexer(code).filter_map(Result::ok).collect();
+        let symbols: Vec<String> = tokens
+            .iter()
+            .filter(|t| !matches!(t, Token::Ident(_) | Token::Int(_)))
+            .map(|t| t.to_string())
+            .collect();
+
+        // `>>` 保持为两个 `>`，由解析器合并
+        assert_eq!(symbols, vec!["&", "|", "^", "~", "<<", ">", ">", "&&"]);
+    }
+
+    #[test]
+    fn test_parser_bitwise_precedence() {
+        use crate::ast::*;
+
+        let source = "int main() {\n    var v: Vec<Vec<int>> = vec![]\n    var w = id::<Vec<Vec<int>>>(v)\n    return a | b & c >> 1 == d\n}";
+        let program = parser::parse_spanned(source).map_err(|e| format!("{:?}", e));
+        let Ok(Program { decls }) = program else {
+            panic!("parse failed: {:?}", program.err()); // allow: unwrap
+        };
+        let Some(Decl::Function { body, .. }) = decls.first() else {
+            panic!("main not found"); // allow: unwrap
+        };
+        let Some(Stmt::Return {
+            value: Some(value), ..
+        }) = body.get(2)
+        else {
+            panic!("expected return"); // allow: unwrap
+        };
+        // (a | (b & (c >> 1))) == d
+        let ExprKind::Binary(lhs, BinaryOp::Eq, _) = &value.kind else {
+            panic!("expected comparison, got {:?}", value.kind); // allow: unwrap
+        };
+        let ExprKind::Binary(_, BinaryOp::BitOr, and) = &lhs.kind else {
+            panic!("expected `|`, got {:?}", lhs.kind); // allow: unwrap
+        };
+        let ExprKind::Binary(_, BinaryOp::BitAnd, shift) = &and.kind else {
+            panic!("expected `&`, got {:?}", and.kind); // allow: unwrap
+        };
+        assert!(matches!(shift.kind, ExprKind::Binary(_, BinaryOp::Shr, _)));
+    }
+
+    #[test]
+    fn test_parser_rejects_spaced_shift() {
+        assert!(parser::parse_spanned("int main() { return a > > 1 }").is_err());
+    }
+
+    #[test]
+    fn test_lexer_char_literals() {
+        let code = r"char c = 'a' '中' '\n' '\'' '\u{1F600}'";
+        let tokens: Vec<Token> = Token::lexer(code).filter_map(Result::ok).collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::TypeChar,
+                Token::Ident("c".to_string()),
+                Token::Eq,
+                Token::Char('a'),
+                Token::Char('中'),
+                Token::Char('\n'),
+                Token::Char('\''),
+                Token::Char('😀'),
+            ]
+        );
+        // 多个字符或未闭合都不是字符字面量
+        assert!(Token::lexer("'ab'").any(|t| t.is_err()));
+        assert!(Token::lexer("'a").any(|t| t.is_err()));
+    }
+
+    #[test]
+    fn test_parser_char_type_keeps_std_char_import() {
+        let source = "import std.char\nchar first(string s) { return 'x' }";
+        assert!(parser::parse_spanned(source).is_ok());
+    }
+
+    #[test]
+    fn test_parser_import_forms() {
+        use crate::ast::*;
+
+        let source = "import std.io\nimport std.io as out\nimport std.char.*";
+        let program = parser::parse_spanned(source).map_err(|e| format!("{:?}", e));
+        let Ok(Program { decls }) = program else {
+            panic!("parse failed: {:?}", program.err()); // allow: unwrap
+        };
+        let items: Vec<_> = decls
+            .iter()
+            .flat_map(|d| match d {
+                Decl::Import { items, .. } => items.clone(),
+                _ => Vec::new(),
+            })
+            .collect();
+        let bindings: Vec<_> = items.iter().map(ImportItem::binding).collect();
+        assert_eq!(bindings, vec![Some("io"), Some("out"), None]);
+        assert_eq!(items[2].path, vec!["std".to_string(), "char".to_string()]);
+
+        // `*` 只能出现在路径末尾，且 glob 导入不能带别名
+        assert!(parser::parse_spanned("import std.*.io").is_err());
+        assert!(parser::parse_spanned("import std.io.* as io").is_err());
+    }
+
+    #[test]
+    fn test_parser_multiple_generic_bounds() {
+        use crate::ast::*;
+
+        let source = "int slot<K: Hash + Eq<K>, V>(K key) { return 0 }";
+        let program = parser::parse_spanned(source).map_err(|e| format!("{:?}", e));
+        let Ok(Program { decls }) = program else {
+            panic!("parse failed: {:?}", program.err()); // allow: unwrap
+        };
+        let Some(Decl::Function { generic_params, .. }) = decls.first() else {
+            panic!("slot not found"); // allow: unwrap
+        };
+        let eq_k = Type::Generic("Eq".to_string(), vec![Type::Struct("K".to_string())]);
+        assert_eq!(
+            generic_params[0].bounds,
+            vec![Type::Struct("Hash".to_string()), eq_k]
+        );
+        assert!(generic_params[1].bounds.is_empty());
+    }
+
+    #[test]
+    fn test_parser_range_expressions() {
+        use crate::ast::*;
+
+        let source = "int main() {\n    var r: Range = 0..n + 1\n    return v[1..=3]\n}";
+        let program = parser::parse_spanned(source).map_err(|e| format!("{:?}", e));
+        let Ok(Program { decls }) = program else {
+            panic!("parse failed: {:?}", program.err()); // allow: unwrap
+        };
+        let Some(Decl::Function { body, .. }) = decls.first() else {
+            panic!("main not found"); // allow: unwrap
+        };
+        // 0..(n + 1)
+        let Some(Stmt::VarDecl {
+            ty: Some(Type::Range),
+            value,
+            ..
+        }) = body.first()
+        else {
+            panic!("expected range declaration"); // allow: unwrap
+        };
+        let ExprKind::Range {
+            end,
+            inclusive: false,
+            ..
+        } = &value.kind
+        else {
+            panic!("expected range, got {:?}", value.kind); // allow: unwrap
+        };
+        assert!(matches!(end.kind, ExprKind::Binary(_, BinaryOp::Add, _)));
+        let Some(Stmt::Return {
+            value: Some(value), ..
+        }) = body.get(1)
+        else {
+            panic!("expected return"); // allow: unwrap
+        };
+        assert!(matches!(
+            &value.kind,
+            ExprKind::Index { index, .. }
+                if matches!(index.kind, ExprKind::Range { inclusive: true, .. })
+        ));
+        // 区间不能连写
+        assert!(parser::parse_spanned("int main() { return 0..1..2 }").is_err());
+    }
+
+    #[test]
+    fn test_parser_rich_patterns() {
+        use crate::ast::*;
+
+        let source = "int main() {\n    return match p {\n        case Point { x: 0, y } => y,\n        case 1 | 2 => 1,\n        case n @ -9..=9 if n > 0 => n,\n        case _ => 0\n    }\n}";
+        let program = parser::parse_spanned(source).map_err(|e| format!("{:?}", e));
+        let Ok(Program { decls }) = program else {
+            panic!("parse failed: {:?}", program.err()); // allow: unwrap
+        };
+        let Some(Decl::Function { body, .. }) = decls.first() else {
+            panic!("main not found"); // allow: unwrap
+        };
+        let Some(Stmt::Return {
+            value: Some(value), ..
+        }) = body.first()
+        else {
+            panic!("expected return"); // allow: unwrap
+        };
+        let ExprKind::Match { cases, .. } = &value.kind else {
+            panic!("expected match, got {:?}", value.kind); // allow: unwrap
+        };
+        let patterns: Vec<_> = cases.iter().map(|c| &c.pattern).collect();
+
+        // 字段简写 `y` 等价于 `y: y`
+        assert_eq!(
+            patterns[0],
+            &MatchPattern::Struct {
+                name: "Point".to_string(),
+                fields: vec![
+                    ("x".to_string(), MatchPattern::Literal(Literal::Int(0))),
+                    ("y".to_string(), MatchPattern::Variable("y".to_string())),
+                ],
+            }
+        );
+        assert_eq!(
+            patterns[1],
+            &MatchPattern::Or(vec![
+                MatchPattern::Literal(Literal::Int(1)),
+                MatchPattern::Literal(Literal::Int(2)),
+            ])
+        );
+        assert_eq!(
+            patterns[2],
+            &MatchPattern::Binding {
+                name: "n".to_string(),
+                pattern: Box::new(MatchPattern::Range {
+                    start: Literal::Int(-9),
+                    end: Literal::Int(9),
+                    inclusive: true,
+                }),
+            }
+        );
+        assert!(cases[2].guard.is_some());
+        assert!(cases[3].guard.is_none());
+        assert_eq!(patterns[2].bindings(), vec!["n"]);
+    }
+
+    #[test]
+    fn test_parser_pattern_statements() {
+        use crate::ast::*;
+
+        let source = "int main() {\n    var Pair { key, value: v } = p\n    if let Some(x) = opt { print(x) } else { return 0 }\n    while let Option.Some(tok) = it.next() { print(tok) }\n    return 1\n}";
+        let program = parser::parse_spanned(source).map_err(|e| format!("{:?}", e));
+        let Ok(Program { decls }) = program else {
+            panic!("parse failed: {:?}", program.err()); // allow: unwrap
+        };
+        let Some(Decl::Function { body, .. }) = decls.first() else {
+            panic!("main not found"); // allow: unwrap
+        };
+        let Some(Stmt::VarPattern { pattern, .. }) = body.first() else {
+            panic!("expected destructuring declaration, got {:?}", body.first());
+            // allow: unwrap
+        };
+        assert_eq!(pattern.bindings(), vec!["key", "v"]);
+        assert!(matches!(
+            body.get(1),
+            Some(Stmt::IfLet {
+                pattern: MatchPattern::Variant { name, .. },
+                else_block: Some(_),
+                ..
+            }) if name == "Some"
+        ));
+        assert!(matches!(
+            body.get(2),
+            Some(Stmt::WhileLet { value, .. })
+                if matches!(value.kind, ExprKind::Call { .. })
+        ));
+        // 普通的变量声明不受影响
+        let program = parser::parse_spanned("int main() { var x = 1 return x }");
+        assert!(matches!(
+            program.map(|p| p.decls),
+            Ok(decls) if matches!(&decls[0], Decl::Function { body, .. } if matches!(body[0], Stmt::VarDecl { .. }))
+        ));
+    }
+
     // 注意：此测试在某些配置下可能栈溢出
     // 功能已通过 examples/test_parser.rs 验证
     // 如需运行，请确保 .cargo/config.toml 中设置了足够的栈大小
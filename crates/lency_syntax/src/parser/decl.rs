@@ -2,8 +2,10 @@
 //!
 //! 声明解析：函数、类
 
+use super::expr::expr_parser;
 use super::helpers::{field_parser, generic_params_parser, ident_parser, type_parser};
 
+use super::recovery::stmt_recovery;
 use super::stmt::stmt_parser;
 use crate::ast::stmt::ImportItem;
 use crate::ast::*;
@@ -13,8 +15,11 @@ use chumsky::prelude::*;
 pub type ParserError = Simple<Token>;
 
 /// 解析声明 (公共接口)
+#[allow(clippy::result_large_err)]
 pub fn decl_parser() -> impl Parser<Token, Decl, Error = ParserError> {
-    let stmt = stmt_parser().boxed();
+    let stmt = stmt_parser()
+        .recover_with(skip_parser(stmt_recovery()))
+        .boxed();
     recursive(|_decl| {
         // 函数声明: int add(int a, int b) { ... }
         // 泛型函数: T identity<T>(T x) { ... }
@@ -37,6 +42,7 @@ pub fn decl_parser() -> impl Parser<Token, Decl, Error = ParserError> {
             .map_with_span(
                 |((((return_type, name), generic_params), params), body), span| Decl::Function {
                     span,
+                    is_pub: false,
                     name,
                     generic_params,
                     params,
@@ -62,6 +68,7 @@ pub fn decl_parser() -> impl Parser<Token, Decl, Error = ParserError> {
             .map_with_span(|(((return_type, name), generic_params), params), span| {
                 Decl::ExternFunction {
                     span,
+                    is_pub: false,
                     name,
                     generic_params,
                     params,
@@ -81,6 +88,7 @@ pub fn decl_parser() -> impl Parser<Token, Decl, Error = ParserError> {
             )
             .map_with_span(|((name, generic_params), fields), span| Decl::Struct {
                 span,
+                is_pub: false,
                 name,
                 generic_params,
                 fields,
@@ -158,6 +166,7 @@ pub fn decl_parser() -> impl Parser<Token, Decl, Error = ParserError> {
             )
             .map_with_span(|((name, generic_params), methods), span| Decl::Trait {
                 span,
+                is_pub: false,
                 name,
                 generic_params,
                 methods,
@@ -181,12 +190,24 @@ pub fn decl_parser() -> impl Parser<Token, Decl, Error = ParserError> {
                 }
             });
 
-        // Import: import std.io [as alias]; OR import { ... }
-        let import_item = ident_parser()
+        // Import: import std.io [as alias]; import std.io.*; OR import { ... }
+        // `char` 是类型关键字，但仍可作为模块名 (import std.char.*)
+        let import_segment = ident_parser().or(just(Token::TypeChar).to("char".to_string()));
+        let import_item = import_segment
             .separated_by(just(Token::Dot))
             .at_least(1)
+            .then(just(Token::Dot).then(just(Token::Star)).or_not())
             .then(just(Token::As).ignore_then(ident_parser()).or_not())
-            .map(|(path, alias)| ImportItem { path, alias });
+            .try_map(|((path, glob), alias), span| {
+                if glob.is_some() && alias.is_some() {
+                    return Err(Simple::custom(span, "a glob import cannot have an alias"));
+                }
+                Ok(ImportItem {
+                    path,
+                    alias,
+                    glob: glob.is_some(),
+                })
+            });
 
         let import_block = import_item
             .clone()
@@ -210,20 +231,60 @@ pub fn decl_parser() -> impl Parser<Token, Decl, Error = ParserError> {
             )
             .map_with_span(|((name, generic_params), variants), span| Decl::Enum {
                 span,
+                is_pub: false,
                 name,
                 generic_params,
                 variants,
             });
 
-        choice((
-            enum_decl,
-            import_decl,
-            trait_decl,
-            struct_decl,
-            impl_decl,
-            extern_decl,
-            func,
-        ))
-        .boxed()
+        // 全局变量: var limit = 3 / var limit: int = 3
+        let global_var = just(Token::Var)
+            .ignore_then(ident_parser())
+            .then(just(Token::Colon).ignore_then(type_parser()).or_not())
+            .then_ignore(just(Token::Eq))
+            .then(expr_parser())
+            .then_ignore(just(Token::Semicolon).or_not())
+            .map_with_span(|((name, ty), value), span| Decl::Var {
+                span,
+                is_pub: false,
+                name,
+                ty,
+                value,
+            });
+
+        // 可见性修饰: pub struct Point { ... } / pub int add(...) { ... }
+        just(Token::Pub)
+            .or_not()
+            .then(choice((
+                enum_decl,
+                import_decl,
+                trait_decl,
+                struct_decl,
+                impl_decl,
+                extern_decl,
+                global_var,
+                func,
+            )))
+            .try_map(|(vis, decl), span| match vis {
+                None => Ok(decl),
+                Some(_) => mark_pub(decl).ok_or_else(|| {
+                    Simple::custom(span, "`pub` is not allowed on impl blocks or imports")
+                }),
+            })
+            .boxed()
     })
 }
+
+/// 将声明标记为 `pub`；impl 块和 import 不支持可见性修饰，返回 None
+fn mark_pub(mut decl: Decl) -> Option<Decl> {
+    match &mut decl {
+        Decl::Function { is_pub, .. }
+        | Decl::ExternFunction { is_pub, .. }
+        | Decl::Struct { is_pub, .. }
+        | Decl::Trait { is_pub, .. }
+        | Decl::Enum { is_pub, .. }
+        | Decl::Var { is_pub, .. } => *is_pub = true,
+        Decl::Impl { .. } | Decl::Import { .. } => return None,
+    }
+    Some(decl)
+}
@@ -7,6 +7,7 @@ use chumsky::prelude::*;
 
 use super::ParserError;
 
+#[allow(clippy::result_large_err)]
 pub fn parser(
     expr: impl Parser<Token, Expr, Error = ParserError> + Clone,
 ) -> impl Parser<Token, Expr, Error = ParserError> + Clone {
@@ -18,19 +19,31 @@ pub fn parser(
         span,
     });
 
+    // 括号内出错时跳到匹配的 `)`，整体替换为占位表达式
     let paren = expr
         .clone()
-        .delimited_by(just(Token::LParen), just(Token::RParen));
+        .delimited_by(just(Token::LParen), just(Token::RParen))
+        .recover_with(nested_delimiters(
+            Token::LParen,
+            Token::RParen,
+            [
+                (Token::LBracket, Token::RBracket),
+                (Token::LBrace, Token::RBrace),
+            ],
+            crate::parser::recovery::error_expr,
+        ));
 
     let match_expr = just(Token::Match)
         .ignore_then(expr.clone())
         .then(
             just(Token::Case)
                 .ignore_then(crate::parser::pattern::pattern_parser())
+                .then(just(Token::If).ignore_then(expr.clone()).or_not())
                 .then_ignore(just(Token::Arrow))
                 .then(expr.clone())
-                .map_with_span(|(pattern, body), span| MatchCase {
+                .map_with_span(|((pattern, guard), body), span| MatchCase {
                     pattern,
+                    guard: guard.map(Box::new),
                     body: Box::new(body),
                     span,
                 })
@@ -85,9 +98,18 @@ pub fn parser(
                 .allow_trailing()
                 .delimited_by(just(Token::LBrace), just(Token::RBrace)),
         )
-        .map_with_span(|(type_, fields), span| Expr {
-            kind: ExprKind::StructLiteral { type_, fields },
-            span,
+        // 空字段的结构体字面量只接受非限定的结构体名：
+        // `if a.b {}` / `if f(x) {}` 中的条件不应被解析为结构体字面量
+        .try_map(|(type_, fields), span| {
+            let plain_name =
+                matches!(&type_, Type::Struct(n) | Type::Generic(n, _) if !n.contains('.'));
+            if fields.is_empty() && !plain_name {
+                return Err(Simple::custom(span, "not a struct literal"));
+            }
+            Ok(Expr {
+                kind: ExprKind::StructLiteral { type_, fields },
+                span,
+            })
         });
 
     // Ok 构造器: Ok(value)
@@ -131,6 +153,8 @@ pub fn parser(
             kind: ExprKind::Closure {
                 params,
                 body: Box::new(body),
+                captures: Vec::new(),
+                return_type: None,
             },
             span,
         });
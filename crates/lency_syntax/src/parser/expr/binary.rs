@@ -1,21 +1,43 @@
+use super::super::helpers::type_parser;
 use crate::ast::*;
 use crate::lexer::Token;
 use chumsky::prelude::*;
 
 use super::ParserError;
 
+#[allow(clippy::result_large_err)]
 pub fn parser<'a>(
     unary: impl Parser<Token, Expr, Error = ParserError> + Clone + 'a,
 ) -> impl Parser<Token, Expr, Error = ParserError> + Clone + 'a {
+    // Cast: x as u8 (比乘除优先，-a as u8 即 (-a) as u8)
+    let cast = unary
+        .then(
+            just(Token::As)
+                .ignore_then(type_parser())
+                .map_with_span(|target, span: Span| (target, span))
+                .repeated(),
+        )
+        .foldl(|value, (target, type_span)| {
+            let span = value.span.start..type_span.end;
+            Expr {
+                kind: ExprKind::Cast {
+                    value: Box::new(value),
+                    target,
+                },
+                span,
+            }
+        })
+        .boxed();
+
     // Product: *, /, %
-    let product = unary
+    let product = cast
         .clone()
         .then(
             just(Token::Star)
                 .to(BinaryOp::Mul)
                 .or(just(Token::Slash).to(BinaryOp::Div))
                 .or(just(Token::Percent).to(BinaryOp::Mod))
-                .then(unary)
+                .then(cast)
                 .repeated(),
         )
         .foldl(|lhs, (op, rhs)| {
@@ -46,8 +68,87 @@ pub fn parser<'a>(
         })
         .boxed();
 
+    // Shift: <<, >>
+    // `>>` 由两个相邻的 `>` 组成（见 lexer 中的说明）
+    let shr = just(Token::Gt)
+        .map_with_span(|_, span: Span| span)
+        .then(just(Token::Gt).map_with_span(|_, span: Span| span))
+        .try_map(|(first, second), span| {
+            if first.end == second.start {
+                Ok(BinaryOp::Shr)
+            } else {
+                Err(Simple::custom(span, "expected `>>`"))
+            }
+        });
+    let shift = sum
+        .clone()
+        .then(
+            just(Token::Shl)
+                .to(BinaryOp::Shl)
+                .or(shr)
+                .then(sum)
+                .repeated(),
+        )
+        .foldl(|lhs, (op, rhs)| {
+            let span = lhs.span.start..rhs.span.end;
+            Expr {
+                kind: ExprKind::Binary(Box::new(lhs), op, Box::new(rhs)),
+                span,
+            }
+        })
+        .boxed();
+
+    // Bitwise And: & (按位运算比比较优先，`a & b == 0` 即 `(a & b) == 0`)
+    let bit_and = shift
+        .clone()
+        .then(just(Token::Amp).to(BinaryOp::BitAnd).then(shift).repeated())
+        .foldl(|lhs, (op, rhs)| {
+            let span = lhs.span.start..rhs.span.end;
+            Expr {
+                kind: ExprKind::Binary(Box::new(lhs), op, Box::new(rhs)),
+                span,
+            }
+        })
+        .boxed();
+
+    // Bitwise Xor: ^
+    let bit_xor = bit_and
+        .clone()
+        .then(
+            just(Token::Caret)
+                .to(BinaryOp::BitXor)
+                .then(bit_and)
+                .repeated(),
+        )
+        .foldl(|lhs, (op, rhs)| {
+            let span = lhs.span.start..rhs.span.end;
+            Expr {
+                kind: ExprKind::Binary(Box::new(lhs), op, Box::new(rhs)),
+                span,
+            }
+        })
+        .boxed();
+
+    // Bitwise Or: | (闭包参数的 `|` 只出现在操作数位置，不会与此冲突)
+    let bit_or = bit_xor
+        .clone()
+        .then(
+            just(Token::Pipe)
+                .to(BinaryOp::BitOr)
+                .then(bit_xor)
+                .repeated(),
+        )
+        .foldl(|lhs, (op, rhs)| {
+            let span = lhs.span.start..rhs.span.end;
+            Expr {
+                kind: ExprKind::Binary(Box::new(lhs), op, Box::new(rhs)),
+                span,
+            }
+        })
+        .boxed();
+
     // Comparison: <, >, <=, >=, ==, !=
-    let comparison = sum
+    let comparison = bit_or
         .clone()
         .then(
             just(Token::EqEq)
@@ -57,7 +158,7 @@ pub fn parser<'a>(
                 .or(just(Token::Geq).to(BinaryOp::Geq))
                 .or(just(Token::Lt).to(BinaryOp::Lt))
                 .or(just(Token::Gt).to(BinaryOp::Gt))
-                .then(sum)
+                .then(bit_or)
                 .repeated(),
         )
         .foldl(|lhs, (op, rhs)| {
@@ -106,7 +207,7 @@ pub fn parser<'a>(
         .boxed();
 
     // Elvis: ?? (Right associative)
-    logical_or
+    let elvis = logical_or
         .clone()
         .then_ignore(just(Token::QuestionQuestion))
         .repeated()
@@ -118,5 +219,31 @@ pub fn parser<'a>(
                 span,
             }
         })
+        .boxed();
+
+    // Range: a..b / a..=b (优先级最低且不可连写，`0..n + 1` 即 `0..(n + 1)`)
+    elvis
+        .clone()
+        .then(
+            just(Token::DotDot)
+                .to(false)
+                .or(just(Token::DotDotEq).to(true))
+                .then(elvis)
+                .or_not(),
+        )
+        .map(|(start, range)| match range {
+            Some((inclusive, end)) => {
+                let span = start.span.start..end.span.end;
+                Expr {
+                    kind: ExprKind::Range {
+                        start: Box::new(start),
+                        end: Box::new(end),
+                        inclusive,
+                    },
+                    span,
+                }
+            }
+            None => start,
+        })
         .boxed()
 }
@@ -11,6 +11,7 @@ pub fn literal_value_parser() -> impl Parser<Token, Literal, Error = ParserError
         Token::Int(x) => Literal::Int(x),
         Token::Float(s) => Literal::Float(s.parse().unwrap_or(0.0)),
         Token::String(s) => Literal::String(s),
+        Token::Char(c) => Literal::Char(c),
         Token::True => Literal::Bool(true),
         Token::False => Literal::Bool(false),
         Token::Null => Literal::Null,
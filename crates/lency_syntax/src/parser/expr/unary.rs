@@ -10,6 +10,7 @@ pub fn parser(
     just(Token::Minus)
         .to(UnaryOp::Neg)
         .or(just(Token::Bang).to(UnaryOp::Not))
+        .or(just(Token::Tilde).to(UnaryOp::BitNot))
         .map_with_span(|op, span| (op, span))
         .repeated()
         .then(postfix)
@@ -21,14 +21,20 @@ pub fn type_parser() -> impl Parser<Token, Type, Error = ParserError> + Clone {
         // 基础类型
         let basic = select! {
             Token::TypeInt => Type::Int,
+            Token::TypeFixedInt(kind) => Type::FixedInt(kind),
             Token::TypeFloat => Type::Float,
             Token::TypeString => Type::String,
             Token::TypeBool => Type::Bool,
+            Token::TypeChar => Type::Char,
             Token::TypeVoid => Type::Void,
         };
 
         // 泛型/结构体: Ident 或 Ident<Type, ...>
+        // 模块限定名: io.File -> Struct("io.File")，由 sema 解析到模块成员
         let ident_or_generic = ident_parser()
+            .separated_by(just(Token::Dot))
+            .at_least(1)
+            .map(|segments| segments.join("."))
             .then(
                 ty.clone()
                     .separated_by(just(Token::Comma))
@@ -36,14 +42,18 @@ pub fn type_parser() -> impl Parser<Token, Type, Error = ParserError> + Clone {
                     .delimited_by(just(Token::Lt), just(Token::Gt))
                     .or_not(),
             )
-            .map(|(name, args)| {
-                if let Some(params) = args {
-                    Type::Generic(name, params)
-                } else {
-                    Type::Struct(name)
-                }
+            .map(|(name, args)| match args {
+                Some(params) => Type::Generic(name, params),
+                // 内置区间类型
+                None if name == "Range" => Type::Range,
+                None => Type::Struct(name),
             });
 
+        // Trait 对象: dyn Greeter / dyn io.Writer
+        let trait_object = just(Token::Dyn)
+            .ignore_then(ident_parser().separated_by(just(Token::Dot)).at_least(1))
+            .map(|segments| Type::TraitObject(segments.join(".")));
+
         // Vec<Type>
         let vec_type = just(Token::Vec)
             .ignore_then(just(Token::Lt))
@@ -62,7 +72,8 @@ pub fn type_parser() -> impl Parser<Token, Type, Error = ParserError> + Clone {
             });
 
         // 组合
-        let type_without_suffix = choice((vec_type, array_type, basic, ident_or_generic));
+        let type_without_suffix =
+            choice((vec_type, array_type, basic, trait_object, ident_or_generic));
 
         // 后缀类型修饰符: T? (可空) 或 T! (Result)
         type_without_suffix
@@ -107,16 +118,19 @@ pub fn field_parser() -> impl Parser<Token, Field, Error = ParserError> + Clone
         .map(|(ty, name)| Field { name, ty })
 }
 
-/// 解析泛型参数列表: <T, U> 或 <T: Bound, U: Bound>
-/// 返回空Vec如果没有泛型参数
-/// 解析泛型参数列表: <T, U> 或 <T: Bound, U: Bound>
+/// 解析泛型参数列表: <T, U>、<T: Bound, U: Bound> 或 <K: Hash + Eq<K>>
 /// 返回空Vec如果没有泛型参数
 pub fn generic_params_parser(
 ) -> impl Parser<Token, Vec<crate::ast::GenericParam>, Error = ParserError> + Clone {
-    // 单个泛型参数: T 或 T: Bound
+    // 单个泛型参数: T、T: Bound 或 T: A + B
+    let bounds = type_parser().separated_by(just(Token::Plus)).at_least(1);
     let single_param = ident_parser()
-        .then(just(Token::Colon).ignore_then(type_parser()).or_not())
-        .map_with_span(|(name, bound), span| crate::ast::GenericParam { span, name, bound });
+        .then(just(Token::Colon).ignore_then(bounds).or_not())
+        .map_with_span(|(name, bounds), span| crate::ast::GenericParam {
+            span,
+            name,
+            bounds: bounds.unwrap_or_default(),
+        });
 
     single_param
         .separated_by(just(Token::Comma))
@@ -6,6 +6,7 @@ pub mod decl;
 pub mod expr;
 pub mod helpers;
 pub mod pattern;
+pub mod recovery;
 pub mod stmt;
 
 use crate::ast::Program;
@@ -15,10 +16,16 @@ use chumsky::prelude::*;
 pub type ParserError = Simple<Token>;
 
 /// 主入口：解析整个程序
+///
+/// 出错的声明被跳过后继续解析，`parse_recovery` 可得到所有语法错误
 pub fn program_parser() -> impl Parser<Token, Program, Error = ParserError> {
     decl::decl_parser()
+        .map(Some)
+        .recover_with(skip_parser(recovery::decl_recovery()))
         .repeated()
-        .map(|decls| Program { decls })
+        .map(|decls| Program {
+            decls: decls.into_iter().flatten().collect(),
+        })
         .then_ignore(end())
 }
 
@@ -32,3 +39,33 @@ pub fn parse(code: &str) -> Result<Program, Vec<ParserError>> {
 
     program_parser().parse(tokens)
 }
+
+/// 解析源码字符串，AST 与错误中的 Span 为源码字节偏移
+///
+/// `parse` 的 Span 是 token 下标，只适合测试；需要映射回源码位置时
+/// （诊断、语言服务器）使用本函数。
+pub fn parse_spanned(code: &str) -> Result<Program, Vec<ParserError>> {
+    match parse_recovery(code) {
+        (Some(program), errors) if errors.is_empty() => Ok(program),
+        (_, errors) => Err(errors),
+    }
+}
+
+/// 容错解析：返回恢复后的程序与全部语法错误（按位置排序），Span 为源码字节偏移
+///
+/// 出错的语句被替换为 `ExprKind::Error` 占位，出错的声明被丢弃，
+/// 因此即使存在错误，返回的程序也可以继续做语义分析（语言服务器）。
+pub fn parse_recovery(code: &str) -> (Option<Program>, Vec<ParserError>) {
+    use logos::Logos;
+    let tokens: Vec<(Token, std::ops::Range<usize>)> = Token::lexer(code)
+        .spanned()
+        .map(|(tok, span)| (tok.unwrap_or(Token::Error), span))
+        .collect();
+
+    let len = code.len();
+    let (program, mut errors) =
+        program_parser().parse_recovery(chumsky::Stream::from_iter(len..len, tokens.into_iter()));
+    errors.sort_by_key(|e| (e.span().start, e.span().end));
+    errors.dedup_by(|a, b| a.span() == b.span());
+    (program, errors)
+}
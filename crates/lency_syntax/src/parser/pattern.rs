@@ -1,15 +1,53 @@
 use super::expr::literal::literal_value_parser;
 // Note: ident_parser removed as we now use select! for Ok/Err support
-use crate::ast::MatchPattern;
+use crate::ast::{Literal, MatchPattern};
 use crate::lexer::Token;
 use chumsky::prelude::*;
 
 pub type ParserError = Simple<Token>;
 
+/// 路径后的子模式列表
+enum Tail {
+    Tuple(Vec<MatchPattern>),
+    Struct(Vec<(String, MatchPattern)>),
+}
+
+/// 模式语法（优先级由低到高）：
+///
+/// ```text
+/// pattern := single ('|' single)*
+/// single  := IDENT '@' pattern | '_' | literal (('..' | '..=') literal)?
+///          | path ('(' pattern, ... ')' | '{' field, ... '}')?
+/// field   := IDENT (':' pattern)?
+/// ```
 pub fn pattern_parser() -> impl Parser<Token, MatchPattern, Error = ParserError> + Clone {
     recursive(|pat| {
-        // Literal Pattern
-        let literal = literal_value_parser().map(MatchPattern::Literal);
+        // Literal Pattern（数字可带负号）
+        #[allow(clippy::result_large_err)] // Macro-generated code, unavoidable
+        let negative = just(Token::Minus).ignore_then(select! {
+            Token::Int(x) => Literal::Int(x.wrapping_neg()),
+            Token::Float(s) => Literal::Float(-s.parse().unwrap_or(0.0)),
+        });
+        let literal = negative.or(literal_value_parser());
+
+        // 区间模式 1..9 / 'a'..='z'，否则是普通字面量模式
+        let literal_or_range = literal
+            .clone()
+            .then(
+                just(Token::DotDot)
+                    .to(false)
+                    .or(just(Token::DotDotEq).to(true))
+                    .then(literal)
+                    .or_not(),
+            )
+            .map(|(start, range)| match range {
+                Some((inclusive, end)) => MatchPattern::Range {
+                    start,
+                    end,
+                    inclusive,
+                },
+                None => MatchPattern::Literal(start),
+            });
 
         // Wildcard Pattern
         let wildcard = just(Token::Underscore).to(MatchPattern::Wildcard);
@@ -21,6 +59,17 @@ pub fn pattern_parser() -> impl Parser<Token, MatchPattern, Error = ParserError>
             Token::Ok => "Ok".to_string(),  // Allow Ok as variant name
             Token::Err => "Err".to_string(), // Allow Err as variant name
         };
+        #[allow(clippy::result_large_err)]
+        let field_name = select! { Token::Ident(ident) => ident };
+
+        // n @ 1..=9
+        let binding = field_name
+            .then_ignore(just(Token::At))
+            .then(pat.clone())
+            .map(|(name, pattern)| MatchPattern::Binding {
+                name,
+                pattern: Box::new(pattern),
+            });
 
         // Qualified identifier pattern (Name or Name.Variant or Ok/Err)
         let qualified_ident = ident_or_result_variant.then(
@@ -29,61 +78,67 @@ pub fn pattern_parser() -> impl Parser<Token, MatchPattern, Error = ParserError>
                 .or_not(),
         );
 
+        let tuple_fields = pat
+            .clone()
+            .separated_by(just(Token::Comma))
+            .allow_trailing()
+            .delimited_by(just(Token::LParen), just(Token::RParen));
+
+        // `x` 是 `x: x` 的简写
+        let struct_field = field_name
+            .then(just(Token::Colon).ignore_then(pat.clone()).or_not())
+            .map(|(name, pattern)| {
+                let pattern = pattern.unwrap_or_else(|| MatchPattern::Variable(name.clone()));
+                (name, pattern)
+            });
+        let struct_fields = struct_field
+            .separated_by(just(Token::Comma))
+            .allow_trailing()
+            .delimited_by(just(Token::LBrace), just(Token::RBrace));
+
         let ident_pat = qualified_ident
             .then(
-                pat.separated_by(just(Token::Comma))
-                    .allow_trailing()
-                    .delimited_by(just(Token::LParen), just(Token::RParen))
+                tuple_fields
+                    .map(Tail::Tuple)
+                    .or(struct_fields.map(Tail::Struct))
                     .or_not(),
             )
-            .map(|((base, suffix), args)| {
-                if let Some(suffix) = suffix {
-                    // Enum.Variant form
-                    MatchPattern::Variant {
-                        name: suffix, // We only store Variant Name in MatchPattern::Variant?
-                        // Wait, MatchPattern::Variant { name, sub_patterns }.
-                        // AST definition: pub enum MatchPattern { Variant { name: String, sub_patterns: Vec<MatchPattern> }, ... }
-                        // It seems we lost the 'Enum Name'.
-                        // If we parse `OptionInt.Some`, we need to know `OptionInt`.
-                        // Does Sema resolve Variant by just name?
-                        // Let's check `lency_sema/src/type_infer/control.rs` check_pattern.
-
-                        // check_pattern uses: `target_ty` (the matched value type) to find the Enum.
-                        // Then it looks up `name` (variant name) in that Enum.
-                        // So `OptionInt.Some(x)` in pattern:
-                        // If value is `OptionInt`, we check if `Some` is a variant of `OptionInt``.
-                        // We DON'T seemingly use the qualifier `OptionInt` from the pattern in Sema?
-                        // BUT, if the user writes `OptionInt.Some`, we should probably verify it matches target type?
-                        // Or is the qualifier just syntax sugar for fully qualified access?
-
-                        // If I map `OptionInt.Some` to just `Variant { name: "Some" }`, it works with Sema (Sema ignores the prefix).
-                        // Is that acceptable?
-                        // Yes, provided Sema infers Enum from the subject expression type.
-                        // The user writing `OptionInt.Some` is helpful for readability, but Sema relies on type inference.
-                        // However, if we discard `OptionInt`, we can't strict check it.
-                        // Ideally we should verify `OptionInt` matches valid type?
-                        // But AST `MatchPattern` only has `Variant { name }`.
-                        // AST Change needed? Or just ignore prefix?
+            .map(|((base, suffix), tail)| match (suffix, tail) {
+                // 结构体模式保留完整类型名：`geo.Point { .. }` 由 resolver 解析模块前缀
+                (suffix, Some(Tail::Struct(fields))) => MatchPattern::Struct {
+                    name: match suffix {
+                        Some(suffix) => format!("{}.{}", base, suffix),
+                        None => base,
+                    },
+                    fields,
+                },
+                // `Enum.Variant(..)`：sema 根据被匹配值的类型查找变体，前缀只用于可读性
+                (Some(suffix), tail) => MatchPattern::Variant {
+                    name: suffix,
+                    sub_patterns: match tail {
+                        Some(Tail::Tuple(args)) => args,
+                        _ => Vec::new(),
+                    },
+                },
+                (None, Some(Tail::Tuple(args))) => MatchPattern::Variant {
+                    name: base,
+                    sub_patterns: args,
+                },
+                (None, None) => MatchPattern::Variable(base),
+            });
 
-                        // For Phase 4, let's just ignore the prefix in AST construction but parse it.
-                        // Update: `control.rs` check_pattern:
-                        // `MatchPattern::Variant { name, ... }`
-                        // `if let Some(Symbol::Enum(e)) = self.lookup(enum_name)` (from target_ty).
-                        // `e.get_variant(name)`.
+        let single = choice((binding, wildcard, literal_or_range, ident_pat)).boxed();
 
-                        // So yes, ignoring prefix is fine for now.
-                        sub_patterns: args.unwrap_or_default(),
-                    }
-                } else if let Some(args) = args {
-                    MatchPattern::Variant {
-                        name: base,
-                        sub_patterns: args,
-                    }
+        single
+            .separated_by(just(Token::Pipe))
+            .at_least(1)
+            .map(|mut alternatives| {
+                if alternatives.len() == 1 {
+                    alternatives.remove(0)
                 } else {
-                    MatchPattern::Variable(base)
+                    MatchPattern::Or(alternatives)
                 }
-            });
-
-        choice((wildcard, literal, ident_pat)).boxed()
+            })
+            .boxed()
     })
 }
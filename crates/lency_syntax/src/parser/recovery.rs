@@ -0,0 +1,178 @@
+//! Error Recovery
+//!
+//! 语法错误恢复：出错的声明/语句跳到同步点后继续解析，
+//! 使一次解析能报告文件中的所有语法错误。
+//!
+//! - 语句：跳到 `;`（一并跳过）、`}` 或下一条语句的关键字，
+//!   出错的语句替换为 `ExprKind::Error` 占位表达式语句
+//! - 声明：跳过一个完整的 `{ ... }` 或停在下一个顶层关键字之前，出错的声明被丢弃
+//!
+//! 同步解析器至少消耗一个 token，并在 `}`/文件结束处失败，
+//! 因此不会吞掉外层块的结束符，也不会让 `repeated()` 原地循环。
+
+use crate::ast::*;
+use crate::lexer::Token;
+use chumsky::prelude::*;
+
+use super::ParserError;
+
+/// 语句级同步点（不会被跳过）
+const STMT_SYNC: [Token; 10] = [
+    Token::Semicolon,
+    Token::LBrace,
+    Token::RBrace,
+    Token::Var,
+    Token::Return,
+    Token::If,
+    Token::While,
+    Token::For,
+    Token::Break,
+    Token::Continue,
+];
+
+/// 声明级同步点：顶层关键字（不会被跳过）
+const DECL_SYNC: [Token; 9] = [
+    Token::LBrace,
+    Token::RBrace,
+    Token::Struct,
+    Token::Impl,
+    Token::Trait,
+    Token::Enum,
+    Token::Import,
+    Token::Extern,
+    Token::Pub,
+];
+
+/// 跳过一段括号平衡的 `{ ... }`
+fn skipped_block() -> impl Parser<Token, (), Error = ParserError> + Clone {
+    recursive(|block| {
+        just(Token::LBrace)
+            .ignore_then(
+                block
+                    .or(none_of([Token::LBrace, Token::RBrace]).ignored())
+                    .repeated(),
+            )
+            .then_ignore(just(Token::RBrace))
+            .ignored()
+    })
+}
+
+/// 语句出错后的同步解析器，产生 `ExprKind::Error` 占位语句
+pub fn stmt_recovery() -> impl Parser<Token, Stmt, Error = ParserError> + Clone {
+    // 至少跳过一个 token（即使它是语句关键字），但不越过外层块的 `}`
+    let first = skipped_block().or(none_of([Token::RBrace]).ignored());
+    let rest = skipped_block().or(none_of(STMT_SYNC).ignored()).repeated();
+
+    first
+        .then(rest)
+        .then_ignore(just(Token::Semicolon).or_not())
+        .map_with_span(|_, span| Stmt::Expression(error_expr(span)))
+}
+
+/// 表达式出错后的同步解析器：跳到语句同步点，产生 `ExprKind::Error`
+///
+/// 可以不消耗任何 token（如 `var x = ;`），只能用在已有进展的位置。
+pub fn expr_recovery() -> impl Parser<Token, Expr, Error = ParserError> + Clone {
+    none_of(STMT_SYNC)
+        .repeated()
+        .map_with_span(|_, span| error_expr(span))
+}
+
+/// 声明出错后的同步解析器；出错的声明被丢弃
+pub fn decl_recovery() -> impl Parser<Token, Option<Decl>, Error = ParserError> + Clone {
+    // 未闭合的 `{` 之后无法再找到可靠的同步点，跳到文件末尾
+    let body = skipped_block().or(just(Token::LBrace).then(any().repeated()).ignored());
+
+    let skipped = just(Token::RBrace).ignored().or(body.clone()).or(any()
+        .ignored()
+        .then(none_of(DECL_SYNC).repeated())
+        .then(body.or_not())
+        .ignored());
+    skipped.to(None)
+}
+
+/// 占位表达式：类型检查时视为 `Type::Error`
+pub fn error_expr(span: Span) -> Expr {
+    Expr {
+        kind: ExprKind::Error,
+        span,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::*;
+    use crate::parser::parse_recovery;
+
+    /// 恢复后的声明与语法错误
+    fn recover(source: &str) -> (Vec<Decl>, Vec<crate::parser::ParserError>) {
+        let (program, errors) = parse_recovery(source);
+        (program.map(|p| p.decls).unwrap_or_default(), errors)
+    }
+
+    fn function_body<'a>(decls: &'a [Decl], name: &str) -> &'a [Stmt] {
+        decls
+            .iter()
+            .find_map(|decl| match decl {
+                Decl::Function { name: n, body, .. } if n == name => Some(body.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_reports_every_statement_error() {
+        let source = "int main() {\n    var a = 1 +* 2\n    print(a)\n    return ) 0\n    var b = 2\n    return b\n}\n";
+        let (decls, errors) = recover(source);
+        assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+
+        let body = function_body(&decls, "main");
+        assert!(body.iter().any(|s| matches!(
+            s,
+            Stmt::Expression(Expr {
+                kind: ExprKind::Error,
+                ..
+            })
+        )));
+        // 出错之后的语句照常解析
+        assert!(body
+            .iter()
+            .any(|s| matches!(s, Stmt::VarDecl { name, .. } if name == "b")));
+    }
+
+    #[test]
+    fn test_skips_broken_declarations() {
+        let source =
+            "int broken(int a,, int b) { return a }\nstruct { int x }\nint ok() { return 1 }\n";
+        let (decls, errors) = recover(source);
+        assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+
+        assert_eq!(decls.len(), 1);
+        assert!(matches!(&decls[0], Decl::Function { name, .. } if name == "ok"));
+    }
+
+    #[test]
+    fn test_broken_initializer_keeps_variable() {
+        let source = "int main() {\n    var x = ;\n    var y = (1 + )\n    return x\n}\n";
+        let (decls, errors) = recover(source);
+        assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+
+        let body = function_body(&decls, "main");
+        let values: Vec<&ExprKind> = body
+            .iter()
+            .filter_map(|s| match s {
+                Stmt::VarDecl { value, .. } => Some(&value.kind),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(values, vec![&ExprKind::Error, &ExprKind::Error]);
+    }
+
+    #[test]
+    fn test_valid_program_has_no_errors() {
+        let source = "struct P { int x }\nint main() {\n    var p = P { x: 1 }\n    if p.x > 0 { print(p.x) }\n    return 0\n}\n";
+        let (program, errors) = parse_recovery(source);
+        assert!(errors.is_empty(), "errors: {:?}", errors);
+        assert_eq!(program.map(|p| p.decls.len()), Some(2));
+    }
+}
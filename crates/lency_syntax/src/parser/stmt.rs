@@ -1,9 +1,11 @@
 //! Statement Parser
 //!
-//! 语句解析：变量声明、赋值、return、if、while、block等
+//! 语句解析：变量声明（含解构）、赋值、return、if / if let、while / while let、block等
 
 use super::expr::expr_parser;
 use super::helpers::{ident_parser, type_parser};
+use super::pattern::pattern_parser;
+use super::recovery::{expr_recovery, stmt_recovery};
 use crate::ast::*;
 use crate::lexer::Token;
 use chumsky::prelude::*;
@@ -15,11 +17,14 @@ pub fn stmt_parser() -> impl Parser<Token, Stmt, Error = ParserError> + Clone {
     let expr = expr_parser();
     let type_p = type_parser();
     let ident = ident_parser();
+    let pattern = pattern_parser();
 
     recursive(|stmt| {
         // Block 逻辑 (返回 Vec<Stmt>)
+        // 出错的语句跳到同步点后继续，块内的其余语句照常解析
         let raw_block = stmt
             .clone()
+            .recover_with(skip_parser(stmt_recovery()))
             .repeated()
             .delimited_by(just(Token::LBrace), just(Token::RBrace));
 
@@ -28,7 +33,8 @@ pub fn stmt_parser() -> impl Parser<Token, Stmt, Error = ParserError> + Clone {
             .ignore_then(ident.clone())
             .then(just(Token::Colon).ignore_then(type_p.clone()).or_not())
             .then_ignore(just(Token::Eq))
-            .then(expr.clone())
+            // 初始化表达式出错时仍保留变量声明（类型为 Type::Error）
+            .then(expr.clone().recover_with(skip_parser(expr_recovery())))
             .then_ignore(just(Token::Semicolon).or_not())
             .map_with_span(|((name, ty), value), span| Stmt::VarDecl {
                 span,
@@ -37,6 +43,19 @@ pub fn stmt_parser() -> impl Parser<Token, Stmt, Error = ParserError> + Clone {
                 value,
             });
 
+        // 解构声明: var Pair { key, value } = p
+        // 普通的 `var x = ...` 已由 var_decl 处理
+        let var_pattern = just(Token::Var)
+            .ignore_then(pattern.clone())
+            .then_ignore(just(Token::Eq))
+            .then(expr.clone())
+            .then_ignore(just(Token::Semicolon).or_not())
+            .map_with_span(|(pattern, value), span| Stmt::VarPattern {
+                span,
+                pattern,
+                value,
+            });
+
         // 赋值语句: x = 10;
         // 赋值语句 & 表达式语句
         // 合并处理以避免前缀冲突，并支持复杂的左值赋值 (e.g. this.count = 1)
@@ -79,6 +98,38 @@ pub fn stmt_parser() -> impl Parser<Token, Stmt, Error = ParserError> + Clone {
                 else_block,
             });
 
+        // If let: if let Some(x) = opt { ... } else { ... }
+        let if_let = just(Token::If)
+            .ignore_then(just(Token::Let))
+            .ignore_then(pattern.clone())
+            .then_ignore(just(Token::Eq))
+            .then(expr.clone())
+            .then(raw_block.clone())
+            .then(just(Token::Else).ignore_then(raw_block.clone()).or_not())
+            .map_with_span(
+                |(((pattern, value), then_block), else_block), span| Stmt::IfLet {
+                    span,
+                    pattern,
+                    value,
+                    then_block,
+                    else_block,
+                },
+            );
+
+        // While let: while let Some(tok) = it.next() { ... }
+        let while_let = just(Token::While)
+            .ignore_then(just(Token::Let))
+            .ignore_then(pattern.clone())
+            .then_ignore(just(Token::Eq))
+            .then(expr.clone())
+            .then(raw_block.clone())
+            .map_with_span(|((pattern, value), body), span| Stmt::WhileLet {
+                span,
+                pattern,
+                value,
+                body,
+            });
+
         // While
         let while_stmt = just(Token::While)
             .ignore_then(expr.clone())
@@ -167,9 +218,12 @@ pub fn stmt_parser() -> impl Parser<Token, Stmt, Error = ParserError> + Clone {
         // 表达式语句
 
         var_decl
+            .or(var_pattern)
             .or(block_stmt)
             .or(ret)
+            .or(if_let)
             .or(if_stmt)
+            .or(while_let)
             .or(while_stmt)
             .or(for_stmt)
             .or(break_stmt)
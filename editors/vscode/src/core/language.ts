@@ -3,7 +3,9 @@ import { BuiltinSpec } from './types';
 export const KEYWORDS = new Set([
     'var', 'const', 'struct', 'impl', 'trait', 'enum', 'if', 'else', 'while', 'for', 'in',
     'break', 'continue', 'return', 'import', 'extern', 'match', 'case', 'as', 'null',
-    'true', 'false', 'void', 'int', 'float', 'bool', 'string',
+    'true', 'false', 'void', 'int', 'float', 'bool', 'string', 'char',
+    // 定宽整数类型
+    'i8', 'i16', 'i32', 'i64', 'u8', 'u16', 'u32', 'u64', 'byte',
     // vec / Result 类型关键字（lexer.rs 中的独立 token）
     'vec', 'Ok', 'Err'
 ]);